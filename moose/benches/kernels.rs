@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use moose::fixedpoint::FixedTensor;
+use moose::prelude::*;
+use ndarray::{Array1, Array2};
+
+const SIZES: [usize; 3] = [100, 10_000, 1_000_000];
+
+const DIMS: [usize; 3] = [16, 64, 256];
+
+fn ring_values(size: usize) -> Array1<u64> {
+    Array1::from_shape_fn(size, |i| (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+fn float_matrix(dim: usize) -> Array2<f64> {
+    Array2::from_shape_fn((dim, dim), |(i, j)| ((i * dim + j) % 13) as f64 / 13.0)
+}
+
+/// Benchmark host ring kernels on vectors of various sizes.
+fn ring_host(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ring_host");
+    let sess = SyncSession::default();
+    let alice = HostPlacement::from("alice");
+    for size in SIZES.iter() {
+        let x: HostRing64Tensor = alice.from_raw(ring_values(*size));
+        let y: HostRing64Tensor = alice.from_raw(ring_values(*size).mapv(|v| v ^ 1));
+
+        group.bench_function(BenchmarkId::new("add", size), |b| {
+            b.iter(|| -> HostRing64Tensor { black_box(alice.add(&sess, &x, &y)) })
+        });
+        group.bench_function(BenchmarkId::new("mul", size), |b| {
+            b.iter(|| -> HostRing64Tensor { black_box(alice.mul(&sess, &x, &y)) })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark replicated ring protocols on vectors of various sizes.
+fn ring_replicated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ring_replicated");
+    let sess = SyncSession::default();
+    let alice = HostPlacement::from("alice");
+    let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+    for size in SIZES.iter() {
+        let x: HostRing64Tensor = alice.from_raw(ring_values(*size));
+        let x: ReplicatedRing64Tensor = rep.share(&sess, &x);
+        let y = x.clone();
+
+        group.bench_function(BenchmarkId::new("share", size), |b| {
+            let x: HostRing64Tensor = alice.from_raw(ring_values(*size));
+            b.iter(|| -> ReplicatedRing64Tensor { black_box(rep.share(&sess, &x)) })
+        });
+        group.bench_function(BenchmarkId::new("mul", size), |b| {
+            b.iter(|| -> ReplicatedRing64Tensor { black_box(rep.mul(&sess, &x, &y)) })
+        });
+        group.bench_function(BenchmarkId::new("trunc_pr", size), |b| {
+            b.iter(|| -> ReplicatedRing64Tensor { black_box(rep.trunc_pr(&sess, 20, &x)) })
+        });
+    }
+    group.finish();
+}
+
+/// Benchmark replicated fixed-point protocols on matrices of various dimensions.
+fn fixed_replicated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fixed_replicated");
+    let sess = SyncSession::default();
+    let alice = HostPlacement::from("alice");
+    let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+    for dim in DIMS.iter() {
+        let x: HostFloat64Tensor = alice.from_raw(float_matrix(*dim));
+        let x: HostFixed128Tensor = alice.fixedpoint_encode(&sess, 40, 24, &x);
+        let x: ReplicatedFixed128Tensor = rep.share(&sess, &x);
+        let x_fixed: Fixed128Tensor = FixedTensor::Replicated(x.clone());
+
+        group.bench_function(BenchmarkId::new("mul", dim), |b| {
+            b.iter(|| -> Fixed128Tensor { black_box(rep.mul(&sess, &x_fixed, &x_fixed)) })
+        });
+        group.bench_function(BenchmarkId::new("dot", dim), |b| {
+            b.iter(|| -> ReplicatedFixed128Tensor { black_box(rep.dot(&sess, &x, &x)) })
+        });
+        group.bench_function(BenchmarkId::new("sigmoid", dim), |b| {
+            b.iter(|| -> ReplicatedFixed128Tensor { black_box(rep.sigmoid(&sess, &x)) })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(kernels, ring_host, ring_replicated, fixed_replicated);
+criterion_main!(kernels);
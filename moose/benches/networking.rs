@@ -1,4 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use moose::networking::inprocess::InProcessNetwork;
+use moose::prelude::*;
+use ndarray::ArrayD;
+use std::convert::TryFrom;
 use std::{collections::HashMap, sync::Arc};
 
 fn networking_tall(c: &mut Criterion) {
@@ -346,6 +350,52 @@ fn networking_wide(c: &mut Criterion) {
     }
 }
 
-criterion_group!(networking, networking_tall, networking_wide);
+/// Benchmark sending host tensors of various sizes between in-process parties,
+/// including serialization and deserialization.
+fn networking_inprocess(c: &mut Criterion) {
+    let mut group = c.benchmark_group("networking_inprocess");
+    for size in [10, 1_000, 100_000, 1_000_000].iter() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let plc = HostPlacement::from("alice");
+        let x: HostFloat64Tensor = plc.from_raw(ArrayD::from_elem(vec![*size], 1.0));
+        let x = Value::from(x);
+
+        let network = InProcessNetwork::default();
+        let alice = network.endpoint(Identity::from("alice"));
+        let bob = network.endpoint(Identity::from("bob"));
+
+        group.bench_function(BenchmarkId::new("host_float64_tensor", size), |b| {
+            b.to_async(&runtime).iter(|| async {
+                let session_id = SessionId::random();
+                let rendezvous_key = RendezvousKey::try_from("x").unwrap();
+
+                let receiving = {
+                    let bob = Arc::clone(&bob);
+                    let session_id = session_id.clone();
+                    let rendezvous_key = rendezvous_key.clone();
+                    tokio::spawn(async move {
+                        bob.receive(&Identity::from("alice"), &rendezvous_key, &session_id)
+                            .await
+                            .unwrap()
+                    })
+                };
+
+                alice
+                    .send(&x, &Identity::from("bob"), &rendezvous_key, &session_id)
+                    .await
+                    .unwrap();
+                black_box(receiving.await.unwrap());
+            });
+        });
+    }
+}
+
+criterion_group!(
+    networking,
+    networking_tall,
+    networking_wide,
+    networking_inprocess
+);
 
 criterion_main!(networking);
@@ -0,0 +1,65 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use moose::bench::{ProtocolBenchmark, Workload};
+use std::time::Duration;
+
+/// One-way latencies of the simulated links, from in-datacenter to cross-region.
+const LATENCIES_MS: [u64; 3] = [0, 1, 20];
+
+fn run_workloads(c: &mut Criterion, group_name: &str, workloads: &[Workload]) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+    for workload in workloads {
+        let arguments = workload.arguments();
+        for latency in LATENCIES_MS.iter() {
+            let benchmark = ProtocolBenchmark::for_workload(workload)
+                .unwrap()
+                .with_latency(Duration::from_millis(*latency));
+            let id = BenchmarkId::new(workload.name(), format!("{}ms", latency));
+            group.bench_function(id, |b| {
+                b.to_async(&runtime)
+                    .iter(|| async { black_box(benchmark.run(arguments.clone()).await.unwrap()) })
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Benchmark logistic regression inference over replicated secret sharing.
+fn logistic_inference(c: &mut Criterion) {
+    run_workloads(
+        c,
+        "logistic_inference",
+        &[
+            Workload::LogisticInference {
+                batch_size: 1,
+                features: 10,
+            },
+            Workload::LogisticInference {
+                batch_size: 100,
+                features: 100,
+            },
+        ],
+    );
+}
+
+/// Benchmark secure aggregation of vectors input by several parties.
+fn aggregation(c: &mut Criterion) {
+    run_workloads(
+        c,
+        "aggregation",
+        &[
+            Workload::Aggregation {
+                parties: 3,
+                size: 1_000,
+            },
+            Workload::Aggregation {
+                parties: 10,
+                size: 100_000,
+            },
+        ],
+    );
+}
+
+criterion_group!(protocols, logistic_inference, aggregation);
+criterion_main!(protocols);
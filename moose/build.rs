@@ -1,7 +1,24 @@
+use std::path::PathBuf;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(target_os = "macos")]
     println!("cargo:rustc-link-search=native=/opt/homebrew/opt/openblas/lib");
-    tonic_build::compile_protos("protos/choreography.proto")?;
-    tonic_build::compile_protos("protos/networking.proto")?;
+    // descriptors of the services of workers are kept for gRPC reflection
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_build::compile_protos("protos/computation.proto")?;
+    tonic_build::configure()
+        .extern_path(".moose_computation", "crate::choreography::proto::gen")
+        .file_descriptor_set_path(out_dir.join("choreography_descriptor.bin"))
+        .compile(&["protos/choreography.proto"], &["protos"])?;
+    // values are kept as `Bytes` so that retrying a send does not copy them
+    tonic_build::configure()
+        .bytes(&[".moose_networking.SendValueRequest.tagged_value"])
+        .file_descriptor_set_path(out_dir.join("networking_descriptor.bin"))
+        .compile(&["protos/networking.proto"], &["protos"])?;
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("relay_descriptor.bin"))
+        .compile(&["protos/relay.proto"], &["protos"])?;
+    tonic_build::compile_protos("protos/onnx.proto")?;
+    tonic_build::compile_protos("protos/tensorflow.proto")?;
     Ok(())
 }
@@ -0,0 +1,72 @@
+/*
+ * C API for embedding local execution of Moose computations.
+ *
+ * Requires building the moose crate with the `capi` feature, for instance using
+ * `cargo rustc --release --features capi --crate-type cdylib`.
+ *
+ * Functions returning int return MOOSE_OK on success and MOOSE_ERROR otherwise,
+ * in which case moose_last_error() describes the error.
+ */
+
+#ifndef MOOSE_H
+#define MOOSE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MOOSE_OK 0
+#define MOOSE_ERROR 1
+
+#define MOOSE_FLOAT32 0
+#define MOOSE_FLOAT64 1
+#define MOOSE_INT32 2
+#define MOOSE_INT64 3
+#define MOOSE_UINT32 4
+#define MOOSE_UINT64 5
+
+typedef struct MooseExecutor MooseExecutor;
+
+/* Create a new executor, to be freed using moose_executor_free. */
+MooseExecutor *moose_executor_new(void);
+
+/* Free an executor created using moose_executor_new. */
+void moose_executor_free(MooseExecutor *executor);
+
+/* Load a computation serialized using msgpack, compiling it using the default
+ * passes if `compile` is set. */
+int moose_executor_load_computation(MooseExecutor *executor, const uint8_t *bytes, size_t len,
+                                    bool compile);
+
+/* Bind the argument `name` to a tensor copied from a row-major buffer holding
+ * entries of type `dtype`. */
+int moose_executor_bind_input(MooseExecutor *executor, const char *name, int dtype,
+                              const void *data, const size_t *shape, size_t ndim);
+
+/* Run the loaded computation on the bound arguments. */
+int moose_executor_run(MooseExecutor *executor);
+
+/* Write the dtype and number of dimensions of the output `name`. */
+int moose_executor_output_info(MooseExecutor *executor, const char *name, int *dtype,
+                               size_t *ndim);
+
+/* Write the `ndim` dimensions of the output `name`. */
+int moose_executor_output_shape(MooseExecutor *executor, const char *name, size_t *shape);
+
+/* Copy the `len` entries of the output `name` in row-major order into `data`. */
+int moose_executor_output_data(MooseExecutor *executor, const char *name, int dtype, void *data,
+                               size_t len);
+
+/* Message describing the last error on the calling thread, or NULL if there was
+ * none. Valid until the next call to this API on the same thread. */
+const char *moose_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MOOSE_H */
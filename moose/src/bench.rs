@@ -0,0 +1,351 @@
+//! Harness for benchmarking protocols end-to-end.
+//!
+//! Computations are compiled and run by in-process parties connected through
+//! `InProcessNetwork`, such that values are serialized and deserialized like
+//! they would be between workers, with every link delayed according to a
+//! `LinkProfile` to simulate a wide-area network. Canonical workloads are
+//! provided by `Workload` for the Criterion benchmarks in `benches/protocols.rs`,
+//! but any computation may be run with `ProtocolBenchmark`.
+//!
+//! Micro-benchmarks of individual ring and fixed-point kernels can be found in
+//! `benches/kernels.rs`. Both are run with `make bench`.
+
+use crate::compilation::{compile, Pass};
+use crate::computation::{Computation, Identity, Placement, Role, SessionId, Value};
+use crate::error::{Error, Result};
+use crate::execution::{
+    AsyncExecutor, AsyncNetworkingImpl, AsyncSession, AsyncStorageImpl, AsyncValue,
+};
+use crate::host::{FromRaw, HostPlacement};
+use crate::networking::accounting::NetworkStats;
+use crate::networking::inprocess::{InProcessNetwork, InProcessNetworking};
+use crate::networking::simulated::{LinkProfile, NetworkProfile, SimulatedNetworking};
+use crate::storage::local::LocalAsyncStorage;
+use crate::types::HostFloat64Tensor;
+use futures::future::try_join_all;
+use ndarray::{Array1, Array2};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Fixed-point tensor type used by the canonical workloads.
+const FIXED: &str = "Tensor<Fixed128(24, 40)>";
+
+const REPLICATED: &str = "@Replicated(alice, bob, carole)";
+
+const LOGISTIC_INFERENCE: &str = r#"
+x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+w = Input{arg_name = "w"}: () -> Tensor<Float64> () @Host(bob)
+x_fixed = Cast: (Tensor<Float64>) -> Tensor<Fixed128(24, 40)> (x) @Host(alice)
+w_fixed = Cast: (Tensor<Float64>) -> Tensor<Fixed128(24, 40)> (w) @Host(bob)
+logits = Dot: (Tensor<Fixed128(24, 40)>, Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (x_fixed, w_fixed) @Replicated(alice, bob, carole)
+probs = Sigmoid: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (logits) @Replicated(alice, bob, carole)
+probs_host = Cast: (Tensor<Fixed128(24, 40)>) -> Tensor<Float64> (probs) @Host(alice)
+output = Output{tag = "probs"}: (Tensor<Float64>) -> Tensor<Float64> (probs_host) @Host(alice)
+"#;
+
+/// Canonical computations used to track the performance of protocols.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// Logistic regression inference on a batch of `batch_size` rows with
+    /// `features` features held by alice, using weights held by bob.
+    LogisticInference { batch_size: usize, features: usize },
+    /// Sum of vectors of length `size` input by each of `parties` parties.
+    Aggregation { parties: usize, size: usize },
+}
+
+impl Workload {
+    /// Short name identifying the workload in benchmark reports.
+    pub fn name(&self) -> String {
+        match self {
+            Workload::LogisticInference {
+                batch_size,
+                features,
+            } => format!("logistic_inference/{}x{}", batch_size, features),
+            Workload::Aggregation { parties, size } => {
+                format!("aggregation/{}x{}", parties, size)
+            }
+        }
+    }
+
+    /// Logical computation of the workload, before compilation.
+    pub fn computation(&self) -> Result<Computation> {
+        let source = match self {
+            Workload::LogisticInference { .. } => LOGISTIC_INFERENCE.to_string(),
+            Workload::Aggregation { parties, .. } => {
+                if *parties == 0 {
+                    return Err(Error::InvalidArgument(
+                        "aggregation needs at least one party".to_string(),
+                    ));
+                }
+                let mut source = String::new();
+                for i in 0..*parties {
+                    let _ = writeln!(
+                        source,
+                        "x{i} = Input{{arg_name = \"x{i}\"}}: () -> Tensor<Float64> () @Host({owner})
+                        x{i}_fixed = Cast: (Tensor<Float64>) -> {fixed} (x{i}) @Host({owner})",
+                        i = i,
+                        owner = party(i),
+                        fixed = FIXED,
+                    );
+                }
+                let mut total = "x0_fixed".to_string();
+                for i in 1..*parties {
+                    let _ = writeln!(
+                        source,
+                        "sum{i} = Add: ({fixed}, {fixed}) -> {fixed} ({total}, x{i}_fixed) {rep}",
+                        i = i,
+                        total = total,
+                        fixed = FIXED,
+                        rep = REPLICATED,
+                    );
+                    total = format!("sum{}", i);
+                }
+                let _ = writeln!(
+                    source,
+                    "total = Cast: ({fixed}) -> Tensor<Float64> ({total}) @Host(alice)
+                    output = Output{{tag = \"total\"}}: (Tensor<Float64>) -> Tensor<Float64> (total) @Host(alice)",
+                    total = total,
+                    fixed = FIXED,
+                );
+                source
+            }
+        };
+        Computation::from_textual(&source)
+    }
+
+    /// Arguments of the workload, filled with deterministic values.
+    pub fn arguments(&self) -> HashMap<String, Value> {
+        let mut arguments = HashMap::new();
+        match self {
+            Workload::LogisticInference {
+                batch_size,
+                features,
+            } => {
+                let alice = HostPlacement::from("alice");
+                let bob = HostPlacement::from("bob");
+                let x: HostFloat64Tensor = alice
+                    .from_raw(Array2::from_shape_fn((*batch_size, *features), |(i, j)| {
+                        ((i + j) % 7) as f64 / 7.0
+                    }));
+                let w: HostFloat64Tensor = bob
+                    .from_raw(Array2::from_shape_fn((*features, 1), |(i, _)| {
+                        (i % 5) as f64 / 10.0 - 0.2
+                    }));
+                arguments.insert("x".to_string(), Value::from(x));
+                arguments.insert("w".to_string(), Value::from(w));
+            }
+            Workload::Aggregation { parties, size } => {
+                for i in 0..*parties {
+                    let plc = HostPlacement::from(party(i).as_str());
+                    let x: HostFloat64Tensor =
+                        plc.from_raw(Array1::from_shape_fn(*size, |j| ((i + j) % 11) as f64));
+                    arguments.insert(format!("x{}", i), Value::from(x));
+                }
+            }
+        }
+        arguments
+    }
+}
+
+/// Name of the `i`-th party, starting with those of the replicated placement.
+fn party(i: usize) -> String {
+    match i {
+        0 => "alice".to_string(),
+        1 => "bob".to_string(),
+        2 => "carole".to_string(),
+        _ => format!("party{}", i),
+    }
+}
+
+/// Outcome of running a computation with `ProtocolBenchmark`.
+#[derive(Debug)]
+pub struct ProtocolRun {
+    pub outputs: HashMap<String, Value>,
+    /// Wall-clock time from launching the sessions until all of them finished.
+    pub elapsed: Duration,
+    /// Traffic of each party.
+    pub network_stats: HashMap<Identity, NetworkStats>,
+}
+
+impl ProtocolRun {
+    /// Total number of bytes sent by all parties.
+    pub fn bytes_sent(&self) -> u64 {
+        self.network_stats
+            .values()
+            .map(|stats| stats.bytes_sent())
+            .sum()
+    }
+}
+
+/// Runs a computation end-to-end with one in-process party per role.
+pub struct ProtocolBenchmark {
+    computation: Computation,
+    roles: Vec<Role>,
+    profile: Arc<NetworkProfile>,
+}
+
+impl ProtocolBenchmark {
+    /// Benchmark of `computation`, compiled using the default passes.
+    pub fn new(computation: Computation) -> anyhow::Result<Self> {
+        let computation = compile::<Pass>(computation, None)?;
+        let roles: BTreeSet<Role> = computation
+            .operations
+            .iter()
+            .flat_map(|op| match &op.placement {
+                Placement::Host(plc) => vec![plc.owner.clone()],
+                Placement::Replicated(plc) => plc.owners.to_vec(),
+                Placement::Mirrored3(plc) => plc.owners.to_vec(),
+                Placement::Additive(plc) => plc.owners.to_vec(),
+            })
+            .collect();
+        Ok(ProtocolBenchmark {
+            computation,
+            roles: roles.into_iter().collect(),
+            profile: Arc::new(NetworkProfile::default()),
+        })
+    }
+
+    /// Benchmark of a canonical workload.
+    pub fn for_workload(workload: &Workload) -> anyhow::Result<Self> {
+        Self::new(workload.computation()?)
+    }
+
+    /// Delay every link between parties according to `link`.
+    pub fn with_link(self, link: LinkProfile) -> Self {
+        ProtocolBenchmark {
+            profile: Arc::new(NetworkProfile::new(link)),
+            ..self
+        }
+    }
+
+    /// Add a one-way `latency` to every message.
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.with_link(LinkProfile {
+            latency,
+            ..Default::default()
+        })
+    }
+
+    /// Run the computation once in a fresh session.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub async fn run(&self, arguments: HashMap<String, Value>) -> anyhow::Result<ProtocolRun> {
+        let network = InProcessNetwork::default();
+        let role_assignments: HashMap<Role, Identity> = self
+            .roles
+            .iter()
+            .map(|role| (role.clone(), Identity::from(&role.0)))
+            .collect();
+        let session_id = SessionId::random();
+        let start = Instant::now();
+
+        // all parties must be registered before any of them starts sending
+        let endpoints: Vec<(Identity, Arc<InProcessNetworking>)> = role_assignments
+            .values()
+            .map(|identity| (identity.clone(), network.endpoint(identity.clone())))
+            .collect();
+        let mut handles = Vec::new();
+        let mut output_futures: HashMap<String, AsyncValue> = HashMap::new();
+        for (identity, endpoint) in endpoints.iter() {
+            let networking: AsyncNetworkingImpl = Arc::new(SimulatedNetworking::new(
+                identity.clone(),
+                Arc::clone(endpoint) as AsyncNetworkingImpl,
+                Arc::clone(&self.profile),
+            ));
+            let storage: AsyncStorageImpl = Arc::new(LocalAsyncStorage::default());
+            let session = AsyncSession::new(
+                session_id.clone(),
+                arguments.clone(),
+                role_assignments.clone(),
+                networking,
+                storage,
+            );
+            let outputs = AsyncExecutor::default().run_computation(
+                &self.computation,
+                &role_assignments,
+                identity,
+                &session,
+            )?;
+            output_futures.extend(outputs);
+            handles.push(session.into_handle()?);
+        }
+
+        try_join_all(handles.into_iter().map(|h| h.join_on_first_error())).await?;
+        let elapsed = start.elapsed();
+
+        let mut outputs = HashMap::with_capacity(output_futures.len());
+        for (tag, output) in output_futures {
+            let value = output
+                .await
+                .map_err(|_| Error::Cancelled(format!("output '{}' was not computed", tag)))?;
+            outputs.insert(tag, value);
+        }
+        let network_stats = endpoints
+            .into_iter()
+            .map(|(identity, endpoint)| {
+                let stats = endpoint.session_stats(&session_id);
+                (identity, stats)
+            })
+            .collect();
+
+        Ok(ProtocolRun {
+            outputs,
+            elapsed,
+            network_stats,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_aggregation() -> anyhow::Result<()> {
+        let workload = Workload::Aggregation {
+            parties: 4,
+            size: 3,
+        };
+        let run = ProtocolBenchmark::for_workload(&workload)?
+            .with_latency(Duration::from_millis(1))
+            .run(workload.arguments())
+            .await?;
+
+        let expected: Vec<f64> = (0..3)
+            .map(|j| (0..4).map(|i| ((i + j) % 11) as f64).sum())
+            .collect();
+        match &run.outputs["total"] {
+            Value::HostFloat64Tensor(total) => {
+                let total: Vec<f64> = total.0.iter().copied().collect();
+                for (actual, expected) in total.iter().zip(expected) {
+                    assert!((actual - expected).abs() < 1e-6);
+                }
+            }
+            other => panic!("expected a float tensor, got {:?}", other),
+        }
+        assert!(run.bytes_sent() > 0);
+        assert!(run.elapsed >= Duration::from_millis(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_logistic_inference() -> anyhow::Result<()> {
+        let workload = Workload::LogisticInference {
+            batch_size: 2,
+            features: 3,
+        };
+        let run = ProtocolBenchmark::for_workload(&workload)?
+            .run(workload.arguments())
+            .await?;
+        match &run.outputs["probs"] {
+            Value::HostFloat64Tensor(probs) => {
+                assert_eq!(probs.0.shape(), [2, 1]);
+                assert!(probs.0.iter().all(|p| (0.0..=1.0).contains(p)));
+            }
+            other => panic!("expected a float tensor, got {:?}", other),
+        }
+        Ok(())
+    }
+}
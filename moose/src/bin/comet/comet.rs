@@ -1,16 +1,61 @@
 //! Reindeer using gRPC choreography and gRPC networking.
 
-use clap::Parser;
+mod config;
+
+use clap::{CommandFactory, Parser};
+use moose::choreography::admission::AdmissionQueue;
+use moose::choreography::bindings::RoleBindings;
 use moose::choreography::grpc::GrpcChoreography;
-use moose::networking::grpc::GrpcNetworkingManager;
+use moose::choreography::StorageStrategy;
+#[cfg(feature = "compile")]
+use moose::compilation::policy::RevealPolicy;
+use moose::execution::audit::{AuditKey, AuditLog};
+use moose::execution::cache::KernelCache;
+use moose::execution::checkpoint::CheckpointConfig;
+use moose::execution::compute::ComputePool;
+use moose::execution::memory::MemoryBudgetConfig;
+use moose::execution::scheduler::Scheduler;
+use moose::execution::{AsyncNetworkingImpl, OperationTimeouts};
+use moose::grpc::{TokenAuth, TokenAuthConfig};
+use moose::kms::{key_provider_from_uri, KeyProviderImpl};
+use moose::networking::grpc::{ChannelConfig, GrpcNetworkingManager, HeartbeatConfig};
+use moose::networking::prioritized::PrioritizedNetworking;
+use moose::networking::relay::{RelayNetworking, RelayService};
+use moose::networking::throttled::{ThrottledNetworking, TokenBucket};
+use moose::networking::transcript::TranscriptSigner;
 use moose::prelude::*;
+use moose::storage::acl::StorageAcl;
+#[cfg(feature = "encrypted-storage")]
+use moose::storage::encrypted::AsyncEncryptedStorage;
 use moose::storage::filesystem::AsyncFilesystemStorage;
+#[cfg(feature = "flight")]
+use moose::storage::flight::AsyncFlightStorage;
+#[cfg(feature = "object-store")]
+use moose::storage::object_store::{AsyncObjectStorage, ObjectStoreConfig};
+#[cfg(feature = "redis")]
+use moose::storage::redis::{AsyncRedisStorage, RedisConfig};
 use moose::tokio;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tonic::transport::Server;
+use std::time::Duration;
+#[cfg(unix)]
+use tokio_stream::wrappers::UnixListenerStream;
+use tonic::transport::{ClientTlsConfig, Server, ServerTlsConfig};
 
-#[derive(Debug, Parser, Clone)]
+#[derive(Debug, Parser, Clone, Serialize)]
 pub struct Opt {
+    #[structopt(env = "COMET_CONFIG", long)]
+    #[serde(skip)]
+    /// TOML or YAML file with options not given as flags or environment variables
+    config: Option<String>,
+
+    #[structopt(long)]
+    #[serde(skip)]
+    /// Print the effective configuration, in the format of `config`, and exit
+    print_config: bool,
+
     #[structopt(env, long)]
     /// Own identity in sessions
     identity: String,
@@ -19,24 +64,395 @@ pub struct Opt {
     /// Port to use for gRPC server
     port: u16,
 
+    #[structopt(env, long)]
+    /// Unix domain socket to use for gRPC server instead of `port`
+    socket: Option<String>,
+
     #[structopt(env, long)]
     /// Directory to read certificates from
     certs: Option<String>,
 
     #[structopt(env, long)]
-    /// Expected identity of choreographer; `certs` must be specified
+    /// Provider of the TLS private key and of AES keys input by sessions, such as `vault+https://vault:8200/secret`
+    key_provider: Option<String>,
+
+    #[cfg(feature = "compression")]
+    #[structopt(env, long)]
+    /// Level at which to compress saved files with zstd, typically from 1 to 22; compressed files are always detected on load
+    storage_compression: Option<i32>,
+
+    #[cfg(feature = "encrypted-storage")]
+    #[structopt(env, long)]
+    /// Name of the 32-byte key from `key_provider` with which to encrypt values saved under keys ending in `.enc`
+    storage_key: Option<String>,
+
+    #[structopt(env, long)]
+    /// Expected identity of choreographer; `certs` or `auth_tokens` must be specified
     choreographer: Option<String>,
 
+    #[structopt(env, long)]
+    /// TOML file listing the bearer tokens accepted from each identity
+    auth_tokens: Option<String>,
+
+    #[structopt(env, long, hide_env_values = true)]
+    /// Bearer token to present when sending values to other workers
+    auth_token: Option<String>,
+
+    #[structopt(long)]
+    /// Reload certificates when files in `certs` change, in addition to on SIGHUP
+    watch_certs: bool,
+
     #[structopt(long)]
     /// Report telemetry to Jaeger
     telemetry: bool,
+
+    #[structopt(env, long)]
+    /// Directives for which events to log, e.g. `info,moose::networking=debug`; defaults to `RUST_LOG`
+    log_filter: Option<String>,
+
+    #[structopt(env, long, default_value = "10")]
+    /// Timeout in seconds for connecting to other workers
+    connect_timeout: u64,
+
+    #[structopt(env, long, default_value = "30")]
+    /// Interval in seconds between keepalive pings; 0 disables keepalive
+    keepalive_interval: u64,
+
+    #[structopt(env, long, default_value = "20")]
+    /// Timeout in seconds for keepalive pings to be acknowledged
+    keepalive_timeout: u64,
+
+    #[structopt(long)]
+    /// Connect to other workers on every send instead of reusing connections
+    no_channel_reuse: bool,
+
+    #[structopt(env, long)]
+    /// Deadline in seconds for each value sent to other workers
+    request_timeout: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Fail sessions in which a worker a value is awaited from sends nothing for this many seconds
+    receive_timeout: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Interval in seconds between heartbeats sent to workers that values are awaited from
+    heartbeat_interval: Option<u64>,
+
+    #[structopt(env, long, default_value = "30")]
+    /// Fail sessions once a worker has not answered heartbeats for this many seconds
+    heartbeat_timeout: u64,
+
+    #[structopt(env, long, default_value = "5")]
+    /// Seconds to wait for each peer to answer a heartbeat when checking the health of this worker
+    health_check_timeout: u64,
+
+    #[structopt(env, long)]
+    /// Fail sessions in which an operation takes more than this many seconds once its operands are available
+    compute_timeout: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Number of threads handling networking and scheduling; defaults to one per core
+    worker_threads: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of threads used for blocking operations such as file access
+    max_blocking_threads: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Run expensive kernels on a dedicated pool with this many threads; 0 uses one per core
+    compute_threads: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of kernels running at a time across all sessions
+    max_running_kernels: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of kernels of a single session running at a time; defaults to half of `max_running_kernels`
+    max_running_kernels_per_session: Option<usize>,
+
+    #[cfg(feature = "gpu")]
+    #[structopt(env, long, value_delimiter = ',')]
+    /// Run supported kernels on the GPU for placements owned by these roles
+    gpu_roles: Vec<String>,
+
+    #[cfg(feature = "gpu")]
+    #[structopt(env, long)]
+    /// Run kernels on the GPU only if they perform at least this many scalar operations
+    gpu_min_work: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of bytes of intermediate values each session keeps in memory
+    memory_budget: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Directory for values exceeding `memory_budget`, only accessible by the worker; required with `memory_budget`
+    spill_dir: Option<String>,
+
+    #[structopt(env, long)]
+    /// Zeroize the secret shares, seeds, and keys computed by each session once it has finished
+    scrub_secrets: bool,
+
+    #[structopt(env, long)]
+    /// Track the bytes of values alive in each session and report them with its results
+    track_memory: bool,
+
+    #[structopt(env, long)]
+    /// Report the messages sent in each session, by placement pair and operator kind, with its results
+    communication_report: bool,
+
+    #[structopt(env, long)]
+    /// Commit to the messages exchanged in each session and report the commitment with its results
+    transcripts: bool,
+
+    #[structopt(env, long)]
+    /// File holding a 32-byte Ed25519 secret key with which to sign transcript commitments; implies `transcripts`
+    transcript_key: Option<String>,
+
+    #[structopt(env, long)]
+    /// Log a summary of the value computed by every operation, for debugging
+    summarize_values: bool,
+
+    #[structopt(env, long)]
+    /// Directory in which to checkpoint sessions so that they can be resumed after a restart
+    checkpoint_dir: Option<String>,
+
+    #[structopt(env, long, default_value = "60")]
+    /// Seconds between checkpoints; should be the same for all workers
+    checkpoint_interval: u64,
+
+    #[structopt(env, long)]
+    /// Maximum number of bytes of results of pure operations to reuse across sessions
+    kernel_cache_size: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of sessions running at a time; further sessions are queued by priority
+    max_concurrent_sessions: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Seconds to wait for running sessions when draining on SIGTERM or Ctrl-C; waits forever by default
+    drain_timeout: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Role bindings file; sessions assigning roles differently are rejected
+    bindings: Option<String>,
+
+    #[structopt(env, long)]
+    /// Storage access list file stating which keys sessions may load and save for each role; requires `bindings`
+    storage_acl: Option<String>,
+
+    #[cfg(feature = "compile")]
+    #[structopt(env, long)]
+    /// Reveal policy file; sessions are only launched if labelled with a logical computation complying with it
+    reveal_policy: Option<String>,
+
+    #[cfg(feature = "dns")]
+    #[structopt(env, long)]
+    /// Resolve the endpoints of other parties via DNS SRV records of their identity followed by this suffix
+    discovery_srv_suffix: Option<String>,
+
+    #[cfg(feature = "metrics")]
+    #[structopt(env, long)]
+    /// Port on which to serve Prometheus metrics over HTTP
+    metrics_port: Option<u16>,
+
+    #[cfg(feature = "flight")]
+    #[structopt(env, long)]
+    /// Arrow Flight service to load and save values with keys starting with `flight:` from and to
+    flight_endpoint: Option<String>,
+
+    #[cfg(feature = "object-store")]
+    #[structopt(env, long)]
+    /// Region of S3 buckets holding values loaded and saved with `s3://` keys
+    object_store_region: Option<String>,
+
+    #[cfg(feature = "object-store")]
+    #[structopt(env, long)]
+    /// Endpoint to use for `s3://` keys instead of AWS, such as that of a MinIO deployment
+    object_store_endpoint: Option<String>,
+
+    #[cfg(feature = "object-store")]
+    #[structopt(env, long)]
+    /// Allow connecting to `object_store_endpoint` over plain HTTP
+    object_store_allow_http: bool,
+
+    #[cfg(feature = "redis")]
+    #[structopt(env, long)]
+    /// Redis server to load and save values with keys starting with `redis:` from and to, such as `redis://redis:6379`
+    redis_url: Option<String>,
+
+    #[cfg(feature = "redis")]
+    #[structopt(env, long)]
+    /// Prefix of the Redis keys of values, to share a server between deployments [default: moose]
+    redis_namespace: Option<String>,
+
+    #[cfg(feature = "redis")]
+    #[structopt(env, long)]
+    /// Only let sessions load values from Redis that were saved by the same session
+    redis_session_scoped: bool,
+
+    #[cfg(feature = "redis")]
+    #[structopt(env, long)]
+    /// Seconds after which values saved to Redis are evicted
+    redis_ttl: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Append-only file in which to log launched sessions and values revealed to this worker
+    audit_log: Option<String>,
+
+    #[structopt(env, long)]
+    /// File holding the 32-byte secret key authenticating the entries of `audit_log`
+    audit_key: Option<String>,
+
+    #[structopt(env, long)]
+    /// Directory in which to record sessions for replaying them with `cometctl replay`
+    record_dir: Option<String>,
+
+    #[structopt(env, long)]
+    /// Directory in which to write per-operation profiles of sessions, as Chrome traces and folded stacks
+    profile_dir: Option<String>,
+
+    #[structopt(env, long)]
+    /// Maximum size in bytes of messages sent and received; unlimited by default
+    max_message_size: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Maximum number of concurrent HTTP/2 streams per connection
+    max_concurrent_streams: Option<u32>,
+
+    #[structopt(env, long)]
+    /// Maximum number of concurrently handled requests per connection
+    concurrency_limit: Option<usize>,
+
+    #[structopt(env, long)]
+    /// Send and receive all values through the relay at this endpoint
+    relay: Option<String>,
+
+    #[structopt(long)]
+    /// Also act as a relay for other workers
+    relay_server: bool,
+
+    #[structopt(long)]
+    /// Serve gRPC reflection for the services of the worker
+    reflection: bool,
+
+    #[structopt(env, long)]
+    /// Send values of at least this many bytes one at a time per worker, after any smaller values
+    bulk_threshold: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Maximum bandwidth in bytes per second used by each session for sending values
+    session_bandwidth: Option<u64>,
+
+    #[structopt(env, long)]
+    /// Number of bytes each session may send in a burst above `session_bandwidth`; defaults to one second worth
+    session_burst: Option<u64>,
+}
+
+/// Bind to a unix domain socket, replacing any stale socket left by a previous run
+#[cfg(unix)]
+fn bind_unix_socket(path: &str) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if metadata.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    tokio::net::UnixListener::bind(path)
+}
+
+/// Wait for a request to shut down, i.e. Ctrl-C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = terminate.recv() => (),
+                    _ = tokio::signal::ctrl_c() => (),
+                }
+                return;
+            }
+            Err(e) => tracing::warn!("Failed to listen for SIGTERM: {}", e),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Client TLS configuration, taking the private key from `keys` if given.
+async fn client_tls_config(
+    my_cert_name: &str,
+    certs_dir: &str,
+    keys: &Option<KeyProviderImpl>,
+) -> Result<ClientTlsConfig, Box<dyn std::error::Error>> {
+    match keys {
+        Some(keys) => {
+            moose::reindeer::load_client_tls_config_with_keys(
+                my_cert_name,
+                certs_dir,
+                keys.as_ref(),
+            )
+            .await
+        }
+        None => moose::reindeer::load_client_tls_config(my_cert_name, certs_dir),
+    }
+}
+
+/// Server TLS configuration, taking the private key from `keys` if given.
+async fn server_tls_config(
+    my_cert_name: &str,
+    certs_dir: &str,
+    keys: &Option<KeyProviderImpl>,
+) -> Result<ServerTlsConfig, Box<dyn std::error::Error>> {
+    match keys {
+        Some(keys) => {
+            moose::reindeer::load_server_tls_config_with_keys(
+                my_cert_name,
+                certs_dir,
+                keys.as_ref(),
+            )
+            .await
+        }
+        None => moose::reindeer::load_server_tlc_config(my_cert_name, certs_dir),
+    }
+}
+
+/// Options given as flags, environment variables, or in the configuration file, in that order of precedence.
+fn parse_opt() -> Result<Opt, Box<dyn std::error::Error>> {
+    // required options may also be given in the configuration file
+    let command = Opt::command().mut_arg("identity", |arg| arg.required(false));
+    let matches = command.clone().get_matches();
+    let config_path = match matches.get_one::<String>("config") {
+        Some(path) => path,
+        None => return Ok(Opt::parse()),
+    };
+    let config = config::read_config_file(config_path)?;
+    let mut args: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    args.extend(config::config_args(&command, &matches, &config)?);
+    Ok(Opt::parse_from(args))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let opt = parse_opt()?;
+    if opt.print_config {
+        print!("{}", config::print_config(&opt)?);
+        return Ok(());
+    }
+
+    let mut runtime = tokio::runtime::Builder::new_multi_thread();
+    runtime.enable_all();
+    if let Some(worker_threads) = opt.worker_threads {
+        runtime.worker_threads(worker_threads);
+    }
+    if let Some(max_blocking_threads) = opt.max_blocking_threads {
+        runtime.max_blocking_threads(max_blocking_threads);
+    }
+    runtime.build()?.block_on(run(opt))
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let opt = Opt::parse();
+async fn run(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
     if !opt.telemetry {
-        tracing_subscriber::fmt::init();
+        moose::reindeer::setup_logging(opt.log_filter.as_deref())?;
     } else {
         moose::reindeer::setup_tracing(&opt.identity, "comet")?;
     }
@@ -45,39 +461,468 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _enter = root_span.enter();
 
     let my_cert_name = opt.identity.replace(':', "_");
+    let keys = match opt.key_provider {
+        Some(ref uri) => Some(key_provider_from_uri(uri).await?),
+        None => None,
+    };
     let own_identity = Identity::from(opt.identity);
 
+    let keepalive_interval = match opt.keepalive_interval {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+
+    let channel_config = ChannelConfig {
+        reuse_channels: !opt.no_channel_reuse,
+        connect_timeout: Some(Duration::from_secs(opt.connect_timeout)),
+        request_timeout: opt.request_timeout.map(Duration::from_secs),
+        keepalive_interval,
+        keepalive_timeout: Some(Duration::from_secs(opt.keepalive_timeout)),
+        keepalive_while_idle: keepalive_interval.is_some(),
+        tcp_keepalive: keepalive_interval,
+    };
+
     let networking = match opt.certs {
         Some(ref certs_dir) => {
-            let client = moose::reindeer::load_client_tls_config(&my_cert_name, certs_dir)?;
+            let client = client_tls_config(&my_cert_name, certs_dir, &keys).await?;
             GrpcNetworkingManager::from_tls_config(client)
         }
         None => GrpcNetworkingManager::without_tls(),
+    }
+    .with_channel_config(channel_config);
+    let networking = match opt.auth_token {
+        Some(token) => networking.with_auth_token(token),
+        None => networking,
+    };
+    let networking = match opt.max_message_size {
+        Some(max_message_size) => networking.with_max_message_size(max_message_size),
+        None => networking,
+    };
+    let networking = match opt.heartbeat_interval {
+        Some(interval) => networking.with_heartbeats(HeartbeatConfig {
+            interval: Duration::from_secs(interval),
+            timeout: Duration::from_secs(opt.heartbeat_timeout),
+        }),
+        None => networking,
+    };
+    #[cfg(feature = "dns")]
+    let networking = match opt.discovery_srv_suffix {
+        Some(ref suffix) => networking.with_discovery(Arc::new(
+            moose::networking::discovery::DnsSrvDiscovery::from_system_conf(suffix.clone())?,
+        )),
+        None => networking,
     };
 
-    let networking_server = networking.new_server();
-    let choreography = GrpcChoreography::new(
-        own_identity,
-        opt.choreographer,
-        Box::new(move |session_id| networking.new_session(session_id)),
-        Box::new(|| Arc::new(AsyncFilesystemStorage::default())),
-    );
+    let auth = match opt.auth_tokens {
+        Some(ref path) => TokenAuth::new(TokenAuthConfig::from_file(path)?),
+        None => TokenAuth::disabled(),
+    };
 
-    let mut server = Server::builder();
+    let relay = match opt.relay {
+        Some(ref relay_endpoint) => {
+            let tls_config = match opt.certs {
+                Some(ref certs_dir) => {
+                    Some(client_tls_config(&my_cert_name, certs_dir, &keys).await?)
+                }
+                None => None,
+            };
+            Some(RelayNetworking::new(
+                own_identity.clone(),
+                relay_endpoint,
+                tls_config,
+            )?)
+        }
+        None => None,
+    };
 
-    if let Some(ref certs_dir) = opt.certs {
-        let tls_server_config = moose::reindeer::load_server_tlc_config(&my_cert_name, certs_dir)?;
-        server = server.tls_config(tls_server_config)?;
-    }
+    let relay_service = if opt.relay_server {
+        Some(RelayService::default())
+    } else {
+        None
+    };
 
-    let router = server
-        .add_service(networking_server)
-        .add_service(choreography.into_server());
+    let reflection_service = if opt.reflection {
+        Some(moose::grpc::reflection_service()?)
+    } else {
+        None
+    };
+
+    let storage: StorageStrategy = Box::new(|| Arc::new(AsyncFilesystemStorage::default()));
+    #[cfg(feature = "compression")]
+    let storage: StorageStrategy = match opt.storage_compression {
+        Some(level) => Box::new(move || {
+            Arc::new(AsyncFilesystemStorage::default().with_compression(Some(level)))
+        }),
+        None => storage,
+    };
+    #[cfg(feature = "encrypted-storage")]
+    let storage: StorageStrategy = match opt.storage_key {
+        Some(ref name) => {
+            let keys = keys
+                .as_ref()
+                .ok_or("`storage_key` requires `key_provider` to be specified")?;
+            let key = zeroize::Zeroizing::new(keys.get_key(name).await?);
+            let encrypted = Arc::new(AsyncEncryptedStorage::new(&key, storage())?);
+            Box::new(move || Arc::clone(&encrypted) as moose::execution::AsyncStorageImpl)
+        }
+        None => storage,
+    };
+    #[cfg(feature = "flight")]
+    let storage: StorageStrategy = match opt.flight_endpoint {
+        Some(ref endpoint) => {
+            let flight = Arc::new(AsyncFlightStorage::new(
+                endpoint,
+                Arc::new(AsyncFilesystemStorage::default()),
+            )?);
+            Box::new(move || Arc::clone(&flight) as moose::execution::AsyncStorageImpl)
+        }
+        None => storage,
+    };
+    #[cfg(feature = "object-store")]
+    let storage: StorageStrategy = {
+        let config = ObjectStoreConfig::default()
+            .with_region(opt.object_store_region.clone())
+            .with_endpoint(opt.object_store_endpoint.clone())
+            .with_allow_http(opt.object_store_allow_http);
+        let objects = Arc::new(AsyncObjectStorage::new(config, storage()));
+        Box::new(move || Arc::clone(&objects) as moose::execution::AsyncStorageImpl)
+    };
+    #[cfg(feature = "redis")]
+    let storage: StorageStrategy = match opt.redis_url {
+        Some(ref url) => {
+            let config = RedisConfig::default()
+                .with_session_scoped(opt.redis_session_scoped)
+                .with_ttl(opt.redis_ttl.map(Duration::from_secs));
+            let config = match opt.redis_namespace {
+                Some(ref namespace) => config.with_namespace(namespace.clone()),
+                None => config,
+            };
+            let redis = Arc::new(AsyncRedisStorage::new(url, config, storage())?);
+            Box::new(move || Arc::clone(&redis) as moose::execution::AsyncStorageImpl)
+        }
+        None => storage,
+    };
+
+    let choreography = {
+        let networking = networking.clone();
+        let session_bandwidth = opt.session_bandwidth;
+        let session_burst = opt.session_burst;
+        let bulk_threshold = opt.bulk_threshold;
+        let choreography = GrpcChoreography::new(
+            own_identity,
+            opt.choreographer,
+            Box::new(move |session_id| {
+                let session_networking: AsyncNetworkingImpl = match relay {
+                    Some(ref relay) => Arc::clone(relay) as AsyncNetworkingImpl,
+                    None => networking.new_session(session_id),
+                };
+                let session_networking: AsyncNetworkingImpl = match session_bandwidth {
+                    Some(rate) => Arc::new(ThrottledNetworking::new(
+                        session_networking,
+                        TokenBucket::new(rate, session_burst.unwrap_or(rate)),
+                    )),
+                    None => session_networking,
+                };
+                match bulk_threshold {
+                    Some(threshold) => {
+                        Arc::new(PrioritizedNetworking::new(session_networking, threshold))
+                    }
+                    None => session_networking,
+                }
+            }),
+            storage,
+        );
+        let choreography = choreography.with_timeouts(OperationTimeouts {
+            receive: opt.receive_timeout.map(Duration::from_secs),
+            compute: opt.compute_timeout.map(Duration::from_secs),
+        });
+        let choreography = match opt.compute_threads {
+            Some(num_threads) => {
+                choreography.with_compute_pool(Arc::new(ComputePool::new(num_threads)?))
+            }
+            None => choreography,
+        };
+        let choreography = match (opt.max_running_kernels, opt.max_running_kernels_per_session) {
+            (None, None) => choreography,
+            (max_running, max_running_per_session) => {
+                let max_running = max_running.unwrap_or(tokio::sync::Semaphore::MAX_PERMITS);
+                let max_running_per_session =
+                    max_running_per_session.unwrap_or(std::cmp::max(max_running / 2, 1));
+                choreography.with_scheduler(Arc::new(Scheduler::new(
+                    max_running,
+                    max_running_per_session,
+                )))
+            }
+        };
+        #[cfg(feature = "gpu")]
+        let choreography = if opt.gpu_roles.is_empty() {
+            choreography
+        } else {
+            let roles = opt.gpu_roles.iter().map(Role::from).collect();
+            let gpu = moose::execution::gpu::GpuBackend::new(roles)?;
+            let gpu = match opt.gpu_min_work {
+                Some(min_work) => gpu.with_min_work(min_work),
+                None => gpu,
+            };
+            choreography.with_gpu(Arc::new(gpu))
+        };
+        let choreography = match (opt.memory_budget, &opt.spill_dir) {
+            (Some(limit), Some(spill_dir)) => {
+                let config = MemoryBudgetConfig {
+                    limit,
+                    spill_dir: spill_dir.into(),
+                };
+                config.prepare_spill_dir()?;
+                choreography.with_memory_budget(config)
+            }
+            // spilled values may be secret shares, which must not end up in a shared directory
+            (Some(_), None) => return Err("`memory_budget` requires `spill_dir`".into()),
+            (None, _) => choreography,
+        };
+        // copies of secret values on disk are out of reach of scrubbing
+        if opt.scrub_secrets
+            && (opt.checkpoint_dir.is_some()
+                || opt.memory_budget.is_some()
+                || opt.record_dir.is_some())
+        {
+            return Err(
+                "`scrub_secrets` cannot be combined with `checkpoint_dir`, `memory_budget`, or `record_dir`"
+                    .into(),
+            );
+        }
+        let choreography = if opt.scrub_secrets {
+            choreography.with_scrubbing()
+        } else {
+            choreography
+        };
+        let choreography = if opt.track_memory {
+            choreography.with_memory_tracking()
+        } else {
+            choreography
+        };
+        let choreography = if opt.communication_report {
+            choreography.with_communication_reports()
+        } else {
+            choreography
+        };
+        let choreography = match opt.transcript_key {
+            Some(ref transcript_key) => choreography.with_transcript_signer(Arc::new(
+                TranscriptSigner::from_file(Path::new(transcript_key))?,
+            )),
+            None if opt.transcripts => choreography.with_transcripts(),
+            None => choreography,
+        };
+        let choreography = if opt.summarize_values {
+            choreography.with_value_summaries()
+        } else {
+            choreography
+        };
+        let choreography = match opt.checkpoint_dir {
+            Some(ref checkpoint_dir) => choreography.with_checkpoints(CheckpointConfig {
+                dir: checkpoint_dir.into(),
+                interval: Duration::from_secs(opt.checkpoint_interval),
+            }),
+            None => choreography,
+        };
+        let choreography = match opt.max_concurrent_sessions {
+            Some(max_running) => {
+                choreography.with_admission(Arc::new(AdmissionQueue::new(max_running)))
+            }
+            None => choreography,
+        };
+        let choreography = match opt.kernel_cache_size {
+            Some(capacity) => choreography.with_cache(Arc::new(KernelCache::new(capacity))),
+            None => choreography,
+        };
+        let choreography = match opt.record_dir {
+            Some(ref record_dir) => choreography.with_recording(record_dir.into()),
+            None => choreography,
+        };
+        let choreography = match opt.bindings {
+            Some(ref bindings) => {
+                choreography.with_bindings(RoleBindings::from_file(Path::new(bindings))?)
+            }
+            None => choreography,
+        };
+        // roles are otherwise chosen by whoever launches a session
+        if opt.storage_acl.is_some() && opt.bindings.is_none() {
+            return Err("`storage_acl` requires `bindings` to be specified".into());
+        }
+        let choreography = match opt.storage_acl {
+            Some(ref storage_acl) => choreography
+                .with_storage_acl(Arc::new(StorageAcl::from_file(Path::new(storage_acl))?)),
+            None => choreography,
+        };
+        #[cfg(feature = "compile")]
+        let choreography = match opt.reveal_policy {
+            Some(ref reveal_policy) => {
+                choreography.with_reveal_policy(RevealPolicy::from_file(reveal_policy)?)
+            }
+            None => choreography,
+        };
+        // peers are only reached directly without a relay
+        let choreography = if opt.relay.is_none() {
+            let networking = networking.clone();
+            let timeout = Duration::from_secs(opt.health_check_timeout);
+            choreography.with_peer_check(Box::new(move |peer| {
+                let networking = networking.clone();
+                Box::pin(async move { networking.check_peer(&peer, timeout).await })
+            }))
+        } else {
+            choreography
+        };
+        let choreography = match opt.profile_dir {
+            Some(ref profile_dir) => choreography.with_profiling(profile_dir.into()),
+            None => choreography,
+        };
+        #[cfg(feature = "metrics")]
+        let choreography = match opt.metrics_port {
+            Some(port) => {
+                let metrics = Arc::new(moose::execution::metrics::Metrics::new()?);
+                let addr = format!("0.0.0.0:{}", port).parse()?;
+                let server = Arc::clone(&metrics).serve(addr);
+                tokio::spawn(async move {
+                    if let Err(e) = server.await {
+                        tracing::error!("Failed to serve metrics: {}", e);
+                    }
+                });
+                choreography.with_metrics(metrics)
+            }
+            None => choreography,
+        };
+        let choreography = match (&opt.audit_log, &opt.audit_key) {
+            (Some(audit_log), Some(audit_key)) => {
+                let key = AuditKey::from_file(Path::new(audit_key))?;
+                choreography.with_audit(Arc::new(AuditLog::open(Path::new(audit_log), key)?))
+            }
+            // an unkeyed chain could be rewritten by anyone able to write the log
+            (Some(_), None) => return Err("`audit_log` requires `audit_key`".into()),
+            (None, _) => choreography,
+        };
+        let choreography = match keys {
+            Some(ref keys) => choreography.with_key_provider(Arc::clone(keys)),
+            None => choreography,
+        };
+        Arc::new(match opt.max_message_size {
+            Some(max_message_size) => choreography.with_max_message_size(max_message_size),
+            None => choreography,
+        })
+    };
+
+    let mut tls_server_config = match opt.certs {
+        Some(ref certs_dir) => Some(server_tls_config(&my_cert_name, certs_dir, &keys).await?),
+        None => None,
+    };
+
+    let reload_trigger = match opt.certs {
+        Some(ref certs_dir) => Some(moose::reindeer::spawn_certificate_reload_trigger(
+            certs_dir,
+            opt.watch_certs,
+        )?),
+        None => None,
+    };
 
     let addr = format!("0.0.0.0:{}", &opt.port).parse()?;
-    let res = router.serve(addr).await;
-    if let Err(e) = res {
-        tracing::error!("gRPC error: {}", e);
+
+    // on shutdown, stop accepting sessions but keep serving until running ones finished
+    let drained = Arc::new(tokio::sync::Notify::new());
+    let is_drained = Arc::new(AtomicBool::new(false));
+    {
+        let choreography = Arc::clone(&choreography);
+        let drained = Arc::clone(&drained);
+        let is_drained = Arc::clone(&is_drained);
+        let drain_timeout = opt.drain_timeout.map(Duration::from_secs);
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            tracing::info!("Draining, no longer accepting new sessions");
+            choreography.drain();
+            match drain_timeout {
+                Some(timeout) => {
+                    if tokio::time::timeout(timeout, choreography.wait_until_idle())
+                        .await
+                        .is_err()
+                    {
+                        tracing::warn!(
+                            "Sessions still running after {:?}; checkpointed sessions can be resumed after restarting",
+                            timeout
+                        );
+                    }
+                }
+                None => choreography.wait_until_idle().await,
+            }
+            is_drained.store(true, Ordering::SeqCst);
+            drained.notify_one();
+        });
+    }
+
+    // the server is restarted whenever certificates are reloaded; all session
+    // state is shared between the servers so running sessions are unaffected
+    loop {
+        let mut server = Server::builder()
+            .http2_keepalive_interval(keepalive_interval)
+            .http2_keepalive_timeout(Some(Duration::from_secs(opt.keepalive_timeout)))
+            .tcp_keepalive(keepalive_interval)
+            .max_concurrent_streams(opt.max_concurrent_streams);
+
+        if let Some(limit) = opt.concurrency_limit {
+            server = server.concurrency_limit_per_connection(limit);
+        }
+
+        if let Some(ref tls_server_config) = tls_server_config {
+            server = server.tls_config(tls_server_config.clone())?;
+        }
+
+        let router = server
+            .layer(tonic::service::interceptor(auth.clone()))
+            .add_service(networking.new_server())
+            .add_service(GrpcChoreography::new_server(&choreography))
+            .add_optional_service(relay_service.as_ref().map(RelayService::new_server))
+            .add_optional_service(reflection_service.clone());
+
+        let reload = reload_trigger.clone();
+        let drained = Arc::clone(&drained);
+        let shutdown = async move {
+            let reload = async move {
+                match reload {
+                    Some(reload) => reload.notified().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = reload => (),
+                _ = drained.notified() => (),
+            }
+        };
+        let res = match opt.socket {
+            #[cfg(unix)]
+            Some(ref path) => {
+                let listener = bind_unix_socket(path)?;
+                router
+                    .serve_with_incoming_shutdown(UnixListenerStream::new(listener), shutdown)
+                    .await
+            }
+            _ => router.serve_with_shutdown(addr, shutdown).await,
+        };
+        if let Err(e) = res {
+            tracing::error!("gRPC error: {}", e);
+            break;
+        }
+        if is_drained.load(Ordering::SeqCst) {
+            tracing::info!("Drained, shutting down");
+            break;
+        }
+
+        if let Some(ref certs_dir) = opt.certs {
+            tracing::info!("Reloading certificates from {}", certs_dir);
+            match server_tls_config(&my_cert_name, certs_dir, &keys).await {
+                Ok(config) => tls_server_config = Some(config),
+                Err(e) => tracing::error!("Failed to reload server certificates: {}", e),
+            }
+            match client_tls_config(&my_cert_name, certs_dir, &keys).await {
+                Ok(config) => networking.reload_tls_config(config),
+                Err(e) => tracing::error!("Failed to reload client certificates: {}", e),
+            }
+        }
     }
     Ok(())
 }
@@ -1,15 +1,29 @@
 //! CLI tool for interacting with a group of Comet reindeers
 
 use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use moose::choreography::admission::Priority;
 use moose::choreography::filesystem::{
     parse_session_config_file_with_computation, parse_session_config_file_without_computation,
 };
-use moose::computation::SessionId;
-use moose::execution::grpc::GrpcMooseRuntime;
+use moose::choreography::grpc::{SessionMetadata, SessionState};
+use moose::computation::{Role, SessionId};
+use moose::execution::audit::{verify_audit_log, AuditEvent, AuditKey};
+use moose::execution::grpc::{GrpcMooseRuntime, GrpcOutputs};
+use moose::execution::recording::Replay;
+use moose::execution::{stream_outputs, ExecutionContext, Identity};
+use moose::host::HostPlacement;
+use moose::networking::local::LocalAsyncNetworking;
+use moose::networking::transcript::{
+    check_transcripts, verify_transcripts, TranscriptKeys, TranscriptSigner,
+};
+use moose::storage::local::LocalAsyncStorage;
 use moose::tokio;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tonic::transport::ClientTlsConfig;
 
 #[derive(Parser, Debug)]
 #[clap(name = "cometctl")]
@@ -25,6 +39,19 @@ pub struct Cli {
     #[clap(long)]
     /// Own identity; `certs` must be specified
     identity: Option<String>,
+
+    #[clap(env, long, hide_env_values = true)]
+    /// Bearer token to present to the workers
+    auth_token: Option<String>,
+
+    #[clap(long)]
+    /// Maximum size in bytes of computations and results; unlimited by default
+    max_message_size: Option<usize>,
+
+    #[clap(long)]
+    /// Directory holding the 32-byte Ed25519 public key of each worker as `<identity>.pub`,
+    /// against which transcript commitments in results are verified
+    transcript_keys: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -37,6 +64,22 @@ pub enum Commands {
         #[clap(long)]
         /// Session id to use
         session_id: Option<String>,
+
+        #[clap(long)]
+        /// Resume the session from this checkpoint on restarted workers
+        resume_checkpoint: Option<u64>,
+
+        #[clap(long)]
+        /// Tag to attach to the session; may be given multiple times
+        tag: Vec<String>,
+
+        #[clap(long, value_parser = parse_label)]
+        /// Label to attach to the session, as `key=value`; may be given multiple times
+        label: Vec<(String, String)>,
+
+        #[clap(long, default_value = "normal")]
+        /// Priority of the session on workers queueing sessions: batch, normal, or interactive
+        priority: Priority,
     },
     /// Abort computation on cluster
     Abort {
@@ -46,6 +89,10 @@ pub enum Commands {
         #[clap(long)]
         /// Session id to use
         session_id: Option<String>,
+
+        #[clap(long)]
+        /// Reason reported when retrieving results of the session
+        reason: Option<String>,
     },
     /// Retrieve results of computation from cluster (blocking)
     Results {
@@ -55,6 +102,23 @@ pub enum Commands {
         #[clap(long)]
         /// Session id to use
         session_id: Option<String>,
+
+        #[clap(long)]
+        /// Print each output as soon as it is available instead of once the session has finished
+        stream: bool,
+
+        #[clap(long, conflicts_with = "stream")]
+        /// Print the outputs completed before the session failed, if it did
+        partial: bool,
+    },
+    /// Show progress of computation on cluster until it has finished (blocking)
+    Progress {
+        /// Session config file to use
+        session_config: PathBuf,
+
+        #[clap(long)]
+        /// Session id to use
+        session_id: Option<String>,
     },
     /// Launch computation and retrieve results (blocking)
     Run {
@@ -64,14 +128,135 @@ pub enum Commands {
         #[clap(long)]
         /// Session id to use
         session_id: Option<String>,
+
+        #[clap(long)]
+        /// Resume the session from this checkpoint on restarted workers
+        resume_checkpoint: Option<u64>,
+
+        #[clap(long, conflicts_with_all = &["session_id", "resume_checkpoint"])]
+        /// Retry failed sessions under this key, returning the results of earlier attempts if any
+        idempotency_key: Option<String>,
+
+        #[clap(long, default_value = "3")]
+        /// Maximum number of attempts when `idempotency_key` is given
+        max_attempts: usize,
+    },
+    /// List sessions launched on each worker of the cluster
+    List {
+        /// Session config file to use
+        session_config: PathBuf,
+
+        #[clap(long)]
+        /// Only list sessions in this state: waiting, running, completed, or failed
+        state: Option<SessionState>,
+
+        #[clap(long)]
+        /// Only list sessions with this tag; may be given multiple times
+        tag: Vec<String>,
+    },
+    /// Show the health of each worker, failing unless all are ready to accept sessions
+    Health {
+        /// Session config file to use
+        session_config: PathBuf,
+    },
+    /// Check that all roles of the computation are bound to resolvable endpoints
+    Check {
+        /// Session config file to use
+        session_config: PathBuf,
+
+        #[clap(long)]
+        /// Also check that the workers can together run the computation
+        workers: bool,
+    },
+    /// Replay a session recorded by a worker locally, without its peers (blocking)
+    Replay {
+        /// Session config file to use
+        session_config: PathBuf,
+
+        #[clap(long)]
+        /// Role played by the worker that recorded the session
+        role: String,
+
+        #[clap(long)]
+        /// Recording written by the worker
+        recording: PathBuf,
+    },
+    /// Verify the audit log of a worker and summarize the values revealed in it
+    Audit {
+        /// Audit log written by the worker
+        log: PathBuf,
+        /// File holding the 32-byte secret key the worker authenticated the log with
+        #[clap(long)]
+        key: PathBuf,
+    },
+    /// Write the public key matching the transcript key of a worker
+    TranscriptKey {
+        /// File holding the 32-byte Ed25519 secret key given to the worker
+        secret_key: PathBuf,
+
+        /// File to write the 32-byte public key to, named `<identity>.pub` for `--transcript-keys`
+        public_key: PathBuf,
     },
 }
 
+fn parse_label(label: &str) -> Result<(String, String), String> {
+    match label.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected `key=value` but found '{}'", label)),
+    }
+}
+
+fn new_runtime(
+    role_assignments: HashMap<Role, Identity>,
+    tls_config: Option<ClientTlsConfig>,
+    auth_token: Option<String>,
+    max_message_size: Option<usize>,
+) -> Result<GrpcMooseRuntime, Box<dyn std::error::Error>> {
+    let runtime = GrpcMooseRuntime::new(role_assignments, tls_config)?;
+    let runtime = match auth_token {
+        Some(token) => runtime.with_auth_token(token),
+        None => runtime,
+    };
+    Ok(match max_message_size {
+        Some(max_message_size) => runtime.with_max_message_size(max_message_size),
+        None => runtime,
+    })
+}
+
+/// Print disagreements between the transcript commitments of the workers, if any.
+///
+/// With `keys`, fails unless every worker reported a commitment signed with its key.
+fn report_transcripts(
+    results: &GrpcOutputs,
+    keys: Option<&TranscriptKeys>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let commitments: Vec<_> = match results.transcripts {
+        Some(ref transcripts) => transcripts.values().cloned().collect(),
+        None if keys.is_some() => {
+            return Err("workers did not report transcript commitments".into())
+        }
+        None => return Ok(()),
+    };
+    if let Some(keys) = keys {
+        verify_transcripts(&commitments, keys)?;
+    }
+    for mismatch in check_transcripts(&commitments) {
+        println!("Transcripts disagree: {}", mismatch);
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Cli::parse();
     tracing_subscriber::fmt::init();
 
+    let auth_token = args.auth_token;
+    let max_message_size = args.max_message_size;
+    let transcript_keys = args
+        .transcript_keys
+        .map(|dir| TranscriptKeys::from_dir(&dir))
+        .transpose()?;
     let tls_config = match (args.certs, args.identity) {
         (Some(certs_dir), Some(identity)) => Some(moose::reindeer::load_client_tls_config(
             &identity, &certs_dir,
@@ -84,61 +269,320 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Launch {
             session_config,
             session_id,
+            resume_checkpoint,
+            tag,
+            label,
+            priority,
         } => {
-            let (_, default_session_id, role_assignments, computation) =
+            let (session_config, default_session_id, role_assignments, computation) =
                 parse_session_config_file_with_computation(&session_config)?;
-            let runtime = GrpcMooseRuntime::new(role_assignments, tls_config)?;
+            session_config.role_bindings()?.check_endpoints()?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
             let session_id = session_id
                 .map(|session_id| SessionId::try_from(session_id.as_ref()))
                 .unwrap_or(Ok(default_session_id))?;
-            // TODO for now we don't support arguments
-            let arguments = HashMap::new();
-            runtime
-                .launch_computation(&session_id, &computation, arguments)
+            let arguments = session_config
+                .load_arguments(&HostPlacement::from("cometctl"))
                 .await?;
+            match resume_checkpoint {
+                Some(checkpoint) => {
+                    runtime
+                        .resume_computation(&session_id, &computation, arguments, checkpoint)
+                        .await?
+                }
+                None => {
+                    let metadata = SessionMetadata {
+                        labels: label.into_iter().collect(),
+                        tags: tag,
+                        priority,
+                        ..Default::default()
+                    };
+                    runtime
+                        .launch_computation_with_metadata(
+                            &session_id,
+                            &computation,
+                            arguments,
+                            &metadata,
+                        )
+                        .await?
+                }
+            }
         }
         Commands::Abort {
             session_config,
             session_id,
+            reason,
         } => {
             let (_, default_session_id, role_assignments) =
                 parse_session_config_file_without_computation(&session_config)?;
-            let runtime = GrpcMooseRuntime::new(role_assignments, tls_config)?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
             let session_id = session_id
                 .map(|session_id| SessionId::try_from(session_id.as_ref()))
                 .unwrap_or(Ok(default_session_id))?;
-            runtime.abort_computation(&session_id).await?;
+            runtime
+                .abort_computation(&session_id, reason.as_deref())
+                .await?;
         }
         Commands::Results {
             session_config,
             session_id,
+            stream,
+            partial,
         } => {
             let (_, default_session_id, role_assignments) =
                 parse_session_config_file_without_computation(&session_config)?;
-            let runtime = GrpcMooseRuntime::new(role_assignments, tls_config)?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
             let session_id = session_id
                 .map(|session_id| SessionId::try_from(session_id.as_ref()))
                 .unwrap_or(Ok(default_session_id))?;
-            let results = runtime.retrieve_results(&session_id).await?;
-            println!("Results: {:?}", results);
+            if stream {
+                runtime
+                    .stream_results(&session_id, |role, tag, value| {
+                        println!("{}: {} = {:?}", role, tag, value);
+                    })
+                    .await?;
+            } else if partial {
+                let results = runtime.retrieve_partial_results(&session_id).await?;
+                report_transcripts(&results, transcript_keys.as_ref())?;
+                if let Some(ref errors) = results.errors {
+                    for (role, error) in errors {
+                        println!("Session failed on {}: {}", role, error);
+                    }
+                    println!("Partial results: {:?}", results);
+                } else {
+                    println!("Results: {:?}", results);
+                }
+            } else {
+                let results = runtime.retrieve_results(&session_id).await?;
+                report_transcripts(&results, transcript_keys.as_ref())?;
+                println!("Results: {:?}", results);
+            }
+        }
+        Commands::Progress {
+            session_config,
+            session_id,
+        } => {
+            let (_, default_session_id, role_assignments) =
+                parse_session_config_file_without_computation(&session_config)?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
+            let session_id = session_id
+                .map(|session_id| SessionId::try_from(session_id.as_ref()))
+                .unwrap_or(Ok(default_session_id))?;
+            runtime
+                .watch_progress(&session_id, |role, progress| {
+                    println!(
+                        "{}: {}/{} operations, {} bytes sent, {} bytes received ({:?})",
+                        role,
+                        progress.ops_completed,
+                        progress.ops_total,
+                        progress.bytes_sent,
+                        progress.bytes_received,
+                        progress.phase
+                    );
+                })
+                .await?;
         }
         Commands::Run {
             session_config,
             session_id,
+            resume_checkpoint,
+            idempotency_key,
+            max_attempts,
         } => {
-            let (_, default_session_id, role_assignments, computation) =
+            let (session_config, default_session_id, role_assignments, computation) =
                 parse_session_config_file_with_computation(&session_config)?;
-            let runtime = GrpcMooseRuntime::new(role_assignments, tls_config)?;
+            session_config.role_bindings()?.check_endpoints()?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
             let session_id = session_id
                 .map(|session_id| SessionId::try_from(session_id.as_ref()))
                 .unwrap_or(Ok(default_session_id))?;
-            // TODO for now we don't support arguments; see comment in FilesystemChoreography::launch_session
-            let arguments = HashMap::new();
-            let results = runtime
-                .run_computation(&session_id, &computation, arguments)
+            let arguments = session_config
+                .load_arguments(&HostPlacement::from("cometctl"))
                 .await?;
+            let results = match (idempotency_key, resume_checkpoint) {
+                (Some(key), _) => {
+                    runtime
+                        .run_computation_with_retries(&key, &computation, arguments, max_attempts)
+                        .await?
+                }
+                (None, Some(checkpoint)) => {
+                    runtime
+                        .resume_computation(&session_id, &computation, arguments, checkpoint)
+                        .await?;
+                    runtime.retrieve_results(&session_id).await?
+                }
+                (None, None) => {
+                    runtime
+                        .run_computation(&session_id, &computation, arguments)
+                        .await?
+                }
+            };
+            report_transcripts(&results, transcript_keys.as_ref())?;
             println!("Results: {:?}", results);
         }
+        Commands::List {
+            session_config,
+            state,
+            tag,
+        } => {
+            let (_, _, role_assignments) =
+                parse_session_config_file_without_computation(&session_config)?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
+            let sessions = runtime.list_sessions(state, &tag).await?;
+            for (role, infos) in sessions {
+                for info in infos {
+                    println!(
+                        "{}: {} {} launched by {} with tags {:?} and labels {:?}",
+                        role,
+                        info.session_id,
+                        info.state,
+                        info.launched_by.as_deref().unwrap_or("unknown"),
+                        info.metadata.tags,
+                        info.metadata.labels
+                    );
+                }
+            }
+        }
+        Commands::Health { session_config } => {
+            let (_, _, role_assignments) =
+                parse_session_config_file_without_computation(&session_config)?;
+            let runtime = new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
+            let mut unready = Vec::new();
+            for (role, health) in runtime.health().await? {
+                println!(
+                    "{}: version {}, up {:?}, {} active sessions{}, storage {}",
+                    role,
+                    health.version,
+                    health.uptime,
+                    health.active_sessions,
+                    if health.draining { ", draining" } else { "" },
+                    health.storage
+                );
+                for (peer, status) in health.peers.iter() {
+                    println!("{}: peer {} {}", role, peer, status);
+                }
+                if !health.is_ready() {
+                    unready.push(role.to_string());
+                }
+            }
+            if !unready.is_empty() {
+                unready.sort();
+                return Err(format!("workers not ready: {}", unready.join(", ")).into());
+            }
+        }
+        Commands::Check {
+            session_config,
+            workers,
+        } => {
+            // parsing validates the bindings against the computation
+            let (session_config, _, role_assignments, computation) =
+                parse_session_config_file_with_computation(&session_config)?;
+            session_config.role_bindings()?.check_endpoints()?;
+            for (role, identity) in role_assignments.iter() {
+                println!("{} -> {}", role, identity);
+            }
+            if workers {
+                let runtime =
+                    new_runtime(role_assignments, tls_config, auth_token, max_message_size)?;
+                for (role, capabilities) in runtime.capabilities(&computation).await? {
+                    println!(
+                        "{} runs {} with features {:?}",
+                        role, capabilities.version, capabilities.features
+                    );
+                }
+                runtime.check_capabilities(&computation).await?;
+            }
+        }
+        Commands::Replay {
+            session_config,
+            role,
+            recording,
+        } => {
+            let (_, session_id, role_assignments, computation) =
+                parse_session_config_file_with_computation(&session_config)?;
+            let own_identity = role_assignments
+                .get(&Role::from(&role))
+                .ok_or_else(|| format!("role {} not found in session config", role))?
+                .clone();
+            let replay = Replay::load(&recording)?;
+            tracing::info!("Replaying {} recorded results", replay.len());
+
+            let context = ExecutionContext::new(
+                own_identity,
+                Arc::new(LocalAsyncNetworking::default()),
+                Arc::new(LocalAsyncStorage::default()),
+            )
+            .with_replay(Arc::new(replay));
+            let (handle, outputs) = context
+                .execute_computation(session_id, &computation, HashMap::new(), role_assignments)
+                .await?;
+            let session = tokio::spawn(handle.join_on_first_error());
+
+            // outputs are printed as soon as they are ready, without waiting for the session
+            let mut outputs = stream_outputs(&computation, outputs)?;
+            while let Some(output) = outputs.next().await {
+                match output {
+                    Ok((tag, value)) => println!("Output {}: {:?}", tag, value),
+                    Err(_) => break,
+                }
+            }
+            session.await??;
+        }
+        Commands::Audit { log, key } => {
+            let entries = verify_audit_log(&log, &AuditKey::from_file(&key)?)?;
+            println!("Audit log verified, {} entries", entries.len());
+            for entry in entries {
+                match entry.event {
+                    AuditEvent::SessionLaunched {
+                        session_id,
+                        computation,
+                        role_assignment,
+                        launched_by,
+                    } => println!(
+                        "#{} session {} launched by {} running computation {} with roles {:?}",
+                        entry.seq,
+                        session_id,
+                        launched_by.as_deref().unwrap_or("unknown"),
+                        computation,
+                        role_assignment
+                    ),
+                    AuditEvent::ValueRevealed {
+                        session_id,
+                        op_name,
+                        placement,
+                        ty,
+                        digest,
+                    } => println!(
+                        "#{} session {} revealed {} of type {} to {} (digest {})",
+                        entry.seq, session_id, op_name, ty, placement, digest
+                    ),
+                    AuditEvent::ValueReceived {
+                        session_id,
+                        op_name,
+                        sender,
+                        placement,
+                        ty,
+                        digest,
+                    } => println!(
+                        "#{} session {} received {} of type {} from {} on {} (digest {})",
+                        entry.seq, session_id, op_name, ty, sender, placement, digest
+                    ),
+                    AuditEvent::SessionFinished { session_id, error } => match error {
+                        Some(error) => {
+                            println!("#{} session {} failed: {}", entry.seq, session_id, error)
+                        }
+                        None => println!("#{} session {} completed", entry.seq, session_id),
+                    },
+                }
+            }
+        }
+        Commands::TranscriptKey {
+            secret_key,
+            public_key,
+        } => {
+            let signer = TranscriptSigner::from_file(&secret_key)?;
+            std::fs::write(&public_key, signer.public_key())?;
+        }
     }
 
     Ok(())
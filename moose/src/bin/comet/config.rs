@@ -0,0 +1,388 @@
+//! Layered configuration of Comet.
+//!
+//! Options are taken from, in order of increasing precedence, a TOML or YAML
+//! configuration file given by `--config`, environment variables, and flags.
+//! The file groups options into sections, using the names of the flags with
+//! underscores in place of dashes:
+//!
+//! ```toml
+//! identity = "localhost:50000"
+//! port = 50000
+//!
+//! [networking]
+//! connect_timeout = 5
+//!
+//! [tls]
+//! certs = "/etc/comet/certs"
+//! ```
+
+use clap::{ArgMatches, Command};
+use serde_json::{Map, Value as Json};
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Section of the configuration file holding each option.
+///
+/// Options in the empty section are given at the top level of the file.
+fn schema() -> Vec<(&'static str, &'static str)> {
+    let mut schema = vec![
+        ("", "identity"),
+        ("", "port"),
+        ("", "socket"),
+        ("", "choreographer"),
+        ("", "bindings"),
+        ("", "relay_server"),
+        ("", "reflection"),
+        ("networking", "connect_timeout"),
+        ("networking", "keepalive_interval"),
+        ("networking", "keepalive_timeout"),
+        ("networking", "no_channel_reuse"),
+        ("networking", "request_timeout"),
+        ("networking", "receive_timeout"),
+        ("networking", "heartbeat_interval"),
+        ("networking", "heartbeat_timeout"),
+        ("networking", "health_check_timeout"),
+        ("networking", "relay"),
+        ("networking", "bulk_threshold"),
+        ("networking", "session_bandwidth"),
+        ("networking", "session_burst"),
+        ("tls", "certs"),
+        ("tls", "key_provider"),
+        ("tls", "watch_certs"),
+        ("tls", "auth_tokens"),
+        ("tls", "auth_token"),
+        ("storage", "spill_dir"),
+        ("storage", "storage_acl"),
+        ("storage", "checkpoint_dir"),
+        ("storage", "checkpoint_interval"),
+        ("limits", "compute_timeout"),
+        ("limits", "worker_threads"),
+        ("limits", "max_blocking_threads"),
+        ("limits", "compute_threads"),
+        ("limits", "max_running_kernels"),
+        ("limits", "max_running_kernels_per_session"),
+        ("limits", "memory_budget"),
+        ("limits", "scrub_secrets"),
+        ("limits", "kernel_cache_size"),
+        ("limits", "max_concurrent_sessions"),
+        ("limits", "drain_timeout"),
+        ("limits", "max_message_size"),
+        ("limits", "max_concurrent_streams"),
+        ("limits", "concurrency_limit"),
+        ("telemetry", "telemetry"),
+        ("telemetry", "log_filter"),
+        ("telemetry", "track_memory"),
+        ("telemetry", "communication_report"),
+        ("telemetry", "transcripts"),
+        ("telemetry", "transcript_key"),
+        ("telemetry", "summarize_values"),
+        ("telemetry", "audit_log"),
+        ("telemetry", "audit_key"),
+        ("telemetry", "record_dir"),
+        ("telemetry", "profile_dir"),
+    ];
+    #[cfg(feature = "compile")]
+    schema.push(("", "reveal_policy"));
+    #[cfg(feature = "gpu")]
+    schema.push(("limits", "gpu_roles"));
+    #[cfg(feature = "gpu")]
+    schema.push(("limits", "gpu_min_work"));
+    #[cfg(feature = "dns")]
+    schema.push(("networking", "discovery_srv_suffix"));
+    #[cfg(feature = "metrics")]
+    schema.push(("telemetry", "metrics_port"));
+    #[cfg(feature = "compression")]
+    schema.push(("storage", "storage_compression"));
+    #[cfg(feature = "encrypted-storage")]
+    schema.push(("storage", "storage_key"));
+    #[cfg(feature = "flight")]
+    schema.push(("storage", "flight_endpoint"));
+    #[cfg(feature = "object-store")]
+    schema.push(("storage", "object_store_region"));
+    #[cfg(feature = "object-store")]
+    schema.push(("storage", "object_store_endpoint"));
+    #[cfg(feature = "object-store")]
+    schema.push(("storage", "object_store_allow_http"));
+    #[cfg(feature = "redis")]
+    schema.push(("storage", "redis_url"));
+    #[cfg(feature = "redis")]
+    schema.push(("storage", "redis_namespace"));
+    #[cfg(feature = "redis")]
+    schema.push(("storage", "redis_session_scoped"));
+    #[cfg(feature = "redis")]
+    schema.push(("storage", "redis_ttl"));
+    schema
+}
+
+/// Options whose values are not shown by `--print-config`.
+const SECRETS: &[&str] = &["auth_token", "redis_url"];
+
+/// Options given in the configuration file, keyed by option name.
+pub fn read_config_file<P: AsRef<Path>>(path: P) -> Result<Map<String, Json>, String> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read config file {}: {}", path.display(), e))?;
+    let parsed: Json = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str::<toml::Value>(&contents)
+            .map_err(|e| e.to_string())
+            .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string())),
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str::<Json>(&contents).map_err(|e| e.to_string())
+        }
+        _ => Err("expected a .toml, .yaml, or .yml file".to_string()),
+    }
+    .map_err(|e| format!("invalid config file {}: {}", path.display(), e))?;
+    flatten(parsed).map_err(|e| format!("invalid config file {}: {}", path.display(), e))
+}
+
+/// Options in sections of the file, checked against the schema.
+fn flatten(parsed: Json) -> Result<Map<String, Json>, String> {
+    let schema = schema();
+    let section_of = |key: &str| {
+        schema
+            .iter()
+            .find(|(_, option)| *option == key)
+            .map(|(section, _)| *section)
+    };
+    let is_section = |key: &str| schema.iter().any(|(section, _)| *section == key);
+
+    let top_level = match parsed {
+        Json::Object(top_level) => top_level,
+        Json::Null => Map::new(),
+        _ => return Err("expected a table of options".to_string()),
+    };
+    let mut options = Map::new();
+    for (key, value) in top_level {
+        let entries = match value {
+            Json::Object(entries) if is_section(&key) && !key.is_empty() => entries
+                .into_iter()
+                .map(|(option, value)| (Some(key.clone()), option, value))
+                .collect(),
+            value => vec![(None, key, value)],
+        };
+        for (section, option, value) in entries {
+            let expected =
+                section_of(&option).ok_or_else(|| format!("unknown option '{}'", option))?;
+            match (section.as_deref(), expected) {
+                (None, "") => (),
+                (Some(section), expected) if section == expected => (),
+                (_, "") => {
+                    return Err(format!(
+                        "option '{}' must be given at the top level",
+                        option
+                    ))
+                }
+                (_, expected) => {
+                    return Err(format!(
+                        "option '{}' must be given in section [{}]",
+                        option, expected
+                    ))
+                }
+            }
+            options.insert(option, value);
+        }
+    }
+    Ok(options)
+}
+
+/// Command line arguments for the options in `config` not already given by
+/// flags or environment variables.
+pub fn config_args(
+    command: &Command,
+    matches: &ArgMatches,
+    config: &Map<String, Json>,
+) -> Result<Vec<OsString>, String> {
+    let mut args = Vec::new();
+    for (option, value) in config.iter() {
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id().as_str() == option)
+            .ok_or_else(|| format!("unknown option '{}'", option))?;
+        if matches!(
+            matches.value_source(option),
+            Some(clap::parser::ValueSource::CommandLine)
+                | Some(clap::parser::ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let flag = format!("--{}", option.replace('_', "-"));
+        let invalid =
+            |expected: &str| format!("option '{}' must be {}, not {}", option, expected, value);
+        if !arg.get_action().takes_values() {
+            match value {
+                Json::Bool(true) => args.push(flag.into()),
+                Json::Bool(false) => (),
+                _ => return Err(invalid("a boolean")),
+            }
+            continue;
+        }
+        let value = match value {
+            Json::String(s) => s.clone(),
+            Json::Number(n) => n.to_string(),
+            Json::Array(items) if arg.get_value_delimiter().is_some() => items
+                .iter()
+                .map(|item| match item {
+                    Json::String(s) => Ok(s.clone()),
+                    Json::Number(n) => Ok(n.to_string()),
+                    _ => Err(invalid("a list of strings")),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join(","),
+            _ => return Err(invalid("a string or number")),
+        };
+        args.push(flag.into());
+        args.push(value.into());
+    }
+    Ok(args)
+}
+
+/// Effective configuration in the format of the configuration file.
+pub fn print_config<T: serde::Serialize>(opt: &T) -> Result<String, String> {
+    let options = match serde_json::to_value(opt).map_err(|e| e.to_string())? {
+        Json::Object(options) => options,
+        _ => return Err("options must serialize to a table".to_string()),
+    };
+    let mut config = toml::value::Table::new();
+    for (section, option) in schema() {
+        let value = match options.get(option) {
+            None | Some(Json::Null) => continue,
+            Some(_) if SECRETS.contains(&option) => toml::Value::String("<redacted>".to_string()),
+            Some(value) => toml::Value::try_from(value).map_err(|e| e.to_string())?,
+        };
+        if section.is_empty() {
+            config.insert(option.to_string(), value);
+        } else {
+            let table = config
+                .entry(section.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+            if let toml::Value::Table(table) = table {
+                table.insert(option.to_string(), value);
+            }
+        }
+    }
+    toml::to_string(&toml::Value::Table(config)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+    use std::io::Write;
+
+    fn command() -> Command {
+        Command::new("comet")
+            .arg(Arg::new("config").long("config"))
+            .arg(Arg::new("identity").long("identity"))
+            .arg(Arg::new("port").long("port").env("COMET_CONFIG_TEST_PORT"))
+            .arg(
+                Arg::new("connect_timeout")
+                    .long("connect-timeout")
+                    .env("COMET_CONFIG_TEST_CONNECT_TIMEOUT"),
+            )
+            .arg(
+                Arg::new("watch_certs")
+                    .long("watch-certs")
+                    .action(ArgAction::SetTrue),
+            )
+    }
+
+    fn config_file(suffix: &str, contents: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file
+    }
+
+    /// Matches of `args` with the options of the configuration file added, as done by Comet.
+    fn parse(args: &[&str]) -> Result<ArgMatches, String> {
+        let command = command();
+        let matches = command.clone().try_get_matches_from(args).unwrap();
+        let config = read_config_file(matches.get_one::<String>("config").unwrap())?;
+        let mut args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        args.extend(config_args(&command, &matches, &config)?);
+        Ok(command.try_get_matches_from(args).unwrap())
+    }
+
+    #[test]
+    fn test_precedence() {
+        let file = config_file(
+            ".toml",
+            r#"
+            identity = "file"
+            port = 1
+
+            [networking]
+            connect_timeout = 1
+
+            [tls]
+            watch_certs = true
+            "#,
+        );
+        std::env::set_var("COMET_CONFIG_TEST_PORT", "2");
+        std::env::set_var("COMET_CONFIG_TEST_CONNECT_TIMEOUT", "2");
+        let path = file.path().to_str().unwrap();
+        let matches = parse(&["comet", "--config", path, "--connect-timeout", "3"]).unwrap();
+        assert_eq!(matches.get_one::<String>("identity").unwrap(), "file");
+        assert_eq!(matches.get_one::<String>("port").unwrap(), "2");
+        assert_eq!(matches.get_one::<String>("connect_timeout").unwrap(), "3");
+        assert!(matches.get_flag("watch_certs"));
+    }
+
+    #[test]
+    fn test_yaml() {
+        let file = config_file(
+            ".yaml",
+            "identity: file\nnetworking:\n  connect_timeout: 1\n",
+        );
+        let options = read_config_file(file.path()).unwrap();
+        assert_eq!(options.get("identity"), Some(&Json::from("file")));
+        assert_eq!(options.get("connect_timeout"), Some(&Json::from(1)));
+    }
+
+    #[test]
+    fn test_unknown_option() {
+        let file = config_file(".toml", "identity = \"file\"\nidentiy = \"typo\"\n");
+        let err = read_config_file(file.path()).unwrap_err();
+        assert!(err.contains("unknown option 'identiy'"), "{}", err);
+
+        let file = config_file(".toml", "[networking]\nconnect_timeot = 1\n");
+        let err = read_config_file(file.path()).unwrap_err();
+        assert!(err.contains("unknown option 'connect_timeot'"), "{}", err);
+
+        // known to the schema but not to the command, e.g. when built without a feature
+        let file = config_file(".toml", "[limits]\ncompute_timeout = 1\n");
+        let path = file.path().to_str().unwrap();
+        let err = parse(&["comet", "--config", path]).unwrap_err();
+        assert!(err.contains("unknown option 'compute_timeout'"), "{}", err);
+    }
+
+    #[test]
+    fn test_misplaced_option() {
+        let file = config_file(".toml", "connect_timeout = 1\n");
+        let err = read_config_file(file.path()).unwrap_err();
+        assert!(
+            err.contains("option 'connect_timeout' must be given in section [networking]"),
+            "{}",
+            err
+        );
+
+        let file = config_file(".toml", "[networking]\nport = 1\n");
+        let err = read_config_file(file.path()).unwrap_err();
+        assert!(
+            err.contains("option 'port' must be given at the top level"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_invalid_value() {
+        let file = config_file(".toml", "[tls]\nwatch_certs = \"yes\"\n");
+        let path = file.path().to_str().unwrap();
+        let err = parse(&["comet", "--config", path]).unwrap_err();
+        assert!(
+            err.contains("option 'watch_certs' must be a boolean"),
+            "{}",
+            err
+        );
+    }
+}
@@ -2,13 +2,16 @@
 
 use clap::Parser;
 use moose::computation::Operator;
+use moose::execution::AsyncNetworkingImpl;
 use moose::networking::local::LocalAsyncNetworking;
+use moose::networking::simulated::{LinkProfile, NetworkProfile, SimulatedNetworking};
 use moose::prelude::*;
 use moose::storage::local::LocalAsyncStorage;
 use moose::tokio;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Parser, Clone)]
 #[structopt(about = "Run computation locally by simulating all roles as seperate identities")]
@@ -20,6 +23,18 @@ pub struct Opt {
 
     #[structopt(short, long, default_value = "dasher-session")]
     session_id: String,
+
+    /// Simulated one-way latency in milliseconds added to every message
+    #[structopt(long)]
+    latency: Option<u64>,
+
+    /// Simulated maximum jitter in milliseconds added to every message
+    #[structopt(long)]
+    jitter: Option<u64>,
+
+    /// Simulated bandwidth in bytes per second
+    #[structopt(long)]
+    bandwidth: Option<u64>,
 }
 
 #[tokio::main]
@@ -66,7 +81,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .collect()
     };
 
-    let networking = Arc::new(LocalAsyncNetworking::default());
+    let networking: AsyncNetworkingImpl = {
+        let networking = Arc::new(LocalAsyncNetworking::default());
+        if opt.latency.is_some() || opt.jitter.is_some() || opt.bandwidth.is_some() {
+            let profile = NetworkProfile::new(LinkProfile {
+                latency: Duration::from_millis(opt.latency.unwrap_or(0)),
+                jitter: Duration::from_millis(opt.jitter.unwrap_or(0)),
+                bandwidth: opt.bandwidth,
+            });
+            // all roles share the local networking so only the default link applies
+            Arc::new(SimulatedNetworking::new(
+                Identity::from("dasher"),
+                networking,
+                Arc::new(profile),
+            ))
+        } else {
+            networking
+        }
+    };
 
     let storage = Arc::new(LocalAsyncStorage::default());
 
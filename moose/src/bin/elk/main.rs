@@ -2,6 +2,9 @@
 
 use clap::{Parser, Subcommand, ValueEnum};
 use moose::compilation::compile;
+use moose::compilation::leakage::leakage_report;
+use moose::compilation::policy::{enforce_policy, RevealPolicy};
+use moose::compilation::validation::{validate, validate_source};
 use moose::prelude::Computation;
 use moose::textual::ToTextual;
 use std::collections::HashMap;
@@ -41,6 +44,30 @@ pub enum Commands {
         /// Comma-separated list of passes to apply in-order; default to all passes
         #[clap(short, long)]
         passes: Option<String>,
+
+        /// Reveal policy in TOML format to enforce before compiling
+        #[clap(long)]
+        policy: Option<PathBuf>,
+    },
+
+    /// Report all problems found in a computation, grouped by severity
+    Check {
+        /// Input file
+        input: PathBuf,
+
+        /// Computation format
+        #[clap(value_enum, short, long, default_value = "textual")]
+        input_format: ComputationFormat,
+    },
+
+    /// Report the values revealed to each role, and the secret inputs they derive from, as JSON
+    Leakage {
+        /// Input file
+        input: PathBuf,
+
+        /// Computation format
+        #[clap(value_enum, short, long, default_value = "textual")]
+        input_format: ComputationFormat,
     },
 
     /// Collect stats about a computation
@@ -109,6 +136,7 @@ fn main() -> anyhow::Result<()> {
             input_format,
             output_format,
             passes,
+            policy,
         } => {
             let passes: Option<Vec<String>> = passes.clone().map(|p| {
                 p.split(',')
@@ -116,10 +144,33 @@ fn main() -> anyhow::Result<()> {
                     .map(|s| s.to_string())
                     .collect()
             });
-            let comp = input_computation(input, input_format)?;
+            let mut comp = input_computation(input, input_format)?;
+            if let Some(policy) = policy {
+                comp = enforce_policy(comp, &RevealPolicy::from_file(policy)?)?;
+            }
             let comp = compile(comp, passes)?;
             output_computation(&comp, output, output_format)?;
         }
+        Commands::Check {
+            input,
+            input_format,
+        } => {
+            let report = match input_format {
+                ComputationFormat::Textual => validate_source(&read_to_string(input)?),
+                _ => validate(&input_computation(input, input_format)?),
+            };
+            print!("{}", report);
+            if report.has_errors() {
+                anyhow::bail!("Computation is not valid");
+            }
+        }
+        Commands::Leakage {
+            input,
+            input_format,
+        } => {
+            let comp = input_computation(input, input_format)?;
+            println!("{}", leakage_report(&comp)?.to_json()?);
+        }
         Commands::Stats(StatsCommands::OpHist {
             input,
             input_format,
@@ -38,13 +38,17 @@ pub struct Opt {
     #[structopt(long)]
     /// Report telemetry to Jaeger
     telemetry: bool,
+
+    #[structopt(env, long)]
+    /// Directives for which events to log, e.g. `info,moose::networking=debug`; defaults to `RUST_LOG`
+    log_filter: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opt = Opt::parse();
     if !opt.telemetry {
-        tracing_subscriber::fmt::init();
+        moose::reindeer::setup_logging(opt.log_filter.as_deref())?;
     } else {
         moose::reindeer::setup_tracing(&opt.identity, "rudolph")?;
     }
@@ -0,0 +1,491 @@
+//! C API for embedding local execution of Moose computations.
+//!
+//! An executor holds a computation, the arguments bound to its inputs, and the
+//! outputs of its last run. All roles of the computation are played locally, in
+//! the same way as by the local runtime of the Python bindings:
+//!
+//! ```c
+//! MooseExecutor *executor = moose_executor_new();
+//! moose_executor_load_computation(executor, bytes, len, true);
+//! moose_executor_bind_input(executor, "x", MOOSE_FLOAT64, data, shape, 2);
+//! moose_executor_run(executor);
+//! moose_executor_output_info(executor, "output_0", &dtype, &ndim);
+//! moose_executor_output_shape(executor, "output_0", shape);
+//! moose_executor_output_data(executor, "output_0", dtype, buffer, len);
+//! moose_executor_free(executor);
+//! ```
+//!
+//! Functions return `MOOSE_OK` on success and `MOOSE_ERROR` otherwise, in which
+//! case `moose_last_error` describes the error. The declarations are found in
+//! `include/moose.h`; build with `cargo rustc --features capi --crate-type cdylib`
+//! (or `staticlib`) to obtain a library to link against.
+
+use crate::compilation::{compile, Pass};
+use crate::error::{Error, Result};
+use crate::execution::AsyncTestRuntime;
+use crate::prelude::*;
+use ndarray::{ArrayD, IxDyn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+
+pub const MOOSE_OK: c_int = 0;
+pub const MOOSE_ERROR: c_int = 1;
+
+pub const MOOSE_FLOAT32: c_int = 0;
+pub const MOOSE_FLOAT64: c_int = 1;
+pub const MOOSE_INT32: c_int = 2;
+pub const MOOSE_INT64: c_int = 3;
+pub const MOOSE_UINT32: c_int = 4;
+pub const MOOSE_UINT64: c_int = 5;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+#[derive(Default)]
+pub struct MooseExecutor {
+    computation: Option<Computation>,
+    arguments: HashMap<String, Value>,
+    outputs: HashMap<String, Value>,
+}
+
+impl MooseExecutor {
+    fn run(&mut self) -> Result<()> {
+        let computation = self
+            .computation
+            .as_ref()
+            .ok_or_else(|| Error::InvalidArgument("no computation has been loaded".to_string()))?;
+        // every role is played by an identity of the same name
+        let storage_mapping = roles(computation)
+            .into_iter()
+            .map(|role| (role.0, HashMap::new()))
+            .collect();
+        let mut runtime = AsyncTestRuntime::new(storage_mapping);
+        self.outputs = runtime.evaluate_computation(computation, self.arguments.clone())?;
+        Ok(())
+    }
+
+    fn output(&self, name: &str) -> Result<&Value> {
+        self.outputs
+            .get(name)
+            .ok_or_else(|| Error::InvalidArgument(format!("no output named '{}'", name)))
+    }
+}
+
+fn roles(computation: &Computation) -> Vec<Role> {
+    let mut roles: Vec<Role> = Vec::new();
+    for op in computation.operations.iter() {
+        let owners = match &op.placement {
+            Placement::Host(plc) => vec![plc.owner.clone()],
+            Placement::Additive(plc) => plc.owners.to_vec(),
+            Placement::Mirrored3(plc) => plc.owners.to_vec(),
+            Placement::Replicated(plc) => plc.owners.to_vec(),
+        };
+        for owner in owners {
+            if !roles.contains(&owner) {
+                roles.push(owner);
+            }
+        }
+    }
+    roles
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Run `f`, turning errors and panics into a status code and the last error.
+fn status<F: FnOnce() -> Result<()>>(f: F) -> c_int {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => MOOSE_OK,
+        Ok(Err(e)) => {
+            set_last_error(e.to_string());
+            MOOSE_ERROR
+        }
+        Err(panic) => {
+            let reason = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown reason".to_string());
+            set_last_error(format!("moose panicked: {}", reason));
+            MOOSE_ERROR
+        }
+    }
+}
+
+unsafe fn executor<'a>(executor: *mut MooseExecutor) -> Result<&'a mut MooseExecutor> {
+    executor
+        .as_mut()
+        .ok_or_else(|| Error::InvalidArgument("executor must not be null".to_string()))
+}
+
+unsafe fn string<'a>(s: *const c_char) -> Result<&'a str> {
+    if s.is_null() {
+        return Err(Error::InvalidArgument("name must not be null".to_string()));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| Error::InvalidArgument(format!("name must be valid UTF-8: {}", e)))
+}
+
+unsafe fn slice<'a, T>(data: *const T, len: usize) -> Result<&'a [T]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if data.is_null() {
+        return Err(Error::InvalidArgument(
+            "buffer must not be null".to_string(),
+        ));
+    }
+    Ok(std::slice::from_raw_parts(data, len))
+}
+
+unsafe fn slice_mut<'a, T>(data: *mut T, len: usize) -> Result<&'a mut [T]> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    if data.is_null() {
+        return Err(Error::InvalidArgument(
+            "buffer must not be null".to_string(),
+        ));
+    }
+    Ok(std::slice::from_raw_parts_mut(data, len))
+}
+
+unsafe fn write<T>(ptr: *mut T, x: T) -> Result<()> {
+    match ptr.as_mut() {
+        Some(entry) => {
+            *entry = x;
+            Ok(())
+        }
+        None => Err(Error::InvalidArgument(
+            "pointer must not be null".to_string(),
+        )),
+    }
+}
+
+fn unknown_dtype(dtype: c_int) -> Error {
+    Error::InvalidArgument(format!("unknown dtype {}", dtype))
+}
+
+/// Dtype and shape of the tensors that can be fetched as outputs.
+fn describe(value: &Value) -> Result<(c_int, &[usize])> {
+    match value {
+        Value::HostFloat32Tensor(x) => Ok((MOOSE_FLOAT32, x.0.shape())),
+        Value::HostFloat64Tensor(x) => Ok((MOOSE_FLOAT64, x.0.shape())),
+        Value::HostInt32Tensor(x) => Ok((MOOSE_INT32, x.0.shape())),
+        Value::HostInt64Tensor(x) => Ok((MOOSE_INT64, x.0.shape())),
+        Value::HostUint32Tensor(x) => Ok((MOOSE_UINT32, x.0.shape())),
+        Value::HostUint64Tensor(x) => Ok((MOOSE_UINT64, x.0.shape())),
+        other => Err(Error::InvalidArgument(format!(
+            "outputs of type {} cannot be fetched",
+            other.ty()
+        ))),
+    }
+}
+
+/// Create a new executor, to be freed using `moose_executor_free`.
+#[no_mangle]
+pub extern "C" fn moose_executor_new() -> *mut MooseExecutor {
+    Box::into_raw(Box::new(MooseExecutor::default()))
+}
+
+/// Free an executor created using `moose_executor_new`.
+///
+/// # Safety
+///
+/// `executor` must be null or have been returned by `moose_executor_new` and
+/// not freed already.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_free(executor: *mut MooseExecutor) {
+    if !executor.is_null() {
+        drop(Box::from_raw(executor));
+    }
+}
+
+/// Load a computation serialized using msgpack, replacing any previous one.
+///
+/// Logical computations must be compiled using the default passes by setting
+/// `compile`; physical computations are loaded as is.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor and `bytes` must point to `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_load_computation(
+    executor: *mut MooseExecutor,
+    bytes: *const u8,
+    len: usize,
+    compile: bool,
+) -> c_int {
+    status(|| {
+        let executor = self::executor(executor)?;
+        let computation = Computation::from_msgpack(slice(bytes, len)?)?;
+        let computation = if compile {
+            self::compile::<Pass>(computation, None)
+                .map_err(|e| Error::MalformedComputation(e.to_string()))?
+        } else {
+            computation
+        };
+        executor.computation = Some(computation);
+        executor.outputs.clear();
+        Ok(())
+    })
+}
+
+/// Bind the argument `name` to a tensor copied from a row-major buffer.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor, `name` a null-terminated string, `shape`
+/// must point to `ndim` dimensions, and `data` to as many entries of type `dtype`
+/// as given by their product.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_bind_input(
+    executor: *mut MooseExecutor,
+    name: *const c_char,
+    dtype: c_int,
+    data: *const c_void,
+    shape: *const usize,
+    ndim: usize,
+) -> c_int {
+    status(|| {
+        let executor = self::executor(executor)?;
+        let name = string(name)?;
+        let shape = slice(shape, ndim)?;
+        let len = shape.iter().product();
+        let plc = HostPlacement::from("TODO");
+
+        macro_rules! tensor {
+            ($t:ty) => {{
+                let values = slice(data as *const $t, len)?.to_vec();
+                let array = ArrayD::from_shape_vec(IxDyn(shape), values)
+                    .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+                Value::from(HostTensor::<$t>(array.into_shared(), plc))
+            }};
+        }
+
+        let value = match dtype {
+            MOOSE_FLOAT32 => tensor!(f32),
+            MOOSE_FLOAT64 => tensor!(f64),
+            MOOSE_INT32 => tensor!(i32),
+            MOOSE_INT64 => tensor!(i64),
+            MOOSE_UINT32 => tensor!(u32),
+            MOOSE_UINT64 => tensor!(u64),
+            other => return Err(unknown_dtype(other)),
+        };
+        executor.arguments.insert(name.to_string(), value);
+        Ok(())
+    })
+}
+
+/// Run the loaded computation on the bound arguments.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_run(executor: *mut MooseExecutor) -> c_int {
+    status(|| self::executor(executor)?.run())
+}
+
+/// Write the dtype and number of dimensions of the output `name`.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor, `name` a null-terminated string, and
+/// `dtype` and `ndim` valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_output_info(
+    executor: *mut MooseExecutor,
+    name: *const c_char,
+    dtype: *mut c_int,
+    ndim: *mut usize,
+) -> c_int {
+    status(|| {
+        let value = self::executor(executor)?.output(string(name)?)?;
+        let (value_dtype, shape) = describe(value)?;
+        write(dtype, value_dtype)?;
+        write(ndim, shape.len())
+    })
+}
+
+/// Write the dimensions of the output `name`.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor, `name` a null-terminated string, and
+/// `shape` must have room for as many dimensions as given by `moose_executor_output_info`.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_output_shape(
+    executor: *mut MooseExecutor,
+    name: *const c_char,
+    shape: *mut usize,
+) -> c_int {
+    status(|| {
+        let value = self::executor(executor)?.output(string(name)?)?;
+        let (_, dims) = describe(value)?;
+        slice_mut(shape, dims.len())?.copy_from_slice(dims);
+        Ok(())
+    })
+}
+
+/// Copy the entries of the output `name` in row-major order into `data`.
+///
+/// # Safety
+///
+/// `executor` must be a valid executor, `name` a null-terminated string, and
+/// `data` must have room for `len` entries of type `dtype`.
+#[no_mangle]
+pub unsafe extern "C" fn moose_executor_output_data(
+    executor: *mut MooseExecutor,
+    name: *const c_char,
+    dtype: c_int,
+    data: *mut c_void,
+    len: usize,
+) -> c_int {
+    status(|| {
+        let name = string(name)?;
+        let value = self::executor(executor)?.output(name)?;
+
+        macro_rules! copy {
+            ($t:ty, $x:expr) => {{
+                if $x.0.len() != len {
+                    return Err(Error::InvalidArgument(format!(
+                        "output '{}' has {} entries but the buffer holds {}",
+                        name,
+                        $x.0.len(),
+                        len
+                    )));
+                }
+                let data = slice_mut(data as *mut $t, len)?;
+                for (entry, x) in data.iter_mut().zip($x.0.iter()) {
+                    *entry = *x;
+                }
+            }};
+        }
+
+        match (dtype, value) {
+            (MOOSE_FLOAT32, Value::HostFloat32Tensor(x)) => copy!(f32, x),
+            (MOOSE_FLOAT64, Value::HostFloat64Tensor(x)) => copy!(f64, x),
+            (MOOSE_INT32, Value::HostInt32Tensor(x)) => copy!(i32, x),
+            (MOOSE_INT64, Value::HostInt64Tensor(x)) => copy!(i64, x),
+            (MOOSE_UINT32, Value::HostUint32Tensor(x)) => copy!(u32, x),
+            (MOOSE_UINT64, Value::HostUint64Tensor(x)) => copy!(u64, x),
+            (MOOSE_FLOAT32..=MOOSE_UINT64, other) => {
+                return Err(Error::InvalidArgument(format!(
+                    "output '{}' of type {} does not have dtype {}",
+                    name,
+                    other.ty(),
+                    dtype
+                )))
+            }
+            (other, _) => return Err(unknown_dtype(other)),
+        }
+        Ok(())
+    })
+}
+
+/// Message describing the last error on the calling thread, or null if there was none.
+///
+/// The message remains valid until the next call to a function of this API on the same thread.
+#[no_mangle]
+pub extern "C" fn moose_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| match last_error.borrow().as_ref() {
+        Some(message) => message.as_ptr(),
+        None => std::ptr::null(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capi() {
+        let source = r#"
+        x = Input {arg_name = "x"}: () -> HostFloat64Tensor @Host(alice)
+        y = Input {arg_name = "y"}: () -> HostFloat64Tensor @Host(alice)
+        z = Add: (HostFloat64Tensor, HostFloat64Tensor) -> HostFloat64Tensor (x, y) @Host(alice)
+        output = Output {tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (z) @Host(alice)
+        "#;
+        let bytes = Computation::from_textual(source)
+            .unwrap()
+            .to_msgpack()
+            .unwrap();
+        let x_name = CString::new("x").unwrap();
+        let y_name = CString::new("y").unwrap();
+        let output_name = CString::new("output_0").unwrap();
+        let x = [1.0f64, 2.0, 3.0, 4.0];
+        let y = [0.5f64, 0.5, 0.5, 0.5];
+        let shape = [2usize, 2];
+
+        unsafe {
+            let executor = moose_executor_new();
+            let status =
+                moose_executor_load_computation(executor, bytes.as_ptr(), bytes.len(), false);
+            assert_eq!(status, MOOSE_OK);
+
+            // running fails until all arguments are bound
+            assert_eq!(moose_executor_run(executor), MOOSE_ERROR);
+            assert!(!moose_last_error().is_null());
+
+            for (name, data) in [(&x_name, &x), (&y_name, &y)].iter() {
+                let status = moose_executor_bind_input(
+                    executor,
+                    name.as_ptr(),
+                    MOOSE_FLOAT64,
+                    data.as_ptr() as *const c_void,
+                    shape.as_ptr(),
+                    shape.len(),
+                );
+                assert_eq!(status, MOOSE_OK);
+            }
+            assert_eq!(moose_executor_run(executor), MOOSE_OK);
+
+            let mut dtype = -1;
+            let mut ndim = 0;
+            let status =
+                moose_executor_output_info(executor, output_name.as_ptr(), &mut dtype, &mut ndim);
+            assert_eq!(status, MOOSE_OK);
+            assert_eq!((dtype, ndim), (MOOSE_FLOAT64, 2));
+
+            let mut output_shape = vec![0usize; ndim];
+            let status = moose_executor_output_shape(
+                executor,
+                output_name.as_ptr(),
+                output_shape.as_mut_ptr(),
+            );
+            assert_eq!(status, MOOSE_OK);
+            assert_eq!(output_shape, vec![2, 2]);
+
+            let mut output = [0.0f64; 4];
+            let status = moose_executor_output_data(
+                executor,
+                output_name.as_ptr(),
+                MOOSE_FLOAT64,
+                output.as_mut_ptr() as *mut c_void,
+                output.len(),
+            );
+            assert_eq!(status, MOOSE_OK);
+            assert_eq!(output, [1.5, 2.5, 3.5, 4.5]);
+
+            // fetching with the wrong dtype is an error rather than a reinterpretation
+            let status = moose_executor_output_data(
+                executor,
+                output_name.as_ptr(),
+                MOOSE_INT64,
+                output.as_mut_ptr() as *mut c_void,
+                output.len(),
+            );
+            assert_eq!(status, MOOSE_ERROR);
+
+            moose_executor_free(executor);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Admission of sessions on a worker.
+//!
+//! Without admission control every launched session starts right away, so a burst
+//! of batch jobs can slow down everything running on the worker. An admission
+//! queue instead bounds the number of sessions running at a time, starting queued
+//! sessions by priority and, within the same priority, in the order they arrived.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// Priority class of a session, with later variants admitted first.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+pub enum Priority {
+    Batch,
+    #[default]
+    Normal,
+    Interactive,
+}
+
+impl FromStr for Priority {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "batch" => Ok(Priority::Batch),
+            "normal" => Ok(Priority::Normal),
+            "interactive" => Ok(Priority::Interactive),
+            _ => Err(format!("unknown priority '{}'", s)),
+        }
+    }
+}
+
+struct Waiter {
+    priority: Priority,
+    arrival: Reverse<u64>,
+    admit: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.priority, self.arrival).cmp(&(other.priority, other.arrival))
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    running: usize,
+    arrivals: u64,
+    waiting: BinaryHeap<Waiter>,
+}
+
+/// Queue admitting at most a fixed number of sessions at a time.
+pub struct AdmissionQueue {
+    max_running: usize,
+    state: Mutex<QueueState>,
+}
+
+/// Permission for a session to run, passed on to the next queued session when dropped.
+pub struct Admission {
+    queue: Arc<AdmissionQueue>,
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+impl AdmissionQueue {
+    pub fn new(max_running: usize) -> Self {
+        AdmissionQueue {
+            max_running: std::cmp::max(max_running, 1),
+            state: Mutex::new(QueueState::default()),
+        }
+    }
+
+    /// Wait until a session with `priority` may run.
+    pub async fn admit(self: &Arc<Self>, priority: Priority) -> Admission {
+        let admitted = {
+            let mut state = self.state.lock();
+            if state.running < self.max_running && state.waiting.is_empty() {
+                state.running += 1;
+                None
+            } else {
+                let (admit, admitted) = oneshot::channel();
+                state.arrivals += 1;
+                let arrival = Reverse(state.arrivals);
+                state.waiting.push(Waiter {
+                    priority,
+                    arrival,
+                    admit,
+                });
+                Some(admitted)
+            }
+        };
+        if let Some(admitted) = admitted {
+            // the queue outlives its waiters, so the sender is never dropped unused
+            let _ = admitted.await;
+        }
+        Admission {
+            queue: Arc::clone(self),
+        }
+    }
+
+    /// Number of sessions running and waiting to run.
+    pub fn load(&self) -> (usize, usize) {
+        let state = self.state.lock();
+        (state.running, state.waiting.len())
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock();
+        state.running -= 1;
+        while state.running < self.max_running {
+            match state.waiting.pop() {
+                // waiters that went away, e.g. since their launch was aborted, are skipped
+                Some(waiter) => {
+                    if waiter.admit.send(()).is_ok() {
+                        state.running += 1;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_admission_order() {
+        let queue = Arc::new(AdmissionQueue::new(1));
+        let first = queue.admit(Priority::Normal).await;
+
+        let (order_sender, mut order) = tokio::sync::mpsc::unbounded_channel();
+        let mut tasks = Vec::new();
+        for (name, priority) in [
+            ("batch", Priority::Batch),
+            ("normal", Priority::Normal),
+            ("interactive", Priority::Interactive),
+        ] {
+            let queue = Arc::clone(&queue);
+            let order_sender = order_sender.clone();
+            tasks.push(tokio::spawn(async move {
+                let _admission = queue.admit(priority).await;
+                order_sender.send(name).unwrap();
+            }));
+            // make sure sessions arrive in the given order
+            while queue.load().1 < tasks.len() {
+                tokio::task::yield_now().await;
+            }
+        }
+        assert_eq!(queue.load(), (1, 3));
+
+        drop(first);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let order: Vec<_> = std::iter::from_fn(|| order.try_recv().ok()).collect();
+        assert_eq!(order, vec!["interactive", "normal", "batch"]);
+        assert_eq!(queue.load(), (0, 0));
+    }
+}
@@ -0,0 +1,204 @@
+//! Bindings of roles to the identities of the workers playing them.
+//!
+//! Bindings are given in TOML as a list of roles, each with the endpoint of the
+//! worker playing it, which is also used as its identity:
+//!
+//! ```toml
+//! [[roles]]
+//! name = "alice"
+//! endpoint = "localhost:50000"
+//!
+//! [[roles]]
+//! name = "bob"
+//! endpoint = "localhost:50001"
+//! ```
+//!
+//! The same bindings can be loaded by drivers to launch sessions and by workers
+//! to reject sessions whose role assignment disagrees with them.
+
+use crate::computation::{Computation, Placement, Role};
+use crate::error::{Error, Result};
+use crate::execution::{Identity, RoleAssignment};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::net::ToSocketAddrs;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RoleConfig {
+    pub name: String,
+    pub endpoint: String,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RoleBindings {
+    pub roles: Vec<RoleConfig>,
+}
+
+fn bindings_error(problems: Vec<String>) -> Result<()> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::MalformedEnvironment(format!(
+            "invalid role bindings: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Roles playing any of the placements of `computation`.
+pub fn computation_roles(computation: &Computation) -> HashSet<Role> {
+    let mut roles = HashSet::new();
+    for op in computation.operations.iter() {
+        match &op.placement {
+            Placement::Host(plc) => {
+                roles.insert(plc.owner.clone());
+            }
+            Placement::Replicated(plc) => roles.extend(plc.owners.iter().cloned()),
+            Placement::Additive(plc) => roles.extend(plc.owners.iter().cloned()),
+            Placement::Mirrored3(plc) => roles.extend(plc.owners.iter().cloned()),
+        }
+    }
+    roles
+}
+
+impl FromStr for RoleBindings {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| {
+            Error::MalformedEnvironment(format!("failed to parse role bindings: {}", e))
+        })
+    }
+}
+
+impl RoleBindings {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            Error::MalformedEnvironment(format!(
+                "failed to read role bindings from {:?}: {}",
+                path, e
+            ))
+        })?;
+        Self::from_str(&raw)
+    }
+
+    /// Assignment of roles to identities, failing if a role is bound more than once.
+    pub fn role_assignment(&self) -> Result<RoleAssignment> {
+        let mut assignment = HashMap::with_capacity(self.roles.len());
+        let mut problems = Vec::new();
+        for role in self.roles.iter() {
+            let identity = Identity::from(&role.endpoint);
+            if let Some(previous) = assignment.insert(Role::from(&role.name), identity) {
+                if previous.0 != role.endpoint {
+                    problems.push(format!(
+                        "role '{}' is bound to both '{}' and '{}'",
+                        role.name, previous, role.endpoint
+                    ));
+                }
+            }
+        }
+        bindings_error(problems)?;
+        Ok(assignment)
+    }
+
+    /// Check that every role of `computation` is bound.
+    pub fn validate_for(&self, computation: &Computation) -> Result<()> {
+        let assignment = self.role_assignment()?;
+        let mut problems: Vec<String> = computation_roles(computation)
+            .into_iter()
+            .filter(|role| !assignment.contains_key(role))
+            .map(|role| format!("role '{}' of the computation is not bound", role))
+            .collect();
+        problems.sort();
+        bindings_error(problems)
+    }
+
+    /// Check that every endpoint resolves to at least one address.
+    pub fn check_endpoints(&self) -> Result<()> {
+        let problems = self
+            .roles
+            .iter()
+            .filter_map(|role| match role.endpoint.to_socket_addrs() {
+                Ok(mut addrs) if addrs.next().is_some() => None,
+                Ok(_) => Some(format!(
+                    "endpoint '{}' of role '{}' resolves to no addresses",
+                    role.endpoint, role.name
+                )),
+                Err(e) => Some(format!(
+                    "endpoint '{}' of role '{}' cannot be resolved: {}",
+                    role.endpoint, role.name, e
+                )),
+            })
+            .collect();
+        bindings_error(problems)
+    }
+
+    /// Check that `role_assignment` of a session agrees with the bindings on every role it binds.
+    pub fn check_assignment(&self, role_assignment: &RoleAssignment) -> Result<()> {
+        let assignment = self.role_assignment()?;
+        let mut problems: Vec<String> = role_assignment
+            .iter()
+            .filter_map(|(role, identity)| match assignment.get(role) {
+                Some(bound) if bound == identity => None,
+                Some(bound) => Some(format!(
+                    "role '{}' is assigned to '{}' but bound to '{}'",
+                    role, identity, bound
+                )),
+                None => Some(format!("role '{}' is not bound", role)),
+            })
+            .collect();
+        problems.sort();
+        bindings_error(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    const BINDINGS: &str = r#"
+        [[roles]]
+        name = "alice"
+        endpoint = "localhost:50000"
+
+        [[roles]]
+        name = "bob"
+        endpoint = "localhost:50001"
+    "#;
+
+    #[test]
+    fn test_validate_for_computation() {
+        let bindings = RoleBindings::from_str(BINDINGS).unwrap();
+        let computation: Computation = r#"
+        x = Constant{value = HostFloat32Tensor([1.0])}: () -> HostFloat32Tensor @Host(alice)
+        y = Send{rendezvous_key = 30313233343536373839616263646566, receiver = "carole"}: (HostFloat32Tensor) -> HostUnit (x) @Host(alice)
+        z = Receive{rendezvous_key = 30313233343536373839616263646566, sender = "alice"}: () -> HostFloat32Tensor () @Host(carole)
+        "#
+        .try_into()
+        .unwrap();
+        let err = bindings.validate_for(&computation).unwrap_err();
+        assert!(err.to_string().contains("role 'carole'"));
+    }
+
+    #[test]
+    fn test_check_assignment() {
+        let bindings = RoleBindings::from_str(BINDINGS).unwrap();
+        let mut assignment = bindings.role_assignment().unwrap();
+        assert!(bindings.check_assignment(&assignment).is_ok());
+
+        assignment.insert(Role::from("bob"), Identity::from("localhost:50002"));
+        assert!(bindings.check_assignment(&assignment).is_err());
+    }
+
+    #[test]
+    fn test_conflicting_bindings() {
+        let bindings = RoleBindings::from_str(&format!(
+            "{}\n[[roles]]\nname = \"alice\"\nendpoint = \"localhost:50002\"",
+            BINDINGS
+        ))
+        .unwrap();
+        assert!(bindings.role_assignment().is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Capabilities of workers, exchanged before running sessions.
+//!
+//! Workers built from different versions of Moose, with different features, or
+//! with different storage may not all be able to run a given computation. Rather
+//! than failing mid-session once a party lacks a kernel, the capabilities of all
+//! parties are collected up front and checked against the computation, reporting
+//! every incompatibility at once.
+
+use crate::computation::{Computation, Constant, HasShortName, Operator, Placement, Role};
+use crate::error::{Error, Result};
+use crate::execution::{AsyncSession, AsyncStorageImpl};
+use crate::kernels::DispatchKernel;
+use crate::prelude::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// Description of what a worker can run, as checked against a computation.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct Capabilities {
+    /// Version of Moose the worker was built from.
+    pub version: String,
+    /// Optional features the worker was built with.
+    pub features: Vec<String>,
+    /// Extensions of the storage keys the worker can save and load, or `None` if unrestricted.
+    pub storage_formats: Option<Vec<String>>,
+    /// Operations of the computation played by the worker that it has no kernel for.
+    pub unsupported_operations: Vec<String>,
+}
+
+fn enabled_features() -> Vec<String> {
+    let features = [
+        ("compile", cfg!(feature = "compile")),
+        ("sync_execute", cfg!(feature = "sync_execute")),
+        ("async_execute", cfg!(feature = "async_execute")),
+        ("telemetry", cfg!(feature = "telemetry")),
+        ("websocket", cfg!(feature = "websocket")),
+        ("dns", cfg!(feature = "dns")),
+        ("metrics", cfg!(feature = "metrics")),
+        ("gpu", cfg!(feature = "gpu")),
+    ];
+    features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| feature.to_string())
+        .collect()
+}
+
+/// Whether `role` takes part in operations placed on `placement`.
+fn plays(placement: &Placement, role: &Role) -> bool {
+    match placement {
+        Placement::Host(plc) => &plc.owner == role,
+        Placement::Replicated(plc) => plc.owners.contains(role),
+        Placement::Additive(plc) => plc.owners.contains(role),
+        Placement::Mirrored3(plc) => plc.owners.contains(role),
+    }
+}
+
+/// Storage keys of the load and save operations of `computation` played by `role`, if constant.
+fn storage_keys<'c>(computation: &'c Computation, role: &Role) -> Vec<(&'c str, &'c str)> {
+    let constants: HashMap<&str, &str> = computation
+        .operations
+        .iter()
+        .filter_map(|op| match &op.kind {
+            Operator::Constant(constant) => match &constant.value {
+                Constant::String(key) => Some((op.name.as_str(), key.as_str())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    computation
+        .operations
+        .iter()
+        .filter(|op| matches!(op.kind, Operator::Load(_) | Operator::Save(_)))
+        .filter(|op| plays(&op.placement, role))
+        .filter_map(|op| {
+            let key = op.inputs.first()?;
+            Some((op.name.as_str(), *constants.get(key.as_str())?))
+        })
+        .collect()
+}
+
+impl Capabilities {
+    /// Capabilities of this worker for playing `roles` in `computation`.
+    pub fn of_worker(
+        storage: &AsyncStorageImpl,
+        computation: &Computation,
+        roles: &[Role],
+    ) -> Capabilities {
+        let unsupported_operations = computation
+            .operations
+            .iter()
+            .filter(|op| roles.iter().any(|role| plays(&op.placement, role)))
+            .filter_map(|op| {
+                let kernel = <Operator as DispatchKernel<AsyncSession, Value>>::compile(
+                    &op.kind,
+                    &op.placement,
+                );
+                kernel
+                    .err()
+                    .map(|e| format!("{} ({}): {}", op.name, op.kind.short_name(), e))
+            })
+            .collect();
+        Capabilities {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            features: enabled_features(),
+            storage_formats: storage.formats(),
+            unsupported_operations,
+        }
+    }
+}
+
+/// Check that the workers playing each role can together run `computation`.
+///
+/// All incompatibilities are reported in a single error.
+pub fn check_compatibility(
+    computation: &Computation,
+    capabilities: &HashMap<Role, Capabilities>,
+) -> Result<()> {
+    let mut problems = Vec::new();
+
+    let versions: BTreeSet<&str> = capabilities
+        .values()
+        .map(|capabilities| capabilities.version.as_str())
+        .collect();
+    if versions.len() > 1 {
+        let mut roles: Vec<String> = capabilities
+            .iter()
+            .map(|(role, capabilities)| format!("{} runs {}", role, capabilities.version))
+            .collect();
+        roles.sort();
+        problems.push(format!("versions differ: {}", roles.join(", ")));
+    }
+
+    let mut roles: Vec<&Role> = capabilities.keys().collect();
+    roles.sort_by_key(|role| role.0.as_str());
+    for role in roles {
+        let capabilities = &capabilities[role];
+        for op in capabilities.unsupported_operations.iter() {
+            problems.push(format!("{} cannot run operation {}", role, op));
+        }
+        if let Some(ref formats) = capabilities.storage_formats {
+            for (op_name, key) in storage_keys(computation, role) {
+                let format = Path::new(key)
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .unwrap_or_default();
+                if !formats.iter().any(|supported| supported == format) {
+                    problems.push(format!(
+                        "{} cannot store key '{}' of operation {}; supported formats are {}",
+                        role,
+                        key,
+                        op_name,
+                        formats.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::MalformedEnvironment(format!(
+            "workers cannot run computation: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::filesystem::AsyncFilesystemStorage;
+    use std::convert::TryInto;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_check_compatibility() {
+        let computation: Computation = r#"
+        key = Constant{value = "data.parquet"}: () -> HostString @Host(alice)
+        query = Constant{value = ""}: () -> HostString @Host(alice)
+        x = Load: (HostString, HostString) -> HostFloat64Tensor (key, query) @Host(alice)
+        y = Identity: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(bob)
+        "#
+        .try_into()
+        .unwrap();
+        let storage: AsyncStorageImpl = Arc::new(AsyncFilesystemStorage::default());
+
+        let alice = Role::from("alice");
+        let bob = Role::from("bob");
+        let mut bob_capabilities = Capabilities::of_worker(&storage, &computation, &[bob.clone()]);
+        assert!(bob_capabilities.unsupported_operations.is_empty());
+        bob_capabilities.version = "0.0.1".to_string();
+
+        let capabilities = vec![
+            (
+                alice.clone(),
+                Capabilities::of_worker(&storage, &computation, &[alice]),
+            ),
+            (bob, bob_capabilities),
+        ]
+        .into_iter()
+        .collect();
+        let err = check_compatibility(&computation, &capabilities)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("versions differ"));
+        assert!(err.contains("cannot store key 'data.parquet'"));
+    }
+}
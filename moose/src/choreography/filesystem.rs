@@ -1,16 +1,21 @@
 //! Filesystem-based choreography.
 
+use crate::choreography::bindings::RoleBindings;
+pub use crate::choreography::bindings::RoleConfig;
 use crate::choreography::{NetworkingStrategy, StorageStrategy};
 use crate::computation::Computation;
-use crate::execution::ExecutionContext;
 use crate::execution::RoleAssignment;
+use crate::execution::{stream_outputs, ExecutionContext, OutputStream};
 use crate::prelude::*;
+use crate::storage::filesystem::csv::{read_csv, CsvOptions};
+use crate::storage::filesystem::numpy::{read_numpy, write_numpy};
+use futures::StreamExt;
 use notify::{DebouncedEvent, Watcher};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 /// Filesystem-based choreography.
@@ -20,11 +25,16 @@ use std::str::FromStr;
 /// - `.moose` files that contain Moose computations;
 ///   these must be physical computations.
 ///
-/// - `.session` files that specify session configurations;
+/// - `.session` files that specify session configurations, including the
+///   `.npy` or `.csv` files to read arguments from;
 ///   the name of the file is used to derive the session id.
 ///
 /// `FilesystemChoreography` listens for changes to the sessions directory
 /// and will launch new sessions when new `.session` files are created.
+///
+/// Outputs computed by this instance are written next to the session file as
+/// `<session>.<tag>.npy` as soon as they are ready, and once the session has
+/// finished its outcome is written to `<session>.<identity>.result`.
 pub struct FilesystemChoreography {
     own_identity: Identity,
     sessions_dir: String,
@@ -100,15 +110,33 @@ impl FilesystemChoreography {
         if path.is_file() {
             match path.extension() {
                 Some(ext) if ext == "session" => {
-                    let session_handle = self.launch_session(path).await?;
+                    let (session_handle, outputs) = self.launch_session(path).await?;
+                    let writer = tokio::spawn(write_outputs(path.to_path_buf(), outputs));
                     let res = session_handle.join_on_first_error().await;
+                    let outputs = writer.await?;
+
+                    let result = SessionResult {
+                        identity: self.own_identity.to_string(),
+                        status: if res.is_ok() {
+                            SessionStatus::Completed
+                        } else {
+                            SessionStatus::Failed
+                        },
+                        error: res.as_ref().err().map(|e| e.to_string()),
+                        outputs,
+                    };
+                    std::fs::write(
+                        result_path(path, &self.own_identity),
+                        toml::to_string(&result)?,
+                    )?;
+
                     if let Err(e) = res {
                         tracing::error!("Session error: {}", e);
                         return Result::Err(e.into());
                     }
                 }
-                Some(ext) if ext == "moose" => {
-                    // ok to skip
+                Some(ext) if ext == "moose" || ext == "npy" || ext == "csv" || ext == "result" => {
+                    // computations, arguments, and results; ok to skip
                 }
                 _ => {
                     tracing::warn!("Skipping {:?}", path);
@@ -122,19 +150,17 @@ impl FilesystemChoreography {
     async fn launch_session(
         &self,
         path: &Path,
-    ) -> Result<AsyncSessionHandle, Box<dyn std::error::Error>> {
+    ) -> Result<(AsyncSessionHandle, OutputStream), Box<dyn std::error::Error>> {
         tracing::info!("Loading session from {:?}", path);
-        let (_, session_id, role_assignments, computation) =
+        let (session_config, session_id, role_assignments, computation) =
             parse_session_config_file_with_computation(path)?;
         let networking = (self.networking_strategy)(session_id.clone());
         let storage = (self.storage_strategy)();
 
         let context = ExecutionContext::new(self.own_identity.clone(), networking, storage);
 
-        // TODO(Morten) for now we don't support arguments in this type of choreography;
-        // we could be eg allowing them to be specified in the .session file (perhaps as
-        // names of .npy files)
-        let arguments = HashMap::new();
+        let plc = HostPlacement::from(self.own_identity.to_string().as_str());
+        let arguments = session_config.load_arguments(&plc).await?;
 
         tracing::debug!("Scheduling computation");
         let (handle, outputs) = context
@@ -147,20 +173,8 @@ impl FilesystemChoreography {
             .await?;
 
         tracing::debug!("Ready for outputs");
-        for (output_name, output_value) in outputs {
-            let session_id = session_id.clone();
-            tokio::spawn(async move {
-                let value = output_value.await.unwrap();
-                tracing::info!(
-                    "Output '{}' from '{}' ready: {:?}",
-                    output_name,
-                    session_id,
-                    value
-                );
-            });
-        }
-
-        Ok(handle)
+        let outputs = stream_outputs(&computation, outputs)?;
+        Ok((handle, outputs))
     }
 
     async fn abort_session_from_path(
@@ -172,10 +186,115 @@ impl FilesystemChoreography {
     }
 }
 
+async fn read_argument(path: &str, plc: &HostPlacement) -> crate::Result<Value> {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("npy") => read_numpy(path, plc, None).await,
+        Some("csv") => read_csv(path, &CsvOptions::default(), plc).await,
+        _ => Err(crate::Error::Storage(format!(
+            "argument must be given as either a '.npy' or '.csv' file, got: {}",
+            path
+        ))),
+    }
+}
+
+/// Path next to `session_path` of a file holding the given `suffix`.
+fn sibling_path(session_path: &Path, suffix: &str) -> PathBuf {
+    let stem = session_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    session_path.with_file_name(format!("{}.{}", stem, suffix))
+}
+
+/// Path of the file holding the outcome of the session in `session_path` on `identity`.
+pub fn result_path(session_path: &Path, identity: &Identity) -> PathBuf {
+    let identity = identity
+        .to_string()
+        .replace(|c: char| !c.is_alphanumeric(), "_");
+    sibling_path(session_path, &format!("{}.result", identity))
+}
+
+/// Path of the file holding output `tag` of the session in `session_path`.
+pub fn output_path(session_path: &Path, tag: &str) -> PathBuf {
+    sibling_path(session_path, &format!("{}.npy", tag))
+}
+
+/// Write `outputs` of the session in `session_path` as they become ready, returning their tags.
+async fn write_outputs(session_path: PathBuf, mut outputs: OutputStream) -> Vec<String> {
+    let mut written = Vec::new();
+    while let Some(output) = outputs.next().await {
+        let (tag, value) = match output {
+            Ok(output) => output,
+            // the session failed, which is reported once it has been joined
+            Err(_) => break,
+        };
+        let path = output_path(&session_path, &tag);
+        match write_numpy(&path.to_string_lossy(), &value).await {
+            Ok(()) => {
+                tracing::info!("Output '{}' written to {:?}", tag, path);
+                written.push(tag);
+            }
+            Err(e) => tracing::warn!("Output '{}' could not be written: {}", tag, e),
+        }
+    }
+    written
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    Completed,
+    Failed,
+}
+
+/// Outcome of a session on a single instance.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionResult {
+    pub identity: String,
+    pub status: SessionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tags of the outputs written by this instance.
+    pub outputs: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SessionConfig {
     pub computation: ComputationConfig,
+    /// Roles bound in the session configuration itself.
+    #[serde(default)]
     pub roles: Vec<RoleConfig>,
+    /// File with further role bindings, shared between sessions; see [`RoleBindings`].
+    pub bindings: Option<String>,
+    /// Files to read arguments from, by argument name.
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+impl SessionConfig {
+    /// All role bindings of the session, including those in the bindings file.
+    pub fn role_bindings(&self) -> crate::Result<RoleBindings> {
+        let mut roles = self.roles.clone();
+        if let Some(ref bindings) = self.bindings {
+            roles.extend(RoleBindings::from_file(Path::new(bindings))?.roles);
+        }
+        Ok(RoleBindings { roles })
+    }
+
+    /// Read the arguments of the session from their files.
+    ///
+    /// The placement of the values is irrelevant since arguments are placed by the
+    /// operations taking them as input.
+    pub async fn load_arguments(
+        &self,
+        plc: &HostPlacement,
+    ) -> crate::Result<HashMap<String, Value>> {
+        let mut arguments = HashMap::with_capacity(self.arguments.len());
+        for (name, path) in self.arguments.iter() {
+            arguments.insert(name.clone(), read_argument(path, plc).await?);
+        }
+        Ok(arguments)
+    }
 }
 
 impl FromStr for SessionConfig {
@@ -199,12 +318,6 @@ pub enum ComputationFormat {
     Bincode,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct RoleConfig {
-    pub name: String,
-    pub endpoint: String,
-}
-
 pub fn parse_session_config_file_with_computation(
     session_config_file: &Path,
 ) -> Result<(SessionConfig, SessionId, RoleAssignment, Computation), Box<dyn std::error::Error>> {
@@ -229,6 +342,8 @@ pub fn parse_session_config_file_with_computation(
         }
     };
 
+    session_config.role_bindings()?.validate_for(&computation)?;
+
     Ok((session_config, session_id, role_assignment, computation))
 }
 
@@ -237,15 +352,7 @@ pub fn parse_session_config_file_without_computation(
 ) -> Result<(SessionConfig, SessionId, RoleAssignment), Box<dyn std::error::Error>> {
     let session_config = SessionConfig::from_str(&std::fs::read_to_string(session_config_file)?)?;
 
-    let role_assignment: RoleAssignment = session_config
-        .roles
-        .iter()
-        .map(|role_config| {
-            let role = Role::from(&role_config.name);
-            let identity = Identity::from(&role_config.endpoint);
-            (role, identity)
-        })
-        .collect();
+    let role_assignment = session_config.role_bindings()?.role_assignment()?;
 
     let session_id: SessionId = SessionId::try_from(
         session_config_file
@@ -257,3 +364,41 @@ pub fn parse_session_config_file_without_computation(
 
     Ok((session_config, session_id, role_assignment))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_config_with_arguments() {
+        let config = SessionConfig::from_str(
+            r#"
+            [computation]
+            path = "./examples/test.moose"
+            format = "textual"
+
+            [[roles]]
+            name = "alice"
+            endpoint = "localhost:50000"
+
+            [arguments]
+            x = "./examples/x.npy"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.arguments["x"], "./examples/x.npy");
+    }
+
+    #[test]
+    fn test_result_paths() {
+        let session = Path::new("./examples/test.session");
+        assert_eq!(
+            output_path(session, "output_0"),
+            Path::new("./examples/test.output_0.npy")
+        );
+        assert_eq!(
+            result_path(session, &Identity::from("localhost:50000")),
+            Path::new("./examples/test.localhost_50000.result")
+        );
+    }
+}
@@ -7,36 +7,202 @@ pub(crate) mod gen {
 
 use self::gen::choreography_server::{Choreography, ChoreographyServer};
 use self::gen::{
-    AbortComputationRequest, AbortComputationResponse, LaunchComputationRequest,
-    LaunchComputationResponse, RetrieveResultsRequest, RetrieveResultsResponse,
+    AbortComputationRequest, AbortComputationResponse, GetCapabilitiesRequest,
+    GetCapabilitiesResponse, GetHealthRequest, GetHealthResponse, GetSignatureRequest,
+    GetSignatureResponse, LaunchComputationRequest, LaunchComputationResponse, ListSessionsRequest,
+    ListSessionsResponse, RetrieveResultsRequest, RetrieveResultsResponse, StreamResultsRequest,
+    StreamResultsResponse, TypedValue, WatchProgressRequest, WatchProgressResponse,
 };
+use super::admission::{AdmissionQueue, Priority};
+use super::bindings::RoleBindings;
+use super::capabilities::Capabilities;
+use super::health::{CheckStatus, PeerCheck, WorkerHealth};
+use super::proto;
 use super::{NetworkingStrategy, StorageStrategy};
-use crate::computation::{Operator, SessionId, Value};
-use crate::execution::ExecutionContext;
-use crate::execution::Identity;
+#[cfg(feature = "compile")]
+use crate::compilation::policy::{LabelledComputation, RevealPolicy};
+use crate::computation::{Computation, Operation, Operator, Role, SessionId, Value};
+use crate::execution::audit::AuditLog;
+use crate::execution::cache::KernelCache;
+use crate::execution::checkpoint::{discard_checkpoints, CheckpointConfig};
+use crate::execution::communication::{CommunicationReport, CommunicationTracker};
+use crate::execution::compute::ComputePool;
+#[cfg(feature = "gpu")]
+use crate::execution::gpu::GpuBackend;
+use crate::execution::memory::{MemoryBudgetConfig, MemoryTracker, MemoryUsage};
+#[cfg(feature = "metrics")]
+use crate::execution::metrics::Metrics;
+use crate::execution::progress::{Phase, SessionProgress};
+use crate::execution::scheduler::Scheduler;
+use crate::execution::{
+    stream_outputs, AsyncNetworkingImpl, AsyncSessionHandle, CancelToken, ExecutionContext,
+    OperationTimeouts, OutputStream,
+};
+use crate::execution::{Identity, RoleAssignment};
+use crate::kms::{provide_aes_keys, KeyProviderImpl};
+use crate::networking::accounting::NetworkStats;
+use crate::networking::transcript::{TranscriptCommitment, TranscriptNetworking, TranscriptSigner};
+use crate::storage::acl::{AclStorage, StorageAcl};
+use crate::textual::ToTextual;
 use async_cell::sync::AsyncCell;
 use async_trait::async_trait;
 use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
+use futures::stream::BoxStream;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
+use tracing::Instrument;
 
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct ComputationOutputs {
     pub outputs: HashMap<String, Value>,
     pub elapsed_time: Option<Duration>,
+    pub network_stats: Option<NetworkStats>,
+    /// Bytes of values alive during the session, if tracked.
+    pub memory_usage: Option<MemoryUsage>,
+    /// Messages sent during the session, if reported.
+    pub communication: Option<CommunicationReport>,
+    /// Commitment to the messages exchanged during the session, if recorded.
+    pub transcript: Option<TranscriptCommitment>,
+    /// Error that caused the session to fail, in which case `outputs` only holds
+    /// the outputs completed before the failure.
+    pub error: Option<String>,
+}
+
+/// Free-form description of a session, given by the driver launching it.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct SessionMetadata {
+    pub labels: HashMap<String, String>,
+    pub tags: Vec<String>,
+    /// Key identifying the logical session across retries.
+    ///
+    /// Workers treat a session launched with the key of an earlier session that
+    /// completed or is still running as a duplicate, attaching it to the result of
+    /// the earlier session instead of running it again. Only sessions retrying a
+    /// failed one are run.
+    pub idempotency_key: Option<String>,
+    /// Priority of the session on workers that limit the number of sessions running at a time.
+    pub priority: Priority,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SessionState {
+    /// Waiting for the sessions it depends on to complete or to be admitted.
+    Waiting,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let state = match self {
+            SessionState::Waiting => "waiting",
+            SessionState::Running => "running",
+            SessionState::Completed => "completed",
+            SessionState::Failed => "failed",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+impl FromStr for SessionState {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waiting" => Ok(SessionState::Waiting),
+            "running" => Ok(SessionState::Running),
+            "completed" => Ok(SessionState::Completed),
+            "failed" => Ok(SessionState::Failed),
+            _ => Err(format!("unknown session state '{}'", s)),
+        }
+    }
+}
+
+/// Description of a session launched on a worker, as returned when listing sessions.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct SessionInfo {
+    pub session_id: SessionId,
+    pub state: SessionState,
+    pub metadata: SessionMetadata,
+    /// Identity of the choreographer that launched the session, if known.
+    pub launched_by: Option<String>,
+    pub launched_at: SystemTime,
+}
+
+impl SessionInfo {
+    /// Whether the session is in `state`, if given, and has all of `tags`.
+    pub fn matches(&self, state: Option<SessionState>, tags: &[String]) -> bool {
+        state.map_or(true, |state| state == self.state)
+            && tags.iter().all(|tag| self.metadata.tags.contains(tag))
+    }
 }
 
 type ResultStores = DashMap<SessionId, Arc<AsyncCell<ComputationOutputs>>>;
+type SessionInfos = DashMap<SessionId, SessionInfo>;
+type IdempotencyKeys = DashMap<String, SessionId>;
+type CancelTokens = DashMap<SessionId, CancelToken>;
+type ProgressWatchers = DashMap<SessionId, watch::Receiver<SessionProgress>>;
+type OutputWatchers = DashMap<SessionId, watch::Receiver<StreamedOutputs>>;
+type Signatures = DashMap<SessionId, GetSignatureResponse>;
+
+/// Outputs of a session available so far.
+#[derive(Clone, Default)]
+struct StreamedOutputs {
+    outputs: Vec<(String, Value)>,
+    /// Set once the session has finished, to the error that caused it to fail if any.
+    finished: Option<Option<String>>,
+}
 
 pub struct GrpcChoreography {
     own_identity: Identity,
     choreographer: Option<String>,
     result_stores: Arc<ResultStores>,
+    sessions: Arc<SessionInfos>,
+    idempotency_keys: IdempotencyKeys,
+    cancel_tokens: Arc<CancelTokens>,
+    progress_watchers: Arc<ProgressWatchers>,
+    output_watchers: Arc<OutputWatchers>,
+    signatures: Signatures,
+    draining: AtomicBool,
     networking_strategy: NetworkingStrategy,
     storage_strategy: StorageStrategy,
+    max_message_size: usize,
+    timeouts: OperationTimeouts,
+    memory_budget: Option<MemoryBudgetConfig>,
+    track_memory: bool,
+    report_communication: bool,
+    record_transcripts: bool,
+    transcript_signer: Option<Arc<TranscriptSigner>>,
+    summarize_values: bool,
+    scrub_secrets: bool,
+    compute_pool: Option<Arc<ComputePool>>,
+    checkpoints: Option<CheckpointConfig>,
+    recording_dir: Option<PathBuf>,
+    profiling_dir: Option<PathBuf>,
+    bindings: Option<RoleBindings>,
+    peer_check: Option<PeerCheck>,
+    started_at: Instant,
+    admission: Option<Arc<AdmissionQueue>>,
+    audit: Option<Arc<AuditLog>>,
+    keys: Option<KeyProviderImpl>,
+    storage_acl: Option<Arc<StorageAcl>>,
+    cache: Option<Arc<KernelCache>>,
+    scheduler: Option<Arc<Scheduler>>,
+    #[cfg(feature = "compile")]
+    reveal_policy: Option<Arc<RevealPolicy>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+    #[cfg(feature = "gpu")]
+    gpu: Option<Arc<GpuBackend>>,
 }
 
 impl GrpcChoreography {
@@ -50,17 +216,398 @@ impl GrpcChoreography {
             own_identity,
             choreographer,
             result_stores: Arc::new(ResultStores::default()),
+            sessions: Arc::new(SessionInfos::default()),
+            idempotency_keys: IdempotencyKeys::default(),
+            cancel_tokens: Arc::new(CancelTokens::default()),
+            progress_watchers: Arc::new(ProgressWatchers::default()),
+            output_watchers: Arc::new(OutputWatchers::default()),
+            signatures: Signatures::default(),
+            draining: AtomicBool::new(false),
             networking_strategy,
             storage_strategy,
+            max_message_size: usize::MAX,
+            timeouts: OperationTimeouts::default(),
+            memory_budget: None,
+            track_memory: false,
+            report_communication: false,
+            record_transcripts: false,
+            transcript_signer: None,
+            summarize_values: false,
+            scrub_secrets: false,
+            compute_pool: None,
+            checkpoints: None,
+            recording_dir: None,
+            profiling_dir: None,
+            bindings: None,
+            peer_check: None,
+            started_at: Instant::now(),
+            admission: None,
+            audit: None,
+            keys: None,
+            storage_acl: None,
+            cache: None,
+            scheduler: None,
+            #[cfg(feature = "compile")]
+            reveal_policy: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "gpu")]
+            gpu: None,
+        }
+    }
+
+    /// Fail sessions in which a single operation takes longer than allowed by `timeouts`.
+    pub fn with_timeouts(self, timeouts: OperationTimeouts) -> Self {
+        GrpcChoreography { timeouts, ..self }
+    }
+
+    /// Run expensive kernels on `compute_pool` instead of the threads handling requests.
+    pub fn with_compute_pool(self, compute_pool: Arc<ComputePool>) -> Self {
+        GrpcChoreography {
+            compute_pool: Some(compute_pool),
+            ..self
+        }
+    }
+
+    /// Run supported kernels on `gpu` for the placements it was configured for.
+    #[cfg(feature = "gpu")]
+    pub fn with_gpu(self, gpu: Arc<GpuBackend>) -> Self {
+        GrpcChoreography {
+            gpu: Some(gpu),
+            ..self
+        }
+    }
+
+    /// Limit the memory used by each session for holding operands, spilling to disk beyond it.
+    pub fn with_memory_budget(self, memory_budget: MemoryBudgetConfig) -> Self {
+        GrpcChoreography {
+            memory_budget: Some(memory_budget),
+            ..self
+        }
+    }
+
+    /// Track the bytes of values alive in each session, reporting them in its results.
+    pub fn with_memory_tracking(self) -> Self {
+        GrpcChoreography {
+            track_memory: true,
+            ..self
+        }
+    }
+
+    /// Report the messages sent in each session, by placement pair and operator kind, in its results.
+    pub fn with_communication_reports(self) -> Self {
+        GrpcChoreography {
+            report_communication: true,
+            ..self
+        }
+    }
+
+    /// Commit to the messages exchanged in each session, reporting the commitment in its results.
+    ///
+    /// Commitments are only reported to whoever retrieves the results, not exchanged between workers.
+    pub fn with_transcripts(self) -> Self {
+        GrpcChoreography {
+            record_transcripts: true,
+            ..self
+        }
+    }
+
+    /// Like `with_transcripts` but signing every commitment with `signer`.
+    pub fn with_transcript_signer(self, signer: Arc<TranscriptSigner>) -> Self {
+        GrpcChoreography {
+            record_transcripts: true,
+            transcript_signer: Some(signer),
+            ..self
+        }
+    }
+
+    /// Log a summary of the value computed by every operation of each session, for debugging.
+    pub fn with_value_summaries(self) -> Self {
+        GrpcChoreography {
+            summarize_values: true,
+            ..self
+        }
+    }
+
+    /// Zeroize the secret shares, seeds, and keys computed by each session once it has finished.
+    pub fn with_scrubbing(self) -> Self {
+        GrpcChoreography {
+            scrub_secrets: true,
+            ..self
+        }
+    }
+
+    /// Periodically checkpoint sessions so that they can be resumed after a restart.
+    ///
+    /// Checkpoints of a session are discarded once it has completed successfully.
+    pub fn with_checkpoints(self, checkpoints: CheckpointConfig) -> Self {
+        GrpcChoreography {
+            checkpoints: Some(checkpoints),
+            ..self
+        }
+    }
+
+    /// Record every session to `recording_dir` so that it can later be replayed locally.
+    pub fn with_recording(self, recording_dir: PathBuf) -> Self {
+        GrpcChoreography {
+            recording_dir: Some(recording_dir),
+            ..self
+        }
+    }
+
+    /// Write a profile of every operation of each session to `profiling_dir`.
+    pub fn with_profiling(self, profiling_dir: PathBuf) -> Self {
+        GrpcChoreography {
+            profiling_dir: Some(profiling_dir),
+            ..self
+        }
+    }
+
+    /// Reject sessions whose role assignment disagrees with `bindings`.
+    pub fn with_bindings(self, bindings: RoleBindings) -> Self {
+        GrpcChoreography {
+            bindings: Some(bindings),
+            ..self
+        }
+    }
+
+    /// Report whether the peers bound using `with_bindings` pass `peer_check` in health checks.
+    pub fn with_peer_check(self, peer_check: PeerCheck) -> Self {
+        GrpcChoreography {
+            peer_check: Some(peer_check),
+            ..self
+        }
+    }
+
+    /// Queue sessions instead of starting them right away once `admission` runs as many as it allows.
+    pub fn with_admission(self, admission: Arc<AdmissionQueue>) -> Self {
+        GrpcChoreography {
+            admission: Some(admission),
+            ..self
+        }
+    }
+
+    /// Record launched sessions and the values revealed to this worker in `audit`.
+    pub fn with_audit(self, audit: Arc<AuditLog>) -> Self {
+        GrpcChoreography {
+            audit: Some(audit),
+            ..self
+        }
+    }
+
+    /// Fetch AES keys that sessions input on this worker from `keys` unless given as arguments.
+    ///
+    /// Keys are looked up under the name of their argument.
+    pub fn with_key_provider(self, keys: KeyProviderImpl) -> Self {
+        GrpcChoreography {
+            keys: Some(keys),
+            ..self
+        }
+    }
+
+    /// Only let sessions load and save the keys that `acl` allows for the roles this worker plays.
+    ///
+    /// Since roles are assigned by whoever launches a session, sessions are only
+    /// launched if the roles are also bound using `with_bindings`.
+    pub fn with_storage_acl(self, acl: Arc<StorageAcl>) -> Self {
+        GrpcChoreography {
+            storage_acl: Some(acl),
+            ..self
+        }
+    }
+
+    /// Only launch sessions whose logical computation, attached by the driver
+    /// as a [`LabelledComputation`], complies with `policy` as well as with the
+    /// policy it is labelled with.
+    #[cfg(feature = "compile")]
+    pub fn with_reveal_policy(self, policy: RevealPolicy) -> Self {
+        GrpcChoreography {
+            reveal_policy: Some(Arc::new(policy)),
+            ..self
+        }
+    }
+
+    /// Check the labelled computation of a launch request, if any, against its
+    /// policy and that of the worker.
+    #[cfg(feature = "compile")]
+    fn check_reveal_policy(&self, request: &LaunchComputationRequest) -> Result<(), tonic::Status> {
+        if request.labelled_computation.is_empty() {
+            return match self.reveal_policy {
+                Some(_) => Err(tonic::Status::new(
+                    tonic::Code::FailedPrecondition,
+                    "worker enforces a reveal policy but the session has no labelled computation"
+                        .to_string(),
+                )),
+                None => Ok(()),
+            };
+        }
+        let labelled: LabelledComputation = bincode::deserialize(&request.labelled_computation)
+            .map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Aborted,
+                    format!("failed to parse labelled computation: {}", e),
+                )
+            })?;
+        labelled
+            .check(self.reveal_policy.as_deref())
+            .map_err(|e| tonic::Status::new(tonic::Code::PermissionDenied, e.to_string()))
+    }
+
+    #[cfg(not(feature = "compile"))]
+    fn check_reveal_policy(&self, request: &LaunchComputationRequest) -> Result<(), tonic::Status> {
+        if request.labelled_computation.is_empty() {
+            Ok(())
+        } else {
+            Err(tonic::Status::new(
+                tonic::Code::Unimplemented,
+                "worker was built without support for reveal policies".to_string(),
+            ))
+        }
+    }
+
+    /// Reuse results of pure operations across sessions through `cache`.
+    pub fn with_cache(self, cache: Arc<KernelCache>) -> Self {
+        GrpcChoreography {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Share the slots for running kernels of `scheduler` fairly between concurrent sessions.
+    pub fn with_scheduler(self, scheduler: Arc<Scheduler>) -> Self {
+        GrpcChoreography {
+            scheduler: Some(scheduler),
+            ..self
+        }
+    }
+
+    /// Report sessions, kernel times, traffic, and storage operations to `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, metrics: Arc<Metrics>) -> Self {
+        GrpcChoreography {
+            metrics: Some(metrics),
+            ..self
+        }
+    }
+
+    /// Limit the size of computations, arguments, and results, in bytes.
+    ///
+    /// By default messages of any size are allowed.
+    pub fn with_max_message_size(self, max_message_size: usize) -> Self {
+        GrpcChoreography {
+            max_message_size,
+            ..self
+        }
+    }
+
+    /// Stop accepting new sessions, e.g. before shutting down the worker.
+    ///
+    /// Sessions launched already, including those waiting for their dependencies,
+    /// are still run.
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Health of the worker, checking its storage and peers.
+    pub async fn health(&self) -> WorkerHealth {
+        let active_sessions = self
+            .sessions
+            .iter()
+            .filter(|info| matches!(info.state, SessionState::Waiting | SessionState::Running))
+            .count();
+        let storage = (self.storage_strategy)().check().await.into();
+
+        let peers: Vec<String> = match (&self.bindings, &self.peer_check) {
+            (Some(bindings), Some(_)) => bindings
+                .roles
+                .iter()
+                .map(|role| role.endpoint.clone())
+                .filter(|endpoint| endpoint != &self.own_identity.0)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let checks = peers.into_iter().map(|peer| async move {
+            let status: CheckStatus = match self.peer_check {
+                Some(ref peer_check) => peer_check(Identity::from(peer.as_str())).await.into(),
+                None => CheckStatus::Healthy,
+            };
+            (peer, status)
+        });
+        let peers = futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .collect();
+
+        WorkerHealth {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime: self.started_at.elapsed(),
+            active_sessions,
+            draining: self.is_draining(),
+            storage,
+            peers,
+        }
+    }
+
+    /// Wait until all sessions launched on this worker have finished.
+    pub async fn wait_until_idle(&self) {
+        loop {
+            let busy = self
+                .sessions
+                .iter()
+                .any(|info| matches!(info.state, SessionState::Waiting | SessionState::Running));
+            if !busy {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
         }
     }
 
     pub fn into_server(self) -> ChoreographyServer<impl Choreography> {
+        let max_message_size = self.max_message_size;
         ChoreographyServer::new(self)
+            .max_decoding_message_size(max_message_size)
+            .max_encoding_message_size(max_message_size)
+    }
+
+    /// Create a server sharing its state with any other server created from `choreography`.
+    ///
+    /// This allows the gRPC server to be restarted, e.g. with new TLS certificates,
+    /// without affecting running sessions.
+    pub fn new_server(choreography: &Arc<Self>) -> ChoreographyServer<impl Choreography> {
+        ChoreographyServer::from_arc(Arc::clone(choreography))
+            .max_decoding_message_size(choreography.max_message_size)
+            .max_encoding_message_size(choreography.max_message_size)
     }
 }
 
 impl GrpcChoreography {
+    /// Earlier session launched with idempotency `key` that `session_id` should
+    /// reuse the result of, unless there is none or it failed.
+    fn attempt_of(&self, key: &str, session_id: &SessionId) -> Option<SessionId> {
+        match self.idempotency_keys.entry(key.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(session_id.clone());
+                None
+            }
+            Entry::Occupied(mut entry) => {
+                let prior_failed = self
+                    .sessions
+                    .get(entry.get())
+                    .map_or(true, |info| info.state == SessionState::Failed);
+                if prior_failed {
+                    entry.insert(session_id.clone());
+                    None
+                } else {
+                    Some(entry.get().clone())
+                }
+            }
+        }
+    }
+
     fn check_choreographer<T>(&self, request: &tonic::Request<T>) -> Result<(), tonic::Status> {
         let choreographer = crate::grpc::extract_sender(request).map_err(|_e| {
             tonic::Status::new(
@@ -93,6 +640,220 @@ impl GrpcChoreography {
     }
 }
 
+/// Bookkeeping for a launched session once it is running or has failed to start.
+struct FinishingSession {
+    session_id: SessionId,
+    result_stores: Arc<ResultStores>,
+    sessions: Arc<SessionInfos>,
+    cancel_tokens: Arc<CancelTokens>,
+    checkpoints: Option<CheckpointConfig>,
+    networking: AsyncNetworkingImpl,
+    outputs: watch::Sender<StreamedOutputs>,
+    audit: Option<Arc<AuditLog>>,
+    memory: Option<Arc<MemoryTracker>>,
+    communication: Option<Arc<CommunicationTracker>>,
+    transcript: Option<Arc<TranscriptNetworking>>,
+    transcript_signer: Option<Arc<TranscriptSigner>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl FinishingSession {
+    fn set_state(&self, state: SessionState) {
+        if let Some(mut info) = self.sessions.get_mut(&self.session_id) {
+            info.state = state;
+        }
+    }
+
+    fn set_result(&self, outputs: ComputationOutputs) {
+        self.set_state(match outputs.error {
+            Some(_) => SessionState::Failed,
+            None => SessionState::Completed,
+        });
+        self.outputs
+            .send_modify(|streamed| streamed.finished = Some(outputs.error.clone()));
+        #[cfg(feature = "metrics")]
+        if let Some(ref metrics) = self.metrics {
+            metrics.session_finished(
+                outputs.error.is_some(),
+                outputs.network_stats.as_ref(),
+                outputs.memory_usage.as_ref(),
+            );
+        }
+        if let Some(ref audit) = self.audit {
+            if let Err(e) = audit.session_finished(&self.session_id, outputs.error.clone()) {
+                tracing::error!("Failed to record end of session {}: {}", self.session_id, e);
+            }
+        }
+        let result_cell = self
+            .result_stores
+            .get(&self.session_id)
+            .expect("session disappeared unexpectedly");
+        result_cell.set(outputs);
+    }
+
+    fn transcript_commitment(&self) -> Option<TranscriptCommitment> {
+        let transcript = self.transcript.as_ref()?;
+        match transcript.commitment(self.transcript_signer.as_deref()) {
+            Ok(commitment) => Some(commitment),
+            Err(e) => {
+                tracing::error!("Failed to commit to transcript: {}", e);
+                None
+            }
+        }
+    }
+
+    fn fail(&self, error: String, elapsed_time: Duration) {
+        tracing::error!("Session {} failed: {}", self.session_id, error);
+        self.cancel_tokens.remove(&self.session_id);
+        let outputs = self.outputs.borrow().outputs.iter().cloned().collect();
+        self.set_result(ComputationOutputs {
+            outputs,
+            elapsed_time: Some(elapsed_time),
+            network_stats: self.networking.network_stats(),
+            memory_usage: self.memory.as_ref().map(|memory| memory.usage()),
+            communication: self
+                .communication
+                .as_ref()
+                .map(|communication| communication.report()),
+            transcript: self.transcript_commitment(),
+            error: Some(error),
+        });
+    }
+
+    /// Publish `outputs` as they become available, skipping those that fail.
+    async fn forward_outputs(&self, mut outputs: OutputStream) {
+        while let Some(output) = outputs.next().await {
+            if let Ok(output) = output {
+                self.outputs
+                    .send_modify(|streamed| streamed.outputs.push(output));
+            }
+        }
+    }
+
+    async fn finish(
+        self,
+        handle: AsyncSessionHandle,
+        outputs: OutputStream,
+        execution_start_timer: Instant,
+    ) {
+        let session_id = &self.session_id;
+        let cancel_token = handle.cancel_token();
+        // an abort may have arrived after the session left the queue but before it started
+        let queued = self
+            .cancel_tokens
+            .insert(session_id.clone(), cancel_token.clone());
+        if let Some(reason) = queued.and_then(|queued| queued.reason()) {
+            cancel_token.cancel(&reason);
+        }
+
+        // pass on outputs to subscribers as soon as they are available; outputs still
+        // pending when the session fails become unavailable once its tasks are aborted,
+        // while those completed before are kept as partial results
+        let (res, ()) = futures::join!(handle.join_on_first_error(), self.forward_outputs(outputs));
+        self.cancel_tokens.remove(session_id);
+        if let Some(ref communication) = self.communication {
+            communication.settled().await;
+        }
+        if let Err(e) = res {
+            // a cancelled session will not be resumed
+            if let (Some(checkpoints), true) = (&self.checkpoints, cancel_token.is_cancelled()) {
+                if let Err(e) = discard_checkpoints(checkpoints, session_id) {
+                    tracing::warn!("Failed to discard checkpoints: {}", e);
+                }
+            }
+            self.fail(e.to_string(), execution_start_timer.elapsed());
+            return;
+        }
+
+        let results: HashMap<String, Value> =
+            self.outputs.borrow().outputs.iter().cloned().collect();
+        tracing::info!("Results ready, {:?}", results.keys());
+
+        if let Some(ref checkpoints) = self.checkpoints {
+            if let Err(e) = discard_checkpoints(checkpoints, session_id) {
+                tracing::warn!("Failed to discard checkpoints: {}", e);
+            }
+        }
+
+        self.set_result(ComputationOutputs {
+            outputs: results,
+            elapsed_time: Some(execution_start_timer.elapsed()),
+            network_stats: self.networking.network_stats(),
+            memory_usage: self.memory.as_ref().map(|memory| memory.usage()),
+            communication: self
+                .communication
+                .as_ref()
+                .map(|communication| communication.report()),
+            transcript: self.transcript_commitment(),
+            error: None,
+        });
+    }
+}
+
+/// Arguments and outputs of `computation`, in the order of their operations.
+fn computation_signature(computation: &Computation) -> GetSignatureResponse {
+    let typed_value = |name: &str, op: &Operation| TypedValue {
+        name: name.to_string(),
+        ty: op.kind.sig().ret().to_textual(),
+        placement: Some(proto::gen::Placement::from(&op.placement)),
+    };
+    let mut signature = GetSignatureResponse::default();
+    for op in computation.operations.iter() {
+        match &op.kind {
+            Operator::Input(input) => signature.arguments.push(typed_value(&input.arg_name, op)),
+            Operator::Output(output) => signature.outputs.push(typed_value(&output.tag, op)),
+            _ => (),
+        }
+    }
+    signature
+}
+
+/// Computation, arguments, and role assignment of a launch request.
+///
+/// Each is taken from its bincode field unless that is empty, in which case it
+/// is taken from the corresponding structured field instead.
+fn parse_launch_request(
+    request: &mut LaunchComputationRequest,
+) -> Result<(Computation, HashMap<String, Value>, RoleAssignment), tonic::Status> {
+    let invalid = |what: &str, e: &dyn std::fmt::Display| {
+        tonic::Status::new(
+            tonic::Code::Aborted,
+            format!("failed to parse {}: {}", what, e),
+        )
+    };
+
+    let computation = match request.structured_computation.take() {
+        Some(computation) if request.computation.is_empty() => {
+            Computation::try_from(computation).map_err(|e| invalid("computation", &e))?
+        }
+        _ => bincode::deserialize(&request.computation).map_err(|e| invalid("computation", &e))?,
+    };
+
+    let arguments = if request.arguments.is_empty() {
+        std::mem::take(&mut request.structured_arguments)
+            .into_iter()
+            .map(|(name, value)| Ok((name, Value::try_from(value)?)))
+            .collect::<crate::error::Result<_>>()
+            .map_err(|e| invalid("arguments", &e))?
+    } else {
+        bincode::deserialize(&request.arguments).map_err(|e| invalid("arguments", &e))?
+    };
+
+    let role_assignment = if request.role_assignment.is_empty() {
+        request
+            .structured_role_assignment
+            .iter()
+            .map(|(role, identity)| (Role::from(role), Identity::from(identity)))
+            .collect()
+    } else {
+        bincode::deserialize(&request.role_assignment)
+            .map_err(|e| invalid("role assignment", &e))?
+    };
+
+    Ok((computation, arguments, role_assignment))
+}
+
 #[async_trait]
 impl Choreography for GrpcChoreography {
     async fn launch_computation(
@@ -102,7 +863,14 @@ impl Choreography for GrpcChoreography {
         tracing::info!("Launching computation");
 
         self.check_choreographer(&request)?;
-        let request = request.into_inner();
+        if self.is_draining() {
+            return Err(tonic::Status::new(
+                tonic::Code::Unavailable,
+                "worker is draining and does not accept new sessions".to_string(),
+            ));
+        }
+        let launched_by = crate::grpc::extract_sender(&request).unwrap_or_default();
+        let mut request = request.into_inner();
 
         let session_id = bincode::deserialize::<SessionId>(&request.session_id).map_err(|_e| {
             tonic::Status::new(
@@ -111,97 +879,692 @@ impl Choreography for GrpcChoreography {
             )
         })?;
 
+        let metadata = match request.metadata.as_slice() {
+            [] => SessionMetadata::default(),
+            metadata => bincode::deserialize(metadata).map_err(|_e| {
+                tonic::Status::new(
+                    tonic::Code::Aborted,
+                    "failed to parse session metadata".to_string(),
+                )
+            })?,
+        };
+
+        // sessions this one depends on must already have been launched on this worker
+        let dependencies = request
+            .depends_on
+            .iter()
+            .map(|dependency_id| {
+                let dependency_id =
+                    bincode::deserialize::<SessionId>(dependency_id).map_err(|_e| {
+                        tonic::Status::new(
+                            tonic::Code::Aborted,
+                            "failed to parse dependency session id".to_string(),
+                        )
+                    })?;
+                match self.result_stores.get(&dependency_id) {
+                    Some(results) => Ok((dependency_id, Arc::clone(results.value()))),
+                    None => Err(tonic::Status::new(
+                        tonic::Code::NotFound,
+                        format!("unknown dependency session id {}", dependency_id),
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.check_reveal_policy(&request)?;
+
         match self.result_stores.entry(session_id.clone()) {
-            Entry::Occupied(_) => Err(tonic::Status::new(
-                tonic::Code::Aborted,
-                "session id exists already or inconsistent metric and result map".to_string(),
-            )),
+            Entry::Occupied(_) => {
+                // relaunching a session with the same idempotency key is a no-op
+                let is_duplicate = metadata.idempotency_key.is_some()
+                    && self.sessions.get(&session_id).map_or(false, |info| {
+                        info.metadata.idempotency_key == metadata.idempotency_key
+                    });
+                if is_duplicate {
+                    tracing::info!("Session {} was launched already", session_id);
+                    return Ok(tonic::Response::new(LaunchComputationResponse::default()));
+                }
+                Err(tonic::Status::new(
+                    tonic::Code::Aborted,
+                    "session id exists already or inconsistent metric and result map".to_string(),
+                ))
+            }
             Entry::Vacant(result_stores_entry) => {
                 let result_cell = AsyncCell::shared();
                 result_stores_entry.insert(result_cell);
+                let (computation, mut arguments, role_assignments) =
+                    parse_launch_request(&mut request)?;
+                if let Some(ref bindings) = self.bindings {
+                    bindings.check_assignment(&role_assignments).map_err(|e| {
+                        tonic::Status::new(tonic::Code::InvalidArgument, e.to_string())
+                    })?;
+                }
+                let own_roles: Vec<Role> = role_assignments
+                    .iter()
+                    .filter(|(_, identity)| *identity == &self.own_identity)
+                    .map(|(role, _)| role.clone())
+                    .collect();
+                if let Some(ref acl) = self.storage_acl {
+                    if self.bindings.is_none() {
+                        return Err(tonic::Status::new(
+                            tonic::Code::FailedPrecondition,
+                            "storage access list requires role bindings".to_string(),
+                        ));
+                    }
+                    acl.check_computation(&computation, &own_roles)
+                        .map_err(|e| {
+                            tonic::Status::new(tonic::Code::PermissionDenied, e.to_string())
+                        })?;
+                }
+                if let Some(ref keys) = self.keys {
+                    provide_aes_keys(keys.as_ref(), &computation, &own_roles, &mut arguments)
+                        .await
+                        .map_err(|e| {
+                            tonic::Status::new(tonic::Code::FailedPrecondition, e.to_string())
+                        })?;
+                }
+                // sessions that cannot be audited are not run
+                if let Some(ref audit) = self.audit {
+                    audit
+                        .session_launched(
+                            &session_id,
+                            &computation,
+                            &role_assignments,
+                            launched_by.clone(),
+                        )
+                        .map_err(|e| tonic::Status::new(tonic::Code::Internal, e.to_string()))?;
+                }
 
-                let computation = bincode::deserialize(&request.computation).map_err(|_e| {
-                    tonic::Status::new(
-                        tonic::Code::Aborted,
-                        "failed to parse computation".to_string(),
-                    )
-                })?;
-
-                let arguments = bincode::deserialize(&request.arguments).map_err(|_e| {
-                    tonic::Status::new(
-                        tonic::Code::Aborted,
-                        "failed to parse arguments".to_string(),
-                    )
-                })?;
+                let idempotency_key = metadata.idempotency_key.clone();
+                let priority = metadata.priority;
+                self.signatures
+                    .insert(session_id.clone(), computation_signature(&computation));
+                self.sessions.insert(
+                    session_id.clone(),
+                    SessionInfo {
+                        session_id: session_id.clone(),
+                        state: SessionState::Waiting,
+                        metadata,
+                        launched_by,
+                        launched_at: SystemTime::now(),
+                    },
+                );
 
-                let role_assignments =
-                    bincode::deserialize(&request.role_assignment).map_err(|_e| {
-                        tonic::Status::new(
-                            tonic::Code::Aborted,
-                            "failed to parse role assignment".to_string(),
-                        )
-                    })?;
+                if let Some(ref key) = idempotency_key {
+                    if let Some(prior_id) = self.attempt_of(key, &session_id) {
+                        tracing::info!(
+                            "Session {} is a retry of session {}; reusing its result",
+                            session_id,
+                            prior_id
+                        );
+                        if let Some(prior) = self.progress_watchers.get(&prior_id) {
+                            let prior = prior.value().clone();
+                            self.progress_watchers.insert(session_id.clone(), prior);
+                        }
+                        if let Some(prior) = self.output_watchers.get(&prior_id) {
+                            let prior = prior.value().clone();
+                            self.output_watchers.insert(session_id.clone(), prior);
+                        }
+                        let prior = self
+                            .result_stores
+                            .get(&prior_id)
+                            .map(|prior| Arc::clone(prior.value()));
+                        if let Some(prior) = prior {
+                            self.result_stores
+                                .insert(session_id.clone(), Arc::clone(&prior));
+                            if let Some(mut info) = self.sessions.get_mut(&session_id) {
+                                info.state = SessionState::Running;
+                            }
+                            let sessions = Arc::clone(&self.sessions);
+                            tokio::spawn(async move {
+                                let state = match prior.get().await.error {
+                                    Some(_) => SessionState::Failed,
+                                    None => SessionState::Completed,
+                                };
+                                if let Some(mut info) = sessions.get_mut(&session_id) {
+                                    info.state = state;
+                                }
+                            });
+                        }
+                        return Ok(tonic::Response::new(LaunchComputationResponse::default()));
+                    }
+                }
 
                 let own_identity = self.own_identity.clone();
                 let networking = (self.networking_strategy)(session_id.clone());
-                let storage = (self.storage_strategy)();
-                let context = ExecutionContext::new(own_identity, networking, storage);
+                let (networking, transcript) = match self.record_transcripts {
+                    true => {
+                        let transcript = Arc::new(TranscriptNetworking::new(
+                            networking,
+                            self.own_identity.clone(),
+                            session_id.clone(),
+                        ));
+                        (
+                            Arc::clone(&transcript) as AsyncNetworkingImpl,
+                            Some(transcript),
+                        )
+                    }
+                    false => (networking, None),
+                };
+                let storage = match self.storage_acl {
+                    Some(ref acl) => Arc::new(AclStorage::new(
+                        (self.storage_strategy)(),
+                        Arc::clone(acl),
+                        own_roles,
+                    )),
+                    None => (self.storage_strategy)(),
+                };
+                let context = ExecutionContext::new(own_identity, Arc::clone(&networking), storage)
+                    .with_timeouts(self.timeouts);
+                let context = match self.memory_budget {
+                    Some(ref memory_budget) => context.with_memory_budget(memory_budget.clone()),
+                    None => context,
+                };
+                let context = match self.compute_pool {
+                    Some(ref compute_pool) => context.with_compute_pool(Arc::clone(compute_pool)),
+                    None => context,
+                };
+                #[cfg(feature = "gpu")]
+                let context = match self.gpu {
+                    Some(ref gpu) => context.with_gpu(Arc::clone(gpu)),
+                    None => context,
+                };
+                let context = match self.checkpoints {
+                    Some(ref checkpoints) => context.with_checkpoints(checkpoints.clone()),
+                    None => context,
+                };
+                let context = match self.scheduler {
+                    Some(ref scheduler) => context.with_scheduler(Arc::clone(scheduler)),
+                    None => context,
+                };
+                let context = match self.cache {
+                    Some(ref cache) => context.with_cache(Arc::clone(cache)),
+                    None => context,
+                };
+                let context = match self.recording_dir {
+                    Some(ref recording_dir) => context.with_recording(recording_dir.clone()),
+                    None => context,
+                };
+                let context = match self.profiling_dir {
+                    Some(ref profiling_dir) => context.with_profiling(profiling_dir.clone()),
+                    None => context,
+                };
+                let context = match self.audit {
+                    Some(ref audit) => context.with_audit(Arc::clone(audit)),
+                    None => context,
+                };
+                #[cfg(feature = "metrics")]
+                let context = match self.metrics {
+                    Some(ref metrics) => context.with_metrics(Arc::clone(metrics)),
+                    None => context,
+                };
+                let memory = if self.track_memory {
+                    #[cfg(feature = "metrics")]
+                    let tracker = match self.metrics {
+                        Some(ref metrics) => metrics.memory_tracker(),
+                        None => MemoryTracker::default(),
+                    };
+                    #[cfg(not(feature = "metrics"))]
+                    let tracker = MemoryTracker::default();
+                    Some(Arc::new(tracker))
+                } else {
+                    None
+                };
+                let context = match memory {
+                    Some(ref memory) => context.with_memory_tracker(Arc::clone(memory)),
+                    None => context,
+                };
+                let communication = match self.report_communication {
+                    true => Some(Arc::new(CommunicationTracker::default())),
+                    false => None,
+                };
+                let context = match communication {
+                    Some(ref communication) => {
+                        context.with_communication_tracker(Arc::clone(communication))
+                    }
+                    None => context,
+                };
+                let context = match self.summarize_values {
+                    true => context.with_value_summaries(),
+                    false => context,
+                };
+                let context = match self.scrub_secrets {
+                    true => context.with_scrubbing(),
+                    false => context,
+                };
+                let (progress_sender, progress_receiver) = watch::channel(SessionProgress {
+                    ops_completed: 0,
+                    ops_total: 0,
+                    phase: Phase::Running,
+                    bytes_sent: 0,
+                    bytes_received: 0,
+                });
+                self.progress_watchers
+                    .insert(session_id.clone(), progress_receiver);
+                let context = context.with_progress(Arc::new(move |_, progress| {
+                    let _ = progress_sender.send(progress.clone());
+                }));
+                let (output_sender, output_receiver) = watch::channel(StreamedOutputs::default());
+                self.output_watchers
+                    .insert(session_id.clone(), output_receiver);
+                let context = match request.resume_checkpoint {
+                    0 => context,
+                    checkpoint => {
+                        tracing::info!("Resuming from checkpoint {}", checkpoint);
+                        context.resume_from(checkpoint)
+                    }
+                };
 
-                let execution_start_timer = Instant::now();
+                let session = FinishingSession {
+                    session_id: session_id.clone(),
+                    result_stores: Arc::clone(&self.result_stores),
+                    sessions: Arc::clone(&self.sessions),
+                    cancel_tokens: Arc::clone(&self.cancel_tokens),
+                    checkpoints: self.checkpoints.clone(),
+                    networking,
+                    outputs: output_sender,
+                    audit: self.audit.clone(),
+                    memory,
+                    communication,
+                    transcript,
+                    transcript_signer: self.transcript_signer.clone(),
+                    #[cfg(feature = "metrics")]
+                    metrics: self.metrics.clone(),
+                };
+                #[cfg(feature = "metrics")]
+                if let Some(ref metrics) = self.metrics {
+                    metrics.session_launched();
+                }
 
-                let (_handle, outputs) = context
-                    .execute_computation(
-                        session_id.clone(),
-                        &computation,
-                        arguments,
-                        role_assignments,
-                    )
-                    .await
-                    .map_err(|_e| {
+                // events of the session are logged in its span, also once the request has been handled
+                let span = tracing::info_span!("session", session_id = %session_id);
+                let admission = self.admission.clone();
+                if dependencies.is_empty() && admission.is_none() {
+                    session.set_state(SessionState::Running);
+                    let execution_start_timer = Instant::now();
+                    let (handle, outputs) = context
+                        .execute_computation(
+                            session_id.clone(),
+                            &computation,
+                            arguments,
+                            role_assignments,
+                        )
+                        .instrument(span.clone())
+                        .await
+                        .map_err(|e| {
+                            session.fail(e.to_string(), execution_start_timer.elapsed());
+                            tonic::Status::new(
+                                tonic::Code::Aborted,
+                                "failed launch computation".to_string(),
+                            )
+                        })?;
+                    let outputs = stream_outputs(&computation, outputs).map_err(|e| {
+                        session.fail(e.to_string(), execution_start_timer.elapsed());
                         tonic::Status::new(
                             tonic::Code::Aborted,
                             "failed launch computation".to_string(),
                         )
                     })?;
+                    tokio::spawn(
+                        session
+                            .finish(handle, outputs, execution_start_timer)
+                            .instrument(span),
+                    );
+                } else {
+                    // lets queued sessions be aborted before they start
+                    let queued = CancelToken::default();
+                    self.cancel_tokens
+                        .insert(session_id.clone(), queued.clone());
+                    tokio::spawn(
+                        async move {
+                            let waiting = async {
+                                for (dependency_id, dependency) in dependencies {
+                                    if let Some(error) = dependency.get().await.error {
+                                        return Err(format!(
+                                            "dependency {} failed: {}",
+                                            dependency_id, error
+                                        ));
+                                    }
+                                }
+                                tracing::info!("Dependencies of session {} completed", session_id);
+                                Ok(match admission {
+                                    Some(admission) => Some(admission.admit(priority).await),
+                                    None => None,
+                                })
+                            };
+                            // held until the session has finished
+                            let _admission = tokio::select! {
+                                reason = queued.cancelled() => {
+                                    let error = crate::error::Error::Cancelled(reason);
+                                    session.fail(error.to_string(), Duration::ZERO);
+                                    return;
+                                }
+                                waited = waiting => match waited {
+                                    Ok(admission) => admission,
+                                    Err(error) => {
+                                        session.fail(error, Duration::ZERO);
+                                        return;
+                                    }
+                                },
+                            };
+                            session.set_state(SessionState::Running);
+                            let execution_start_timer = Instant::now();
+                            let launched = context
+                                .execute_computation(
+                                    session_id,
+                                    &computation,
+                                    arguments,
+                                    role_assignments,
+                                )
+                                .await
+                                .map_err(|e| e.to_string())
+                                .and_then(|(handle, outputs)| {
+                                    let outputs = stream_outputs(&computation, outputs)
+                                        .map_err(|e| e.to_string())?;
+                                    Ok((handle, outputs))
+                                });
+                            match launched {
+                                Ok((handle, outputs)) => {
+                                    session.finish(handle, outputs, execution_start_timer).await
+                                }
+                                Err(e) => session.fail(e, execution_start_timer.elapsed()),
+                            }
+                        }
+                        .instrument(span),
+                    );
+                }
 
-                let result_stores = Arc::clone(&self.result_stores);
+                Ok(tonic::Response::new(LaunchComputationResponse::default()))
+            }
+        }
+    }
 
-                tokio::spawn(async move {
-                    let mut results = HashMap::with_capacity(outputs.len());
-                    for (output_ix, output_value) in outputs {
-                        let value = output_value.await.unwrap();
-                        let output_op = &computation.operations[output_ix];
-                        let output_tag = match &output_op.kind {
-                            Operator::Output(op) => Some(op.tag.clone()),
-                            _ => None,
-                        }
-                        .unwrap();
-                        results.insert(output_tag, value);
-                    }
-                    tracing::info!("Results ready, {:?}", results.keys());
+    async fn abort_computation(
+        &self,
+        request: tonic::Request<AbortComputationRequest>,
+    ) -> Result<tonic::Response<AbortComputationResponse>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
 
-                    let result_cell = result_stores
-                        .get(&session_id)
-                        .expect("session disappeared unexpectedly");
+        let session_id = bincode::deserialize::<SessionId>(&request.session_id).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Aborted,
+                "failed to parse session id".to_string(),
+            )
+        })?;
 
-                    let execution_stop_timer = Instant::now();
-                    let elapsed_time = execution_stop_timer.duration_since(execution_start_timer);
-                    result_cell.set(ComputationOutputs {
-                        outputs: results,
-                        elapsed_time: Some(elapsed_time),
-                    });
+        if !self.result_stores.contains_key(&session_id) {
+            return Err(tonic::Status::new(
+                tonic::Code::NotFound,
+                "unknown session id".to_string(),
+            ));
+        }
+
+        // sessions that already finished have nothing left to abort
+        if let Some(cancel_token) = self.cancel_tokens.get(&session_id) {
+            let reason = if request.reason.is_empty() {
+                "aborted by choreographer"
+            } else {
+                &request.reason
+            };
+            tracing::info!("Aborting session {}: {}", session_id, reason);
+            cancel_token.cancel(reason);
+        }
+
+        Ok(tonic::Response::new(AbortComputationResponse::default()))
+    }
+
+    type WatchProgressStream = BoxStream<'static, Result<WatchProgressResponse, tonic::Status>>;
+
+    async fn watch_progress(
+        &self,
+        request: tonic::Request<WatchProgressRequest>,
+    ) -> Result<tonic::Response<Self::WatchProgressStream>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
+
+        let session_id = bincode::deserialize::<SessionId>(&request.session_id).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Aborted,
+                "failed to parse session id".to_string(),
+            )
+        })?;
+
+        let receiver = match self.progress_watchers.get(&session_id) {
+            Some(receiver) => receiver.value().clone(),
+            None => {
+                return Err(tonic::Status::new(
+                    tonic::Code::NotFound,
+                    "unknown session id".to_string(),
+                ))
+            }
+        };
+
+        // send the current progress right away, then every update until the session finishes
+        let stream = futures::stream::unfold(Some((receiver, false)), |state| async move {
+            let (mut receiver, wait) = state?;
+            if wait && receiver.changed().await.is_err() {
+                return None;
+            }
+            let progress = receiver.borrow_and_update().clone();
+            let response = bincode::serialize(&progress)
+                .map(|progress| WatchProgressResponse { progress })
+                .map_err(|_e| {
+                    tonic::Status::new(
+                        tonic::Code::Internal,
+                        "failed to serialize progress".to_string(),
+                    )
                 });
+            let next = if progress.phase.is_finished() {
+                None
+            } else {
+                Some((receiver, true))
+            };
+            Some((response, next))
+        });
+        Ok(tonic::Response::new(stream.boxed()))
+    }
 
-                Ok(tonic::Response::new(LaunchComputationResponse::default()))
+    type StreamResultsStream = BoxStream<'static, Result<StreamResultsResponse, tonic::Status>>;
+
+    async fn stream_results(
+        &self,
+        request: tonic::Request<StreamResultsRequest>,
+    ) -> Result<tonic::Response<Self::StreamResultsStream>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
+
+        let session_id = bincode::deserialize::<SessionId>(&request.session_id).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Aborted,
+                "failed to parse session id".to_string(),
+            )
+        })?;
+
+        let receiver = match self.output_watchers.get(&session_id) {
+            Some(receiver) => receiver.value().clone(),
+            None => {
+                return Err(tonic::Status::new(
+                    tonic::Code::NotFound,
+                    "unknown session id".to_string(),
+                ))
             }
+        };
+
+        // send outputs available so far, then each new one until the session finishes
+        let structured = request.structured;
+        let stream = futures::stream::unfold(Some((receiver, 0)), move |state| async move {
+            let (mut receiver, sent) = state?;
+            loop {
+                let next = {
+                    let streamed = receiver.borrow_and_update();
+                    match (streamed.outputs.get(sent), &streamed.finished) {
+                        (Some(output), _) => Some(Ok(output.clone())),
+                        (None, Some(Some(error))) => Some(Err(error.clone())),
+                        (None, Some(None)) => return None,
+                        (None, None) => None,
+                    }
+                };
+                match next {
+                    Some(Ok((tag, value))) => {
+                        let ty = value.ty().to_textual();
+                        let response = if structured {
+                            proto::gen::Value::try_from(&value)
+                                .map(|value| StreamResultsResponse {
+                                    tag,
+                                    ty,
+                                    structured_value: Some(value),
+                                    ..Default::default()
+                                })
+                                .map_err(|e| e.to_string())
+                        } else {
+                            bincode::serialize(&value)
+                                .map(|value| StreamResultsResponse {
+                                    tag,
+                                    value,
+                                    ty,
+                                    ..Default::default()
+                                })
+                                .map_err(|e| e.to_string())
+                        }
+                        .map_err(|e| {
+                            tonic::Status::new(
+                                tonic::Code::Internal,
+                                format!("failed to serialize output: {}", e),
+                            )
+                        });
+                        return Some((response, Some((receiver, sent + 1))));
+                    }
+                    Some(Err(error)) => {
+                        let status = tonic::Status::new(
+                            tonic::Code::Aborted,
+                            format!("session failed: {}", error),
+                        );
+                        return Some((Err(status), None));
+                    }
+                    None => {
+                        if receiver.changed().await.is_err() {
+                            return None;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(tonic::Response::new(stream.boxed()))
+    }
+
+    async fn get_capabilities(
+        &self,
+        request: tonic::Request<GetCapabilitiesRequest>,
+    ) -> Result<tonic::Response<GetCapabilitiesResponse>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
+
+        let computation = bincode::deserialize(&request.computation).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Aborted,
+                "failed to parse computation".to_string(),
+            )
+        })?;
+        let role_assignment: RoleAssignment = bincode::deserialize(&request.role_assignment)
+            .map_err(|_e| {
+                tonic::Status::new(
+                    tonic::Code::Aborted,
+                    "failed to parse role assignment".to_string(),
+                )
+            })?;
+        let own_roles: Vec<_> = role_assignment
+            .into_iter()
+            .filter(|(_, identity)| identity == &self.own_identity)
+            .map(|(role, _)| role)
+            .collect();
+
+        let storage = (self.storage_strategy)();
+        let capabilities = Capabilities::of_worker(&storage, &computation, &own_roles);
+        let capabilities = bincode::serialize(&capabilities).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Internal,
+                "failed to serialize capabilities".to_string(),
+            )
+        })?;
+        Ok(tonic::Response::new(GetCapabilitiesResponse {
+            capabilities,
+        }))
+    }
+
+    async fn get_health(
+        &self,
+        _request: tonic::Request<GetHealthRequest>,
+    ) -> Result<tonic::Response<GetHealthResponse>, tonic::Status> {
+        let health = self.health().await;
+        let ready = health.is_ready();
+        let health = bincode::serialize(&health).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Internal,
+                "failed to serialize health".to_string(),
+            )
+        })?;
+        Ok(tonic::Response::new(GetHealthResponse { ready, health }))
+    }
+
+    async fn get_signature(
+        &self,
+        request: tonic::Request<GetSignatureRequest>,
+    ) -> Result<tonic::Response<GetSignatureResponse>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
+
+        let session_id = if request.session_id.is_empty() {
+            SessionId::try_from(request.session_name.as_str())
+                .map_err(|e| tonic::Status::new(tonic::Code::InvalidArgument, e.to_string()))?
+        } else {
+            bincode::deserialize::<SessionId>(&request.session_id).map_err(|_e| {
+                tonic::Status::new(
+                    tonic::Code::Aborted,
+                    "failed to parse session id".to_string(),
+                )
+            })?
+        };
+
+        match self.signatures.get(&session_id) {
+            Some(signature) => Ok(tonic::Response::new(signature.value().clone())),
+            None => Err(tonic::Status::new(
+                tonic::Code::NotFound,
+                "unknown session id".to_string(),
+            )),
         }
     }
 
-    async fn abort_computation(
+    async fn list_sessions(
         &self,
-        _request: tonic::Request<AbortComputationRequest>,
-    ) -> Result<tonic::Response<AbortComputationResponse>, tonic::Status> {
-        unimplemented!()
+        request: tonic::Request<ListSessionsRequest>,
+    ) -> Result<tonic::Response<ListSessionsResponse>, tonic::Status> {
+        self.check_choreographer(&request)?;
+        let request = request.into_inner();
+
+        let state = match request.state.as_str() {
+            "" => None,
+            state => Some(
+                SessionState::from_str(state)
+                    .map_err(|e| tonic::Status::new(tonic::Code::InvalidArgument, e))?,
+            ),
+        };
+
+        let mut sessions: Vec<SessionInfo> = self
+            .sessions
+            .iter()
+            .filter(|info| info.matches(state, &request.tags))
+            .map(|info| info.value().clone())
+            .collect();
+        sessions.sort_by_key(|info| info.launched_at);
+
+        let sessions = bincode::serialize(&sessions).map_err(|_e| {
+            tonic::Status::new(
+                tonic::Code::Internal,
+                "failed to serialize sessions".to_string(),
+            )
+        })?;
+        Ok(tonic::Response::new(ListSessionsResponse { sessions }))
     }
 
     async fn retrieve_results(
@@ -221,9 +1584,30 @@ impl Choreography for GrpcChoreography {
         match self.result_stores.get(&session_id) {
             Some(results) => {
                 let results = results.value().get().await;
-                let values = bincode::serialize(&results).expect("failed to serialize results");
+                if !request.structured {
+                    let values = bincode::serialize(&results).expect("failed to serialize results");
+                    return Ok(tonic::Response::new(RetrieveResultsResponse {
+                        values,
+                        ..Default::default()
+                    }));
+                }
 
-                Ok(tonic::Response::new(RetrieveResultsResponse { values }))
+                let structured_outputs = results
+                    .outputs
+                    .iter()
+                    .map(|(tag, value)| Ok((tag.clone(), proto::gen::Value::try_from(value)?)))
+                    .collect::<crate::error::Result<_>>()
+                    .map_err(|e| {
+                        tonic::Status::new(
+                            tonic::Code::Internal,
+                            format!("failed to serialize results: {}", e),
+                        )
+                    })?;
+                Ok(tonic::Response::new(RetrieveResultsResponse {
+                    values: Vec::new(),
+                    structured_outputs,
+                    error: results.error.clone().unwrap_or_default(),
+                }))
             }
             None => Err(tonic::Status::new(
                 tonic::Code::NotFound,
@@ -232,3 +1616,376 @@ impl Choreography for GrpcChoreography {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::{HostUnit, Placement, RendezvousKey, Ty};
+    use crate::execution::AsyncStorageImpl;
+    use crate::host::HostPlacement;
+    use crate::networking::local::LocalAsyncNetworking;
+    use crate::storage::local::LocalAsyncStorage;
+    use maplit::hashmap;
+    use std::convert::TryInto;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Computation with an output available right away and one waiting for bob.
+    const WAITING_FOR_BOB: &str = r#"key = PrfKeyGen: () -> HostPrfKey () @Host(alice)
+    x = Receive {rendezvous_key = 30303030303030303030303030303030, sender = "bob"}: () -> HostUnit () @Host(alice)
+    slow = Output{tag = "slow"}: (HostUnit) -> HostUnit (x) @Host(alice)
+    fast = Output{tag = "fast"}: (HostPrfKey) -> HostPrfKey (key) @Host(alice)"#;
+
+    /// Worker playing alice with in-memory storage.
+    fn worker(networking_strategy: NetworkingStrategy) -> GrpcChoreography {
+        GrpcChoreography::new(
+            Identity::from("alice"),
+            None,
+            networking_strategy,
+            Box::new(|| Arc::new(LocalAsyncStorage::default()) as AsyncStorageImpl),
+        )
+    }
+
+    fn launch_request(
+        session_id: &str,
+        metadata: &SessionMetadata,
+    ) -> tonic::Request<LaunchComputationRequest> {
+        let computation: Computation = WAITING_FOR_BOB.try_into().unwrap();
+        let role_assignment: RoleAssignment = hashmap!(
+            Role::from("alice") => Identity::from("alice"),
+            Role::from("bob") => Identity::from("bob"),
+        );
+        tonic::Request::new(LaunchComputationRequest {
+            session_id: bincode::serialize(&SessionId::try_from(session_id).unwrap()).unwrap(),
+            computation: bincode::serialize(&computation).unwrap(),
+            arguments: bincode::serialize(&HashMap::<String, Value>::new()).unwrap(),
+            role_assignment: bincode::serialize(&role_assignment).unwrap(),
+            metadata: bincode::serialize(metadata).unwrap(),
+            ..Default::default()
+        })
+    }
+
+    async fn results(worker: &GrpcChoreography, session_id: &str) -> ComputationOutputs {
+        let request = tonic::Request::new(RetrieveResultsRequest {
+            session_id: bincode::serialize(&SessionId::try_from(session_id).unwrap()).unwrap(),
+            structured: false,
+        });
+        let response = worker.retrieve_results(request).await.unwrap();
+        bincode::deserialize(&response.get_ref().values).unwrap()
+    }
+
+    /// Send what alice is waiting for in `session_id` on behalf of bob.
+    async fn send_from_bob(networking: &AsyncNetworkingImpl, session_id: &str) {
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("bob"))));
+        networking
+            .send(
+                &unit,
+                &Identity::from("alice"),
+                &RendezvousKey::try_from("0000000000000000").unwrap(),
+                &SessionId::try_from(session_id).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_session_info_matches() {
+        let info = SessionInfo {
+            session_id: SessionId::try_from("my-session").unwrap(),
+            state: SessionState::Running,
+            metadata: SessionMetadata {
+                tags: vec!["nightly".to_string(), "team-a".to_string()],
+                ..Default::default()
+            },
+            launched_by: None,
+            launched_at: SystemTime::now(),
+        };
+        assert!(info.matches(None, &[]));
+        assert!(info.matches(Some(SessionState::Running), &["team-a".to_string()]));
+        assert!(!info.matches(Some(SessionState::Failed), &[]));
+        assert!(!info.matches(None, &["team-b".to_string()]));
+        assert_eq!(SessionState::from_str("running"), Ok(SessionState::Running));
+    }
+
+    #[test]
+    fn test_computation_signature() {
+        let computation: Computation = r#"
+        x = Input{arg_name = "x"}: () -> HostFloat64Tensor @Host(alice)
+        y = Identity: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(bob)
+        output = Output{tag = "result"}: (HostFloat64Tensor) -> HostFloat64Tensor (y) @Host(bob)
+        "#
+        .try_into()
+        .unwrap();
+        let signature = computation_signature(&computation);
+        assert_eq!(signature.arguments.len(), 1);
+        assert_eq!(signature.arguments[0].name, "x");
+        assert_eq!(signature.arguments[0].ty, "HostFloat64Tensor");
+        assert_eq!(signature.outputs.len(), 1);
+        assert_eq!(signature.outputs[0].name, "result");
+        assert_eq!(
+            signature.outputs[0].placement,
+            Some(proto::gen::Placement::from(&Placement::Host(
+                HostPlacement::from("bob")
+            )))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_idempotency_key_is_not_run_again() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let launched = Arc::new(AtomicUsize::new(0));
+        let worker = {
+            let networking = Arc::clone(&networking);
+            let launched = Arc::clone(&launched);
+            worker(Box::new(move |_| {
+                launched.fetch_add(1, Ordering::SeqCst);
+                Arc::clone(&networking)
+            }))
+        };
+        let metadata = SessionMetadata {
+            idempotency_key: Some("pipeline".to_string()),
+            ..Default::default()
+        };
+
+        worker
+            .launch_computation(launch_request("pipeline#1", &metadata))
+            .await
+            .unwrap();
+        // a retry while the first attempt is still running, and a relaunch of it
+        worker
+            .launch_computation(launch_request("pipeline#2", &metadata))
+            .await
+            .unwrap();
+        worker
+            .launch_computation(launch_request("pipeline#1", &metadata))
+            .await
+            .unwrap();
+        assert_eq!(launched.load(Ordering::SeqCst), 1);
+
+        send_from_bob(&networking, "pipeline#1").await;
+        let first = results(&worker, "pipeline#1").await;
+        let second = results(&worker, "pipeline#2").await;
+        assert_eq!(first.error, None);
+        assert_eq!(first, second);
+        assert_eq!(launched.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_results() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let worker = {
+            let networking = Arc::clone(&networking);
+            worker(Box::new(move |_| Arc::clone(&networking)))
+        };
+        worker
+            .launch_computation(launch_request("12345", &SessionMetadata::default()))
+            .await
+            .unwrap();
+
+        let stream_request = |session_id: &str| {
+            tonic::Request::new(StreamResultsRequest {
+                session_id: bincode::serialize(&SessionId::try_from(session_id).unwrap()).unwrap(),
+                ..Default::default()
+            })
+        };
+        match worker.stream_results(stream_request("unknown")).await {
+            Err(status) => assert_eq!(status.code(), tonic::Code::NotFound),
+            Ok(_) => panic!("expected unknown session to be rejected"),
+        }
+
+        let mut outputs = worker
+            .stream_results(stream_request("12345"))
+            .await
+            .unwrap()
+            .into_inner();
+        // the key is streamed long before anything is received from bob
+        let fast = outputs.next().await.unwrap().unwrap();
+        assert_eq!(fast.tag, "fast");
+        assert_eq!(fast.ty, "HostPrfKey");
+
+        send_from_bob(&networking, "12345").await;
+        let slow = outputs.next().await.unwrap().unwrap();
+        assert_eq!(slow.tag, "slow");
+        let value: Value = bincode::deserialize(&slow.value).unwrap();
+        assert_eq!(value.ty(), Ty::HostUnit);
+        assert!(outputs.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_storage_acl_requires_bindings() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let acl: StorageAcl = r#"
+        [[roles]]
+        name = "alice"
+        load = ["**"]
+        "#
+        .parse()
+        .unwrap();
+        let worker =
+            worker(Box::new(move |_| Arc::clone(&networking))).with_storage_acl(Arc::new(acl));
+
+        // without bindings the launcher could assign any role to this worker
+        let status = worker
+            .launch_computation(launch_request("unbound", &SessionMetadata::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let bindings: RoleBindings = r#"
+        [[roles]]
+        name = "alice"
+        endpoint = "alice"
+
+        [[roles]]
+        name = "bob"
+        endpoint = "bob"
+        "#
+        .parse()
+        .unwrap();
+        worker
+            .with_bindings(bindings)
+            .launch_computation(launch_request("bound", &SessionMetadata::default()))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drain() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let worker = {
+            let networking = Arc::clone(&networking);
+            worker(Box::new(move |_| Arc::clone(&networking)))
+        };
+        worker
+            .launch_computation(launch_request("running", &SessionMetadata::default()))
+            .await
+            .unwrap();
+
+        worker.drain();
+        let status = worker
+            .launch_computation(launch_request("new", &SessionMetadata::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert!(worker.is_draining());
+
+        // sessions launched before draining are still run to completion
+        let waiting = tokio::time::timeout(Duration::from_millis(200), worker.wait_until_idle());
+        assert!(waiting.await.is_err());
+        send_from_bob(&networking, "running").await;
+        tokio::time::timeout(Duration::from_secs(5), worker.wait_until_idle())
+            .await
+            .unwrap();
+        assert_eq!(results(&worker, "running").await.error, None);
+    }
+
+    #[tokio::test]
+    async fn test_partial_results() {
+        let worker = worker(Box::new(|_| {
+            Arc::new(LocalAsyncNetworking::default()) as AsyncNetworkingImpl
+        }))
+        .with_timeouts(OperationTimeouts {
+            receive: Some(Duration::from_millis(50)),
+            compute: None,
+        });
+        worker
+            .launch_computation(launch_request("12345", &SessionMetadata::default()))
+            .await
+            .unwrap();
+
+        // bob never sends anything, but the key was output before the session failed
+        let results = results(&worker, "12345").await;
+        assert!(results.error.unwrap().contains("'x'"));
+        assert_eq!(results.outputs.len(), 1);
+        assert_eq!(results.outputs["fast"].ty(), Ty::HostPrfKey);
+        assert_eq!(
+            worker
+                .sessions
+                .get(&SessionId::try_from("12345").unwrap())
+                .unwrap()
+                .state,
+            SessionState::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abort_queued_session() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let worker = {
+            let networking = Arc::clone(&networking);
+            worker(Box::new(move |_| Arc::clone(&networking)))
+        };
+        worker
+            .launch_computation(launch_request("first", &SessionMetadata::default()))
+            .await
+            .unwrap();
+        // queued until the first session has finished
+        let mut request = launch_request("second", &SessionMetadata::default());
+        request.get_mut().depends_on =
+            vec![bincode::serialize(&SessionId::try_from("first").unwrap()).unwrap()];
+        worker.launch_computation(request).await.unwrap();
+
+        worker
+            .abort_computation(tonic::Request::new(AbortComputationRequest {
+                session_id: bincode::serialize(&SessionId::try_from("second").unwrap()).unwrap(),
+                reason: "no longer needed".to_string(),
+            }))
+            .await
+            .unwrap();
+        let second = tokio::time::timeout(Duration::from_secs(5), results(&worker, "second"))
+            .await
+            .unwrap();
+        assert!(second.error.unwrap().contains("no longer needed"));
+
+        // the session it was waiting for is not affected
+        send_from_bob(&networking, "first").await;
+        assert_eq!(results(&worker, "first").await.error, None);
+    }
+
+    #[cfg(feature = "compile")]
+    #[tokio::test]
+    async fn test_reveal_policy_checked_before_launch() {
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let policy: RevealPolicy = r#"
+        [[rules]]
+        kind = "never_reveal_to"
+        role = "alice"
+        "#
+        .try_into()
+        .unwrap();
+        let worker = worker(Box::new(move |_| Arc::clone(&networking))).with_reveal_policy(policy);
+
+        let status = worker
+            .launch_computation(launch_request("unlabelled", &SessionMetadata::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let labelled_request = |session_id: &str, revealed_to: &str| {
+            let logical = format!(
+                r#"
+                y = Input{{arg_name = "y"}}: () -> Tensor<Float64> @Host(bob)
+                r = Identity: (Tensor<Float64>) -> Tensor<Float64> (y) @Host({0})
+                output = Output{{tag = "output_0"}}: (Tensor<Float64>) -> Tensor<Float64> (r) @Host({0})
+                "#,
+                revealed_to
+            );
+            let labelled = LabelledComputation {
+                logical: logical.as_str().try_into().unwrap(),
+                policy: RevealPolicy::default(),
+            };
+            let mut request = launch_request(session_id, &SessionMetadata::default());
+            request.get_mut().labelled_computation = bincode::serialize(&labelled).unwrap();
+            request
+        };
+
+        let status = worker
+            .launch_computation(labelled_request("revealing", "alice"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        worker
+            .launch_computation(labelled_request("compliant", "bob"))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+//! Health of workers, as reported to drivers, load balancers, and readiness probes.
+//!
+//! Besides describing the worker itself, a health report checks the dependencies
+//! a session needs to succeed: that the storage backend can be reached and that
+//! the peers the worker is bound to answer heartbeats. A worker is only ready to
+//! accept sessions if all checks pass and it is not draining.
+
+use crate::error::Result;
+use crate::execution::Identity;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Check of whether a peer can be reached, typically by sending it a heartbeat.
+pub type PeerCheck = Box<dyn Fn(Identity) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Outcome of checking a dependency of the worker.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub enum CheckStatus {
+    Healthy,
+    Unhealthy(String),
+}
+
+impl From<Result<()>> for CheckStatus {
+    fn from(res: Result<()>) -> Self {
+        match res {
+            Ok(()) => CheckStatus::Healthy,
+            Err(e) => CheckStatus::Unhealthy(e.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CheckStatus::Healthy => write!(f, "healthy"),
+            CheckStatus::Unhealthy(reason) => write!(f, "unhealthy: {}", reason),
+        }
+    }
+}
+
+/// Health of a worker at the time it was asked for it.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct WorkerHealth {
+    /// Version of Moose the worker was built from.
+    pub version: String,
+    /// Time since the worker was started.
+    pub uptime: Duration,
+    /// Number of sessions waiting or running on the worker.
+    pub active_sessions: usize,
+    /// Whether the worker has stopped accepting new sessions.
+    pub draining: bool,
+    pub storage: CheckStatus,
+    /// Status of each peer the worker is bound to, by endpoint.
+    pub peers: BTreeMap<String, CheckStatus>,
+}
+
+impl WorkerHealth {
+    /// Whether the worker can be expected to accept and run new sessions.
+    pub fn is_ready(&self) -> bool {
+        !self.draining
+            && self.storage == CheckStatus::Healthy
+            && self
+                .peers
+                .values()
+                .all(|peer| peer == &CheckStatus::Healthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_readiness() {
+        let mut health = WorkerHealth {
+            version: "0.0.0".to_string(),
+            uptime: Duration::from_secs(1),
+            active_sessions: 0,
+            draining: false,
+            storage: CheckStatus::Healthy,
+            peers: BTreeMap::new(),
+        };
+        assert!(health.is_ready());
+
+        health.peers.insert(
+            "localhost:50001".to_string(),
+            Err(Error::Networking("connection refused".to_string())).into(),
+        );
+        assert!(!health.is_ready());
+        assert_eq!(
+            health.peers["localhost:50001"].to_string(),
+            "unhealthy: Networking error: connection refused"
+        );
+    }
+}
@@ -1,7 +1,12 @@
 //! Choreography extensions.
 
+pub mod admission;
+pub mod bindings;
+pub mod capabilities;
 pub mod filesystem;
 pub mod grpc;
+pub mod health;
+pub mod proto;
 
 use crate::execution::{AsyncNetworkingImpl, AsyncStorageImpl};
 use crate::prelude::SessionId;
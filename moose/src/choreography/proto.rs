@@ -0,0 +1,288 @@
+//! Protobuf messages for computations and values.
+//!
+//! Drivers not written in Rust can use these in place of the bincode bytes
+//! otherwise carried by the choreography. Operators are given in their textual
+//! form; host tensors, shapes, strings, and units are given as native messages,
+//! and all other values as bincode bytes.
+
+pub mod gen {
+    #![allow(clippy::derive_partial_eq_without_eq)]
+    tonic::include_proto!("moose_computation");
+}
+
+use self::gen::placement::Kind as PlacementKind;
+use self::gen::value::Kind as ValueKind;
+use crate::additive::AdditivePlacement;
+use crate::computation::{Computation, HostUnit, Operation, Operator, Placement, Role, Value};
+use crate::error::{Error, Result};
+use crate::host::{HostPlacement, HostRingTensor, HostShape, HostString, HostTensor, RawShape};
+use crate::mirrored::Mirrored3Placement;
+use crate::replicated::ReplicatedPlacement;
+use crate::textual::ToTextual;
+use ndarray::ArrayD;
+use std::convert::{TryFrom, TryInto};
+use std::num::Wrapping;
+
+impl From<&Placement> for gen::Placement {
+    fn from(plc: &Placement) -> gen::Placement {
+        let (kind, owners) = match plc {
+            Placement::Host(plc) => (PlacementKind::Host, std::slice::from_ref(&plc.owner)),
+            Placement::Replicated(plc) => (PlacementKind::Replicated, &plc.owners[..]),
+            Placement::Additive(plc) => (PlacementKind::Additive, &plc.owners[..]),
+            Placement::Mirrored3(plc) => (PlacementKind::Mirrored3, &plc.owners[..]),
+        };
+        gen::Placement {
+            kind: kind as i32,
+            owners: owners.iter().map(|owner| owner.0.clone()).collect(),
+        }
+    }
+}
+
+impl TryFrom<gen::Placement> for Placement {
+    type Error = Error;
+
+    fn try_from(plc: gen::Placement) -> Result<Placement> {
+        let owners: Vec<Role> = plc.owners.iter().map(Role::from).collect();
+        match (PlacementKind::from_i32(plc.kind), owners.as_slice()) {
+            (Some(PlacementKind::Host), [owner]) => Ok(Placement::Host(HostPlacement {
+                owner: owner.clone(),
+            })),
+            (Some(PlacementKind::Replicated), [a, b, c]) => {
+                Ok(Placement::Replicated(ReplicatedPlacement {
+                    owners: [a.clone(), b.clone(), c.clone()],
+                }))
+            }
+            (Some(PlacementKind::Additive), [a, b]) => Ok(Placement::Additive(AdditivePlacement {
+                owners: [a.clone(), b.clone()],
+            })),
+            (Some(PlacementKind::Mirrored3), [a, b, c]) => {
+                Ok(Placement::Mirrored3(Mirrored3Placement {
+                    owners: [a.clone(), b.clone(), c.clone()],
+                }))
+            }
+            _ => Err(Error::SerializationError(format!(
+                "invalid placement of kind {} with {} owners",
+                plc.kind,
+                owners.len()
+            ))),
+        }
+    }
+}
+
+impl From<&Computation> for gen::Computation {
+    fn from(comp: &Computation) -> gen::Computation {
+        let operations = comp
+            .operations
+            .iter()
+            .map(|op| gen::Operation {
+                name: op.name.clone(),
+                operator: op.kind.to_textual(),
+                inputs: op.inputs.clone(),
+                placement: Some(gen::Placement::from(&op.placement)),
+            })
+            .collect();
+        gen::Computation { operations }
+    }
+}
+
+impl TryFrom<gen::Computation> for Computation {
+    type Error = Error;
+
+    fn try_from(comp: gen::Computation) -> Result<Computation> {
+        let operations = comp
+            .operations
+            .into_iter()
+            .map(|op| {
+                let kind = Operator::try_from(op.operator.as_str()).map_err(|e| {
+                    Error::SerializationError(format!("operation {}: {}", op.name, e))
+                })?;
+                let placement = op.placement.ok_or_else(|| {
+                    Error::SerializationError(format!("operation {} has no placement", op.name))
+                })?;
+                Ok(Operation {
+                    name: op.name,
+                    kind,
+                    inputs: op.inputs,
+                    placement: placement.try_into()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Computation { operations })
+    }
+}
+
+impl TryFrom<&Value> for gen::Value {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<gen::Value> {
+        macro_rules! tensor {
+            ($x:expr, $dtype:ident, $data:ident, $entry:expr) => {
+                ValueKind::Tensor(gen::Tensor {
+                    dtype: gen::DType::$dtype as i32,
+                    shape: $x.0.shape().iter().map(|dim| *dim as u64).collect(),
+                    $data: $x.0.iter().map($entry).collect(),
+                    ..Default::default()
+                })
+            };
+        }
+
+        let (plc, kind) = match value {
+            Value::HostFloat32Tensor(x) => (&x.1, tensor!(x, Float32, float32_data, |v| *v)),
+            Value::HostFloat64Tensor(x) => (&x.1, tensor!(x, Float64, float64_data, |v| *v)),
+            Value::HostInt8Tensor(x) => (&x.1, tensor!(x, Int8, int_data, |v| *v as i64)),
+            Value::HostInt16Tensor(x) => (&x.1, tensor!(x, Int16, int_data, |v| *v as i64)),
+            Value::HostInt32Tensor(x) => (&x.1, tensor!(x, Int32, int_data, |v| *v as i64)),
+            Value::HostInt64Tensor(x) => (&x.1, tensor!(x, Int64, int_data, |v| *v)),
+            Value::HostUint8Tensor(x) => (&x.1, tensor!(x, Uint8, uint_data, |v| *v as u64)),
+            Value::HostUint16Tensor(x) => (&x.1, tensor!(x, Uint16, uint_data, |v| *v as u64)),
+            Value::HostUint32Tensor(x) => (&x.1, tensor!(x, Uint32, uint_data, |v| *v as u64)),
+            Value::HostUint64Tensor(x) => (&x.1, tensor!(x, Uint64, uint_data, |v| *v)),
+            Value::HostRing64Tensor(x) => (&x.1, tensor!(x, Ring64, uint_data, |v| v.0)),
+            Value::HostShape(x) => (
+                &x.1,
+                ValueKind::Shape(gen::Shape {
+                    dims: x.0 .0.iter().map(|dim| *dim as u64).collect(),
+                }),
+            ),
+            Value::HostString(x) => (&x.1, ValueKind::String(x.0.clone())),
+            Value::HostUnit(x) => (&x.0, ValueKind::Unit(gen::Unit {})),
+            other => {
+                let bytes = bincode::serialize(other)
+                    .map_err(|e| Error::SerializationError(e.to_string()))?;
+                return Ok(gen::Value {
+                    owner: String::new(),
+                    kind: Some(ValueKind::Opaque(bytes)),
+                });
+            }
+        };
+        Ok(gen::Value {
+            owner: plc.owner.0.clone(),
+            kind: Some(kind),
+        })
+    }
+}
+
+impl TryFrom<gen::Value> for Value {
+    type Error = Error;
+
+    fn try_from(value: gen::Value) -> Result<Value> {
+        let plc = HostPlacement::from(value.owner.as_str());
+        match value.kind {
+            Some(ValueKind::Tensor(tensor)) => tensor_from_proto(tensor, plc),
+            Some(ValueKind::Shape(shape)) => {
+                let dims = shape.dims.into_iter().map(|dim| dim as usize).collect();
+                Ok(Value::from(HostShape(RawShape(dims), plc)))
+            }
+            Some(ValueKind::String(s)) => Ok(Value::from(HostString(s, plc))),
+            Some(ValueKind::Unit(_)) => Ok(Value::from(HostUnit(plc))),
+            Some(ValueKind::Opaque(bytes)) => {
+                bincode::deserialize(&bytes).map_err(|e| Error::SerializationError(e.to_string()))
+            }
+            None => Err(Error::SerializationError(
+                "value message holds no value".to_string(),
+            )),
+        }
+    }
+}
+
+fn tensor_from_proto(tensor: gen::Tensor, plc: HostPlacement) -> Result<Value> {
+    let dtype = gen::DType::from_i32(tensor.dtype).ok_or_else(|| {
+        Error::SerializationError(format!("unknown tensor dtype {}", tensor.dtype))
+    })?;
+    let shape: Vec<usize> = tensor.shape.iter().map(|dim| *dim as usize).collect();
+
+    macro_rules! array {
+        ($data:ident, $t:ty) => {{
+            let entries = tensor
+                .$data
+                .into_iter()
+                .map(|v| {
+                    <$t>::try_from(v).map_err(|_| {
+                        Error::SerializationError(format!(
+                            "tensor entry {} out of range of dtype {:?}",
+                            v, dtype
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<$t>>>()?;
+            ArrayD::from_shape_vec(shape, entries)
+                .map_err(|e| Error::SerializationError(e.to_string()))?
+                .into_shared()
+        }};
+    }
+
+    let value = match dtype {
+        gen::DType::Float32 => Value::from(HostTensor(array!(float32_data, f32), plc)),
+        gen::DType::Float64 => Value::from(HostTensor(array!(float64_data, f64), plc)),
+        gen::DType::Int8 => Value::from(HostTensor(array!(int_data, i8), plc)),
+        gen::DType::Int16 => Value::from(HostTensor(array!(int_data, i16), plc)),
+        gen::DType::Int32 => Value::from(HostTensor(array!(int_data, i32), plc)),
+        gen::DType::Int64 => Value::from(HostTensor(array!(int_data, i64), plc)),
+        gen::DType::Uint8 => Value::from(HostTensor(array!(uint_data, u8), plc)),
+        gen::DType::Uint16 => Value::from(HostTensor(array!(uint_data, u16), plc)),
+        gen::DType::Uint32 => Value::from(HostTensor(array!(uint_data, u32), plc)),
+        gen::DType::Uint64 => Value::from(HostTensor(array!(uint_data, u64), plc)),
+        gen::DType::Ring64 => {
+            let array = array!(uint_data, u64);
+            Value::from(HostRingTensor(array.mapv(Wrapping).into_shared(), plc))
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{HostSeed, RawSeed};
+    use crate::prelude::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_computation_round_trip() {
+        let source = r#"
+        x = Constant{value = HostFloat64Tensor([[1.0, 2.0], [3.0, 4.0]])}: () -> HostFloat64Tensor @Host(alice)
+        y = Constant{value = HostFloat64Tensor([[1.0, 0.0], [0.0, 1.0]])}: () -> HostFloat64Tensor @Host(bob)
+        z = Dot: (HostFloat64Tensor, HostFloat64Tensor) -> HostFloat64Tensor (x, y) @Replicated(alice, bob, carole)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (z) @Host(alice)
+        "#;
+        let comp: Computation = source.try_into().unwrap();
+        let message = gen::Computation::from(&comp);
+        assert_eq!(
+            message.operations[2].operator,
+            comp.operations[2].kind.to_textual()
+        );
+        assert_eq!(Computation::try_from(message).unwrap(), comp);
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let plc = HostPlacement::from("alice");
+        let x: HostInt32Tensor = plc.from_raw(array![[1, -2], [3, -4]]);
+        let message = gen::Value::try_from(&Value::from(x.clone())).unwrap();
+        assert_eq!(message.owner, "alice");
+        match &message.kind {
+            Some(ValueKind::Tensor(tensor)) => {
+                assert_eq!(tensor.shape, vec![2, 2]);
+                assert_eq!(tensor.int_data, vec![1, -2, 3, -4]);
+            }
+            _ => panic!("expected a tensor message"),
+        }
+        assert_eq!(Value::try_from(message).unwrap(), Value::from(x));
+
+        let seed = Value::from(HostSeed(RawSeed([7; 16]), plc.clone()));
+        let message = gen::Value::try_from(&seed).unwrap();
+        assert!(matches!(message.kind, Some(ValueKind::Opaque(_))));
+        assert_eq!(Value::try_from(message).unwrap(), seed);
+
+        let out_of_range = gen::Value {
+            owner: "alice".to_string(),
+            kind: Some(ValueKind::Tensor(gen::Tensor {
+                dtype: gen::DType::Int8 as i32,
+                shape: vec![1],
+                int_data: vec![1000],
+                ..Default::default()
+            })),
+        };
+        assert!(Value::try_from(out_of_range).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Batched execution of independent instances of the same computation.
+//!
+//! Running many small sessions of the same computation pays for the rounds of
+//! communication and the per-operation overhead once per session. Instead, the
+//! inputs of all instances can be stacked along a new leading axis and the
+//! computation rewritten so that every operation depending on an input treats
+//! this axis as a batch axis, evaluating all instances in a single session.
+
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::host::{HostTensor, SliceInfoElem};
+use ndarray::{ArcArrayD, Axis};
+use std::collections::{HashMap, HashSet};
+
+/// A computation rewritten to evaluate a batch of instances at once.
+pub struct BatchedComputation {
+    pub computation: Computation,
+    /// Tags of the outputs that hold one result per instance; all other
+    /// outputs do not depend on the inputs and are shared by all instances.
+    pub batched_outputs: HashSet<String>,
+}
+
+fn unsupported(op: &Operation, reason: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Cannot batch operation '{}' ({}): {}",
+        op.name,
+        op.kind.short_name(),
+        reason
+    )
+}
+
+/// Rewrite a logical computation so that its inputs may be stacked along a new leading axis.
+///
+/// Must be applied before the networking pass since batching follows the data flow
+/// from the inputs, which send and receive operations interrupt.
+pub fn batch_computation(comp: &Computation) -> anyhow::Result<BatchedComputation> {
+    let comp = super::toposort::toposort(comp.clone())?;
+    let mut batched: HashSet<String> = HashSet::new();
+    let mut batched_outputs = HashSet::new();
+    let mut operations = Vec::with_capacity(comp.operations.len());
+
+    for mut op in comp.operations {
+        let batched_inputs: Vec<bool> = op.inputs.iter().map(|i| batched.contains(i)).collect();
+        let is_batched = matches!(op.kind, Operator::Input(_)) || batched_inputs.contains(&true);
+        if !is_batched {
+            operations.push(op);
+            continue;
+        }
+
+        match op.kind {
+            Operator::Input(_)
+            | Operator::Output(_)
+            | Operator::Save(_)
+            | Operator::Identity(_)
+            | Operator::Cast(_)
+            | Operator::Abs(_)
+            | Operator::Neg(_)
+            | Operator::Exp(_)
+            | Operator::Log(_)
+            | Operator::Log2(_)
+            | Operator::Sqrt(_)
+            | Operator::Sigmoid(_)
+            | Operator::Relu(_)
+            | Operator::Sign(_)
+            | Operator::Add(_)
+            | Operator::Sub(_)
+            | Operator::Mul(_)
+            | Operator::Div(_)
+            | Operator::AddN(_)
+            | Operator::Maximum(_)
+            | Operator::Equal(_)
+            | Operator::Greater(_)
+            | Operator::Less(_)
+            | Operator::And(_)
+            | Operator::Or(_)
+            | Operator::Xor(_)
+            | Operator::Mux(_) => {
+                // elementwise, with unbatched operands broadcast over the batch axis
+            }
+            Operator::Dot(_) => {
+                return Err(unsupported(
+                    &op,
+                    "dot products do not support operands of rank greater than two",
+                ));
+            }
+            Operator::IndexAxis(ref mut inner) => inner.axis += 1,
+            Operator::ExpandDims(ref mut inner) => {
+                inner.axis.iter_mut().for_each(|axis| *axis += 1)
+            }
+            Operator::Concat(ref mut inner) => {
+                if batched_inputs.contains(&false) {
+                    return Err(unsupported(&op, "cannot concatenate unbatched operands"));
+                }
+                inner.axis += 1
+            }
+            Operator::Slice(ref mut inner) => {
+                let full = SliceInfoElem {
+                    start: 0,
+                    end: None,
+                    step: None,
+                };
+                inner.slice.0.insert(0, full);
+            }
+            Operator::Softmax(ref mut inner) => inner.axis += 1,
+            Operator::Argmax(ref mut inner) => inner.axis += 1,
+            Operator::Squeeze(SqueezeOp {
+                axis: Some(ref mut axis),
+                ..
+            }) => *axis += 1,
+            Operator::Sum(SumOp {
+                axis: Some(ref mut axis),
+                ..
+            }) => *axis += 1,
+            Operator::Mean(MeanOp {
+                axis: Some(ref mut axis),
+                ..
+            }) => *axis += 1,
+            Operator::Squeeze(_) | Operator::Sum(_) | Operator::Mean(_) => {
+                return Err(unsupported(&op, "an axis is required"));
+            }
+            Operator::Send(_) | Operator::Receive(_) => {
+                return Err(unsupported(
+                    &op,
+                    "computations must be batched before the networking pass",
+                ));
+            }
+            _ => {
+                return Err(unsupported(&op, "operation does not support batching"));
+            }
+        }
+
+        if let Operator::Output(ref output) = op.kind {
+            batched_outputs.insert(output.tag.clone());
+        }
+        batched.insert(op.name.clone());
+        operations.push(op);
+    }
+
+    Ok(BatchedComputation {
+        computation: Computation { operations },
+        batched_outputs,
+    })
+}
+
+macro_rules! batched_tensors {
+    ($($variant:ident),*) => {
+        /// Stack values of the same type along a new leading axis.
+        pub fn stack_values(values: &[Value]) -> Result<Value> {
+            match values.first() {
+                $(
+                    Some(Value::$variant(first)) => {
+                        let mut views = Vec::with_capacity(values.len());
+                        for value in values {
+                            match value {
+                                Value::$variant(x) => views.push(x.0.view()),
+                                _ => {
+                                    return Err(Error::TypeMismatch {
+                                        expected: format!("{:?}", values[0].ty()),
+                                        found: value.ty(),
+                                    })
+                                }
+                            }
+                        }
+                        let stacked = ndarray::stack(Axis(0), &views)
+                            .map_err(|e| Error::KernelError(e.to_string()))?;
+                        Ok(Value::$variant(Box::new(HostTensor(
+                            stacked.into_shared(),
+                            first.1.clone(),
+                        ))))
+                    }
+                )*
+                Some(value) => Err(Error::UnimplementedOperator(format!(
+                    "Cannot batch values of type {:?}",
+                    value.ty()
+                ))),
+                None => Err(Error::MalformedEnvironment(
+                    "Cannot batch zero instances".to_string(),
+                )),
+            }
+        }
+
+        /// Split a value along its leading axis into `n` values.
+        pub fn unstack_value(value: &Value, n: usize) -> Result<Vec<Value>> {
+            match value {
+                $(
+                    Value::$variant(x) => {
+                        if x.0.ndim() == 0 || x.0.shape()[0] != n {
+                            return Err(Error::KernelError(format!(
+                                "Expected a leading axis of length {}, found shape {:?}",
+                                n,
+                                x.0.shape()
+                            )));
+                        }
+                        Ok(x.0
+                            .axis_iter(Axis(0))
+                            .map(|row| {
+                                let row: ArcArrayD<_> = row.to_owned().into_shared();
+                                Value::$variant(Box::new(HostTensor(row, x.1.clone())))
+                            })
+                            .collect())
+                    }
+                )*
+                _ => Err(Error::UnimplementedOperator(format!(
+                    "Cannot unbatch values of type {:?}",
+                    value.ty()
+                ))),
+            }
+        }
+    };
+}
+
+batched_tensors!(
+    HostFloat32Tensor,
+    HostFloat64Tensor,
+    HostInt8Tensor,
+    HostInt16Tensor,
+    HostInt32Tensor,
+    HostInt64Tensor,
+    HostUint8Tensor,
+    HostUint16Tensor,
+    HostUint32Tensor,
+    HostUint64Tensor
+);
+
+/// Combine the arguments of several instances into the arguments of a batched session.
+pub fn stack_arguments(instances: &[HashMap<String, Value>]) -> Result<HashMap<String, Value>> {
+    let first = instances
+        .first()
+        .ok_or_else(|| Error::MalformedEnvironment("Cannot batch zero instances".to_string()))?;
+    first
+        .keys()
+        .map(|name| {
+            let values = instances
+                .iter()
+                .map(|arguments| {
+                    arguments.get(name).cloned().ok_or_else(|| {
+                        Error::MalformedEnvironment(format!(
+                            "Argument '{}' is missing for some instances",
+                            name
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok((name.clone(), stack_values(&values)?))
+        })
+        .collect()
+}
+
+/// Split the outputs of a batched session into the outputs of each of its `n` instances.
+pub fn unstack_outputs(
+    outputs: &HashMap<String, Value>,
+    batched_outputs: &HashSet<String>,
+    n: usize,
+) -> Result<Vec<HashMap<String, Value>>> {
+    let mut instances = vec![HashMap::with_capacity(outputs.len()); n];
+    for (name, value) in outputs {
+        if batched_outputs.contains(name) {
+            for (instance, value) in instances.iter_mut().zip(unstack_value(value, n)?) {
+                instance.insert(name.clone(), value);
+            }
+        } else {
+            for instance in instances.iter_mut() {
+                instance.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    Ok(instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::HostPlacement;
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    fn tensor(x: ndarray::ArrayD<f64>) -> Value {
+        let plc = HostPlacement::from("alice");
+        Value::HostFloat64Tensor(Box::new(HostTensor::<f64>(x.into_shared(), plc)))
+    }
+
+    #[test]
+    fn test_batch_computation() -> std::result::Result<(), anyhow::Error> {
+        let source = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+        w = Constant{value = HostFloat64Tensor([1.0, 2.0])}: () -> Tensor<Float64> () @Host(alice)
+        y = Mul: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)
+        z = Sum{axis = 0}: (Tensor<Float64>) -> Tensor<Float64> (y) @Host(alice)
+        out_z = Output{tag = "z"}: (Tensor<Float64>) -> Tensor<Float64> (z) @Host(alice)
+        out_w = Output{tag = "w"}: (Tensor<Float64>) -> Tensor<Float64> (w) @Host(alice)
+        "#;
+        let batched = batch_computation(&source.try_into()?)?;
+        let sum = batched
+            .computation
+            .operations
+            .iter()
+            .find(|op| op.name == "z")
+            .unwrap();
+        assert!(matches!(
+            sum.kind,
+            Operator::Sum(SumOp { axis: Some(1), .. })
+        ));
+        assert_eq!(
+            batched.batched_outputs,
+            vec!["z".to_string()].into_iter().collect()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_unbatchable() -> std::result::Result<(), anyhow::Error> {
+        let source = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+        y = Transpose: (Tensor<Float64>) -> Tensor<Float64> (x) @Host(alice)
+        "#;
+        assert!(batch_computation(&source.try_into()?).is_err());
+
+        let source = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+        w = Constant{value = HostFloat64Tensor([[1.0], [2.0]])}: () -> Tensor<Float64> () @Host(alice)
+        y = Dot: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)
+        "#;
+        assert!(batch_computation(&source.try_into()?).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "compile")]
+    #[test]
+    fn test_execute_batched_computation() -> std::result::Result<(), anyhow::Error> {
+        use crate::compilation::{compile, Pass};
+        use crate::execution::{SyncSession, TestSyncExecutor};
+        use crate::storage::local::LocalSyncStorage;
+        use std::convert::TryFrom;
+        use std::rc::Rc;
+
+        let source = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+        w = Constant{value = HostFloat64Tensor([1.0, 2.0])}: () -> Tensor<Float64> () @Host(alice)
+        y = Mul: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)
+        z = Sum{axis = 0}: (Tensor<Float64>) -> Tensor<Float64> (y) @Host(alice)
+        out_z = Output{tag = "z"}: (Tensor<Float64>) -> Tensor<Float64> (z) @Host(alice)
+        out_w = Output{tag = "w"}: (Tensor<Float64>) -> Tensor<Float64> (w) @Host(alice)
+        "#;
+        let batched = batch_computation(&source.try_into()?)?;
+        let computation = compile::<Pass>(batched.computation, None)?;
+
+        let instances: Vec<HashMap<String, Value>> = (0..3)
+            .map(|i| {
+                let x = array![i as f64, 1.0].into_dyn();
+                vec![("x".to_string(), tensor(x))].into_iter().collect()
+            })
+            .collect();
+        let session = SyncSession::from_storage(
+            SessionId::try_from("batched")?,
+            stack_arguments(&instances)?,
+            HashMap::new(),
+            Rc::new(LocalSyncStorage::default()),
+        );
+        let outputs = TestSyncExecutor::default().run_computation(&computation, &session)?;
+
+        let results = unstack_outputs(&outputs, &batched.batched_outputs, instances.len())?;
+        for (i, result) in results.iter().enumerate() {
+            let expected = ndarray::arr0(i as f64 + 2.0).into_dyn();
+            assert_eq!(result["z"], tensor(expected), "instance {}", i);
+            assert_eq!(result["w"], tensor(array![1.0, 2.0].into_dyn()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stack_and_unstack() {
+        let instances: Vec<HashMap<String, Value>> = (0..3)
+            .map(|i| {
+                let x = array![i as f64, 1.0].into_dyn();
+                vec![("x".to_string(), tensor(x))].into_iter().collect()
+            })
+            .collect();
+        let stacked = stack_arguments(&instances).unwrap();
+        assert_eq!(
+            stacked["x"],
+            tensor(array![[0.0, 1.0], [1.0, 1.0], [2.0, 1.0]].into_dyn())
+        );
+
+        let batched_outputs = vec!["x".to_string()].into_iter().collect();
+        let unstacked = unstack_outputs(&stacked, &batched_outputs, 3).unwrap();
+        assert_eq!(unstacked, instances);
+    }
+}
@@ -0,0 +1,252 @@
+//! Information-flow analysis of computations.
+//!
+//! Computes, for each role, which values of a computation are revealed to it
+//! and which secret inputs these values derive from. Arguments and loaded
+//! values are the secret inputs; a value is revealed to the owner of the host
+//! placement it is computed on, to all owners of a mirrored placement, and, if
+//! it is a shape, to all owners of a replicated or additive placement. Values on
+//! replicated and additive placements are otherwise kept secret.
+//!
+//! The analysis is meant for logical computations: after lowering, secret
+//! shares live on host placements and cannot be told apart from plaintext
+//! values, so every share is reported as revealed to the host holding it.
+
+use crate::computation::{Computation, Constant, Operator, Placement, RendezvousKey, Role, Ty};
+use crate::error::Error;
+use crate::textual::ToTextual;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Argument given to the computation.
+    Argument,
+    /// Value loaded from storage.
+    Storage,
+}
+
+/// Secret input of a computation.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// Name of the argument, or key of the loaded value; the name of the load
+    /// operation if the key is not a constant.
+    pub name: String,
+    /// Roles providing the input.
+    pub owners: Vec<String>,
+}
+
+/// Value revealed to a role.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RevealedValue {
+    pub op_name: String,
+    pub operator: String,
+    pub ty: String,
+    /// Secret inputs the value derives from.
+    pub sources: Vec<Source>,
+}
+
+/// Everything revealed to a single role.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RoleLeakage {
+    pub role: String,
+    /// Secret inputs of other roles that values revealed to this role derive from.
+    pub foreign_sources: Vec<Source>,
+    /// Values revealed to this role that derive from secret inputs, in the
+    /// order of the computation.
+    pub values: Vec<RevealedValue>,
+}
+
+/// Values revealed to each role of a computation, ordered by role.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LeakageReport {
+    pub roles: Vec<RoleLeakage>,
+}
+
+impl LeakageReport {
+    pub fn role(&self, role: &str) -> Option<&RoleLeakage> {
+        self.roles.iter().find(|leakage| leakage.role == role)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn owners(plc: &Placement) -> Vec<&Role> {
+    match plc {
+        Placement::Host(plc) => vec![&plc.owner],
+        Placement::Replicated(plc) => plc.owners.iter().collect(),
+        Placement::Additive(plc) => plc.owners.iter().collect(),
+        Placement::Mirrored3(plc) => plc.owners.iter().collect(),
+    }
+}
+
+fn is_shape(ty: &Ty) -> bool {
+    matches!(
+        ty,
+        Ty::Shape(_) | Ty::HostShape | Ty::ReplicatedShape | Ty::AdditiveShape
+    )
+}
+
+/// Roles the output of an operation is revealed to.
+fn revealed_to(plc: &Placement, ty: &Ty) -> Vec<&Role> {
+    match plc {
+        Placement::Host(_) | Placement::Mirrored3(_) => owners(plc),
+        Placement::Replicated(_) | Placement::Additive(_) if is_shape(ty) => owners(plc),
+        Placement::Replicated(_) | Placement::Additive(_) => vec![],
+    }
+}
+
+/// Compute the values revealed to each role of `comp`.
+pub fn leakage_report(comp: &Computation) -> anyhow::Result<LeakageReport> {
+    let graph = comp.as_graph();
+    let order = petgraph::algo::toposort(&graph, None).map_err(|_| {
+        Error::MalformedComputation("cycle detected in the computation graph".into())
+    })?;
+
+    let mut sources: Vec<Source> = Vec::new();
+    // secret inputs each operation derives from, as indices into `sources`
+    let mut derived: HashMap<&str, BTreeSet<usize>> = HashMap::new();
+    let mut sent: HashMap<&RendezvousKey, BTreeSet<usize>> = HashMap::new();
+
+    for node in order {
+        let op = &comp.operations[graph[node].index];
+        let mut op_sources = BTreeSet::new();
+        for input in &op.inputs {
+            let input_sources = derived.get(input.as_str()).ok_or_else(|| {
+                Error::MalformedComputation(format!(
+                    "operation '{}' refers to undefined input '{}'",
+                    op.name, input
+                ))
+            })?;
+            op_sources.extend(input_sources.iter().copied());
+        }
+
+        let source = match &op.kind {
+            Operator::Input(input) => Some((SourceKind::Argument, input.arg_name.clone())),
+            Operator::Load(_) => {
+                let key = op
+                    .inputs
+                    .first()
+                    .and_then(|key| comp.operations.iter().find(|op| &op.name == key))
+                    .and_then(|key| match &key.kind {
+                        Operator::Constant(key) => match &key.value {
+                            Constant::String(key) => Some(key.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    });
+                Some((SourceKind::Storage, key.unwrap_or_else(|| op.name.clone())))
+            }
+            Operator::Send(send) => {
+                sent.insert(&send.rendezvous_key, op_sources.clone());
+                None
+            }
+            Operator::Receive(receive) => {
+                if let Some(sent_sources) = sent.get(&receive.rendezvous_key) {
+                    op_sources.extend(sent_sources.iter().copied());
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some((kind, name)) = source {
+            op_sources.insert(sources.len());
+            sources.push(Source {
+                kind,
+                name,
+                owners: owners(&op.placement)
+                    .into_iter()
+                    .map(|role| role.0.clone())
+                    .collect(),
+            });
+        }
+        derived.insert(&op.name, op_sources);
+    }
+
+    let mut roles: BTreeMap<String, RoleLeakage> = BTreeMap::new();
+    for role in comp.operations.iter().flat_map(|op| owners(&op.placement)) {
+        roles.entry(role.0.clone()).or_insert_with(|| RoleLeakage {
+            role: role.0.clone(),
+            foreign_sources: Vec::new(),
+            values: Vec::new(),
+        });
+    }
+
+    for op in &comp.operations {
+        let op_sources = &derived[op.name.as_str()];
+        if op_sources.is_empty() {
+            continue;
+        }
+        let ty = op.kind.sig().ret();
+        for role in revealed_to(&op.placement, &ty) {
+            let leakage = roles.get_mut(&role.0).expect("role of unknown placement");
+            let value_sources: Vec<Source> =
+                op_sources.iter().map(|i| sources[*i].clone()).collect();
+            for source in &value_sources {
+                if !source.owners.contains(&role.0) && !leakage.foreign_sources.contains(source) {
+                    leakage.foreign_sources.push(source.clone());
+                }
+            }
+            leakage.values.push(RevealedValue {
+                op_name: op.name.clone(),
+                operator: op.kind.short_name().to_string(),
+                ty: ty.to_textual(),
+                sources: value_sources,
+            });
+        }
+    }
+
+    let roles = roles
+        .into_values()
+        .map(|mut leakage| {
+            leakage.foreign_sources.sort();
+            leakage
+        })
+        .collect();
+    Ok(LeakageReport { roles })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_leakage_report() -> anyhow::Result<()> {
+        let comp: Computation = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Fixed128(24, 40)> @Host(alice)
+        y = Input{arg_name = "y"}: () -> Tensor<Fixed128(24, 40)> @Host(bob)
+        c = Constant{value = HostFloat64Tensor([1.0])}: () -> Tensor<Float64> @Host(carole)
+        z = Mul: (Tensor<Fixed128(24, 40)>, Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (x, y) @Replicated(alice, bob, carole)
+        s = Shape: (Tensor<Fixed128(24, 40)>) -> Shape<Replicated> (z) @Replicated(alice, bob, carole)
+        r = Identity: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (z) @Host(carole)
+        output = Output{tag = "output_0"}: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (r) @Host(carole)
+        "#
+        .try_into()?;
+        let report = leakage_report(&comp)?;
+        assert_eq!(report.roles.len(), 3);
+
+        let alice = report.role("alice").unwrap();
+        let names: Vec<&str> = alice.values.iter().map(|v| v.op_name.as_str()).collect();
+        assert_eq!(names, vec!["x", "s"]);
+        assert_eq!(alice.foreign_sources.len(), 1);
+        assert_eq!(alice.foreign_sources[0].name, "y");
+
+        let carole = report.role("carole").unwrap();
+        let names: Vec<&str> = carole.values.iter().map(|v| v.op_name.as_str()).collect();
+        assert_eq!(names, vec!["s", "r", "output"]);
+        assert_eq!(carole.values[1].sources.len(), 2);
+        assert_eq!(
+            carole.values[1].sources[0].owners,
+            vec!["alice".to_string()]
+        );
+        assert_eq!(carole.foreign_sources.len(), 2);
+
+        let json = report.to_json()?;
+        assert!(json.contains("\"foreign_sources\""));
+        Ok(())
+    }
+}
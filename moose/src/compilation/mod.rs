@@ -1,16 +1,21 @@
 //! Moose compilation framework.
 
+use crate::compilation::policy::RevealPolicy;
 use crate::computation::Computation;
 use crate::textual::ToTextual;
 use std::convert::TryFrom;
 
+pub mod batching;
 mod deprecated_shape;
+pub mod leakage;
 mod lowering;
 mod networking;
+pub mod policy;
 mod print;
 mod pruning;
 pub mod toposort;
 mod typing;
+pub mod validation;
 mod well_formed;
 
 /// Default compiler passes in order.
@@ -42,6 +47,8 @@ pub enum Pass {
     Typing,
     /// Check well-formedness.
     WellFormed,
+    /// Reject computations violating a reveal policy; must run before lowering.
+    Policy(RevealPolicy),
     DeprecatedShape, // Support HostShape in the logical dialect (for pre-0.2.0 computations)
 }
 
@@ -55,6 +62,7 @@ impl Pass {
             Pass::Lowering => self::lowering::lowering(comp),
             Pass::Typing => self::typing::update_types_one_hop(comp),
             Pass::WellFormed => self::well_formed::well_formed(comp),
+            Pass::Policy(policy) => self::policy::enforce_policy(comp, policy),
             Pass::DeprecatedShape => self::deprecated_shape::deprecated_shape_support(comp),
             Pass::Dump => {
                 println!("{}", comp.to_textual());
@@ -0,0 +1,322 @@
+//! Reveal policies enforced at compile time.
+//!
+//! A policy is a list of rules about the secret inputs of a computation,
+//! referring to arguments by name and to loaded values by key. Rules are
+//! checked against the values revealed to each role, as found by the
+//! [leakage analysis](super::leakage), so policies should be enforced on
+//! logical computations, before lowering. Values revealed to the role
+//! providing an input never violate rules about that input.
+//!
+//! Aggregation is measured in secret inputs, not in rows or elements: a value
+//! aggregates `k` inputs if it derives from `k` distinct arguments and loaded
+//! keys, so summing all rows of a single argument aggregates one input. Rules
+//! about minimum aggregation thus only make sense for computations taking, for
+//! instance, one argument per data subject.
+//!
+//! Besides enforcing a policy when compiling, drivers can launch sessions with
+//! a [`LabelledComputation`], which workers check against its policy and
+//! against any policy of their own before running the session.
+//!
+//! Policies can be written in TOML, for instance
+//!
+//! ```toml
+//! [[rules]]
+//! kind = "never_reveal_to"
+//! role = "carole"
+//! inputs = ["salary"]
+//!
+//! [[rules]]
+//! kind = "min_aggregation"
+//! k = 3
+//! ```
+
+use super::leakage::{leakage_report, RevealedValue, Source};
+use crate::computation::Computation;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    /// Values derived from `inputs` must never be revealed to `role`.
+    NeverRevealTo {
+        role: String,
+        /// Inputs the rule applies to; all inputs if empty.
+        #[serde(default)]
+        inputs: Vec<String>,
+    },
+    /// Values derived from `inputs` may only be revealed to other roles if
+    /// they aggregate at least `k` secret inputs.
+    MinAggregation {
+        /// Number of distinct arguments and loaded keys a revealed value must
+        /// derive from; rows within an input are not counted.
+        k: usize,
+        /// Inputs the rule applies to; all inputs if empty.
+        #[serde(default)]
+        inputs: Vec<String>,
+    },
+}
+
+impl Rule {
+    fn applies_to(&self, source: &Source) -> bool {
+        let inputs = match self {
+            Rule::NeverRevealTo { inputs, .. } => inputs,
+            Rule::MinAggregation { inputs, .. } => inputs,
+        };
+        inputs.is_empty() || inputs.contains(&source.name)
+    }
+
+    /// Input of another role that `value`, revealed to `role`, may not derive from.
+    fn violated_by<'v>(&self, role: &str, value: &'v RevealedValue) -> Option<&'v Source> {
+        let mut foreign_sources = value
+            .sources
+            .iter()
+            .filter(|source| !source.owners.iter().any(|owner| owner == role))
+            .filter(|source| self.applies_to(source));
+        match self {
+            Rule::NeverRevealTo { role: denied, .. } if denied == role => foreign_sources.next(),
+            Rule::NeverRevealTo { .. } => None,
+            Rule::MinAggregation { k, .. } if value.sources.len() < *k => foreign_sources.next(),
+            Rule::MinAggregation { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::NeverRevealTo { role, .. } => write!(f, "never reveal to '{}'", role),
+            Rule::MinAggregation { k, .. } => {
+                write!(f, "only reveal aggregates of at least {} inputs", k)
+            }
+        }
+    }
+}
+
+/// Rules about which values of a computation may be revealed to whom.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RevealPolicy {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl RevealPolicy {
+    pub fn from_toml(source: &str) -> anyhow::Result<RevealPolicy> {
+        toml::from_str(source)
+            .map_err(|e| anyhow::anyhow!("Failed to parse the reveal policy due to {}", e))
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<RevealPolicy> {
+        RevealPolicy::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Descriptions of every violation of the policy by `comp`.
+    pub fn violations(&self, comp: &Computation) -> anyhow::Result<Vec<String>> {
+        let report = leakage_report(comp)?;
+        let mut violations = Vec::new();
+        for leakage in &report.roles {
+            for value in &leakage.values {
+                for rule in &self.rules {
+                    if let Some(source) = rule.violated_by(&leakage.role, value) {
+                        violations.push(format!(
+                            "'{}' reveals input '{}' to '{}', but the policy says to {}",
+                            value.op_name, source.name, leakage.role, rule
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+impl TryFrom<&str> for RevealPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(source: &str) -> anyhow::Result<RevealPolicy> {
+        RevealPolicy::from_toml(source)
+    }
+}
+
+fn reject_violations(violations: Vec<String>) -> anyhow::Result<()> {
+    if !violations.is_empty() {
+        return Err(Error::Compilation(format!(
+            "computation violates its reveal policy: {}",
+            violations.join("; ")
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Reject `comp` if it violates `policy`.
+pub fn enforce_policy(comp: Computation, policy: &RevealPolicy) -> anyhow::Result<Computation> {
+    reject_violations(policy.violations(&comp)?)?;
+    Ok(comp)
+}
+
+/// Logical computation of a session labelled with the reveal policy it must
+/// comply with.
+///
+/// Drivers attach it to the compiled computation of a session so that workers
+/// can check the policy before launching the session. Since lowering is
+/// randomized, workers cannot check that the compiled computation was in fact
+/// compiled from the logical one; the check thus protects against launching
+/// computations that violate a policy, not against drivers lying about them.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LabelledComputation {
+    pub logical: Computation,
+    // rules are internally tagged, which formats such as bincode cannot deserialize
+    #[serde(with = "policy_as_toml")]
+    pub policy: RevealPolicy,
+}
+
+mod policy_as_toml {
+    use super::RevealPolicy;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        policy: &RevealPolicy,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let source = toml::to_string(policy).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&source)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<RevealPolicy, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        RevealPolicy::from_toml(&source).map_err(de::Error::custom)
+    }
+}
+
+impl LabelledComputation {
+    /// Reject the logical computation if it violates its own policy or the
+    /// rules of `policy`, if given.
+    pub fn check(&self, policy: Option<&RevealPolicy>) -> anyhow::Result<()> {
+        let mut combined = self.policy.clone();
+        if let Some(policy) = policy {
+            combined.rules.extend(policy.rules.iter().cloned());
+        }
+        reject_violations(combined.violations(&self.logical)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    const SOURCE: &str = r#"
+    x = Input{arg_name = "x"}: () -> Tensor<Fixed128(24, 40)> @Host(alice)
+    y = Input{arg_name = "y"}: () -> Tensor<Fixed128(24, 40)> @Host(bob)
+    z = Add: (Tensor<Fixed128(24, 40)>, Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (x, y) @Replicated(alice, bob, carole)
+    r = Identity: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (z) @Host(carole)
+    output = Output{tag = "output_0"}: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (r) @Host(carole)
+    "#;
+
+    #[test]
+    fn test_never_reveal_to() -> anyhow::Result<()> {
+        let comp: Computation = SOURCE.try_into()?;
+
+        let policy = RevealPolicy::from_toml(
+            r#"
+            [[rules]]
+            kind = "never_reveal_to"
+            role = "alice"
+            "#,
+        )?;
+        assert!(enforce_policy(comp.clone(), &policy).is_ok());
+
+        let policy = RevealPolicy::from_toml(
+            r#"
+            [[rules]]
+            kind = "never_reveal_to"
+            role = "carole"
+            inputs = ["y"]
+            "#,
+        )?;
+        let violations = policy.violations(&comp)?;
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].starts_with("'r' reveals input 'y' to 'carole'"));
+        assert!(enforce_policy(comp, &policy).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_aggregation() -> anyhow::Result<()> {
+        let comp: Computation = SOURCE.try_into()?;
+
+        let policy: RevealPolicy = r#"
+            [[rules]]
+            kind = "min_aggregation"
+            k = 2
+            "#
+        .try_into()?;
+        assert!(enforce_policy(comp.clone(), &policy).is_ok());
+
+        let policy: RevealPolicy = r#"
+            [[rules]]
+            kind = "min_aggregation"
+            k = 3
+            inputs = ["x"]
+            "#
+        .try_into()?;
+        let violations = policy.violations(&comp)?;
+        assert_eq!(violations.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_aggregation_counts_inputs() -> anyhow::Result<()> {
+        // summing all rows of a single input still aggregates a single input
+        let comp: Computation = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Fixed128(24, 40)> @Host(alice)
+        s = Sum{axis = 0}: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (x) @Replicated(alice, bob, carole)
+        r = Identity: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (s) @Host(bob)
+        output = Output{tag = "output_0"}: (Tensor<Fixed128(24, 40)>) -> Tensor<Fixed128(24, 40)> (r) @Host(bob)
+        "#
+        .try_into()?;
+        let policy: RevealPolicy = r#"
+            [[rules]]
+            kind = "min_aggregation"
+            k = 2
+            "#
+        .try_into()?;
+        assert!(enforce_policy(comp, &policy).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_labelled_computation() -> anyhow::Result<()> {
+        let labelled = LabelledComputation {
+            logical: SOURCE.try_into()?,
+            policy: RevealPolicy::default(),
+        };
+        assert!(labelled.check(None).is_ok());
+
+        // rules of the worker apply on top of those of the computation
+        let worker_policy: RevealPolicy = r#"
+            [[rules]]
+            kind = "never_reveal_to"
+            role = "carole"
+            "#
+        .try_into()?;
+        assert!(labelled.check(Some(&worker_policy)).is_err());
+
+        let labelled = LabelledComputation {
+            policy: worker_policy,
+            ..labelled
+        };
+        assert!(labelled.check(None).is_err());
+
+        // labelled computations are sent to workers in bincode
+        let received: LabelledComputation = bincode::deserialize(&bincode::serialize(&labelled)?)?;
+        assert_eq!(received, labelled);
+        Ok(())
+    }
+}
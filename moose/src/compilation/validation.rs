@@ -0,0 +1,504 @@
+//! Validation of computations reporting every problem found.
+//!
+//! Unlike the compiler passes, which stop at the first problem, validation checks
+//! the whole computation and collects all problems, grouped by severity, so that
+//! they can be fixed at once. Errors prevent a computation from running, while
+//! warnings point out what will be fixed up or dropped by the compiler.
+
+use super::well_formed::compile_error;
+use crate::computation::{Computation, Operator, Placement, Role, Ty};
+use crate::logical::{TensorDType, TensorShape};
+use crate::textual::ToTextual;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Aspect of a computation a finding is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    Parsing,
+    Structure,
+    Typing,
+    Operator,
+    Placement,
+    Reachability,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Parsing => "parsing",
+            Category::Structure => "structure",
+            Category::Typing => "typing",
+            Category::Operator => "operator",
+            Category::Placement => "placement",
+            Category::Reachability => "reachability",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub category: Category,
+    /// Operation the finding is about, if any.
+    pub op_name: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op_name {
+            Some(ref op_name) => write!(f, "[{}] '{}': {}", self.category, op_name, self.message),
+            None => write!(f, "[{}] {}", self.category, self.message),
+        }
+    }
+}
+
+/// All problems found in a computation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.severity == severity)
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        category: Category,
+        op_name: Option<&str>,
+        message: String,
+    ) {
+        self.findings.push(Finding {
+            severity,
+            category,
+            op_name: op_name.map(str::to_string),
+            message,
+        });
+    }
+
+    fn error(&mut self, category: Category, op_name: &str, message: String) {
+        self.push(Severity::Error, category, Some(op_name), message)
+    }
+
+    fn warning(&mut self, category: Category, op_name: &str, message: String) {
+        self.push(Severity::Warning, category, Some(op_name), message)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num_errors = self.errors().count();
+        let num_warnings = self.warnings().count();
+        if num_errors == 0 && num_warnings == 0 {
+            return writeln!(f, "No problems found");
+        }
+        writeln!(f, "{} error(s), {} warning(s)", num_errors, num_warnings)?;
+        for (title, severity, count) in [
+            ("Errors", Severity::Error, num_errors),
+            ("Warnings", Severity::Warning, num_warnings),
+        ]
+        .iter()
+        {
+            if *count > 0 {
+                writeln!(f, "\n{}:", title)?;
+                for finding in self.with_severity(*severity) {
+                    writeln!(f, "  {}", finding)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `ty` still needs to be inferred.
+fn is_partial(ty: &Ty) -> bool {
+    matches!(
+        ty,
+        Ty::Unknown | Ty::Tensor(TensorDType::Unknown) | Ty::Shape(TensorShape::Unknown)
+    )
+}
+
+fn owners(plc: &Placement) -> Vec<&Role> {
+    match plc {
+        Placement::Host(plc) => vec![&plc.owner],
+        Placement::Replicated(plc) => plc.owners.iter().collect(),
+        Placement::Additive(plc) => plc.owners.iter().collect(),
+        Placement::Mirrored3(plc) => plc.owners.iter().collect(),
+    }
+}
+
+/// Parse and validate the textual computation in `source`.
+///
+/// If the computation cannot be parsed, each line is parsed on its own so that
+/// all lines with errors are reported, and the remaining lines are validated.
+pub fn validate_source(source: &str) -> ValidationReport {
+    if let Ok(computation) = Computation::from_textual(source) {
+        return validate(&computation);
+    }
+
+    let mut report = ValidationReport::default();
+    let mut operations = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        match Computation::from_textual(line) {
+            Ok(computation) => operations.extend(computation.operations),
+            Err(e) => report.push(
+                Severity::Error,
+                Category::Parsing,
+                None,
+                format!("line {}: {}", index + 1, e),
+            ),
+        }
+    }
+    report
+        .findings
+        .extend(validate(&Computation { operations }).findings);
+    report
+}
+
+/// Check `computation` for problems, without modifying it.
+pub fn validate(computation: &Computation) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let ops = &computation.operations;
+
+    // structure
+    let mut indices: HashMap<&str, usize> = HashMap::with_capacity(ops.len());
+    for (index, op) in ops.iter().enumerate() {
+        if indices.insert(op.name.as_str(), index).is_some() {
+            report.error(
+                Category::Structure,
+                &op.name,
+                "name is used by more than one operation".to_string(),
+            );
+        }
+    }
+    let mut unknown_inputs: HashSet<usize> = HashSet::new();
+    for (index, op) in ops.iter().enumerate() {
+        for input in op.inputs.iter() {
+            match indices.get(input.as_str()) {
+                None => {
+                    unknown_inputs.insert(index);
+                    report.error(
+                        Category::Structure,
+                        &op.name,
+                        format!("input '{}' is not defined", input),
+                    );
+                }
+                Some(input_index) if *input_index > index => report.warning(
+                    Category::Structure,
+                    &op.name,
+                    format!(
+                        "input '{}' is defined later; operations must be sorted topologically",
+                        input
+                    ),
+                ),
+                Some(_) => (),
+            }
+        }
+    }
+
+    // typing and operators
+    for op in ops.iter() {
+        let sig = op.kind.sig();
+        if let Some(arity) = sig.arity() {
+            if arity != op.inputs.len() {
+                report.error(
+                    Category::Typing,
+                    &op.name,
+                    format!(
+                        "expects {} operand(s) but is given {}",
+                        arity,
+                        op.inputs.len()
+                    ),
+                );
+                continue;
+            }
+        }
+        let mut unresolved = is_partial(&sig.ret());
+        let mut mistyped = false;
+        for (i, input) in op.inputs.iter().enumerate() {
+            let expected = match sig.arg(i) {
+                Ok(ty) => ty,
+                Err(_) => continue,
+            };
+            let found = indices
+                .get(input.as_str())
+                .map(|input_index| ops[*input_index].kind.sig().ret());
+            match found {
+                Some(found) if !is_partial(&expected) && !is_partial(&found) => {
+                    if expected != found {
+                        mistyped = true;
+                        report.error(
+                            Category::Typing,
+                            &op.name,
+                            format!(
+                                "operand {} ('{}') should be {} but is {}",
+                                i,
+                                input,
+                                expected.to_textual(),
+                                found.to_textual()
+                            ),
+                        );
+                    }
+                }
+                // inferred by the typing pass from the operand
+                Some(found) if !is_partial(&found) => (),
+                _ => unresolved |= is_partial(&expected),
+            }
+        }
+        if unresolved {
+            report.warning(
+                Category::Typing,
+                &op.name,
+                format!(
+                    "type {} cannot be fully resolved",
+                    op.kind.sig().to_textual()
+                ),
+            );
+        } else if mistyped {
+            // kernels cannot be checked against a signature known to be wrong
+        } else if let Some(e) = compile_error(op) {
+            report.error(Category::Operator, &op.name, e.to_string());
+        }
+    }
+
+    // placements
+    let mut sends: HashMap<_, usize> = HashMap::new();
+    for (index, op) in ops.iter().enumerate() {
+        let plc_owners = owners(&op.placement);
+        let distinct: HashSet<&Role> = plc_owners.iter().copied().collect();
+        if distinct.len() != plc_owners.len() {
+            report.error(
+                Category::Placement,
+                &op.name,
+                format!(
+                    "placement {} has the same role more than once",
+                    op.placement.to_textual()
+                ),
+            );
+        }
+        let is_host = matches!(op.placement, Placement::Host(_));
+        match op.kind {
+            Operator::Send(_) | Operator::Receive(_) if !is_host => report.error(
+                Category::Placement,
+                &op.name,
+                "only host placements can send and receive".to_string(),
+            ),
+            Operator::Send(ref send) => {
+                if sends.insert(&send.rendezvous_key, index).is_some() {
+                    report.error(
+                        Category::Placement,
+                        &op.name,
+                        format!(
+                            "rendezvous key {} is used by more than one send",
+                            send.rendezvous_key
+                        ),
+                    );
+                }
+            }
+            _ => (),
+        }
+    }
+    let mut received = HashSet::new();
+    for op in ops.iter() {
+        if let (Operator::Receive(receive), Placement::Host(host)) = (&op.kind, &op.placement) {
+            received.insert(&receive.rendezvous_key);
+            let send_op = match sends.get(&receive.rendezvous_key) {
+                Some(send_index) => &ops[*send_index],
+                None => {
+                    report.error(
+                        Category::Placement,
+                        &op.name,
+                        format!("no send with rendezvous key {}", receive.rendezvous_key),
+                    );
+                    continue;
+                }
+            };
+            if let (Operator::Send(send), Placement::Host(sender)) =
+                (&send_op.kind, &send_op.placement)
+            {
+                if send.receiver != host.owner || receive.sender != sender.owner {
+                    report.error(
+                        Category::Placement,
+                        &op.name,
+                        format!(
+                            "receives from {} but '{}' on {} sends to {}",
+                            receive.sender, send_op.name, sender.owner, send.receiver
+                        ),
+                    );
+                }
+            }
+        }
+    }
+    for (key, send_index) in sends.iter() {
+        if !received.contains(key) {
+            report.error(
+                Category::Placement,
+                &ops[*send_index].name,
+                format!("no receive with rendezvous key {}", key),
+            );
+        }
+    }
+
+    // reachability
+    let is_sink = |kind: &Operator| {
+        matches!(
+            kind,
+            Operator::Output(_) | Operator::Save(_) | Operator::Send(_)
+        )
+    };
+    if !ops
+        .iter()
+        .any(|op| matches!(op.kind, Operator::Output(_) | Operator::Save(_)))
+    {
+        report.push(
+            Severity::Warning,
+            Category::Reachability,
+            None,
+            "computation has no outputs or saves".to_string(),
+        );
+    }
+    // operations depending on undefined values, which can never be computed
+    let mut broken = vec![false; ops.len()];
+    let mut changed = !unknown_inputs.is_empty();
+    for index in unknown_inputs {
+        broken[index] = true;
+    }
+    while changed {
+        changed = false;
+        for (index, op) in ops.iter().enumerate() {
+            if !broken[index]
+                && op.inputs.iter().any(|input| {
+                    indices
+                        .get(input.as_str())
+                        .map(|input_index| broken[*input_index])
+                        .unwrap_or(false)
+                })
+            {
+                broken[index] = true;
+                changed = true;
+            }
+        }
+    }
+    // operations contributing to an output, save, or send
+    let mut used = vec![false; ops.len()];
+    let mut stack: Vec<usize> = (0..ops.len()).filter(|i| is_sink(&ops[*i].kind)).collect();
+    while let Some(index) = stack.pop() {
+        if used[index] {
+            continue;
+        }
+        used[index] = true;
+        stack.extend(
+            ops[index]
+                .inputs
+                .iter()
+                .filter_map(|input| indices.get(input.as_str()).copied()),
+        );
+    }
+    for (index, op) in ops.iter().enumerate() {
+        if matches!(op.kind, Operator::Output(_)) && broken[index] {
+            report.error(
+                Category::Reachability,
+                &op.name,
+                "output can never be computed since it depends on undefined values".to_string(),
+            );
+        } else if !used[index] {
+            report.warning(
+                Category::Reachability,
+                &op.name,
+                "result never reaches an output, save, or send and will be pruned".to_string(),
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_computation() {
+        let source = r#"
+        x = Constant{value = HostFloat32Tensor([1.0])}: () -> HostFloat32Tensor () @Host(alice)
+        y = Send{rendezvous_key = 30313233343536373839616263646566, receiver = "bob"}: (HostFloat32Tensor) -> HostUnit (x) @Host(alice)
+        z = Receive{rendezvous_key = 30313233343536373839616263646566, sender = "alice"}: () -> HostFloat32Tensor () @Host(bob)
+        out = Output{tag = "out"}: (HostFloat32Tensor) -> HostFloat32Tensor (z) @Host(bob)
+        "#;
+        let report = validate_source(source);
+        assert_eq!(report.findings, vec![], "{}", report);
+    }
+
+    #[test]
+    fn test_report_all_problems() {
+        let source = r#"
+        x = Constant{value = HostFloat32Tensor([1.0])}: () -> HostFloat32Tensor () @Host(alice)
+        y = Frobnicate: (HostFloat32Tensor) -> HostFloat32Tensor (x) @Host(alice)
+        z = Add: (HostFloat32Tensor, HostFloat64Tensor) -> HostFloat32Tensor (x, x) @Host(alice)
+        unused = Neg: (HostFloat32Tensor) -> HostFloat32Tensor (x) @Host(alice)
+        s = Send{rendezvous_key = 30313233343536373839616263646566, receiver = "bob"}: (HostFloat32Tensor) -> HostUnit (z) @Host(alice)
+        out = Output{tag = "out"}: (HostFloat32Tensor) -> HostFloat32Tensor (y) @Host(alice)
+        "#;
+        let report = validate_source(source);
+        let errors: Vec<_> = report
+            .errors()
+            .map(|f| (f.category, f.op_name.as_deref()))
+            .collect();
+        assert!(errors.contains(&(Category::Parsing, None)), "{}", report);
+        assert!(
+            errors.contains(&(Category::Structure, Some("out"))),
+            "{}",
+            report
+        );
+        assert!(
+            errors.contains(&(Category::Typing, Some("z"))),
+            "{}",
+            report
+        );
+        assert!(
+            errors.contains(&(Category::Placement, Some("s"))),
+            "{}",
+            report
+        );
+        assert!(
+            errors.contains(&(Category::Reachability, Some("out"))),
+            "{}",
+            report
+        );
+        assert!(report.warnings().any(
+            |f| f.category == Category::Reachability && f.op_name.as_deref() == Some("unused")
+        ));
+
+        let printed = report.to_string();
+        assert!(printed.find("Errors:").unwrap() < printed.find("Warnings:").unwrap());
+    }
+}
@@ -563,6 +563,9 @@ values![
     Float32Tensor,
     Float64Tensor,
     Uint64Tensor,
+    Uint8Tensor,
+    Int8Tensor,
+    Int16Tensor,
     ReplicatedRing64Tensor,
     ReplicatedRing128Tensor,
     ReplicatedBitTensor,
@@ -835,6 +838,7 @@ operators![
     Cast,
     Concat,
     Constant,
+    Conv2D,
     Decrypt,
     DeriveSeed,
     Div,
@@ -910,6 +914,13 @@ operators![
     // Mirrored Operators
     Demirror,
     Mirror,
+    // Differential privacy operators
+    GaussianNoise,
+    LaplaceNoise,
+    // Commitment operators
+    Commit,
+    OpenCommitment,
+    VerifyCommitment,
 ];
 
 pub trait HasShortName {
@@ -1111,6 +1122,20 @@ pub struct DotOp {
     pub sig: Signature,
 }
 
+/// Two-dimensional convolution of a `[batch, channels, height, width]` input
+/// with a `[out_channels, channels, kernel_height, kernel_width]` filter.
+///
+/// The input is zero-padded by `padding` on each side of both spatial axes,
+/// and the filter moved by `stride` along both of them.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, ToTextual, FromTextual,
+)]
+pub struct Conv2DOp {
+    pub sig: Signature,
+    pub stride: usize,
+    pub padding: usize,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, FromTextual)]
 pub struct MeanOp {
     pub sig: Signature,
@@ -1204,6 +1229,81 @@ pub struct SampleSeededOp {
     pub max_value: Option<u64>,
 }
 
+/// Hash-based commitment to a value under a nonce, which should be a fresh key.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, ToTextual, FromTextual,
+)]
+pub struct CommitOp {
+    pub sig: Signature,
+}
+
+/// Returns the committed value if it matches the commitment and nonce, and fails otherwise.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, ToTextual, FromTextual,
+)]
+pub struct OpenCommitmentOp {
+    pub sig: Signature,
+}
+
+/// Whether a value matches a commitment and nonce, as a single bit.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, ToTextual, FromTextual,
+)]
+pub struct VerifyCommitmentOp {
+    pub sig: Signature,
+}
+
+/// Adds noise drawn from the Laplace distribution with scale `sensitivity / epsilon`,
+/// making the result `epsilon`-differentially private.
+///
+/// Inputs are rounded to a fine grid and the noise is drawn from the discrete
+/// Laplace distribution over that grid, such that the floating-point
+/// representation of the result does not leak the input.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, ShortName)]
+pub struct LaplaceNoiseOp {
+    pub sig: Signature,
+    pub epsilon: f64,
+    pub sensitivity: f64,
+}
+
+impl std::cmp::Eq for LaplaceNoiseOp {}
+
+#[allow(clippy::derive_hash_xor_eq)]
+impl Hash for LaplaceNoiseOp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sig.hash(state);
+        self.epsilon.to_bits().hash(state);
+        self.sensitivity.to_bits().hash(state);
+    }
+}
+
+/// Adds noise drawn from the normal distribution with standard deviation
+/// `sensitivity * sqrt(2 ln(1.25 / delta)) / epsilon`, making the result
+/// `(epsilon, delta)`-differentially private. This bound only holds for
+/// `epsilon < 1`, so larger values are rejected.
+///
+/// Like [`LaplaceNoiseOp`], inputs are rounded to a fine grid and the noise is
+/// drawn from the discrete Gaussian distribution over that grid.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, ShortName)]
+pub struct GaussianNoiseOp {
+    pub sig: Signature,
+    pub epsilon: f64,
+    pub delta: f64,
+    pub sensitivity: f64,
+}
+
+impl std::cmp::Eq for GaussianNoiseOp {}
+
+#[allow(clippy::derive_hash_xor_eq)]
+impl Hash for GaussianNoiseOp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sig.hash(state);
+        self.epsilon.to_bits().hash(state);
+        self.delta.to_bits().hash(state);
+        self.sensitivity.to_bits().hash(state);
+    }
+}
+
 #[derive(
     Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, ShortName, ToTextual, FromTextual,
 )]
@@ -5,7 +5,7 @@ use crate::execution::Session;
 use crate::fixedpoint::FixedTensor;
 use crate::host::*;
 use crate::kernels::*;
-use crate::logical::AbstractTensor;
+use crate::logical::{AbstractTensor, AbstractTensorResult};
 use crate::replicated::*;
 use crate::types::*;
 use crate::{BitArray, N128, N224};
@@ -119,12 +119,25 @@ impl DecryptOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         key: HostAesKeyT,
         ciphertext: AbstractAesTensor<Fixed128AesT>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementDecrypt<S, HostAesKeyT, Fixed128AesT, Fixed128T>,
     {
@@ -189,12 +202,25 @@ impl DecryptOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         key: ReplicatedAesKeyT,
         ciphertext: AbstractAesTensor<Fixed128AesT>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementDecrypt<S, ReplicatedAesKeyT, Fixed128AesT, Fixed128T>,
     {
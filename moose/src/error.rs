@@ -21,6 +21,10 @@ pub enum Error {
     #[error("Operator instantiation not supported: {0}")]
     UnimplementedOperator(String),
 
+    /// Failure of a kernel, described by the kernel itself.
+    ///
+    /// Errors of kernels run in a session are wrapped in [`Error::Operation`],
+    /// which names the operation, operator, placement, and session.
     #[error("Kernel error: {0}")]
     KernelError(String),
 
@@ -45,9 +49,16 @@ pub enum Error {
     #[error("Networking error: {0}")]
     Networking(String),
 
+    /// Failure of a storage backend; wrapped in [`Error::Operation`] like [`Error::KernelError`].
     #[error("Storage error: {0}")]
     Storage(String),
 
+    #[error("Operation '{operation}' timed out: {reason}")]
+    Timeout { operation: String, reason: String },
+
+    #[error("Session cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Test runtime error: {0}")]
     TestRuntime(String),
 
@@ -56,6 +67,115 @@ pub enum Error {
 
     #[error("Failed to serialize computation: {0}")]
     SerializationError(String),
+
+    #[error(
+        "Operation '{}' ({}) on {} in session {} failed: {source}",
+        .context.op_name, .context.operator, .context.placement, .context.session_id
+    )]
+    Operation {
+        context: Box<OperationContext>,
+        source: Box<Error>,
+    },
+}
+
+/// Operation of a computation in which an error occurred.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationContext {
+    pub session_id: String,
+    pub op_name: String,
+    /// Short name of the operator, e.g. `Add`.
+    pub operator: String,
+    /// Placement of the operation in textual form, e.g. `@Host(alice)`.
+    pub placement: String,
+}
+
+impl Error {
+    /// Attribute `self` to the operation described by `context`.
+    ///
+    /// Errors already attributed to an operation, such as those of operands that
+    /// failed earlier, keep their original context.
+    pub fn in_operation(self, context: &OperationContext) -> Error {
+        match self {
+            Error::Operation { .. } => self,
+            source => Error::Operation {
+                context: Box::new(context.clone()),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Operation in which the error occurred, if known.
+    pub fn operation(&self) -> Option<&OperationContext> {
+        match self {
+            Error::Operation { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without the context of the operation in which it occurred.
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Operation { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(op_name: &str) -> OperationContext {
+        OperationContext {
+            session_id: "12345".to_string(),
+            op_name: op_name.to_string(),
+            operator: "Dot".to_string(),
+            placement: "@Host(alice)".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_operation_context_chain() {
+        let source = Error::KernelError("shapes do not match".to_string());
+        assert_eq!(source.operation(), None);
+        assert!(matches!(source.root_cause(), Error::KernelError(_)));
+
+        let err = source.in_operation(&context("z"));
+        assert_eq!(err.operation(), Some(&context("z")));
+        assert_eq!(
+            err.to_string(),
+            "Operation 'z' (Dot) on @Host(alice) in session 12345 failed: Kernel error: shapes do not match"
+        );
+        match err.root_cause() {
+            Error::KernelError(reason) => assert_eq!(reason, "shapes do not match"),
+            e => panic!("expected kernel error but got {:?}", e),
+        }
+
+        // errors propagating through later operations keep the original context
+        let err = err.in_operation(&context("w"));
+        assert_eq!(err.operation().map(|ctx| ctx.op_name.as_str()), Some("z"));
+
+        // nested contexts, e.g. from deserialized errors, are unwound to the root
+        let nested = Error::Operation {
+            context: Box::new(context("w")),
+            source: Box::new(err),
+        };
+        assert_eq!(
+            nested.operation().map(|ctx| ctx.op_name.as_str()),
+            Some("w")
+        );
+        assert!(matches!(nested.root_cause(), Error::KernelError(_)));
+    }
+
+    #[test]
+    fn test_operation_context_roundtrip() {
+        let err = Error::Storage("key not found".to_string()).in_operation(&context("x"));
+        let bytes = bincode::serialize(&err).unwrap();
+        let res: Error = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(res.operation(), Some(&context("x")));
+        assert!(matches!(res.root_cause(), Error::Storage(_)));
+        assert_eq!(res.to_string(), err.to_string());
+    }
+}
@@ -2,21 +2,36 @@
 
 use super::{RoleAssignment, RuntimeSession, Session, SetupGeneration};
 use crate::computation::*;
-use crate::error::{Error, Result};
+use crate::error::{Error, OperationContext, Result};
+use crate::execution::cache::{cache_key, is_cacheable, op_digest, KernelCache};
+use crate::execution::compute::{is_heavy, ComputePool};
+#[cfg(feature = "gpu")]
+use crate::execution::gpu::GpuBackend;
+use crate::execution::memory::{collect_operands, MemoryBudget, MemoryBudgetConfig};
+#[cfg(feature = "metrics")]
+use crate::execution::metrics::Metrics;
+use crate::execution::profiling::Profiler;
+use crate::execution::recording::{is_recorded, is_skipped_on_replay, Recorder, Replay};
+use crate::execution::scheduler::{Scheduler, SessionSlots};
+use crate::execution::scrub::{scrub_results, ResultHolders, ScrubSummary};
 use crate::execution::{Identity, Operands};
 use crate::host::{HostPrfKey, HostString};
-use crate::kernels::{DispatchKernel, Kernel};
+use crate::kernels::{DispatchKernel, Kernel, VariadicKernel};
 use crate::networking::{local::LocalAsyncNetworking, AsyncNetworking};
 use crate::replicated::{RepSetup, ReplicatedPlacement};
 use crate::storage::{local::LocalAsyncStorage, AsyncStorage};
+use crate::textual::ToTextual;
 use futures::future::{Map, Shared};
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
+use std::future::Future;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, Notify};
+use tracing::Instrument;
 
 pub(crate) type AsyncTask = tokio::task::JoinHandle<Result<()>>;
 
@@ -24,22 +39,96 @@ pub type AsyncNetworkingImpl = Arc<dyn AsyncNetworking + Send + Sync>;
 
 pub type AsyncStorageImpl = Arc<dyn AsyncStorage + Send + Sync>;
 
+/// Cancels a running session, possibly from another task than the one joining it.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    reason: Arc<parking_lot::Mutex<Option<String>>>,
+    notify: Arc<Notify>,
+}
+
+impl CancelToken {
+    /// Cancel the session; only the first reason given is kept.
+    pub fn cancel(&self, reason: &str) {
+        let mut current = self.reason.lock();
+        if current.is_none() {
+            *current = Some(reason.to_string());
+        }
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.reason.lock().is_some()
+    }
+
+    pub(crate) fn reason(&self) -> Option<String> {
+        self.reason.lock().clone()
+    }
+
+    /// Wait until the session is cancelled, returning the reason.
+    pub(crate) async fn cancelled(&self) -> String {
+        loop {
+            let notified = self.notify.notified();
+            if let Some(ref reason) = *self.reason.lock() {
+                return reason.clone();
+            }
+            notified.await;
+        }
+    }
+}
+
 pub struct AsyncSessionHandle {
     tasks: FuturesUnordered<AsyncTask>,
+    cancel_token: CancelToken,
+    scrubbed: Vec<AsyncValue>,
+    holders: ResultHolders,
 }
 
 impl AsyncSessionHandle {
-    pub async fn join_on_first_error(mut self) -> anyhow::Result<()> {
+    /// Zeroize `results` once the session has been joined and `holders` have released them.
+    pub(crate) fn with_scrubbing(self, results: Vec<AsyncValue>, holders: ResultHolders) -> Self {
+        AsyncSessionHandle {
+            scrubbed: results,
+            holders,
+            ..self
+        }
+    }
+
+    /// Token for cancelling the session while it is being joined.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token.clone()
+    }
+
+    /// Wait for all tasks of the session to finish.
+    ///
+    /// All remaining tasks are aborted on the first error or when the session is
+    /// cancelled, in which case an [`Error::Cancelled`] error is returned.
+    pub async fn join_on_first_error(self) -> anyhow::Result<()> {
+        self.join().await.0
+    }
+
+    /// Like `join_on_first_error`, also summarizing the scrubbing of the results
+    /// of a successful session.
+    pub(crate) async fn join(mut self) -> (anyhow::Result<()>, Option<ScrubSummary>) {
         use crate::error::Error::{OperandUnavailable, ResultUnused};
 
         let mut maybe_error = None;
-        while let Some(x) = self.tasks.next().await {
+        loop {
+            let x = tokio::select! {
+                reason = self.cancel_token.cancelled() => {
+                    maybe_error = Some(Err(anyhow::Error::from(Error::Cancelled(reason))));
+                    break;
+                }
+                x = self.tasks.next() => match x {
+                    Some(x) => x,
+                    None => break,
+                },
+            };
             match x {
                 Ok(Ok(_)) => {
                     continue;
                 }
                 Ok(Err(e)) => {
-                    match e {
+                    match e.root_cause() {
                         // OperandUnavailable and ResultUnused are typically not root causes.
                         // Wait to get an error that would indicate the root cause of the problem,
                         // and return it instead.
@@ -62,13 +151,49 @@ impl AsyncSessionHandle {
             }
         }
 
+        let results = std::mem::take(&mut self.scrubbed);
+        let holders = std::mem::take(&mut self.holders);
         if let Some(e) = maybe_error {
             for task in self.tasks.iter_mut() {
                 task.abort();
             }
-            e
+            // holders only release results once the aborted tasks are gone, which
+            // the error is not held back for; values still held by aborted tasks
+            // are left to the allocator
+            tokio::spawn(scrub_results(results, holders));
+            (e, None)
         } else {
-            Ok(())
+            (Ok(()), Some(scrub_results(results, holders).await))
+        }
+    }
+}
+
+/// Deadlines for individual operations in a session; `None` means wait forever.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OperationTimeouts {
+    /// Maximum time another party may stay silent while a value from it is
+    /// awaited; every value received from it restarts the timer.
+    pub receive: Option<Duration>,
+    /// Maximum time a compute operation may take once its operands are available.
+    pub compute: Option<Duration>,
+}
+
+/// Times at which a value was last received from each peer of a session.
+#[derive(Clone, Default)]
+pub(crate) struct PeerActivity(Arc<parking_lot::Mutex<HashMap<Identity, tokio::time::Instant>>>);
+
+impl PeerActivity {
+    fn heard_from(&self, peer: &Identity) {
+        self.0
+            .lock()
+            .insert(peer.clone(), tokio::time::Instant::now());
+    }
+
+    /// Time at which `peer` was last heard from, or `since` if later.
+    fn last_heard(&self, peer: &Identity, since: tokio::time::Instant) -> tokio::time::Instant {
+        match self.0.lock().get(peer) {
+            Some(heard) => std::cmp::max(*heard, since),
+            None => since,
         }
     }
 }
@@ -82,6 +207,19 @@ pub struct AsyncSession {
     pub networking: AsyncNetworkingImpl,
     pub storage: AsyncStorageImpl,
     pub tasks: Arc<Mutex<Option<FuturesUnordered<AsyncTask>>>>,
+    pub timeouts: OperationTimeouts,
+    pub(crate) peer_activity: PeerActivity,
+    pub memory_budget: Option<Arc<MemoryBudget>>,
+    pub compute_pool: Option<Arc<ComputePool>>,
+    pub recorder: Option<Arc<Recorder>>,
+    pub replay: Option<Arc<Replay>>,
+    pub cache: Option<Arc<KernelCache>>,
+    pub slots: Option<SessionSlots>,
+    pub profiler: Option<Arc<Profiler>>,
+    #[cfg(feature = "metrics")]
+    pub metrics: Option<Arc<Metrics>>,
+    #[cfg(feature = "gpu")]
+    pub gpu: Option<Arc<GpuBackend>>,
 }
 
 impl AsyncSession {
@@ -99,6 +237,98 @@ impl AsyncSession {
             networking,
             storage,
             tasks: Arc::new(Mutex::new(Some(Default::default()))),
+            timeouts: OperationTimeouts::default(),
+            peer_activity: PeerActivity::default(),
+            memory_budget: None,
+            compute_pool: None,
+            recorder: None,
+            replay: None,
+            cache: None,
+            slots: None,
+            profiler: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "gpu")]
+            gpu: None,
+        }
+    }
+
+    pub fn with_timeouts(self, timeouts: OperationTimeouts) -> Self {
+        AsyncSession { timeouts, ..self }
+    }
+
+    /// Run expensive kernels on `compute_pool` instead of the async runtime.
+    pub fn with_compute_pool(self, compute_pool: Arc<ComputePool>) -> Self {
+        AsyncSession {
+            compute_pool: Some(compute_pool),
+            ..self
+        }
+    }
+
+    /// Run supported kernels on `gpu` for the placements it was configured for.
+    #[cfg(feature = "gpu")]
+    pub fn with_gpu(self, gpu: Arc<GpuBackend>) -> Self {
+        AsyncSession {
+            gpu: Some(gpu),
+            ..self
+        }
+    }
+
+    /// Record everything needed to replay the session to `recorder`.
+    pub fn with_recorder(self, recorder: Arc<Recorder>) -> Self {
+        AsyncSession {
+            recorder: Some(recorder),
+            ..self
+        }
+    }
+
+    /// Profile every operation of the session with `profiler`.
+    pub fn with_profiler(self, profiler: Arc<Profiler>) -> Self {
+        AsyncSession {
+            profiler: Some(profiler),
+            ..self
+        }
+    }
+
+    /// Report the time spent running each kernel to `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, metrics: Arc<Metrics>) -> Self {
+        AsyncSession {
+            metrics: Some(metrics),
+            ..self
+        }
+    }
+
+    /// Replay a recorded session, without communicating with other parties.
+    pub fn with_replay(self, replay: Arc<Replay>) -> Self {
+        AsyncSession {
+            replay: Some(replay),
+            ..self
+        }
+    }
+
+    /// Reuse results of pure operations from `cache`, shared with other sessions.
+    pub fn with_cache(self, cache: Arc<KernelCache>) -> Self {
+        AsyncSession {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Take turns with other sessions of `scheduler` when running kernels.
+    pub fn with_scheduler(self, scheduler: &Scheduler) -> Self {
+        AsyncSession {
+            slots: Some(scheduler.session_slots()),
+            ..self
+        }
+    }
+
+    /// Spill operands to disk instead of exceeding the memory budget.
+    pub fn with_memory_budget(self, config: &MemoryBudgetConfig) -> Self {
+        let memory_budget = Some(Arc::new(MemoryBudget::new(config, &self.session_id)));
+        AsyncSession {
+            memory_budget,
+            ..self
         }
     }
 
@@ -143,13 +373,19 @@ impl AsyncSession {
                 "Session has been already converted into a handle".to_string(),
             )
         })?;
-        Ok(AsyncSessionHandle { tasks })
+        Ok(AsyncSessionHandle {
+            tasks,
+            cancel_token: CancelToken::default(),
+            scrubbed: Vec::new(),
+            holders: Vec::new(),
+        })
     }
 }
 
 impl AsyncSession {
     fn storage_load(
         &self,
+        context: &OperationContext,
         op: &LoadOp,
         plc: &Placement,
         operands: Operands<AsyncValue>,
@@ -162,7 +398,7 @@ impl AsyncSession {
             let expected_ty = op.sig.ret();
 
             let (sender, receiver) = new_channel();
-            let task = tokio::spawn(async move {
+            let task = tokio::spawn(in_operation(context.clone(), async move {
                 let mut operands = operands;
 
                 let query: HostString = operands
@@ -194,7 +430,7 @@ impl AsyncSession {
 
                 map_send_result(sender.send(value))?;
                 Ok(())
-            });
+            }));
             Self::add_task(&self.tasks, task)?;
             Ok(receiver)
         } else {
@@ -204,7 +440,12 @@ impl AsyncSession {
         }
     }
 
-    fn storage_save(&self, plc: &Placement, operands: Operands<AsyncValue>) -> Result<AsyncValue> {
+    fn storage_save(
+        &self,
+        context: &OperationContext,
+        plc: &Placement,
+        operands: Operands<AsyncValue>,
+    ) -> Result<AsyncValue> {
         assert_eq!(operands.len(), 2);
 
         if let Placement::Host(plc) = plc {
@@ -213,7 +454,7 @@ impl AsyncSession {
             let unit = Value::from(HostUnit(plc.clone()));
 
             let (sender, receiver) = new_channel();
-            let task = tokio::spawn(async move {
+            let task = tokio::spawn(in_operation(context.clone(), async move {
                 let mut operands = operands;
 
                 let x: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
@@ -229,7 +470,7 @@ impl AsyncSession {
 
                 map_send_result(sender.send(unit))?;
                 Ok(())
-            });
+            }));
             Self::add_task(&self.tasks, task)?;
             Ok(receiver)
         } else {
@@ -241,6 +482,7 @@ impl AsyncSession {
 
     fn networking_receive(
         &self,
+        context: &OperationContext,
         op: &ReceiveOp,
         plc: &Placement,
         operands: Operands<AsyncValue>,
@@ -253,12 +495,45 @@ impl AsyncSession {
             let rendezvous_key = op.rendezvous_key.clone();
             let networking = Arc::clone(&self.networking);
             let expected_ty = op.sig.ret();
+            let timeout = self.timeouts.receive;
+            let peer_activity = self.peer_activity.clone();
+            let op_name = context.op_name.clone();
+            let role = op.sender.clone();
 
             let (sender, receiver) = new_channel();
-            let task = tokio::spawn(async move {
-                let value = networking
-                    .receive(&networking_sender, &rendezvous_key, &session_id)
-                    .await?;
+            let task = tokio::spawn(in_operation(context.clone(), async move {
+                let started = tokio::time::Instant::now();
+                let receive = networking.receive(&networking_sender, &rendezvous_key, &session_id);
+                let value = match timeout {
+                    // the value may only be sent late in a long session, so this
+                    // only fails once the peer has sent nothing at all for a while
+                    Some(timeout) => {
+                        tokio::pin!(receive);
+                        loop {
+                            let deadline =
+                                peer_activity.last_heard(&networking_sender, started) + timeout;
+                            match tokio::time::timeout_at(deadline, &mut receive).await {
+                                Ok(value) => break value?,
+                                Err(_)
+                                    if peer_activity.last_heard(&networking_sender, started)
+                                        + timeout
+                                        <= tokio::time::Instant::now() =>
+                                {
+                                    return Err(Error::Timeout {
+                                        operation: op_name,
+                                        reason: format!(
+                                            "nothing received from {} (role {}) for '{}', nor anything else within {:?}",
+                                            networking_sender, role, rendezvous_key, timeout
+                                        ),
+                                    });
+                                }
+                                Err(_) => continue,
+                            }
+                        }
+                    }
+                    None => receive.await?,
+                };
+                peer_activity.heard_from(&networking_sender);
 
                 if value.ty() != expected_ty {
                     return Err(Error::TypeMismatch {
@@ -271,7 +546,7 @@ impl AsyncSession {
 
                 map_send_result(sender.send(value))?;
                 Ok(())
-            });
+            }));
             Self::add_task(&self.tasks, task)?;
             Ok(receiver)
         } else {
@@ -283,6 +558,7 @@ impl AsyncSession {
 
     fn networking_send(
         &self,
+        context: &OperationContext,
         op: &SendOp,
         plc: &Placement,
         operands: Operands<AsyncValue>,
@@ -297,7 +573,7 @@ impl AsyncSession {
             let unit = Value::from(HostUnit(plc.clone()));
 
             let (sender, receiver) = new_channel();
-            let task = tokio::spawn(async move {
+            let task = tokio::spawn(in_operation(context.clone(), async move {
                 let mut operands = operands;
 
                 let value = operands.pop().unwrap().await.map_err(map_receive_error)?;
@@ -308,7 +584,7 @@ impl AsyncSession {
 
                 map_send_result(sender.send(unit))?;
                 Ok(())
-            });
+            }));
             Self::add_task(&self.tasks, task)?;
             Ok(receiver)
         } else {
@@ -349,7 +625,14 @@ fn map_receive_error<T>(_: T) -> Error {
     Error::OperandUnavailable
 }
 
-fn new_channel() -> (Sender, Receiver) {
+/// Value that is available immediately, e.g. after being restored from a checkpoint.
+pub(crate) fn ready_value(value: Value) -> AsyncValue {
+    let (sender, receiver) = new_channel();
+    let _ = sender.send(value);
+    receiver
+}
+
+pub(crate) fn new_channel() -> (Sender, Receiver) {
     use futures::FutureExt;
 
     fn remove_err<T, E>(r: std::result::Result<T, E>) -> std::result::Result<T, ()> {
@@ -377,6 +660,7 @@ impl DispatchKernel<AsyncSession, Value> for Operator {
             AddN(op) => DispatchKernel::compile(op, plc),
             And(op) => DispatchKernel::compile(op, plc),
             Argmax(op) => DispatchKernel::compile(op, plc),
+            Conv2D(op) => DispatchKernel::compile(op, plc),
             AtLeast2D(op) => DispatchKernel::compile(op, plc),
             BitCompose(op) => DispatchKernel::compile(op, plc),
             BitDecompose(op) => DispatchKernel::compile(op, plc),
@@ -398,12 +682,17 @@ impl DispatchKernel<AsyncSession, Value> for Operator {
             Fill(op) => DispatchKernel::compile(op, plc),
             FixedpointDecode(op) => DispatchKernel::compile(op, plc),
             FixedpointEncode(op) => DispatchKernel::compile(op, plc),
+            GaussianNoise(op) => DispatchKernel::compile(op, plc),
             Greater(op) => DispatchKernel::compile(op, plc),
             Identity(op) => DispatchKernel::compile(op, plc),
             Index(op) => DispatchKernel::compile(op, plc),
             IndexAxis(op) => DispatchKernel::compile(op, plc),
             Input(op) => DispatchKernel::compile(op, plc),
             Inverse(op) => DispatchKernel::compile(op, plc),
+            LaplaceNoise(op) => DispatchKernel::compile(op, plc),
+            Commit(op) => DispatchKernel::compile(op, plc),
+            OpenCommitment(op) => DispatchKernel::compile(op, plc),
+            VerifyCommitment(op) => DispatchKernel::compile(op, plc),
             Less(op) => DispatchKernel::compile(op, plc),
             Log(op) => DispatchKernel::compile(op, plc),
             Log2(op) => DispatchKernel::compile(op, plc),
@@ -460,72 +749,258 @@ impl Session for AsyncSession {
         plc: &Placement,
         operands: Operands<Self::Value>,
     ) -> Result<Self::Value> {
+        self.execute_named(op.short_name(), op, plc, operands)
+    }
+}
+
+/// Fail with a timeout error naming `op_name` if `task` does not finish within `timeout`.
+async fn with_deadline<F>(op_name: String, timeout: Option<Duration>, task: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, task)
+            .await
+            .map_err(|_| Error::Timeout {
+                operation: op_name,
+                reason: format!("result not available within {:?}", timeout),
+            })?,
+        None => task.await,
+    }
+}
+
+/// Run `task` of the operation described by `context`, attributing its errors to the operation.
+///
+/// The task runs in a span of the operation, so that events of networking and
+/// storage are attributed to the operation as well.
+async fn in_operation<F>(context: OperationContext, task: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    let span = tracing::debug_span!(
+        "operation",
+        session_id = %context.session_id,
+        op = %context.op_name,
+        operator = %context.operator,
+        placement = %context.placement,
+    );
+    task.instrument(span)
+        .await
+        .map_err(|e| e.in_operation(&context))
+}
+
+impl AsyncSession {
+    /// Compile `op`, also returning whether the kernel blocks while waiting for a device.
+    fn compile_kernel(
+        &self,
+        op: &Operator,
+        plc: &Placement,
+    ) -> Result<(Kernel<AsyncSession, Value>, bool)> {
+        #[cfg(feature = "gpu")]
+        if let Some(ref gpu) = self.gpu {
+            if let Some(kernel) = gpu.compile(op, plc)? {
+                return Ok((kernel, true));
+            }
+        }
+        Ok((DispatchKernel::compile(op, plc)?, false))
+    }
+}
+
+/// Run `kernel` on `compute_pool` if given, on a blocking thread if it is `blocking`,
+/// and otherwise directly in the current task.
+async fn run_kernel<F>(
+    compute_pool: Option<Arc<ComputePool>>,
+    blocking: bool,
+    kernel: F,
+) -> Result<Value>
+where
+    F: FnOnce() -> Result<Value> + Send + 'static,
+{
+    match compute_pool {
+        Some(compute_pool) => compute_pool.run(kernel).await,
+        None if blocking => tokio::task::spawn_blocking(kernel)
+            .await
+            .map_err(|e| Error::KernelError(format!("blocking kernel failed: {}", e)))?,
+        None => kernel(),
+    }
+}
+
+impl AsyncSession {
+    /// Like [`Session::execute`] but uses `op_name` to identify the operation in errors.
+    pub fn execute_named(
+        &self,
+        op_name: &str,
+        op: &Operator,
+        plc: &Placement,
+        operands: Operands<AsyncValue>,
+    ) -> Result<AsyncValue> {
+        if let Some(ref replay) = self.replay {
+            if is_recorded(op) {
+                return Ok(ready_value(replay.value(op_name)?));
+            }
+            if is_skipped_on_replay(op) {
+                return match plc {
+                    Placement::Host(plc) => Ok(ready_value(Value::from(HostUnit(plc.clone())))),
+                    _ => Err(Error::UnimplementedOperator(format!(
+                        "{} is only supported on host placements",
+                        op.short_name()
+                    ))),
+                };
+            }
+        }
+
+        let context = OperationContext {
+            session_id: self.session_id.to_string(),
+            op_name: op_name.to_string(),
+            operator: op.short_name().to_string(),
+            placement: plc.to_textual(),
+        };
+        let profiled_operands = self.profiler.as_ref().map(|_| operands.clone());
+        let result = self
+            .execute_kernel(&context, op, plc, operands)
+            .map_err(|e| e.in_operation(&context))?;
+
+        if let (Some(profiler), Some(operands)) = (&self.profiler, profiled_operands) {
+            let task = profiler.watch(op_name, op, plc, operands, result.clone());
+            Self::add_task(&self.tasks, task)?;
+        }
+
+        if let (Some(recorder), true) = (&self.recorder, is_recorded(op)) {
+            let recorder = Arc::clone(recorder);
+            let op_name = op_name.to_string();
+            let result = result.clone();
+            let task = tokio::spawn(in_operation(context, async move {
+                if let Ok(value) = result.await {
+                    recorder.record(&op_name, &value)?;
+                }
+                Ok(())
+            }));
+            Self::add_task(&self.tasks, task)?;
+        }
+        Ok(result)
+    }
+
+    fn execute_kernel(
+        &self,
+        context: &OperationContext,
+        op: &Operator,
+        plc: &Placement,
+        operands: Operands<AsyncValue>,
+    ) -> Result<AsyncValue> {
         use Operator::*;
-        let kernel: Kernel<AsyncSession, _> = match op {
+        let (kernel, blocking): (Kernel<AsyncSession, _>, bool) = match op {
             // Kernels that take advantage of async
-            Load(op) => return self.storage_load(op, plc, operands),
-            Save(_) => return self.storage_save(plc, operands),
-            Send(op) => return self.networking_send(op, plc, operands),
-            Receive(op) => return self.networking_receive(op, plc, operands),
+            Load(op) => return self.storage_load(context, op, plc, operands),
+            Save(_) => return self.storage_save(context, plc, operands),
+            Send(op) => return self.networking_send(context, op, plc, operands),
+            Receive(op) => return self.networking_receive(context, op, plc, operands),
             // Compute kernels
-            op => DispatchKernel::compile(op, plc),
+            op => self.compile_kernel(op, plc),
         }?;
 
-        let (sender, receiver) = new_channel();
-        let sess = self.clone();
-        let plc = plc.clone();
-
-        let task = match kernel {
+        // all kernels take their operands as a vector so they can be run the same way
+        let closure: VariadicKernel<AsyncSession, Value> = match kernel {
             Kernel::Nullary { closure } => {
                 assert_eq!(operands.len(), 0);
-                tokio::spawn(async move {
-                    let y: Value = closure(&sess, &plc)?;
-                    map_send_result(sender.send(y))?;
-                    Ok(())
-                })
+                Box::new(move |sess, plc, _| closure(sess, plc))
             }
             Kernel::Unary { closure } => {
                 assert_eq!(operands.len(), 1);
-                tokio::spawn(async move {
-                    let mut operands = operands;
-                    let x0: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let y: Value = closure(&sess, &plc, x0)?;
-                    map_send_result(sender.send(y))?;
-                    Ok(())
+                Box::new(move |sess, plc, mut xs| {
+                    let x0 = xs.pop().unwrap();
+                    closure(sess, plc, x0)
                 })
             }
             Kernel::Binary { closure } => {
                 assert_eq!(operands.len(), 2);
-                tokio::spawn(async move {
-                    let mut operands = operands;
-                    let x1: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let x0: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let y: Value = closure(&sess, &plc, x0, x1)?;
-                    map_send_result(sender.send(y))?;
-                    Ok(())
+                Box::new(move |sess, plc, mut xs| {
+                    let x1 = xs.pop().unwrap();
+                    let x0 = xs.pop().unwrap();
+                    closure(sess, plc, x0, x1)
                 })
             }
             Kernel::Ternary { closure } => {
                 assert_eq!(operands.len(), 3);
-                tokio::spawn(async move {
-                    let mut operands = operands;
-                    let x2: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let x1: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let x0: Value = operands.pop().unwrap().await.map_err(map_receive_error)?;
-                    let y: Value = closure(&sess, &plc, x0, x1, x2)?;
-                    map_send_result(sender.send(y))?;
-                    Ok(())
+                Box::new(move |sess, plc, mut xs| {
+                    let x2 = xs.pop().unwrap();
+                    let x1 = xs.pop().unwrap();
+                    let x0 = xs.pop().unwrap();
+                    closure(sess, plc, x0, x1, x2)
                 })
             }
-            Kernel::Variadic { closure } => tokio::spawn(async move {
-                let operands = futures::future::join_all(operands).await;
-                let xs: std::result::Result<Operands<Value>, _> = operands.into_iter().collect();
-                let xs = xs.map_err(map_receive_error)?;
-                let y: Value = closure(&sess, &plc, xs)?;
+            Kernel::Variadic { closure } => closure,
+        };
+
+        let cache = match self.cache {
+            Some(ref cache) if is_cacheable(op, plc) => {
+                Some((Arc::clone(cache), op_digest(op, plc)?))
+            }
+            _ => None,
+        };
+
+        let (sender, receiver) = new_channel();
+        let sess = self.clone();
+        let plc = plc.clone();
+        let op_name = context.op_name.clone();
+        let timeout = self.timeouts.compute;
+        // kernels waiting for a device block their thread however cheap they are
+        let compute_pool = self
+            .compute_pool
+            .clone()
+            .filter(|_| blocking || is_heavy(op));
+        let slots = self.slots.clone();
+        let profiler = self
+            .profiler
+            .clone()
+            .map(|profiler| (profiler, op_name.clone()));
+        #[cfg(feature = "metrics")]
+        let metrics = self
+            .metrics
+            .clone()
+            .map(|metrics| (metrics, context.operator.clone()));
+
+        let task = tokio::spawn(in_operation(context.clone(), async move {
+            let (xs, _reservations) =
+                collect_operands(operands, sess.memory_budget.as_ref()).await?;
+            // operands may take arbitrarily long in a long session, so the
+            // deadline only starts once they are available
+            with_deadline(op_name, timeout, async move {
+                let cache = match cache {
+                    Some((cache, op)) => Some((cache, cache_key(&op, &xs)?)),
+                    None => None,
+                };
+                if let Some((ref cache, ref key)) = cache {
+                    if let Some(y) = cache.get(key) {
+                        map_send_result(sender.send(y))?;
+                        return Ok(());
+                    }
+                }
+
+                // slots are only taken once operands are available, since waiting
+                // for them while holding a slot could prevent them from being computed
+                let slot = match slots {
+                    Some(slots) => Some(slots.acquire().await?),
+                    None => None,
+                };
+                let kernel_start = Instant::now();
+                let y =
+                    run_kernel(compute_pool, blocking, move || closure(&sess, &plc, xs)).await?;
+                drop(slot);
+                if let Some((profiler, op_name)) = profiler {
+                    profiler.record_kernel_time(&op_name, kernel_start.elapsed());
+                }
+                #[cfg(feature = "metrics")]
+                if let Some((metrics, operator)) = metrics {
+                    metrics.record_kernel_time(&operator, kernel_start.elapsed());
+                }
+                if let Some((cache, key)) = cache {
+                    cache.insert(key, &y)?;
+                }
                 map_send_result(sender.send(y))?;
                 Ok(())
-            }),
-        };
+            })
+            .await
+        }));
         Self::add_task(&self.tasks, task)?;
         Ok(receiver)
     }
@@ -614,11 +1089,7 @@ impl AsyncExecutor {
                 .iter()
                 .map(|input_name| env.get(input_name).unwrap().clone())
                 .collect();
-            let value = session
-                .execute(&op.kind, &op.placement, operands)
-                .map_err(|e| {
-                    Error::KernelError(format!("AsyncSession failed due to an error: {:?}", e,))
-                })?;
+            let value = session.execute_named(&op.name, &op.kind, &op.placement, operands)?;
             if let Operator::Output(output_op) = &op.kind {
                 // If it is an output, we need to make sure we capture it for returning.
                 outputs.insert(output_op.tag.clone(), value.clone());
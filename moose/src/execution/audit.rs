@@ -0,0 +1,385 @@
+//! Tamper-evident audit log of sessions.
+//!
+//! Each worker can append the sessions it is asked to run, together with a digest
+//! of their computation and their role assignment, to an append-only log. It also
+//! records every value handed to one of its host placements through an output or
+//! save operation, and every value it receives from another party.
+//!
+//! Reveals are lowered into receive operations followed by additions on the host
+//! learning the value, which cannot be told apart from other operations on
+//! shares. Recording all received values instead covers everything a reveal hands
+//! to the worker, whether or not the computation marks it as such.
+//!
+//! Entries are stored as JSON lines, each including a MAC of the entry and of the
+//! MAC of the entry before it, keyed with a secret key of the worker. Removing or
+//! changing entries breaks the chain and is detected when verifying the log with
+//! the same key; without the key the chain cannot be rewritten.
+
+use crate::computation::{Computation, Placement, Role, SessionId, Value};
+use crate::error::{Error, Result};
+use crate::execution::{AsyncValue, RoleAssignment};
+use crate::textual::ToTextual;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// MAC preceding the first entry of a log.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "kind")]
+pub enum AuditEvent {
+    SessionLaunched {
+        session_id: String,
+        /// Hex-encoded BLAKE3 digest of the serialized computation.
+        computation: String,
+        role_assignment: BTreeMap<String, String>,
+        launched_by: Option<String>,
+    },
+    /// A value handed to the host placement of an output or save operation.
+    ValueRevealed {
+        session_id: String,
+        op_name: String,
+        placement: String,
+        ty: String,
+        /// Hex-encoded BLAKE3 digest of the serialized value.
+        digest: String,
+    },
+    /// A value received from the party playing `sender`, such as a share being revealed.
+    ValueReceived {
+        session_id: String,
+        op_name: String,
+        sender: String,
+        placement: String,
+        ty: String,
+        /// Hex-encoded BLAKE3 digest of the serialized value.
+        digest: String,
+    },
+    SessionFinished {
+        session_id: String,
+        error: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// Milliseconds since the Unix epoch.
+    pub time: u64,
+    /// MAC of the previous entry.
+    pub prev: String,
+    pub event: AuditEvent,
+    /// MAC of this entry, covering all fields above.
+    pub hash: String,
+}
+
+fn audit_error(e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("audit log: {}", e))
+}
+
+/// Secret key used to authenticate the entries of an audit log.
+#[derive(Clone)]
+pub struct AuditKey([u8; 32]);
+
+impl AuditKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        AuditKey(key)
+    }
+
+    /// Read a key from a file holding exactly 32 bytes.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(audit_error)?;
+        let key = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+            Error::InvalidArgument(format!(
+                "audit key must consist of 32 bytes, got {}",
+                bytes.len()
+            ))
+        })?;
+        Ok(AuditKey(key))
+    }
+}
+
+fn entry_hash(
+    key: &AuditKey,
+    seq: u64,
+    time: u64,
+    prev: &str,
+    event: &AuditEvent,
+) -> Result<String> {
+    let content = serde_json::to_vec(&(seq, time, prev, event)).map_err(audit_error)?;
+    Ok(blake3::keyed_hash(&key.0, &content).to_hex().to_string())
+}
+
+fn digest<T: Serialize>(value: &T) -> Result<String> {
+    let bytes = bincode::serialize(value).map_err(audit_error)?;
+    Ok(blake3::hash(&bytes).to_hex().to_string())
+}
+
+/// Digest of `computation` as recorded in the log.
+pub fn computation_digest(computation: &Computation) -> Result<String> {
+    digest(computation)
+}
+
+/// Check the MAC chain of the log at `path` under `key`, returning its entries.
+pub fn verify_audit_log(path: &Path, key: &AuditKey) -> Result<Vec<AuditEntry>> {
+    let file = File::open(path).map_err(audit_error)?;
+    let mut entries = Vec::new();
+    let mut prev = GENESIS.to_string();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(audit_error)?;
+        let entry: AuditEntry = serde_json::from_str(&line)
+            .map_err(|e| audit_error(format!("malformed entry on line {}: {}", index + 1, e)))?;
+        if entry.seq != index as u64 || entry.prev != prev {
+            return Err(audit_error(format!(
+                "chain broken at line {}, entries were removed or reordered",
+                index + 1
+            )));
+        }
+        if entry.hash != entry_hash(key, entry.seq, entry.time, &entry.prev, &entry.event)? {
+            return Err(audit_error(format!(
+                "entry on line {} was modified or the log was written with another key",
+                index + 1
+            )));
+        }
+        prev = entry.hash.clone();
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+struct AuditState {
+    file: File,
+    seq: u64,
+    prev: String,
+}
+
+/// Append-only, MAC-chained log of sessions and the values revealed in them.
+pub struct AuditLog {
+    path: PathBuf,
+    key: AuditKey,
+    state: Mutex<AuditState>,
+}
+
+impl AuditLog {
+    /// Open the log at `path`, continuing its chain if it exists after verifying it under `key`.
+    pub fn open(path: &Path, key: AuditKey) -> Result<Self> {
+        let (seq, prev) = if path.exists() {
+            let entries = verify_audit_log(path, &key)?;
+            match entries.last() {
+                Some(last) => (last.seq + 1, last.hash.clone()),
+                None => (0, GENESIS.to_string()),
+            }
+        } else {
+            (0, GENESIS.to_string())
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(audit_error)?;
+        Ok(AuditLog {
+            path: path.to_path_buf(),
+            key,
+            state: Mutex::new(AuditState { file, seq, prev }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, event: AuditEvent) -> Result<()> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(audit_error)?
+            .as_millis() as u64;
+        let mut state = self.state.lock();
+        let hash = entry_hash(&self.key, state.seq, time, &state.prev, &event)?;
+        let entry = AuditEntry {
+            seq: state.seq,
+            time,
+            prev: state.prev.clone(),
+            event,
+            hash: hash.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry).map_err(audit_error)?;
+        line.push(b'\n');
+        state.file.write_all(&line).map_err(audit_error)?;
+        state.file.sync_data().map_err(audit_error)?;
+        state.seq += 1;
+        state.prev = hash;
+        Ok(())
+    }
+
+    pub fn session_launched(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        role_assignment: &RoleAssignment,
+        launched_by: Option<String>,
+    ) -> Result<()> {
+        self.append(AuditEvent::SessionLaunched {
+            session_id: session_id.to_string(),
+            computation: computation_digest(computation)?,
+            role_assignment: role_assignment
+                .iter()
+                .map(|(role, identity)| (role.to_string(), identity.to_string()))
+                .collect(),
+            launched_by,
+        })
+    }
+
+    pub fn session_finished(&self, session_id: &SessionId, error: Option<String>) -> Result<()> {
+        self.append(AuditEvent::SessionFinished {
+            session_id: session_id.to_string(),
+            error,
+        })
+    }
+
+    /// Record `value` once available as revealed to `plc` by operation `op_name`.
+    pub(crate) fn watch_reveal(
+        self: &Arc<Self>,
+        session_id: &SessionId,
+        op_name: &str,
+        plc: &Placement,
+        value: AsyncValue,
+    ) {
+        let session_id = session_id.to_string();
+        let op_name = op_name.to_string();
+        let placement = plc.to_textual();
+        self.watch(value, move |ty, digest| AuditEvent::ValueRevealed {
+            session_id,
+            op_name,
+            placement,
+            ty,
+            digest,
+        });
+    }
+
+    /// Record `value` once available as received by `plc` from `sender` in operation `op_name`.
+    pub(crate) fn watch_receive(
+        self: &Arc<Self>,
+        session_id: &SessionId,
+        op_name: &str,
+        sender: &Role,
+        plc: &Placement,
+        value: AsyncValue,
+    ) {
+        let session_id = session_id.to_string();
+        let op_name = op_name.to_string();
+        let sender = sender.to_string();
+        let placement = plc.to_textual();
+        self.watch(value, move |ty, digest| AuditEvent::ValueReceived {
+            session_id,
+            op_name,
+            sender,
+            placement,
+            ty,
+            digest,
+        });
+    }
+
+    /// Append the event built from the type and digest of `value` once it is available.
+    fn watch<F>(self: &Arc<Self>, value: AsyncValue, event: F)
+    where
+        F: FnOnce(String, String) -> AuditEvent + Send + 'static,
+    {
+        let audit = Arc::clone(self);
+        tokio::spawn(async move {
+            // values of failed operations never reach the worker
+            let value: Value = match value.await {
+                Ok(value) => value,
+                Err(_) => return,
+            };
+            let event = digest(&value).map(|digest| event(value.ty().to_textual(), digest));
+            if let Err(e) = event.and_then(|event| audit.append(event)) {
+                tracing::error!("Failed to record revealed value: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::Identity;
+
+    #[test]
+    fn test_audit_log_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let session_id = SessionId::try_from("my-session").unwrap();
+        let computation = Computation { operations: vec![] };
+        let role_assignment: RoleAssignment = vec![(Role::from("alice"), Identity::from("w0"))]
+            .into_iter()
+            .collect();
+
+        let key = AuditKey::new([7; 32]);
+
+        let log = AuditLog::open(&path, key.clone()).unwrap();
+        log.session_launched(&session_id, &computation, &role_assignment, None)
+            .unwrap();
+        drop(log);
+
+        // reopening continues the chain
+        let log = AuditLog::open(&path, key.clone()).unwrap();
+        log.session_finished(&session_id, None).unwrap();
+        let entries = verify_audit_log(&path, &key).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev, entries[0].hash);
+
+        // the chain cannot be verified, nor rewritten, without the key
+        assert!(verify_audit_log(&path, &AuditKey::new([8; 32])).is_err());
+        assert!(AuditLog::open(&path, AuditKey::new([8; 32])).is_err());
+
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("\"error\":null", "\"error\":\"oops\"");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(verify_audit_log(&path, &key).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_audit_received_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let key = AuditKey::new([7; 32]);
+        let log = Arc::new(AuditLog::open(&path, key.clone()).unwrap());
+
+        let session_id = SessionId::try_from("my-session").unwrap();
+        let plc = Placement::Host(crate::host::HostPlacement::from("alice"));
+        let value = Value::HostUnit(Box::new(crate::computation::HostUnit(
+            crate::host::HostPlacement::from("alice"),
+        )));
+        log.watch_receive(
+            &session_id,
+            "receive_0",
+            &Role::from("bob"),
+            &plc,
+            crate::execution::ready_value(value.clone()),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let entries = verify_audit_log(&path, &key).unwrap();
+        assert_eq!(entries.len(), 1);
+        match &entries[0].event {
+            AuditEvent::ValueReceived {
+                op_name,
+                sender,
+                digest: value_digest,
+                ..
+            } => {
+                assert_eq!(op_name, "receive_0");
+                assert_eq!(sender, "bob");
+                assert_eq!(value_digest, &digest(&value).unwrap());
+            }
+            event => panic!("expected received value but got {:?}", event),
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Cache of kernel results shared between sessions.
+//!
+//! Repeated sessions over the same model frequently perform identical work on
+//! identical values, such as encoding the same weights. Results of pure
+//! operations are cached under a digest of the operation together with digests
+//! of its operands, so that later sessions can skip recomputing them.
+
+use crate::computation::{Operator, Placement, Ty, Value};
+use crate::error::{Error, Result};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub(crate) type Digest = [u8; 32];
+
+fn digest_error(e: impl std::fmt::Display) -> Error {
+    Error::KernelError(format!("failed to compute cache key: {}", e))
+}
+
+/// Whether results of `op` on `plc` may be cached and reused by other sessions.
+///
+/// Only deterministic host kernels whose results do not depend on the session are
+/// cached, which rules out randomness, noise, seeds, and communication. Results that
+/// may be secret shares, ring and bit tensors as well as seeds and keys, are never
+/// cached, since they would otherwise outlive the session holding them.
+pub(crate) fn is_cacheable(op: &Operator, plc: &Placement) -> bool {
+    use Operator::*;
+    let deterministic = matches!(
+        op,
+        Abs(_)
+            | Add(_)
+            | AtLeast2D(_)
+            | Broadcast(_)
+            | Cast(_)
+            | Concat(_)
+            | Diag(_)
+            | Div(_)
+            | Dot(_)
+            | Exp(_)
+            | ExpandDims(_)
+            | IndexAxis(_)
+            | Inverse(_)
+            | Mean(_)
+            | Mul(_)
+            | Neg(_)
+            | Ones(_)
+            | Relu(_)
+            | Reshape(_)
+            | Shape(_)
+            | Sigmoid(_)
+            | Slice(_)
+            | Sqrt(_)
+            | Squeeze(_)
+            | Sub(_)
+            | Sum(_)
+            | Transpose(_)
+            | Zeros(_)
+    );
+    let plaintext = matches!(
+        op.sig().ret(),
+        Ty::HostFloat32Tensor
+            | Ty::HostFloat64Tensor
+            | Ty::HostInt8Tensor
+            | Ty::HostInt16Tensor
+            | Ty::HostInt32Tensor
+            | Ty::HostInt64Tensor
+            | Ty::HostUint8Tensor
+            | Ty::HostUint16Tensor
+            | Ty::HostUint32Tensor
+            | Ty::HostUint64Tensor
+            | Ty::HostShape
+    );
+    matches!(plc, Placement::Host(_)) && deterministic && plaintext
+}
+
+/// Digest identifying `op` on `plc` independently of the session.
+pub(crate) fn op_digest(op: &Operator, plc: &Placement) -> Result<Digest> {
+    let mut hasher = blake3::Hasher::new();
+    bincode::serialize_into(&mut hasher, &(op, plc)).map_err(digest_error)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Key under which the result of an operation with digest `op` applied to `operands` is cached.
+pub(crate) fn cache_key(op: &Digest, operands: &[Value]) -> Result<Digest> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(op);
+    for operand in operands {
+        let mut operand_hasher = blake3::Hasher::new();
+        bincode::serialize_into(&mut operand_hasher, operand).map_err(digest_error)?;
+        hasher.update(operand_hasher.finalize().as_bytes());
+    }
+    Ok(hasher.finalize().into())
+}
+
+struct CacheEntry {
+    value: Value,
+    num_bytes: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<Digest, CacheEntry>,
+    by_last_use: BTreeMap<u64, Digest>,
+    used: usize,
+    clock: u64,
+}
+
+/// Kernel results kept within a capacity, evicting the least recently used first.
+pub struct KernelCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl KernelCache {
+    /// Create a cache holding at most `capacity` bytes of values.
+    pub fn new(capacity: usize) -> Self {
+        KernelCache {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of bytes of values currently cached.
+    pub fn used(&self) -> usize {
+        self.state.lock().used
+    }
+
+    /// Number of lookups that did and did not find a cached result, respectively.
+    pub fn hits_and_misses(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    pub(crate) fn get(&self, key: &Digest) -> Option<Value> {
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        state.clock += 1;
+        match state.entries.get_mut(key) {
+            Some(entry) => {
+                state.by_last_use.remove(&entry.last_used);
+                entry.last_used = state.clock;
+                state.by_last_use.insert(entry.last_used, *key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entry.value.clone())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&self, key: Digest, value: &Value) -> Result<()> {
+        let num_bytes = bincode::serialized_size(value).map_err(digest_error)? as usize;
+        if num_bytes > self.capacity {
+            return Ok(());
+        }
+
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        if state.entries.contains_key(&key) {
+            return Ok(());
+        }
+        while state.used + num_bytes > self.capacity {
+            let oldest = match state.by_last_use.values().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            if let Some(evicted) = state.entries.remove(&oldest) {
+                state.by_last_use.remove(&evicted.last_used);
+                state.used -= evicted.num_bytes;
+            }
+        }
+
+        state.clock += 1;
+        state.by_last_use.insert(state.clock, key);
+        state.used += num_bytes;
+        state.entries.insert(
+            key,
+            CacheEntry {
+                value: value.clone(),
+                num_bytes,
+                last_used: state.clock,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::{AddOp, DeriveSeedOp, LaplaceNoiseOp, Signature, SyncKey};
+    use crate::host::{HostPlacement, HostTensor};
+    use crate::replicated::ReplicatedPlacement;
+    use ndarray::ArrayD;
+    use std::convert::TryFrom;
+
+    fn tensor(len: usize, x: f64) -> Value {
+        let plc = HostPlacement::from("alice");
+        let x = HostTensor::<f64>(ArrayD::from_elem(vec![len], x).into_shared(), plc);
+        Value::HostFloat64Tensor(Box::new(x))
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let op = [0; 32];
+        let key = cache_key(&op, &[tensor(10, 1.0)]).unwrap();
+        assert_eq!(key, cache_key(&op, &[tensor(10, 1.0)]).unwrap());
+        assert_ne!(key, cache_key(&op, &[tensor(10, 2.0)]).unwrap());
+        assert_ne!(key, cache_key(&[1; 32], &[tensor(10, 1.0)]).unwrap());
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let size = bincode::serialized_size(&tensor(10, 0.0)).unwrap() as usize;
+        let cache = KernelCache::new(2 * size);
+
+        cache.insert([0; 32], &tensor(10, 0.0)).unwrap();
+        cache.insert([1; 32], &tensor(10, 1.0)).unwrap();
+        assert!(cache.get(&[0; 32]).is_some());
+        cache.insert([2; 32], &tensor(10, 2.0)).unwrap();
+
+        assert_eq!(cache.get(&[0; 32]), Some(tensor(10, 0.0)));
+        assert_eq!(cache.get(&[1; 32]), None);
+        assert_eq!(cache.get(&[2; 32]), Some(tensor(10, 2.0)));
+        assert_eq!(cache.used(), 2 * size);
+        assert_eq!(cache.hits_and_misses(), (3, 1));
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        let alice: Placement = HostPlacement::from("alice").into();
+        let rep: Placement = ReplicatedPlacement::from(["alice", "bob", "carole"]).into();
+        let add = |ty| -> Operator {
+            AddOp {
+                sig: Signature::binary(ty, ty, ty),
+            }
+            .into()
+        };
+
+        assert!(is_cacheable(&add(Ty::HostFloat64Tensor), &alice));
+        // ring tensors may be shares, which must not outlive their session
+        assert!(!is_cacheable(&add(Ty::HostRing128Tensor), &alice));
+        assert!(!is_cacheable(&add(Ty::HostFloat64Tensor), &rep));
+
+        let noise: Operator = LaplaceNoiseOp {
+            sig: Signature::unary(Ty::HostFloat64Tensor, Ty::HostFloat64Tensor),
+            epsilon: 1.0,
+            sensitivity: 1.0,
+        }
+        .into();
+        assert!(!is_cacheable(&noise, &alice));
+
+        let seed: Operator = DeriveSeedOp {
+            sig: Signature::unary(Ty::HostPrfKey, Ty::HostSeed),
+            sync_key: SyncKey::try_from(vec![1, 2, 3]).unwrap(),
+        }
+        .into();
+        assert!(!is_cacheable(&seed, &alice));
+    }
+}
@@ -0,0 +1,417 @@
+//! Checkpointing of completed operation results, allowing sessions to be resumed.
+//!
+//! Results are recorded as operations complete and flushed to disk at instants
+//! aligned to multiples of the checkpoint interval since the Unix epoch. Each
+//! flush is identified by its instant, and contains every result completed
+//! before it. Since all parties flush at the same instants, and a value is
+//! always computed before it is received, resuming every party from the same
+//! checkpoint gives a consistent state as long as their clocks are synchronized
+//! to well within the interval.
+//!
+//! Results of send operations are never checkpointed; values are instead sent
+//! again when resuming in case the receiver did not checkpoint them.
+//!
+//! Resuming from a checkpoint discards everything recorded after it, and
+//! starting a session afresh discards all of its checkpoints, such that the
+//! results of a session always form a single chain. Otherwise results of an
+//! abandoned run, possibly computed using different randomness than the peers
+//! used, could be mixed into a later resumption.
+//!
+//! Checkpoints hold results as they are, which includes the secret shares held
+//! by the party. They should therefore be written to a directory that is only
+//! accessible to the worker, ideally on an encrypted volume.
+
+use crate::computation::{SessionId, Value};
+use crate::error::{Error, Result};
+use crate::execution::AsyncValue;
+use crate::networking::wire;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LOG_FILE: &str = "log";
+const CHECKPOINTS_FILE: &str = "checkpoints";
+
+/// Configuration of session checkpointing.
+#[derive(Clone, Debug)]
+pub struct CheckpointConfig {
+    /// Directory under which each session keeps its checkpoints.
+    pub dir: PathBuf,
+    /// Time between checkpoints.
+    pub interval: Duration,
+}
+
+impl CheckpointConfig {
+    fn session_dir(&self, session_id: &SessionId) -> PathBuf {
+        let name: String = session_id
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        self.dir.join(name)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct LogEntry {
+    op_name: String,
+    file: String,
+    checkpoint: u64,
+}
+
+fn storage_error(e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("checkpoint error: {}", e))
+}
+
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Next checkpoint instant after `now`, in milliseconds since the epoch.
+fn next_checkpoint(now: u64, interval_millis: u64) -> u64 {
+    (now / interval_millis + 1) * interval_millis
+}
+
+/// Records the results of a single session for checkpointing.
+///
+/// Results are recorded for as long as there are operations being watched.
+pub struct Checkpointer {
+    dir: PathBuf,
+    interval_millis: u64,
+    pending: Mutex<Vec<(String, Value)>>,
+    next_file: Mutex<u64>,
+}
+
+impl Checkpointer {
+    /// Start checkpointing `session_id`, flushing every `config.interval` until dropped.
+    pub fn start(config: &CheckpointConfig, session_id: &SessionId) -> Result<Arc<Self>> {
+        let dir = config.session_dir(session_id);
+        std::fs::create_dir_all(&dir).map_err(storage_error)?;
+        let mut next_file = 0;
+        for entry in std::fs::read_dir(&dir).map_err(storage_error)? {
+            let name = entry.map_err(storage_error)?.file_name();
+            let number = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(".value"))
+                .and_then(|number| number.parse().ok());
+            if let Some(number) = number {
+                next_file = std::cmp::max(next_file, number);
+            }
+        }
+        let checkpointer = Arc::new(Checkpointer {
+            dir,
+            interval_millis: std::cmp::max(config.interval.as_millis() as u64, 1),
+            pending: Mutex::new(Vec::new()),
+            next_file: Mutex::new(next_file),
+        });
+        spawn_flush_loop(Arc::downgrade(&checkpointer), session_id);
+        Ok(checkpointer)
+    }
+
+    /// Record the result of operation `op_name` once it becomes available.
+    pub(crate) fn watch(self: &Arc<Self>, op_name: String, value: AsyncValue) {
+        let checkpointer = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Ok(value) = value.await {
+                checkpointer.pending.lock().push((op_name, value));
+            }
+        });
+    }
+
+    /// Write all results recorded so far as part of checkpoint `checkpoint`.
+    fn flush(&self, checkpoint: u64) -> Result<usize> {
+        let pending: Vec<_> = std::mem::take(&mut *self.pending.lock());
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(LOG_FILE))
+            .map_err(storage_error)?;
+        let mut log = BufWriter::new(log);
+        for (op_name, value) in pending.iter() {
+            let file = {
+                let mut next_file = self.next_file.lock();
+                *next_file += 1;
+                format!("{}.value", next_file)
+            };
+            std::fs::write(self.dir.join(&file), wire::serialize_value(value)?)
+                .map_err(storage_error)?;
+            let entry = LogEntry {
+                op_name: op_name.clone(),
+                file,
+                checkpoint,
+            };
+            bincode::serialize_into(&mut log, &entry).map_err(storage_error)?;
+        }
+        let log = log.into_inner().map_err(storage_error)?;
+        log.sync_all().map_err(storage_error)?;
+
+        // the checkpoint only becomes visible once all of its values are durable
+        let mut checkpoints = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(CHECKPOINTS_FILE))
+            .map_err(storage_error)?;
+        writeln!(checkpoints, "{}", checkpoint).map_err(storage_error)?;
+        checkpoints.sync_all().map_err(storage_error)?;
+        Ok(pending.len())
+    }
+}
+
+impl Drop for Checkpointer {
+    fn drop(&mut self) {
+        // all watched operations have completed, so their results can be flushed
+        // as part of the next checkpoint without waiting for it; unless the
+        // checkpoints have already been discarded
+        if self.pending.lock().is_empty() || !self.dir.exists() {
+            return;
+        }
+        let now = millis_since_epoch(SystemTime::now());
+        if let Err(e) = self.flush(next_checkpoint(now, self.interval_millis)) {
+            tracing::error!("Failed to write final checkpoint: {}", e);
+        }
+    }
+}
+
+fn spawn_flush_loop(checkpointer: Weak<Checkpointer>, session_id: &SessionId) {
+    let session_id = session_id.clone();
+    tokio::spawn(async move {
+        loop {
+            let interval_millis = match checkpointer.upgrade() {
+                Some(checkpointer) => checkpointer.interval_millis,
+                None => return,
+            };
+            let now = millis_since_epoch(SystemTime::now());
+            let checkpoint = next_checkpoint(now, interval_millis);
+            tokio::time::sleep(Duration::from_millis(checkpoint - now)).await;
+
+            let checkpointer = match checkpointer.upgrade() {
+                Some(checkpointer) => checkpointer,
+                None => return,
+            };
+            let res = tokio::task::spawn_blocking(move || checkpointer.flush(checkpoint)).await;
+            match res {
+                Ok(Ok(count)) => tracing::info!(
+                    "Checkpointed session {} at {} with {} new results",
+                    session_id,
+                    checkpoint,
+                    count
+                ),
+                Ok(Err(e)) => tracing::error!("Failed to checkpoint session {}: {}", session_id, e),
+                Err(e) => tracing::error!("Failed to checkpoint session {}: {}", session_id, e),
+            }
+        }
+    });
+}
+
+/// Checkpoints available for `session_id`, in the order they were taken.
+pub fn list_checkpoints(config: &CheckpointConfig, session_id: &SessionId) -> Result<Vec<u64>> {
+    let path = config.session_dir(session_id).join(CHECKPOINTS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = File::open(path).map_err(storage_error)?;
+    let mut checkpoints = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(storage_error)?;
+        // a partially written last line means the checkpoint was never completed
+        if let Ok(checkpoint) = line.trim().parse() {
+            checkpoints.push(checkpoint);
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// Load the results of all operations completed before `checkpoint`.
+pub fn load_checkpoint(
+    config: &CheckpointConfig,
+    session_id: &SessionId,
+    checkpoint: u64,
+) -> Result<HashMap<String, Value>> {
+    let dir = config.session_dir(session_id);
+    let mut results = HashMap::new();
+    for entry in checkpoint_entries(config, session_id, checkpoint)? {
+        let bytes = std::fs::read(dir.join(&entry.file)).map_err(storage_error)?;
+        results.insert(entry.op_name, wire::deserialize_value(&bytes)?);
+    }
+    Ok(results)
+}
+
+/// Like `load_checkpoint`, but also discarding all results recorded after `checkpoint`.
+///
+/// Sessions resumed from `checkpoint` thereby continue the chain of results
+/// leading to it, and never pick up results of runs that were abandoned.
+pub fn resume_from_checkpoint(
+    config: &CheckpointConfig,
+    session_id: &SessionId,
+    checkpoint: u64,
+) -> Result<HashMap<String, Value>> {
+    let dir = config.session_dir(session_id);
+    let kept = checkpoint_entries(config, session_id, checkpoint)?;
+
+    let checkpoints: Vec<u64> = list_checkpoints(config, session_id)?
+        .into_iter()
+        .filter(|c| *c <= checkpoint)
+        .collect();
+    let mut log = Vec::new();
+    for entry in kept.iter() {
+        bincode::serialize_into(&mut log, entry).map_err(storage_error)?;
+    }
+    let checkpoints: String = checkpoints.iter().map(|c| format!("{}\n", c)).collect();
+    // the checkpoints file is replaced first, so that later checkpoints are gone
+    // even if replacing the log is interrupted
+    replace_file(&dir.join(CHECKPOINTS_FILE), checkpoints.as_bytes())?;
+    replace_file(&dir.join(LOG_FILE), &log)?;
+
+    let kept_files: HashSet<&str> = kept.iter().map(|entry| entry.file.as_str()).collect();
+    for entry in std::fs::read_dir(&dir).map_err(storage_error)? {
+        let entry = entry.map_err(storage_error)?;
+        let name = entry.file_name();
+        let is_dropped = name
+            .to_str()
+            .map(|name| name.ends_with(".value") && !kept_files.contains(name))
+            .unwrap_or(false);
+        if is_dropped {
+            std::fs::remove_file(entry.path()).map_err(storage_error)?;
+        }
+    }
+
+    load_checkpoint(config, session_id, checkpoint)
+}
+
+/// Log entries of the results completed before `checkpoint`.
+fn checkpoint_entries(
+    config: &CheckpointConfig,
+    session_id: &SessionId,
+    checkpoint: u64,
+) -> Result<Vec<LogEntry>> {
+    if !list_checkpoints(config, session_id)?.contains(&checkpoint) {
+        return Err(Error::Storage(format!(
+            "no checkpoint {} for session {}",
+            checkpoint, session_id
+        )));
+    }
+    let dir = config.session_dir(session_id);
+    let log = File::open(dir.join(LOG_FILE)).map_err(storage_error)?;
+    let mut log = BufReader::new(log);
+    let mut entries = Vec::new();
+    // entries of later or incomplete checkpoints may follow, possibly truncated
+    while let Ok(entry) = bincode::deserialize_from::<_, LogEntry>(&mut log) {
+        if entry.checkpoint <= checkpoint {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Durably replace the contents of `path` with `contents`.
+fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp).map_err(storage_error)?;
+    file.write_all(contents).map_err(storage_error)?;
+    file.sync_all().map_err(storage_error)?;
+    std::fs::rename(&tmp, path).map_err(storage_error)
+}
+
+/// Remove all checkpoints of `session_id`, e.g. once it has completed.
+pub fn discard_checkpoints(config: &CheckpointConfig, session_id: &SessionId) -> Result<()> {
+    let dir = config.session_dir(session_id);
+    if Path::new(&dir).exists() {
+        std::fs::remove_dir_all(dir).map_err(storage_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::HostUnit;
+    use crate::host::HostPlacement;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_flush_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CheckpointConfig {
+            dir: dir.path().to_path_buf(),
+            // long enough for the flush loop not to interfere
+            interval: Duration::from_secs(3600),
+        };
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+
+        let checkpointer = Checkpointer::start(&config, &session_id).unwrap();
+        checkpointer
+            .pending
+            .lock()
+            .push(("x".to_string(), unit.clone()));
+        checkpointer.flush(1_000).unwrap();
+        checkpointer
+            .pending
+            .lock()
+            .push(("y".to_string(), unit.clone()));
+        checkpointer.flush(2_000).unwrap();
+        checkpointer
+            .pending
+            .lock()
+            .push(("z".to_string(), unit.clone()));
+
+        assert_eq!(
+            list_checkpoints(&config, &session_id).unwrap(),
+            vec![1_000, 2_000]
+        );
+        let results = load_checkpoint(&config, &session_id, 1_000).unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["x"]);
+        let results = load_checkpoint(&config, &session_id, 2_000).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(load_checkpoint(&config, &session_id, 3_000).is_err());
+
+        discard_checkpoints(&config, &session_id).unwrap();
+        assert!(list_checkpoints(&config, &session_id).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resume_discards_later_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = CheckpointConfig {
+            dir: dir.path().to_path_buf(),
+            interval: Duration::from_secs(3600),
+        };
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let record = |checkpointer: &Checkpointer, op_name: &str, checkpoint: u64| {
+            checkpointer
+                .pending
+                .lock()
+                .push((op_name.to_string(), unit.clone()));
+            checkpointer.flush(checkpoint).unwrap();
+        };
+
+        let abandoned = Checkpointer::start(&config, &session_id).unwrap();
+        record(&abandoned, "x", 1_000);
+        record(&abandoned, "y", 2_000);
+        record(&abandoned, "z", 3_000);
+        drop(abandoned);
+
+        // resuming from the first checkpoint drops the results of the later ones
+        let results = resume_from_checkpoint(&config, &session_id, 1_000).unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["x"]);
+        assert_eq!(list_checkpoints(&config, &session_id).unwrap(), vec![1_000]);
+
+        let resumed = Checkpointer::start(&config, &session_id).unwrap();
+        record(&resumed, "w", 4_000);
+        let mut ops: Vec<_> = load_checkpoint(&config, &session_id, 4_000)
+            .unwrap()
+            .into_keys()
+            .collect();
+        ops.sort();
+        assert_eq!(ops, vec!["w", "x"]);
+    }
+}
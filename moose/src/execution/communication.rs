@@ -0,0 +1,288 @@
+//! Reports of the communication taking place in sessions.
+//!
+//! Every message of a session is sent by a send operation, so the traffic of a
+//! party can be accounted for by watching the values handed to its own send
+//! operations. Each message is attributed to the pair of placements exchanging
+//! it and to the kind of operator that computed the value sent, and the number
+//! of rounds is the length of the longest chain of messages each waiting on the
+//! one before it. Reports of all parties can be merged into one for the session.
+
+use crate::computation::{Computation, Operator, Placement};
+use crate::execution::AsyncValue;
+use futures::future::{BoxFuture, Shared};
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Messages and bytes of serialized values exchanged.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrafficStats {
+    pub messages: u64,
+    pub bytes: u64,
+}
+
+impl TrafficStats {
+    fn add(&mut self, other: &TrafficStats) {
+        self.messages += other.messages;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Communication of a session, by placement pair and by operator kind.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct CommunicationReport {
+    /// Length of the longest chain of messages in which each depends on the one before.
+    pub rounds: u64,
+    /// Traffic by sending and then receiving placement.
+    pub placements: BTreeMap<String, BTreeMap<String, TrafficStats>>,
+    /// Traffic by kind of the operator computing the values sent.
+    pub operators: BTreeMap<String, TrafficStats>,
+}
+
+impl CommunicationReport {
+    /// Combine with the report of another party of the same session.
+    pub fn merge(&mut self, other: &CommunicationReport) {
+        self.rounds = self.rounds.max(other.rounds);
+        for (sender, receivers) in other.placements.iter() {
+            let own_receivers = self.placements.entry(sender.clone()).or_default();
+            for (receiver, stats) in receivers.iter() {
+                own_receivers
+                    .entry(receiver.clone())
+                    .or_default()
+                    .add(stats);
+            }
+        }
+        for (operator, stats) in other.operators.iter() {
+            self.operators
+                .entry(operator.clone())
+                .or_default()
+                .add(stats);
+        }
+    }
+
+    fn record(&mut self, message: &PlannedMessage, bytes: u64) {
+        let stats = TrafficStats { messages: 1, bytes };
+        self.rounds = self.rounds.max(message.round);
+        self.placements
+            .entry(message.sender.clone())
+            .or_default()
+            .entry(message.receiver.clone())
+            .or_default()
+            .add(&stats);
+        self.operators
+            .entry(message.operator.clone())
+            .or_default()
+            .add(&stats);
+    }
+}
+
+/// Report of the messages sent by a party, filled in as the values to send are computed.
+#[derive(Default)]
+pub struct CommunicationTracker {
+    report: Mutex<CommunicationReport>,
+    task: Mutex<Option<Shared<BoxFuture<'static, ()>>>>,
+}
+
+impl CommunicationTracker {
+    pub fn report(&self) -> CommunicationReport {
+        self.report.lock().clone()
+    }
+
+    /// Wait until all messages of a finished session have been recorded.
+    pub async fn settled(&self) {
+        let task = self.task.lock().clone();
+        if let Some(task) = task {
+            task.await;
+        }
+    }
+}
+
+/// Message sent by a send operation, as known before running the computation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PlannedMessage {
+    pub sender: String,
+    pub receiver: String,
+    pub operator: String,
+    /// Number of messages in the longest chain ending with this one.
+    pub round: u64,
+}
+
+/// Messages sent by the send operations of `computation`, by operation index.
+pub(crate) fn plan_messages(computation: &Computation) -> HashMap<usize, PlannedMessage> {
+    let ops = &computation.operations;
+    let indices: HashMap<&str, usize> = ops
+        .iter()
+        .enumerate()
+        .map(|(i, op)| (op.name.as_str(), i))
+        .collect();
+    let sends: HashMap<_, usize> = ops
+        .iter()
+        .enumerate()
+        .filter_map(|(i, op)| match op.kind {
+            Operator::Send(ref send) => Some((&send.rendezvous_key, i)),
+            _ => None,
+        })
+        .collect();
+    // dependencies of each operation, including the send matching a receive
+    let dependencies: Vec<Vec<usize>> = ops
+        .iter()
+        .map(|op| {
+            let mut dependencies: Vec<usize> = op
+                .inputs
+                .iter()
+                .filter_map(|input| indices.get(input.as_str()).copied())
+                .collect();
+            if let Operator::Receive(ref receive) = op.kind {
+                dependencies.extend(sends.get(&receive.rendezvous_key));
+            }
+            dependencies
+        })
+        .collect();
+
+    // messages in the longest chain ending at each operation, computed depth first
+    // since receives may come before their send in the order of operations
+    let mut rounds: Vec<Option<u64>> = vec![None; ops.len()];
+    for start in 0..ops.len() {
+        let mut stack = vec![start];
+        while let Some(&i) = stack.last() {
+            if rounds[i].is_some() {
+                stack.pop();
+                continue;
+            }
+            let pending: Vec<usize> = dependencies[i]
+                .iter()
+                .copied()
+                .filter(|dependency| rounds[*dependency].is_none())
+                .collect();
+            if pending.is_empty() || pending.iter().any(|dependency| stack.contains(dependency)) {
+                let round = dependencies[i]
+                    .iter()
+                    .filter_map(|dependency| rounds[*dependency])
+                    .max()
+                    .unwrap_or(0);
+                let is_send = matches!(ops[i].kind, Operator::Send(_));
+                rounds[i] = Some(round + is_send as u64);
+                stack.pop();
+            } else {
+                stack.extend(pending);
+            }
+        }
+    }
+
+    ops.iter()
+        .enumerate()
+        .filter_map(|(i, op)| match (&op.kind, &op.placement) {
+            (Operator::Send(send), Placement::Host(host)) => {
+                let operator = op
+                    .inputs
+                    .first()
+                    .and_then(|input| indices.get(input.as_str()))
+                    .map(|input| ops[*input].kind.short_name().to_string())
+                    .unwrap_or_default();
+                Some((
+                    i,
+                    PlannedMessage {
+                        sender: host.owner.to_string(),
+                        receiver: send.receiver.to_string(),
+                        operator,
+                        round: rounds[i].unwrap_or(0),
+                    },
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Record `messages` in `tracker` as the values to send become available.
+pub(crate) fn track_communication(
+    messages: Vec<(PlannedMessage, AsyncValue)>,
+    tracker: Arc<CommunicationTracker>,
+) -> BoxFuture<'static, ()> {
+    let recorder = Arc::clone(&tracker);
+    let task = tokio::spawn(async move {
+        let mut pending: FuturesUnordered<_> = messages
+            .into_iter()
+            .map(|(message, value)| async move { (message, value.await) })
+            .collect();
+        while let Some((message, value)) = pending.next().await {
+            // values of failed operations are never sent
+            if let Ok(value) = value {
+                let bytes = bincode::serialized_size(&value).unwrap_or_default();
+                recorder.report.lock().record(&message, bytes);
+            }
+        }
+    })
+    .map(|_| ())
+    .boxed()
+    .shared();
+    *tracker.task.lock() = Some(task.clone());
+    task.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_plan_messages() {
+        let computation: Computation = r#"
+        x = Constant{value = HostFloat64Tensor([1.0])}: () -> HostFloat64Tensor () @Host(alice)
+        send_x = Send{rendezvous_key = 30303030303030303030303030303030, receiver = "bob"}: (HostFloat64Tensor) -> HostUnit (x) @Host(alice)
+        recv_x = Receive{rendezvous_key = 30303030303030303030303030303030, sender = "alice"}: () -> HostFloat64Tensor () @Host(bob)
+        y = Add: (HostFloat64Tensor, HostFloat64Tensor) -> HostFloat64Tensor (recv_x, recv_x) @Host(bob)
+        send_y = Send{rendezvous_key = 31313131313131313131313131313131, receiver = "alice"}: (HostFloat64Tensor) -> HostUnit (y) @Host(bob)
+        "#
+        .try_into()
+        .unwrap();
+        let messages = plan_messages(&computation);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[&1],
+            PlannedMessage {
+                sender: "alice".to_string(),
+                receiver: "bob".to_string(),
+                operator: "Constant".to_string(),
+                round: 1,
+            }
+        );
+        assert_eq!(messages[&4].operator, "Add");
+        assert_eq!(messages[&4].round, 2);
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let mut report = CommunicationReport::default();
+        let message = PlannedMessage {
+            sender: "alice".to_string(),
+            receiver: "bob".to_string(),
+            operator: "Add".to_string(),
+            round: 1,
+        };
+        report.record(&message, 10);
+        let mut other = CommunicationReport::default();
+        other.record(&message, 5);
+        other.record(
+            &PlannedMessage {
+                round: 3,
+                ..message.clone()
+            },
+            1,
+        );
+
+        report.merge(&other);
+        assert_eq!(report.rounds, 3);
+        assert_eq!(
+            report.placements["alice"]["bob"],
+            TrafficStats {
+                messages: 3,
+                bytes: 16
+            }
+        );
+        assert_eq!(report.operators["Add"].messages, 3);
+    }
+}
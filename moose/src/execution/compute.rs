@@ -0,0 +1,86 @@
+//! Dedicated thread pool for expensive host kernels.
+//!
+//! Kernels otherwise run directly on the threads of the async runtime, where a
+//! single large matrix multiplication can keep networking and other sessions
+//! from making progress. Running expensive kernels on a separate pool also
+//! bounds the number of cores used for computation.
+
+use crate::computation::{Operator, Value};
+use crate::error::{Error, Result};
+use std::panic::AssertUnwindSafe;
+use tokio::sync::oneshot;
+
+pub struct ComputePool {
+    pool: rayon::ThreadPool,
+}
+
+impl ComputePool {
+    /// Create a pool with `num_threads` threads, or one per core if zero.
+    pub fn new(num_threads: usize) -> Result<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("moose-compute-{}", i))
+            .build()
+            .map_err(|e| Error::Unexpected(Some(format!("failed to start compute pool: {}", e))))?;
+        Ok(ComputePool { pool })
+    }
+
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Run `kernel` on the pool; any parallel iterators it uses also run on the pool.
+    pub(crate) async fn run<F>(&self, kernel: F) -> Result<Value>
+    where
+        F: FnOnce() -> Result<Value> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.pool.spawn(move || {
+            let res = std::panic::catch_unwind(AssertUnwindSafe(kernel)).unwrap_or_else(|_| {
+                Err(Error::KernelError(
+                    "kernel panicked on compute pool".to_string(),
+                ))
+            });
+            let _ = sender.send(res);
+        });
+        receiver.await.map_err(|_| {
+            Error::KernelError("compute pool dropped kernel before completion".to_string())
+        })?
+    }
+}
+
+/// Whether `op` is expensive enough to be worth running on a [`ComputePool`].
+pub(crate) fn is_heavy(op: &Operator) -> bool {
+    use Operator::*;
+    matches!(
+        op,
+        Dot(_)
+            | Conv2D(_)
+            | Inverse(_)
+            | Sample(_)
+            | SampleSeeded(_)
+            | BitDecompose(_)
+            | Softmax(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::HostUnit;
+    use crate::host::HostPlacement;
+
+    #[tokio::test]
+    async fn test_run() {
+        let pool = ComputePool::new(2).unwrap();
+        assert_eq!(pool.num_threads(), 2);
+
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let expected = unit.clone();
+        let res = pool.run(move || Ok(unit)).await.unwrap();
+        assert_eq!(res, expected);
+
+        let res = pool.run(|| panic!("kernel failure")).await;
+        assert!(matches!(res, Err(Error::KernelError(_))));
+    }
+}
@@ -0,0 +1,313 @@
+//! Timing harness for auditing kernels that handle secret values.
+//!
+//! Follows the approach of [dudect](https://eprint.iacr.org/2016/1123): a
+//! kernel is timed on inputs from two classes, one fixed and one random,
+//! interleaved in random order, and Welch's t-test is applied to the two
+//! distributions of running times. A large t-statistic means that running
+//! times depend on the inputs, as happens with secret-dependent branching or
+//! early exits. Measurements above a percentile are cropped beforehand since
+//! the upper tail is dominated by interrupts and other noise.
+//!
+//! The timing tests of this module audit kernels operating on secret shares.
+//! Since they are slow and sensitive to the load of the machine they are
+//! ignored by default; run them with
+//!
+//! ```sh
+//! cargo test --release -p moose constant_time -- --ignored --test-threads=1
+//! ```
+//!
+//! Passing tests are no proof of constant-time behaviour, but new protocol
+//! code should be added to them to catch accidental timing leaks.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::time::Instant;
+
+/// Class of the inputs a kernel is timed on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputClass {
+    /// The same input for every measurement.
+    Fixed,
+    /// A freshly sampled input for every measurement.
+    Random,
+}
+
+/// Dudect-style test of whether the running time of a kernel depends on its inputs.
+#[derive(Clone, Debug)]
+pub struct TimingTest {
+    samples: usize,
+    threshold: f64,
+    crop_percentile: f64,
+    seed: u64,
+}
+
+impl TimingTest {
+    /// Test taking `samples` measurements of each input class.
+    pub fn new(samples: usize) -> Self {
+        TimingTest {
+            samples,
+            threshold: 4.5,
+            crop_percentile: 0.9,
+            seed: 0,
+        }
+    }
+
+    /// Absolute t-statistic above which the kernel is considered leaky.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        TimingTest { threshold, ..self }
+    }
+
+    /// Fraction of measurements kept, dropping the slowest ones.
+    pub fn with_crop_percentile(self, crop_percentile: f64) -> Self {
+        TimingTest {
+            crop_percentile,
+            ..self
+        }
+    }
+
+    /// Seed of the randomness deciding the order of measurements and given to `inputs`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        TimingTest { seed, ..self }
+    }
+
+    /// Time `kernel` on inputs generated by `inputs` for each class.
+    ///
+    /// Inputs are generated ahead of time so that their generation is not
+    /// part of the measurements.
+    pub fn run<I, O, G, K>(&self, mut inputs: G, mut kernel: K) -> TimingReport
+    where
+        G: FnMut(InputClass, &mut StdRng) -> I,
+        K: FnMut(I) -> O,
+    {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut classes: Vec<InputClass> = std::iter::repeat(InputClass::Fixed)
+            .take(self.samples)
+            .chain(std::iter::repeat(InputClass::Random).take(self.samples))
+            .collect();
+        // Fisher-Yates shuffle, interleaving the classes
+        for i in (1..classes.len()).rev() {
+            classes.swap(i, rng.gen_range(0..=i));
+        }
+        let prepared: Vec<(InputClass, I)> = classes
+            .into_iter()
+            .map(|class| (class, inputs(class, &mut rng)))
+            .collect();
+
+        let mut measurements = Vec::with_capacity(prepared.len());
+        for (class, input) in prepared {
+            let start = Instant::now();
+            let output = kernel(input);
+            let elapsed = start.elapsed().as_nanos() as f64;
+            let _ = std::hint::black_box(output);
+            measurements.push((class, elapsed));
+        }
+
+        let cutoff = percentile(
+            measurements.iter().map(|(_, time)| *time).collect(),
+            self.crop_percentile,
+        );
+        let (fixed, random): (Vec<_>, Vec<_>) = measurements
+            .into_iter()
+            .filter(|(_, time)| *time <= cutoff)
+            .partition(|(class, _)| *class == InputClass::Fixed);
+        let fixed: Vec<f64> = fixed.into_iter().map(|(_, time)| time).collect();
+        let random: Vec<f64> = random.into_iter().map(|(_, time)| time).collect();
+
+        TimingReport {
+            t: welch_t(&fixed, &random),
+            threshold: self.threshold,
+            samples: fixed.len() + random.len(),
+        }
+    }
+}
+
+/// Outcome of a `TimingTest`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimingReport {
+    /// Welch's t-statistic of the running times of the two input classes.
+    pub t: f64,
+    pub threshold: f64,
+    /// Number of measurements left after cropping.
+    pub samples: usize,
+}
+
+impl TimingReport {
+    /// Whether running times differ significantly between the input classes.
+    pub fn leaks(&self) -> bool {
+        self.t.is_nan() || self.t.abs() > self.threshold
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "t = {:.2} over {} measurements ({})",
+            self.t,
+            self.samples,
+            if self.leaks() {
+                "timing leak"
+            } else {
+                "no leak detected"
+            }
+        )
+    }
+}
+
+fn percentile(mut values: Vec<f64>, fraction: f64) -> f64 {
+    if values.is_empty() {
+        return f64::INFINITY;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let index = ((values.len() - 1) as f64 * fraction.clamp(0.0, 1.0)).round() as usize;
+    values[index]
+}
+
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance)
+}
+
+/// Welch's t-statistic for the difference in means of two samples.
+fn welch_t(xs: &[f64], ys: &[f64]) -> f64 {
+    if xs.len() < 2 || ys.len() < 2 {
+        return 0.0;
+    }
+    let (x_mean, x_var) = mean_and_variance(xs);
+    let (y_mean, y_var) = mean_and_variance(ys);
+    let se = (x_var / xs.len() as f64 + y_var / ys.len() as f64).sqrt();
+    if se == 0.0 {
+        return if x_mean == y_mean { 0.0 } else { f64::INFINITY };
+    }
+    (x_mean - y_mean) / se
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_welch_t() {
+        assert_eq!(welch_t(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), 0.0);
+        let t = welch_t(&[1.0, 2.0, 3.0, 4.0], &[3.0, 4.0, 5.0, 6.0]);
+        assert!((t - (-2.0 / (5.0_f64 / 6.0).sqrt())).abs() < 1e-12);
+        assert_eq!(welch_t(&[1.0, 1.0], &[2.0, 2.0]), f64::INFINITY);
+
+        let report = TimingReport {
+            t: f64::NAN,
+            threshold: 4.5,
+            samples: 0,
+        };
+        assert!(report.leaks());
+        assert_eq!(percentile(vec![3.0, 1.0, 2.0, 4.0, 5.0], 0.5), 3.0);
+    }
+
+    /// Comparison exiting at the first difference.
+    fn leaky_eq(xs: &[u8], ys: &[u8]) -> bool {
+        for (x, y) in xs.iter().zip(ys) {
+            if x != y {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    #[ignore]
+    fn test_detects_early_exit() {
+        let secret = vec![0u8; 4096];
+        let report = TimingTest::new(10_000).run(
+            |class, rng| match class {
+                InputClass::Fixed => vec![0u8; 4096],
+                InputClass::Random => {
+                    let mut guess = vec![0u8; 4096];
+                    guess[rng.gen_range(0..64)] = 1;
+                    guess
+                }
+            },
+            |guess| leaky_eq(std::hint::black_box(&secret), &guess),
+        );
+        assert!(report.leaks(), "{}", report);
+    }
+
+    #[cfg(feature = "sync_execute")]
+    mod kernels {
+        use super::*;
+        use crate::host::{HostBitTensor, HostPlacement, HostRing64Tensor};
+        use crate::kernels::*;
+        use crate::prelude::*;
+        use crate::replicated::{ReplicatedPlacement, ReplicatedRing64Tensor};
+        use ndarray::prelude::*;
+
+        const SAMPLES: usize = 2_000;
+
+        fn values(class: InputClass, rng: &mut StdRng) -> Array1<u64> {
+            match class {
+                InputClass::Fixed => Array1::zeros(64),
+                InputClass::Random => Array1::from_shape_fn(64, |_| rng.gen()),
+            }
+        }
+
+        fn shared(class: InputClass, rng: &mut StdRng) -> ReplicatedRing64Tensor {
+            let alice = HostPlacement::from("alice");
+            let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+            let x: HostRing64Tensor = alice.from_raw(values(class, rng));
+            rep.share(&SyncSession::default(), &x)
+        }
+
+        fn audit<I, O>(
+            inputs: impl FnMut(InputClass, &mut StdRng) -> I,
+            kernel: impl FnMut(I) -> O,
+        ) {
+            let report = TimingTest::new(SAMPLES).run(inputs, kernel);
+            assert!(!report.leaks(), "{}", report);
+        }
+
+        #[test]
+        #[ignore]
+        fn test_rep_mul_constant_time() {
+            let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+            let sess = SyncSession::default();
+            audit(
+                |class, rng| (shared(class, rng), shared(class, rng)),
+                |(x, y)| rep.mul(&sess, &x, &y),
+            );
+        }
+
+        #[test]
+        #[ignore]
+        fn test_rep_msb_constant_time() {
+            let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+            let sess = SyncSession::default();
+            audit(shared, |x| -> ReplicatedRing64Tensor { rep.msb(&sess, &x) });
+        }
+
+        #[test]
+        #[ignore]
+        fn test_host_bit_decompose_constant_time() {
+            let alice = HostPlacement::from("alice");
+            let sess = SyncSession::default();
+            audit(
+                |class, rng| -> HostRing64Tensor { alice.from_raw(values(class, rng)) },
+                |x| -> HostRing64Tensor { alice.bit_decompose(&sess, &x) },
+            );
+        }
+
+        #[test]
+        #[ignore]
+        fn test_host_bit_and_constant_time() {
+            let alice = HostPlacement::from("alice");
+            let sess = SyncSession::default();
+            let bits = |class, rng: &mut StdRng| -> HostBitTensor {
+                alice.from_raw(values(class, rng).mapv(|x| (x & 1) as u8))
+            };
+            audit(
+                |class, rng| (bits(class, rng), bits(class, rng)),
+                |(x, y)| -> HostBitTensor { alice.and(&sess, &x, &y) },
+            );
+        }
+    }
+}
@@ -2,17 +2,57 @@
 
 use crate::computation::IndexedComputation;
 use crate::computation::Operator;
-use crate::execution::{AsyncNetworkingImpl, AsyncStorageImpl};
+use crate::execution::audit::AuditLog;
+use crate::execution::cache::KernelCache;
+use crate::execution::checkpoint::{
+    discard_checkpoints, resume_from_checkpoint, CheckpointConfig, Checkpointer,
+};
+use crate::execution::communication::{plan_messages, track_communication, CommunicationTracker};
+use crate::execution::compute::ComputePool;
+#[cfg(feature = "gpu")]
+use crate::execution::gpu::GpuBackend;
+use crate::execution::memory::{track_memory, MemoryBudgetConfig, MemoryTracker};
+#[cfg(feature = "metrics")]
+use crate::execution::metrics::{MeteredStorage, Metrics};
+use crate::execution::profiling::{write_profile, Profiler};
+use crate::execution::progress::{track_progress, ProgressCallback};
+use crate::execution::recording::{recording_path, Recorder, Replay};
+use crate::execution::scheduler::Scheduler;
+use crate::execution::scrub::{task_holder, ResultHolders};
+use crate::execution::summary::log_summaries;
+use crate::execution::{ready_value, AsyncNetworkingImpl, AsyncStorageImpl, OperationTimeouts};
 use crate::prelude::*;
 use crate::Error;
+use futures::stream::{BoxStream, FuturesUnordered, StreamExt};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 pub struct ExecutionContext {
     own_identity: Identity,
     networking: AsyncNetworkingImpl,
     storage: AsyncStorageImpl,
+    timeouts: OperationTimeouts,
+    memory_budget: Option<MemoryBudgetConfig>,
+    compute_pool: Option<Arc<ComputePool>>,
+    checkpoints: Option<CheckpointConfig>,
+    resume_from: Option<u64>,
+    recording_dir: Option<PathBuf>,
+    replay: Option<Arc<Replay>>,
+    progress: Option<ProgressCallback>,
+    profiling_dir: Option<PathBuf>,
+    cache: Option<Arc<KernelCache>>,
+    scheduler: Option<Arc<Scheduler>>,
+    audit: Option<Arc<AuditLog>>,
+    memory_tracker: Option<Arc<MemoryTracker>>,
+    communication_tracker: Option<Arc<CommunicationTracker>>,
+    summarize_values: bool,
+    scrub_results: bool,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+    #[cfg(feature = "gpu")]
+    gpu: Option<Arc<GpuBackend>>,
 }
 
 #[allow(dead_code)]
@@ -21,6 +61,37 @@ type Environment = HashMap<String, <AsyncSession as Session>::Value>;
 type IndexedEnvironment = Vec<Option<<AsyncSession as Session>::Value>>;
 type IndexedOutputEnvironment = Vec<(usize, <AsyncSession as Session>::Value)>;
 
+/// Tagged outputs of a session, in the order in which they become available.
+pub type OutputStream = BoxStream<'static, Result<(String, Value), Error>>;
+
+/// Deliver the `outputs` of a session executing `computation` as soon as each of them is ready.
+///
+/// The stream ends early with [`Error::OperandUnavailable`] if the session fails.
+pub fn stream_outputs(
+    computation: &Computation,
+    outputs: IndexedOutputEnvironment,
+) -> Result<OutputStream, Error> {
+    let pending: FuturesUnordered<_> = outputs
+        .into_iter()
+        .map(|(op_index, output)| {
+            let tag = match computation.operations.get(op_index).map(|op| &op.kind) {
+                Some(Operator::Output(op)) => op.tag.clone(),
+                _ => {
+                    return Err(Error::MalformedComputation(format!(
+                        "Operation '{}' is not an output",
+                        op_index
+                    )))
+                }
+            };
+            Ok(async move {
+                let value = output.await.map_err(|_| Error::OperandUnavailable)?;
+                Ok((tag, value))
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(pending.boxed())
+}
+
 impl ExecutionContext {
     pub fn new(
         own_identity: Identity,
@@ -31,29 +102,381 @@ impl ExecutionContext {
             own_identity,
             networking,
             storage,
+            timeouts: OperationTimeouts::default(),
+            memory_budget: None,
+            compute_pool: None,
+            checkpoints: None,
+            resume_from: None,
+            recording_dir: None,
+            replay: None,
+            progress: None,
+            profiling_dir: None,
+            cache: None,
+            scheduler: None,
+            audit: None,
+            memory_tracker: None,
+            communication_tracker: None,
+            summarize_values: false,
+            scrub_results: false,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "gpu")]
+            gpu: None,
         }
     }
 
-    #[tracing::instrument(skip(self, computation, role_assignments))]
-    pub async fn execute_computation(
+    /// Fail sessions with a timeout error instead of waiting forever on silent peers.
+    pub fn with_timeouts(self, timeouts: OperationTimeouts) -> Self {
+        ExecutionContext { timeouts, ..self }
+    }
+
+    /// Give each session a memory budget, spilling operands to disk when exceeded.
+    pub fn with_memory_budget(self, memory_budget: MemoryBudgetConfig) -> Self {
+        ExecutionContext {
+            memory_budget: Some(memory_budget),
+            ..self
+        }
+    }
+
+    /// Run expensive kernels of all sessions on `compute_pool`.
+    pub fn with_compute_pool(self, compute_pool: Arc<ComputePool>) -> Self {
+        ExecutionContext {
+            compute_pool: Some(compute_pool),
+            ..self
+        }
+    }
+
+    /// Run supported kernels on `gpu` for the placements it was configured for.
+    #[cfg(feature = "gpu")]
+    pub fn with_gpu(self, gpu: Arc<GpuBackend>) -> Self {
+        ExecutionContext {
+            gpu: Some(gpu),
+            ..self
+        }
+    }
+
+    /// Periodically checkpoint the results of sessions executed with `execute_computation`.
+    pub fn with_checkpoints(self, checkpoints: CheckpointConfig) -> Self {
+        ExecutionContext {
+            checkpoints: Some(checkpoints),
+            ..self
+        }
+    }
+
+    /// Resume sessions from `checkpoint` instead of starting from scratch.
+    ///
+    /// All parties must resume from the same checkpoint.
+    pub fn resume_from(self, checkpoint: u64) -> Self {
+        ExecutionContext {
+            resume_from: Some(checkpoint),
+            ..self
+        }
+    }
+
+    /// Record each session to a file in `recording_dir` so that it can be replayed later.
+    pub fn with_recording(self, recording_dir: PathBuf) -> Self {
+        ExecutionContext {
+            recording_dir: Some(recording_dir),
+            ..self
+        }
+    }
+
+    /// Replay sessions from `replay` instead of communicating with other parties.
+    pub fn with_replay(self, replay: Arc<Replay>) -> Self {
+        ExecutionContext {
+            replay: Some(replay),
+            ..self
+        }
+    }
+
+    /// Reuse results of pure operations across sessions through `cache`.
+    pub fn with_cache(self, cache: Arc<KernelCache>) -> Self {
+        ExecutionContext {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Share the slots for running kernels of `scheduler` fairly between sessions.
+    pub fn with_scheduler(self, scheduler: Arc<Scheduler>) -> Self {
+        ExecutionContext {
+            scheduler: Some(scheduler),
+            ..self
+        }
+    }
+
+    /// Report the progress of each session to `callback`.
+    pub fn with_progress(self, callback: ProgressCallback) -> Self {
+        ExecutionContext {
+            progress: Some(callback),
+            ..self
+        }
+    }
+
+    /// Profile every operation of each session, writing the profiles to `profiling_dir`.
+    pub fn with_profiling(self, profiling_dir: PathBuf) -> Self {
+        ExecutionContext {
+            profiling_dir: Some(profiling_dir),
+            ..self
+        }
+    }
+
+    /// Record every value revealed to a host placement of this worker in `audit`.
+    ///
+    /// Values output or saved by a placement are recorded, as well as every value
+    /// received from another party. Reveals are lowered into receiving the missing
+    /// shares and adding them up on the host, so the received values are what a
+    /// reveal hands to the worker.
+    pub fn with_audit(self, audit: Arc<AuditLog>) -> Self {
+        ExecutionContext {
+            audit: Some(audit),
+            ..self
+        }
+    }
+
+    /// Track the bytes of values alive during the session in `memory_tracker`.
+    ///
+    /// Only suited for contexts running a single session.
+    pub fn with_memory_tracker(self, memory_tracker: Arc<MemoryTracker>) -> Self {
+        ExecutionContext {
+            memory_tracker: Some(memory_tracker),
+            ..self
+        }
+    }
+
+    /// Report the messages sent during the session to `communication_tracker`.
+    ///
+    /// Only suited for contexts running a single session.
+    pub fn with_communication_tracker(
+        self,
+        communication_tracker: Arc<CommunicationTracker>,
+    ) -> Self {
+        ExecutionContext {
+            communication_tracker: Some(communication_tracker),
+            ..self
+        }
+    }
+
+    /// Log a summary of the value computed by every operation, for debugging.
+    ///
+    /// Summaries of plaintext values reveal statistics about them, so this
+    /// should not be enabled for sessions on sensitive data.
+    pub fn with_value_summaries(self) -> Self {
+        ExecutionContext {
+            summarize_values: true,
+            ..self
+        }
+    }
+
+    /// Zeroize secret shares, seeds, and keys computed by each session once it has been joined.
+    ///
+    /// Values that are still in use, such as the outputs of the session, are
+    /// left intact. Sessions fail to launch if checkpoints, a memory budget, or
+    /// recordings are enabled as well, since these keep copies of secret values
+    /// on disk.
+    pub fn with_scrubbing(self) -> Self {
+        ExecutionContext {
+            scrub_results: true,
+            ..self
+        }
+    }
+
+    /// Report kernel times and storage operations of each session to `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, metrics: Arc<Metrics>) -> Self {
+        ExecutionContext {
+            metrics: Some(metrics),
+            ..self
+        }
+    }
+
+    /// Record the value revealed to or received by operation `op_name`, if any, in the audit log.
+    fn audit_reveal(
+        &self,
+        session_id: &SessionId,
+        op_name: &str,
+        operator: &Operator,
+        plc: &Placement,
+        operands: &[AsyncValue],
+        result: &AsyncValue,
+    ) {
+        if let Some(ref audit) = self.audit {
+            let revealed = match operator {
+                Operator::Output(_) => Some(result),
+                Operator::Save(_) => operands.get(1),
+                Operator::Receive(op) => {
+                    audit.watch_receive(session_id, op_name, &op.sender, plc, result.clone());
+                    None
+                }
+                _ => None,
+            };
+            if let Some(value) = revealed {
+                audit.watch_reveal(session_id, op_name, plc, value.clone());
+            }
+        }
+    }
+
+    /// Whether the results of all operations are needed once the session has been set up.
+    fn watches_results(&self) -> bool {
+        self.progress.is_some() || self.profiling_dir.is_some() || self.scrub_results
+    }
+
+    /// Report progress of `session` and write its profile as `results` become available, if enabled.
+    ///
+    /// Returns the tasks doing so, which hold on to the results until finished.
+    fn watch_results(&self, session: &AsyncSession, results: Vec<AsyncValue>) -> ResultHolders {
+        let mut holders = ResultHolders::new();
+        if let (Some(profiler), Some(dir)) = (&session.profiler, &self.profiling_dir) {
+            holders.push(task_holder(write_profile(
+                Arc::clone(profiler),
+                session.session_id.clone(),
+                results.clone(),
+                dir,
+            )));
+        }
+        if let Some(ref callback) = self.progress {
+            holders.push(task_holder(track_progress(
+                session.session_id.clone(),
+                results,
+                Arc::clone(&self.networking),
+                Arc::clone(callback),
+            )));
+        }
+        holders
+    }
+
+    /// Fail unless secret values are kept in memory only when they are to be scrubbed.
+    fn check_scrubbing(&self) -> Result<(), Error> {
+        if self.scrub_results
+            && (self.checkpoints.is_some()
+                || self.memory_budget.is_some()
+                || self.recording_dir.is_some())
+        {
+            return Err(Error::InvalidArgument(
+                "scrubbing cannot be combined with checkpoints, a memory budget, or recordings"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn new_session(
         &self,
         session_id: SessionId,
-        computation: &Computation,
         arguments: HashMap<String, Value>,
         role_assignments: HashMap<Role, Identity>,
-    ) -> Result<(AsyncSessionHandle, IndexedOutputEnvironment), Box<dyn std::error::Error>> {
+    ) -> Result<AsyncSession, Error> {
+        let recorder = match self.recording_dir {
+            Some(ref dir) => Some(Recorder::create(&recording_path(dir, &session_id))?),
+            None => None,
+        };
+        let storage = Arc::clone(&self.storage);
+        #[cfg(feature = "metrics")]
+        let storage: AsyncStorageImpl = match self.metrics {
+            Some(ref metrics) => Arc::new(MeteredStorage::new(storage, Arc::clone(metrics))),
+            None => storage,
+        };
         let session = AsyncSession::new(
             session_id,
             arguments,
-            role_assignments.clone(),
+            role_assignments,
             Arc::clone(&self.networking),
-            Arc::clone(&self.storage),
-        );
+            storage,
+        )
+        .with_timeouts(self.timeouts);
+        #[cfg(feature = "metrics")]
+        let session = match self.metrics {
+            Some(ref metrics) => session.with_metrics(Arc::clone(metrics)),
+            None => session,
+        };
+        let session = match self.compute_pool {
+            Some(ref compute_pool) => session.with_compute_pool(Arc::clone(compute_pool)),
+            None => session,
+        };
+        #[cfg(feature = "gpu")]
+        let session = match self.gpu {
+            Some(ref gpu) => session.with_gpu(Arc::clone(gpu)),
+            None => session,
+        };
+        let session = match recorder {
+            Some(recorder) => session.with_recorder(Arc::new(recorder)),
+            None => session,
+        };
+        let session = match self.scheduler {
+            Some(ref scheduler) => session.with_scheduler(scheduler),
+            None => session,
+        };
+        let session = match self.cache {
+            Some(ref cache) => session.with_cache(Arc::clone(cache)),
+            None => session,
+        };
+        let session = match self.replay {
+            Some(ref replay) => session.with_replay(Arc::clone(replay)),
+            None => session,
+        };
+        let session = match self.profiling_dir {
+            Some(_) => session.with_profiler(Arc::new(Profiler::new())),
+            None => session,
+        };
+        Ok(match self.memory_budget {
+            Some(ref memory_budget) => session.with_memory_budget(memory_budget),
+            None => session,
+        })
+    }
+
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn execute_computation(
+        &self,
+        session_id: SessionId,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        role_assignments: HashMap<Role, Identity>,
+    ) -> Result<(AsyncSessionHandle, IndexedOutputEnvironment), Box<dyn std::error::Error>> {
+        self.check_scrubbing()?;
+        let mut restored = match (&self.checkpoints, self.resume_from) {
+            (Some(checkpoints), Some(checkpoint)) => {
+                let restored = resume_from_checkpoint(checkpoints, &session_id, checkpoint)?;
+                tracing::info!(
+                    "Resuming session {} with {} results from checkpoint {}",
+                    session_id,
+                    restored.len(),
+                    checkpoint
+                );
+                restored
+            }
+            (None, Some(_)) => {
+                return Err(Error::MalformedEnvironment(
+                    "Cannot resume without checkpoints".to_string(),
+                )
+                .into())
+            }
+            (Some(checkpoints), None) => {
+                // checkpoints of an earlier run would otherwise be mixed into later ones
+                discard_checkpoints(checkpoints, &session_id)?;
+                HashMap::new()
+            }
+            (None, None) => HashMap::new(),
+        };
+        let checkpointer = match self.checkpoints {
+            Some(ref checkpoints) => Some(Checkpointer::start(checkpoints, &session_id)?),
+            None => None,
+        };
+
+        let session = self.new_session(session_id, arguments, role_assignments.clone())?;
 
         let mut outputs: IndexedOutputEnvironment = Vec::default();
+        let mut results = Vec::new();
+        let mut tracked = Vec::new();
+        let planned_messages = match self.communication_tracker {
+            Some(_) => plan_messages(computation),
+            None => HashMap::new(),
+        };
+        let mut messages = Vec::new();
+        let mut summaries = Vec::new();
 
         {
             let mut env: Environment = HashMap::with_capacity(computation.operations.len());
+            let mut tracked_indices: HashMap<&str, usize> = HashMap::new();
 
             for (op_index, op) in computation.operations.iter().enumerate() {
                 // TODO(Morten) move filtering logic to the session
@@ -73,13 +496,59 @@ impl ExecutionContext {
                     }
                 };
 
-                let operands = op
+                let operands: Vec<_> = op
                     .inputs
                     .iter()
                     .map(|input_name| env.get(input_name).unwrap().clone())
                     .collect();
+                let audited_operands = match self.audit {
+                    Some(_) => operands.clone(),
+                    None => Vec::new(),
+                };
+                if let (Some(message), Some(value)) =
+                    (planned_messages.get(&op_index), operands.first())
+                {
+                    messages.push((message.clone(), value.clone()));
+                }
 
-                let result = session.execute(&op.kind, &op.placement, operands)?;
+                let result = match restored.remove(&op.name) {
+                    Some(value) => ready_value(value),
+                    None => {
+                        let result =
+                            session.execute_named(&op.name, &op.kind, &op.placement, operands)?;
+                        // values are always sent again since the receiver may not have them
+                        if let (Some(checkpointer), false) =
+                            (&checkpointer, matches!(op.kind, Operator::Send(_)))
+                        {
+                            checkpointer.watch(op.name.clone(), result.clone());
+                        }
+                        result
+                    }
+                };
+                self.audit_reveal(
+                    &session.session_id,
+                    &op.name,
+                    &op.kind,
+                    &op.placement,
+                    &audited_operands,
+                    &result,
+                );
+                if self.memory_tracker.is_some() {
+                    let operands = op
+                        .inputs
+                        .iter()
+                        .filter_map(|input_name| tracked_indices.get(input_name.as_str()).copied())
+                        .collect();
+                    tracked_indices.insert(op.name.as_str(), tracked.len());
+                    tracked.push((result.clone(), operands));
+                }
+
+                if self.watches_results() {
+                    results.push(result.clone());
+                }
+                if self.summarize_values {
+                    summaries.push((op.name.clone(), result.clone()));
+                }
 
                 if matches!(op.kind, Operator::Output(_)) {
                     // If it is an output, we need to make sure we capture it for returning.
@@ -91,11 +560,32 @@ impl ExecutionContext {
             }
         }
 
-        let handle = session.into_handle()?;
+        let scrubbed = if self.scrub_results {
+            results.clone()
+        } else {
+            Vec::new()
+        };
+        let mut holders = self.watch_results(&session, results);
+        if let Some(ref memory_tracker) = self.memory_tracker {
+            holders.push(task_holder(track_memory(
+                tracked,
+                Arc::clone(memory_tracker),
+            )));
+        }
+        if let Some(ref communication_tracker) = self.communication_tracker {
+            holders.push(track_communication(
+                messages,
+                Arc::clone(communication_tracker),
+            ));
+        }
+        if self.summarize_values {
+            holders.push(task_holder(log_summaries(summaries)));
+        }
+        let handle = session.into_handle()?.with_scrubbing(scrubbed, holders);
         Ok((handle, outputs))
     }
 
-    #[tracing::instrument(skip(self, computation, role_assignments))]
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
     pub async fn execute_indexed_computation(
         &self,
         session_id: SessionId,
@@ -103,18 +593,22 @@ impl ExecutionContext {
         arguments: HashMap<String, Value>,
         role_assignments: HashMap<Role, Identity>,
     ) -> Result<(AsyncSessionHandle, IndexedOutputEnvironment), Box<dyn std::error::Error>> {
-        let session = AsyncSession::new(
-            session_id,
-            arguments,
-            role_assignments.clone(),
-            Arc::clone(&self.networking),
-            Arc::clone(&self.storage),
-        );
+        self.check_scrubbing()?;
+        let session = self.new_session(session_id, arguments, role_assignments.clone())?;
 
+        let planned_messages = match self.communication_tracker {
+            Some(_) => plan_messages(computation),
+            None => HashMap::new(),
+        };
         let computation = IndexedComputation::try_from(computation)?;
         let mut outputs: IndexedOutputEnvironment = Vec::default();
+        let mut results = Vec::new();
+        let mut tracked = Vec::new();
+        let mut messages = Vec::new();
+        let mut summaries = Vec::new();
         {
             let mut env: IndexedEnvironment = Vec::with_capacity(computation.operations.len());
+            let mut tracked_indices: HashMap<usize, usize> = HashMap::new();
 
             for (op_index, op) in computation.operations.iter().enumerate() {
                 // TODO(Morten) move filtering logic to the session
@@ -142,11 +636,20 @@ impl ExecutionContext {
                     }
                 };
 
-                let operands = op
+                let operands: Vec<_> = op
                     .inputs
                     .iter()
                     .map(|input_index| env.get(*input_index).unwrap().clone().unwrap())
                     .collect();
+                let audited_operands = match self.audit {
+                    Some(_) => operands.clone(),
+                    None => Vec::new(),
+                };
+                if let (Some(message), Some(value)) =
+                    (planned_messages.get(&op_index), operands.first())
+                {
+                    messages.push((message.clone(), value.clone()));
+                }
 
                 let operator = computation.operators.get(op.operator).ok_or_else(|| {
                     Error::MalformedComputation(format!(
@@ -156,7 +659,31 @@ impl ExecutionContext {
                 })?;
                 let is_output = matches!(operator, Operator::Output(_));
 
-                let result = session.execute(operator, placement, operands)?;
+                let op_name = format!("{}#{}", operator.short_name(), op_index);
+                let result = session.execute_named(&op_name, operator, placement, operands)?;
+                self.audit_reveal(
+                    &session.session_id,
+                    &op_name,
+                    operator,
+                    placement,
+                    &audited_operands,
+                    &result,
+                );
+                if self.memory_tracker.is_some() {
+                    let operands = op
+                        .inputs
+                        .iter()
+                        .filter_map(|input_index| tracked_indices.get(input_index).copied())
+                        .collect();
+                    tracked_indices.insert(op_index, tracked.len());
+                    tracked.push((result.clone(), operands));
+                }
+                if self.watches_results() {
+                    results.push(result.clone());
+                }
+                if self.summarize_values {
+                    summaries.push((op_name, result.clone()));
+                }
 
                 if is_output {
                     // If it is an output, we need to make sure we capture it for returning.
@@ -168,7 +695,28 @@ impl ExecutionContext {
             }
         }
 
-        let handle = session.into_handle()?;
+        let scrubbed = if self.scrub_results {
+            results.clone()
+        } else {
+            Vec::new()
+        };
+        let mut holders = self.watch_results(&session, results);
+        if let Some(ref memory_tracker) = self.memory_tracker {
+            holders.push(task_holder(track_memory(
+                tracked,
+                Arc::clone(memory_tracker),
+            )));
+        }
+        if let Some(ref communication_tracker) = self.communication_tracker {
+            holders.push(track_communication(
+                messages,
+                Arc::clone(communication_tracker),
+            ));
+        }
+        if self.summarize_values {
+            holders.push(task_holder(log_summaries(summaries)));
+        }
+        let handle = session.into_handle()?.with_scrubbing(scrubbed, holders);
         Ok((handle, outputs))
     }
 }
@@ -0,0 +1,594 @@
+//! GPU execution of expensive host kernels.
+//!
+//! Matrix multiplication and elementwise arithmetic on `HostFloat32Tensor` and
+//! `HostRing64Tensor` values can be offloaded to a GPU using wgpu. Operands are
+//! uploaded to the device when a kernel runs and results are copied back into
+//! host tensors, so offloaded kernels are interchangeable with their CPU
+//! counterparts. Kernels on other types, on operands with shapes the GPU
+//! kernels do not support (such as broadcasting), or on operands too small to
+//! make up for the cost of copying them to the device, fall back to the CPU.
+//!
+//! Waiting for the device blocks the calling thread, so kernels compiled here
+//! always run on the compute pool, or on a blocking thread if there is none.
+//!
+//! Ring elements are represented on the device as pairs of 32-bit words since
+//! WGSL has no 64-bit integers.
+
+use crate::computation::{Operator, Placement, Role, Ty, Value};
+use crate::error::{Error, Result};
+use crate::execution::AsyncSession;
+use crate::host::{HostPlacement, HostRingTensor, HostTensor};
+use crate::kernels::{DispatchKernel, Kernel};
+use ndarray::{ArrayD, IxDyn};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::num::Wrapping;
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+
+const ELEMENTWISE_WORKGROUP_SIZE: u32 = 64;
+const MATMUL_WORKGROUP_SIZE: u32 = 8;
+const MAX_WORKGROUPS: u32 = 65535;
+
+/// Default number of scalar operations below which kernels run on the CPU.
+pub const DEFAULT_MIN_GPU_WORK: usize = 1 << 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Dot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ElementType {
+    Float32,
+    Ring64,
+}
+
+impl ElementType {
+    fn of(ty: Ty) -> Option<ElementType> {
+        match ty {
+            Ty::HostFloat32Tensor => Some(ElementType::Float32),
+            Ty::HostRing64Tensor => Some(ElementType::Ring64),
+            _ => None,
+        }
+    }
+
+    fn wgsl(&self) -> &'static str {
+        match self {
+            ElementType::Float32 => "f32",
+            ElementType::Ring64 => "vec2<u32>",
+        }
+    }
+}
+
+/// WGSL helpers for arithmetic on 64-bit ring elements stored as (low, high) words.
+const RING64_FUNCTIONS: &str = r#"
+fn add_elem(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let lo = a.x + b.x;
+    let carry = select(0u, 1u, lo < a.x);
+    return vec2<u32>(lo, a.y + b.y + carry);
+}
+
+fn sub_elem(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let borrow = select(0u, 1u, a.x < b.x);
+    return vec2<u32>(a.x - b.x, a.y - b.y - borrow);
+}
+
+fn mul_wide(a: u32, b: u32) -> vec2<u32> {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    let lo = (p00 & 0xffffu) | (mid << 16u);
+    let hi = p11 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u);
+    return vec2<u32>(lo, hi);
+}
+
+fn mul_elem(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let lo = mul_wide(a.x, b.x);
+    return vec2<u32>(lo.x, lo.y + a.x * b.y + a.y * b.x);
+}
+
+fn zero_elem() -> vec2<u32> {
+    return vec2<u32>(0u, 0u);
+}
+"#;
+
+const FLOAT32_FUNCTIONS: &str = r#"
+fn add_elem(a: f32, b: f32) -> f32 {
+    return a + b;
+}
+
+fn sub_elem(a: f32, b: f32) -> f32 {
+    return a - b;
+}
+
+fn mul_elem(a: f32, b: f32) -> f32 {
+    return a * b;
+}
+
+fn zero_elem() -> f32 {
+    return 0.0;
+}
+"#;
+
+fn shader_source(op: BinaryOp, elem: ElementType) -> String {
+    let functions = match elem {
+        ElementType::Float32 => FLOAT32_FUNCTIONS,
+        ElementType::Ring64 => RING64_FUNCTIONS,
+    };
+    let ty = elem.wgsl();
+    let body = match op {
+        BinaryOp::Dot => format!(
+            r#"
+struct Dims {{
+    m: u32,
+    k: u32,
+    n: u32,
+    pad: u32,
+}}
+
+@group(0) @binding(3) var<uniform> dims: Dims;
+
+@compute @workgroup_size({size}, {size})
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
+    let row = id.x;
+    let col = id.y;
+    if (row >= dims.m || col >= dims.n) {{
+        return;
+    }}
+    var acc = zero_elem();
+    for (var i = 0u; i < dims.k; i = i + 1u) {{
+        acc = add_elem(acc, mul_elem(lhs[row * dims.k + i], rhs[i * dims.n + col]));
+    }}
+    out[row * dims.n + col] = acc;
+}}
+"#,
+            size = MATMUL_WORKGROUP_SIZE
+        ),
+        _ => {
+            let f = match op {
+                BinaryOp::Add => "add_elem",
+                BinaryOp::Sub => "sub_elem",
+                BinaryOp::Mul => "mul_elem",
+                BinaryOp::Dot => unreachable!(),
+            };
+            format!(
+                r#"
+@compute @workgroup_size({size})
+fn main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {{
+    let i = id.y * groups.x * {size}u + id.x;
+    if (i >= arrayLength(&out)) {{
+        return;
+    }}
+    out[i] = {f}(lhs[i], rhs[i]);
+}}
+"#,
+                size = ELEMENTWISE_WORKGROUP_SIZE,
+                f = f
+            )
+        }
+    };
+    format!(
+        r#"
+@group(0) @binding(0) var<storage, read> lhs: array<{ty}>;
+@group(0) @binding(1) var<storage, read> rhs: array<{ty}>;
+@group(0) @binding(2) var<storage, read_write> out: array<{ty}>;
+{functions}
+{body}
+"#,
+        ty = ty,
+        functions = functions,
+        body = body
+    )
+}
+
+/// Operand of a GPU kernel in row-major order, as 32-bit words.
+struct DeviceOperand {
+    shape: Vec<usize>,
+    words: Vec<u32>,
+}
+
+impl DeviceOperand {
+    fn from_value(value: &Value) -> Option<(ElementType, DeviceOperand)> {
+        match value {
+            Value::HostFloat32Tensor(x) => Some((
+                ElementType::Float32,
+                DeviceOperand {
+                    shape: x.0.shape().to_vec(),
+                    words: x.0.iter().map(|v| v.to_bits()).collect(),
+                },
+            )),
+            Value::HostRing64Tensor(x) => Some((
+                ElementType::Ring64,
+                DeviceOperand {
+                    shape: x.0.shape().to_vec(),
+                    words: x
+                        .0
+                        .iter()
+                        .flat_map(|v| [v.0 as u32, (v.0 >> 32) as u32])
+                        .collect(),
+                },
+            )),
+            _ => None,
+        }
+    }
+
+    fn num_elements(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+fn into_value(
+    elem: ElementType,
+    shape: Vec<usize>,
+    words: Vec<u32>,
+    plc: HostPlacement,
+) -> Result<Value> {
+    let shape = IxDyn(&shape);
+    let to_kernel_error =
+        |e: ndarray::ShapeError| Error::KernelError(format!("unexpected GPU result shape: {}", e));
+    match elem {
+        ElementType::Float32 => {
+            let values = words.into_iter().map(f32::from_bits).collect();
+            let array = ArrayD::from_shape_vec(shape, values).map_err(to_kernel_error)?;
+            Ok(Value::HostFloat32Tensor(Box::new(HostTensor(
+                array.into_shared(),
+                plc,
+            ))))
+        }
+        ElementType::Ring64 => {
+            let values = words
+                .chunks_exact(2)
+                .map(|w| Wrapping((w[0] as u64) | ((w[1] as u64) << 32)))
+                .collect();
+            let array = ArrayD::from_shape_vec(shape, values).map_err(to_kernel_error)?;
+            Ok(Value::HostRing64Tensor(Box::new(HostRingTensor(
+                array.into_shared(),
+                plc,
+            ))))
+        }
+    }
+}
+
+/// GPU device used for the host kernels of selected placements.
+pub struct GpuBackend {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    roles: HashSet<Role>,
+    min_work: usize,
+    pipelines: Mutex<HashMap<(BinaryOp, ElementType), Arc<wgpu::ComputePipeline>>>,
+}
+
+impl GpuBackend {
+    /// Open the default GPU and use it for host placements owned by any of `roles`.
+    pub fn new(roles: HashSet<Role>) -> Result<Self> {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .ok_or_else(|| Error::Unexpected(Some("no GPU adapter available".to_string())))?;
+        tracing::info!("Using GPU {:?}", adapter.get_info());
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("moose"),
+                features: wgpu::Features::empty(),
+                limits: adapter.limits(),
+            },
+            None,
+        ))
+        .map_err(|e| Error::Unexpected(Some(format!("failed to open GPU device: {}", e))))?;
+        Ok(GpuBackend {
+            device,
+            queue,
+            roles,
+            min_work: DEFAULT_MIN_GPU_WORK,
+            pipelines: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Only use the GPU for kernels performing at least `min_work` scalar operations.
+    pub fn with_min_work(self, min_work: usize) -> Self {
+        GpuBackend { min_work, ..self }
+    }
+
+    /// Compile `op` for execution on the GPU, if supported on `plc`.
+    pub(crate) fn compile(
+        self: &Arc<Self>,
+        op: &Operator,
+        plc: &Placement,
+    ) -> Result<Option<Kernel<AsyncSession, Value>>> {
+        let host = match plc {
+            Placement::Host(host) if self.roles.contains(&host.owner) => host.clone(),
+            _ => return Ok(None),
+        };
+        let binary_op = match op {
+            Operator::Add(_) => BinaryOp::Add,
+            Operator::Sub(_) => BinaryOp::Sub,
+            Operator::Mul(_) => BinaryOp::Mul,
+            Operator::Dot(_) => BinaryOp::Dot,
+            _ => return Ok(None),
+        };
+        let sig = op.sig();
+        let elem = match ElementType::of(sig.ret()) {
+            Some(elem) if sig.arg(0)? == sig.ret() && sig.arg(1)? == sig.ret() => elem,
+            _ => return Ok(None),
+        };
+        let fallback = match <Operator as DispatchKernel<AsyncSession, Value>>::compile(op, plc)? {
+            Kernel::Binary { closure } => closure,
+            _ => return Ok(None),
+        };
+
+        let gpu = Arc::clone(self);
+        Ok(Some(Kernel::Binary {
+            closure: Box::new(move |sess, plc, x, y| {
+                match gpu.execute(binary_op, elem, &x, &y, &host)? {
+                    Some(z) => Ok(z),
+                    None => fallback(sess, plc, x, y),
+                }
+            }),
+        }))
+    }
+
+    fn pipeline(&self, op: BinaryOp, elem: ElementType) -> Arc<wgpu::ComputePipeline> {
+        let mut pipelines = self.pipelines.lock();
+        let pipeline = pipelines.entry((op, elem)).or_insert_with(|| {
+            let module = self
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("moose kernel"),
+                    source: wgpu::ShaderSource::Wgsl(shader_source(op, elem).into()),
+                });
+            Arc::new(
+                self.device
+                    .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                        label: Some("moose kernel"),
+                        layout: None,
+                        module: &module,
+                        entry_point: "main",
+                    }),
+            )
+        });
+        Arc::clone(pipeline)
+    }
+
+    fn storage_buffer(&self, words: &[u32]) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(words),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+    }
+
+    /// Run `op` on the GPU, returning `None` if the operands are not supported.
+    fn execute(
+        &self,
+        op: BinaryOp,
+        elem: ElementType,
+        x: &Value,
+        y: &Value,
+        plc: &HostPlacement,
+    ) -> Result<Option<Value>> {
+        let (lhs, rhs) = match (DeviceOperand::from_value(x), DeviceOperand::from_value(y)) {
+            (Some((lhs_elem, lhs)), Some((rhs_elem, rhs)))
+                if lhs_elem == elem && rhs_elem == elem =>
+            {
+                (lhs, rhs)
+            }
+            _ => return Ok(None),
+        };
+        if lhs.num_elements() == 0 || rhs.num_elements() == 0 {
+            return Ok(None);
+        }
+
+        let words_per_elem = lhs.words.len() / lhs.num_elements();
+        let (out_shape, workgroups, dims) = match op {
+            BinaryOp::Dot => match (&lhs.shape[..], &rhs.shape[..]) {
+                (&[m, k], &[k2, n]) if k == k2 => {
+                    let groups = |len: usize| {
+                        (len as u32 + MATMUL_WORKGROUP_SIZE - 1) / MATMUL_WORKGROUP_SIZE
+                    };
+                    if groups(m) > MAX_WORKGROUPS
+                        || groups(n) > MAX_WORKGROUPS
+                        || m * k * n < self.min_work
+                    {
+                        return Ok(None);
+                    }
+                    let dims = [m as u32, k as u32, n as u32, 0];
+                    (vec![m, n], (groups(m), groups(n)), Some(dims))
+                }
+                _ => return Ok(None),
+            },
+            _ => {
+                if lhs.shape != rhs.shape || lhs.num_elements() < self.min_work {
+                    return Ok(None);
+                }
+                let len = lhs.num_elements() as u32;
+                let groups = (len + ELEMENTWISE_WORKGROUP_SIZE - 1) / ELEMENTWISE_WORKGROUP_SIZE;
+                let x_groups = std::cmp::min(groups, MAX_WORKGROUPS);
+                let y_groups = (groups + x_groups - 1) / x_groups;
+                (lhs.shape.clone(), (x_groups, y_groups), None)
+            }
+        };
+        let out_len: usize = out_shape.iter().product::<usize>() * words_per_elem;
+        let out_size = (out_len * std::mem::size_of::<u32>()) as wgpu::BufferAddress;
+
+        let lhs_buffer = self.storage_buffer(&lhs.words);
+        let rhs_buffer = self.storage_buffer(&rhs.words);
+        let out_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: out_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: out_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let dims_buffer = dims.map(|dims| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(&dims),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                })
+        });
+
+        let pipeline = self.pipeline(op, elem);
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: lhs_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: rhs_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: out_buffer.as_entire_binding(),
+            },
+        ];
+        if let Some(ref dims_buffer) = dims_buffer {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 3,
+                resource: dims_buffer.as_entire_binding(),
+            });
+        }
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups.0, workgroups.1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&out_buffer, 0, &staging_buffer, 0, out_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            let _ = sender.send(res);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| Error::KernelError("GPU result was never mapped".to_string()))?
+            .map_err(|e| Error::KernelError(format!("failed to read GPU result: {}", e)))?;
+        let view = slice.get_mapped_range();
+        let words = bytemuck::cast_slice::<u8, u32>(&view).to_vec();
+        drop(view);
+        staging_buffer.unmap();
+
+        into_value(elem, out_shape, words, plc.clone()).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_operand_roundtrip() {
+        let plc = HostPlacement::from("alice");
+        let x = HostRingTensor::<u64>(
+            ArrayD::from_shape_vec(
+                IxDyn(&[2]),
+                vec![Wrapping((1 << 40) | 7), Wrapping(u64::MAX)],
+            )
+            .unwrap()
+            .into_shared(),
+            plc.clone(),
+        );
+        let value = Value::HostRing64Tensor(Box::new(x));
+        let (elem, operand) = DeviceOperand::from_value(&value).unwrap();
+        assert_eq!(elem, ElementType::Ring64);
+        assert_eq!(operand.words, vec![7, 1 << 8, u32::MAX, u32::MAX]);
+        let res = into_value(elem, operand.shape, operand.words, plc).unwrap();
+        assert_eq!(res, value);
+    }
+
+    #[test]
+    fn test_shader_source() {
+        let source = shader_source(BinaryOp::Dot, ElementType::Ring64);
+        assert!(source.contains("array<vec2<u32>>"));
+        assert!(source.contains("var<uniform> dims"));
+        let source = shader_source(BinaryOp::Add, ElementType::Float32);
+        assert!(source.contains("out[i] = add_elem(lhs[i], rhs[i]);"));
+    }
+
+    #[test]
+    fn test_execute_on_gpu() {
+        let roles = vec![Role::from("alice")].into_iter().collect();
+        let gpu = match GpuBackend::new(roles) {
+            Ok(gpu) => gpu.with_min_work(0),
+            Err(_) => {
+                eprintln!("skipping test_execute_on_gpu: no GPU adapter available");
+                return;
+            }
+        };
+        let plc = HostPlacement::from("alice");
+        let ring = |values: Vec<u64>, shape: &[usize]| {
+            let values = values.into_iter().map(Wrapping).collect();
+            let array = ArrayD::from_shape_vec(IxDyn(shape), values).unwrap();
+            Value::HostRing64Tensor(Box::new(HostRingTensor(array.into_shared(), plc.clone())))
+        };
+        let x = ring(vec![u64::MAX, 1 << 33, 3, 4], &[2, 2]);
+        let y = ring(vec![2, 1 << 31, 5, 6], &[2, 2]);
+
+        let z = gpu
+            .execute(BinaryOp::Add, ElementType::Ring64, &x, &y, &plc)
+            .unwrap();
+        assert_eq!(
+            z,
+            Some(ring(vec![1, (1 << 33) + (1 << 31), 8, 10], &[2, 2]))
+        );
+
+        let z = gpu
+            .execute(BinaryOp::Mul, ElementType::Ring64, &x, &y, &plc)
+            .unwrap();
+        assert_eq!(z, Some(ring(vec![u64::MAX - 1, 0, 15, 24], &[2, 2])));
+
+        let z = gpu
+            .execute(BinaryOp::Dot, ElementType::Ring64, &x, &y, &plc)
+            .unwrap();
+        let expected = vec![
+            u64::MAX.wrapping_mul(2).wrapping_add((1 << 33) * 5),
+            u64::MAX.wrapping_mul(1 << 31).wrapping_add((1 << 33) * 6),
+            3 * 2 + 4 * 5,
+            3 * (1 << 31) + 4 * 6,
+        ];
+        assert_eq!(z, Some(ring(expected, &[2, 2])));
+
+        // small kernels stay on the CPU
+        let gpu = gpu.with_min_work(DEFAULT_MIN_GPU_WORK);
+        let z = gpu
+            .execute(BinaryOp::Add, ElementType::Ring64, &x, &y, &plc)
+            .unwrap();
+        assert_eq!(z, None);
+    }
+}
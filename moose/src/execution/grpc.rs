@@ -1,22 +1,120 @@
+use crate::choreography::capabilities::{check_compatibility, Capabilities};
 use crate::choreography::grpc::gen::choreography_client::ChoreographyClient;
 use crate::choreography::grpc::gen::{
-    AbortComputationRequest, LaunchComputationRequest, RetrieveResultsRequest,
+    AbortComputationRequest, GetCapabilitiesRequest, GetHealthRequest, LaunchComputationRequest,
+    ListSessionsRequest, RetrieveResultsRequest, StreamResultsRequest, WatchProgressRequest,
 };
-use crate::choreography::grpc::ComputationOutputs;
+use crate::choreography::grpc::{ComputationOutputs, SessionInfo, SessionMetadata, SessionState};
+use crate::choreography::health::WorkerHealth;
+#[cfg(feature = "compile")]
+use crate::compilation::batching::{batch_computation, stack_arguments, unstack_outputs};
+#[cfg(feature = "compile")]
+use crate::compilation::policy::{enforce_policy, LabelledComputation, RevealPolicy};
+#[cfg(feature = "compile")]
+use crate::compilation::{compile, Pass};
+use crate::execution::communication::CommunicationReport;
+use crate::execution::memory::MemoryUsage;
+use crate::execution::progress::SessionProgress;
+use crate::networking::accounting::NetworkStats;
+use crate::networking::discovery::{resolve_all, Discovery};
+use crate::networking::transcript::TranscriptCommitment;
 use crate::prelude::{Computation, Identity, Role, SessionId, Value};
-use std::collections::HashMap;
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::time::Duration;
-use tonic::transport::{Channel, ClientTlsConfig, Uri};
+use tonic::transport::{Channel, ClientTlsConfig};
 
 pub struct GrpcMooseRuntime {
     role_assignments: HashMap<Role, Identity>,
     channels: HashMap<Role, Channel>,
+    auth_token: Option<String>,
+    max_message_size: usize,
+    check_capabilities: bool,
+}
+
+/// Session in a DAG of sessions launched with [`GrpcMooseRuntime::launch_dag`].
+pub struct DagSession {
+    pub session_id: SessionId,
+    pub computation: Computation,
+    pub arguments: HashMap<String, Value>,
+    /// Sessions in the same DAG that must complete before this one starts.
+    pub depends_on: Vec<SessionId>,
+}
+
+/// Order `sessions` such that every session comes after those it depends on.
+fn dag_order(sessions: Vec<DagSession>) -> Result<Vec<DagSession>, Box<dyn std::error::Error>> {
+    let ids: HashSet<&SessionId> = sessions.iter().map(|session| &session.session_id).collect();
+    if ids.len() != sessions.len() {
+        return Err("session ids in DAG must be unique".into());
+    }
+    for session in sessions.iter() {
+        if let Some(unknown) = session.depends_on.iter().find(|id| !ids.contains(id)) {
+            return Err(format!(
+                "session {} depends on {} which is not part of the DAG",
+                session.session_id, unknown
+            )
+            .into());
+        }
+    }
+
+    let mut launched: HashSet<SessionId> = HashSet::with_capacity(sessions.len());
+    let mut ordered = Vec::with_capacity(sessions.len());
+    let mut remaining = sessions;
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) = remaining
+            .into_iter()
+            .partition(|session| session.depends_on.iter().all(|id| launched.contains(id)));
+        if ready.is_empty() {
+            return Err("sessions in DAG have cyclic dependencies".into());
+        }
+        launched.extend(ready.iter().map(|session| session.session_id.clone()));
+        ordered.extend(ready);
+        remaining = blocked;
+    }
+    Ok(ordered)
+}
+
+/// Channel to the worker with `identity`, connecting to the `endpoint` it was resolved to.
+fn connect(
+    identity: &Identity,
+    endpoint: &str,
+    tls_config: &Option<ClientTlsConfig>,
+) -> Result<Channel, Box<dyn std::error::Error>> {
+    let mut channel = crate::grpc::endpoint(endpoint)?;
+    if let Some(ref tls_config) = tls_config {
+        let tls_config = match crate::grpc::tls_domain_name(&identity.0) {
+            Some(domain_name) => tls_config.clone().domain_name(domain_name),
+            None => tls_config.clone(),
+        };
+        channel = channel.tls_config(tls_config)?;
+    };
+    Ok(crate::grpc::connect_lazy(channel, endpoint))
 }
 
 #[derive(Debug)]
 pub struct GrpcOutputs {
     pub outputs: HashMap<String, Value>,
     pub elapsed_time: Option<HashMap<Role, Duration>>,
+    pub network_stats: Option<HashMap<Role, NetworkStats>>,
+    pub memory_usage: Option<HashMap<Role, MemoryUsage>>,
+    /// Messages sent during the session by all roles, if reported by the workers.
+    pub communication: Option<CommunicationReport>,
+    /// Commitments of the roles to the messages they exchanged, if recorded by the workers.
+    ///
+    /// These can be compared using [`crate::networking::transcript::check_transcripts`].
+    pub transcripts: Option<HashMap<Role, TranscriptCommitment>>,
+    /// Errors of the roles on which the session failed, in which case `outputs` are partial.
+    ///
+    /// Only ever set by [`GrpcMooseRuntime::retrieve_partial_results`].
+    pub errors: Option<HashMap<Role, String>>,
+}
+
+impl GrpcOutputs {
+    /// Whether the session failed, so that `outputs` only holds those completed before.
+    pub fn is_partial(&self) -> bool {
+        self.errors.is_some()
+    }
 }
 
 impl GrpcMooseRuntime {
@@ -27,22 +125,113 @@ impl GrpcMooseRuntime {
         let channels = role_assignments
             .iter()
             .map(|(role, identity)| {
-                let endpoint: Uri = format!("http://{}", identity).parse()?;
-                let mut channel = Channel::builder(endpoint);
-                if let Some(ref tls_config) = tls_config {
-                    channel = channel.tls_config(tls_config.clone())?;
-                };
-                let channel = channel.connect_lazy();
-                Ok((role.clone(), channel))
+                Ok((role.clone(), connect(identity, &identity.0, &tls_config)?))
+            })
+            .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+
+        Ok(GrpcMooseRuntime {
+            role_assignments,
+            channels,
+            auth_token: None,
+            max_message_size: usize::MAX,
+            check_capabilities: false,
+        })
+    }
+
+    /// Like `new` but connecting to the endpoints that `discovery` resolves identities to.
+    ///
+    /// Sessions are still launched with the given identities, which workers resolve
+    /// in the same way when sending values to each other.
+    pub async fn new_with_discovery(
+        role_assignments: HashMap<Role, Identity>,
+        tls_config: Option<ClientTlsConfig>,
+        discovery: &dyn Discovery,
+    ) -> Result<GrpcMooseRuntime, Box<dyn std::error::Error>> {
+        let endpoints = resolve_all(discovery, role_assignments.values()).await?;
+        let channels = role_assignments
+            .iter()
+            .map(|(role, identity)| {
+                Ok((
+                    role.clone(),
+                    connect(identity, &endpoints[identity], &tls_config)?,
+                ))
             })
             .collect::<Result<_, Box<dyn std::error::Error>>>()?;
 
         Ok(GrpcMooseRuntime {
             role_assignments,
             channels,
+            auth_token: None,
+            max_message_size: usize::MAX,
+            check_capabilities: false,
         })
     }
 
+    /// Limit the size of computations, arguments, and results, in bytes.
+    pub fn with_max_message_size(self, max_message_size: usize) -> Self {
+        GrpcMooseRuntime {
+            max_message_size,
+            ..self
+        }
+    }
+
+    fn client(&self, channel: &Channel) -> ChoreographyClient<Channel> {
+        ChoreographyClient::new(channel.clone())
+            .max_decoding_message_size(self.max_message_size)
+            .max_encoding_message_size(self.max_message_size)
+    }
+
+    /// Check the capabilities of the workers before launching each session.
+    pub fn with_capability_check(self) -> Self {
+        GrpcMooseRuntime {
+            check_capabilities: true,
+            ..self
+        }
+    }
+
+    /// Capabilities of the worker playing each role, checked against `computation`.
+    pub async fn capabilities(
+        &self,
+        computation: &Computation,
+    ) -> Result<HashMap<Role, Capabilities>, Box<dyn std::error::Error>> {
+        let computation = bincode::serialize(computation)?;
+        let role_assignment = bincode::serialize(&self.role_assignments)?;
+
+        let mut capabilities = HashMap::with_capacity(self.channels.len());
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
+
+            let mut request = tonic::Request::new(GetCapabilitiesRequest {
+                computation: computation.clone(),
+                role_assignment: role_assignment.clone(),
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
+
+            let response = client.get_capabilities(request).await?;
+            let worker: Capabilities = bincode::deserialize(&response.get_ref().capabilities)?;
+            capabilities.insert(role.clone(), worker);
+        }
+        Ok(capabilities)
+    }
+
+    /// Check that the workers can together run `computation`, reporting all incompatibilities.
+    pub async fn check_capabilities(
+        &self,
+        computation: &Computation,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let capabilities = self.capabilities(computation).await?;
+        check_compatibility(computation, &capabilities)?;
+        Ok(())
+    }
+
+    /// Use `auth_token` as bearer token when talking to the workers.
+    pub fn with_auth_token(self, auth_token: String) -> Self {
+        GrpcMooseRuntime {
+            auth_token: Some(auth_token),
+            ..self
+        }
+    }
+
     pub async fn run_computation(
         &self,
         session_id: &SessionId,
@@ -54,28 +243,275 @@ impl GrpcMooseRuntime {
         self.retrieve_results(session_id).await
     }
 
+    /// Run a session, retrying it up to `max_attempts` times in total if it fails.
+    ///
+    /// Each attempt is launched with `idempotency_key` and a session id derived from
+    /// it, so that if the driver is restarted and runs the same pipeline again,
+    /// workers return the results of attempts that completed or are still running
+    /// instead of running them again.
+    pub async fn run_computation_with_retries(
+        &self,
+        idempotency_key: &str,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        max_attempts: usize,
+    ) -> Result<GrpcOutputs, Box<dyn std::error::Error>> {
+        let metadata = SessionMetadata {
+            idempotency_key: Some(idempotency_key.to_string()),
+            ..Default::default()
+        };
+        let mut backoff = Duration::from_secs(1);
+        for attempt in 1..=max_attempts {
+            let session_id =
+                SessionId::try_from(format!("{}#{}", idempotency_key, attempt).as_str())?;
+            let res = match self
+                .launch_computation_with_metadata(
+                    &session_id,
+                    computation,
+                    arguments.clone(),
+                    &metadata,
+                )
+                .await
+            {
+                Ok(()) => self.retrieve_results(&session_id).await,
+                Err(e) => Err(e),
+            };
+            match res {
+                Ok(outputs) => return Ok(outputs),
+                Err(e) if attempt < max_attempts => {
+                    tracing::warn!(
+                        "Attempt {} of session {} failed: {}",
+                        attempt,
+                        idempotency_key,
+                        e
+                    );
+                    self.settle(&session_id).await;
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err("at least one attempt is required".into())
+    }
+
+    /// Make sure a failed session has stopped on all workers before it is retried,
+    /// so that they all agree that the retry is not a duplicate of it.
+    async fn settle(&self, session_id: &SessionId) {
+        let _ = self
+            .abort_computation(session_id, Some("retrying failed session"))
+            .await;
+        let session_id = match bincode::serialize(&session_id) {
+            Ok(session_id) => session_id,
+            Err(_) => return,
+        };
+        for channel in self.channels.values() {
+            let mut request = tonic::Request::new(RetrieveResultsRequest {
+                session_id: session_id.clone(),
+                structured: false,
+            });
+            if crate::grpc::attach_token(&mut request, &self.auth_token).is_ok() {
+                let _ = self.client(channel).retrieve_results(request).await;
+            }
+        }
+    }
+
+    /// Run independent instances of `computation`, one for each element of `instances`,
+    /// as a single batched session.
+    #[cfg(feature = "compile")]
+    pub async fn run_batched_computation(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        instances: Vec<HashMap<String, Value>>,
+    ) -> Result<Vec<HashMap<String, Value>>, Box<dyn std::error::Error>> {
+        let batched = batch_computation(computation)?;
+        let arguments = stack_arguments(&instances)?;
+        let results = self
+            .run_computation(session_id, &batched.computation, arguments)
+            .await?;
+        Ok(unstack_outputs(
+            &results.outputs,
+            &batched.batched_outputs,
+            instances.len(),
+        )?)
+    }
+
     pub async fn launch_computation(
         &self,
         session_id: &SessionId,
         computation: &Computation,
         arguments: HashMap<String, Value>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.launch_computation_with_metadata(
+            session_id,
+            computation,
+            arguments,
+            &SessionMetadata::default(),
+        )
+        .await
+    }
+
+    /// Launch a session described by `metadata`, which is reported when listing sessions.
+    pub async fn launch_computation_with_metadata(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        metadata: &SessionMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.launch(
+            session_id,
+            computation,
+            arguments,
+            0,
+            &[],
+            metadata,
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Compile the logical computation `logical` and launch it labelled with
+    /// `policy`, which workers check it against before launching it, together
+    /// with any policy of their own.
+    #[cfg(feature = "compile")]
+    pub async fn launch_computation_with_policy(
+        &self,
+        session_id: &SessionId,
+        logical: &Computation,
+        policy: &RevealPolicy,
+        arguments: HashMap<String, Value>,
+        metadata: &SessionMetadata,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let computation = compile(enforce_policy(logical.clone(), policy)?, None::<Vec<Pass>>)?;
+        let labelled = bincode::serialize(&LabelledComputation {
+            logical: logical.clone(),
+            policy: policy.clone(),
+        })?;
+        self.launch(
+            session_id,
+            &computation,
+            arguments,
+            0,
+            &[],
+            metadata,
+            labelled,
+        )
+        .await
+    }
+
+    /// Launch a session that workers only start once all of `dependencies` have
+    /// completed, failing it instead if any of them failed.
+    ///
+    /// The dependencies must already have been launched. Outputs of a dependency
+    /// are typically passed on by saving them to storage under keys that the
+    /// computation of this session loads from.
+    pub async fn launch_computation_after(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        dependencies: &[SessionId],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.launch(
+            session_id,
+            computation,
+            arguments,
+            0,
+            dependencies,
+            &SessionMetadata::default(),
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Launch a DAG of sessions at once, leaving it to the workers to run each
+    /// session once the sessions it depends on have completed.
+    pub async fn launch_dag(
+        &self,
+        sessions: Vec<DagSession>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for session in dag_order(sessions)? {
+            self.launch_computation_after(
+                &session.session_id,
+                &session.computation,
+                session.arguments,
+                &session.depends_on,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Relaunch a session on restarted workers, resuming all of them from `checkpoint`.
+    ///
+    /// The workers must have been configured with checkpointing, and `checkpoint`
+    /// must be available on all of them.
+    pub async fn resume_computation(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        checkpoint: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if checkpoint == 0 {
+            return Err("checkpoint must be non-zero".into());
+        }
+        self.launch(
+            session_id,
+            computation,
+            arguments,
+            checkpoint,
+            &[],
+            &SessionMetadata::default(),
+            Vec::new(),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn launch(
+        &self,
+        session_id: &SessionId,
+        computation: &Computation,
+        arguments: HashMap<String, Value>,
+        resume_checkpoint: u64,
+        dependencies: &[SessionId],
+        metadata: &SessionMetadata,
+        labelled_computation: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.check_capabilities {
+            self.check_capabilities(computation).await?;
+        }
+
         let session_id = bincode::serialize(session_id)?;
+        let metadata = bincode::serialize(metadata)?;
+        let depends_on = dependencies
+            .iter()
+            .map(bincode::serialize)
+            .collect::<Result<Vec<_>, _>>()?;
         let computation = bincode::serialize(computation)?;
         let arguments = bincode::serialize(&arguments)?;
         let role_assignment = bincode::serialize(&self.role_assignments)?;
 
         for channel in self.channels.values() {
-            let mut client = ChoreographyClient::new(channel.clone());
+            let mut client = self.client(channel);
 
             // TODO(Morten) SECURITY: note that _all_ arguments are sent to _all_ workers;
             // this may still be okay/needed if/when we send value references around
-            let request = LaunchComputationRequest {
+            let mut request = tonic::Request::new(LaunchComputationRequest {
                 session_id: session_id.clone(),
                 computation: computation.clone(),
                 arguments: arguments.clone(),
                 role_assignment: role_assignment.clone(),
-            };
+                resume_checkpoint,
+                depends_on: depends_on.clone(),
+                metadata: metadata.clone(),
+                labelled_computation: labelled_computation.clone(),
+                ..Default::default()
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
 
             let _response = client.launch_computation(request).await?;
         }
@@ -83,64 +519,360 @@ impl GrpcMooseRuntime {
         Ok(())
     }
 
+    /// Abort a running session on all workers, optionally giving a `reason`.
+    ///
+    /// All workers are asked to abort, even if some of them fail to do so.
     pub async fn abort_computation(
         &self,
         session_id: &SessionId,
+        reason: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let session_id = bincode::serialize(&session_id)?;
 
-        for channel in self.channels.values() {
-            let mut client = ChoreographyClient::new(channel.clone());
+        let mut failures = Vec::new();
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
 
-            let request = AbortComputationRequest {
+            let mut request = tonic::Request::new(AbortComputationRequest {
                 session_id: session_id.clone(),
-            };
+                reason: reason.unwrap_or_default().to_string(),
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
 
-            let _response = client.abort_computation(request).await?;
+            if let Err(e) = client.abort_computation(request).await {
+                failures.push(format!("{}: {}", role, e.message()));
+            }
         }
 
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("failed to abort session on {}", failures.join(", ")).into())
+        }
+    }
+
+    /// Call `on_progress` with the progress of the session on each worker until it has finished everywhere.
+    pub async fn watch_progress<F>(
+        &self,
+        session_id: &SessionId,
+        mut on_progress: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(&Role, &SessionProgress),
+    {
+        let session_id = bincode::serialize(&session_id)?;
+
+        let mut streams = Vec::with_capacity(self.channels.len());
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
+
+            let mut request = tonic::Request::new(WatchProgressRequest {
+                session_id: session_id.clone(),
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
+
+            let stream = client.watch_progress(request).await?.into_inner();
+            let role = role.clone();
+            streams.push(stream.map(move |res| (role.clone(), res)).boxed());
+        }
+
+        let mut updates = futures::stream::select_all(streams);
+        while let Some((role, res)) = updates.next().await {
+            let progress: SessionProgress = bincode::deserialize(&res?.progress)?;
+            on_progress(&role, &progress);
+        }
+        Ok(())
+    }
+
+    /// Call `on_output` with each output of the session as soon as the worker computing it has it.
+    ///
+    /// Returns once all workers have sent all outputs, failing as soon as the session
+    /// fails on any of them.
+    pub async fn stream_results<F>(
+        &self,
+        session_id: &SessionId,
+        mut on_output: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(&Role, String, Value),
+    {
+        let session_id = bincode::serialize(&session_id)?;
+
+        let mut streams = Vec::with_capacity(self.channels.len());
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
+
+            let mut request = tonic::Request::new(StreamResultsRequest {
+                session_id: session_id.clone(),
+                structured: false,
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
+
+            let stream = client.stream_results(request).await?.into_inner();
+            let role = role.clone();
+            streams.push(stream.map(move |res| (role.clone(), res)).boxed());
+        }
+
+        let mut outputs = futures::stream::select_all(streams);
+        while let Some((role, res)) = outputs.next().await {
+            let response =
+                res.map_err(|e| format!("session failed on {}: {}", role, e.message()))?;
+            let value: Value = bincode::deserialize(&response.value)?;
+            on_output(&role, response.tag, value);
+        }
         Ok(())
     }
 
+    /// Health of the worker playing each role.
+    pub async fn health(&self) -> Result<HashMap<Role, WorkerHealth>, Box<dyn std::error::Error>> {
+        let mut health = HashMap::with_capacity(self.channels.len());
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
+
+            let mut request = tonic::Request::new(GetHealthRequest {});
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
+
+            let response = client.get_health(request).await?;
+            let worker_health: WorkerHealth = bincode::deserialize(&response.get_ref().health)?;
+            health.insert(role.clone(), worker_health);
+        }
+        Ok(health)
+    }
+
+    /// Sessions launched on each worker that are in `state`, if given, and have all of `tags`.
+    pub async fn list_sessions(
+        &self,
+        state: Option<SessionState>,
+        tags: &[String],
+    ) -> Result<HashMap<Role, Vec<SessionInfo>>, Box<dyn std::error::Error>> {
+        let mut sessions = HashMap::with_capacity(self.channels.len());
+        for (role, channel) in self.channels.iter() {
+            let mut client = self.client(channel);
+
+            let mut request = tonic::Request::new(ListSessionsRequest {
+                state: state.map(|state| state.to_string()).unwrap_or_default(),
+                tags: tags.to_vec(),
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
+
+            let response = client.list_sessions(request).await?;
+            let infos: Vec<SessionInfo> = bincode::deserialize(&response.get_ref().sessions)?;
+            sessions.insert(role.clone(), infos);
+        }
+        Ok(sessions)
+    }
+
     pub async fn retrieve_results(
         &self,
         session_id: &SessionId,
+    ) -> Result<GrpcOutputs, Box<dyn std::error::Error>> {
+        self.collect_results(session_id, false).await
+    }
+
+    /// Retrieve the results of a session even if it failed, in which case they only
+    /// hold the outputs completed before the failure and the errors of the roles.
+    pub async fn retrieve_partial_results(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<GrpcOutputs, Box<dyn std::error::Error>> {
+        self.collect_results(session_id, true).await
+    }
+
+    async fn collect_results(
+        &self,
+        session_id: &SessionId,
+        partial: bool,
     ) -> Result<GrpcOutputs, Box<dyn std::error::Error>> {
         let session_id = bincode::serialize(&session_id)?;
 
         let mut combined_outputs = HashMap::new();
         let mut combined_stats = HashMap::new();
+        let mut combined_network_stats = HashMap::new();
+        let mut combined_memory_usage = HashMap::new();
+        let mut combined_communication: Option<CommunicationReport> = None;
+        let mut combined_transcripts = HashMap::new();
+        let mut errors = HashMap::new();
 
         for (role, channel) in self.channels.iter() {
-            let mut client = ChoreographyClient::new(channel.clone());
+            let mut client = self.client(channel);
 
-            let request = RetrieveResultsRequest {
+            let mut request = tonic::Request::new(RetrieveResultsRequest {
                 session_id: session_id.clone(),
-            };
+                structured: false,
+            });
+            crate::grpc::attach_token(&mut request, &self.auth_token)?;
 
             let response = client.retrieve_results(request).await?;
 
             let ComputationOutputs {
                 outputs,
                 elapsed_time,
+                network_stats,
+                memory_usage,
+                communication,
+                transcript,
+                error,
             } = bincode::deserialize::<ComputationOutputs>(&response.get_ref().values)?;
+            match error {
+                Some(error) if !partial => {
+                    return Err(format!("session failed on {}: {}", role, error).into());
+                }
+                Some(error) => {
+                    errors.insert(role.clone(), error);
+                }
+                None => (),
+            }
             combined_outputs.extend(outputs);
 
             if let Some(time) = elapsed_time {
                 combined_stats.insert(role.clone(), time);
             }
+
+            if let Some(stats) = network_stats {
+                combined_network_stats.insert(role.clone(), stats);
+            }
+
+            if let Some(usage) = memory_usage {
+                combined_memory_usage.insert(role.clone(), usage);
+            }
+
+            if let Some(report) = communication {
+                combined_communication
+                    .get_or_insert_with(CommunicationReport::default)
+                    .merge(&report);
+            }
+
+            if let Some(commitment) = transcript {
+                combined_transcripts.insert(role.clone(), commitment);
+            }
         }
 
-        if combined_stats.is_empty() {
-            Ok(GrpcOutputs {
-                outputs: combined_outputs,
-                elapsed_time: None,
-            })
-        } else {
-            Ok(GrpcOutputs {
-                outputs: combined_outputs,
-                elapsed_time: Some(combined_stats),
-            })
+        Ok(GrpcOutputs {
+            outputs: combined_outputs,
+            elapsed_time: if combined_stats.is_empty() {
+                None
+            } else {
+                Some(combined_stats)
+            },
+            network_stats: if combined_network_stats.is_empty() {
+                None
+            } else {
+                Some(combined_network_stats)
+            },
+            memory_usage: if combined_memory_usage.is_empty() {
+                None
+            } else {
+                Some(combined_memory_usage)
+            },
+            communication: combined_communication,
+            transcripts: if combined_transcripts.is_empty() {
+                None
+            } else {
+                Some(combined_transcripts)
+            },
+            errors: if errors.is_empty() {
+                None
+            } else {
+                Some(errors)
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::choreography::grpc::GrpcChoreography;
+    use crate::execution::{AsyncNetworkingImpl, AsyncStorageImpl};
+    use crate::networking::local::LocalAsyncNetworking;
+    use crate::storage::local::LocalAsyncStorage;
+    use maplit::hashmap;
+    use std::convert::{TryFrom, TryInto};
+    use std::sync::Arc;
+
+    /// Serve a worker with in-memory storage on a local port, returning its identity.
+    async fn serve() -> Identity {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let identity = Identity::from(listener.local_addr().unwrap().to_string());
+        let choreography = Arc::new(GrpcChoreography::new(
+            identity.clone(),
+            None,
+            Box::new(|_| Arc::new(LocalAsyncNetworking::default()) as AsyncNetworkingImpl),
+            Box::new(|| Arc::new(LocalAsyncStorage::default()) as AsyncStorageImpl),
+        ));
+        let server = tonic::transport::Server::builder()
+            .add_service(GrpcChoreography::new_server(&choreography))
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener));
+        tokio::spawn(server);
+        identity
+    }
+
+    fn session(id: &str, depends_on: &[&str]) -> DagSession {
+        DagSession {
+            session_id: SessionId::try_from(id).unwrap(),
+            computation: Computation { operations: vec![] },
+            arguments: HashMap::new(),
+            depends_on: depends_on
+                .iter()
+                .map(|id| SessionId::try_from(*id).unwrap())
+                .collect(),
         }
     }
+
+    #[test]
+    fn test_dag_order() {
+        let sessions = vec![
+            session("predict", &["train", "encode"]),
+            session("train", &["encode"]),
+            session("encode", &[]),
+        ];
+        let order: Vec<String> = dag_order(sessions)
+            .unwrap()
+            .iter()
+            .map(|session| session.session_id.to_string())
+            .collect();
+        let position = |id: &str| order.iter().position(|x| x == id).unwrap();
+        assert!(position("encode") < position("train"));
+        assert!(position("train") < position("predict"));
+    }
+
+    #[test]
+    fn test_dag_order_rejects_cycles() {
+        let sessions = vec![session("a", &["b"]), session("b", &["a"])];
+        assert!(dag_order(sessions).is_err());
+        assert!(dag_order(vec![session("a", &["c"])]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retries_stop_after_max_attempts() {
+        let runtime =
+            GrpcMooseRuntime::new(hashmap!(Role::from("alice") => serve().await), None).unwrap();
+        let computation: Computation = r#"
+        key = Constant{value = HostString("missing")}: () -> HostString () @Host(alice)
+        query = Constant{value = HostString("")}: () -> HostString () @Host(alice)
+        x = Load: (HostString, HostString) -> HostFloat64Tensor (key, query) @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(alice)
+        "#
+        .try_into()
+        .unwrap();
+
+        let err = runtime
+            .run_computation_with_retries("pipeline", &computation, HashMap::new(), 2)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("key not found"));
+
+        let sessions = runtime.list_sessions(None, &[]).await.unwrap();
+        let attempts: Vec<(String, SessionState)> = sessions[&Role::from("alice")]
+            .iter()
+            .map(|info| (info.session_id.to_string(), info.state))
+            .collect();
+        assert_eq!(
+            attempts,
+            vec![
+                ("pipeline#1".to_string(), SessionState::Failed),
+                ("pipeline#2".to_string(), SessionState::Failed),
+            ]
+        );
+    }
 }
@@ -0,0 +1,562 @@
+//! Memory budget for operands held by kernels during asynchronous execution.
+//!
+//! Kernels may receive some of their operands long before the rest, in which case
+//! the completed values would otherwise stay in memory while waiting. When a
+//! session holds more than its budget, newly completed operands are instead
+//! written to disk and only reloaded once the kernel is ready to run.
+//!
+//! Tensors are accounted for by their buffers, so a buffer used by several
+//! waiting kernels only counts once, and operands whose buffer is already held
+//! by another kernel are never spilled since that would not free any memory.
+//! Spilled operands may be secret shares, so they are written to files only
+//! readable by the worker, in a directory only accessible by the worker.
+//!
+//! Independently of any budget, the bytes of values alive during a session can be
+//! tracked to find out how much memory sessions actually need.
+
+use crate::computation::{SessionId, Value};
+use crate::error::{Error, Result};
+use crate::execution::AsyncValue;
+use crate::networking::wire;
+use futures::stream::FuturesUnordered;
+use futures::{Future, FutureExt, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Configuration of the memory budget given to each session.
+#[derive(Clone, Debug)]
+pub struct MemoryBudgetConfig {
+    /// Maximum number of bytes of operands a session may hold in memory.
+    pub limit: usize,
+    /// Directory in which operands exceeding the limit are kept until needed.
+    ///
+    /// Created if missing; must not be accessible by other users.
+    pub spill_dir: PathBuf,
+}
+
+impl MemoryBudgetConfig {
+    /// Create the spill directory if needed and check that only the worker can access it.
+    pub fn prepare_spill_dir(&self) -> Result<()> {
+        prepare_private_dir(&self.spill_dir)
+    }
+}
+
+fn prepare_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(dir)
+        .map_err(|e| Error::Storage(format!("failed to create spill directory: {}", e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(dir)
+            .map_err(|e| Error::Storage(format!("failed to inspect spill directory: {}", e)))?;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            return Err(Error::InvalidArgument(format!(
+                "spill directory {:?} must not be accessible by other users",
+                dir
+            )));
+        }
+    }
+    Ok(())
+}
+
+macro_rules! tensor_buffers {
+    ($($variant:ident),*) => {
+        /// Address of the buffer holding the elements of a host tensor.
+        fn buffer_address(value: &Value) -> Option<usize> {
+            match value {
+                $(
+                    Value::$variant(x) if !x.0.is_empty() => Some(x.0.as_ptr() as usize),
+                )*
+                _ => None,
+            }
+        }
+    };
+}
+
+tensor_buffers!(
+    HostRing64Tensor,
+    HostRing128Tensor,
+    HostFloat32Tensor,
+    HostFloat64Tensor,
+    HostInt8Tensor,
+    HostInt16Tensor,
+    HostInt32Tensor,
+    HostInt64Tensor,
+    HostUint8Tensor,
+    HostUint16Tensor,
+    HostUint32Tensor,
+    HostUint64Tensor
+);
+
+/// Accounting of the operands held in memory by a single session.
+pub struct MemoryBudget {
+    limit: usize,
+    used: AtomicUsize,
+    /// Number of reservations holding each buffer accounted for in `used`, and its bytes.
+    buffers: Mutex<HashMap<usize, (usize, usize)>>,
+    spill_dir: PathBuf,
+    spill_prefix: String,
+    next_spill: AtomicU64,
+}
+
+impl MemoryBudget {
+    pub fn new(config: &MemoryBudgetConfig, session_id: &SessionId) -> Self {
+        let spill_prefix = session_id
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        MemoryBudget {
+            limit: config.limit,
+            used: AtomicUsize::new(0),
+            buffers: Mutex::new(HashMap::new()),
+            spill_dir: config.spill_dir.clone(),
+            spill_prefix,
+            next_spill: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of bytes currently held in memory.
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    /// Account for `num_bytes` held in `buffer`, unless another reservation already holds it.
+    fn try_reserve(
+        self: &Arc<Self>,
+        buffer: Option<usize>,
+        num_bytes: usize,
+    ) -> Option<Reservation> {
+        let mut buffers = self.buffers.lock();
+        if let Some((holders, _)) = buffer.and_then(|buffer| buffers.get_mut(&buffer)) {
+            *holders += 1;
+            return Some(Reservation {
+                budget: Arc::clone(self),
+                buffer,
+                num_bytes,
+            });
+        }
+        let reserved = self
+            .used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                let used = used.checked_add(num_bytes)?;
+                if used <= self.limit {
+                    Some(used)
+                } else {
+                    None
+                }
+            })
+            .is_ok();
+        if reserved {
+            if let Some(buffer) = buffer {
+                buffers.insert(buffer, (1, num_bytes));
+            }
+            Some(Reservation {
+                budget: Arc::clone(self),
+                buffer,
+                num_bytes,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn spill_path(&self, index: u64) -> PathBuf {
+        self.spill_dir
+            .join(format!("{}-{}.spill", self.spill_prefix, index))
+    }
+
+    async fn spill(&self, value: &Value) -> Result<PathBuf> {
+        let path = self.spill_path(self.next_spill.fetch_add(1, Ordering::SeqCst));
+        let bytes = wire::serialize_value(value)?;
+        let spill_dir = self.spill_dir.clone();
+        tokio::task::spawn_blocking(move || prepare_private_dir(&spill_dir))
+            .await
+            .map_err(|e| Error::Storage(format!("failed to create spill directory: {}", e)))??;
+
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let to_storage_error = |e: std::io::Error| {
+            Error::Storage(format!("failed to spill value to {:?}: {}", path, e))
+        };
+        let mut file = options.open(&path).await.map_err(to_storage_error)?;
+        tokio::io::AsyncWriteExt::write_all(&mut file, &bytes)
+            .await
+            .map_err(to_storage_error)?;
+        Ok(path)
+    }
+
+    async fn reload(path: PathBuf) -> Result<Value> {
+        let bytes = tokio::fs::read(&path).await.map_err(|e| {
+            Error::Storage(format!("failed to reload value from {:?}: {}", path, e))
+        })?;
+        let _ = tokio::fs::remove_file(&path).await;
+        wire::deserialize_value(&bytes)
+    }
+}
+
+impl Drop for MemoryBudget {
+    fn drop(&mut self) {
+        // operands of failed or cancelled sessions may never have been reloaded
+        for index in 0..*self.next_spill.get_mut() {
+            let _ = std::fs::remove_file(self.spill_path(index));
+        }
+    }
+}
+
+/// Bytes accounted for in a [`MemoryBudget`], released when dropped.
+///
+/// The bytes of a buffer are only released by the last reservation holding it.
+pub(crate) struct Reservation {
+    budget: Arc<MemoryBudget>,
+    buffer: Option<usize>,
+    num_bytes: usize,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let num_bytes = match self.buffer {
+            Some(buffer) => {
+                let mut buffers = self.budget.buffers.lock();
+                match buffers.get_mut(&buffer) {
+                    Some((holders, _)) if *holders > 1 => {
+                        *holders -= 1;
+                        0
+                    }
+                    Some(_) => buffers
+                        .remove(&buffer)
+                        .map_or(0, |(_, num_bytes)| num_bytes),
+                    None => 0,
+                }
+            }
+            None => self.num_bytes,
+        };
+        self.budget.used.fetch_sub(num_bytes, Ordering::SeqCst);
+    }
+}
+
+enum HeldOperand {
+    InMemory(Value),
+    Spilled(PathBuf),
+}
+
+/// Await all `operands`, spilling completed ones that do not fit within `budget`
+/// while waiting for the rest.
+///
+/// The returned reservations account for the operands and should be kept until
+/// the kernel has used them.
+pub(crate) async fn collect_operands<F>(
+    operands: Vec<F>,
+    budget: Option<&Arc<MemoryBudget>>,
+) -> Result<(Vec<Value>, Vec<Reservation>)>
+where
+    F: Future<Output = std::result::Result<Value, ()>>,
+{
+    let budget = match budget {
+        Some(budget) => budget,
+        None => {
+            let operands = futures::future::join_all(operands).await;
+            let xs: std::result::Result<Vec<Value>, _> = operands.into_iter().collect();
+            let xs = xs.map_err(|_| Error::OperandUnavailable)?;
+            return Ok((xs, Vec::new()));
+        }
+    };
+
+    let mut held: Vec<Option<HeldOperand>> = operands.iter().map(|_| None).collect();
+    let mut reservations = Vec::with_capacity(operands.len());
+    let mut pending: FuturesUnordered<_> = operands
+        .into_iter()
+        .enumerate()
+        .map(|(i, operand)| operand.map(move |res| (i, res)))
+        .collect();
+
+    while let Some((i, res)) = pending.next().await {
+        let value = res.map_err(|_| Error::OperandUnavailable)?;
+        let num_bytes = bincode::serialized_size(&value)
+            .map_err(|e| Error::KernelError(format!("failed to estimate value size: {}", e)))?
+            as usize;
+        match budget.try_reserve(buffer_address(&value), num_bytes) {
+            Some(reservation) => {
+                reservations.push(reservation);
+                held[i] = Some(HeldOperand::InMemory(value));
+            }
+            // the last operand is needed right away so there is no point in spilling it
+            None if pending.is_empty() => held[i] = Some(HeldOperand::InMemory(value)),
+            None => {
+                tracing::debug!(
+                    "Spilling operand of {} bytes; {} bytes in use",
+                    num_bytes,
+                    budget.used()
+                );
+                held[i] = Some(HeldOperand::Spilled(budget.spill(&value).await?));
+            }
+        }
+    }
+
+    let mut xs = Vec::with_capacity(held.len());
+    for operand in held {
+        match operand {
+            Some(HeldOperand::InMemory(value)) => xs.push(value),
+            Some(HeldOperand::Spilled(path)) => xs.push(MemoryBudget::reload(path).await?),
+            None => return Err(Error::OperandUnavailable),
+        }
+    }
+    Ok((xs, reservations))
+}
+
+/// Bytes of the values of a session alive at the same time.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub current: u64,
+    pub peak: u64,
+}
+
+/// Accounting of the bytes of the values alive during a single session.
+#[derive(Default)]
+pub struct MemoryTracker {
+    current: AtomicU64,
+    peak: AtomicU64,
+    #[cfg(feature = "metrics")]
+    gauge: Option<prometheus::IntGauge>,
+}
+
+impl MemoryTracker {
+    /// Also account for the bytes in `gauge`, shared with other sessions.
+    #[cfg(feature = "metrics")]
+    pub fn with_gauge(self, gauge: prometheus::IntGauge) -> Self {
+        MemoryTracker {
+            gauge: Some(gauge),
+            ..self
+        }
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            current: self.current.load(Ordering::SeqCst),
+            peak: self.peak.load(Ordering::SeqCst),
+        }
+    }
+
+    fn allocate(&self, num_bytes: u64) {
+        let current = self.current.fetch_add(num_bytes, Ordering::SeqCst) + num_bytes;
+        self.peak.fetch_max(current, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        if let Some(ref gauge) = self.gauge {
+            gauge.add(num_bytes as i64);
+        }
+    }
+
+    fn release(&self, num_bytes: u64) {
+        self.current.fetch_sub(num_bytes, Ordering::SeqCst);
+        #[cfg(feature = "metrics")]
+        if let Some(ref gauge) = self.gauge {
+            gauge.sub(num_bytes as i64);
+        }
+    }
+}
+
+/// Track the bytes of values alive during a session in `tracker`.
+///
+/// `results` are the results of the operations run by the party, each with the
+/// indices of the results it takes as operands. A value is considered alive from
+/// the time it is available until all operations using it have their result, or,
+/// for values not used by any operation such as outputs, until the session ends.
+pub(crate) fn track_memory(
+    results: Vec<(AsyncValue, Vec<usize>)>,
+    tracker: Arc<MemoryTracker>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut users = vec![0usize; results.len()];
+        for (_, operands) in results.iter() {
+            for operand in operands {
+                users[*operand] += 1;
+            }
+        }
+        let is_used: Vec<bool> = users.iter().map(|users| *users > 0).collect();
+        let mut sizes: Vec<Option<u64>> = vec![None; results.len()];
+        let mut operands = Vec::with_capacity(results.len());
+        let mut pending = FuturesUnordered::new();
+        for (i, (result, result_operands)) in results.into_iter().enumerate() {
+            operands.push(result_operands);
+            pending.push(result.map(move |res| (i, res)));
+        }
+
+        while let Some((i, res)) = pending.next().await {
+            let value = match res {
+                Ok(value) => value,
+                Err(_) => {
+                    // the session failed or was cancelled, so remaining values are dropped
+                    tracker.release(tracker.current.load(Ordering::SeqCst));
+                    return;
+                }
+            };
+            let num_bytes = bincode::serialized_size(&value).unwrap_or_default();
+            tracker.allocate(num_bytes);
+            sizes[i] = Some(num_bytes);
+            // the users of a value may all have their result before the value is seen here
+            if is_used[i] && users[i] == 0 {
+                tracker.release(num_bytes);
+            }
+            for operand in operands[i].iter() {
+                users[*operand] -= 1;
+                if let (0, Some(num_bytes)) = (users[*operand], sizes[*operand]) {
+                    tracker.release(num_bytes);
+                }
+            }
+        }
+
+        for (i, num_bytes) in sizes.into_iter().enumerate() {
+            if let (false, Some(num_bytes)) = (is_used[i], num_bytes) {
+                tracker.release(num_bytes);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::asynchronous::new_channel;
+    use crate::execution::ready_value;
+    use crate::host::{HostPlacement, HostTensor};
+    use ndarray::ArrayD;
+    use std::convert::TryInto;
+
+    fn tensor(len: usize) -> Value {
+        let plc = HostPlacement::from("alice");
+        let x = HostTensor::<f64>(ArrayD::ones(vec![len]).into_shared(), plc);
+        Value::HostFloat64Tensor(Box::new(x))
+    }
+
+    #[tokio::test]
+    async fn test_spill_and_reload() {
+        let spill_dir = tempfile::tempdir().unwrap();
+        let config = MemoryBudgetConfig {
+            limit: 1_000,
+            spill_dir: spill_dir.path().to_path_buf(),
+        };
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let budget = Arc::new(MemoryBudget::new(&config, &session_id));
+
+        let (sender, receiver) = tokio::sync::oneshot::channel::<Value>();
+        let operands = vec![
+            futures::future::ready(Ok(tensor(1_000))).boxed(),
+            receiver.map(|res| res.map_err(|_| ())).boxed(),
+        ];
+        let collecting = tokio::spawn({
+            let budget = Arc::clone(&budget);
+            async move {
+                collect_operands(operands, Some(&budget))
+                    .await
+                    .map(|(xs, _)| xs)
+            }
+        });
+
+        // the first operand does not fit within the budget and ends up on disk
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(std::fs::read_dir(spill_dir.path()).unwrap().count(), 1);
+
+        sender.send(tensor(10)).unwrap();
+        let xs = collecting.await.unwrap().unwrap();
+        assert_eq!(xs, vec![tensor(1_000), tensor(10)]);
+        assert_eq!(std::fs::read_dir(spill_dir.path()).unwrap().count(), 0);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_buffers_count_once() {
+        let spill_dir = tempfile::tempdir().unwrap();
+        let config = MemoryBudgetConfig {
+            limit: 10_000,
+            spill_dir: spill_dir.path().join("spill"),
+        };
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let budget = Arc::new(MemoryBudget::new(&config, &session_id));
+
+        // both kernels hold the same buffer, which fits within the budget only once
+        let x = tensor(1_000);
+        let (xs, first) =
+            collect_operands(vec![futures::future::ready(Ok(x.clone()))], Some(&budget))
+                .await
+                .unwrap();
+        let used = budget.used();
+        assert!(used > 1_000 * 8);
+        let (ys, second) = collect_operands(
+            vec![
+                futures::future::ready(Ok(x.clone())),
+                futures::future::ready(Ok(tensor(10))),
+            ],
+            Some(&budget),
+        )
+        .await
+        .unwrap();
+        assert_eq!(ys, vec![x.clone(), tensor(10)]);
+        assert!(budget.used() < 2 * used);
+        assert!(!config.spill_dir.exists());
+
+        drop((xs, first));
+        assert!(budget.used() > used / 2);
+        drop((ys, second));
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reject_shared_spill_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let spill_dir = tempfile::tempdir().unwrap();
+        let config = MemoryBudgetConfig {
+            limit: 1_000,
+            spill_dir: spill_dir.path().join("spill"),
+        };
+        config.prepare_spill_dir().unwrap();
+        let mode = std::fs::metadata(&config.spill_dir)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        std::fs::set_permissions(&config.spill_dir, std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        assert!(matches!(
+            config.prepare_spill_dir(),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_track_memory() {
+        let tracker = Arc::new(MemoryTracker::default());
+        let (sender, receiver) = new_channel();
+        // x and y are both used by z, which is the output
+        let results = vec![
+            (ready_value(tensor(100)), vec![]),
+            (ready_value(tensor(200)), vec![]),
+            (receiver, vec![0, 1]),
+        ];
+        track_memory(results, Arc::clone(&tracker));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let usage = tracker.usage();
+        assert!(usage.current > 300 * 8);
+        assert_eq!(usage.current, usage.peak);
+
+        sender.send(tensor(10)).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let usage = tracker.usage();
+        assert_eq!(usage.current, 0);
+        assert!(usage.peak > 300 * 8);
+    }
+}
@@ -0,0 +1,334 @@
+//! Prometheus metrics of a worker.
+//!
+//! Exported metrics are:
+//!
+//! - `moose_sessions_total`, sessions launched, completed, and failed, by `state`;
+//! - `moose_sessions_running`, sessions launched but not yet finished;
+//! - `moose_operation_duration_seconds`, time spent running kernels, by `operator`;
+//! - `moose_network_bytes_total`, bytes exchanged with each `peer` by finished sessions,
+//!   by `direction`;
+//! - `moose_storage_operations_total`, loads and saves, by `operation` and `outcome`;
+//! - `moose_live_value_bytes`, bytes of values alive in running sessions, and
+//!   `moose_session_peak_value_bytes`, the most bytes alive at once in finished
+//!   sessions, if memory is tracked;
+//!
+//! as well as the standard `process_*` metrics, including resident memory, on Linux.
+
+use crate::computation::{SessionId, Ty, Value};
+use crate::error::{Error, Result};
+use crate::execution::memory::{MemoryTracker, MemoryUsage};
+use crate::execution::AsyncStorageImpl;
+use crate::networking::accounting::NetworkStats;
+use crate::storage::{AsyncStorage, ValueStat, ValueStream};
+use async_trait::async_trait;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, StatusCode};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn metrics_error(e: impl std::fmt::Display) -> Error {
+    Error::Unexpected(Some(format!("metrics: {}", e)))
+}
+
+/// Metrics of all sessions run by a worker.
+pub struct Metrics {
+    registry: Registry,
+    sessions: IntCounterVec,
+    sessions_running: IntGauge,
+    operation_duration: HistogramVec,
+    network_bytes: IntCounterVec,
+    storage_operations: IntCounterVec,
+    live_value_bytes: IntGauge,
+    session_peak_value_bytes: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        let sessions = IntCounterVec::new(
+            Opts::new("moose_sessions_total", "Sessions by state"),
+            &["state"],
+        )
+        .map_err(metrics_error)?;
+        let sessions_running = IntGauge::new("moose_sessions_running", "Sessions not yet finished")
+            .map_err(metrics_error)?;
+        let operation_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "moose_operation_duration_seconds",
+                "Time spent running kernels",
+            ),
+            &["operator"],
+        )
+        .map_err(metrics_error)?;
+        let network_bytes = IntCounterVec::new(
+            Opts::new(
+                "moose_network_bytes_total",
+                "Bytes exchanged with peers by finished sessions",
+            ),
+            &["peer", "direction"],
+        )
+        .map_err(metrics_error)?;
+        let storage_operations = IntCounterVec::new(
+            Opts::new("moose_storage_operations_total", "Loads and saves"),
+            &["operation", "outcome"],
+        )
+        .map_err(metrics_error)?;
+
+        let live_value_bytes = IntGauge::new(
+            "moose_live_value_bytes",
+            "Bytes of values alive in running sessions",
+        )
+        .map_err(metrics_error)?;
+        let session_peak_value_bytes = Histogram::with_opts(
+            HistogramOpts::new(
+                "moose_session_peak_value_bytes",
+                "Most bytes of values alive at once in finished sessions",
+            )
+            .buckets(prometheus::exponential_buckets(1024.0, 4.0, 12).map_err(metrics_error)?),
+        )
+        .map_err(metrics_error)?;
+
+        registry
+            .register(Box::new(sessions.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(sessions_running.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(operation_duration.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(network_bytes.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(storage_operations.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(live_value_bytes.clone()))
+            .map_err(metrics_error)?;
+        registry
+            .register(Box::new(session_peak_value_bytes.clone()))
+            .map_err(metrics_error)?;
+        #[cfg(target_os = "linux")]
+        registry
+            .register(Box::new(
+                prometheus::process_collector::ProcessCollector::for_self(),
+            ))
+            .map_err(metrics_error)?;
+
+        Ok(Metrics {
+            registry,
+            sessions,
+            sessions_running,
+            operation_duration,
+            network_bytes,
+            storage_operations,
+            live_value_bytes,
+            session_peak_value_bytes,
+        })
+    }
+
+    /// Tracker of the values alive in a new session, reporting to these metrics.
+    pub fn memory_tracker(&self) -> MemoryTracker {
+        MemoryTracker::default().with_gauge(self.live_value_bytes.clone())
+    }
+
+    pub fn session_launched(&self) {
+        self.sessions.with_label_values(&["launched"]).inc();
+        self.sessions_running.inc();
+    }
+
+    pub fn session_finished(
+        &self,
+        failed: bool,
+        network_stats: Option<&NetworkStats>,
+        memory_usage: Option<&MemoryUsage>,
+    ) {
+        let state = if failed { "failed" } else { "completed" };
+        self.sessions.with_label_values(&[state]).inc();
+        self.sessions_running.dec();
+        if let Some(memory_usage) = memory_usage {
+            self.session_peak_value_bytes
+                .observe(memory_usage.peak as f64);
+        }
+        let peers = network_stats.iter().flat_map(|stats| stats.peers.iter());
+        for (peer, stats) in peers {
+            self.network_bytes
+                .with_label_values(&[&peer.0, "sent"])
+                .inc_by(stats.bytes_sent);
+            self.network_bytes
+                .with_label_values(&[&peer.0, "received"])
+                .inc_by(stats.bytes_received);
+        }
+    }
+
+    pub fn record_kernel_time(&self, operator: &str, elapsed: Duration) {
+        self.operation_duration
+            .with_label_values(&[operator])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    fn record_storage<T>(&self, operation: &str, res: &Result<T>) {
+        let outcome = if res.is_ok() { "ok" } else { "error" };
+        self.storage_operations
+            .with_label_values(&[operation, outcome])
+            .inc();
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(metrics_error)?;
+        String::from_utf8(buffer).map_err(metrics_error)
+    }
+
+    /// Serve the metrics over HTTP at `addr`, regardless of the path requested.
+    pub async fn serve(self: Arc<Self>, addr: SocketAddr) -> Result<()> {
+        let make_service = make_service_fn(move |_| {
+            let metrics = Arc::clone(&self);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_request: Request<Body>| {
+                    let rendered = metrics.render();
+                    async move {
+                        let response = match rendered {
+                            Ok(body) => {
+                                let mut response = Response::new(Body::from(body));
+                                response.headers_mut().insert(
+                                    CONTENT_TYPE,
+                                    HeaderValue::from_static(prometheus::TEXT_FORMAT),
+                                );
+                                response
+                            }
+                            Err(e) => {
+                                let mut response = Response::new(Body::from(e.to_string()));
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                                response
+                            }
+                        };
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+        hyper::Server::try_bind(&addr)
+            .map_err(metrics_error)?
+            .serve(make_service)
+            .await
+            .map_err(metrics_error)
+    }
+}
+
+/// Storage counting the loads and saves of another storage.
+pub struct MeteredStorage {
+    storage: AsyncStorageImpl,
+    metrics: Arc<Metrics>,
+}
+
+impl MeteredStorage {
+    pub fn new(storage: AsyncStorageImpl, metrics: Arc<Metrics>) -> Self {
+        MeteredStorage { storage, metrics }
+    }
+}
+
+#[async_trait]
+impl AsyncStorage for MeteredStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        let res = self.storage.save(key, session_id, val).await;
+        self.metrics.record_storage("save", &res);
+        res
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        let res = self.storage.load(key, session_id, type_hint, query).await;
+        self.metrics.record_storage("load", &res);
+        res
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        let res = self
+            .storage
+            .load_chunks(key, session_id, type_hint, query, chunk_rows)
+            .await;
+        self.metrics.record_storage("load", &res);
+        res
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        self.storage.list(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        self.storage.stat(key).await
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        self.storage.formats()
+    }
+
+    async fn check(&self) -> Result<()> {
+        self.storage.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::Identity;
+    use crate::networking::accounting::PeerStats;
+    use crate::storage::local::LocalAsyncStorage;
+    use std::convert::TryFrom;
+
+    #[tokio::test]
+    async fn test_render_metrics() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let storage =
+            MeteredStorage::new(Arc::new(LocalAsyncStorage::default()), Arc::clone(&metrics));
+        let session_id = SessionId::try_from("my-session").unwrap();
+        assert!(storage
+            .load("missing", &session_id, None, "")
+            .await
+            .is_err());
+
+        metrics.session_launched();
+        metrics.record_kernel_time("Add", Duration::from_millis(5));
+        let mut network_stats = NetworkStats::default();
+        network_stats.peers.insert(
+            Identity::from("bob"),
+            PeerStats {
+                bytes_sent: 42,
+                ..PeerStats::default()
+            },
+        );
+        metrics.session_finished(false, Some(&network_stats), None);
+
+        let rendered = metrics.render().unwrap();
+        assert!(rendered.contains(r#"moose_sessions_total{state="completed"} 1"#));
+        assert!(rendered.contains("moose_sessions_running 0"));
+        assert!(rendered.contains(r#"moose_operation_duration_seconds_count{operator="Add"} 1"#));
+        assert!(rendered.contains(r#"moose_network_bytes_total{direction="sent",peer="bob"} 42"#));
+        assert!(rendered
+            .contains(r#"moose_storage_operations_total{operation="load",outcome="error"} 1"#));
+    }
+}
@@ -9,9 +9,39 @@ use std::sync::Arc;
 
 #[cfg(feature = "async_execute")]
 pub mod asynchronous;
+#[cfg(feature = "async_execute")]
+pub mod audit;
+#[cfg(feature = "async_execute")]
+pub mod cache;
+#[cfg(feature = "async_execute")]
+pub mod checkpoint;
+#[cfg(feature = "async_execute")]
+pub mod communication;
+#[cfg(feature = "async_execute")]
+pub mod compute;
+pub mod constant_time;
+#[cfg(feature = "async_execute")]
 pub mod context;
+#[cfg(all(feature = "gpu", feature = "async_execute"))]
+pub mod gpu;
+#[cfg(feature = "async_execute")]
 pub mod grpc;
 pub(crate) mod kernel_helpers;
+#[cfg(feature = "async_execute")]
+pub mod memory;
+#[cfg(all(feature = "metrics", feature = "async_execute"))]
+pub mod metrics;
+#[cfg(feature = "async_execute")]
+pub mod profiling;
+#[cfg(feature = "async_execute")]
+pub mod progress;
+#[cfg(feature = "async_execute")]
+pub mod recording;
+#[cfg(feature = "async_execute")]
+pub mod scheduler;
+pub(crate) mod scrub;
+#[cfg(feature = "async_execute")]
+pub mod summary;
 #[cfg(feature = "compile")]
 pub mod symbolic;
 #[cfg(feature = "sync_execute")]
@@ -19,7 +49,8 @@ pub mod synchronous;
 
 #[cfg(feature = "async_execute")]
 pub use asynchronous::*;
-pub use context::ExecutionContext;
+#[cfg(feature = "async_execute")]
+pub use context::{stream_outputs, ExecutionContext, OutputStream};
 #[cfg(feature = "compile")]
 pub use symbolic::*;
 #[cfg(feature = "sync_execute")]
@@ -396,6 +427,150 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "compile")]
+    #[rstest]
+    #[case("Uint8", "HostUint8Tensor([0, 5, 255]) @Host(alice)", true)]
+    #[case("Int8", "HostInt8Tensor([-128, -3, 0, 127]) @Host(alice)", true)]
+    #[case(
+        "Int16",
+        "HostInt16Tensor([-32768, -300, 0, 32767]) @Host(alice)",
+        true
+    )]
+    #[case("Uint8", "HostUint8Tensor([0, 5, 255]) @Host(alice)", false)]
+    #[case("Int8", "HostInt8Tensor([-128, -3, 0, 127]) @Host(alice)", false)]
+    #[case(
+        "Int16",
+        "HostInt16Tensor([-32768, -300, 0, 32767]) @Host(alice)",
+        false
+    )]
+    fn test_small_int_tensor(
+        #[case] dtype: String,
+        #[case] x: Value,
+        #[case] run_async: bool,
+    ) -> std::result::Result<(), anyhow::Error> {
+        use crate::textual::ToTextual;
+
+        let source_template = r#"x = Input{arg_name = "x"}: () -> Tensor<DType> @Host(alice)
+        c = Constant{value = XValue}: () -> Tensor<DType> @Host(alice)
+        y = Identity: (Tensor<DType>) -> Tensor<DType> (x) @Host(alice)
+        y_fixed = Cast: (Tensor<DType>) -> Tensor<Fixed128(24, 40)> (y) @Host(alice)
+        z_fixed = Cast: (Tensor<Fixed128(24, 40)>) -> Tensor<DType> (y_fixed) @Host(alice)
+        y_float = Cast: (Tensor<DType>) -> Tensor<Float64> (y) @Host(alice)
+        z_float = Cast: (Tensor<Float64>) -> Tensor<DType> (y_float) @Host(alice)
+        out_c = Output{tag = "constant"}: (Tensor<DType>) -> Tensor<DType> (c) @Host(alice)
+        out_y = Output{tag = "identity"}: (Tensor<DType>) -> Tensor<DType> (y) @Host(alice)
+        out_fixed = Output{tag = "fixed"}: (Tensor<DType>) -> Tensor<DType> (z_fixed) @Host(alice)
+        out_float = Output{tag = "float"}: (Tensor<DType>) -> Tensor<DType> (z_float) @Host(alice)
+        "#;
+        let (literal, _) = x.to_textual().split_once(" @").unwrap();
+        let source = source_template
+            .replace("DType", &dtype)
+            .replace("XValue", literal);
+        let computation = compile::<Pass>(source.try_into()?, None)?;
+        let arguments: HashMap<String, Value> = hashmap!("x".to_string() => x.clone());
+        let storage_mapping: HashMap<String, HashMap<String, Value>> =
+            hashmap!("alice".to_string() => hashmap!());
+        let outputs = _run_computation_test(computation, storage_mapping, arguments, run_async)?;
+
+        for tag in ["constant", "identity", "fixed", "float"] {
+            assert_eq!(outputs[tag], x, "output {}", tag);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "compile")]
+    #[rstest]
+    #[case("HostUint8Tensor([0, 5, 255]) @Host(alice)", true)]
+    #[case("HostInt8Tensor([-128, -3, 0, 127]) @Host(alice)", true)]
+    #[case("HostInt16Tensor([-32768, -300, 0, 32767]) @Host(alice)", true)]
+    #[case("HostUint8Tensor([0, 5, 255]) @Host(alice)", false)]
+    #[case("HostInt8Tensor([-128, -3, 0, 127]) @Host(alice)", false)]
+    #[case("HostInt16Tensor([-32768, -300, 0, 32767]) @Host(alice)", false)]
+    fn test_small_int_load_save(
+        #[case] input_data: Value,
+        #[case] run_async: bool,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let dtype = match input_data.ty() {
+            Ty::HostUint8Tensor => "Uint8",
+            Ty::HostInt8Tensor => "Int8",
+            Ty::HostInt16Tensor => "Int16",
+            ty => panic!("unexpected type {:?}", ty),
+        };
+        let source_template = r#"x_uri = Input {arg_name="x_uri"}: () -> HostString () @Host(alice)
+        x_query = Input {arg_name="x_query"}: () -> HostString () @Host(alice)
+        saved_uri = Constant{value = HostString("saved_data")}: () -> HostString () @Host(alice)
+        x = Load: (HostString, HostString) -> Tensor<DType> (x_uri, x_query) @Host(alice)
+        save = Save: (HostString, Tensor<DType>) -> HostUnit (saved_uri, x) @Host(alice)
+        output = Output{tag = "output_0"}: (HostUnit) -> HostUnit (save) @Host(alice)
+        "#;
+        let source = source_template.replace("DType", dtype);
+        let computation = compile::<Pass>(source.try_into()?, None)?;
+        let plc = HostPlacement::from("alice");
+
+        let arguments: HashMap<String, Value> = hashmap!("x_uri".to_string()=> HostString("input_data".to_string(), plc.clone()).into(),
+            "x_query".to_string() => HostString("".to_string(), plc).into());
+
+        let saved_data = match run_async {
+            true => {
+                let storage_mapping: HashMap<String, HashMap<String, Value>> = hashmap!("alice".to_string() => hashmap!("input_data".to_string() => input_data.clone()));
+                let mut executor = AsyncTestRuntime::new(storage_mapping);
+                let _outputs = executor.evaluate_computation(&computation, arguments)?;
+
+                executor.read_value_from_storage(
+                    Identity::from("alice".to_string()),
+                    "saved_data".to_string(),
+                )?
+            }
+            false => {
+                let store: HashMap<String, Value> =
+                    hashmap!("input_data".to_string() => input_data.clone());
+                let storage: Rc<dyn SyncStorage> = Rc::new(LocalSyncStorage::from_hashmap(store));
+                let executor = TestSyncExecutor::default();
+                let session = SyncSession::from_storage(
+                    SessionId::try_from("foobar").unwrap(),
+                    arguments,
+                    hashmap!(),
+                    storage.clone(),
+                );
+                let _outputs = executor.run_computation(&computation, &session)?;
+                storage.load(
+                    "saved_data",
+                    &SessionId::try_from("foobar").unwrap(),
+                    None,
+                    "",
+                )?
+            }
+        };
+
+        assert_eq!(input_data, saved_data);
+        Ok(())
+    }
+
+    #[cfg(feature = "compile")]
+    #[rstest]
+    #[case("300.0")]
+    #[case("-129.0")]
+    fn test_small_int_cast_out_of_range(
+        #[case] x: String,
+    ) -> std::result::Result<(), anyhow::Error> {
+        let source_template = r#"x = Constant{value = HostFloat64Tensor([1.0, XValue])}: () -> Tensor<Float64> @Host(alice)
+        y = Cast: (Tensor<Float64>) -> Tensor<Int8> (x) @Host(alice)
+        output = Output{tag = "output_0"}: (Tensor<Int8>) -> Tensor<Int8> (y) @Host(alice)
+        "#;
+        let source = source_template.replace("XValue", &x);
+        let computation = compile::<Pass>(source.try_into()?, None)?;
+        let storage_mapping: HashMap<String, HashMap<String, Value>> =
+            hashmap!("alice".to_string() => hashmap!());
+        let err = _run_computation_test(computation, storage_mapping, hashmap!(), false)
+            .unwrap_err()
+            .downcast::<Error>()?;
+        match err.root_cause() {
+            Error::KernelError(msg) => assert!(msg.contains("Conversion error")),
+            e => panic!("expected kernel error but got {:?}", e),
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "compile")]
     #[rstest]
     #[case(true)]
@@ -674,7 +849,8 @@ mod tests {
             if let Value::HostFloat32Tensor(x) = comp_result {
                 let shaped_result = x
                     .clone()
-                    .reshape(HostShape(RawShape(vec![1]), HostPlacement::from("alice")));
+                    .reshape(HostShape(RawShape(vec![1]), HostPlacement::from("alice")))
+                    .unwrap();
                 assert_eq!(
                     expected_result,
                     Value::Float32(Box::new(shaped_result.0[0]))
@@ -1035,4 +1211,438 @@ mod tests {
             panic!("expected session already exists error")
         }
     }
+
+    #[cfg(feature = "async_execute")]
+    #[test]
+    fn test_receive_timeout() {
+        let source = r#"x = Receive {rendezvous_key = 30303030303030303030303030303030, sender = "bob"}: () -> HostFloat64Tensor () @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(alice)"#;
+
+        let networking: Arc<dyn Send + Sync + AsyncNetworking> =
+            Arc::new(LocalAsyncNetworking::default());
+        let exec_storage: Arc<dyn Send + Sync + AsyncStorage> =
+            Arc::new(LocalAsyncStorage::default());
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> = hashmap!(
+            Role::from("alice") => alice.clone(),
+            Role::from("bob") => Identity::from("bob"),
+        );
+
+        let rt = Runtime::new().unwrap();
+        let _guard = rt.enter();
+
+        let moose_session =
+            _create_async_session(&networking, &exec_storage, role_assignments.clone())
+                .with_timeouts(OperationTimeouts {
+                    receive: Some(std::time::Duration::from_millis(50)),
+                    compute: None,
+                });
+
+        let computation: Computation = source.try_into().unwrap();
+        let mut executor = AsyncExecutor::default();
+        executor
+            .run_computation(&computation, &role_assignments, &alice, &moose_session)
+            .unwrap();
+
+        let res = rt.block_on(moose_session.into_handle().unwrap().join_on_first_error());
+        let err = res.unwrap_err().downcast::<Error>().unwrap();
+        assert_eq!(err.operation().map(|ctx| ctx.op_name.as_str()), Some("x"));
+        match err.root_cause() {
+            Error::Timeout { operation, reason } => {
+                assert_eq!(operation, "x");
+                assert!(reason.contains("bob"));
+            }
+            e => panic!("expected timeout error but got {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[test]
+    fn test_kernel_error_names_operation() {
+        let source = r#"x = Constant{value=HostFloat64Tensor([[1.0, 2.0, 3.0]])}: () -> HostFloat64Tensor @Host(alice)
+        y = Constant{value=HostFloat64Tensor([[1.0, 2.0]])}: () -> HostFloat64Tensor @Host(alice)
+        z = Dot: (HostFloat64Tensor, HostFloat64Tensor) -> HostFloat64Tensor (x, y) @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (z) @Host(alice)"#;
+
+        let networking: Arc<dyn Send + Sync + AsyncNetworking> =
+            Arc::new(LocalAsyncNetworking::default());
+        let exec_storage: Arc<dyn Send + Sync + AsyncStorage> =
+            Arc::new(LocalAsyncStorage::default());
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> =
+            hashmap!(Role::from("alice") => alice.clone());
+
+        let rt = Runtime::new().unwrap();
+        let _guard = rt.enter();
+
+        let moose_session =
+            _create_async_session(&networking, &exec_storage, role_assignments.clone());
+        let computation: Computation = source.try_into().unwrap();
+        let mut executor = AsyncExecutor::default();
+        executor
+            .run_computation(&computation, &role_assignments, &alice, &moose_session)
+            .unwrap();
+
+        let res = rt.block_on(moose_session.into_handle().unwrap().join_on_first_error());
+        let err = res.unwrap_err().downcast::<Error>().unwrap();
+        let context = err
+            .operation()
+            .expect("error should name the failed operation");
+        assert_eq!(context.op_name, "z");
+        assert_eq!(context.operator, "Dot");
+        assert_eq!(context.placement, "@Host(alice)");
+        assert!(err
+            .to_string()
+            .contains("Operation 'z' (Dot) on @Host(alice)"));
+        match err.root_cause() {
+            Error::KernelError(_) => (),
+            e => panic!("expected kernel error but got {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[test]
+    fn test_cancel_session() {
+        let source = r#"x = Receive {rendezvous_key = 30303030303030303030303030303030, sender = "bob"}: () -> HostFloat64Tensor () @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(alice)"#;
+
+        let networking: Arc<dyn Send + Sync + AsyncNetworking> =
+            Arc::new(LocalAsyncNetworking::default());
+        let exec_storage: Arc<dyn Send + Sync + AsyncStorage> =
+            Arc::new(LocalAsyncStorage::default());
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> = hashmap!(
+            Role::from("alice") => alice.clone(),
+            Role::from("bob") => Identity::from("bob"),
+        );
+
+        let rt = Runtime::new().unwrap();
+        let _guard = rt.enter();
+
+        let moose_session =
+            _create_async_session(&networking, &exec_storage, role_assignments.clone());
+        let computation: Computation = source.try_into().unwrap();
+        let mut executor = AsyncExecutor::default();
+        executor
+            .run_computation(&computation, &role_assignments, &alice, &moose_session)
+            .unwrap();
+
+        let handle = moose_session.into_handle().unwrap();
+        let cancel_token = handle.cancel_token();
+        rt.spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            cancel_token.cancel("no longer needed");
+        });
+
+        let res = rt.block_on(handle.join_on_first_error());
+        let err = res.unwrap_err().downcast::<Error>().unwrap();
+        match err {
+            Error::Cancelled(reason) => assert_eq!(reason, "no longer needed"),
+            e => panic!("expected cancelled error but got {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        use crate::execution::recording::{recording_path, Replay};
+
+        let source = r#"key = PrfKeyGen: () -> HostPrfKey () @Host(alice)
+        seed = DeriveSeed {sync_key = [1, 2, 3]}: (HostPrfKey) -> HostSeed (key) @Host(alice)
+        shape = Constant{value = HostShape([2, 3])}: () -> HostShape @Host(alice)
+        x = SampleSeeded{}: (HostShape, HostSeed) -> HostRing64Tensor (shape, seed) @Host(alice)
+        y = Sample{}: (HostShape) -> HostRing64Tensor (shape) @Host(alice)
+        z = Add: (HostRing64Tensor, HostRing64Tensor) -> HostRing64Tensor (x, y) @Host(alice)
+        output = Output{tag = "output_0"}: (HostRing64Tensor) -> HostRing64Tensor (z) @Host(alice)"#;
+        let computation: Computation = source.try_into().unwrap();
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> =
+            hashmap!(Role::from("alice") => alice.clone());
+        let session_id = SessionId::try_from("12345").unwrap();
+        let recording_dir = tempfile::tempdir().unwrap();
+
+        let run = |context: ExecutionContext| {
+            let computation = computation.clone();
+            let role_assignments = role_assignments.clone();
+            let session_id = session_id.clone();
+            async move {
+                let (handle, mut outputs) = context
+                    .execute_computation(session_id, &computation, hashmap!(), role_assignments)
+                    .await
+                    .unwrap();
+                handle.join_on_first_error().await.unwrap();
+                outputs.pop().unwrap().1.await.unwrap()
+            }
+        };
+        let new_context = || {
+            ExecutionContext::new(
+                alice.clone(),
+                Arc::new(LocalAsyncNetworking::default()),
+                Arc::new(LocalAsyncStorage::default()),
+            )
+        };
+
+        let recorded = run(new_context().with_recording(recording_dir.path().to_path_buf())).await;
+        let replay = Replay::load(&recording_path(recording_dir.path(), &session_id)).unwrap();
+        // the key, the unseeded sample, and nothing else
+        assert_eq!(replay.len(), 2);
+
+        let replayed = run(new_context().with_replay(Arc::new(replay))).await;
+        assert_eq!(recorded, replayed);
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[tokio::test]
+    async fn test_stream_outputs_in_completion_order() {
+        use crate::computation::HostUnit;
+        use futures::StreamExt;
+
+        let source = r#"key = PrfKeyGen: () -> HostPrfKey () @Host(alice)
+        x = Receive {rendezvous_key = 30303030303030303030303030303030, sender = "bob"}: () -> HostUnit () @Host(alice)
+        slow = Output{tag = "slow"}: (HostUnit) -> HostUnit (x) @Host(alice)
+        fast = Output{tag = "fast"}: (HostPrfKey) -> HostPrfKey (key) @Host(alice)"#;
+        let computation: Computation = source.try_into().unwrap();
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> = hashmap!(
+            Role::from("alice") => alice.clone(),
+            Role::from("bob") => Identity::from("bob"),
+        );
+        let session_id = SessionId::try_from("12345").unwrap();
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let context = ExecutionContext::new(
+            alice.clone(),
+            Arc::clone(&networking),
+            Arc::new(LocalAsyncStorage::default()),
+        );
+
+        let (handle, outputs) = context
+            .execute_computation(
+                session_id.clone(),
+                &computation,
+                hashmap!(),
+                role_assignments,
+            )
+            .await
+            .unwrap();
+        let mut outputs = stream_outputs(&computation, outputs).unwrap();
+
+        // the key is available long before anything is received from bob
+        let (tag, _) = outputs.next().await.unwrap().unwrap();
+        assert_eq!(tag, "fast");
+
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("bob"))));
+        let rendezvous_key = RendezvousKey::try_from("0000000000000000").unwrap();
+        networking
+            .send(&unit, &alice, &rendezvous_key, &session_id)
+            .await
+            .unwrap();
+        let (tag, _) = outputs.next().await.unwrap().unwrap();
+        assert_eq!(tag, "slow");
+        assert!(outputs.next().await.is_none());
+        handle.join_on_first_error().await.unwrap();
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[tokio::test]
+    async fn test_storage_events_in_operation_span() {
+        use std::io::Write;
+
+        /// Buffer collecting formatted events.
+        #[derive(Clone, Default)]
+        struct Logs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl Write for Logs {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let logs = Logs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer({
+                let logs = logs.clone();
+                move || logs.clone()
+            })
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let source = r#"key = Constant{value = HostString("x")}: () -> HostString () @Host(alice)
+        query = Constant{value = HostString("")}: () -> HostString () @Host(alice)
+        x = Load: (HostString, HostString) -> HostFloat64Tensor (key, query) @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (x) @Host(alice)"#;
+        let computation: Computation = source.try_into().unwrap();
+
+        let alice = Identity::from("alice");
+        let x: Value = HostTensor::<f64>(
+            array![1.0, 2.0].into_shared().into_dyn(),
+            HostPlacement::from("alice"),
+        )
+        .into();
+        let context = ExecutionContext::new(
+            alice.clone(),
+            Arc::new(LocalAsyncNetworking::default()),
+            Arc::new(LocalAsyncStorage::from_hashmap(
+                hashmap!("x".to_string() => x),
+            )),
+        );
+        let (handle, _outputs) = context
+            .execute_computation(
+                SessionId::try_from("12345").unwrap(),
+                &computation,
+                hashmap!(),
+                hashmap!(Role::from("alice") => alice),
+            )
+            .await
+            .unwrap();
+        handle.join_on_first_error().await.unwrap();
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let load = logs
+            .lines()
+            .find(|line| line.contains("Loading value"))
+            .expect("loading the value should have been logged");
+        assert!(load.contains("session_id=12345"));
+        assert!(load.contains("op=x"));
+        assert!(load.contains("operator=Load"));
+        assert!(load.contains("placement=@Host(alice)"));
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[tokio::test]
+    async fn test_scrub_results() {
+        use crate::execution::memory::MemoryBudgetConfig;
+        use crate::execution::scrub::ScrubSummary;
+
+        let source = r#"key = PrfKeyGen: () -> HostPrfKey () @Host(alice)
+        seed = DeriveSeed {sync_key = [1, 2, 3]}: (HostPrfKey) -> HostSeed (key) @Host(alice)
+        shape = Constant{value = HostShape([2, 3])}: () -> HostShape @Host(alice)
+        x = SampleSeeded{}: (HostShape, HostSeed) -> HostRing64Tensor (shape, seed) @Host(alice)
+        y = Sample{}: (HostShape) -> HostRing64Tensor (shape) @Host(alice)
+        z = Add: (HostRing64Tensor, HostRing64Tensor) -> HostRing64Tensor (x, y) @Host(alice)
+        output = Output{tag = "output_0"}: (HostRing64Tensor) -> HostRing64Tensor (z) @Host(alice)"#;
+        let computation: Computation = source.try_into().unwrap();
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> =
+            hashmap!(Role::from("alice") => alice.clone());
+        let new_context = || {
+            ExecutionContext::new(
+                alice.clone(),
+                Arc::new(LocalAsyncNetworking::default()),
+                Arc::new(LocalAsyncStorage::default()),
+            )
+            .with_scrubbing()
+        };
+
+        // progress reports and summaries hold on to every result until they have seen it
+        let context = new_context()
+            .with_progress(Arc::new(|_, _| ()))
+            .with_value_summaries();
+        let (handle, outputs) = context
+            .execute_computation(
+                SessionId::try_from("12345").unwrap(),
+                &computation,
+                hashmap!(),
+                role_assignments.clone(),
+            )
+            .await
+            .unwrap();
+        let (res, summary) = handle.join().await;
+        res.unwrap();
+        // everything but the output still held by the caller
+        assert_eq!(
+            summary,
+            Some(ScrubSummary {
+                scrubbed: 5,
+                in_use: 1
+            })
+        );
+        drop(outputs);
+
+        // values spilled to disk could not be scrubbed
+        let context = new_context().with_memory_budget(MemoryBudgetConfig {
+            limit: 0,
+            spill_dir: std::env::temp_dir(),
+        });
+        let res = context
+            .execute_computation(
+                SessionId::try_from("12345").unwrap(),
+                &computation,
+                hashmap!(),
+                role_assignments,
+            )
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "async_execute")]
+    #[tokio::test]
+    async fn test_timeouts_only_count_idle_time() {
+        let source = r#"x = Receive {rendezvous_key = 30303030303030303030303030303030, sender = "bob"}: () -> HostFloat64Tensor () @Host(alice)
+        y = Receive {rendezvous_key = 31313131313131313131313131313131, sender = "bob"}: () -> HostFloat64Tensor () @Host(alice)
+        z = Receive {rendezvous_key = 32323232323232323232323232323232, sender = "bob"}: () -> HostFloat64Tensor () @Host(alice)
+        w = Add: (HostFloat64Tensor, HostFloat64Tensor) -> HostFloat64Tensor (y, z) @Host(alice)
+        output = Output{tag = "output_0"}: (HostFloat64Tensor) -> HostFloat64Tensor (w) @Host(alice)"#;
+        let computation: Computation = source.try_into().unwrap();
+
+        let alice = Identity::from("alice");
+        let role_assignments: HashMap<Role, Identity> = hashmap!(
+            Role::from("alice") => alice.clone(),
+            Role::from("bob") => Identity::from("bob"),
+        );
+        let session_id = SessionId::try_from("12345").unwrap();
+        let networking: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let timeout = std::time::Duration::from_millis(150);
+        let context = ExecutionContext::new(
+            alice.clone(),
+            Arc::clone(&networking),
+            Arc::new(LocalAsyncStorage::default()),
+        )
+        .with_timeouts(OperationTimeouts {
+            receive: Some(timeout),
+            compute: Some(timeout),
+        });
+        let (handle, _outputs) = context
+            .execute_computation(
+                session_id.clone(),
+                &computation,
+                hashmap!(),
+                role_assignments,
+            )
+            .await
+            .unwrap();
+
+        // the last value and the operands of the addition arrive well after the
+        // timeouts, but bob is never silent for long
+        let x: Value = HostTensor::<f64>(
+            array![1.0].into_dyn().into_shared(),
+            HostPlacement::from("alice"),
+        )
+        .into();
+        for key in ["0000000000000000", "1111111111111111", "2222222222222222"] {
+            tokio::time::sleep(timeout / 2).await;
+            networking
+                .send(
+                    &x,
+                    &alice,
+                    &RendezvousKey::try_from(key).unwrap(),
+                    &session_id,
+                )
+                .await
+                .unwrap();
+        }
+        handle.join_on_first_error().await.unwrap();
+    }
 }
@@ -0,0 +1,251 @@
+//! Per-operation profiling of sessions.
+//!
+//! Records when each operation of a session had its operands available and when
+//! it finished, how long its kernel ran, and how many bytes it sent or received.
+//! Profiles can be exported in the Chrome trace event format, for viewing in
+//! chrome://tracing or Perfetto, and as folded stacks for flamegraph tools.
+
+use crate::computation::{Operator, Placement, SessionId, Value};
+use crate::error::{Error, Result};
+use crate::execution::recording::recording_path;
+use crate::execution::{AsyncTask, AsyncValue};
+use crate::textual::ToTextual;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Profile of a single operation, with times relative to the start of the session.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct OpProfile {
+    pub op_name: String,
+    pub op_kind: String,
+    pub placement: String,
+    /// When all operands were available.
+    pub start: Duration,
+    /// When the result was available.
+    pub end: Duration,
+    /// Time spent running the kernel, if the operation has one.
+    pub kernel_time: Option<Duration>,
+    /// Bytes sent or received by networking operations.
+    pub bytes_moved: u64,
+}
+
+/// Collects the profiles of all operations of a session.
+pub struct Profiler {
+    started: Instant,
+    ops: Mutex<Vec<OpProfile>>,
+    kernel_times: Mutex<HashMap<String, Duration>>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Profiler {
+            started: Instant::now(),
+            ops: Mutex::new(Vec::new()),
+            kernel_times: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+fn profile_error(e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("failed to write profile: {}", e))
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Profile operation `op_name` computing `result` from `operands`.
+    pub(crate) fn watch(
+        self: &Arc<Self>,
+        op_name: &str,
+        op: &Operator,
+        plc: &Placement,
+        operands: Vec<AsyncValue>,
+        result: AsyncValue,
+    ) -> AsyncTask {
+        let profiler = Arc::clone(self);
+        let op_name = op_name.to_string();
+        let op_kind = op.short_name().to_string();
+        let placement = plc.to_textual();
+        let is_send = matches!(op, Operator::Send(_));
+        let is_receive = matches!(op, Operator::Receive(_));
+
+        tokio::spawn(async move {
+            let operands = match futures::future::try_join_all(operands).await {
+                Ok(operands) => operands,
+                // the operation fails as well, which is reported elsewhere
+                Err(_) => return Ok(()),
+            };
+            let start = profiler.started.elapsed();
+            let value = match result.await {
+                Ok(value) => value,
+                Err(_) => return Ok(()),
+            };
+            let end = profiler.started.elapsed();
+
+            let moved: Option<&Value> = match (is_send, is_receive) {
+                (true, _) => operands.first(),
+                (_, true) => Some(&value),
+                _ => None,
+            };
+            let bytes_moved = match moved {
+                Some(value) => bincode::serialized_size(value).unwrap_or_default(),
+                None => 0,
+            };
+            profiler.ops.lock().push(OpProfile {
+                op_name,
+                op_kind,
+                placement,
+                start,
+                end,
+                kernel_time: None,
+                bytes_moved,
+            });
+            Ok(())
+        })
+    }
+
+    pub(crate) fn record_kernel_time(&self, op_name: &str, kernel_time: Duration) {
+        self.kernel_times
+            .lock()
+            .insert(op_name.to_string(), kernel_time);
+    }
+
+    /// Profiles of all finished operations, ordered by start time.
+    pub fn profiles(&self) -> Vec<OpProfile> {
+        let kernel_times = self.kernel_times.lock();
+        let mut profiles: Vec<OpProfile> = self
+            .ops
+            .lock()
+            .iter()
+            .map(|op| OpProfile {
+                kernel_time: kernel_times.get(&op.op_name).copied(),
+                ..op.clone()
+            })
+            .collect();
+        profiles.sort_by_key(|op| op.start);
+        profiles
+    }
+
+    /// Profiles as a JSON array of Chrome trace events, with one process per placement.
+    pub fn chrome_trace(&self) -> String {
+        let profiles = self.profiles();
+        let mut pids = BTreeMap::new();
+        for op in &profiles {
+            let next_pid = pids.len();
+            pids.entry(op.placement.clone()).or_insert(next_pid);
+        }
+
+        let mut events: Vec<serde_json::Value> = pids
+            .iter()
+            .map(|(placement, pid)| {
+                serde_json::json!({
+                    "name": "process_name",
+                    "ph": "M",
+                    "pid": pid,
+                    "args": { "name": placement },
+                })
+            })
+            .collect();
+        events.extend(profiles.iter().map(|op| {
+            serde_json::json!({
+                "name": op.op_name,
+                "cat": op.op_kind,
+                "ph": "X",
+                "ts": op.start.as_micros() as u64,
+                "dur": (op.end - op.start).as_micros() as u64,
+                "pid": pids[&op.placement],
+                "tid": 0,
+                "args": {
+                    "kernel_us": op.kernel_time.map(|t| t.as_micros() as u64),
+                    "bytes_moved": op.bytes_moved,
+                },
+            })
+        }));
+        serde_json::Value::Array(events).to_string()
+    }
+
+    /// Kernel times in microseconds as folded stacks of placement, operator, and operation.
+    ///
+    /// Networking operations, which have no kernel, are attributed their duration.
+    pub fn folded_stacks(&self) -> String {
+        self.profiles()
+            .iter()
+            .map(|op| {
+                let time = op.kernel_time.unwrap_or(op.end - op.start);
+                format!(
+                    "{};{};{} {}\n",
+                    op.placement,
+                    op.op_kind,
+                    op.op_name,
+                    time.as_micros()
+                )
+            })
+            .collect()
+    }
+
+    /// Write the profile of `session_id` to `dir`, both as a Chrome trace and as folded stacks.
+    pub fn write(&self, dir: &Path, session_id: &SessionId) -> Result<()> {
+        let path = recording_path(dir, session_id);
+        std::fs::write(path.with_extension("trace.json"), self.chrome_trace())
+            .map_err(profile_error)?;
+        std::fs::write(path.with_extension("folded"), self.folded_stacks())
+            .map_err(profile_error)?;
+        Ok(())
+    }
+}
+
+/// Write the profile of `session_id` to `dir` once the given operation `results` are available.
+pub(crate) fn write_profile(
+    profiler: Arc<Profiler>,
+    session_id: SessionId,
+    results: Vec<AsyncValue>,
+    dir: &Path,
+) -> JoinHandle<()> {
+    let dir = dir.to_path_buf();
+    tokio::spawn(async move {
+        futures::future::join_all(results).await;
+        match profiler.write(&dir, &session_id) {
+            Ok(()) => tracing::info!("Wrote profile of session {}", session_id),
+            Err(e) => tracing::warn!("Failed to write profile of session {}: {}", session_id, e),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::{HostUnit, IdentityOp, Signature, Ty};
+    use crate::execution::ready_value;
+    use crate::host::HostPlacement;
+
+    #[tokio::test]
+    async fn test_profile_export() {
+        let plc = HostPlacement::from("alice");
+        let unit = Value::HostUnit(Box::new(HostUnit(plc.clone())));
+        let op = Operator::Identity(IdentityOp {
+            sig: Signature::unary(Ty::HostUnit, Ty::HostUnit),
+        });
+
+        let profiler = Arc::new(Profiler::new());
+        let operands = vec![ready_value(unit.clone())];
+        let task = profiler.watch("x", &op, &plc.into(), operands, ready_value(unit));
+        task.await.unwrap().unwrap();
+        profiler.record_kernel_time("x", Duration::from_micros(42));
+
+        let profiles = profiler.profiles();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].kernel_time, Some(Duration::from_micros(42)));
+        assert_eq!(profiler.folded_stacks(), "@Host(alice);Identity;x 42\n");
+
+        let trace: serde_json::Value = serde_json::from_str(&profiler.chrome_trace()).unwrap();
+        assert_eq!(trace[1]["name"], "x");
+        assert_eq!(trace[1]["ph"], "X");
+    }
+}
@@ -0,0 +1,118 @@
+//! Progress reporting for long running sessions.
+
+use crate::computation::SessionId;
+use crate::execution::{AsyncNetworkingImpl, AsyncValue};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Minimum time between reports while a session is running.
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl Phase {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Phase::Running)
+    }
+}
+
+/// Progress of a session on a single party.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SessionProgress {
+    pub ops_completed: usize,
+    pub ops_total: usize,
+    pub phase: Phase,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+/// Called with the progress of a session whenever it changes, at most every 100ms
+/// while the session is running, and once it has finished.
+pub type ProgressCallback = Arc<dyn Fn(&SessionId, &SessionProgress) + Send + Sync>;
+
+/// Report progress of `session_id` as the given operation `results` become available.
+pub(crate) fn track_progress(
+    session_id: SessionId,
+    results: Vec<AsyncValue>,
+    networking: AsyncNetworkingImpl,
+    callback: ProgressCallback,
+) -> JoinHandle<()> {
+    let report = move |ops_completed, ops_total, phase| {
+        let stats = networking.network_stats().unwrap_or_default();
+        let progress = SessionProgress {
+            ops_completed,
+            ops_total,
+            phase,
+            bytes_sent: stats.bytes_sent(),
+            bytes_received: stats.bytes_received(),
+        };
+        callback(&session_id, &progress);
+    };
+
+    tokio::spawn(async move {
+        let ops_total = results.len();
+        let mut pending: FuturesUnordered<_> = results.into_iter().collect();
+        let mut ops_completed = 0;
+        let mut last_report = Instant::now();
+        report(ops_completed, ops_total, Phase::Running);
+
+        while let Some(res) = pending.next().await {
+            if res.is_err() {
+                // the session failed or was cancelled, so remaining results are dropped
+                report(ops_completed, ops_total, Phase::Failed);
+                return;
+            }
+            ops_completed += 1;
+            if last_report.elapsed() >= REPORT_INTERVAL {
+                report(ops_completed, ops_total, Phase::Running);
+                last_report = Instant::now();
+            }
+        }
+        report(ops_completed, ops_total, Phase::Completed);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::{HostUnit, Value};
+    use crate::execution::ready_value;
+    use crate::host::HostPlacement;
+    use crate::networking::local::LocalAsyncNetworking;
+    use parking_lot::Mutex;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_track_progress() {
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let reports = Arc::new(Mutex::new(Vec::new()));
+
+        let callback: ProgressCallback = {
+            let reports = Arc::clone(&reports);
+            Arc::new(move |_, progress| reports.lock().push(progress.clone()))
+        };
+        track_progress(
+            session_id,
+            vec![ready_value(unit.clone()), ready_value(unit)],
+            Arc::new(LocalAsyncNetworking::default()),
+            callback,
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let reports = reports.lock();
+        assert_eq!(reports.first().unwrap().phase, Phase::Running);
+        let last = reports.last().unwrap();
+        assert_eq!(last.phase, Phase::Completed);
+        assert_eq!((last.ops_completed, last.ops_total), (2, 2));
+    }
+}
@@ -0,0 +1,151 @@
+//! Recording of sessions for later replay without peers.
+//!
+//! The result of every operation that depends on something other than its
+//! operands, such as values received from other parties, values loaded from
+//! storage, and sampled randomness, is written to a recording. Replaying a
+//! session serves these results from the recording instead, and skips sending
+//! and saving values, so that the computation of a single party can be
+//! reproduced locally and deterministically, e.g. when debugging protocols or
+//! numerical issues.
+//!
+//! Operations are identified by name, so recording only makes sense for
+//! computations in which operation names are unique.
+
+use crate::computation::{Operator, SessionId, Value};
+use crate::error::{Error, Result};
+use crate::networking::wire;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize)]
+struct RecordedValue {
+    op_name: String,
+    value: Vec<u8>,
+}
+
+fn recording_error(e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("recording error: {}", e))
+}
+
+/// Whether results of `op` are needed to replay a session.
+pub(crate) fn is_recorded(op: &Operator) -> bool {
+    use Operator::*;
+    matches!(
+        op,
+        Input(_) | Load(_) | Receive(_) | PrfKeyGen(_) | Sample(_)
+    )
+}
+
+/// Whether `op` only has effects outside the session and is skipped when replaying.
+pub(crate) fn is_skipped_on_replay(op: &Operator) -> bool {
+    matches!(op, Operator::Send(_) | Operator::Save(_))
+}
+
+/// Path of the recording of `session_id` in `dir`.
+pub fn recording_path(dir: &Path, session_id: &SessionId) -> PathBuf {
+    let name: String = session_id
+        .as_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    dir.join(format!("{}.recording", name))
+}
+
+/// Writes the results needed to replay a single session.
+pub struct Recorder {
+    file: Mutex<BufWriter<File>>,
+}
+
+impl Recorder {
+    /// Record to `path`, appending to any existing recording, e.g. of a resumed session.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(recording_error)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(recording_error)?;
+        Ok(Recorder {
+            file: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Append the result of operation `op_name` to the recording.
+    ///
+    /// Every result is flushed right away so that the recording remains usable if
+    /// the worker crashes.
+    pub(crate) fn record(&self, op_name: &str, value: &Value) -> Result<()> {
+        let entry = RecordedValue {
+            op_name: op_name.to_string(),
+            value: wire::serialize_value(value)?,
+        };
+        let mut file = self.file.lock();
+        bincode::serialize_into(&mut *file, &entry).map_err(recording_error)?;
+        file.flush().map_err(recording_error)
+    }
+}
+
+/// Results recorded by a [`Recorder`], to be served when replaying.
+pub struct Replay {
+    values: HashMap<String, Value>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(recording_error)?;
+        let mut file = BufReader::new(file);
+        let mut values = HashMap::new();
+        // a truncated last entry means the worker was stopped while recording
+        while let Ok(entry) = bincode::deserialize_from::<_, RecordedValue>(&mut file) {
+            values.insert(entry.op_name, wire::deserialize_value(&entry.value)?);
+        }
+        Ok(Replay { values })
+    }
+
+    /// Number of recorded results.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Recorded result of operation `op_name`.
+    pub(crate) fn value(&self, op_name: &str) -> Result<Value> {
+        self.values.get(op_name).cloned().ok_or_else(|| {
+            Error::MalformedEnvironment(format!("No recorded result for operation '{}'", op_name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::HostUnit;
+    use crate::host::HostPlacement;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let path = recording_path(dir.path(), &session_id);
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+
+        let recorder = Recorder::create(&path).unwrap();
+        recorder.record("x", &unit).unwrap();
+        recorder.record("y", &unit).unwrap();
+
+        let replay = Replay::load(&path).unwrap();
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.value("x").unwrap(), unit);
+        assert!(replay.value("z").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Fair scheduling of kernels across concurrent sessions.
+//!
+//! Kernels become ready to run as soon as their operands are available, so a
+//! large session can easily have thousands of ready kernels. Without a bound
+//! these are all started at once, and kernels of other sessions only run once
+//! they are all done. Instead, each session may only run a bounded number of
+//! kernels at a time, and sessions take turns acquiring the slots shared by
+//! all sessions in the order in which their kernels became ready.
+
+use crate::error::{Error, Result};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Slots for running kernels shared by all sessions of a worker.
+pub struct Scheduler {
+    slots: Arc<Semaphore>,
+    slots_per_session: usize,
+}
+
+impl Scheduler {
+    /// Run at most `max_running` kernels at a time, of which at most
+    /// `max_running_per_session` may belong to the same session.
+    pub fn new(max_running: usize, max_running_per_session: usize) -> Self {
+        Scheduler {
+            slots: Arc::new(Semaphore::new(max_running)),
+            slots_per_session: std::cmp::min(max_running, max_running_per_session),
+        }
+    }
+
+    /// Slots for a new session.
+    pub fn session_slots(&self) -> SessionSlots {
+        SessionSlots {
+            session: Arc::new(Semaphore::new(self.slots_per_session)),
+            shared: Arc::clone(&self.slots),
+        }
+    }
+}
+
+/// Slots of a single session.
+#[derive(Clone)]
+pub struct SessionSlots {
+    session: Arc<Semaphore>,
+    shared: Arc<Semaphore>,
+}
+
+/// Permission to run a kernel, released when dropped.
+pub(crate) struct Slot {
+    _session: OwnedSemaphorePermit,
+    _shared: OwnedSemaphorePermit,
+}
+
+fn closed_error(e: impl std::fmt::Display) -> Error {
+    Error::Unexpected(Some(format!("scheduler closed: {}", e)))
+}
+
+impl SessionSlots {
+    /// Wait for a slot; must only be called once the kernel is otherwise ready to run.
+    pub(crate) async fn acquire(&self) -> Result<Slot> {
+        // waiting for the session's own slot first means that at most
+        // `max_running_per_session` of its kernels are queued for a shared slot
+        let session = Arc::clone(&self.session)
+            .acquire_owned()
+            .await
+            .map_err(closed_error)?;
+        let shared = Arc::clone(&self.shared)
+            .acquire_owned()
+            .await
+            .map_err(closed_error)?;
+        Ok(Slot {
+            _session: session,
+            _shared: shared,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_small_session_not_starved() {
+        let scheduler = Scheduler::new(2, 1);
+        let large = scheduler.session_slots();
+        let small = scheduler.session_slots();
+
+        // many ready kernels of the large session are only run one at a time
+        let mut running = Vec::new();
+        for _ in 0..10 {
+            let large = large.clone();
+            running.push(tokio::spawn(async move {
+                let _slot = large.acquire().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        // so the small session gets a slot right away
+        tokio::time::timeout(Duration::from_millis(50), small.acquire())
+            .await
+            .expect("small session was starved")
+            .unwrap();
+
+        for task in running {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Scrubbing of secret values from memory.
+//!
+//! Seeds and PRF keys are zeroized when dropped. Tensors holding secret shares
+//! are zeroized explicitly, typically once a session has ended, since their
+//! buffers are reference counted and shared between operations: a buffer is
+//! only scrubbed when the tensor being zeroized holds its last reference, and
+//! otherwise left intact for whoever else is still using it.
+//!
+//! Results of a session are therefore only scrubbed once the tasks watching
+//! them, such as progress tracking, have released them. Checkpoints, memory
+//! budgets, and recordings keep copies of secret values on disk, out of reach
+//! of scrubbing, and cannot be combined with it; the kernel cache only ever
+//! holds plaintext values.
+
+use crate::additive::AdtTensor;
+use crate::computation::Value;
+use crate::host::{
+    AbstractHostAesKey, HostBitArray, HostBitTensor, HostFixedTensor, HostRingTensor,
+};
+use crate::replicated::RepTensor;
+use ndarray::{ArcArrayD, IxDyn};
+use std::sync::Arc;
+use zeroize::Zeroize;
+
+impl<T> Zeroize for HostRingTensor<T>
+where
+    T: Zeroize,
+{
+    fn zeroize(&mut self) {
+        let empty = ArcArrayD::from_shape_vec(IxDyn(&[0]), Vec::new()).unwrap();
+        let array = std::mem::replace(&mut self.0, empty);
+        if let Ok(mut array) = array.try_into_owned_nocopy() {
+            array.iter_mut().for_each(Zeroize::zeroize);
+        }
+    }
+}
+
+impl Zeroize for HostBitTensor {
+    fn zeroize(&mut self) {
+        if let Some(data) = Arc::get_mut(&mut self.0.data) {
+            data.as_raw_mut_slice().zeroize();
+        }
+    }
+}
+
+impl<HostBitTensorT: Zeroize, N> Zeroize for HostBitArray<HostBitTensorT, N> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<HostBitArrayT: Zeroize> Zeroize for AbstractHostAesKey<HostBitArrayT> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<HostRingT: Zeroize> Zeroize for HostFixedTensor<HostRingT> {
+    fn zeroize(&mut self) {
+        self.tensor.zeroize();
+    }
+}
+
+impl<HostRingT: Zeroize> Zeroize for RepTensor<HostRingT> {
+    fn zeroize(&mut self) {
+        self.shares.iter_mut().flatten().for_each(Zeroize::zeroize);
+    }
+}
+
+impl<HostTensorT: Zeroize> Zeroize for AdtTensor<HostTensorT> {
+    fn zeroize(&mut self) {
+        self.shares.iter_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+/// Whether `value` may hold secret shares or keys, and is thus zeroized when scrubbed.
+pub(crate) fn is_secret(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::HostBitTensor(_)
+            | Value::HostBitArray64(_)
+            | Value::HostBitArray128(_)
+            | Value::HostBitArray224(_)
+            | Value::HostBitArray256(_)
+            | Value::HostRing64Tensor(_)
+            | Value::HostRing128Tensor(_)
+            | Value::HostFixed64Tensor(_)
+            | Value::HostFixed128Tensor(_)
+            | Value::HostAesKey(_)
+            | Value::HostSeed(_)
+            | Value::HostPrfKey(_)
+            | Value::ReplicatedRing64Tensor(_)
+            | Value::ReplicatedRing128Tensor(_)
+            | Value::ReplicatedBitTensor(_)
+            | Value::AdditiveRing64Tensor(_)
+            | Value::AdditiveRing128Tensor(_)
+            | Value::AdditiveBitTensor(_)
+    )
+}
+
+/// Zeroizes values that may hold secret shares or keys; other values are left intact.
+impl Zeroize for Value {
+    fn zeroize(&mut self) {
+        match self {
+            Value::HostBitTensor(x) => x.zeroize(),
+            Value::HostBitArray64(x) => x.zeroize(),
+            Value::HostBitArray128(x) => x.zeroize(),
+            Value::HostBitArray224(x) => x.zeroize(),
+            Value::HostBitArray256(x) => x.zeroize(),
+            Value::HostRing64Tensor(x) => x.zeroize(),
+            Value::HostRing128Tensor(x) => x.zeroize(),
+            Value::HostFixed64Tensor(x) => x.zeroize(),
+            Value::HostFixed128Tensor(x) => x.zeroize(),
+            Value::HostAesKey(x) => x.zeroize(),
+            Value::HostSeed(x) => x.0.zeroize(),
+            Value::HostPrfKey(x) => x.0.zeroize(),
+            Value::ReplicatedRing64Tensor(x) => x.zeroize(),
+            Value::ReplicatedRing128Tensor(x) => x.zeroize(),
+            Value::ReplicatedBitTensor(x) => x.zeroize(),
+            Value::AdditiveRing64Tensor(x) => x.zeroize(),
+            Value::AdditiveRing128Tensor(x) => x.zeroize(),
+            Value::AdditiveBitTensor(x) => x.zeroize(),
+            _ => (),
+        }
+    }
+}
+
+/// Futures completing once the tasks watching the results of a session have released them.
+#[cfg(feature = "async_execute")]
+pub(crate) type ResultHolders = Vec<futures::future::BoxFuture<'static, ()>>;
+
+/// Holder of results released once `task` has finished.
+#[cfg(feature = "async_execute")]
+pub(crate) fn task_holder(
+    task: tokio::task::JoinHandle<()>,
+) -> futures::future::BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let _ = task.await;
+    })
+}
+
+/// Secret results of a session, by whether they were zeroized.
+#[cfg(feature = "async_execute")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ScrubSummary {
+    /// Results held by nothing but the session, which were zeroized.
+    pub scrubbed: usize,
+    /// Results still in use elsewhere, such as outputs, which were left intact.
+    pub in_use: usize,
+}
+
+/// Zeroize the results of a finished session once `holders` have released them.
+#[cfg(feature = "async_execute")]
+pub(crate) async fn scrub_results(
+    results: Vec<crate::execution::AsyncValue>,
+    holders: ResultHolders,
+) -> ScrubSummary {
+    use futures::FutureExt;
+
+    let mut summary = ScrubSummary::default();
+    if results.is_empty() {
+        return summary;
+    }
+    futures::future::join_all(holders).await;
+    // taking the values out of the shared futures releases their references
+    let mut values: Vec<(bool, Value)> = results
+        .into_iter()
+        .filter_map(|result| {
+            let is_held_elsewhere = result.strong_count() != Some(1);
+            match result.now_or_never() {
+                Some(Ok(value)) if is_secret(&value) => Some((is_held_elsewhere, value)),
+                _ => None,
+            }
+        })
+        .collect();
+    for (is_held_elsewhere, value) in values.iter_mut() {
+        value.zeroize();
+        match is_held_elsewhere {
+            true => summary.in_use += 1,
+            false => summary.scrubbed += 1,
+        }
+    }
+    tracing::debug!(
+        "Scrubbed {} results, {} still in use",
+        summary.scrubbed,
+        summary.in_use
+    );
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{FromRaw, HostPlacement, RawSeed};
+    use crate::types::HostRing64Tensor;
+    use ndarray::array;
+
+    #[test]
+    fn test_zeroize_ring_tensor() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![1u64, 2, 3]);
+
+        let mut shared = x.clone();
+        shared.zeroize();
+        assert_eq!(shared.0.len(), 0);
+        // still in use by `x`, so left intact
+        assert_eq!(x, plc.from_raw(array![1u64, 2, 3]));
+
+        let mut bits: HostBitTensor = plc.from_raw(array![1u8, 0, 1]);
+        bits.zeroize();
+        assert!(bits.0.data.not_any());
+
+        let mut seed = RawSeed([7; 16]);
+        seed.zeroize();
+        assert_eq!(seed.0, [0; 16]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Summaries of the values computed in sessions, for debugging.
+//!
+//! When enabled, a one-line summary of the value computed by every operation is
+//! logged once it is available: its type and shape, and for plaintext tensors the
+//! minimum, maximum, and mean as well as the number of NaN and infinite entries,
+//! which helps locating where values first go wrong. Values that are secret shared
+//! are only described by their placement, shape, and precision, since their shares
+//! reveal nothing about the values themselves.
+
+use crate::computation::{Placed, Value};
+use crate::execution::AsyncValue;
+use crate::textual::ToTextual;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::fmt::Display;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+fn stats<I: Iterator<Item = f64>>(values: I) -> String {
+    let mut count = 0usize;
+    let mut nans = 0usize;
+    let mut infinites = 0usize;
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    let mut sum = 0.0;
+    for x in values {
+        if x.is_nan() {
+            nans += 1;
+            continue;
+        }
+        if x.is_infinite() {
+            infinites += 1;
+            continue;
+        }
+        count += 1;
+        min = min.min(x);
+        max = max.max(x);
+        sum += x;
+    }
+    let mut summary = if count > 0 {
+        format!("min {}, max {}, mean {}", min, max, sum / count as f64)
+    } else {
+        "no finite entries".to_string()
+    };
+    if nans > 0 {
+        summary.push_str(&format!(", {} NaN", nans));
+    }
+    if infinites > 0 {
+        summary.push_str(&format!(", {} infinite", infinites));
+    }
+    summary
+}
+
+fn secret(placement: String, shape: &[usize], precision: Option<u32>) -> String {
+    let mut summary = format!("secret shared on {}, shape {:?}", placement, shape);
+    if let Some(precision) = precision {
+        summary.push_str(&format!(", fractional precision {}", precision));
+    }
+    summary
+}
+
+fn placement_or_unknown<P: ToTextual, E>(placement: Result<P, E>) -> String {
+    placement
+        .map(|placement| placement.to_textual())
+        .unwrap_or_else(|_| "unknown placement".to_string())
+}
+
+/// One-line summary of `value`.
+pub fn summarize(value: &Value) -> String {
+    let ty = value.ty().to_textual();
+    let details = match value {
+        Value::HostFloat32Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostFloat64Tensor(x) => Some(with_shape(x.0.shape(), stats(x.0.iter().copied()))),
+        Value::HostInt8Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostInt16Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostInt32Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostInt64Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostUint8Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostUint16Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostUint32Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostUint64Tensor(x) => Some(with_shape(
+            x.0.shape(),
+            stats(x.0.iter().map(|x| *x as f64)),
+        )),
+        Value::HostFixed64Tensor(x) => {
+            let scale = 2f64.powi(x.fractional_precision as i32);
+            Some(with_shape(
+                x.tensor.0.shape(),
+                format!(
+                    "{}, fractional precision {}",
+                    stats(x.tensor.0.iter().map(|x| x.0 as i64 as f64 / scale)),
+                    x.fractional_precision
+                ),
+            ))
+        }
+        Value::HostFixed128Tensor(x) => {
+            let scale = 2f64.powi(x.fractional_precision as i32);
+            Some(with_shape(
+                x.tensor.0.shape(),
+                format!(
+                    "{}, fractional precision {}",
+                    stats(x.tensor.0.iter().map(|x| x.0 as i128 as f64 / scale)),
+                    x.fractional_precision
+                ),
+            ))
+        }
+        Value::HostRing64Tensor(x) => Some(format!("shape {:?}", x.0.shape())),
+        Value::HostRing128Tensor(x) => Some(format!("shape {:?}", x.0.shape())),
+        Value::HostBitTensor(x) => Some(format!("shape {:?}", x.0.shape())),
+        Value::HostShape(x) => Some(format!("{:?}", (x.0).0)),
+        Value::ReplicatedRing64Tensor(x) => Some(secret(
+            placement_or_unknown(x.placement()),
+            x.shares[0][0].0.shape(),
+            None,
+        )),
+        Value::ReplicatedRing128Tensor(x) => Some(secret(
+            placement_or_unknown(x.placement()),
+            x.shares[0][0].0.shape(),
+            None,
+        )),
+        Value::ReplicatedBitTensor(x) => Some(secret(
+            placement_or_unknown(x.placement()),
+            x.shares[0][0].0.shape(),
+            None,
+        )),
+        Value::ReplicatedFixed64Tensor(x) => Some(secret(
+            placement_or_unknown(x.tensor.placement()),
+            x.tensor.shares[0][0].0.shape(),
+            Some(x.fractional_precision),
+        )),
+        Value::ReplicatedFixed128Tensor(x) => Some(secret(
+            placement_or_unknown(x.tensor.placement()),
+            x.tensor.shares[0][0].0.shape(),
+            Some(x.fractional_precision),
+        )),
+        Value::AdditiveRing64Tensor(x) => Some(secret(
+            placement_or_unknown(x.placement()),
+            x.shares[0].0.shape(),
+            None,
+        )),
+        Value::AdditiveRing128Tensor(x) => Some(secret(
+            placement_or_unknown(x.placement()),
+            x.shares[0].0.shape(),
+            None,
+        )),
+        _ => None,
+    };
+    match details {
+        Some(details) => format!("{}: {}", ty, details),
+        None => ty,
+    }
+}
+
+fn with_shape(shape: &[usize], details: impl Display) -> String {
+    format!("shape {:?}, {}", shape, details)
+}
+
+/// Log a summary of each of the named `results` once available.
+pub(crate) fn log_summaries(results: Vec<(String, AsyncValue)>) -> JoinHandle<()> {
+    let task = async move {
+        let mut pending: FuturesUnordered<_> = results
+            .into_iter()
+            .map(|(op_name, value)| async move { (op_name, value.await) })
+            .collect();
+        while let Some((op_name, value)) = pending.next().await {
+            // failures are reported by the session itself
+            if let Ok(value) = value {
+                tracing::info!(op = %op_name, "{}", summarize(&value));
+            }
+        }
+    };
+    tokio::spawn(task.instrument(tracing::Span::current()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{HostFixedTensor, HostPlacement, HostRingTensor, HostTensor};
+    use ndarray::array;
+    use std::num::Wrapping;
+
+    #[test]
+    fn test_summarize_float() {
+        let plc = HostPlacement::from("alice");
+        let x = HostTensor::<f64>(
+            array![1.0, 3.0, f64::NAN, f64::INFINITY]
+                .into_dyn()
+                .into_shared(),
+            plc,
+        );
+        assert_eq!(
+            summarize(&Value::from(x)),
+            "HostFloat64Tensor: shape [4], min 1, max 3, mean 2, 1 NaN, 1 infinite"
+        );
+    }
+
+    #[test]
+    fn test_summarize_fixed() {
+        let plc = HostPlacement::from("alice");
+        let ring = HostRingTensor::<u64>(
+            array![Wrapping(1u64 << 8), Wrapping((-(1i64 << 9)) as u64)]
+                .into_dyn()
+                .into_shared(),
+            plc,
+        );
+        let x = HostFixedTensor {
+            tensor: ring,
+            fractional_precision: 8,
+            integral_precision: 8,
+        };
+        assert_eq!(
+            summarize(&Value::from(x)),
+            "HostFixed64Tensor: shape [2], min -2, max 1, mean -0.5, fractional precision 8"
+        );
+    }
+}
@@ -281,6 +281,7 @@ impl DispatchKernel<SymbolicSession, SymbolicValue> for Operator {
             AddN(op) => DispatchKernel::compile(op, plc),
             And(op) => DispatchKernel::compile(op, plc),
             Argmax(op) => DispatchKernel::compile(op, plc),
+            Conv2D(op) => DispatchKernel::compile(op, plc),
             AtLeast2D(op) => DispatchKernel::compile(op, plc),
             BitCompose(op) => DispatchKernel::compile(op, plc),
             BitDecompose(op) => DispatchKernel::compile(op, plc),
@@ -302,12 +303,17 @@ impl DispatchKernel<SymbolicSession, SymbolicValue> for Operator {
             Fill(op) => DispatchKernel::compile(op, plc),
             FixedpointDecode(op) => DispatchKernel::compile(op, plc),
             FixedpointEncode(op) => DispatchKernel::compile(op, plc),
+            GaussianNoise(op) => DispatchKernel::compile(op, plc),
             Greater(op) => DispatchKernel::compile(op, plc),
             Identity(op) => DispatchKernel::compile(op, plc),
             Index(op) => DispatchKernel::compile(op, plc),
             IndexAxis(op) => DispatchKernel::compile(op, plc),
             Input(op) => DispatchKernel::compile(op, plc),
             Inverse(op) => DispatchKernel::compile(op, plc),
+            LaplaceNoise(op) => DispatchKernel::compile(op, plc),
+            Commit(op) => DispatchKernel::compile(op, plc),
+            OpenCommitment(op) => DispatchKernel::compile(op, plc),
+            VerifyCommitment(op) => DispatchKernel::compile(op, plc),
             Less(op) => DispatchKernel::compile(op, plc),
             Load(op) => DispatchKernel::compile(op, plc),
             Log(op) => DispatchKernel::compile(op, plc),
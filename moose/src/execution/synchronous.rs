@@ -2,12 +2,13 @@
 
 use super::{Identity, Operands, RuntimeSession, Session, SetupGeneration};
 use crate::computation::*;
-use crate::error::{Error, Result};
+use crate::error::{Error, OperationContext, Result};
 use crate::host::*;
 use crate::kernels::{DispatchKernel, Kernel};
 use crate::networking::{local::LocalSyncNetworking, SyncNetworking};
 use crate::replicated::*;
 use crate::storage::{local::LocalSyncStorage, SyncStorage};
+use crate::textual::ToTextual;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::rc::Rc;
@@ -162,6 +163,7 @@ impl DispatchKernel<SyncSession, Value> for Operator {
             AddN(op) => DispatchKernel::compile(op, plc),
             And(op) => DispatchKernel::compile(op, plc),
             Argmax(op) => DispatchKernel::compile(op, plc),
+            Conv2D(op) => DispatchKernel::compile(op, plc),
             AtLeast2D(op) => DispatchKernel::compile(op, plc),
             BitCompose(op) => DispatchKernel::compile(op, plc),
             BitDecompose(op) => DispatchKernel::compile(op, plc),
@@ -183,12 +185,17 @@ impl DispatchKernel<SyncSession, Value> for Operator {
             Fill(op) => DispatchKernel::compile(op, plc),
             FixedpointDecode(op) => DispatchKernel::compile(op, plc),
             FixedpointEncode(op) => DispatchKernel::compile(op, plc),
+            GaussianNoise(op) => DispatchKernel::compile(op, plc),
             Greater(op) => DispatchKernel::compile(op, plc),
             Identity(op) => DispatchKernel::compile(op, plc),
             Index(op) => DispatchKernel::compile(op, plc),
             IndexAxis(op) => DispatchKernel::compile(op, plc),
             Input(op) => DispatchKernel::compile(op, plc),
             Inverse(op) => DispatchKernel::compile(op, plc),
+            LaplaceNoise(op) => DispatchKernel::compile(op, plc),
+            Commit(op) => DispatchKernel::compile(op, plc),
+            OpenCommitment(op) => DispatchKernel::compile(op, plc),
+            VerifyCommitment(op) => DispatchKernel::compile(op, plc),
             Less(op) => DispatchKernel::compile(op, plc),
             Log(op) => DispatchKernel::compile(op, plc),
             Log2(op) => DispatchKernel::compile(op, plc),
@@ -353,10 +360,12 @@ impl TestSyncExecutor {
             let value = session
                 .execute(&op.kind, &op.placement, operands)
                 .map_err(|e| {
-                    Error::Compilation(format!(
-                        "SyncSession failed to execute computation due to an error: {:?}",
-                        e,
-                    ))
+                    e.in_operation(&OperationContext {
+                        session_id: session.session_id.to_string(),
+                        op_name: op.name.clone(),
+                        operator: op.kind.short_name().to_string(),
+                        placement: op.placement.to_textual(),
+                    })
                 })?;
             env.insert(op.name.clone(), value);
         }
@@ -7,7 +7,7 @@ use crate::error::{Error, Result};
 use crate::execution::Session;
 use crate::floatingpoint::FloatTensor;
 use crate::host::*;
-use crate::integer::AbstractUint64Tensor;
+use crate::integer::{AbstractSmallIntTensor, AbstractUint64Tensor};
 use crate::kernels::*;
 use crate::mirrored::*;
 use crate::replicated::*;
@@ -152,6 +152,78 @@ impl FixedpointEncodeOp {
             integral_precision,
         })
     }
+
+    pub(crate) fn u64_fixed_kernel<
+        S: Session,
+        HostUintT,
+        RepUintT,
+        HostFixedT,
+        MirFixedT,
+        RepFixedT,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        fractional_precision: u32,
+        integral_precision: u32,
+        x: AbstractUint64Tensor<HostUintT, RepUintT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        HostPlacement: PlacementReveal<S, RepUintT, HostUintT>,
+        HostPlacement: PlacementFixedpointEncode<S, HostUintT, HostFixedT>,
+    {
+        let v = match x {
+            AbstractUint64Tensor::Host(v) => v,
+            AbstractUint64Tensor::Replicated(v) => plc.reveal(sess, &v),
+        };
+
+        Ok(FixedTensor::Host(plc.fixedpoint_encode(
+            sess,
+            fractional_precision,
+            integral_precision,
+            &v,
+        )))
+    }
+
+    pub(crate) fn small_int_fixed_kernel<S: Session, HostIntT, HostFixedT, MirFixedT, RepFixedT>(
+        sess: &S,
+        plc: &HostPlacement,
+        fractional_precision: u32,
+        integral_precision: u32,
+        x: AbstractSmallIntTensor<HostIntT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        HostPlacement: PlacementPlace<S, HostIntT>,
+        HostPlacement: PlacementFixedpointEncode<S, HostIntT, HostFixedT>,
+    {
+        let AbstractSmallIntTensor::Host(v) = x;
+        let v = plc.place(sess, v);
+        Ok(FixedTensor::Host(plc.fixedpoint_encode(
+            sess,
+            fractional_precision,
+            integral_precision,
+            &v,
+        )))
+    }
+
+    /// Encode integers exactly, by scaling their ring representation.
+    pub(crate) fn hostfixed_int_kernel<S: Session, HostIntT, HostRingT>(
+        sess: &S,
+        plc: &HostPlacement,
+        fractional_precision: u32,
+        integral_precision: u32,
+        x: HostIntT,
+    ) -> Result<HostFixedTensor<HostRingT>>
+    where
+        HostPlacement: PlacementCast<S, HostIntT, HostRingT>,
+        HostPlacement: PlacementShl<S, HostRingT, HostRingT>,
+    {
+        let y = plc.cast(sess, &x);
+        Ok(HostFixedTensor {
+            tensor: plc.shl(sess, fractional_precision as usize, &y),
+            fractional_precision,
+            integral_precision,
+        })
+    }
 }
 
 impl FixedpointDecodeOp {
@@ -238,6 +310,75 @@ impl FixedpointDecodeOp {
         assert_eq!(x.fractional_precision, precision);
         Ok(plc.fixedpoint_ring_decode(sess, 2, precision, &x.tensor))
     }
+
+    pub(crate) fn fixed_u64_kernel<
+        S: Session,
+        HostFixedT,
+        MirFixedT,
+        RepFixedT,
+        HostUintT,
+        RepUintT,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        precision: u32,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<AbstractUint64Tensor<HostUintT, RepUintT>>
+    where
+        HostPlacement: PlacementReveal<S, RepFixedT, HostFixedT>,
+        HostPlacement: PlacementDemirror<S, MirFixedT, HostFixedT>,
+        HostPlacement: PlacementFixedpointDecode<S, HostFixedT, HostUintT>,
+    {
+        let v = match x {
+            FixedTensor::Host(v) => v,
+            FixedTensor::Mirrored3(v) => plc.demirror(sess, &v),
+            FixedTensor::Replicated(v) => plc.reveal(sess, &v),
+        };
+
+        Ok(AbstractUint64Tensor::Host(
+            plc.fixedpoint_decode(sess, precision, &v),
+        ))
+    }
+
+    pub(crate) fn fixed_small_int_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT, HostIntT>(
+        sess: &S,
+        plc: &HostPlacement,
+        precision: u32,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<AbstractSmallIntTensor<HostIntT>>
+    where
+        HostPlacement: PlacementReveal<S, RepFixedT, HostFixedT>,
+        HostPlacement: PlacementDemirror<S, MirFixedT, HostFixedT>,
+        HostPlacement: PlacementFixedpointDecode<S, HostFixedT, HostIntT>,
+    {
+        let v = match x {
+            FixedTensor::Host(v) => v,
+            FixedTensor::Mirrored3(v) => plc.demirror(sess, &v),
+            FixedTensor::Replicated(v) => plc.reveal(sess, &v),
+        };
+
+        Ok(AbstractSmallIntTensor::Host(
+            plc.fixedpoint_decode(sess, precision, &v),
+        ))
+    }
+
+    /// Decode into integers by dropping the fractional bits, which rounds values
+    /// down. Values outside the range of the integer type wrap around, such as
+    /// negative values decoded into unsigned integers.
+    pub(crate) fn hostfixed_int_kernel<S: Session, HostRingT, HostIntT>(
+        sess: &S,
+        plc: &HostPlacement,
+        precision: u32,
+        x: HostFixedTensor<HostRingT>,
+    ) -> Result<HostIntT>
+    where
+        HostPlacement: PlacementShr<S, HostRingT, HostRingT>,
+        HostPlacement: PlacementCast<S, HostRingT, HostIntT>,
+    {
+        assert_eq!(x.fractional_precision, precision);
+        let y = plc.shr(sess, precision as usize, &x.tensor);
+        Ok(plc.cast(sess, &y))
+    }
 }
 
 impl AbsOp {
@@ -848,6 +989,110 @@ impl DotOp {
     }
 }
 
+impl Conv2DOp {
+    pub(crate) fn fixed_on_host_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
+        sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+        filter: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        HostPlacement: PlacementReveal<S, RepFixedT, HostFixedT>,
+        HostPlacement: PlacementDemirror<S, MirFixedT, HostFixedT>,
+        HostPlacement: PlacementConv2D<S, HostFixedT, HostFixedT, HostFixedT>,
+    {
+        let x_revealed = match x {
+            FixedTensor::Host(x) => x,
+            FixedTensor::Mirrored3(x) => plc.demirror(sess, &x),
+            FixedTensor::Replicated(x) => plc.reveal(sess, &x),
+        };
+        let filter_revealed = match filter {
+            FixedTensor::Host(x) => x,
+            FixedTensor::Mirrored3(x) => plc.demirror(sess, &x),
+            FixedTensor::Replicated(x) => plc.reveal(sess, &x),
+        };
+
+        let z = plc.conv2d(sess, stride, padding, &x_revealed, &filter_revealed);
+        Ok(FixedTensor::Host(z))
+    }
+
+    pub(crate) fn fixed_on_rep_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        stride: usize,
+        padding: usize,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+        filter: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        ReplicatedPlacement: PlacementShare<S, HostFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementShare<S, MirFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementConv2D<S, RepFixedT, RepFixedT, RepFixedT>,
+    {
+        let x_shared = match x {
+            FixedTensor::Host(x) => plc.share(sess, &x),
+            FixedTensor::Mirrored3(x) => plc.share(sess, &x),
+            FixedTensor::Replicated(x) => x,
+        };
+        let filter_shared = match filter {
+            FixedTensor::Host(x) => plc.share(sess, &x),
+            FixedTensor::Mirrored3(x) => plc.share(sess, &x),
+            FixedTensor::Replicated(x) => x,
+        };
+
+        let z = plc.conv2d(sess, stride, padding, &x_shared, &filter_shared);
+        Ok(FixedTensor::Replicated(z))
+    }
+
+    /// Unlike dot products, convolutions truncate their result such that it
+    /// keeps the precision of the operands.
+    pub(crate) fn hostfixed_kernel<S: Session, HostRingT>(
+        sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: HostFixedTensor<HostRingT>,
+        filter: HostFixedTensor<HostRingT>,
+    ) -> Result<HostFixedTensor<HostRingT>>
+    where
+        HostPlacement: PlacementConv2D<S, HostRingT, HostRingT, HostRingT>,
+        HostPlacement: PlacementShr<S, HostRingT, HostRingT>,
+    {
+        assert_eq!(x.fractional_precision, filter.fractional_precision);
+        let z = plc.conv2d(sess, stride, padding, &x.tensor, &filter.tensor);
+        Ok(HostFixedTensor {
+            tensor: plc.shr(sess, x.fractional_precision as usize, &z),
+            fractional_precision: x.fractional_precision,
+            integral_precision: u32::max(x.integral_precision, filter.integral_precision),
+        })
+    }
+
+    /// Unlike dot products, convolutions truncate their result such that it
+    /// keeps the precision of the operands.
+    pub(crate) fn repfixed_kernel<S: Session, RepRingT>(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        stride: usize,
+        padding: usize,
+        x: RepFixedTensor<RepRingT>,
+        filter: RepFixedTensor<RepRingT>,
+    ) -> Result<RepFixedTensor<RepRingT>>
+    where
+        ReplicatedPlacement: PlacementConv2D<S, RepRingT, RepRingT, RepRingT>,
+        ReplicatedPlacement: PlacementTruncPr<S, RepRingT, RepRingT>,
+    {
+        assert_eq!(x.fractional_precision, filter.fractional_precision);
+        let z = plc.conv2d(sess, stride, padding, &x.tensor, &filter.tensor);
+        Ok(RepFixedTensor {
+            tensor: plc.trunc_pr(sess, x.fractional_precision, &z),
+            fractional_precision: x.fractional_precision,
+            integral_precision: u32::max(x.integral_precision, filter.integral_precision),
+        })
+    }
+}
+
 impl TruncPrOp {
     pub(crate) fn fixed_host_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
         sess: &S,
@@ -1711,6 +1956,53 @@ impl SqrtOp {
     }
 }
 
+impl LaplaceNoiseOp {
+    pub(crate) fn fixed_rep_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        epsilon: f64,
+        sensitivity: f64,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        ReplicatedPlacement: PlacementShare<S, HostFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementShare<S, MirFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementLaplaceNoise<S, RepFixedT, RepFixedT>,
+    {
+        let x = match x {
+            FixedTensor::Host(v) => plc.share(sess, &v),
+            FixedTensor::Mirrored3(v) => plc.share(sess, &v),
+            FixedTensor::Replicated(v) => v,
+        };
+        let z = plc.laplace_noise(sess, epsilon, sensitivity, &x);
+        Ok(FixedTensor::Replicated(z))
+    }
+}
+
+impl GaussianNoiseOp {
+    pub(crate) fn fixed_rep_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: FixedTensor<HostFixedT, MirFixedT, RepFixedT>,
+    ) -> Result<FixedTensor<HostFixedT, MirFixedT, RepFixedT>>
+    where
+        ReplicatedPlacement: PlacementShare<S, HostFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementShare<S, MirFixedT, RepFixedT>,
+        ReplicatedPlacement: PlacementGaussianNoise<S, RepFixedT, RepFixedT>,
+    {
+        let x = match x {
+            FixedTensor::Host(v) => plc.share(sess, &v),
+            FixedTensor::Mirrored3(v) => plc.share(sess, &v),
+            FixedTensor::Replicated(v) => v,
+        };
+        let z = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
+        Ok(FixedTensor::Replicated(z))
+    }
+}
+
 impl SigmoidOp {
     pub(crate) fn fixed_rep_kernel<S: Session, HostFixedT, MirFixedT, RepFixedT>(
         sess: &S,
@@ -3396,6 +3688,125 @@ mod tests {
         test_rep_sqrt_fixed128(x, y_targets);
     }
 
+    #[test]
+    fn test_rep_noise() {
+        let alice = HostPlacement::from("alice");
+        let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+        let sess = SyncSession::default();
+
+        let targets = [1.5, -2.0, 3.25];
+        let x_encoded = array![1.5, -2.0, 3.25]
+            .into_dyn()
+            .map(|item: &f64| (2f64.powi(20) * item) as i128 as u128);
+        let x = FixedTensor::Host(HostFixedTensor {
+            tensor: HostRingTensor::from_raw_plc(x_encoded, alice.clone()),
+            integral_precision: 20,
+            fractional_precision: 20,
+        });
+
+        // the privacy budget, or the sensitivity, make the noise negligible
+        let laplace = rep.laplace_noise(&sess, 1e6, 1.0, &x);
+        let gaussian = rep.gaussian_noise(&sess, 0.5, 1e-5, 1e-6, &x);
+        for z in [laplace, gaussian] {
+            let opened = match z {
+                FixedTensor::Replicated(r) => alice.reveal(&sess, &r),
+                _ => {
+                    panic!("Should not produce an non-replicated tensor on a replicated placement")
+                }
+            };
+            let result = Convert::decode(&opened.tensor, 2u128.pow(20));
+            for (r, t) in result.0.iter().zip(targets) {
+                assert!((r - t).abs() < 0.01, "expected {} but got {}", t, r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rep_conv2d() {
+        let alice = HostPlacement::from("alice");
+        let rep = ReplicatedPlacement::from(["alice", "bob", "carole"]);
+        let sess = SyncSession::default();
+
+        let encode = |x: ArrayD<f64>| -> Fixed128Tensor {
+            FixedTensor::Host(HostFixedTensor {
+                tensor: HostRingTensor::from_raw_plc(
+                    x.map(|item| (2f64.powi(20) * item) as i128 as u128),
+                    alice.clone(),
+                ),
+                integral_precision: 20,
+                fractional_precision: 20,
+            })
+        };
+        let x = encode(array![[[[1.0, -2.0], [3.0, 0.5]], [[0.0, 1.5], [-1.0, 2.0]]]].into_dyn());
+        let filter = encode(array![[[[0.5]], [[-1.0]]]].into_dyn());
+
+        let z = match rep.conv2d(&sess, 1, 0, &x, &filter) {
+            FixedTensor::Replicated(r) => alice.reveal(&sess, &r),
+            _ => panic!("Should not produce an non-replicated tensor on a replicated placement"),
+        };
+        assert_eq!(z.fractional_precision, 20);
+        let result = Convert::decode(&z.tensor, 2u128.pow(20));
+        let targets = [0.5, -2.5, 2.5, -1.75];
+        for (r, t) in result.0.iter().zip(targets) {
+            assert!((r - t).abs() < 0.001, "expected {} but got {}", t, r);
+        }
+    }
+
+    #[test]
+    fn test_host_fixed_u64_round_trip() {
+        let alice = HostPlacement::from("alice");
+        let sess = SyncSession::default();
+
+        let x: HostUint64Tensor = alice.from_raw(array![0u64, 5, 300]);
+        let x_fixed64: HostFixed64Tensor = alice.fixedpoint_encode(&sess, 10, 20, &x);
+        let expected: HostRing64Tensor = alice.from_raw(array![0u64, 5 << 10, 300 << 10]);
+        assert_eq!(x_fixed64.tensor, expected);
+        let y: HostUint64Tensor = alice.fixedpoint_decode(&sess, 10, &x_fixed64);
+        assert_eq!(y, x);
+
+        let x_fixed128: HostFixed128Tensor = alice.fixedpoint_encode(&sess, 40, 20, &x);
+        let expected: HostRing128Tensor = alice.from_raw(array![0u128, 5 << 40, 300 << 40]);
+        assert_eq!(x_fixed128.tensor, expected);
+        let y: HostUint64Tensor = alice.fixedpoint_decode(&sess, 40, &x_fixed128);
+        assert_eq!(y, x);
+    }
+
+    #[test]
+    fn test_host_fixed_small_int_round_trip() {
+        let alice = HostPlacement::from("alice");
+        let sess = SyncSession::default();
+
+        let x: HostInt8Tensor = alice.from_raw(array![-128i8, -3, 0, 5, 127]);
+        let x_fixed64: HostFixed64Tensor = alice.fixedpoint_encode(&sess, 10, 20, &x);
+        let expected: HostRing64Tensor = alice.from_raw(array![
+            (-128i64 << 10) as u64,
+            (-3i64 << 10) as u64,
+            0,
+            5 << 10,
+            127 << 10
+        ]);
+        assert_eq!(x_fixed64.tensor, expected);
+        let y: HostInt8Tensor = alice.fixedpoint_decode(&sess, 10, &x_fixed64);
+        assert_eq!(y, x);
+
+        let x: HostInt16Tensor = alice.from_raw(array![-32768i16, -300, 0, 300, 32767]);
+        let x_fixed128: HostFixed128Tensor = alice.fixedpoint_encode(&sess, 40, 20, &x);
+        let y: HostInt16Tensor = alice.fixedpoint_decode(&sess, 40, &x_fixed128);
+        assert_eq!(y, x);
+
+        let x: HostUint8Tensor = alice.from_raw(array![0u8, 5, 255]);
+        let x_fixed64: HostFixed64Tensor = alice.fixedpoint_encode(&sess, 10, 20, &x);
+        let y: HostUint8Tensor = alice.fixedpoint_decode(&sess, 10, &x_fixed64);
+        assert_eq!(y, x);
+
+        // dropping the fractional bits of negative values rounds them down
+        let x: HostFloat64Tensor = alice.from_raw(array![-2.5f64, 2.5]);
+        let x_fixed128: HostFixed128Tensor = alice.fixedpoint_encode(&sess, 40, 20, &x);
+        let y: HostInt8Tensor = alice.fixedpoint_decode(&sess, 40, &x_fixed128);
+        let expected: HostInt8Tensor = alice.from_raw(array![-3i8, 2]);
+        assert_eq!(y, expected);
+    }
+
     rep_approx_unary_fixed_test!(test_rep_sigmoid_fixed64, sigmoid<i64, u64>, 10, 10, 0.1);
     rep_approx_unary_fixed_test!(test_rep_sigmoid_fixed128, sigmoid<i128, u128>, 20, 20, 0.001);
 
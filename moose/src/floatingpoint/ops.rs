@@ -339,6 +339,31 @@ impl DotOp {
     }
 }
 
+impl Conv2DOp {
+    pub(crate) fn float_host_kernel<S: Session, HostFloatT, MirroredT>(
+        sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: FloatTensor<HostFloatT, MirroredT>,
+        filter: FloatTensor<HostFloatT, MirroredT>,
+    ) -> Result<FloatTensor<HostFloatT, MirroredT>>
+    where
+        HostPlacement: PlacementConv2D<S, HostFloatT, HostFloatT, HostFloatT>,
+    {
+        let (x, filter) = match (x, filter) {
+            (FloatTensor::Host(x), FloatTensor::Host(filter)) => (x, filter),
+            _ => {
+                return Err(Error::UnimplementedOperator(
+                    "Conv2DOp @ Mirrored3Placement".to_string(),
+                ))
+            }
+        };
+        let z = plc.conv2d(sess, stride, padding, &x, &filter);
+        Ok(FloatTensor::Host(z))
+    }
+}
+
 impl LessOp {
     pub(crate) fn float_kernel<S: Session, HostFloatT, HostBitT, RepBitT, MirroredT>(
         sess: &S,
@@ -613,6 +638,55 @@ impl SqrtOp {
     }
 }
 
+impl LaplaceNoiseOp {
+    pub(crate) fn float_host_kernel<S: Session, HostFloatT, MirroredT>(
+        sess: &S,
+        plc: &HostPlacement,
+        epsilon: f64,
+        sensitivity: f64,
+        x: FloatTensor<HostFloatT, MirroredT>,
+    ) -> Result<FloatTensor<HostFloatT, MirroredT>>
+    where
+        HostPlacement: PlacementLaplaceNoise<S, HostFloatT, HostFloatT>,
+    {
+        let x = match x {
+            FloatTensor::Host(v) => v,
+            FloatTensor::Mirrored3(_v) => {
+                return Err(Error::UnimplementedOperator(
+                    "LaplaceNoiseOp @ Mirrored3Placement".to_string(),
+                ))
+            }
+        };
+        let z = plc.laplace_noise(sess, epsilon, sensitivity, &x);
+        Ok(FloatTensor::Host(z))
+    }
+}
+
+impl GaussianNoiseOp {
+    pub(crate) fn float_host_kernel<S: Session, HostFloatT, MirroredT>(
+        sess: &S,
+        plc: &HostPlacement,
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: FloatTensor<HostFloatT, MirroredT>,
+    ) -> Result<FloatTensor<HostFloatT, MirroredT>>
+    where
+        HostPlacement: PlacementGaussianNoise<S, HostFloatT, HostFloatT>,
+    {
+        let x = match x {
+            FloatTensor::Host(v) => v,
+            FloatTensor::Mirrored3(_v) => {
+                return Err(Error::UnimplementedOperator(
+                    "GaussianNoiseOp @ Mirrored3Placement".to_string(),
+                ))
+            }
+        };
+        let z = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
+        Ok(FloatTensor::Host(z))
+    }
+}
+
 impl ExpOp {
     pub(crate) fn float_kernel<S: Session, HostFloatT, MirroredT>(
         sess: &S,
@@ -0,0 +1,588 @@
+//! Generation of random computations for fuzzing.
+//!
+//! `ComputationGenerator` produces computations that are well-typed and whose
+//! operands have matching shapes, such that they are expected to make it through
+//! the parser, the compiler passes, and the executors. Values are kept as
+//! `Float64` tensors on host placements and as fixed-point tensors on the
+//! replicated placement, with casts inserted where values move between the two.
+//!
+//! Computations triggering a bug are typically much larger than needed to
+//! reproduce it. `shrink` minimizes them by removing as many operations as
+//! possible while the bug persists, and `fuzz` runs a check on many generated
+//! computations and returns the first failure found in minimized form.
+
+use crate::computation::{
+    AddOp, CastOp, Computation, ConstantOp, DotOp, MulOp, Operation, Operator, OutputOp, Placement,
+    Role, Signature, SubOp, SumOp, TransposeOp, Ty,
+};
+use crate::host::{FromRaw, HostPlacement};
+use crate::logical::TensorDType;
+use crate::replicated::ReplicatedPlacement;
+use crate::types::HostFloat64Tensor;
+use ndarray::{ArrayD, IxDyn};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const FIXED: TensorDType = TensorDType::Fixed128 {
+    integral_precision: 24,
+    fractional_precision: 40,
+};
+
+/// Operations that generated computations may consist of, besides constants,
+/// casts, and outputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FuzzOp {
+    Add,
+    Sub,
+    Mul,
+    Dot,
+    Transpose,
+    Sum,
+}
+
+impl FuzzOp {
+    pub const ALL: [FuzzOp; 6] = [
+        FuzzOp::Add,
+        FuzzOp::Sub,
+        FuzzOp::Mul,
+        FuzzOp::Dot,
+        FuzzOp::Transpose,
+        FuzzOp::Sum,
+    ];
+}
+
+/// Generator of random well-typed computations.
+#[derive(Clone, Debug)]
+pub struct ComputationGenerator {
+    ops: Vec<FuzzOp>,
+    hosts: Vec<Role>,
+    replicated: Option<ReplicatedPlacement>,
+    num_operations: usize,
+    max_dim: usize,
+}
+
+impl Default for ComputationGenerator {
+    fn default() -> Self {
+        ComputationGenerator {
+            ops: FuzzOp::ALL.to_vec(),
+            hosts: vec![Role::from("alice"), Role::from("bob"), Role::from("carole")],
+            replicated: Some(ReplicatedPlacement::from(["alice", "bob", "carole"])),
+            num_operations: 20,
+            max_dim: 4,
+        }
+    }
+}
+
+impl ComputationGenerator {
+    /// Operations to choose from; must not be empty.
+    pub fn with_ops(self, ops: Vec<FuzzOp>) -> Self {
+        ComputationGenerator { ops, ..self }
+    }
+
+    /// Owners of the host placements to choose from; must not be empty.
+    pub fn with_hosts(self, hosts: Vec<Role>) -> Self {
+        ComputationGenerator { hosts, ..self }
+    }
+
+    /// Replicated placement to choose from, if any.
+    pub fn with_replicated(self, replicated: Option<ReplicatedPlacement>) -> Self {
+        ComputationGenerator { replicated, ..self }
+    }
+
+    /// Number of operations chosen from the op set, not counting the constants,
+    /// casts, and outputs added around them.
+    pub fn with_num_operations(self, num_operations: usize) -> Self {
+        ComputationGenerator {
+            num_operations,
+            ..self
+        }
+    }
+
+    /// Upper bound on the length of each dimension of generated tensors.
+    pub fn with_max_dim(self, max_dim: usize) -> Self {
+        ComputationGenerator { max_dim, ..self }
+    }
+
+    /// Computation determined by `seed`.
+    pub fn generate_from_seed(&self, seed: u64) -> Computation {
+        self.generate(&mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Computation {
+        assert!(!self.ops.is_empty(), "generator needs at least one op");
+        assert!(!self.hosts.is_empty(), "generator needs at least one host");
+
+        let mut builder = Builder {
+            generator: self,
+            operations: Vec::new(),
+            nodes: Vec::new(),
+        };
+        for _ in 0..self.num_operations {
+            let op = *self.ops.choose(rng).unwrap();
+            builder.add_op(rng, op);
+        }
+        builder.add_outputs(rng);
+        Computation {
+            operations: builder.operations,
+        }
+    }
+}
+
+/// Shape required of an operand.
+enum ShapeConstraint {
+    Any,
+    Matrix,
+    Exactly(Vec<usize>),
+    Rows(usize),
+}
+
+impl ShapeConstraint {
+    fn allows(&self, shape: &[usize]) -> bool {
+        match self {
+            ShapeConstraint::Any => true,
+            ShapeConstraint::Matrix => shape.len() == 2,
+            ShapeConstraint::Exactly(expected) => shape == expected.as_slice(),
+            ShapeConstraint::Rows(rows) => shape.len() == 2 && shape[0] == *rows,
+        }
+    }
+}
+
+/// Value computed by a generated operation.
+#[derive(Clone)]
+struct Node {
+    name: String,
+    shape: Vec<usize>,
+    dtype: TensorDType,
+    placement: Placement,
+}
+
+struct Builder<'g> {
+    generator: &'g ComputationGenerator,
+    operations: Vec<Operation>,
+    nodes: Vec<Node>,
+}
+
+impl<'g> Builder<'g> {
+    fn push(
+        &mut self,
+        kind: &str,
+        operator: Operator,
+        inputs: Vec<String>,
+        placement: Placement,
+        shape: Vec<usize>,
+        dtype: TensorDType,
+    ) -> Node {
+        let name = format!("{}_{}", kind, self.operations.len());
+        self.operations.push(Operation {
+            name: name.clone(),
+            kind: operator,
+            inputs,
+            placement: placement.clone(),
+        });
+        let node = Node {
+            name,
+            shape,
+            dtype,
+            placement,
+        };
+        self.nodes.push(node.clone());
+        node
+    }
+
+    fn random_host<R: Rng>(&self, rng: &mut R) -> HostPlacement {
+        HostPlacement {
+            owner: self.generator.hosts.choose(rng).unwrap().clone(),
+        }
+    }
+
+    fn random_dim<R: Rng>(&self, rng: &mut R) -> usize {
+        rng.gen_range(1..=self.generator.max_dim.max(1))
+    }
+
+    fn random_shape<R: Rng>(&self, rng: &mut R, constraint: &ShapeConstraint) -> Vec<usize> {
+        match constraint {
+            ShapeConstraint::Any if rng.gen_bool(0.25) => vec![self.random_dim(rng)],
+            ShapeConstraint::Any | ShapeConstraint::Matrix => {
+                vec![self.random_dim(rng), self.random_dim(rng)]
+            }
+            ShapeConstraint::Exactly(shape) => shape.clone(),
+            ShapeConstraint::Rows(rows) => vec![*rows, self.random_dim(rng)],
+        }
+    }
+
+    fn constant<R: Rng>(&mut self, rng: &mut R, shape: Vec<usize>) -> Node {
+        let plc = self.random_host(rng);
+        let len: usize = shape.iter().product();
+        let values: Vec<f64> = (0..len)
+            .map(|_| rng.gen_range(-200..=200) as f64 / 100.0)
+            .collect();
+        let value: HostFloat64Tensor =
+            plc.from_raw(ArrayD::from_shape_vec(IxDyn(&shape), values).unwrap());
+        let ty = Ty::Tensor(TensorDType::Float64);
+        let op = ConstantOp {
+            sig: Signature::nullary(ty),
+            value: value.into(),
+        };
+        self.push(
+            "constant",
+            op.into(),
+            vec![],
+            plc.into(),
+            shape,
+            TensorDType::Float64,
+        )
+    }
+
+    fn cast(&mut self, node: Node, dtype: TensorDType, plc: HostPlacement) -> Node {
+        let op = CastOp {
+            sig: Signature::unary(Ty::Tensor(node.dtype), Ty::Tensor(dtype)),
+        };
+        self.push(
+            "cast",
+            op.into(),
+            vec![node.name],
+            plc.into(),
+            node.shape,
+            dtype,
+        )
+    }
+
+    /// Operand satisfying `constraint` to be used on `placement`, reusing an
+    /// existing value where possible and casting it as needed.
+    fn operand<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        constraint: ShapeConstraint,
+        placement: &Placement,
+    ) -> Node {
+        let candidates: Vec<&Node> = self
+            .nodes
+            .iter()
+            .filter(|node| constraint.allows(&node.shape))
+            .collect();
+        let node = match candidates.choose(rng) {
+            Some(node) if rng.gen_bool(0.75) => (*node).clone(),
+            _ => {
+                let shape = self.random_shape(rng, &constraint);
+                self.constant(rng, shape)
+            }
+        };
+        match (placement, node.dtype) {
+            (Placement::Replicated(_), TensorDType::Float64) => {
+                // floats only live on hosts, so cast where the value is
+                let plc = match &node.placement {
+                    Placement::Host(plc) => plc.clone(),
+                    _ => self.random_host(rng),
+                };
+                self.cast(node, FIXED, plc)
+            }
+            (Placement::Host(plc), dtype) if dtype != TensorDType::Float64 => {
+                self.cast(node, TensorDType::Float64, plc.clone())
+            }
+            _ => node,
+        }
+    }
+
+    fn add_op<R: Rng>(&mut self, rng: &mut R, op: FuzzOp) {
+        let placement: Placement = match &self.generator.replicated {
+            Some(rep) if rng.gen_bool(0.5) => rep.clone().into(),
+            _ => self.random_host(rng).into(),
+        };
+        let dtype = match placement {
+            Placement::Replicated(_) => FIXED,
+            _ => TensorDType::Float64,
+        };
+        let ty = Ty::Tensor(dtype);
+        let unary = Signature::unary(ty, ty);
+        let binary = Signature::binary(ty, ty, ty);
+
+        let (kind, operator, inputs, shape): (&str, Operator, Vec<Node>, Vec<usize>) = match op {
+            FuzzOp::Add | FuzzOp::Sub | FuzzOp::Mul => {
+                let x = self.operand(rng, ShapeConstraint::Any, &placement);
+                let y = self.operand(rng, ShapeConstraint::Exactly(x.shape.clone()), &placement);
+                let shape = x.shape.clone();
+                let (kind, operator) = match op {
+                    FuzzOp::Add => ("add", AddOp { sig: binary }.into()),
+                    FuzzOp::Sub => ("sub", SubOp { sig: binary }.into()),
+                    _ => ("mul", MulOp { sig: binary }.into()),
+                };
+                (kind, operator, vec![x, y], shape)
+            }
+            FuzzOp::Dot => {
+                let x = self.operand(rng, ShapeConstraint::Matrix, &placement);
+                let y = self.operand(rng, ShapeConstraint::Rows(x.shape[1]), &placement);
+                let shape = vec![x.shape[0], y.shape[1]];
+                ("dot", DotOp { sig: binary }.into(), vec![x, y], shape)
+            }
+            FuzzOp::Transpose => {
+                let x = self.operand(rng, ShapeConstraint::Matrix, &placement);
+                let shape = vec![x.shape[1], x.shape[0]];
+                (
+                    "transpose",
+                    TransposeOp { sig: unary }.into(),
+                    vec![x],
+                    shape,
+                )
+            }
+            FuzzOp::Sum => {
+                let x = self.operand(rng, ShapeConstraint::Matrix, &placement);
+                let axis = rng.gen_range(0..2);
+                let shape = vec![x.shape[1 - axis]];
+                let operator = SumOp {
+                    sig: unary,
+                    axis: Some(axis),
+                };
+                ("sum", operator.into(), vec![x], shape)
+            }
+        };
+        let inputs = inputs.into_iter().map(|node| node.name).collect();
+        self.push(kind, operator, inputs, placement, shape, dtype);
+    }
+
+    /// Output every value that is not used by any other operation.
+    fn add_outputs<R: Rng>(&mut self, rng: &mut R) {
+        let used: HashSet<String> = self
+            .operations
+            .iter()
+            .flat_map(|op| op.inputs.iter().cloned())
+            .collect();
+        let unused: Vec<Node> = self
+            .nodes
+            .iter()
+            .filter(|node| !used.contains(&node.name))
+            .cloned()
+            .collect();
+        for node in unused {
+            let plc = self.random_host(rng);
+            let node = if node.dtype == TensorDType::Float64 {
+                node
+            } else {
+                self.cast(node, TensorDType::Float64, plc.clone())
+            };
+            let ty = Ty::Tensor(TensorDType::Float64);
+            let op = OutputOp {
+                sig: Signature::unary(ty, ty),
+                tag: format!("{}_output", node.name),
+            };
+            self.push(
+                "output",
+                op.into(),
+                vec![node.name],
+                plc.into(),
+                node.shape,
+                TensorDType::Float64,
+            );
+        }
+    }
+}
+
+/// Computation without the operations in `removed` nor any operation depending on them.
+fn without(computation: &Computation, removed: &HashSet<String>) -> Computation {
+    let mut removed = removed.clone();
+    loop {
+        let num_removed = removed.len();
+        for op in computation.operations.iter() {
+            if op.inputs.iter().any(|input| removed.contains(input)) {
+                removed.insert(op.name.clone());
+            }
+        }
+        if removed.len() == num_removed {
+            break;
+        }
+    }
+    Computation {
+        operations: computation
+            .operations
+            .iter()
+            .filter(|op| !removed.contains(&op.name))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Minimize `computation` while `fails` keeps returning true for it.
+///
+/// Operations are removed in chunks of decreasing size, together with the
+/// operations depending on them, such that the result is well-formed whenever
+/// the original is. The result is minimal in that removing any single operation
+/// makes `fails` return false.
+pub fn shrink<F>(computation: &Computation, mut fails: F) -> Computation
+where
+    F: FnMut(&Computation) -> bool,
+{
+    let mut current = computation.clone();
+    let mut chunk_size = std::cmp::max(current.operations.len() / 2, 1);
+    loop {
+        let mut progress = false;
+        let mut start = 0;
+        while start < current.operations.len() {
+            let end = std::cmp::min(start + chunk_size, current.operations.len());
+            let removed: HashSet<String> = current.operations[start..end]
+                .iter()
+                .map(|op| op.name.clone())
+                .collect();
+            let candidate = without(&current, &removed);
+            if fails(&candidate) {
+                current = candidate;
+                progress = true;
+            } else {
+                start = end;
+            }
+        }
+        if chunk_size == 1 && !progress {
+            return current;
+        }
+        if !progress {
+            chunk_size = std::cmp::max(chunk_size / 2, 1);
+        }
+    }
+}
+
+/// Failure found by `fuzz`.
+#[derive(Clone, Debug)]
+pub struct FuzzFailure {
+    /// Seed of the generated computation that failed.
+    pub seed: u64,
+    /// Error, or panic message, of the check on the original computation.
+    pub error: String,
+    /// Shrunk computation still failing the check.
+    pub computation: Computation,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::textual::ToTextual;
+        writeln!(f, "seed {} failed: {}", self.seed, self.error)?;
+        writeln!(f, "shrunk computation:")?;
+        write!(f, "{}", self.computation.to_textual())
+    }
+}
+
+fn run_check<F>(check: &mut F, computation: &Computation) -> Result<(), String>
+where
+    F: FnMut(&Computation) -> anyhow::Result<()>,
+{
+    match catch_unwind(AssertUnwindSafe(|| check(computation))) {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(panic) => Err(panic
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())),
+    }
+}
+
+/// Run `check` on the computations generated from `seeds`, returning the first
+/// failure after shrinking it.
+///
+/// A check fails by returning an error or by panicking.
+pub fn fuzz<F>(
+    generator: &ComputationGenerator,
+    seeds: Range<u64>,
+    mut check: F,
+) -> Result<(), FuzzFailure>
+where
+    F: FnMut(&Computation) -> anyhow::Result<()>,
+{
+    for seed in seeds {
+        let computation = generator.generate_from_seed(seed);
+        if let Err(error) = run_check(&mut check, &computation) {
+            let computation = shrink(&computation, |candidate| {
+                run_check(&mut check, candidate).is_err()
+            });
+            return Err(FuzzFailure {
+                seed,
+                error,
+                computation,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "compile")]
+    #[test]
+    fn test_generated_computations_compile() {
+        use crate::compilation::{compile, Pass};
+        use crate::textual::ToTextual;
+
+        let generator = ComputationGenerator::default();
+        let result = fuzz(&generator, 0..20, |computation| {
+            let source = computation.to_textual();
+            let parsed = Computation::from_textual(&source)?;
+            anyhow::ensure!(parsed.operations.len() == computation.operations.len());
+            compile::<Pass>(parsed, None)?;
+            Ok(())
+        });
+        if let Err(failure) = result {
+            panic!("{}", failure);
+        }
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let generator = ComputationGenerator::default()
+            .with_ops(vec![FuzzOp::Dot, FuzzOp::Sum])
+            .with_num_operations(5);
+        let computation = generator.generate_from_seed(7);
+        assert_eq!(computation, generator.generate_from_seed(7));
+        assert!(computation
+            .operations
+            .iter()
+            .any(|op| matches!(op.kind, Operator::Dot(_) | Operator::Sum(_))));
+    }
+
+    #[test]
+    fn test_shrink() {
+        let generator = ComputationGenerator::default().with_num_operations(30);
+        let is_dot = |op: &Operation| matches!(op.kind, Operator::Dot(_));
+        let seed = (0..)
+            .find(|seed| {
+                let computation = generator.generate_from_seed(*seed);
+                computation
+                    .operations
+                    .iter()
+                    .filter(|op| is_dot(op))
+                    .count()
+                    > 1
+            })
+            .unwrap();
+
+        let failure = fuzz(&generator, seed..seed + 1, |computation| {
+            anyhow::ensure!(!computation.operations.iter().any(is_dot), "found a dot");
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(failure.seed, seed);
+        assert_eq!(failure.error, "found a dot");
+
+        // only a single dot and the operations computing its operands remain
+        let ops = &failure.computation.operations;
+        assert_eq!(ops.iter().filter(|op| is_dot(op)).count(), 1);
+        let dot = ops.iter().find(|op| is_dot(op)).unwrap();
+        assert!(ops
+            .iter()
+            .all(|op| op.name == dot.name || depends_on(ops, dot, &op.name)));
+    }
+
+    fn depends_on(ops: &[Operation], op: &Operation, name: &str) -> bool {
+        op.inputs.iter().any(|input| {
+            input == name
+                || ops
+                    .iter()
+                    .find(|other| other.name == *input)
+                    .map(|other| depends_on(ops, other, name))
+                    .unwrap_or(false)
+        })
+    }
+}
@@ -1,6 +1,35 @@
+//! Helpers shared by the gRPC-based networking and choreography.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+use tonic_reflection::server::{ServerReflection, ServerReflectionServer};
+
+/// Prefix of endpoints (and identities) referring to unix domain sockets, as in `unix:/tmp/alice.sock`.
+pub const UNIX_SOCKET_PREFIX: &str = "unix:";
+
+const AUTHORIZATION_HEADER: &str = "authorization";
+
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Identity of the caller as established by `TokenAuth`.
+#[derive(Clone, Debug)]
+struct TokenIdentity(String);
+
+/// Extract the identity of the sender of a request.
+///
+/// The identity is taken from the client certificate if present, and otherwise
+/// from the bearer token validated by `TokenAuth`.
 pub(crate) fn extract_sender<T>(request: &tonic::Request<T>) -> Result<Option<String>, String> {
     match request.peer_certs() {
-        None => Ok(None),
+        None => Ok(request
+            .extensions()
+            .get::<TokenIdentity>()
+            .map(|identity| identity.0.clone())),
         Some(certs) => {
             if certs.len() != 1 {
                 return Err(format!(
@@ -28,3 +57,238 @@ pub(crate) fn extract_sender<T>(request: &tonic::Request<T>) -> Result<Option<St
         }
     }
 }
+
+/// Create an endpoint for `target`, which is either an address such as `localhost:50000`
+/// or a unix domain socket such as `unix:/tmp/alice.sock`.
+pub(crate) fn endpoint(target: &str) -> Result<Endpoint, String> {
+    let uri = if target.starts_with(UNIX_SOCKET_PREFIX) {
+        // the URI is ignored when connecting, except for TLS server name verification
+        "http://localhost".to_string()
+    } else {
+        format!("http://{}", target)
+    };
+    Endpoint::from_shared(uri).map_err(|e| format!("failed to parse endpoint {:?}: {}", target, e))
+}
+
+/// Name that the TLS certificate of the party with `identity` must be valid for.
+///
+/// Channels to parties resolved through discovery are verified against this
+/// name rather than against the endpoint they were resolved to, such that
+/// whoever controls discovery, for instance through DNS, cannot redirect values
+/// to another host with a valid certificate.
+pub(crate) fn tls_domain_name(identity: &str) -> Option<String> {
+    if identity.starts_with(UNIX_SOCKET_PREFIX) {
+        return None;
+    }
+    let host = match identity.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => identity,
+    };
+    Some(
+        host.trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+    )
+}
+
+/// Lazily connect `endpoint` created by [`endpoint`] for `target`.
+pub(crate) fn connect_lazy(endpoint: Endpoint, target: &str) -> Channel {
+    match target.strip_prefix(UNIX_SOCKET_PREFIX) {
+        #[cfg(unix)]
+        Some(path) => {
+            let path = path.to_string();
+            endpoint.connect_with_connector_lazy(tower::service_fn(
+                move |_: tonic::transport::Uri| tokio::net::UnixStream::connect(path.clone()),
+            ))
+        }
+        _ => endpoint.connect_lazy(),
+    }
+}
+
+/// Attach `token` (if any) as a bearer token to an outgoing request.
+pub(crate) fn attach_token<T>(
+    request: &mut Request<T>,
+    token: &Option<String>,
+) -> Result<(), String> {
+    if let Some(token) = token {
+        let value: MetadataValue<Ascii> = format!("{}{}", BEARER_PREFIX, token)
+            .parse()
+            .map_err(|_e| "token contains invalid characters".to_string())?;
+        request.metadata_mut().insert(AUTHORIZATION_HEADER, value);
+    }
+    Ok(())
+}
+
+/// Encoded descriptors of the services offered by workers.
+const FILE_DESCRIPTOR_SETS: &[&[u8]] = &[
+    include_bytes!(concat!(env!("OUT_DIR"), "/choreography_descriptor.bin")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/networking_descriptor.bin")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/relay_descriptor.bin")),
+];
+
+/// Service implementing gRPC reflection for the services offered by workers, such
+/// that generic clients like `grpcurl` can call them without compiled stubs.
+pub fn reflection_service(
+) -> Result<ServerReflectionServer<impl ServerReflection>, tonic_reflection::server::Error> {
+    FILE_DESCRIPTOR_SETS
+        .iter()
+        .fold(
+            tonic_reflection::server::Builder::configure(),
+            |builder, descriptors| builder.register_encoded_file_descriptor_set(descriptors),
+        )
+        .build()
+}
+
+/// Tokens accepted from each identity, typically loaded from a TOML file such as:
+///
+/// ```toml
+/// [identities]
+/// "localhost:50001" = ["secret-token-1"]
+/// "choreographer" = ["secret-token-2", "secret-token-3"]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TokenAuthConfig {
+    pub identities: HashMap<String, Vec<String>>,
+}
+
+impl TokenAuthConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let raw = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+}
+
+/// Interceptor authenticating incoming requests using bearer tokens.
+///
+/// When enabled, requests without a known token are rejected, and the identity
+/// associated with the token is used as the sender of the request wherever no
+/// client certificate is available.
+#[derive(Clone, Default)]
+pub struct TokenAuth {
+    tokens: Option<Arc<Vec<(String, String)>>>,
+}
+
+impl TokenAuth {
+    /// Accept all requests.
+    pub fn disabled() -> Self {
+        TokenAuth { tokens: None }
+    }
+
+    pub fn new(config: TokenAuthConfig) -> Self {
+        let tokens = config
+            .identities
+            .into_iter()
+            .flat_map(|(identity, tokens)| {
+                tokens
+                    .into_iter()
+                    .map(move |token| (token, identity.clone()))
+            })
+            .collect();
+        TokenAuth {
+            tokens: Some(Arc::new(tokens)),
+        }
+    }
+
+    fn lookup(&self, token: &str) -> Option<String> {
+        let tokens = self.tokens.as_ref()?;
+        // go through all tokens to avoid leaking which one matched through timing
+        let mut identity = None;
+        for (candidate, candidate_identity) in tokens.iter() {
+            if constant_time_eq(candidate.as_bytes(), token.as_bytes()) {
+                identity = Some(candidate_identity.clone());
+            }
+        }
+        identity
+    }
+}
+
+impl tonic::service::Interceptor for TokenAuth {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if self.tokens.is_none() {
+            return Ok(request);
+        }
+
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+            .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+
+        let identity = self
+            .lookup(token)
+            .ok_or_else(|| Status::unauthenticated("invalid bearer token"))?;
+
+        request.extensions_mut().insert(TokenIdentity(identity));
+        Ok(request)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::service::Interceptor;
+
+    fn auth() -> TokenAuth {
+        let config: TokenAuthConfig = toml::from_str(
+            r#"
+            [identities]
+            "alice" = ["token-a1", "token-a2"]
+            "bob" = ["token-b"]
+            "#,
+        )
+        .unwrap();
+        TokenAuth::new(config)
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let tcp = endpoint("localhost:50000").unwrap();
+        assert_eq!(tcp.uri().host(), Some("localhost"));
+        assert_eq!(tcp.uri().port_u16(), Some(50000));
+
+        let unix = endpoint("unix:/tmp/alice.sock").unwrap();
+        assert_eq!(unix.uri().host(), Some("localhost"));
+        assert_eq!(unix.uri().port_u16(), None);
+    }
+
+    #[test]
+    fn test_valid_token() {
+        let mut request = Request::new(());
+        attach_token(&mut request, &Some("token-a2".to_string())).unwrap();
+        let request = auth().call(request).unwrap();
+        assert_eq!(extract_sender(&request).unwrap(), Some("alice".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_token() {
+        let mut request = Request::new(());
+        attach_token(&mut request, &Some("token-c".to_string())).unwrap();
+        assert!(auth().call(request).is_err());
+    }
+
+    #[test]
+    fn test_missing_token() {
+        assert!(auth().call(Request::new(())).is_err());
+        let request = TokenAuth::disabled().call(Request::new(())).unwrap();
+        assert_eq!(extract_sender(&request).unwrap(), None);
+    }
+
+    #[test]
+    fn test_tls_domain_name() {
+        assert_eq!(
+            tls_domain_name("alice.example.com:50000").as_deref(),
+            Some("alice.example.com")
+        );
+        assert_eq!(tls_domain_name("alice").as_deref(), Some("alice"));
+        assert_eq!(tls_domain_name("[::1]:50000").as_deref(), Some("::1"));
+        assert_eq!(tls_domain_name("unix:/tmp/alice.sock"), None);
+    }
+}
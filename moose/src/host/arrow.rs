@@ -0,0 +1,390 @@
+//! Conversions between Arrow record batches and host tensors.
+//!
+//! Primitive arrays convert to host tensors of their native type using
+//! `HostPlacement::from_raw`, and one-dimensional host tensors convert to
+//! primitive arrays using `TryFrom`. The latter hands the entries of the tensor
+//! over to Arrow without copying them if the tensor is not shared and laid out
+//! contiguously, as all tensors computed by Moose kernels are:
+//!
+//! ```
+//! # use moose::prelude::*;
+//! # use arrow::array::Float64Array;
+//! # use std::convert::TryFrom;
+//! let plc = HostPlacement::from("alice");
+//! let x: HostFloat64Tensor = plc.from_raw(Float64Array::from(vec![1.0, 2.0]));
+//! let column = Float64Array::try_from(x).unwrap();
+//! ```
+//!
+//! Each column of a record batch converts to and from a one-dimensional tensor
+//! of the same dtype, named after the column, such that tables with columns of
+//! mixed dtypes can be passed to and from computations. Columns with missing
+//! values cannot be converted.
+
+use super::{FromRaw, HostPlacement, HostTensor};
+use crate::computation::Value;
+use crate::error::{Error, Result};
+use crate::types::HostFloat64Tensor;
+use arrow::array::PrimitiveArray;
+use arrow::array::{
+    Array, ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::buffer::ScalarBuffer;
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::RecordBatch;
+use ndarray::{ArcArray, Array1, Array2, ArrayView2, Ix1, Ix2, IxDyn};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Entries of host tensors that are also native types of Arrow primitive arrays.
+pub trait ArrowElement: Clone {
+    type ArrowType: ArrowPrimitiveType<Native = Self>;
+}
+
+macro_rules! arrow_element {
+    ($($t:ty => $arrow:ty),*) => {
+        $(
+        impl ArrowElement for $t {
+            type ArrowType = $arrow;
+        }
+        )*
+    };
+}
+
+arrow_element!(
+    f32 => Float32Type,
+    f64 => Float64Type,
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type
+);
+
+/// Missing values of the array are taken to be the default value of its native type.
+impl<T: ArrowPrimitiveType> FromRaw<PrimitiveArray<T>, HostTensor<T::Native>> for HostPlacement {
+    fn from_raw(&self, raw: PrimitiveArray<T>) -> HostTensor<T::Native> {
+        let values: Vec<T::Native> = if raw.null_count() > 0 {
+            raw.iter().map(Option::unwrap_or_default).collect()
+        } else {
+            raw.values().to_vec()
+        };
+        self.from_raw(Array1::from(values))
+    }
+}
+
+impl<T: ArrowElement> TryFrom<HostTensor<T>> for PrimitiveArray<T::ArrowType> {
+    type Error = Error;
+
+    fn try_from(x: HostTensor<T>) -> Result<PrimitiveArray<T::ArrowType>> {
+        x.view_as::<Ix1>()?;
+        // only copies the entries if the tensor is shared or not laid out contiguously
+        let array = x.0.into_owned();
+        let values = if array.is_standard_layout() {
+            let start = array.as_ptr() as usize;
+            let len = array.len();
+            let mut values = array.into_raw_vec();
+            // the tensor may be a slice of the entries it holds
+            let offset = (start - values.as_ptr() as usize) / std::mem::size_of::<T>().max(1);
+            values.truncate(offset + len);
+            values.drain(..offset);
+            values
+        } else {
+            array.iter().cloned().collect()
+        };
+        Ok(PrimitiveArray::new(ScalarBuffer::from(values), None))
+    }
+}
+
+impl<T: ArrowElement> TryFrom<&HostTensor<T>> for PrimitiveArray<T::ArrowType> {
+    type Error = Error;
+
+    fn try_from(x: &HostTensor<T>) -> Result<PrimitiveArray<T::ArrowType>> {
+        let values: Vec<T> = x.view_as::<Ix1>()?.iter().cloned().collect();
+        Ok(PrimitiveArray::new(ScalarBuffer::from(values), None))
+    }
+}
+
+/// One-dimensional tensor holding the entries of the column `name`.
+pub fn array_to_value(name: &str, column: &dyn Array, plc: &HostPlacement) -> Result<Value> {
+    if column.null_count() > 0 {
+        return Err(Error::InvalidArgument(format!(
+            "column '{}' has {} missing values",
+            name,
+            column.null_count()
+        )));
+    }
+
+    macro_rules! tensor {
+        ($array:ty) => {{
+            let values = column
+                .as_any()
+                .downcast_ref::<$array>()
+                .expect("column of another type than its dtype")
+                .values()
+                .to_vec();
+            Value::from(HostTensor(
+                Array1::from(values).into_dyn().into_shared(),
+                plc.clone(),
+            ))
+        }};
+    }
+
+    let value = match column.data_type() {
+        DataType::Float32 => tensor!(Float32Array),
+        DataType::Float64 => tensor!(Float64Array),
+        DataType::Int8 => tensor!(Int8Array),
+        DataType::Int16 => tensor!(Int16Array),
+        DataType::Int32 => tensor!(Int32Array),
+        DataType::Int64 => tensor!(Int64Array),
+        DataType::UInt8 => tensor!(UInt8Array),
+        DataType::UInt16 => tensor!(UInt16Array),
+        DataType::UInt32 => tensor!(UInt32Array),
+        DataType::UInt64 => tensor!(UInt64Array),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "column '{}' of dtype {} cannot be converted to a tensor",
+                name, other
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Column holding the entries of a one-dimensional tensor.
+pub fn value_to_array(value: &Value) -> Result<ArrayRef> {
+    macro_rules! column {
+        ($x:expr, $array:ty) => {{
+            if $x.0.ndim() != 1 {
+                return Err(Error::InvalidArgument(format!(
+                    "only one-dimensional tensors can be converted to a column, got shape {:?}",
+                    $x.0.shape()
+                )));
+            }
+            Arc::new(<$array>::from($x.0.iter().cloned().collect::<Vec<_>>())) as ArrayRef
+        }};
+    }
+
+    let column = match value {
+        Value::HostFloat32Tensor(x) => column!(x, Float32Array),
+        Value::HostFloat64Tensor(x) => column!(x, Float64Array),
+        Value::HostInt8Tensor(x) => column!(x, Int8Array),
+        Value::HostInt16Tensor(x) => column!(x, Int16Array),
+        Value::HostInt32Tensor(x) => column!(x, Int32Array),
+        Value::HostInt64Tensor(x) => column!(x, Int64Array),
+        Value::HostUint8Tensor(x) => column!(x, UInt8Array),
+        Value::HostUint16Tensor(x) => column!(x, UInt16Array),
+        Value::HostUint32Tensor(x) => column!(x, UInt32Array),
+        Value::HostUint64Tensor(x) => column!(x, UInt64Array),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "values of type {} cannot be converted to a column",
+                other.ty()
+            )))
+        }
+    };
+    Ok(column)
+}
+
+/// Tensor for each column of `batch`, keyed by column name.
+pub fn record_batch_to_tensors(
+    batch: &RecordBatch,
+    plc: &HostPlacement,
+) -> Result<HashMap<String, Value>> {
+    batch
+        .schema()
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| {
+            let value = array_to_value(field.name(), column.as_ref(), plc)?;
+            Ok((field.name().clone(), value))
+        })
+        .collect()
+}
+
+/// Record batch with a column for each of the named one-dimensional `tensors`,
+/// in the order given.
+pub fn tensors_to_record_batch(tensors: &[(String, Value)]) -> Result<RecordBatch> {
+    let columns = tensors
+        .iter()
+        .map(|(_, value)| value_to_array(value))
+        .collect::<Result<Vec<_>>>()?;
+    let fields: Vec<Field> = tensors
+        .iter()
+        .zip(columns.iter())
+        .map(|((name, _), column)| Field::new(name, column.data_type().clone(), false))
+        .collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| {
+        Error::InvalidArgument(format!("failed to convert tensors to record batch: {}", e))
+    })
+}
+
+/// Table with a column `col_<i>` for each column of a one- or two-dimensional
+/// floating-point tensor.
+pub fn matrix_to_record_batch(value: &Value) -> Result<RecordBatch> {
+    let (data_type, columns): (_, Vec<ArrayRef>) = match value {
+        Value::HostFloat64Tensor(t) => (
+            DataType::Float64,
+            matrix(&t.0)?
+                .columns()
+                .into_iter()
+                .map(|column| Arc::new(Float64Array::from(column.to_vec())) as ArrayRef)
+                .collect(),
+        ),
+        Value::HostFloat32Tensor(t) => (
+            DataType::Float32,
+            matrix(&t.0)?
+                .columns()
+                .into_iter()
+                .map(|column| Arc::new(Float32Array::from(column.to_vec())) as ArrayRef)
+                .collect(),
+        ),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "values of type {} cannot be converted to a table",
+                other.ty()
+            )))
+        }
+    };
+    let fields = (0..columns.len())
+        .map(|i| Field::new(format!("col_{}", i), data_type.clone(), false))
+        .collect::<Vec<_>>();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| {
+        Error::InvalidArgument(format!("failed to convert tensor to record batch: {}", e))
+    })
+}
+
+fn matrix<T>(x: &ArcArray<T, IxDyn>) -> Result<ArrayView2<'_, T>> {
+    let x = x.view();
+    let invalid = |e: ndarray::ShapeError| {
+        Error::InvalidArgument(format!("cannot convert tensor to a table: {}", e))
+    };
+    match x.ndim() {
+        1 => {
+            let len = x.len();
+            x.into_shape((len, 1)).map_err(invalid)
+        }
+        2 => x.into_dimensionality::<Ix2>().map_err(invalid),
+        ndim => Err(Error::InvalidArgument(format!(
+            "cannot convert tensor with {} dimensions to a table",
+            ndim
+        ))),
+    }
+}
+
+/// Two-dimensional `Float64` tensor with a row per record of `batches` and a
+/// column per selected column, or per column of `schema` if none are selected.
+pub fn record_batches_to_matrix(
+    schema: &Schema,
+    batches: &[RecordBatch],
+    columns: &[String],
+    plc: &HostPlacement,
+) -> Result<Value> {
+    let indices = if columns.is_empty() {
+        (0..schema.fields().len()).collect()
+    } else {
+        columns
+            .iter()
+            .map(|column| {
+                schema
+                    .index_of(column)
+                    .map_err(|_| Error::InvalidArgument(format!("no column '{}' in table", column)))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let nrows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    let mut matrix = Array2::<f64>::zeros((nrows, indices.len()));
+    for (j, index) in indices.iter().enumerate() {
+        let mut row = 0;
+        for batch in batches.iter() {
+            let column =
+                arrow::compute::cast(batch.column(*index), &DataType::Float64).map_err(|e| {
+                    Error::InvalidArgument(format!(
+                        "could not convert column '{}' to f64: {}",
+                        schema.field(*index).name(),
+                        e
+                    ))
+                })?;
+            if column.null_count() > 0 {
+                return Err(Error::InvalidArgument(format!(
+                    "column '{}' has missing values",
+                    schema.field(*index).name(),
+                )));
+            }
+            let column = column
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .expect("cast to f64 returned another type");
+            for x in column.values().iter() {
+                matrix[[row, j]] = *x;
+                row += 1;
+            }
+        }
+    }
+    let tensor: HostFloat64Tensor = HostTensor(matrix.into_dyn().into_shared(), plc.clone());
+    Ok(Value::from(tensor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HostFloat64Tensor, HostInt32Tensor};
+    use ndarray::array;
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let plc = HostPlacement::from("host");
+        let age: HostInt32Tensor = HostTensor(array![31, 47].into_dyn().into_shared(), plc.clone());
+        let income: HostFloat64Tensor =
+            HostTensor(array![2.5, 4.0].into_dyn().into_shared(), plc.clone());
+        let tensors = vec![
+            ("age".to_string(), Value::from(age)),
+            ("income".to_string(), Value::from(income)),
+        ];
+
+        let batch = tensors_to_record_batch(&tensors).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::Int32);
+        assert_eq!(batch.schema().field(1).data_type(), &DataType::Float64);
+
+        let back = record_batch_to_tensors(&batch, &plc).unwrap();
+        assert_eq!(back.len(), 2);
+        assert_eq!(back["age"], tensors[0].1);
+        assert_eq!(back["income"], tensors[1].1);
+
+        let matrix: HostFloat64Tensor =
+            HostTensor(array![[1.0], [2.0]].into_dyn().into_shared(), plc);
+        let invalid = vec![("x".to_string(), Value::from(matrix))];
+        assert!(tensors_to_record_batch(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_primitive_array_round_trip() {
+        let plc = HostPlacement::from("host");
+        let x: HostTensor<i64> = plc.from_raw(Int64Array::from(vec![3, -1, 4]));
+        let expected: HostTensor<i64> = plc.from_raw(array![3, -1, 4]);
+        assert_eq!(x, expected);
+
+        let column = Int64Array::try_from(&x).unwrap();
+        assert_eq!(column.values().as_ref(), &[3, -1, 4]);
+        let column = Int64Array::try_from(x).unwrap();
+        assert_eq!(column.values().as_ref(), &[3, -1, 4]);
+
+        // missing values become zeros
+        let y: HostTensor<f32> = plc.from_raw(Float32Array::from(vec![Some(1.5), None]));
+        let expected: HostTensor<f32> = plc.from_raw(array![1.5, 0.0]);
+        assert_eq!(y, expected);
+
+        let matrix: HostTensor<f32> = plc.from_raw(array![[1.0f32], [2.0]]);
+        assert!(Float32Array::try_from(matrix).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Hash-based commitments to host values.
+//!
+//! A commitment is the hex-encoded BLAKE3 digest of a nonce, the type, and the
+//! plaintext of a value, leaving out its placement such that the value can be
+//! opened on another host after being sent there. The type is included since
+//! values of different types may share their plaintext, such as ring and integer
+//! tensors holding the same bits. Commitments are hiding as long as the
+//! nonce is a fresh key from `PrfKeyGen` that is only revealed when opening,
+//! and binding since the digest is collision resistant. This lets protocols
+//! commit to values first and reveal them later, as needed for instance for
+//! fair output delivery or coin tossing.
+
+use super::*;
+use crate::computation::{CommitOp, OpenCommitmentOp, VerifyCommitmentOp};
+use crate::error::{Error, Result};
+use crate::execution::RuntimeSession;
+use crate::kernels::PlacementPlace;
+
+const COMMITMENT_CONTEXT: &str = "moose host commitment v2";
+
+/// Encoding of the plaintext of a value that commitments are computed over.
+pub(crate) trait CommittedBytes {
+    fn committed_bytes(&self) -> Result<Vec<u8>>;
+}
+
+fn encode<T: Serialize>(x: &T) -> Result<Vec<u8>> {
+    bincode::serialize(x).map_err(|e| Error::KernelError(format!("failed to encode value: {}", e)))
+}
+
+impl<T: Serialize> CommittedBytes for HostTensor<T> {
+    fn committed_bytes(&self) -> Result<Vec<u8>> {
+        encode(&self.0)
+    }
+}
+
+impl<T: Serialize> CommittedBytes for HostRingTensor<T> {
+    fn committed_bytes(&self) -> Result<Vec<u8>> {
+        encode(&self.0)
+    }
+}
+
+impl CommittedBytes for HostBitTensor {
+    fn committed_bytes(&self) -> Result<Vec<u8>> {
+        encode(&self.0)
+    }
+}
+
+impl CommittedBytes for HostString {
+    fn committed_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.0.as_bytes().to_vec())
+    }
+}
+
+fn commitment<T: CommittedBytes>(nonce: &HostPrfKey, ty: Ty, x: &T) -> Result<String> {
+    let mut hasher = blake3::Hasher::new_derive_key(COMMITMENT_CONTEXT);
+    hasher.update(nonce.0.as_bytes());
+    hasher.update(&encode(&ty)?);
+    hasher.update(&x.committed_bytes()?);
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+impl CommitOp {
+    pub(crate) fn host_kernel<S: RuntimeSession, T: CommittedBytes + KnownType<S>>(
+        _sess: &S,
+        plc: &HostPlacement,
+        nonce: HostPrfKey,
+        x: T,
+    ) -> Result<HostString> {
+        Ok(HostString(commitment(&nonce, T::TY, &x)?, plc.clone()))
+    }
+}
+
+impl OpenCommitmentOp {
+    pub(crate) fn host_kernel<S: RuntimeSession, T: CommittedBytes + KnownType<S>>(
+        sess: &S,
+        plc: &HostPlacement,
+        commitment: HostString,
+        nonce: HostPrfKey,
+        x: T,
+    ) -> Result<T>
+    where
+        HostPlacement: PlacementPlace<S, T>,
+    {
+        if self::commitment(&nonce, T::TY, &x)? != commitment.0 {
+            return Err(Error::KernelError(
+                "opened value does not match its commitment".to_string(),
+            ));
+        }
+        Ok(plc.place(sess, x))
+    }
+}
+
+impl VerifyCommitmentOp {
+    pub(crate) fn host_kernel<S: RuntimeSession, T: CommittedBytes + KnownType<S>>(
+        _sess: &S,
+        plc: &HostPlacement,
+        commitment: HostString,
+        nonce: HostPrfKey,
+        x: T,
+    ) -> Result<HostBitTensor> {
+        let valid = self::commitment(&nonce, T::TY, &x)? == commitment.0;
+        Ok(plc.from_raw(ndarray::arr0(valid as u8)))
+    }
+}
+
+#[cfg(feature = "sync_execute")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_commit_then_open() {
+        let sess = SyncSession::default();
+        let alice = HostPlacement::from("alice");
+        let bob = HostPlacement::from("bob");
+
+        let nonce: HostPrfKey = alice.gen_key(&sess);
+        let x: HostFloat64Tensor = alice.from_raw(array![1.0, 2.0]);
+        let c: HostString = alice.commit(&sess, &nonce, &x);
+        assert_eq!(c.0.len(), 64);
+        assert_eq!(c.1, alice);
+
+        // commitments do not depend on where values are placed
+        let opened: HostFloat64Tensor = bob.open_commitment(&sess, &c, &nonce, &x);
+        assert_eq!(opened.0, x.0);
+        assert_eq!(opened.1, bob);
+        let valid: HostBitTensor = bob.verify_commitment(&sess, &c, &nonce, &x);
+        assert_eq!(valid, bob.from_raw(ndarray::arr0(1u8)));
+
+        let other: HostFloat64Tensor = alice.from_raw(array![1.0, 3.0]);
+        let valid: HostBitTensor = bob.verify_commitment(&sess, &c, &nonce, &other);
+        assert_eq!(valid, bob.from_raw(ndarray::arr0(0u8)));
+        let other_nonce: HostPrfKey = alice.gen_key(&sess);
+        assert!(commitment(&other_nonce, Ty::HostFloat64Tensor, &x).unwrap() != c.0);
+    }
+
+    #[test]
+    fn test_commitment_binds_type() {
+        let sess = SyncSession::default();
+        let alice = HostPlacement::from("alice");
+        let bob = HostPlacement::from("bob");
+
+        let nonce: HostPrfKey = alice.gen_key(&sess);
+        let x: HostRing64Tensor = alice.from_raw(array![1u64, 2u64]);
+        let c: HostString = alice.commit(&sess, &nonce, &x);
+
+        // same bits, but a different type
+        let y: HostUint64Tensor = alice.from_raw(array![1u64, 2u64]);
+        assert_eq!(x.committed_bytes().unwrap(), y.committed_bytes().unwrap());
+        let opened =
+            OpenCommitmentOp::host_kernel(&sess, &bob, c.clone(), nonce.clone(), y.clone());
+        assert!(opened.is_err());
+        let valid: HostBitTensor = bob.verify_commitment(&sess, &c, &nonce, &y);
+        assert_eq!(valid, bob.from_raw(ndarray::arr0(0u8)));
+    }
+}
@@ -0,0 +1,291 @@
+//! JSON representation of host values, for debugging dumps and golden files.
+//!
+//! Tensors are represented as objects giving their `dtype`, `placement`, and
+//! `shape`, along with their entries as nested arrays in `data`:
+//!
+//! ```json
+//! {"dtype": "float64", "placement": "alice", "shape": [2, 2], "data": [[1.0, 2.0], [3.0, 4.0]]}
+//! ```
+//!
+//! Non-finite floats are given as the strings `"NaN"`, `"inf"`, and `"-inf"`, and
+//! entries of 128 bit rings as decimal strings since JSON numbers cannot hold them.
+
+use super::{
+    BitArrayRepr, HostBitTensor, HostPlacement, HostRingTensor, HostShape, HostTensor, RawShape,
+};
+use crate::computation::Value;
+use crate::error::{Error, Result};
+use ndarray::{ArrayD, IxDyn};
+use serde_json::{json, Value as Json};
+use std::convert::TryFrom;
+use std::num::Wrapping;
+
+/// Entries in row-major order nested into arrays according to `shape`.
+fn nested<I: Iterator<Item = Json>>(shape: &[usize], entries: &mut I) -> Json {
+    match shape.split_first() {
+        None => entries.next().unwrap_or(Json::Null),
+        Some((len, rest)) => Json::Array((0..*len).map(|_| nested(rest, entries)).collect()),
+    }
+}
+
+/// Entries of `data` in row-major order, checking that they are nested according to `shape`.
+fn flatten<'a>(data: &'a Json, shape: &[usize], entries: &mut Vec<&'a Json>) -> Result<()> {
+    match (shape.split_first(), data) {
+        (None, Json::Array(_)) => Err(invalid("data is nested deeper than its shape")),
+        (None, entry) => {
+            entries.push(entry);
+            Ok(())
+        }
+        (Some((len, rest)), Json::Array(items)) if items.len() == *len => {
+            for item in items.iter() {
+                flatten(item, rest, entries)?;
+            }
+            Ok(())
+        }
+        (Some(_), _) => Err(invalid("data does not match its shape")),
+    }
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidArgument(format!("invalid JSON value: {}", reason))
+}
+
+fn float_to_json(x: f64) -> Json {
+    if x.is_nan() {
+        json!("NaN")
+    } else if x.is_infinite() {
+        json!(if x > 0.0 { "inf" } else { "-inf" })
+    } else {
+        json!(x)
+    }
+}
+
+fn float_from_json(entry: &Json) -> Option<f64> {
+    match entry {
+        Json::String(s) if s == "NaN" => Some(f64::NAN),
+        Json::String(s) if s == "inf" => Some(f64::INFINITY),
+        Json::String(s) if s == "-inf" => Some(f64::NEG_INFINITY),
+        entry => entry.as_f64(),
+    }
+}
+
+fn int_from_json<T: TryFrom<i64>>(entry: &Json) -> Option<T> {
+    entry.as_i64().and_then(|x| T::try_from(x).ok())
+}
+
+fn uint_from_json<T: TryFrom<u64>>(entry: &Json) -> Option<T> {
+    entry.as_u64().and_then(|x| T::try_from(x).ok())
+}
+
+fn array<T>(
+    dtype: &str,
+    shape: &[usize],
+    entries: &[&Json],
+    parse: impl Fn(&Json) -> Option<T>,
+) -> Result<ArrayD<T>> {
+    let values = entries
+        .iter()
+        .map(|entry| {
+            parse(entry).ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "invalid JSON value: entry {} is not a valid {}",
+                    entry, dtype
+                ))
+            })
+        })
+        .collect::<Result<Vec<T>>>()?;
+    ArrayD::from_shape_vec(IxDyn(shape), values).map_err(|e| invalid(&e.to_string()))
+}
+
+impl Value {
+    /// JSON representation of host tensors and shapes.
+    pub fn to_json(&self) -> Result<String> {
+        macro_rules! tensor {
+            ($dtype:expr, $x:expr, $entry:expr) => {
+                (
+                    $dtype,
+                    &$x.1,
+                    $x.0.shape().to_vec(),
+                    nested($x.0.shape(), &mut $x.0.iter().map($entry)),
+                )
+            };
+        }
+
+        let (dtype, placement, shape, data): (_, &HostPlacement, Vec<usize>, Json) = match self {
+            Value::HostFloat32Tensor(x) => tensor!("float32", x, |x| float_to_json(*x as f64)),
+            Value::HostFloat64Tensor(x) => tensor!("float64", x, |x| float_to_json(*x)),
+            Value::HostInt8Tensor(x) => tensor!("int8", x, |x| json!(x)),
+            Value::HostInt16Tensor(x) => tensor!("int16", x, |x| json!(x)),
+            Value::HostInt32Tensor(x) => tensor!("int32", x, |x| json!(x)),
+            Value::HostInt64Tensor(x) => tensor!("int64", x, |x| json!(x)),
+            Value::HostUint8Tensor(x) => tensor!("uint8", x, |x| json!(x)),
+            Value::HostUint16Tensor(x) => tensor!("uint16", x, |x| json!(x)),
+            Value::HostUint32Tensor(x) => tensor!("uint32", x, |x| json!(x)),
+            Value::HostUint64Tensor(x) => tensor!("uint64", x, |x| json!(x)),
+            Value::HostRing64Tensor(x) => tensor!("ring64", x, |x| json!(x.0)),
+            Value::HostRing128Tensor(x) => tensor!("ring128", x, |x| json!(x.0.to_string())),
+            Value::HostBitTensor(x) => {
+                let bits = x.0.into_array::<u8>().map_err(|e| {
+                    Error::SerializationError(format!("failed to read bit tensor: {}", e))
+                })?;
+                let data = nested(bits.shape(), &mut bits.iter().map(|bit| json!(bit)));
+                ("bit", &x.1, bits.shape().to_vec(), data)
+            }
+            Value::HostShape(x) => {
+                let dims = &(x.0).0;
+                ("shape", &x.1, vec![dims.len()], json!(dims))
+            }
+            other => {
+                return Err(Error::InvalidArgument(format!(
+                    "values of type {} cannot be converted to JSON",
+                    other.ty()
+                )))
+            }
+        };
+        let json = json!({
+            "dtype": dtype,
+            "placement": placement.owner.0,
+            "shape": shape,
+            "data": data,
+        });
+        serde_json::to_string(&json).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    /// Parse the JSON representation of a host tensor or shape, as given by [`Value::to_json`].
+    pub fn from_json(json: &str) -> Result<Value> {
+        let json: Json = serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+        let dtype = json
+            .get("dtype")
+            .and_then(Json::as_str)
+            .ok_or_else(|| invalid("missing dtype"))?;
+        let plc = json
+            .get("placement")
+            .and_then(Json::as_str)
+            .map(HostPlacement::from)
+            .ok_or_else(|| invalid("missing placement"))?;
+        let shape = json
+            .get("shape")
+            .and_then(Json::as_array)
+            .and_then(|dims| {
+                dims.iter()
+                    .map(|dim| dim.as_u64().map(|dim| dim as usize))
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .ok_or_else(|| invalid("missing shape"))?;
+        let data = json.get("data").ok_or_else(|| invalid("missing data"))?;
+        let mut entries = Vec::new();
+        flatten(data, &shape, &mut entries)?;
+
+        macro_rules! tensor {
+            ($parse:expr) => {
+                Value::from(HostTensor(
+                    array(dtype, &shape, &entries, $parse)?.into_shared(),
+                    plc,
+                ))
+            };
+        }
+
+        let value = match dtype {
+            "float32" => tensor!(|x| float_from_json(x).map(|x| x as f32)),
+            "float64" => tensor!(float_from_json),
+            "int8" => tensor!(int_from_json::<i8>),
+            "int16" => tensor!(int_from_json::<i16>),
+            "int32" => tensor!(int_from_json::<i32>),
+            "int64" => tensor!(int_from_json::<i64>),
+            "uint8" => tensor!(uint_from_json::<u8>),
+            "uint16" => tensor!(uint_from_json::<u16>),
+            "uint32" => tensor!(uint_from_json::<u32>),
+            "uint64" => tensor!(uint_from_json::<u64>),
+            "ring64" => {
+                let x = array(dtype, &shape, &entries, |x| x.as_u64().map(Wrapping))?;
+                Value::from(HostRingTensor(x.into_shared(), plc))
+            }
+            "ring128" => {
+                let x = array(dtype, &shape, &entries, |x| {
+                    x.as_str()
+                        .and_then(|x| x.parse::<u128>().ok())
+                        .or_else(|| x.as_u64().map(u128::from))
+                        .map(Wrapping)
+                })?;
+                Value::from(HostRingTensor(x.into_shared(), plc))
+            }
+            "bit" => {
+                let bits = array(dtype, &shape, &entries, |x| match x {
+                    Json::Bool(b) => Some(*b as u8),
+                    x => x.as_u64().filter(|bit| *bit <= 1).map(|bit| bit as u8),
+                })?;
+                let bits = BitArrayRepr::from_vec(bits.into_raw_vec(), &RawShape(shape.clone()));
+                Value::from(HostBitTensor(bits, plc))
+            }
+            "shape" => {
+                let dims = array(dtype, &shape, &entries, |x| x.as_u64().map(|x| x as usize))?;
+                if dims.ndim() != 1 {
+                    return Err(invalid("shapes must be given as a list of dimensions"));
+                }
+                Value::from(HostShape(RawShape(dims.into_raw_vec()), plc))
+            }
+            other => {
+                return Err(Error::InvalidArgument(format!(
+                    "invalid JSON value: unknown dtype '{}'",
+                    other
+                )))
+            }
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HostInt32Tensor;
+    use ndarray::array;
+
+    #[test]
+    fn test_json_round_trip() {
+        let plc = HostPlacement::from("alice");
+        let x = HostTensor::<f64>(
+            array![[1.5, -2.0], [f64::INFINITY, 0.25]]
+                .into_dyn()
+                .into_shared(),
+            plc.clone(),
+        );
+        let x = Value::from(x);
+        let json = x.to_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Json>(&json).unwrap(),
+            json!({
+                "dtype": "float64",
+                "placement": "alice",
+                "shape": [2, 2],
+                "data": [[1.5, -2.0], ["inf", 0.25]],
+            })
+        );
+        assert_eq!(Value::from_json(&json).unwrap(), x);
+
+        let ring = HostRingTensor::<u128>(
+            array![Wrapping(u128::MAX), Wrapping(7)]
+                .into_dyn()
+                .into_shared(),
+            plc.clone(),
+        );
+        let ring = Value::from(ring);
+        assert_eq!(Value::from_json(&ring.to_json().unwrap()).unwrap(), ring);
+
+        let shape = Value::from(HostShape(RawShape(vec![2, 3]), plc));
+        assert_eq!(Value::from_json(&shape.to_json().unwrap()).unwrap(), shape);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = r#"{"dtype": "int32", "placement": "bob", "shape": [3], "data": [1, -2, 3]}"#;
+        let plc = HostPlacement::from("bob");
+        let expected: HostInt32Tensor = HostTensor(array![1, -2, 3].into_dyn().into_shared(), plc);
+        assert_eq!(Value::from_json(json).unwrap(), Value::from(expected));
+
+        let ragged = r#"{"dtype": "float64", "placement": "bob", "shape": [2, 2], "data": [[1.0, 2.0], [3.0]]}"#;
+        assert!(Value::from_json(ragged).is_err());
+        let overflow = r#"{"dtype": "int8", "placement": "bob", "shape": [1], "data": [300]}"#;
+        assert!(Value::from_json(overflow).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Placement for plaintext operations by a single role.
 
 use crate::computation::*;
-use crate::error::Result;
+use crate::error::{Error, Result};
 #[cfg(feature = "compile")]
 use crate::execution::symbolic::Symbolic;
 use crate::execution::Session;
@@ -19,13 +19,27 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::num::Wrapping;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
 mod bitarray;
+mod commitment;
 mod fixedpoint;
+mod json;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
 mod ops;
+#[cfg(feature = "polars")]
+pub mod polars;
 mod prim;
+#[cfg(feature = "arrow")]
+mod table;
+#[cfg(feature = "nalgebra")]
+pub use self::nalgebra::HostMatrixView;
 pub use bitarray::*;
 pub use fixedpoint::Convert;
 pub use prim::*;
+#[cfg(feature = "arrow")]
+pub use table::HostTable;
 
 pub type ArcArrayD<A> = ArcArray<A, IxDyn>;
 
@@ -80,14 +94,18 @@ impl<S: Session> PlacementPlace<S, HostString> for HostPlacement {
 pub struct RawShape(pub Vec<usize>);
 
 impl RawShape {
-    fn extend_singletons(self, mut axis: Vec<usize>) -> Self {
+    fn extend_singletons(self, mut axis: Vec<usize>) -> Result<Self> {
         let ax = axis.pop();
         match ax {
+            Some(ax) if ax > self.0.len() => Err(Error::KernelError(format!(
+                "ExpandDims: axis {} is out of bounds for shape {:?}",
+                ax, self.0
+            ))),
             Some(ax) => {
                 let (left, right) = self.0.split_at(ax);
                 RawShape::extend_singletons(RawShape([left, right].join(&1usize)), axis)
             }
-            None => self,
+            None => Ok(self),
         }
     }
 
@@ -96,23 +114,38 @@ impl RawShape {
         RawShape(slc.to_vec())
     }
 
-    fn squeeze(mut self, axis: Option<usize>) -> Self {
+    fn squeeze(mut self, axis: Option<usize>) -> Result<Self> {
         match axis {
+            Some(axis) if axis >= self.0.len() => Err(Error::KernelError(format!(
+                "Squeeze: axis {} is out of bounds for shape {:?}",
+                axis, self.0
+            ))),
+            Some(axis) if self.0[axis] != 1 => Err(Error::KernelError(format!(
+                "Squeeze: cannot squeeze out axis {} of shape {:?} since its size is not one",
+                axis, self.0
+            ))),
             Some(axis) => {
-                let removed_axis = self.0.remove(axis);
-                match removed_axis {
-                    1 => self,
-                    _ => panic!(
-                        "The axis selected has a value of {:?}. Cannot select an axis to squeeze out
-                        which has size not equal to one", removed_axis
-                    ),
-                }
+                self.0.remove(axis);
+                Ok(self)
             }
-            None => RawShape(self.0.into_iter().filter(|x| *x != 1).collect::<Vec<_>>()),
+            None => Ok(RawShape(
+                self.0.into_iter().filter(|x| *x != 1).collect::<Vec<_>>(),
+            )),
         }
     }
 }
 
+pub(crate) fn reshape_error(
+    shape: &[usize],
+    newshape: &RawShape,
+    reason: impl std::fmt::Display,
+) -> Error {
+    Error::KernelError(format!(
+        "Reshape: cannot reshape tensor of shape {:?} into {:?}: {}",
+        shape, newshape.0, reason
+    ))
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 pub struct HostShape(pub RawShape, pub HostPlacement);
 
@@ -152,6 +185,38 @@ pub struct SliceInfoElem {
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
 pub struct SliceInfo(pub Vec<SliceInfoElem>);
 
+impl SliceInfo {
+    /// Check that the slice is within `shape`, since ndarray panics otherwise.
+    pub(crate) fn check(&self, shape: &[usize]) -> Result<()> {
+        let error = |reason: String| {
+            Error::KernelError(format!(
+                "Slice: cannot slice tensor of shape {:?} with {:?}: {}",
+                shape, self.0, reason
+            ))
+        };
+        if self.0.len() != shape.len() {
+            return Err(error(format!(
+                "expected {} axes but got {}",
+                shape.len(),
+                self.0.len()
+            )));
+        }
+        for (axis, (elem, len)) in self.0.iter().zip(shape.iter()).enumerate() {
+            let len = *len as isize;
+            let resolve = |index: isize| if index < 0 { len + index } else { index };
+            let start = resolve(elem.start);
+            let end = elem.end.map(resolve).unwrap_or(len);
+            if start < 0 || start > len || end < 0 || end > len {
+                return Err(error(format!("axis {} is out of bounds", axis)));
+            }
+            if elem.step == Some(0) {
+                return Err(error(format!("step of axis {} is zero", axis)));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl From<SliceInfo> for ndarray::SliceInfo<Vec<ndarray::SliceInfoElem>, IxDyn, IxDyn> {
     fn from(s: SliceInfo) -> ndarray::SliceInfo<Vec<ndarray::SliceInfoElem>, IxDyn, IxDyn> {
         let all_slices: Vec<ndarray::SliceInfoElem> = s
@@ -192,8 +257,13 @@ where
         HostTensor::<T>(x, plc.clone())
     }
 
-    pub(crate) fn reshape(self, newshape: HostShape) -> Self {
-        HostTensor::<T>(self.0.into_shape(newshape.0 .0).unwrap(), self.1) // TODO need to be fix (unwrap)
+    pub(crate) fn reshape(self, newshape: HostShape) -> Result<Self> {
+        let shape = self.0.shape().to_vec();
+        let reshaped = self
+            .0
+            .into_shape(newshape.0 .0)
+            .map_err(|e| reshape_error(&shape, &newshape.0, e))?;
+        Ok(HostTensor::<T>(reshaped, self.1))
     }
 
     pub(crate) fn shape(&self) -> HostShape {
@@ -201,6 +271,51 @@ where
     }
 }
 
+impl<T> HostTensor<T> {
+    /// View of the entries of the tensor, without copying them.
+    pub fn view(&self) -> ArrayViewD<'_, T> {
+        self.0.view()
+    }
+
+    /// View of the entries of the tensor with dimensionality `D`, such as `Ix2` for matrices.
+    pub fn view_as<D: Dimension>(&self) -> Result<ArrayView<'_, T, D>> {
+        self.0.view().into_dimensionality::<D>().map_err(|_| {
+            Error::InvalidArgument(format!(
+                "cannot view tensor of shape {:?} with {} dimensions",
+                self.0.shape(),
+                D::NDIM.map_or("a dynamic number of".to_string(), |ndim| ndim.to_string())
+            ))
+        })
+    }
+}
+
+impl<T: Clone> HostTensor<T> {
+    /// Mutable view of the entries of the tensor, which are only copied if shared
+    /// with other tensors.
+    pub fn view_mut(&mut self) -> ArrayViewMutD<'_, T> {
+        self.0.view_mut()
+    }
+}
+
+impl<'a, T> From<&'a HostTensor<T>> for ArrayViewD<'a, T> {
+    fn from(x: &'a HostTensor<T>) -> Self {
+        x.view()
+    }
+}
+
+impl<T: Clone> From<HostTensor<T>> for ArrayD<T> {
+    fn from(x: HostTensor<T>) -> Self {
+        // only copies if the entries are shared with other tensors
+        x.0.into_owned()
+    }
+}
+
+impl<T> From<HostTensor<T>> for ArcArrayD<T> {
+    fn from(x: HostTensor<T>) -> Self {
+        x.0
+    }
+}
+
 #[derive(Serialize, Deserialize, Hash, Clone, PartialEq, Eq)]
 pub struct HostBitTensor(pub BitArrayRepr, pub HostPlacement);
 
@@ -240,18 +355,22 @@ impl HostBitTensor {
         HostBitTensor(x, plc.clone())
     }
 
-    fn reshape(self, newshape: HostShape) -> Self {
+    fn reshape(self, newshape: HostShape) -> Result<Self> {
+        let dim = IxDyn(&newshape.0 .0);
+        if dim.size() != self.0.dim.size() {
+            return Err(reshape_error(self.0.shape(), &newshape.0, "sizes differ"));
+        }
         let arr = BitArrayRepr {
             data: self.0.data.clone(),
-            dim: std::sync::Arc::new(IxDyn(&newshape.0 .0)),
+            dim: std::sync::Arc::new(dim),
         };
-        HostBitTensor(arr, self.1)
+        Ok(HostBitTensor(arr, self.1))
     }
 
-    fn expand_dims(self, mut axis: Vec<usize>) -> Self {
+    fn expand_dims(self, mut axis: Vec<usize>) -> Result<Self> {
         let plc = self.1.clone();
         axis.sort_by_key(|ax| Reverse(*ax));
-        let newshape = self.shape().0.extend_singletons(axis);
+        let newshape = self.shape().0.extend_singletons(axis)?;
         self.reshape(HostShape(newshape, plc))
     }
 
@@ -428,14 +547,19 @@ impl<T> HostRingTensor<T> {
         HostRingTensor::<T>(x, plc.clone())
     }
 
-    fn reshape(self, newshape: HostShape) -> Self {
-        HostRingTensor::<T>(self.0.into_shape(newshape.0 .0).unwrap(), self.1) // TODO need to be fix (unwrap)
+    fn reshape(self, newshape: HostShape) -> Result<Self> {
+        let shape = self.0.shape().to_vec();
+        let reshaped = self
+            .0
+            .into_shape(newshape.0 .0)
+            .map_err(|e| reshape_error(&shape, &newshape.0, e))?;
+        Ok(HostRingTensor::<T>(reshaped, self.1))
     }
 
-    fn expand_dims(self, mut axis: Vec<usize>) -> Self {
+    fn expand_dims(self, mut axis: Vec<usize>) -> Result<Self> {
         let plc = self.1.clone();
         axis.sort_by_key(|ax| Reverse(*ax));
-        let newshape = self.shape().0.extend_singletons(axis);
+        let newshape = self.shape().0.extend_singletons(axis)?;
         self.reshape(HostShape(newshape, plc))
     }
 }
@@ -452,6 +576,20 @@ impl<T: Clone> HostRingTensor<T> {
 }
 
 impl<T> HostRingTensor<T> {
+    /// View of the entries of the tensor, without copying them.
+    pub fn view(&self) -> ArrayViewD<'_, Wrapping<T>> {
+        self.0.view()
+    }
+
+    /// Mutable view of the entries of the tensor, which are only copied if shared
+    /// with other tensors.
+    pub fn view_mut(&mut self) -> ArrayViewMutD<'_, Wrapping<T>>
+    where
+        T: Clone,
+    {
+        self.0.view_mut()
+    }
+
     fn shape(&self) -> HostShape {
         HostShape(RawShape(self.0.shape().into()), self.1.clone())
     }
@@ -515,6 +653,24 @@ impl<T: Clone, D: ndarray::Dimension> FromRaw<Array<T, D>, HostTensor<T>> for Ho
     }
 }
 
+/// Tensor sharing its entries with `raw` and any other tensors created from it,
+/// without copying them.
+impl<T: Clone, D: ndarray::Dimension> FromRaw<ArcArray<T, D>, HostTensor<T>> for HostPlacement {
+    fn from_raw(&self, raw: ArcArray<T, D>) -> HostTensor<T> {
+        HostTensor(raw.into_dyn(), self.clone())
+    }
+}
+
+/// Tensor holding a copy of the entries of `raw`, since tensors cannot borrow
+/// their entries; share them through an `ArcArray` instead to avoid copying.
+impl<'a, T: Clone, D: ndarray::Dimension> FromRaw<ArrayView<'a, T, D>, HostTensor<T>>
+    for HostPlacement
+{
+    fn from_raw(&self, raw: ArrayView<'a, T, D>) -> HostTensor<T> {
+        HostTensor(raw.to_owned().into_dyn().into_shared(), self.clone())
+    }
+}
+
 impl<T: Clone, D: ndarray::Dimension> FromRaw<Array<T, D>, HostRingTensor<T>> for HostPlacement {
     fn from_raw(&self, raw: Array<T, D>) -> HostRingTensor<T> {
         HostRingTensor(raw.mapv(Wrapping).into_dyn().into_shared(), self.clone())
@@ -578,6 +734,30 @@ mod tests {
         assert_eq!(expected, shape);
     }
 
+    #[test]
+    fn test_shared_entries() {
+        let plc = HostPlacement::from("host");
+        let raw = array![[1.0, 2.0], [3.0, 4.0]].into_shared();
+        let x: HostFloat64Tensor = plc.from_raw(raw.clone());
+        let y: HostFloat64Tensor = plc.from_raw(raw.view());
+        assert_eq!(x, y);
+        // tensors created from shared arrays do not copy their entries
+        assert_eq!(x.view().as_ptr(), raw.as_ptr());
+        assert_ne!(y.view().as_ptr(), raw.as_ptr());
+
+        // writing to a tensor copies its entries if shared, leaving the array untouched
+        let mut x = x;
+        x.view_mut()[[0, 0]] = 5.0;
+        assert_eq!(raw[[0, 0]], 1.0);
+        assert_eq!(x.view()[[0, 0]], 5.0);
+        let ptr = x.view().as_ptr();
+        x.view_mut()[[1, 1]] = 6.0;
+        assert_eq!(x.view().as_ptr(), ptr);
+
+        let entries: ArcArrayD<f64> = x.into();
+        assert_eq!(entries, array![[5.0, 2.0], [3.0, 6.0]].into_dyn());
+    }
+
     #[test]
     fn dot_prod_f32() {
         let sess = SyncSession::default();
@@ -590,6 +770,7 @@ mod tests {
         assert_eq!(z, expected);
     }
 
+    #[cfg(feature = "blas")]
     #[test]
     fn test_inverse() {
         let sess = SyncSession::default();
@@ -895,6 +1076,57 @@ mod tests {
         assert_eq!(expected, sqrt)
     }
 
+    #[test]
+    fn test_kernel_noise() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let moments = |x: &HostFloat64Tensor| {
+            let mean = x.0.mean().unwrap();
+            let var = x.0.mapv(|v| (v - mean).powi(2)).mean().unwrap();
+            (mean, var)
+        };
+        let x: HostFloat64Tensor = plc.from_raw(ArrayD::zeros(IxDyn(&[10000])));
+
+        // Laplace distribution of scale 2, hence variance 8
+        let (mean, var) = moments(&plc.laplace_noise(&sess, 0.5, 1.0, &x));
+        assert!(mean.abs() < 0.2, "mean is {}", mean);
+        assert!((var - 8.0).abs() < 1.5, "variance is {}", var);
+
+        let expected_var = GaussianNoiseOp::scale(0.5, 1e-5, 1.0).unwrap().powi(2);
+        let (mean, var) = moments(&plc.gaussian_noise(&sess, 0.5, 1e-5, 1.0, &x));
+        assert!(mean.abs() < 0.6, "mean is {}", mean);
+        assert!(
+            (var / expected_var - 1.0).abs() < 0.1,
+            "variance is {}",
+            var
+        );
+
+        assert!(LaplaceNoiseOp::scale(0.0, 1.0).is_err());
+        assert!(GaussianNoiseOp::scale(0.5, 1.0, 1.0).is_err());
+        assert!(GaussianNoiseOp::scale(1.0, 1e-5, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_kernel_noise_on_grid() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        // results are multiples of a power of two, whatever the low bits of the input
+        let spacing = 2f64.powi(-19);
+        let x: HostFloat64Tensor = plc.from_raw(ArrayD::from_elem(IxDyn(&[1000]), 0.1));
+        for z in [
+            plc.laplace_noise(&sess, 0.5, 1.0, &x),
+            plc.gaussian_noise(&sess, 0.5, 1e-5, 1.0, &x),
+        ] {
+            assert!(z.0.iter().all(|v| (v / spacing).fract() == 0.0));
+        }
+
+        // no noise is needed if the result does not depend on the input
+        let z = plc.laplace_noise(&sess, 0.5, 0.0, &x);
+        assert_eq!(z, x);
+    }
+
     #[rstest]
     #[case(None)]
     #[case(Some(2))]
@@ -937,6 +1169,153 @@ mod tests {
         assert_eq!(expected, c);
     }
 
+    fn assert_kernel_error<T: std::fmt::Debug>(res: Result<T>, shapes: &[&str]) {
+        match res {
+            Err(Error::KernelError(msg)) => {
+                for shape in shapes {
+                    assert!(msg.contains(shape), "{} does not mention {}", msg, shape);
+                }
+            }
+            other => panic!("expected kernel error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_kernel_dot_shape_mismatch() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let y: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0, 3.0]]);
+        let res = DotOp::host_kernel(&sess, &plc, x.clone(), y);
+        assert_kernel_error(res, &["[2, 2]", "[1, 3]"]);
+
+        let z: HostFloat64Tensor = plc.from_raw(ArrayD::zeros(IxDyn(&[2, 2, 2])));
+        let res = DotOp::host_kernel(&sess, &plc, x, z);
+        assert_kernel_error(res, &["[2, 2]", "[2, 2, 2]"]);
+
+        let x: HostRing64Tensor = plc.from_raw(array![1, 2, 3]);
+        let y: HostRing64Tensor = plc.from_raw(array![1, 2]);
+        let res = DotOp::ring_kernel(&sess, &plc, x, y);
+        assert_kernel_error(res, &["[3]", "[2]"]);
+    }
+
+    #[test]
+    fn test_kernel_conv2d() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![[[
+            [1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0],
+            [7.0, 8.0, 9.0]
+        ]]]);
+        let filter: HostFloat64Tensor = plc.from_raw(array![[[[1.0, 1.0], [1.0, 1.0]]]]);
+        let res = Conv2DOp::host_kernel(&sess, &plc, 1, 0, x.clone(), filter.clone()).unwrap();
+        let expected: HostFloat64Tensor = plc.from_raw(array![[[[12.0, 16.0], [24.0, 28.0]]]]);
+        assert_eq!(res, expected);
+
+        let res = Conv2DOp::host_kernel(&sess, &plc, 2, 1, x, filter).unwrap();
+        let expected: HostFloat64Tensor = plc.from_raw(array![[[[1.0, 5.0], [11.0, 28.0]]]]);
+        assert_eq!(res, expected);
+
+        // channels are summed over, and each filter gives an output channel
+        let x: HostRing64Tensor = plc.from_raw(array![[[[1, 2], [3, 4]], [[5, 6], [7, 8]]]]);
+        let filter: HostRing64Tensor = plc.from_raw(array![[[[1]], [[0]]], [[[1]], [[1]]]]);
+        let res = Conv2DOp::ring_kernel(&sess, &plc, 1, 0, x.clone(), filter).unwrap();
+        let expected: HostRing64Tensor =
+            plc.from_raw(array![[[[1, 2], [3, 4]], [[6, 8], [10, 12]]]]);
+        assert_eq!(res, expected);
+
+        let filter: HostRing64Tensor = plc.from_raw(array![[[[1, 1]]]]);
+        assert!(Conv2DOp::ring_kernel(&sess, &plc, 1, 0, x, filter).is_err());
+    }
+
+    #[test]
+    fn test_kernel_reshape_size_mismatch() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let shape = HostShape(RawShape(vec![3]), plc.clone());
+        let res = ReshapeOp::host_kernel(&sess, &plc, x, shape.clone());
+        assert_kernel_error(res, &["[2, 2]", "[3]"]);
+
+        let x: HostBitTensor = plc.from_raw(array![[0u8, 1], [1, 0]]);
+        let res = ReshapeOp::host_bit_kernel(&sess, &plc, x, shape);
+        assert_kernel_error(res, &["[2, 2]", "[3]"]);
+    }
+
+    #[test]
+    fn test_kernel_squeeze_and_expand_dims_invalid_axis() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let res = SqueezeOp::host_kernel(&sess, &plc, Some(0), x.clone());
+        assert_kernel_error(res, &["[2, 2]"]);
+        let res = SqueezeOp::host_kernel(&sess, &plc, Some(5), x.clone());
+        assert_kernel_error(res, &["[2, 2]"]);
+        let res = ExpandDimsOp::host_int_float_kernel(&sess, &plc, vec![3], x);
+        assert_kernel_error(res, &["[2, 2]"]);
+    }
+
+    #[test]
+    fn test_kernel_concatenate_shape_mismatch() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let y: HostFloat64Tensor = plc.from_raw(array![[5.0, 6.0, 7.0]]);
+        let res = ConcatOp::host_kernel(&sess, &plc, 0, &[x.clone(), y]);
+        assert_kernel_error(res, &["[2, 2]", "[1, 3]"]);
+
+        let res = ConcatOp::host_kernel(&sess, &plc, 2, &[x.clone(), x]);
+        assert_kernel_error(res, &["[2, 2]"]);
+    }
+
+    #[test]
+    fn test_kernel_slice_out_of_bounds() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostRing64Tensor = plc.from_raw(array![[1, 2], [3, 4]]);
+        let slice = SliceInfo(vec![
+            SliceInfoElem {
+                start: 0,
+                end: Some(3),
+                step: None,
+            },
+            SliceInfoElem {
+                start: -3,
+                end: None,
+                step: None,
+            },
+        ]);
+        let res = SliceOp::host_ring_kernel(&sess, &plc, slice, x);
+        assert_kernel_error(res, &["[2, 2]"]);
+
+        let shape = HostShape(RawShape(vec![2, 2]), plc.clone());
+        let slice = SliceInfo(vec![SliceInfoElem {
+            start: 1,
+            end: Some(3),
+            step: None,
+        }]);
+        let res = SliceOp::shape_kernel(&sess, &plc, slice, shape);
+        assert_kernel_error(res, &["[2]"]);
+    }
+
+    #[cfg(feature = "blas")]
+    #[test]
+    fn test_kernel_inverse_not_square() {
+        let plc = HostPlacement::from("host");
+        let sess = SyncSession::default();
+
+        let x: HostFloat64Tensor = plc.from_raw(array![1.0, 2.0]);
+        let res = InverseOp::host_kernel(&sess, &plc, x);
+        assert_kernel_error(res, &["[2]"]);
+    }
+
     #[test]
     fn bit_sample() {
         let plc = HostPlacement::from("host");
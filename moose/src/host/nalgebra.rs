@@ -0,0 +1,106 @@
+//! Conversions between host tensors and nalgebra matrices and vectors.
+
+use super::{FromRaw, HostPlacement, HostTensor};
+use crate::error::{Error, Result};
+use nalgebra::{DMatrix, DVector, Dyn, MatrixView, Scalar};
+use ndarray::{Array1, Array2, Ix1, Ix2};
+use std::convert::TryFrom;
+
+/// Matrix view of a two-dimensional tensor, with arbitrary strides.
+pub type HostMatrixView<'a, T> = MatrixView<'a, T, Dyn, Dyn, Dyn, Dyn>;
+
+impl<T: Scalar> FromRaw<DMatrix<T>, HostTensor<T>> for HostPlacement {
+    fn from_raw(&self, raw: DMatrix<T>) -> HostTensor<T> {
+        let array = Array2::from_shape_fn(raw.shape(), |(i, j)| raw[(i, j)].clone());
+        self.from_raw(array)
+    }
+}
+
+impl<T: Scalar> FromRaw<DVector<T>, HostTensor<T>> for HostPlacement {
+    fn from_raw(&self, raw: DVector<T>) -> HostTensor<T> {
+        self.from_raw(Array1::from_iter(raw.iter().cloned()))
+    }
+}
+
+impl<T: Scalar> TryFrom<&HostTensor<T>> for DMatrix<T> {
+    type Error = Error;
+
+    fn try_from(x: &HostTensor<T>) -> Result<DMatrix<T>> {
+        let matrix = x.view_as::<Ix2>()?;
+        let (nrows, ncols) = matrix.dim();
+        Ok(DMatrix::from_fn(nrows, ncols, |i, j| {
+            matrix[[i, j]].clone()
+        }))
+    }
+}
+
+impl<T: Scalar> TryFrom<&HostTensor<T>> for DVector<T> {
+    type Error = Error;
+
+    fn try_from(x: &HostTensor<T>) -> Result<DVector<T>> {
+        let vector = x.view_as::<Ix1>()?;
+        Ok(DVector::from_iterator(vector.len(), vector.iter().cloned()))
+    }
+}
+
+impl<T: Scalar> HostTensor<T> {
+    /// Matrix view of a two-dimensional tensor, without copying its entries.
+    ///
+    /// Only tensors whose entries are laid out contiguously in memory, such as
+    /// all tensors computed by Moose kernels, can be viewed.
+    pub fn matrix_view(&self) -> Result<HostMatrixView<'_, T>> {
+        let matrix = self.view_as::<Ix2>()?;
+        let (nrows, ncols) = matrix.dim();
+        let strides = matrix.strides();
+        let not_contiguous = || {
+            Error::InvalidArgument(format!(
+                "cannot view tensor of shape {:?} with strides {:?} as matrix",
+                self.0.shape(),
+                strides
+            ))
+        };
+        if nrows == 0 || ncols == 0 || strides.iter().any(|stride| *stride < 0) {
+            return Err(not_contiguous());
+        }
+        // with non-negative strides the first entry in memory order is the one at [0, 0]
+        let data = matrix.to_slice_memory_order().ok_or_else(not_contiguous)?;
+        Ok(MatrixView::from_slice_with_strides_generic(
+            data,
+            Dyn(nrows),
+            Dyn(ncols),
+            Dyn(strides[0] as usize),
+            Dyn(strides[1] as usize),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_matrix_round_trip() {
+        let plc = HostPlacement::from("host");
+        let x: HostTensor<f64> = plc.from_raw(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        let matrix = DMatrix::try_from(&x).unwrap();
+        assert_eq!(
+            matrix,
+            DMatrix::from_row_slice(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+        );
+        let back: HostTensor<f64> = plc.from_raw(matrix.clone());
+        assert_eq!(back, x);
+
+        assert_eq!(x.matrix_view().unwrap(), matrix);
+        let transposed = HostTensor(x.0.clone().reversed_axes(), plc.clone());
+        assert_eq!(transposed.matrix_view().unwrap(), matrix.transpose());
+
+        let vector: HostTensor<f64> = plc.from_raw(array![1.0, 2.0]);
+        assert_eq!(
+            DVector::try_from(&vector).unwrap(),
+            DVector::from_vec(vec![1.0, 2.0])
+        );
+        assert!(DMatrix::try_from(&vector).is_err());
+    }
+}
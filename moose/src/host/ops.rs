@@ -7,6 +7,7 @@ use aes_prng::AesRng;
 use bitvec::prelude::BitVec;
 use ndarray::LinalgScalar;
 use ndarray::Zip;
+#[cfg(feature = "blas")]
 use ndarray_linalg::{Inverse, Lapack};
 use num_traits::{clamp_min, Float, FromPrimitive, Signed, Zero};
 use std::convert::TryInto;
@@ -343,57 +344,73 @@ impl DivOp {
     }
 }
 
+/// Check that tensors of shape `lhs` and `rhs` can be multiplied, since ndarray panics otherwise.
+fn check_dot_shapes(lhs: &[usize], rhs: &[usize]) -> Result<()> {
+    let inner = match (lhs.len(), rhs.len()) {
+        (1, 1) | (1, 2) => (lhs[0], rhs[0]),
+        (2, 1) | (2, 2) => (lhs[1], rhs[0]),
+        _ => {
+            return Err(Error::KernelError(format!(
+                "Dot: not implemented between tensors of rank {} and {} (shapes {:?} and {:?})",
+                lhs.len(),
+                rhs.len(),
+                lhs,
+                rhs
+            )))
+        }
+    };
+    if inner.0 != inner.1 {
+        return Err(Error::KernelError(format!(
+            "Dot: cannot multiply tensors of shape {:?} and {:?}",
+            lhs, rhs
+        )));
+    }
+    Ok(())
+}
+
 impl<T> HostTensor<T>
 where
     T: LinalgScalar,
 {
-    fn dot(self, other: HostTensor<T>) -> HostTensor<T> {
-        match (self.0.ndim(), other.0.ndim()) {
+    fn dot(self, other: HostTensor<T>) -> Result<HostTensor<T>> {
+        check_dot_shapes(self.0.shape(), other.0.shape())?;
+        let res = match (self.0.ndim(), other.0.ndim()) {
             (1, 1) => {
                 let l = self.0.into_dimensionality::<Ix1>().unwrap();
                 let r = other.0.into_dimensionality::<Ix1>().unwrap();
-                let res = Array::from_elem([], l.dot(&r))
+                Array::from_elem([], l.dot(&r))
                     .into_shared()
                     .into_dimensionality::<IxDyn>()
-                    .unwrap();
-                HostTensor(res, self.1)
+                    .unwrap()
             }
             (1, 2) => {
                 let l = self.0.into_dimensionality::<Ix1>().unwrap();
                 let r = other.0.into_dimensionality::<Ix2>().unwrap();
-                let res = l
-                    .dot(&r)
+                l.dot(&r)
                     .into_shared()
                     .into_dimensionality::<IxDyn>()
-                    .unwrap();
-                HostTensor(res, self.1)
+                    .unwrap()
             }
             (2, 1) => {
                 let l = self.0.into_dimensionality::<Ix2>().unwrap();
                 let r = other.0.into_dimensionality::<Ix1>().unwrap();
-                let res = l
-                    .dot(&r)
+                l.dot(&r)
                     .into_shared()
                     .into_dimensionality::<IxDyn>()
-                    .unwrap();
-                HostTensor(res, self.1)
+                    .unwrap()
             }
             (2, 2) => {
                 let l = self.0.into_dimensionality::<Ix2>().unwrap();
                 let r = other.0.into_dimensionality::<Ix2>().unwrap();
-                let res = l
-                    .dot(&r)
+                l.dot(&r)
                     .into_shared()
                     .into_dimensionality::<IxDyn>()
-                    .unwrap();
-                HostTensor(res, self.1)
+                    .unwrap()
             }
-            (self_rank, other_rank) => panic!(
-                // TODO: replace with proper error handling
-                "Dot<HostTensor> not implemented between tensors of rank {:?} and {:?}.",
-                self_rank, other_rank,
-            ),
-        }
+            // ranks were checked above
+            _ => unreachable!(),
+        };
+        Ok(HostTensor(res, self.1))
     }
 }
 
@@ -409,7 +426,7 @@ impl DotOp {
     {
         let x = plc.place(sess, x);
         let y = plc.place(sess, y);
-        Ok(x.dot(y))
+        x.dot(y)
     }
 }
 
@@ -541,9 +558,18 @@ impl SliceOp {
         info: SliceInfo,
         x: HostShape,
     ) -> Result<HostShape> {
-        let slice =
-            x.0.slice(info.0[0].start as usize, info.0[0].end.unwrap() as usize);
-        Ok(HostShape(slice, plc.clone()))
+        let shape = [x.0 .0.len()];
+        info.check(&shape)?;
+        let resolve = |index: isize| {
+            if index < 0 {
+                (shape[0] as isize + index) as usize
+            } else {
+                index as usize
+            }
+        };
+        let begin = resolve(info.0[0].start);
+        let end = info.0[0].end.map(resolve).unwrap_or(shape[0]).max(begin);
+        Ok(HostShape(x.0.slice(begin, end), plc.clone()))
     }
 }
 
@@ -613,6 +639,7 @@ impl<T: Clone> HostTensor<T> {
                 self.0.ndim()
             )));
         }
+        info.check(self.0.shape())?;
         let info = ndarray::SliceInfo::<Vec<ndarray::SliceInfoElem>, IxDyn, IxDyn>::from(info);
         let result = self.0.slice(info);
         Ok(HostTensor(result.to_owned().into_shared(), self.1.clone()))
@@ -650,6 +677,7 @@ impl<T: Clone> HostRingTensor<T> {
                 self.0.ndim()
             )));
         }
+        info.check(self.0.shape())?;
         let info = ndarray::SliceInfo::<Vec<ndarray::SliceInfoElem>, IxDyn, IxDyn>::from(info);
         let result = self.0.slice(info);
         Ok(HostRingTensor(
@@ -1048,10 +1076,10 @@ impl AddNOp {
 
 // TODO(Morten) inline
 impl<T: LinalgScalar> HostTensor<T> {
-    fn expand_dims(self, mut axis: Vec<usize>) -> Self {
+    fn expand_dims(self, mut axis: Vec<usize>) -> Result<Self> {
         let plc = self.1.clone();
         axis.sort_by_key(|ax| Reverse(*ax));
-        let newshape = self.shape().0.extend_singletons(axis);
+        let newshape = self.shape().0.extend_singletons(axis)?;
         self.reshape(HostShape(newshape, plc))
     }
 }
@@ -1064,7 +1092,7 @@ impl ExpandDimsOp {
         x: HostTensor<T>,
     ) -> Result<HostTensor<T>> {
         let x = plc.place(sess, x);
-        Ok(x.expand_dims(axis))
+        x.expand_dims(axis)
     }
 
     pub(crate) fn host_bit_kernel<S: RuntimeSession>(
@@ -1074,7 +1102,7 @@ impl ExpandDimsOp {
         x: HostBitTensor,
     ) -> Result<HostBitTensor> {
         let x = plc.place(sess, x);
-        Ok(x.expand_dims(axis))
+        x.expand_dims(axis)
     }
 
     pub(crate) fn host_ring_kernel<S: RuntimeSession, T>(
@@ -1084,7 +1112,7 @@ impl ExpandDimsOp {
         x: HostRingTensor<T>,
     ) -> Result<HostRingTensor<T>> {
         let x = plc.place(sess, x);
-        Ok(x.expand_dims(axis))
+        x.expand_dims(axis)
     }
 }
 
@@ -1099,8 +1127,8 @@ impl SqueezeOp {
         HostPlacement: PlacementPlace<S, HostTensor<T>>,
     {
         let x = plc.place(sess, x);
-        let newshape = HostShape(x.shape().0.squeeze(axis), plc.clone());
-        Ok(x.reshape(newshape))
+        let newshape = HostShape(x.shape().0.squeeze(axis)?, plc.clone());
+        x.reshape(newshape)
     }
 
     pub(crate) fn host_ring_kernel<S: RuntimeSession, T>(
@@ -1113,8 +1141,8 @@ impl SqueezeOp {
         HostPlacement: PlacementPlace<S, HostRingTensor<T>>,
     {
         let x = plc.place(sess, x);
-        let newshape = HostShape(x.shape().0.squeeze(axis), plc.clone());
-        Ok(x.reshape(newshape))
+        let newshape = HostShape(x.shape().0.squeeze(axis)?, plc.clone());
+        x.reshape(newshape)
     }
 
     pub(crate) fn host_bit_kernel<S: RuntimeSession>(
@@ -1127,11 +1155,26 @@ impl SqueezeOp {
         HostPlacement: PlacementPlace<S, HostBitTensor>,
     {
         let x = plc.place(sess, x);
-        let newshape = HostShape(x.shape().0.squeeze(axis), plc.clone());
-        Ok(x.reshape(newshape))
+        let newshape = HostShape(x.shape().0.squeeze(axis)?, plc.clone());
+        x.reshape(newshape)
     }
 }
 
+fn concat_error<A, D: Dimension>(
+    axis: u32,
+    xs: &[ArrayBase<A, D>],
+    reason: impl std::fmt::Display,
+) -> Error
+where
+    A: ndarray::RawData,
+{
+    let shapes: Vec<&[usize]> = xs.iter().map(|x| x.shape()).collect();
+    Error::KernelError(format!(
+        "Concat: cannot concatenate tensors of shapes {:?} along axis {}: {}",
+        shapes, axis, reason
+    ))
+}
+
 impl ConcatOp {
     pub(crate) fn host_kernel<S: Session, T: LinalgScalar + FromPrimitive>(
         _sess: &S,
@@ -1145,7 +1188,7 @@ impl ConcatOp {
         let arr: Vec<ArrayBase<ViewRepr<&T>, Dim<IxDynImpl>>> =
             xs.iter().map(|x| x.0.view()).collect();
 
-        let c = ndarray::concatenate(ax, &arr).map_err(|e| Error::KernelError(e.to_string()))?;
+        let c = ndarray::concatenate(ax, &arr).map_err(|e| concat_error(axis, &arr, e))?;
         Ok(HostTensor(c.into_shared(), plc.clone()))
     }
 
@@ -1164,7 +1207,7 @@ impl ConcatOp {
             xs.iter().map(|x| x.0.view()).collect();
         let ax = Axis(axis as usize);
         let concatenated =
-            ndarray::concatenate(ax, &arr).map_err(|e| Error::KernelError(e.to_string()))?;
+            ndarray::concatenate(ax, &arr).map_err(|e| concat_error(axis, &arr, e))?;
         Ok(HostRingTensor(concatenated.into_shared(), plc.clone()))
     }
 
@@ -1223,6 +1266,7 @@ impl TransposeOp {
 }
 
 impl InverseOp {
+    #[cfg(feature = "blas")]
     pub(crate) fn host_kernel<S: RuntimeSession, T: LinalgScalar + FromPrimitive + Lapack>(
         sess: &S,
         plc: &HostPlacement,
@@ -1232,26 +1276,38 @@ impl InverseOp {
         HostPlacement: PlacementPlace<S, HostTensor<T>>,
     {
         let x = plc.place(sess, x);
-        // TODO(Morten) better error handling below
-        let x_inv = match x.0.ndim() {
+        match x.0.ndim() {
             2 => {
+                let shape = x.0.shape().to_vec();
                 let two_dim: ndarray::ArcArray2<T> = x.0.into_dimensionality::<Ix2>().unwrap();
-                HostTensor::<T>(
-                    two_dim
-                        .inv()
-                        .unwrap()
-                        .into_shared()
-                        .into_dimensionality::<IxDyn>()
-                        .unwrap(),
+                let inv = two_dim.inv().map_err(|e| {
+                    Error::KernelError(format!(
+                        "Inverse: cannot invert matrix of shape {:?}: {}",
+                        shape, e
+                    ))
+                })?;
+                Ok(HostTensor::<T>(
+                    inv.into_shared().into_dimensionality::<IxDyn>().unwrap(),
                     x.1,
-                )
+                ))
             }
-            other_rank => panic!(
-                "Inverse only defined for rank 2 matrices, not rank {:?}",
+            other_rank => Err(Error::KernelError(format!(
+                "Inverse: only defined for rank 2 matrices, not rank {} (shape {:?})",
                 other_rank,
-            ),
-        };
-        Ok(x_inv)
+                x.0.shape()
+            ))),
+        }
+    }
+
+    #[cfg(not(feature = "blas"))]
+    pub(crate) fn host_kernel<S: RuntimeSession, T>(
+        _sess: &S,
+        _plc: &HostPlacement,
+        _x: HostTensor<T>,
+    ) -> Result<HostTensor<T>> {
+        Err(Error::UnimplementedOperator(
+            "Inverse: host kernel requires the `blas` feature".to_string(),
+        ))
     }
 }
 
@@ -1397,8 +1453,9 @@ impl ReshapeOp {
         HostPlacement: PlacementPlace<S, HostTensor<T>>,
     {
         let res =
-            x.0.into_shape(shape.0 .0)
-                .map_err(|e| Error::KernelError(e.to_string()))?;
+            x.0.clone()
+                .into_shape(shape.0 .0.clone())
+                .map_err(|e| reshape_error(x.0.shape(), &shape.0, e))?;
         Ok(HostTensor::<T>(res, plc.clone()))
     }
 
@@ -1408,9 +1465,13 @@ impl ReshapeOp {
         x: HostBitTensor,
         shape: HostShape,
     ) -> Result<HostBitTensor> {
+        let dim = IxDyn(&shape.0 .0);
+        if dim.size() != x.0.dim.size() {
+            return Err(reshape_error(x.0.shape(), &shape.0, "sizes differ"));
+        }
         let res = BitArrayRepr {
             data: x.0.data,
-            dim: std::sync::Arc::new(IxDyn(&shape.0 .0)),
+            dim: std::sync::Arc::new(dim),
         };
         Ok(HostBitTensor(res, plc.clone()))
     }
@@ -1422,8 +1483,9 @@ impl ReshapeOp {
         shape: HostShape,
     ) -> Result<HostRingTensor<T>> {
         let res =
-            x.0.into_shape(shape.0 .0)
-                .map_err(|e| Error::KernelError(e.to_string()))?;
+            x.0.clone()
+                .into_shape(shape.0 .0.clone())
+                .map_err(|e| reshape_error(x.0.shape(), &shape.0, e))?;
         Ok(HostRingTensor::<T>(res, plc.clone()))
     }
 }
@@ -1682,6 +1744,7 @@ where
     Wrapping<T>: LinalgScalar,
 {
     fn dot(self, rhs: HostRingTensor<T>) -> Result<HostRingTensor<T>> {
+        check_dot_shapes(self.0.shape(), rhs.0.shape())?;
         match self.0.ndim() {
             1 => match rhs.0.ndim() {
                 1 => {
@@ -1779,6 +1842,109 @@ impl DotOp {
     }
 }
 
+/// Convolution of `x` of shape `[batch, channels, height, width]` with `filter`
+/// of shape `[out_channels, channels, kernel_height, kernel_width]`.
+///
+/// The patches of `x` are unrolled into the columns of a matrix (im2col), such
+/// that the convolution becomes a product with the flattened filter.
+fn conv2d<T: LinalgScalar>(
+    x: ArrayViewD<T>,
+    filter: ArrayViewD<T>,
+    stride: usize,
+    padding: usize,
+) -> Result<ArrayD<T>> {
+    let x = x
+        .into_dimensionality::<Ix4>()
+        .map_err(|_| Error::KernelError("Conv2D expects an input of rank 4".to_string()))?;
+    let filter = filter
+        .into_dimensionality::<Ix4>()
+        .map_err(|_| Error::KernelError("Conv2D expects a filter of rank 4".to_string()))?;
+    let (batch, channels, height, width) = x.dim();
+    let (out_channels, filter_channels, kernel_height, kernel_width) = filter.dim();
+    if filter_channels != channels {
+        return Err(Error::KernelError(format!(
+            "Conv2D filter has {} input channels but the input has {}",
+            filter_channels, channels
+        )));
+    }
+    if stride == 0 {
+        return Err(Error::KernelError(
+            "Conv2D requires a positive stride".to_string(),
+        ));
+    }
+    let padded_height = height + 2 * padding;
+    let padded_width = width + 2 * padding;
+    if kernel_height > padded_height || kernel_width > padded_width {
+        return Err(Error::KernelError(format!(
+            "Conv2D filter of size {}x{} is larger than the padded input of size {}x{}",
+            kernel_height, kernel_width, padded_height, padded_width
+        )));
+    }
+    let out_height = (padded_height - kernel_height) / stride + 1;
+    let out_width = (padded_width - kernel_width) / stride + 1;
+
+    // a row per entry of a patch and a column per output position
+    let patch_len = channels * kernel_height * kernel_width;
+    let mut cols = Array2::<T>::zeros((patch_len, batch * out_height * out_width));
+    for ((row, col), entry) in cols.indexed_iter_mut() {
+        let c = row / (kernel_height * kernel_width);
+        let i = (row / kernel_width) % kernel_height;
+        let j = row % kernel_width;
+        let n = col / (out_height * out_width);
+        let h = ((col / out_width) % out_height) * stride + i;
+        let w = (col % out_width) * stride + j;
+        // entries in the padding are left as zeros
+        let h = h.checked_sub(padding).filter(|h| *h < height);
+        let w = w.checked_sub(padding).filter(|w| *w < width);
+        if let (Some(h), Some(w)) = (h, w) {
+            *entry = x[[n, c, h, w]];
+        }
+    }
+
+    let filter = filter
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((out_channels, patch_len))
+        .map_err(|e| Error::KernelError(e.to_string()))?;
+    let z = filter
+        .dot(&cols)
+        .into_shape((out_channels, batch, out_height, out_width))
+        .map_err(|e| Error::KernelError(e.to_string()))?;
+    Ok(z.permuted_axes([1, 0, 2, 3])
+        .as_standard_layout()
+        .into_owned()
+        .into_dyn())
+}
+
+impl Conv2DOp {
+    pub(crate) fn host_kernel<S: RuntimeSession, T: LinalgScalar>(
+        _sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: HostTensor<T>,
+        filter: HostTensor<T>,
+    ) -> Result<HostTensor<T>> {
+        let z = conv2d(x.0.view(), filter.0.view(), stride, padding)?;
+        Ok(HostTensor(z.into_shared(), plc.clone()))
+    }
+
+    pub(crate) fn ring_kernel<S: RuntimeSession, T>(
+        _sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: HostRingTensor<T>,
+        filter: HostRingTensor<T>,
+    ) -> Result<HostRingTensor<T>>
+    where
+        Wrapping<T>: LinalgScalar,
+    {
+        let z = conv2d(x.0.view(), filter.0.view(), stride, padding)?;
+        Ok(HostRingTensor(z.into_shared(), plc.clone()))
+    }
+}
+
 impl ShlOp {
     pub(crate) fn ring_kernel<S: RuntimeSession, T>(
         _sess: &S,
@@ -1809,6 +1975,165 @@ impl ShrOp {
     }
 }
 
+/// Uniform sample from the open unit interval.
+fn sample_unit_interval(rng: &mut AesRng) -> f64 {
+    ((rng.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+}
+
+// Adding continuous noise in floating point leaks the input through the least
+// significant bits of the result (Mironov, CCS 2012). Instead, inputs are
+// clamped and rounded to a grid whose spacing is a power of two, and noise is
+// drawn from a discrete distribution over the same grid, so every result is an
+// exactly computed multiple of the spacing that does not depend on the input
+// beyond its rounded value (Canonne, Kamath and Steinke, NeurIPS 2020).
+
+/// Bits of precision of the grid below the scale of the noise.
+const NOISE_GRID_BITS: i32 = 20;
+
+/// Bound on the number of grid steps of inputs, keeping all sums exact in `f64`.
+const MAX_NOISE_GRID_STEPS: f64 = (1u64 << 50) as f64;
+
+/// Spacing of the grid used for noise of the given scale.
+fn noise_grid_spacing(scale: f64) -> f64 {
+    2f64.powi(scale.log2().ceil() as i32 - NOISE_GRID_BITS)
+}
+
+/// Number of failed trials before the first success, when each fails with probability `exp(-1 / t)`.
+fn sample_geometric(rng: &mut AesRng, t: f64) -> f64 {
+    (-t * sample_unit_interval(rng).ln()).floor()
+}
+
+/// Sample from the discrete Laplace distribution over the integers with scale `t`.
+fn sample_discrete_laplace(rng: &mut AesRng, t: f64) -> f64 {
+    sample_geometric(rng, t) - sample_geometric(rng, t)
+}
+
+/// Sample from the discrete Gaussian distribution over the integers with parameter `sigma`,
+/// by rejection from the discrete Laplace distribution.
+fn sample_discrete_gaussian(rng: &mut AesRng, sigma: f64) -> f64 {
+    let t = sigma.floor() + 1.0;
+    let variance = sigma * sigma;
+    loop {
+        let y = sample_discrete_laplace(rng, t);
+        let exponent = (y.abs() - variance / t).powi(2) / (2.0 * variance);
+        if sample_unit_interval(rng) < (-exponent).exp() {
+            return y;
+        }
+    }
+}
+
+/// Round `v` to the grid of the given spacing and add `noise` steps of the grid.
+fn add_grid_noise<T: Float + FromPrimitive>(v: T, spacing: f64, noise: f64) -> T {
+    let steps = (v.to_f64().unwrap() / spacing)
+        .round()
+        .max(-MAX_NOISE_GRID_STEPS)
+        .min(MAX_NOISE_GRID_STEPS);
+    T::from_f64((steps + noise) * spacing).unwrap()
+}
+
+impl LaplaceNoiseOp {
+    /// Scale of the noise, checking the privacy parameters along the way.
+    pub(crate) fn scale(epsilon: f64, sensitivity: f64) -> Result<f64> {
+        if !(epsilon > 0.0 && epsilon.is_finite()) {
+            return Err(Error::InvalidArgument(format!(
+                "LaplaceNoise requires a positive epsilon, got {}",
+                epsilon
+            )));
+        }
+        if !(sensitivity >= 0.0 && sensitivity.is_finite()) {
+            return Err(Error::InvalidArgument(format!(
+                "LaplaceNoise requires a non-negative sensitivity, got {}",
+                sensitivity
+            )));
+        }
+        Ok(sensitivity / epsilon)
+    }
+
+    pub(crate) fn host_kernel<S: RuntimeSession, T: 'static + Float + FromPrimitive>(
+        _sess: &S,
+        plc: &HostPlacement,
+        epsilon: f64,
+        sensitivity: f64,
+        x: HostTensor<T>,
+    ) -> Result<HostTensor<T>>
+    where
+        HostPlacement: PlacementPlace<S, HostTensor<T>>,
+    {
+        let scale = Self::scale(epsilon, sensitivity)?;
+        if scale == 0.0 {
+            // the result does not depend on the private inputs
+            return Ok(HostTensor::place(plc, x.0));
+        }
+        let spacing = noise_grid_spacing(scale);
+        // rounding may move neighbouring inputs up to one more step apart
+        let steps_scale = Self::scale(epsilon, sensitivity + spacing)? / spacing;
+        let mut rng = AesRng::from_random_seed();
+        let z = x.0.mapv(|v| {
+            let noise = sample_discrete_laplace(&mut rng, steps_scale);
+            add_grid_noise(v, spacing, noise)
+        });
+        Ok(HostTensor::place(plc, z.into_shared()))
+    }
+}
+
+impl GaussianNoiseOp {
+    /// Standard deviation of the noise, checking the privacy parameters along the way.
+    pub(crate) fn scale(epsilon: f64, delta: f64, sensitivity: f64) -> Result<f64> {
+        if !(epsilon > 0.0 && epsilon.is_finite()) {
+            return Err(Error::InvalidArgument(format!(
+                "GaussianNoise requires a positive epsilon, got {}",
+                epsilon
+            )));
+        }
+        if epsilon >= 1.0 {
+            return Err(Error::InvalidArgument(format!(
+                "GaussianNoise requires an epsilon below 1 for its noise to suffice, got {}",
+                epsilon
+            )));
+        }
+        if !(delta > 0.0 && delta < 1.0) {
+            return Err(Error::InvalidArgument(format!(
+                "GaussianNoise requires a delta between 0 and 1, got {}",
+                delta
+            )));
+        }
+        if !(sensitivity >= 0.0 && sensitivity.is_finite()) {
+            return Err(Error::InvalidArgument(format!(
+                "GaussianNoise requires a non-negative sensitivity, got {}",
+                sensitivity
+            )));
+        }
+        Ok(sensitivity * (2.0 * (1.25 / delta).ln()).sqrt() / epsilon)
+    }
+
+    pub(crate) fn host_kernel<S: RuntimeSession, T: 'static + Float + FromPrimitive>(
+        _sess: &S,
+        plc: &HostPlacement,
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: HostTensor<T>,
+    ) -> Result<HostTensor<T>>
+    where
+        HostPlacement: PlacementPlace<S, HostTensor<T>>,
+    {
+        let scale = Self::scale(epsilon, delta, sensitivity)?;
+        if scale == 0.0 {
+            // the result does not depend on the private inputs
+            return Ok(HostTensor::place(plc, x.0));
+        }
+        let spacing = noise_grid_spacing(scale);
+        // rounding may move neighbouring inputs up to one more step apart
+        let steps_sigma = Self::scale(epsilon, delta, sensitivity + spacing)? / spacing;
+        let mut rng = AesRng::from_random_seed();
+        let z = x.0.mapv(|v| {
+            let noise = sample_discrete_gaussian(&mut rng, steps_sigma);
+            add_grid_noise(v, spacing, noise)
+        });
+        Ok(HostTensor::place(plc, z.into_shared()))
+    }
+}
+
 impl SampleOp {
     pub(crate) fn ring64_kernel<S: RuntimeSession>(
         _sess: &S,
@@ -2203,6 +2528,58 @@ impl CastOp {
         Ok(HostTensor(unwrapped.into_shared(), plc.clone()))
     }
 
+    pub(crate) fn hr128_hu64_kernel<S: RuntimeSession>(
+        _sess: &S,
+        plc: &HostPlacement,
+        x: HostRing128Tensor,
+    ) -> Result<HostTensor<u64>> {
+        // reduction modulo 2^64
+        let unwrapped = x.0.mapv(|item| item.0 as u64);
+        Ok(HostTensor(unwrapped.into_shared(), plc.clone()))
+    }
+
+    pub(crate) fn hu64_ring_kernel<S: RuntimeSession, T>(
+        _sess: &S,
+        plc: &HostPlacement,
+        x: HostTensor<u64>,
+    ) -> Result<HostRingTensor<T>>
+    where
+        T: From<u64>,
+    {
+        let wrapped = x.0.mapv(|item| Wrapping(T::from(item)));
+        Ok(HostRingTensor(wrapped.into_shared(), plc.clone()))
+    }
+
+    /// Embed small integers into a ring, using two's complement for negative values.
+    pub(crate) fn small_int_ring_kernel<S: RuntimeSession, T, R>(
+        _sess: &S,
+        plc: &HostPlacement,
+        x: HostTensor<T>,
+    ) -> Result<HostRingTensor<R>>
+    where
+        T: Into<i128> + Copy,
+        i128: num_traits::AsPrimitive<R>,
+        R: Copy + 'static,
+    {
+        use num_traits::AsPrimitive;
+        let wrapped = x.0.mapv(|item| Wrapping(item.into().as_()));
+        Ok(HostRingTensor(wrapped.into_shared(), plc.clone()))
+    }
+
+    pub(crate) fn ring_small_int_kernel<S: RuntimeSession, R, T>(
+        _sess: &S,
+        plc: &HostPlacement,
+        x: HostRingTensor<R>,
+    ) -> Result<HostTensor<T>>
+    where
+        R: num_traits::AsPrimitive<T>,
+        T: Copy + 'static,
+    {
+        // reduction modulo 2^8 or 2^16, keeping two's complement values
+        let unwrapped = x.0.mapv(|item| item.0.as_());
+        Ok(HostTensor(unwrapped.into_shared(), plc.clone()))
+    }
+
     pub(crate) fn ring_reduction_kernel<S: RuntimeSession>(
         _sess: &S,
         plc: &HostPlacement,
@@ -0,0 +1,213 @@
+//! Conversions between Polars series and data frames and host tensors.
+//!
+//! A series converts to and from a one-dimensional tensor of the same dtype,
+//! while a data frame converts to and from a two-dimensional tensor with a
+//! column per series. Series with missing values cannot be converted.
+
+use super::{HostPlacement, HostTensor};
+use crate::computation::Value;
+use crate::error::{Error, Result};
+use crate::types::HostFloat64Tensor;
+use ndarray::{Array1, Array2, ArrayD, Axis, Ix2};
+use polars::prelude::{DataFrame, DataType, NamedFrom, PolarsError, Series};
+
+fn polars_error(e: PolarsError) -> Error {
+    Error::InvalidArgument(format!("Polars conversion failed: {}", e))
+}
+
+/// One-dimensional tensor holding the entries of `series`.
+pub fn series_to_value(series: &Series, plc: &HostPlacement) -> Result<Value> {
+    if series.null_count() > 0 {
+        return Err(Error::InvalidArgument(format!(
+            "series '{}' has {} missing values",
+            series.name(),
+            series.null_count()
+        )));
+    }
+
+    macro_rules! tensor {
+        ($chunked:ident) => {{
+            let values: Vec<_> = series
+                .$chunked()
+                .map_err(polars_error)?
+                .into_no_null_iter()
+                .collect();
+            Value::from(HostTensor(
+                Array1::from(values).into_dyn().into_shared(),
+                plc.clone(),
+            ))
+        }};
+    }
+
+    let value = match series.dtype() {
+        DataType::Float32 => tensor!(f32),
+        DataType::Float64 => tensor!(f64),
+        DataType::Int8 => tensor!(i8),
+        DataType::Int16 => tensor!(i16),
+        DataType::Int32 => tensor!(i32),
+        DataType::Int64 => tensor!(i64),
+        DataType::UInt8 => tensor!(u8),
+        DataType::UInt16 => tensor!(u16),
+        DataType::UInt32 => tensor!(u32),
+        DataType::UInt64 => tensor!(u64),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "series '{}' of dtype {} cannot be converted to a tensor",
+                series.name(),
+                other
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Series named `name` holding the entries of a one-dimensional tensor.
+pub fn value_to_series(name: &str, value: &Value) -> Result<Series> {
+    macro_rules! series {
+        ($x:expr) => {{
+            if $x.0.ndim() != 1 {
+                return Err(Error::InvalidArgument(format!(
+                    "only one-dimensional tensors can be converted to a series, got shape {:?}",
+                    $x.0.shape()
+                )));
+            }
+            Series::new(name, $x.0.iter().cloned().collect::<Vec<_>>())
+        }};
+    }
+
+    let series = match value {
+        Value::HostFloat32Tensor(x) => series!(x),
+        Value::HostFloat64Tensor(x) => series!(x),
+        Value::HostInt8Tensor(x) => series!(x),
+        Value::HostInt16Tensor(x) => series!(x),
+        Value::HostInt32Tensor(x) => series!(x),
+        Value::HostInt64Tensor(x) => series!(x),
+        Value::HostUint8Tensor(x) => series!(x),
+        Value::HostUint16Tensor(x) => series!(x),
+        Value::HostUint32Tensor(x) => series!(x),
+        Value::HostUint64Tensor(x) => series!(x),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "values of type {} cannot be converted to a series",
+                other.ty()
+            )))
+        }
+    };
+    Ok(series)
+}
+
+/// Two-dimensional `Float64` tensor with a column per series of `df`.
+pub fn dataframe_to_tensor(df: &DataFrame, plc: &HostPlacement) -> Result<HostFloat64Tensor> {
+    let mut matrix = Array2::<f64>::zeros((df.height(), df.width()));
+    for (j, series) in df.get_columns().iter().enumerate() {
+        let column = series.cast(&DataType::Float64).map_err(polars_error)?;
+        let column = series_to_value(&column, plc)?;
+        match column {
+            Value::HostFloat64Tensor(column) => matrix.column_mut(j).assign(&column.0),
+            _ => unreachable!("series cast to Float64 converted to another type"),
+        }
+    }
+    Ok(HostTensor(matrix.into_dyn().into_shared(), plc.clone()))
+}
+
+/// Data frame with a series for each column of a two-dimensional tensor.
+///
+/// Series are named after `column_names`, or `col_<i>` if no names are given.
+pub fn value_to_dataframe(value: &Value, column_names: &[String]) -> Result<DataFrame> {
+    macro_rules! columns {
+        ($x:expr, $value:path) => {{
+            let matrix = $x.0.view().into_dimensionality::<Ix2>().map_err(|_| {
+                Error::InvalidArgument(format!(
+                    "only two-dimensional tensors can be converted to a data frame, got shape {:?}",
+                    $x.0.shape()
+                ))
+            })?;
+            matrix
+                .axis_iter(Axis(1))
+                .map(|column| {
+                    let column: ArrayD<_> = column.to_owned().into_dyn();
+                    $value(Box::new(HostTensor(column.into_shared(), $x.1.clone())))
+                })
+                .collect::<Vec<Value>>()
+        }};
+    }
+
+    let columns = match value {
+        Value::HostFloat32Tensor(x) => columns!(x, Value::HostFloat32Tensor),
+        Value::HostFloat64Tensor(x) => columns!(x, Value::HostFloat64Tensor),
+        Value::HostInt8Tensor(x) => columns!(x, Value::HostInt8Tensor),
+        Value::HostInt16Tensor(x) => columns!(x, Value::HostInt16Tensor),
+        Value::HostInt32Tensor(x) => columns!(x, Value::HostInt32Tensor),
+        Value::HostInt64Tensor(x) => columns!(x, Value::HostInt64Tensor),
+        Value::HostUint8Tensor(x) => columns!(x, Value::HostUint8Tensor),
+        Value::HostUint16Tensor(x) => columns!(x, Value::HostUint16Tensor),
+        Value::HostUint32Tensor(x) => columns!(x, Value::HostUint32Tensor),
+        Value::HostUint64Tensor(x) => columns!(x, Value::HostUint64Tensor),
+        other => {
+            return Err(Error::InvalidArgument(format!(
+                "values of type {} cannot be converted to a data frame",
+                other.ty()
+            )))
+        }
+    };
+    if !column_names.is_empty() && column_names.len() != columns.len() {
+        return Err(Error::InvalidArgument(format!(
+            "got {} column names for a tensor with {} columns",
+            column_names.len(),
+            columns.len()
+        )));
+    }
+    let series = columns
+        .iter()
+        .enumerate()
+        .map(|(j, column)| match column_names.get(j) {
+            Some(name) => value_to_series(name, column),
+            None => value_to_series(&format!("col_{}", j), column),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    DataFrame::new(series).map_err(polars_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HostInt64Tensor;
+    use ndarray::array;
+
+    #[test]
+    fn test_series_round_trip() {
+        let plc = HostPlacement::from("host");
+        let series = Series::new("x", &[1i64, -2, 3]);
+        let value = series_to_value(&series, &plc).unwrap();
+        let expected: HostInt64Tensor = HostTensor(array![1, -2, 3].into_dyn().into_shared(), plc);
+        assert_eq!(value, Value::from(expected));
+        assert!(value_to_series("x", &value).unwrap().series_equal(&series));
+
+        let missing = Series::new("y", &[Some(1.0f64), None]);
+        assert!(series_to_value(&missing, &HostPlacement::from("host")).is_err());
+    }
+
+    #[test]
+    fn test_dataframe_round_trip() {
+        let plc = HostPlacement::from("host");
+        let df = DataFrame::new(vec![
+            Series::new("age", &[31i32, 47]),
+            Series::new("income", &[2.5f64, 4.0]),
+        ])
+        .unwrap();
+        let tensor = dataframe_to_tensor(&df, &plc).unwrap();
+        let expected: HostFloat64Tensor = HostTensor(
+            array![[31.0, 2.5], [47.0, 4.0]].into_dyn().into_shared(),
+            plc,
+        );
+        assert_eq!(tensor, expected);
+
+        let names = vec!["age".to_string(), "income".to_string()];
+        let back = value_to_dataframe(&Value::from(tensor), &names).unwrap();
+        assert_eq!(back.get_column_names(), vec!["age", "income"]);
+        assert_eq!(
+            dataframe_to_tensor(&back, &HostPlacement::from("host")).unwrap(),
+            expected
+        );
+    }
+}
@@ -5,8 +5,10 @@ use crate::kernels::PlacementPlace;
 use aes_prng::{AesRng, RngSeed, SEED_SIZE};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+/// Raw seed, zeroized when dropped.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Zeroize, ZeroizeOnDrop)]
 pub struct RawSeed(pub [u8; 16]);
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
@@ -33,7 +35,8 @@ impl<S: Session> PlacementPlace<S, HostSeed> for HostPlacement {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+/// Raw PRF key, zeroized when dropped.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Zeroize, ZeroizeOnDrop)]
 pub struct RawPrfKey(pub [u8; 16]);
 
 impl RawPrfKey {
@@ -0,0 +1,306 @@
+//! Tables of named columns of mixed dtypes held by a host.
+//!
+//! A `HostTable` wraps an Arrow record batch, such that feature engineering on
+//! tabular inputs can happen on the host owning them before the selected
+//! features are projected into a tensor and secret shared:
+//!
+//! ```
+//! # use moose::prelude::*;
+//! # use moose::host::HostTable;
+//! # use arrow::array::{ArrayRef, Float64Array, Int64Array};
+//! # use arrow::record_batch::RecordBatch;
+//! # use std::sync::Arc;
+//! let plc = HostPlacement::from("alice");
+//! let batch = RecordBatch::try_from_iter(vec![
+//!     ("id", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+//!     ("income", Arc::new(Float64Array::from(vec![2.5, 4.0])) as ArrayRef),
+//! ])
+//! .unwrap();
+//! let table: HostTable = plc.from_raw(batch);
+//! let x = plc.project_table(&table, &["income".to_string()]).unwrap();
+//! ```
+//!
+//! Tables are joined and concatenated on a host placement, which holds the
+//! result. Columns with missing values are kept as such, but cannot be
+//! projected into tensors.
+
+use super::arrow::{array_to_value, record_batches_to_matrix};
+use super::{FromRaw, HostPlacement};
+use crate::computation::{Placed, Value};
+use crate::error::{Error, Result};
+use crate::execution::Session;
+use crate::kernels::PlacementPlace;
+use crate::types::HostFloat64Tensor;
+use arrow::array::{Array, ArrayRef, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Schema};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Table with named columns of mixed dtypes, held by a host.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostTable(pub RecordBatch, pub HostPlacement);
+
+impl HostTable {
+    pub fn num_rows(&self) -> usize {
+        self.0.num_rows()
+    }
+
+    /// Names of the columns, in order.
+    pub fn column_names(&self) -> Vec<String> {
+        self.0
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    fn index_of(&self, column: &str) -> Result<usize> {
+        self.0
+            .schema()
+            .index_of(column)
+            .map_err(|_| Error::InvalidArgument(format!("no column '{}' in table", column)))
+    }
+}
+
+impl Placed for HostTable {
+    type Placement = HostPlacement;
+
+    fn placement(&self) -> Result<Self::Placement> {
+        Ok(self.1.clone())
+    }
+}
+
+impl<S: Session> PlacementPlace<S, HostTable> for HostPlacement {
+    fn place(&self, _sess: &S, table: HostTable) -> HostTable {
+        match table.placement() {
+            Ok(place) if self == &place => table,
+            _ => {
+                // TODO just updating the placement isn't enough,
+                // we need this to eventually turn into Send + Recv
+                HostTable(table.0, self.clone())
+            }
+        }
+    }
+}
+
+impl FromRaw<RecordBatch, HostTable> for HostPlacement {
+    fn from_raw(&self, raw: RecordBatch) -> HostTable {
+        HostTable(raw, self.clone())
+    }
+}
+
+fn table_error(e: ArrowError) -> Error {
+    Error::InvalidArgument(format!("table operation failed: {}", e))
+}
+
+impl HostPlacement {
+    /// One-dimensional tensor holding the entries of `column` of `table`.
+    pub fn table_column(&self, table: &HostTable, column: &str) -> Result<Value> {
+        let index = table.index_of(column)?;
+        array_to_value(column, table.0.column(index).as_ref(), self)
+    }
+
+    /// Two-dimensional `Float64` tensor with a row per row of `table` and a
+    /// column per selected column, or per column of `table` if none are selected.
+    pub fn project_table(
+        &self,
+        table: &HostTable,
+        columns: &[String],
+    ) -> Result<HostFloat64Tensor> {
+        let value = record_batches_to_matrix(
+            table.0.schema().as_ref(),
+            std::slice::from_ref(&table.0),
+            columns,
+            self,
+        )?;
+        HostFloat64Tensor::try_from(value)
+    }
+
+    /// Table with the rows of all `tables` in order; the tables must have the same columns.
+    pub fn concat_tables(&self, tables: &[HostTable]) -> Result<HostTable> {
+        let first = tables.first().ok_or_else(|| {
+            Error::InvalidArgument("cannot concatenate an empty list of tables".to_string())
+        })?;
+        let schema = first.0.schema();
+        if let Some(other) = tables.iter().find(|table| table.0.schema() != schema) {
+            return Err(Error::InvalidArgument(format!(
+                "cannot concatenate tables with columns {:?} and {:?}",
+                first.column_names(),
+                other.column_names()
+            )));
+        }
+        let batch = arrow::compute::concat_batches(&schema, tables.iter().map(|table| &table.0))
+            .map_err(table_error)?;
+        Ok(HostTable(batch, self.clone()))
+    }
+
+    /// Inner join of `left` and `right` on the column `on` of both tables.
+    ///
+    /// The result holds the columns of `left` followed by those of `right`
+    /// except `on`, with a row per pair of rows with equal keys, ordered by the
+    /// rows of `left` and then by those of `right`. Rows with missing keys are
+    /// never joined.
+    pub fn join_tables(&self, left: &HostTable, right: &HostTable, on: &str) -> Result<HostTable> {
+        let left_key = left.0.column(left.index_of(on)?);
+        let right_index = right.index_of(on)?;
+        let right_key = right.0.column(right_index);
+        if left_key.data_type() != right_key.data_type() {
+            return Err(Error::InvalidArgument(format!(
+                "cannot join on column '{}' of dtypes {} and {}",
+                on,
+                left_key.data_type(),
+                right_key.data_type()
+            )));
+        }
+
+        let left_names = left.column_names();
+        let right_names: Vec<String> = right
+            .column_names()
+            .into_iter()
+            .filter(|name| name != on)
+            .collect();
+        if let Some(name) = right_names.iter().find(|name| left_names.contains(name)) {
+            return Err(Error::InvalidArgument(format!(
+                "cannot join tables both having column '{}'",
+                name
+            )));
+        }
+
+        // keys are compared by their textual representation, which is exact
+        // since both columns are of the same dtype
+        let left_key = join_keys(left_key)?;
+        let right_key = join_keys(right_key)?;
+        let mut rows: HashMap<&str, Vec<u32>> = HashMap::new();
+        for (j, key) in right_key.iter().enumerate() {
+            if let Some(key) = key {
+                rows.entry(key).or_default().push(j as u32);
+            }
+        }
+        let mut left_rows = Vec::new();
+        let mut right_rows = Vec::new();
+        for (i, key) in left_key.iter().enumerate() {
+            let matches = key.and_then(|key| rows.get(key));
+            for j in matches.into_iter().flatten() {
+                left_rows.push(i as u32);
+                right_rows.push(*j);
+            }
+        }
+        let left_rows = UInt32Array::from(left_rows);
+        let right_rows = UInt32Array::from(right_rows);
+
+        let schema = left.0.schema();
+        let right_schema = right.0.schema();
+        let mut fields = schema.fields().to_vec();
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(fields.len() + right_names.len());
+        for column in left.0.columns() {
+            columns.push(
+                arrow::compute::take(column.as_ref(), &left_rows, None).map_err(table_error)?,
+            );
+        }
+        for (index, column) in right.0.columns().iter().enumerate() {
+            if index == right_index {
+                continue;
+            }
+            fields.push(right_schema.fields()[index].clone());
+            columns.push(
+                arrow::compute::take(column.as_ref(), &right_rows, None).map_err(table_error)?,
+            );
+        }
+        let batch =
+            RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(table_error)?;
+        Ok(HostTable(batch, self.clone()))
+    }
+}
+
+fn join_keys(column: &ArrayRef) -> Result<StringArray> {
+    let keys = arrow::compute::cast(column, &DataType::Utf8).map_err(table_error)?;
+    Ok(keys
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .expect("cast to utf8 returned another type")
+        .clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HostInt64Tensor;
+    use arrow::array::{Float64Array, Int64Array};
+    use ndarray::array;
+
+    fn table(plc: &HostPlacement, columns: Vec<(&str, ArrayRef)>) -> HostTable {
+        plc.from_raw(RecordBatch::try_from_iter(columns).unwrap())
+    }
+
+    #[test]
+    fn test_project_table() {
+        let plc = HostPlacement::from("host");
+        let people = table(
+            &plc,
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![1, 2]))),
+                ("income", Arc::new(Float64Array::from(vec![2.5, 4.0]))),
+            ],
+        );
+        assert_eq!(people.num_rows(), 2);
+        assert_eq!(people.column_names(), vec!["id", "income"]);
+
+        let x = plc
+            .project_table(&people, &["income".to_string(), "id".to_string()])
+            .unwrap();
+        let expected: HostFloat64Tensor = plc.from_raw(array![[2.5, 1.0], [4.0, 2.0]]);
+        assert_eq!(x, expected);
+
+        let id = plc.table_column(&people, "id").unwrap();
+        let expected: HostInt64Tensor = plc.from_raw(array![1, 2]);
+        assert_eq!(id, Value::from(expected));
+        assert!(plc.table_column(&people, "age").is_err());
+    }
+
+    #[test]
+    fn test_concat_and_join_tables() {
+        let plc = HostPlacement::from("host");
+        let first = table(
+            &plc,
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![1, 2]))),
+                ("income", Arc::new(Float64Array::from(vec![2.5, 4.0]))),
+            ],
+        );
+        let second = table(
+            &plc,
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![3]))),
+                ("income", Arc::new(Float64Array::from(vec![1.0]))),
+            ],
+        );
+        let people = plc.concat_tables(&[first.clone(), second]).unwrap();
+        assert_eq!(people.num_rows(), 3);
+
+        let ages = table(
+            &plc,
+            vec![
+                ("id", Arc::new(Int64Array::from(vec![3, 1, 3, 4]))),
+                (
+                    "age",
+                    Arc::new(Float64Array::from(vec![60.0, 31.0, 61.0, 20.0])),
+                ),
+            ],
+        );
+        let joined = plc.join_tables(&people, &ages, "id").unwrap();
+        assert_eq!(joined.column_names(), vec!["id", "income", "age"]);
+        let x = plc.project_table(&joined, &[]).unwrap();
+        let expected: HostFloat64Tensor =
+            plc.from_raw(array![[1.0, 2.5, 31.0], [3.0, 1.0, 60.0], [3.0, 1.0, 61.0]]);
+        assert_eq!(x, expected);
+
+        // tables must agree on their columns
+        assert!(plc.concat_tables(&[first.clone(), ages.clone()]).is_err());
+        assert!(plc.join_tables(&people, &first, "id").is_err());
+        assert!(plc.join_tables(&people, &ages, "age").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Construction of logical computations from imported models.
+//!
+//! The importers of ONNX, TensorFlow, and XGBoost models all translate a model into
+//! operations placed according to a [`PlacementStrategy`]: arguments are provided by
+//! the input owner, weights are constants held by the model owner, everything else is
+//! computed on the compute placement, and outputs are revealed to the output receiver.
+//! [`ComputationBuilder`] implements this placement once for all of them.
+
+use crate::computation::*;
+use crate::host::{HostPlacement, HostTensor, RawShape};
+use crate::logical::{TensorDType, TensorShape};
+use crate::onnx::PlacementStrategy;
+use ndarray::ArrayD;
+
+/// Logical computation under construction, placed according to a strategy.
+pub(crate) struct ComputationBuilder<'a> {
+    strategy: &'a PlacementStrategy,
+    operations: Vec<Operation>,
+}
+
+impl<'a> ComputationBuilder<'a> {
+    pub(crate) fn new(strategy: &'a PlacementStrategy) -> Self {
+        ComputationBuilder {
+            strategy,
+            operations: Vec::new(),
+        }
+    }
+
+    pub(crate) fn finish(self) -> Computation {
+        Computation {
+            operations: self.operations,
+        }
+    }
+
+    /// Add an operation on `plc`, named by `prefix` and its position in the computation.
+    fn push(
+        &mut self,
+        prefix: &str,
+        kind: Operator,
+        inputs: Vec<String>,
+        plc: Placement,
+    ) -> String {
+        let name = format!("{}_{}", prefix, self.operations.len());
+        self.operations.push(Operation {
+            name: name.clone(),
+            kind,
+            inputs,
+            placement: plc,
+        });
+        name
+    }
+
+    /// Add an operation computing on the compute placement.
+    pub(crate) fn compute(&mut self, prefix: &str, kind: Operator, inputs: Vec<String>) -> String {
+        let plc = self.strategy.compute_placement();
+        self.push(prefix, kind, inputs, plc)
+    }
+
+    /// Host providing the inputs of the model as arguments.
+    pub(crate) fn input_owner(&self) -> HostPlacement {
+        match self.strategy {
+            PlacementStrategy::Host(host) => host.clone(),
+            PlacementStrategy::Replicated { input_owner, .. } => input_owner.clone(),
+        }
+    }
+
+    /// Host owning the model, and thereby its weights and the shapes used by it.
+    pub(crate) fn model_owner(&self) -> HostPlacement {
+        match self.strategy {
+            PlacementStrategy::Host(host) => host.clone(),
+            PlacementStrategy::Replicated { model_owner, .. } => model_owner.clone(),
+        }
+    }
+
+    /// Move a plaintext `Float64` value from `plc` into the type used for computing.
+    fn encode(&mut self, op_name: String, plc: &HostPlacement) -> String {
+        match self.strategy {
+            PlacementStrategy::Host(_) => op_name,
+            PlacementStrategy::Replicated { dtype, .. } => {
+                let sig = Signature::unary(Ty::Tensor(TensorDType::Float64), Ty::Tensor(*dtype));
+                self.push(
+                    "cast",
+                    CastOp { sig }.into(),
+                    vec![op_name],
+                    plc.clone().into(),
+                )
+            }
+        }
+    }
+
+    /// Add the `Float64` argument `arg_name`, provided by the input owner.
+    pub(crate) fn input(&mut self, arg_name: &str) -> String {
+        let plc = self.input_owner();
+        let input = self.push(
+            "input",
+            InputOp {
+                sig: Signature::nullary(Ty::Tensor(TensorDType::Float64)),
+                arg_name: arg_name.to_string(),
+            }
+            .into(),
+            vec![],
+            plc.clone().into(),
+        );
+        self.encode(input, &plc)
+    }
+
+    /// Add the weights `array` as a constant held by the model owner.
+    pub(crate) fn constant(&mut self, array: ArrayD<f64>) -> String {
+        let plc = self.model_owner();
+        let constant = self.push(
+            "constant",
+            ConstantOp {
+                sig: Signature::nullary(Ty::Tensor(TensorDType::Float64)),
+                value: Constant::HostFloat64Tensor(HostTensor::<f64>(
+                    array.into_shared(),
+                    plc.clone(),
+                )),
+            }
+            .into(),
+            vec![],
+            plc.clone().into(),
+        );
+        self.encode(constant, &plc)
+    }
+
+    /// Add a constant holding `shape`, for reshaping values on the compute placement.
+    pub(crate) fn shape_constant(&mut self, shape: Vec<usize>) -> String {
+        let plc = self.model_owner();
+        self.push(
+            "shape",
+            ConstantOp {
+                sig: Signature::nullary(Ty::Shape(TensorShape::Host)),
+                value: Constant::RawShape(RawShape(shape)),
+            }
+            .into(),
+            vec![],
+            plc.into(),
+        )
+    }
+
+    /// Reveal the value computed by `op_name` to the output receiver as output `tag`.
+    pub(crate) fn output(&mut self, tag: &str, op_name: String) {
+        let float = Ty::Tensor(TensorDType::Float64);
+        let (op_name, plc) = match self.strategy {
+            PlacementStrategy::Host(host) => (op_name, host.clone()),
+            PlacementStrategy::Replicated {
+                output_receiver,
+                dtype,
+                ..
+            } => {
+                let sig = Signature::unary(Ty::Tensor(*dtype), float);
+                let decoded = self.push(
+                    "cast",
+                    CastOp { sig }.into(),
+                    vec![op_name],
+                    output_receiver.clone().into(),
+                );
+                (decoded, output_receiver.clone())
+            }
+        };
+        self.push(
+            "output",
+            OutputOp {
+                sig: Signature::unary(float, float),
+                tag: tag.to_string(),
+            }
+            .into(),
+            vec![op_name],
+            plc.into(),
+        );
+    }
+}
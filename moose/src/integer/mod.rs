@@ -7,6 +7,26 @@ use serde::{Deserialize, Serialize};
 
 mod ops;
 
+/// Uint8, Int8, and Int16 tensors, currently only held by hosts
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbstractSmallIntTensor<HostT> {
+    Host(HostT),
+}
+
+impl<HostT> Placed for AbstractSmallIntTensor<HostT>
+where
+    HostT: Placed,
+    HostT::Placement: Into<Placement>,
+{
+    type Placement = Placement;
+
+    fn placement(&self) -> Result<Self::Placement> {
+        match self {
+            AbstractSmallIntTensor::Host(x) => Ok(x.placement()?.into()),
+        }
+    }
+}
+
 /// Uint64 Tensor abstracting over host and replicated values
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AbstractUint64Tensor<HostT, RepT> {
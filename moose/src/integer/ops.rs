@@ -235,3 +235,126 @@ impl TransposeOp {
         Ok(AbstractUint64Tensor::Replicated(z))
     }
 }
+
+impl ConstantOp {
+    pub(crate) fn small_int_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        value: Constant,
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostPlacement: PlacementConstant<S, HostT>,
+    {
+        let z = plc.constant(sess, value);
+        Ok(AbstractSmallIntTensor::Host(z))
+    }
+}
+
+impl InputOp {
+    pub(crate) fn small_int_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        arg_name: String,
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostPlacement: PlacementInput<S, HostT>,
+    {
+        let z = plc.input(sess, arg_name);
+        Ok(AbstractSmallIntTensor::Host(z))
+    }
+}
+
+impl OutputOp {
+    pub(crate) fn small_int_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        tag: String,
+        x: AbstractSmallIntTensor<HostT>,
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostPlacement: PlacementOutput<S, HostT, HostT>,
+    {
+        let AbstractSmallIntTensor::Host(x) = x;
+        Ok(AbstractSmallIntTensor::Host(plc.output(sess, tag, &x)))
+    }
+}
+
+impl LoadOp {
+    pub(crate) fn small_int_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        key: m!(HostString),
+        query: m!(HostString),
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostString: KnownType<S>,
+        HostPlacement: PlacementLoad<S, m!(HostString), m!(HostString), HostT>,
+    {
+        let z = plc.load(sess, &key, &query);
+        Ok(AbstractSmallIntTensor::Host(z))
+    }
+}
+
+impl SaveOp {
+    pub fn small_int_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        key: m!(HostString),
+        x: AbstractSmallIntTensor<HostT>,
+    ) -> Result<m!(HostUnit)>
+    where
+        HostString: KnownType<S>,
+        HostUnit: KnownType<S>,
+        HostPlacement: PlacementSave<S, m!(HostString), HostT, m!(HostUnit)>,
+    {
+        let AbstractSmallIntTensor::Host(x) = x;
+        Ok(plc.save(sess, &key, &x))
+    }
+}
+
+impl IdentityOp {
+    pub(crate) fn small_int_host_kernel<S: Session, HostT>(
+        sess: &S,
+        plc: &HostPlacement,
+        x: AbstractSmallIntTensor<HostT>,
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostPlacement: PlacementPlace<S, HostT>,
+    {
+        let AbstractSmallIntTensor::Host(x) = x;
+        Ok(AbstractSmallIntTensor::Host(plc.place(sess, x)))
+    }
+}
+
+impl CastOp {
+    pub(crate) fn small_int_float_kernel<S: Session, HostT, HostFloatT, MirFloatT>(
+        sess: &S,
+        plc: &HostPlacement,
+        x: AbstractSmallIntTensor<HostT>,
+    ) -> Result<FloatTensor<HostFloatT, MirFloatT>>
+    where
+        HostPlacement: PlacementPlace<S, HostT>,
+        HostPlacement: PlacementCast<S, HostT, HostFloatT>,
+    {
+        let AbstractSmallIntTensor::Host(x) = x;
+        let x = plc.place(sess, x);
+        Ok(FloatTensor::Host(plc.cast(sess, &x)))
+    }
+
+    pub(crate) fn float_small_int_kernel<S: Session, HostT, HostFloatT, MirFloatT>(
+        sess: &S,
+        plc: &HostPlacement,
+        x: FloatTensor<HostFloatT, MirFloatT>,
+    ) -> Result<AbstractSmallIntTensor<HostT>>
+    where
+        HostPlacement: PlacementPlace<S, HostFloatT>,
+        HostPlacement: PlacementDemirror<S, MirFloatT, HostFloatT>,
+        HostPlacement: PlacementCast<S, HostFloatT, HostT>,
+    {
+        let x = match x {
+            FloatTensor::Host(v) => plc.place(sess, v),
+            FloatTensor::Mirrored3(v) => plc.demirror(sess, &v),
+        };
+        Ok(AbstractSmallIntTensor::Host(plc.cast(sess, &x)))
+    }
+}
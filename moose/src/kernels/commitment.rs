@@ -0,0 +1,55 @@
+use super::*;
+
+pub trait PlacementCommit<S: Session, KeyT, T, O> {
+    fn commit(&self, sess: &S, nonce: &KeyT, x: &T) -> O;
+}
+
+modelled_kernel! {
+    PlacementCommit::commit, CommitOp,
+    [
+        (HostPlacement, (HostPrfKey, HostBitTensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostRing64Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostRing128Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostFloat32Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostFloat64Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostInt64Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostUint64Tensor) -> HostString => [runtime] Self::host_kernel),
+        (HostPlacement, (HostPrfKey, HostString) -> HostString => [runtime] Self::host_kernel),
+    ]
+}
+
+pub trait PlacementOpenCommitment<S: Session, CommitmentT, KeyT, T, O> {
+    fn open_commitment(&self, sess: &S, commitment: &CommitmentT, nonce: &KeyT, x: &T) -> O;
+}
+
+modelled_kernel! {
+    PlacementOpenCommitment::open_commitment, OpenCommitmentOp,
+    [
+        (HostPlacement, (HostString, HostPrfKey, HostBitTensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostRing64Tensor) -> HostRing64Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostRing128Tensor) -> HostRing128Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostFloat32Tensor) -> HostFloat32Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostFloat64Tensor) -> HostFloat64Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostInt64Tensor) -> HostInt64Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostUint64Tensor) -> HostUint64Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostString) -> HostString => [runtime] Self::host_kernel),
+    ]
+}
+
+pub trait PlacementVerifyCommitment<S: Session, CommitmentT, KeyT, T, O> {
+    fn verify_commitment(&self, sess: &S, commitment: &CommitmentT, nonce: &KeyT, x: &T) -> O;
+}
+
+modelled_kernel! {
+    PlacementVerifyCommitment::verify_commitment, VerifyCommitmentOp,
+    [
+        (HostPlacement, (HostString, HostPrfKey, HostBitTensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostRing64Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostRing128Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostFloat32Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostFloat64Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostInt64Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostUint64Tensor) -> HostBitTensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostString, HostPrfKey, HostString) -> HostBitTensor => [runtime] Self::host_kernel),
+    ]
+}
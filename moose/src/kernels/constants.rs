@@ -334,6 +334,9 @@ modelled_kernel! {
         (HostPlacement, () -> Float32Tensor => [concrete] Self::float_kernel),
         (HostPlacement, () -> Float64Tensor => [concrete] Self::float_kernel),
         (HostPlacement, () -> Uint64Tensor => [concrete] Self::u64_kernel),
+        (HostPlacement, () -> Uint8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, () -> Int8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, () -> Int16Tensor => [concrete] Self::small_int_kernel),
         (HostPlacement, () -> BooleanTensor => [concrete] Self::bool_kernel),
         (Mirrored3Placement, () -> Tensor => [concrete] custom |op| {
             let sig = op.sig;
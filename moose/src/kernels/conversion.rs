@@ -22,6 +22,18 @@ modelled_kernel! {
         (HostPlacement, (Uint64Tensor) -> BooleanTensor => [concrete] Self::u64_bool_kernel),
         (HostPlacement, (Uint64Tensor) -> Float32Tensor => [concrete] Self::u64_float_kernel),
         (HostPlacement, (Uint64Tensor) -> Float64Tensor => [concrete] Self::u64_float_kernel),
+        (HostPlacement, (Float32Tensor) -> Uint8Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Float32Tensor) -> Int8Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Float32Tensor) -> Int16Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Float64Tensor) -> Uint8Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Float64Tensor) -> Int8Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Float64Tensor) -> Int16Tensor => [concrete] Self::float_small_int_kernel),
+        (HostPlacement, (Uint8Tensor) -> Float32Tensor => [concrete] Self::small_int_float_kernel),
+        (HostPlacement, (Uint8Tensor) -> Float64Tensor => [concrete] Self::small_int_float_kernel),
+        (HostPlacement, (Int8Tensor) -> Float32Tensor => [concrete] Self::small_int_float_kernel),
+        (HostPlacement, (Int8Tensor) -> Float64Tensor => [concrete] Self::small_int_float_kernel),
+        (HostPlacement, (Int16Tensor) -> Float32Tensor => [concrete] Self::small_int_float_kernel),
+        (HostPlacement, (Int16Tensor) -> Float64Tensor => [concrete] Self::small_int_float_kernel),
         // runtime kernels
         (HostPlacement, (HostBitTensor) -> HostFloat32Tensor => [runtime] Self::from_bool_host_kernel),
         (HostPlacement, (HostBitTensor) -> HostFloat64Tensor => [runtime] Self::from_bool_host_kernel),
@@ -35,11 +47,38 @@ modelled_kernel! {
         (HostPlacement, (HostUint64Tensor) -> HostBitTensor => [runtime] Self::u64_bool_host_kernel),
         (HostPlacement, (HostUint64Tensor) -> HostFloat32Tensor => [runtime] Self::standard_host_kernel),
         (HostPlacement, (HostUint64Tensor) -> HostFloat64Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat32Tensor) -> HostUint8Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat32Tensor) -> HostInt8Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat32Tensor) -> HostInt16Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat64Tensor) -> HostUint8Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat64Tensor) -> HostInt8Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostFloat64Tensor) -> HostInt16Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostFloat32Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostFloat64Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostFloat32Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostFloat64Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostFloat32Tensor => [runtime] Self::standard_host_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostFloat64Tensor => [runtime] Self::standard_host_kernel),
         // mirrored casting
         (Mirrored3Placement, (Tensor) -> Tensor => [concrete] attributes[sig] Self::mir_kernel),
         // ring conversions
         (HostPlacement, (HostRing64Tensor) -> HostRing64Tensor => [runtime] Self::no_op_reduction_kernel),
         (HostPlacement, (HostRing64Tensor) -> HostUint64Tensor => [runtime] Self::hr64_hu64_kernel),
+        (HostPlacement, (HostRing128Tensor) -> HostUint64Tensor => [runtime] Self::hr128_hu64_kernel),
+        (HostPlacement, (HostUint64Tensor) -> HostRing64Tensor => [runtime] Self::hu64_ring_kernel),
+        (HostPlacement, (HostUint64Tensor) -> HostRing128Tensor => [runtime] Self::hu64_ring_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostRing64Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostRing128Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostRing64Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostRing128Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostRing64Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostRing128Tensor => [runtime] Self::small_int_ring_kernel),
+        (HostPlacement, (HostRing64Tensor) -> HostUint8Tensor => [runtime] Self::ring_small_int_kernel),
+        (HostPlacement, (HostRing128Tensor) -> HostUint8Tensor => [runtime] Self::ring_small_int_kernel),
+        (HostPlacement, (HostRing64Tensor) -> HostInt8Tensor => [runtime] Self::ring_small_int_kernel),
+        (HostPlacement, (HostRing128Tensor) -> HostInt8Tensor => [runtime] Self::ring_small_int_kernel),
+        (HostPlacement, (HostRing64Tensor) -> HostInt16Tensor => [runtime] Self::ring_small_int_kernel),
+        (HostPlacement, (HostRing128Tensor) -> HostInt16Tensor => [runtime] Self::ring_small_int_kernel),
         (HostPlacement, (HostRing128Tensor) -> HostRing64Tensor => [runtime] Self::ring_reduction_kernel),
         (ReplicatedPlacement, (ReplicatedRing64Tensor) -> ReplicatedRing64Tensor => [concrete] Self::rep_reduction_kernel),
         (ReplicatedPlacement, (ReplicatedRing128Tensor) -> ReplicatedRing64Tensor => [concrete] Self::rep_reduction_kernel),
@@ -222,6 +261,22 @@ modelled_kernel! {
         (HostPlacement, (Float64Tensor) -> Fixed128Tensor => [concrete] Self::fixed_kernel),
         (HostPlacement, (HostFloat32Tensor) -> HostFixed64Tensor => [hybrid] Self::hostfixed_kernel),
         (HostPlacement, (HostFloat64Tensor) -> HostFixed128Tensor => [hybrid] Self::hostfixed_kernel),
+        (HostPlacement, (Uint64Tensor) -> Fixed64Tensor => [concrete] Self::u64_fixed_kernel),
+        (HostPlacement, (Uint64Tensor) -> Fixed128Tensor => [concrete] Self::u64_fixed_kernel),
+        (HostPlacement, (HostUint64Tensor) -> HostFixed64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostUint64Tensor) -> HostFixed128Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (Uint8Tensor) -> Fixed64Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (Uint8Tensor) -> Fixed128Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (Int8Tensor) -> Fixed64Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (Int8Tensor) -> Fixed128Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (Int16Tensor) -> Fixed64Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (Int16Tensor) -> Fixed128Tensor => [concrete] Self::small_int_fixed_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostFixed64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostUint8Tensor) -> HostFixed128Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostFixed64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostInt8Tensor) -> HostFixed128Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostFixed64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostInt16Tensor) -> HostFixed128Tensor => [hybrid] Self::hostfixed_int_kernel),
         (Mirrored3Placement, (Float32Tensor) -> Fixed64Tensor => [concrete] Self::mir_fixed_kernel),
         (Mirrored3Placement, (Float64Tensor) -> Fixed128Tensor => [concrete] Self::mir_fixed_kernel),
         (Mirrored3Placement, (Mirrored3Float32) -> Mirrored3Fixed64Tensor => [hybrid] Self::mir_fixed_lower_kernel),
@@ -240,6 +295,22 @@ modelled_kernel! {
         (HostPlacement, (Fixed128Tensor) -> Float64Tensor => [concrete] Self::fixed_kernel),
         (HostPlacement, (HostFixed64Tensor) -> HostFloat32Tensor => [hybrid] Self::hostfixed_kernel),
         (HostPlacement, (HostFixed128Tensor) -> HostFloat64Tensor => [hybrid] Self::hostfixed_kernel),
+        (HostPlacement, (Fixed64Tensor) -> Uint64Tensor => [concrete] Self::fixed_u64_kernel),
+        (HostPlacement, (Fixed128Tensor) -> Uint64Tensor => [concrete] Self::fixed_u64_kernel),
+        (HostPlacement, (HostFixed64Tensor) -> HostUint64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed128Tensor) -> HostUint64Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (Fixed64Tensor) -> Uint8Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (Fixed128Tensor) -> Uint8Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (Fixed64Tensor) -> Int8Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (Fixed128Tensor) -> Int8Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (Fixed64Tensor) -> Int16Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (Fixed128Tensor) -> Int16Tensor => [concrete] Self::fixed_small_int_kernel),
+        (HostPlacement, (HostFixed64Tensor) -> HostUint8Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed128Tensor) -> HostUint8Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed64Tensor) -> HostInt8Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed128Tensor) -> HostInt8Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed64Tensor) -> HostInt16Tensor => [hybrid] Self::hostfixed_int_kernel),
+        (HostPlacement, (HostFixed128Tensor) -> HostInt16Tensor => [hybrid] Self::hostfixed_int_kernel),
         (Mirrored3Placement, (Fixed64Tensor) -> Float32Tensor => [concrete] Self::mir_fixed_kernel),
         (Mirrored3Placement, (Fixed128Tensor) -> Float64Tensor => [concrete] Self::mir_fixed_kernel),
         (Mirrored3Placement, (Mirrored3Fixed64Tensor) -> Mirrored3Float32 => [hybrid] Self::mir_fixed_lower_kernel),
@@ -47,6 +47,9 @@ modelled_kernel! {
         }),
         (HostPlacement, () -> Float32Tensor => [concrete] Self::float_kernel),
         (HostPlacement, () -> Float64Tensor => [concrete] Self::float_kernel),
+        (HostPlacement, () -> Uint8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, () -> Int8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, () -> Int16Tensor => [concrete] Self::small_int_kernel),
         (HostPlacement, () -> AesKey => [concrete] Self::aes_kernel_on_host),
         (HostPlacement, () -> HostAesKey => [concrete] Self::host_aes_kernel),
         (HostPlacement, () -> AesTensor => [concrete] Self::aestensor),
@@ -114,6 +117,9 @@ modelled_kernel! {
         (HostPlacement, (BooleanTensor) -> BooleanTensor => [hybrid] Self::bool_kernel),
         (HostPlacement, (Float32Tensor) -> Float32Tensor => [concrete] Self::float_kernel),
         (HostPlacement, (Float64Tensor) -> Float64Tensor => [concrete] Self::float_kernel),
+        (HostPlacement, (Uint8Tensor) -> Uint8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, (Int8Tensor) -> Int8Tensor => [concrete] Self::small_int_kernel),
+        (HostPlacement, (Int16Tensor) -> Int16Tensor => [concrete] Self::small_int_kernel),
     ]
 }
 
@@ -148,6 +154,9 @@ modelled_kernel! {
         (HostPlacement, (HostString, HostString) -> Float32Tensor => [hybrid] Self::float_kernel),
         (HostPlacement, (HostString, HostString) -> Float64Tensor => [hybrid] Self::float_kernel),
         (HostPlacement, (HostString, HostString) -> Uint64Tensor => [hybrid] Self::u64_kernel),
+        (HostPlacement, (HostString, HostString) -> Uint8Tensor => [hybrid] Self::small_int_kernel),
+        (HostPlacement, (HostString, HostString) -> Int8Tensor => [hybrid] Self::small_int_kernel),
+        (HostPlacement, (HostString, HostString) -> Int16Tensor => [hybrid] Self::small_int_kernel),
         (HostPlacement, (HostString, HostString) -> Tensor => [hybrid] custom |op| {
             use crate::logical::{AbstractTensor, TensorDType};
             match op.sig.ret() {
@@ -163,6 +172,15 @@ modelled_kernel! {
                 Ty::Tensor(TensorDType::Uint64) => Ok(Box::new(move |sess, plc, key, query| {
                     Self::logical_kernel::<_, Uint64Tensor>(sess, plc, key, query).map(AbstractTensor::Uint64)
                 })),
+                Ty::Tensor(TensorDType::Uint8) => Ok(Box::new(move |sess, plc, key, query| {
+                    Self::logical_kernel::<_, Uint8Tensor>(sess, plc, key, query).map(AbstractTensor::Uint8)
+                })),
+                Ty::Tensor(TensorDType::Int8) => Ok(Box::new(move |sess, plc, key, query| {
+                    Self::logical_kernel::<_, Int8Tensor>(sess, plc, key, query).map(AbstractTensor::Int8)
+                })),
+                Ty::Tensor(TensorDType::Int16) => Ok(Box::new(move |sess, plc, key, query| {
+                    Self::logical_kernel::<_, Int16Tensor>(sess, plc, key, query).map(AbstractTensor::Int16)
+                })),
                 other => {
                     Err(Error::UnimplementedOperator(
                         format!("Cannot load tensor of type {:?}", other)))
@@ -204,5 +222,8 @@ modelled_kernel! {
         (HostPlacement, (HostString, Float64Tensor) -> HostUnit => [hybrid] Self::float_kernel),
         (HostPlacement, (HostString, BooleanTensor) -> HostUnit => [hybrid] Self::bool_kernel),
         (HostPlacement, (HostString, Uint64Tensor) -> HostUnit => [hybrid] Self::u64_kernel),
+        (HostPlacement, (HostString, Uint8Tensor) -> HostUnit => [hybrid] Self::small_int_kernel),
+        (HostPlacement, (HostString, Int8Tensor) -> HostUnit => [hybrid] Self::small_int_kernel),
+        (HostPlacement, (HostString, Int16Tensor) -> HostUnit => [hybrid] Self::small_int_kernel),
     ]
 }
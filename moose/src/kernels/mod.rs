@@ -11,6 +11,7 @@ use crate::types::*;
 
 mod arithmetic;
 mod boolean;
+mod commitment;
 mod comparison;
 mod constants;
 mod control_flow;
@@ -22,6 +23,7 @@ mod shapes;
 
 pub use arithmetic::*;
 pub use boolean::*;
+pub use commitment::*;
 pub use comparison::*;
 pub use constants::*;
 pub use control_flow::*;
@@ -155,6 +157,9 @@ modelled_kernel! {
         (HostPlacement, (Float32Tensor) -> Float32Tensor => [concrete] Self::float_host_kernel),
         (HostPlacement, (Float64Tensor) -> Float64Tensor => [concrete] Self::float_host_kernel),
         (HostPlacement, (Uint64Tensor) -> Uint64Tensor => [concrete] Self::u64_host_kernel),
+        (HostPlacement, (Uint8Tensor) -> Uint8Tensor => [concrete] Self::small_int_host_kernel),
+        (HostPlacement, (Int8Tensor) -> Int8Tensor => [concrete] Self::small_int_host_kernel),
+        (HostPlacement, (Int16Tensor) -> Int16Tensor => [concrete] Self::small_int_host_kernel),
         (HostPlacement, (HostBitTensor) -> HostBitTensor => [runtime] Self::kernel),
         (HostPlacement, (HostFixed64Tensor) -> HostFixed64Tensor => [concrete] Self::host_kernel),
         (HostPlacement, (HostFixed128Tensor) -> HostFixed128Tensor => [concrete] Self::host_kernel),
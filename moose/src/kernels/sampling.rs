@@ -89,3 +89,71 @@ where
         self.sample_seeded(sess, Some(1), shape, seed)
     }
 }
+
+/// Laplace noise for differential privacy
+pub trait PlacementLaplaceNoise<S: Session, T, O> {
+    fn laplace_noise(&self, sess: &S, epsilon: f64, sensitivity: f64, x: &T) -> O;
+}
+
+modelled_kernel! {
+    PlacementLaplaceNoise::laplace_noise, LaplaceNoiseOp{epsilon: f64, sensitivity: f64},
+    [
+        (HostPlacement, (Tensor) -> Tensor => [concrete] Self::logical_host_kernel),
+        (HostPlacement, (Float32Tensor) -> Float32Tensor => [concrete] Self::float_host_kernel),
+        (HostPlacement, (Float64Tensor) -> Float64Tensor => [concrete] Self::float_host_kernel),
+        (HostPlacement, (HostFloat32Tensor) -> HostFloat32Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostFloat64Tensor) -> HostFloat64Tensor => [runtime] Self::host_kernel),
+        (ReplicatedPlacement, (Tensor) -> Tensor => [concrete] Self::logical_rep_kernel),
+        (ReplicatedPlacement, (Fixed64Tensor) -> Fixed64Tensor => [concrete] Self::fixed_rep_kernel),
+        (ReplicatedPlacement, (Fixed128Tensor) -> Fixed128Tensor => [concrete] Self::fixed_rep_kernel),
+        (ReplicatedPlacement, (ReplicatedFixed64Tensor) -> ReplicatedFixed64Tensor => [transparent] custom |op| {
+            let epsilon = op.epsilon;
+            let sensitivity = op.sensitivity;
+            Ok(Box::new(move |sess, plc, x| {
+                Self::rep_kernel::<_, _, HostFloat32Tensor, HostFixed64Tensor>(sess, plc, epsilon, sensitivity, x)
+            }))
+        }),
+        (ReplicatedPlacement, (ReplicatedFixed128Tensor) -> ReplicatedFixed128Tensor => [transparent] custom |op| {
+            let epsilon = op.epsilon;
+            let sensitivity = op.sensitivity;
+            Ok(Box::new(move |sess, plc, x| {
+                Self::rep_kernel::<_, _, HostFloat64Tensor, HostFixed128Tensor>(sess, plc, epsilon, sensitivity, x)
+            }))
+        }),
+    ]
+}
+
+/// Gaussian noise for differential privacy
+pub trait PlacementGaussianNoise<S: Session, T, O> {
+    fn gaussian_noise(&self, sess: &S, epsilon: f64, delta: f64, sensitivity: f64, x: &T) -> O;
+}
+
+modelled_kernel! {
+    PlacementGaussianNoise::gaussian_noise, GaussianNoiseOp{epsilon: f64, delta: f64, sensitivity: f64},
+    [
+        (HostPlacement, (Tensor) -> Tensor => [concrete] Self::logical_host_kernel),
+        (HostPlacement, (Float32Tensor) -> Float32Tensor => [concrete] Self::float_host_kernel),
+        (HostPlacement, (Float64Tensor) -> Float64Tensor => [concrete] Self::float_host_kernel),
+        (HostPlacement, (HostFloat32Tensor) -> HostFloat32Tensor => [runtime] Self::host_kernel),
+        (HostPlacement, (HostFloat64Tensor) -> HostFloat64Tensor => [runtime] Self::host_kernel),
+        (ReplicatedPlacement, (Tensor) -> Tensor => [concrete] Self::logical_rep_kernel),
+        (ReplicatedPlacement, (Fixed64Tensor) -> Fixed64Tensor => [concrete] Self::fixed_rep_kernel),
+        (ReplicatedPlacement, (Fixed128Tensor) -> Fixed128Tensor => [concrete] Self::fixed_rep_kernel),
+        (ReplicatedPlacement, (ReplicatedFixed64Tensor) -> ReplicatedFixed64Tensor => [transparent] custom |op| {
+            let epsilon = op.epsilon;
+            let delta = op.delta;
+            let sensitivity = op.sensitivity;
+            Ok(Box::new(move |sess, plc, x| {
+                Self::rep_kernel::<_, _, HostFloat32Tensor, HostFixed64Tensor>(sess, plc, epsilon, delta, sensitivity, x)
+            }))
+        }),
+        (ReplicatedPlacement, (ReplicatedFixed128Tensor) -> ReplicatedFixed128Tensor => [transparent] custom |op| {
+            let epsilon = op.epsilon;
+            let delta = op.delta;
+            let sensitivity = op.sensitivity;
+            Ok(Box::new(move |sess, plc, x| {
+                Self::rep_kernel::<_, _, HostFloat64Tensor, HostFixed128Tensor>(sess, plc, epsilon, delta, sensitivity, x)
+            }))
+        }),
+    ]
+}
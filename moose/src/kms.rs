@@ -0,0 +1,363 @@
+//! Providers of keys that should not be stored in plaintext on workers.
+//!
+//! Keys are looked up by name, such as the name of a TLS private key or of the
+//! argument holding an AES key within `AES_KEY_NAMESPACE`, from one of the
+//! following providers:
+//!
+//! - `FileKeyProvider` reads keys from files in a directory, for instance one
+//!   mounted in memory by a secrets manager;
+//! - `VaultKeyProvider` reads keys from the key/value secrets engine of
+//!   HashiCorp Vault, requiring the `vault` feature;
+//! - `AwsKmsKeyProvider` reads keys encrypted by AWS KMS from files in a
+//!   directory and decrypts them using KMS, requiring the `aws-kms` feature.
+//!
+//! Providers can be created from URIs using `key_provider_from_uri`.
+
+use crate::computation::{Computation, Operator, Placement, Role, Ty, Value};
+use crate::error::{Error, Result};
+use crate::host::{FromRaw, HostBitTensor, HostPlacement};
+use async_trait::async_trait;
+use ndarray::Array;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait KeyProvider {
+    /// Key stored under `name`.
+    async fn get_key(&self, name: &str) -> Result<Vec<u8>>;
+}
+
+pub type KeyProviderImpl = Arc<dyn KeyProvider + Send + Sync>;
+
+fn key_error(name: &str, e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("failed to get key '{}': {}", name, e))
+}
+
+/// Reject names that could refer to keys outside of the provider's namespace.
+///
+/// Names may consist of several `/`-separated components, such as the names of
+/// AES keys in `AES_KEY_NAMESPACE`.
+fn check_key_name(name: &str) -> Result<()> {
+    let is_invalid = |component: &str| {
+        component.is_empty() || component.starts_with('.') || component.contains('\\')
+    };
+    if name.split('/').any(is_invalid) {
+        return Err(key_error(name, "invalid key name"));
+    }
+    Ok(())
+}
+
+/// Namespace of the keys that computations may take as AES key arguments.
+///
+/// Arguments are named by the driver, so only keys below this namespace are
+/// provided for them; other keys, such as TLS and storage keys, are never
+/// handed to computations.
+pub const AES_KEY_NAMESPACE: &str = "aes/";
+
+/// Keys stored in files named after them.
+pub struct FileKeyProvider {
+    dir: PathBuf,
+}
+
+impl FileKeyProvider {
+    pub fn new(dir: PathBuf) -> Self {
+        FileKeyProvider { dir }
+    }
+}
+
+#[async_trait]
+impl KeyProvider for FileKeyProvider {
+    async fn get_key(&self, name: &str) -> Result<Vec<u8>> {
+        check_key_name(name)?;
+        tokio::fs::read(self.dir.join(name))
+            .await
+            .map_err(|e| key_error(name, e))
+    }
+}
+
+/// Keys stored base64-encoded in the `value` field of secrets of a Vault KV
+/// version 2 secrets engine.
+#[cfg(feature = "vault")]
+pub struct VaultKeyProvider {
+    address: String,
+    mount: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "vault")]
+impl VaultKeyProvider {
+    /// Provider for the secrets engine mounted at `mount` of the Vault server at `address`.
+    pub fn new(address: &str, mount: &str, token: String) -> Self {
+        VaultKeyProvider {
+            address: address.trim_end_matches('/').to_string(),
+            mount: mount.trim_matches('/').to_string(),
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Like `new` but authenticating with the token given by `VAULT_TOKEN`.
+    pub fn from_env(address: &str, mount: &str) -> Result<Self> {
+        let token = std::env::var("VAULT_TOKEN").map_err(|_| {
+            Error::InvalidArgument("VAULT_TOKEN must be set to read keys from Vault".to_string())
+        })?;
+        Ok(VaultKeyProvider::new(address, mount, token))
+    }
+}
+
+#[cfg(feature = "vault")]
+#[async_trait]
+impl KeyProvider for VaultKeyProvider {
+    async fn get_key(&self, name: &str) -> Result<Vec<u8>> {
+        check_key_name(name)?;
+        let url = format!("{}/v1/{}/data/{}", self.address, self.mount, name);
+        let secret: serde_json::Value = self
+            .client
+            .get(url)
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| key_error(name, e))?
+            .json()
+            .await
+            .map_err(|e| key_error(name, e))?;
+        let value = secret["data"]["data"]["value"]
+            .as_str()
+            .ok_or_else(|| key_error(name, "secret has no 'value' field"))?;
+        base64::decode(value).map_err(|e| key_error(name, e))
+    }
+}
+
+/// Keys stored in files named after them, encrypted under a KMS key.
+#[cfg(feature = "aws-kms")]
+pub struct AwsKmsKeyProvider {
+    dir: PathBuf,
+    client: aws_sdk_kms::Client,
+}
+
+#[cfg(feature = "aws-kms")]
+impl AwsKmsKeyProvider {
+    /// Provider using the AWS credentials and region of the environment.
+    pub async fn from_env(dir: PathBuf) -> Self {
+        let config = aws_config::load_from_env().await;
+        AwsKmsKeyProvider {
+            dir,
+            client: aws_sdk_kms::Client::new(&config),
+        }
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+#[async_trait]
+impl KeyProvider for AwsKmsKeyProvider {
+    async fn get_key(&self, name: &str) -> Result<Vec<u8>> {
+        check_key_name(name)?;
+        let ciphertext = tokio::fs::read(self.dir.join(name))
+            .await
+            .map_err(|e| key_error(name, e))?;
+        // symmetric ciphertexts identify the KMS key they were encrypted under
+        let decrypted = self
+            .client
+            .decrypt()
+            .ciphertext_blob(aws_sdk_kms::primitives::Blob::new(ciphertext))
+            .send()
+            .await
+            .map_err(|e| key_error(name, aws_sdk_kms::error::DisplayErrorContext(e)))?;
+        decrypted
+            .plaintext()
+            .map(|plaintext| plaintext.as_ref().to_vec())
+            .ok_or_else(|| key_error(name, "KMS returned no plaintext"))
+    }
+}
+
+/// Provider given by `uri`.
+///
+/// Supported are `file://<dir>`, `vault+http://<host>:<port>/<mount>` and
+/// `vault+https://<host>:<port>/<mount>` authenticating with `VAULT_TOKEN`, and
+/// `aws-kms://<dir>`.
+pub async fn key_provider_from_uri(uri: &str) -> Result<KeyProviderImpl> {
+    let (scheme, rest) = uri.split_once("://").ok_or_else(|| {
+        Error::InvalidArgument(format!("expected a key provider URI, got '{}'", uri))
+    })?;
+    match scheme {
+        "file" => Ok(Arc::new(FileKeyProvider::new(PathBuf::from(rest)))),
+        #[cfg(feature = "vault")]
+        "vault+http" | "vault+https" => {
+            let (authority, mount) = rest.split_once('/').ok_or_else(|| {
+                Error::InvalidArgument(format!("missing secrets engine mount in '{}'", uri))
+            })?;
+            let address = format!("{}://{}", &scheme["vault+".len()..], authority);
+            Ok(Arc::new(VaultKeyProvider::from_env(&address, mount)?))
+        }
+        #[cfg(feature = "aws-kms")]
+        "aws-kms" => Ok(Arc::new(
+            AwsKmsKeyProvider::from_env(PathBuf::from(rest)).await,
+        )),
+        _ => Err(Error::InvalidArgument(format!(
+            "unsupported key provider '{}'",
+            scheme
+        ))),
+    }
+}
+
+/// Fetch AES keys input on host placements of `own_roles` but missing from `arguments`.
+///
+/// Each key is fetched from `keys` under the name of its argument within
+/// `AES_KEY_NAMESPACE` and must consist of 16 bytes. Computations are rejected
+/// if they output, save, or send a provided key as is.
+pub async fn provide_aes_keys(
+    keys: &(dyn KeyProvider + Send + Sync),
+    computation: &Computation,
+    own_roles: &[Role],
+    arguments: &mut HashMap<String, Value>,
+) -> Result<()> {
+    let mut provided: HashMap<String, (String, HostPlacement)> = HashMap::new();
+    for op in computation.operations.iter() {
+        let (input, plc) = match (&op.kind, &op.placement) {
+            (Operator::Input(input), Placement::Host(plc)) => (input, plc),
+            _ => continue,
+        };
+        let is_key = matches!(input.sig.ret(), Ty::AesKey | Ty::HostAesKey);
+        if !is_key || !own_roles.contains(&plc.owner) || arguments.contains_key(&input.arg_name) {
+            continue;
+        }
+        provided.insert(op.name.clone(), (input.arg_name.clone(), plc.clone()));
+    }
+    if provided.is_empty() {
+        return Ok(());
+    }
+    check_key_flows(computation, provided.keys().cloned().collect())?;
+
+    for (arg_name, plc) in provided.into_values() {
+        let name = format!("{}{}", AES_KEY_NAMESPACE, arg_name);
+        let key = keys.get_key(&name).await?;
+        if key.len() != 16 {
+            return Err(key_error(
+                &name,
+                format!("expected 16 bytes of an AES-128 key, found {}", key.len()),
+            ));
+        }
+        let bits = crate::bristol_fashion::byte_vec_to_bit_vec_be(&key);
+        let bits = Array::from_shape_vec((128, 1), bits).map_err(|e| key_error(&name, e))?;
+        let key: HostBitTensor = plc.from_raw(bits);
+        arguments.insert(arg_name, Value::from(key));
+    }
+    Ok(())
+}
+
+/// Reject computations passing any of the `keys` operations, possibly through
+/// identities and casts, to an output, save, or send.
+fn check_key_flows(computation: &Computation, mut keys: HashSet<String>) -> Result<()> {
+    // operations are only required to be topologically sorted once compiled
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for op in computation.operations.iter() {
+            let is_forwarding = matches!(op.kind, Operator::Identity(_) | Operator::Cast(_));
+            if is_forwarding
+                && !keys.contains(&op.name)
+                && op.inputs.iter().any(|input| keys.contains(input))
+            {
+                keys.insert(op.name.clone());
+                changed = true;
+            }
+        }
+    }
+
+    for op in computation.operations.iter() {
+        let is_sink = matches!(
+            op.kind,
+            Operator::Output(_) | Operator::Save(_) | Operator::Send(_)
+        );
+        if let Some(key) = op.inputs.iter().find(|input| keys.contains(*input)) {
+            if is_sink {
+                return Err(Error::InvalidArgument(format!(
+                    "operation '{}' would reveal the provided key '{}'",
+                    op.name, key
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_provide_aes_keys() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("aes")).unwrap();
+        std::fs::write(dir.path().join("aes/key"), [201; 16]).unwrap();
+        std::fs::write(dir.path().join("aes/short_key"), [201; 8]).unwrap();
+        std::fs::write(dir.path().join("tls.key"), [202; 16]).unwrap();
+        let keys = FileKeyProvider::new(dir.path().to_path_buf());
+        assert!(keys.get_key("../key").await.is_err());
+        assert!(keys.get_key("aes/../tls.key").await.is_err());
+
+        let computation: Computation = r#"
+        key = Input{arg_name = "key"}: () -> HostAesKey () @Host(alice)
+        short_key = Input{arg_name = "short_key"}: () -> HostAesKey () @Host(bob)
+        "#
+        .try_into()?;
+        let mut arguments = HashMap::new();
+        provide_aes_keys(&keys, &computation, &[Role::from("alice")], &mut arguments).await?;
+        match arguments.get("key") {
+            Some(Value::HostBitTensor(key)) => assert_eq!(key.0.shape(), vec![128, 1]),
+            other => panic!("expected a bit tensor, got {:?}", other),
+        }
+
+        let result =
+            provide_aes_keys(&keys, &computation, &[Role::from("bob")], &mut arguments).await;
+        assert!(result.is_err());
+
+        // keys outside of the namespace are not provided
+        let computation: Computation = r#"
+        key = Input{arg_name = "tls.key"}: () -> HostAesKey () @Host(alice)
+        "#
+        .try_into()?;
+        let result = provide_aes_keys(
+            &keys,
+            &computation,
+            &[Role::from("alice")],
+            &mut HashMap::new(),
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reject_revealed_keys() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("aes")).unwrap();
+        std::fs::write(dir.path().join("aes/key"), [201; 16]).unwrap();
+        let keys = FileKeyProvider::new(dir.path().to_path_buf());
+        let alice = [Role::from("alice")];
+
+        let computation: Computation = r#"
+        key = Input{arg_name = "key"}: () -> HostAesKey () @Host(alice)
+        key_copy = Identity: (HostAesKey) -> HostAesKey (key) @Host(alice)
+        output = Output{tag = "output_0"}: (HostAesKey) -> HostAesKey (key_copy) @Host(alice)
+        "#
+        .try_into()?;
+        let mut arguments = HashMap::new();
+        let result = provide_aes_keys(&keys, &computation, &alice, &mut arguments).await;
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        assert!(arguments.is_empty());
+
+        let computation: Computation = r#"
+        key = Input{arg_name = "key"}: () -> HostAesKey () @Host(alice)
+        send = Send{rendezvous_key = 30303030303030303030303030303030, receiver = "bob"}: (HostAesKey) -> HostUnit (key) @Host(alice)
+        "#
+        .try_into()?;
+        let result = provide_aes_keys(&keys, &computation, &alice, &mut arguments).await;
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        Ok(())
+    }
+}
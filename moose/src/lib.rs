@@ -2773,8 +2773,13 @@ pub(crate) trait MirroredCounterpart {
 }
 
 pub mod additive;
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod boolean;
 pub mod bristol_fashion;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "async_execute")]
 pub mod choreography;
 #[cfg(feature = "compile")]
 pub mod compilation;
@@ -2784,19 +2789,30 @@ pub mod error; // TODO make non-pub
 pub mod execution;
 pub mod fixedpoint;
 pub mod floatingpoint;
-mod grpc;
+pub mod fuzzing;
+#[cfg(feature = "async_execute")]
+pub mod grpc;
 pub mod host;
+mod importer;
 pub mod integer;
 pub mod kernels;
+#[cfg(feature = "async_execute")]
+pub mod kms;
 pub mod logical;
 pub mod mirrored;
 pub mod networking;
+pub mod onnx;
 pub mod prelude;
+#[cfg(feature = "async_execute")]
 pub mod reindeer;
 pub mod replicated;
 pub mod storage;
+pub mod tensorflow;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod textual;
 pub mod types;
+pub mod xgboost;
 
 pub use error::{Error, Result};
 pub use tokio;
@@ -30,6 +30,9 @@ pub enum TensorDType {
     Float64,
     Bool,
     Uint64,
+    Uint8,
+    Int8,
+    Int16,
     Unknown,
 }
 
@@ -51,6 +54,9 @@ impl HasShortName for TensorDType {
             TensorDType::Float64 => "Float64",
             TensorDType::Bool => "Bool",
             TensorDType::Uint64 => "Uint64",
+            TensorDType::Uint8 => "Uint8",
+            TensorDType::Int8 => "Int8",
+            TensorDType::Int16 => "Int16",
             TensorDType::Unknown => "Unknown",
         }
     }
@@ -69,23 +75,75 @@ impl HasShortName for TensorShape {
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
-pub enum AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T> {
+pub enum AbstractTensor<
+    Fixed64T,
+    Fixed128T,
+    Float32T,
+    Float64T,
+    BoolT,
+    Uint64T,
+    Uint8T,
+    Int8T,
+    Int16T,
+> {
     Fixed64(Fixed64T),
     Fixed128(Fixed128T),
     Float32(Float32T),
     Float64(Float64T),
     Bool(BoolT),
     Uint64(Uint64T),
+    Uint8(Uint8T),
+    Int8(Int8T),
+    Int16(Int16T),
 }
 
+/// Result of kernels computing logical tensors.
+pub(crate) type AbstractTensorResult<
+    Fixed64T,
+    Fixed128T,
+    Float32T,
+    Float64T,
+    BoolT,
+    Uint64T,
+    Uint8T,
+    Int8T,
+    Int16T,
+> = Result<
+    AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T>,
+>;
+
+/// Operands of variadic kernels on logical tensors.
+pub(crate) type AbstractTensorSlice<
+    'a,
+    Fixed64T,
+    Fixed128T,
+    Float32T,
+    Float64T,
+    BoolT,
+    Uint64T,
+    Uint8T,
+    Int8T,
+    Int16T,
+> = &'a [AbstractTensor<
+    Fixed64T,
+    Fixed128T,
+    Float32T,
+    Float64T,
+    BoolT,
+    Uint64T,
+    Uint8T,
+    Int8T,
+    Int16T,
+>];
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum AbstractShape<HostS, RepS> {
     Host(HostS),
     Replicated(RepS),
 }
 
-impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
-    AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
+impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T>
+    AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T>
 {
     pub(crate) fn ty_desc(&self) -> String {
         match self {
@@ -95,13 +153,26 @@ impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
             AbstractTensor::Float64(_) => "Tensor(Float64)",
             AbstractTensor::Bool(_) => "Tensor(Bool)",
             AbstractTensor::Uint64(_) => "Tensor(Uint64T)",
+            AbstractTensor::Uint8(_) => "Tensor(Uint8)",
+            AbstractTensor::Int8(_) => "Tensor(Int8)",
+            AbstractTensor::Int16(_) => "Tensor(Int16)",
         }
         .to_string()
     }
 }
 
-impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T> Placed
-    for AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
+impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T> Placed
+    for AbstractTensor<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
 where
     Fixed64T: Placed,
     Fixed64T::Placement: Into<Placement>,
@@ -115,6 +186,12 @@ where
     BoolT::Placement: Into<Placement>,
     Uint64T: Placed,
     Uint64T::Placement: Into<Placement>,
+    Uint8T: Placed,
+    Uint8T::Placement: Into<Placement>,
+    Int8T: Placed,
+    Int8T::Placement: Into<Placement>,
+    Int16T: Placed,
+    Int16T::Placement: Into<Placement>,
 {
     type Placement = Placement;
 
@@ -126,6 +203,9 @@ where
             AbstractTensor::Float64(x) => Ok(x.placement()?.into()),
             AbstractTensor::Bool(x) => Ok(x.placement()?.into()),
             AbstractTensor::Uint64(x) => Ok(x.placement()?.into()),
+            AbstractTensor::Uint8(x) => Ok(x.placement()?.into()),
+            AbstractTensor::Int8(x) => Ok(x.placement()?.into()),
+            AbstractTensor::Int16(x) => Ok(x.placement()?.into()),
         }
     }
 }
@@ -140,13 +220,40 @@ impl PartiallySymbolicType for Tensor {
         <Float64Tensor as SymbolicType>::Type,
         <BooleanTensor as SymbolicType>::Type,
         <Uint64Tensor as SymbolicType>::Type,
+        <Uint8Tensor as SymbolicType>::Type,
+        <Int8Tensor as SymbolicType>::Type,
+        <Int16Tensor as SymbolicType>::Type,
     >;
 }
 
 #[cfg(feature = "compile")]
-impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
-    From<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
-    for Symbolic<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T>
+    From<
+        AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    >
+    for Symbolic<
+        AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    >
 where
     Fixed64T: Placed<Placement = Placement>,
     Fixed128T: Placed<Placement = Placement>,
@@ -154,16 +261,55 @@ where
     Float64T: Placed<Placement = Placement>,
     BoolT: Placed<Placement = Placement>,
     Uint64T: Placed<Placement = Placement>,
+    Uint8T: Placed<Placement = Placement>,
+    Int8T: Placed<Placement = Placement>,
+    Int16T: Placed<Placement = Placement>,
 {
-    fn from(x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>) -> Self {
+    fn from(
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> Self {
         Symbolic::Concrete(x)
     }
 }
 
 #[cfg(feature = "compile")]
-impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
-    TryFrom<Symbolic<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>>
-    for AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>
+impl<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T, Uint8T, Int8T, Int16T>
+    TryFrom<
+        Symbolic<
+            AbstractTensor<
+                Fixed64T,
+                Fixed128T,
+                Float32T,
+                Float64T,
+                BoolT,
+                Uint64T,
+                Uint8T,
+                Int8T,
+                Int16T,
+            >,
+        >,
+    >
+    for AbstractTensor<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
 where
     Fixed64T: Placed<Placement = Placement>,
     Fixed128T: Placed<Placement = Placement>,
@@ -171,10 +317,25 @@ where
     Float64T: Placed<Placement = Placement>,
     BoolT: Placed<Placement = Placement>,
     Uint64T: Placed<Placement = Placement>,
+    Uint8T: Placed<Placement = Placement>,
+    Int8T: Placed<Placement = Placement>,
+    Int16T: Placed<Placement = Placement>,
 {
     type Error = ();
     fn try_from(
-        v: Symbolic<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>,
+        v: Symbolic<
+            AbstractTensor<
+                Fixed64T,
+                Fixed128T,
+                Float32T,
+                Float64T,
+                BoolT,
+                Uint64T,
+                Uint8T,
+                Int8T,
+                Int16T,
+            >,
+        >,
     ) -> std::result::Result<Self, ()> {
         match v {
             Symbolic::Concrete(x) => Ok(x),
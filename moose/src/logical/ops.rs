@@ -16,11 +16,34 @@ impl IdentityOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementIdentity<S, Fixed64T, Fixed64T>,
         HostPlacement: PlacementIdentity<S, Fixed128T, Fixed128T>,
@@ -28,6 +51,9 @@ impl IdentityOp {
         HostPlacement: PlacementIdentity<S, Float64T, Float64T>,
         HostPlacement: PlacementIdentity<S, BoolT, BoolT>,
         HostPlacement: PlacementIdentity<S, Uint64T, Uint64T>,
+        HostPlacement: PlacementIdentity<S, Uint8T, Uint8T>,
+        HostPlacement: PlacementIdentity<S, Int8T, Int8T>,
+        HostPlacement: PlacementIdentity<S, Int16T, Int16T>,
     {
         use AbstractTensor::*;
         match x {
@@ -55,6 +81,18 @@ impl IdentityOp {
                 let result = plc.identity(sess, &x);
                 Ok(AbstractTensor::Uint64(result))
             }
+            Uint8(x) => {
+                let result = plc.identity(sess, &x);
+                Ok(AbstractTensor::Uint8(result))
+            }
+            Int8(x) => {
+                let result = plc.identity(sess, &x);
+                Ok(AbstractTensor::Int8(result))
+            }
+            Int16(x) => {
+                let result = plc.identity(sess, &x);
+                Ok(AbstractTensor::Int16(result))
+            }
         }
     }
 
@@ -66,11 +104,34 @@ impl IdentityOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         rep: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementIdentity<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementIdentity<S, Fixed128T, Fixed128T>,
@@ -85,9 +146,12 @@ impl IdentityOp {
                 let result = rep.identity(sess, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing rep identity op for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing rep identity op for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -101,12 +165,45 @@ impl AddOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementAdd<S, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementAdd<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -136,6 +233,9 @@ impl AddOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host add op for {:?} and {:?}",
                 x.ty_desc(),
@@ -152,12 +252,45 @@ impl AddOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementAdd<S, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementAdd<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -177,6 +310,9 @@ impl AddOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated add op for {:?} and {:?}",
                 x.ty_desc(),
@@ -195,11 +331,34 @@ impl AbsOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementAbs<S, Float32T, Float32T>,
         HostPlacement: PlacementAbs<S, Float64T, Float64T>,
@@ -229,11 +388,34 @@ impl AbsOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementAbs<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementAbs<S, Fixed128T, Fixed128T>,
@@ -265,11 +447,34 @@ impl ReluOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementRelu<S, Float32T, Float32T>,
         HostPlacement: PlacementRelu<S, Float64T, Float64T>,
@@ -297,11 +502,34 @@ impl ReluOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementRelu<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementRelu<S, Fixed128T, Fixed128T>,
@@ -332,11 +560,35 @@ impl AddNOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        xs: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        xs: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementAddN<S, Fixed64T, Fixed64T>,
         HostPlacement: PlacementAddN<S, Fixed128T, Fixed128T>,
@@ -399,10 +651,12 @@ impl AddNOp {
                     let result = plc.add_n(sess, &vec);
                     Ok(Float64(result))
                 }
-                Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                    "Missing host add_n op for {:?}",
-                    &x.ty_desc(),
-                ))),
+                Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                    Err(Error::UnimplementedOperator(format!(
+                        "Missing host add_n op for {:?}",
+                        &x.ty_desc(),
+                    )))
+                }
             }
         }
     }
@@ -415,11 +669,35 @@ impl AddNOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        xs: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        xs: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementAddN<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementAddN<S, Fixed128T, Fixed128T>,
@@ -456,9 +734,12 @@ impl AddNOp {
                     let result = plc.add_n(sess, &vec);
                     Ok(Fixed128(result))
                 }
-                Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                    format!("Missing replicated add_n op for {:?}", &x.ty_desc(),),
-                )),
+                Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                    Err(Error::UnimplementedOperator(format!(
+                        "Missing replicated add_n op for {:?}",
+                        &x.ty_desc(),
+                    )))
+                }
             }
         }
     }
@@ -473,12 +754,45 @@ impl SubOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSub<S, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementSub<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -508,6 +822,9 @@ impl SubOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host sub op for {:?} and {:?}",
                 x.ty_desc(),
@@ -516,12 +833,53 @@ impl SubOp {
         }
     }
 
-    pub(crate) fn rep_kernel<S: Session, Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>(
+    pub(crate) fn rep_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSub<S, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementSub<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -541,6 +899,9 @@ impl SubOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated sub op for {:?} and {:?}",
                 x.ty_desc(),
@@ -559,13 +920,46 @@ impl MulOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementMul<S, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementMul<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -613,6 +1007,9 @@ impl MulOp {
             | (AbstractTensor::Float32(_), _)
             | (AbstractTensor::Float64(_), _)
             | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
             | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host mul op for {:?} and {:?}",
                 x.ty_desc(),
@@ -629,13 +1026,46 @@ impl MulOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementMul<S, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementMul<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -670,6 +1100,9 @@ impl MulOp {
             | (AbstractTensor::Float32(_), _)
             | (AbstractTensor::Float64(_), _)
             | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
             | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated mul op for {:?} and {:?}",
                 x.ty_desc(),
@@ -688,12 +1121,45 @@ impl DivOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementDiv<S, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementDiv<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -723,6 +1189,9 @@ impl DivOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host div op for {:?} and {:?}",
                 x.ty_desc(),
@@ -739,12 +1208,45 @@ impl DivOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementDiv<S, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementDiv<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -764,6 +1266,9 @@ impl DivOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated div for {:?} and {:?}",
                 x.ty_desc(),
@@ -782,13 +1287,46 @@ impl DotOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementDot<S, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementDot<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -834,6 +1372,9 @@ impl DotOp {
             | (AbstractTensor::Float32(_), _)
             | (AbstractTensor::Float64(_), _)
             | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
             | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host dot op for {:?} and {:?}",
                 x.ty_desc(),
@@ -850,13 +1391,46 @@ impl DotOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementDot<S, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementDot<S, Fixed128T, Fixed128T, Fixed128T>,
@@ -891,6 +1465,9 @@ impl DotOp {
             | (AbstractTensor::Float32(_), _)
             | (AbstractTensor::Float64(_), _)
             | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
             | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated dot op for {:?} and {:?}",
                 x.ty_desc(),
@@ -900,6 +1477,174 @@ impl DotOp {
     }
 }
 
+impl Conv2DOp {
+    pub(crate) fn logical_host_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        stride: usize,
+        padding: usize,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        filter: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        HostPlacement: PlacementConv2D<S, Fixed64T, Fixed64T, Fixed64T>,
+        HostPlacement: PlacementConv2D<S, Fixed128T, Fixed128T, Fixed128T>,
+        HostPlacement: PlacementConv2D<S, Float32T, Float32T, Float32T>,
+        HostPlacement: PlacementConv2D<S, Float64T, Float64T, Float64T>,
+    {
+        match (&x, &filter) {
+            (AbstractTensor::Fixed64(x), AbstractTensor::Fixed64(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Fixed64(result))
+            }
+            (AbstractTensor::Fixed128(x), AbstractTensor::Fixed128(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Fixed128(result))
+            }
+            (AbstractTensor::Float32(x), AbstractTensor::Float32(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Float32(result))
+            }
+            (AbstractTensor::Float64(x), AbstractTensor::Float64(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Float64(result))
+            }
+            (AbstractTensor::Fixed64(_), _)
+            | (AbstractTensor::Fixed128(_), _)
+            | (AbstractTensor::Float32(_), _)
+            | (AbstractTensor::Float64(_), _)
+            | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
+            | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
+                "Missing host conv2d op for {:?} and {:?}",
+                x.ty_desc(),
+                filter.ty_desc()
+            ))),
+        }
+    }
+
+    pub(crate) fn logical_rep_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        stride: usize,
+        padding: usize,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        filter: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        ReplicatedPlacement: PlacementConv2D<S, Fixed64T, Fixed64T, Fixed64T>,
+        ReplicatedPlacement: PlacementConv2D<S, Fixed128T, Fixed128T, Fixed128T>,
+    {
+        match (&x, &filter) {
+            (AbstractTensor::Fixed64(x), AbstractTensor::Fixed64(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Fixed64(result))
+            }
+            (AbstractTensor::Fixed128(x), AbstractTensor::Fixed128(filter)) => {
+                let result = plc.conv2d(sess, stride, padding, x, filter);
+                Ok(AbstractTensor::Fixed128(result))
+            }
+            (AbstractTensor::Fixed64(_), _)
+            | (AbstractTensor::Fixed128(_), _)
+            | (AbstractTensor::Float32(_), _)
+            | (AbstractTensor::Float64(_), _)
+            | (AbstractTensor::Uint64(_), _)
+            | (AbstractTensor::Uint8(_), _)
+            | (AbstractTensor::Int8(_), _)
+            | (AbstractTensor::Int16(_), _)
+            | (AbstractTensor::Bool(_), _) => Err(Error::UnimplementedOperator(format!(
+                "Missing replicated conv2d op for {:?} and {:?}",
+                x.ty_desc(),
+                filter.ty_desc()
+            ))),
+        }
+    }
+}
+
 impl LessOp {
     pub(crate) fn logical_host_kernel<
         S: Session,
@@ -909,12 +1654,45 @@ impl LessOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementLess<S, Fixed64T, Fixed64T, BoolT>,
         HostPlacement: PlacementLess<S, Fixed128T, Fixed128T, BoolT>,
@@ -944,6 +1722,9 @@ impl LessOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host less op for {:?} and {:?}",
                 x.ty_desc(),
@@ -960,12 +1741,45 @@ impl LessOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementLess<S, Fixed64T, Fixed64T, BoolT>,
         ReplicatedPlacement: PlacementLess<S, Fixed128T, Fixed128T, BoolT>,
@@ -985,6 +1799,9 @@ impl LessOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host less op for {:?} and {:?}",
                 x.ty_desc(),
@@ -1003,12 +1820,45 @@ impl GreaterOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementGreater<S, Fixed64T, Fixed64T, BoolT>,
         HostPlacement: PlacementGreater<S, Fixed128T, Fixed128T, BoolT>,
@@ -1038,6 +1888,9 @@ impl GreaterOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host greater op for {:?} and {:?}",
                 x.ty_desc(),
@@ -1054,12 +1907,45 @@ impl GreaterOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementGreater<S, Fixed64T, Fixed64T, BoolT>,
         ReplicatedPlacement: PlacementGreater<S, Fixed128T, Fixed128T, BoolT>,
@@ -1079,6 +1965,9 @@ impl GreaterOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host greater op for {:?} and {:?}",
                 x.ty_desc(),
@@ -1097,13 +1986,56 @@ impl MuxOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        s: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        s: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementMux<S, BoolT, Fixed64T, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementMux<S, BoolT, Fixed128T, Fixed128T, Fixed128T>,
@@ -1123,6 +2055,9 @@ impl MuxOp {
             | (Float32(_), _, _)
             | (Float64(_), _, _)
             | (Uint64(_), _, _)
+            | (Uint8(_), _, _)
+            | (Int8(_), _, _)
+            | (Int16(_), _, _)
             | (Bool(_), _, _) => Err(Error::UnimplementedOperator(format!(
                 "Missing replicated mux op for {:?}, {:?} and {:?}",
                 s.ty_desc(),
@@ -1140,13 +2075,56 @@ impl MuxOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        s: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        s: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementMux<S, BoolT, Fixed64T, Fixed64T, Fixed64T>,
         HostPlacement: PlacementMux<S, BoolT, Fixed128T, Fixed128T, Fixed128T>,
@@ -1176,6 +2154,9 @@ impl MuxOp {
             | (Float32(_), _, _)
             | (Float64(_), _, _)
             | (Uint64(_), _, _)
+            | (Uint8(_), _, _)
+            | (Int8(_), _, _)
+            | (Int16(_), _, _)
             | (Bool(_), _, _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host mux op for {:?}, {:?} and {:?}",
                 s.ty_desc(),
@@ -1187,12 +2168,43 @@ impl MuxOp {
 }
 
 impl CastOp {
-    pub(crate) fn kernel<S: Session, Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>(
+    pub(crate) fn kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementCast<S, BoolT, Float32T>,
         HostPlacement: PlacementCast<S, BoolT, Float64T>,
@@ -1206,10 +2218,38 @@ impl CastOp {
         HostPlacement: PlacementCast<S, Uint64T, BoolT>,
         HostPlacement: PlacementCast<S, Uint64T, Float32T>,
         HostPlacement: PlacementCast<S, Uint64T, Float64T>,
+        HostPlacement: PlacementCast<S, Float32T, Uint8T>,
+        HostPlacement: PlacementCast<S, Float64T, Uint8T>,
+        HostPlacement: PlacementCast<S, Uint8T, Float32T>,
+        HostPlacement: PlacementCast<S, Uint8T, Float64T>,
+        HostPlacement: PlacementCast<S, Float32T, Int8T>,
+        HostPlacement: PlacementCast<S, Float64T, Int8T>,
+        HostPlacement: PlacementCast<S, Int8T, Float32T>,
+        HostPlacement: PlacementCast<S, Int8T, Float64T>,
+        HostPlacement: PlacementCast<S, Float32T, Int16T>,
+        HostPlacement: PlacementCast<S, Float64T, Int16T>,
+        HostPlacement: PlacementCast<S, Int16T, Float32T>,
+        HostPlacement: PlacementCast<S, Int16T, Float64T>,
         HostPlacement: PlacementFixedpointDecode<S, Fixed64T, Float32T>,
         HostPlacement: PlacementFixedpointDecode<S, Fixed128T, Float64T>,
         HostPlacement: PlacementFixedpointEncode<S, Float32T, Fixed64T>,
         HostPlacement: PlacementFixedpointEncode<S, Float64T, Fixed128T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed64T, Uint64T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed128T, Uint64T>,
+        HostPlacement: PlacementFixedpointEncode<S, Uint64T, Fixed64T>,
+        HostPlacement: PlacementFixedpointEncode<S, Uint64T, Fixed128T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed64T, Uint8T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed128T, Uint8T>,
+        HostPlacement: PlacementFixedpointEncode<S, Uint8T, Fixed64T>,
+        HostPlacement: PlacementFixedpointEncode<S, Uint8T, Fixed128T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed64T, Int8T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed128T, Int8T>,
+        HostPlacement: PlacementFixedpointEncode<S, Int8T, Fixed64T>,
+        HostPlacement: PlacementFixedpointEncode<S, Int8T, Fixed128T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed64T, Int16T>,
+        HostPlacement: PlacementFixedpointDecode<S, Fixed128T, Int16T>,
+        HostPlacement: PlacementFixedpointEncode<S, Int16T, Fixed64T>,
+        HostPlacement: PlacementFixedpointEncode<S, Int16T, Fixed128T>,
     {
         let arg0_precision = match sig.arg(0) {
             Ok(Ty::Tensor(TensorDType::Fixed64 {
@@ -1276,9 +2316,58 @@ impl CastOp {
                 let res = plc.cast(sess, x);
                 Ok(AbstractTensor::Bool(res))
             }
-            // fixedpoint casts
-            // fixedpoint decoding
-            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Float32)) => {
+            // to and from small integers
+            (AbstractTensor::Float32(x), Ty::Tensor(TensorDType::Uint8)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Uint8(res))
+            }
+            (AbstractTensor::Float32(x), Ty::Tensor(TensorDType::Int8)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Int8(res))
+            }
+            (AbstractTensor::Float32(x), Ty::Tensor(TensorDType::Int16)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Int16(res))
+            }
+            (AbstractTensor::Float64(x), Ty::Tensor(TensorDType::Uint8)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Uint8(res))
+            }
+            (AbstractTensor::Float64(x), Ty::Tensor(TensorDType::Int8)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Int8(res))
+            }
+            (AbstractTensor::Float64(x), Ty::Tensor(TensorDType::Int16)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Int16(res))
+            }
+            (AbstractTensor::Uint8(x), Ty::Tensor(TensorDType::Float32)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float32(res))
+            }
+            (AbstractTensor::Uint8(x), Ty::Tensor(TensorDType::Float64)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float64(res))
+            }
+            (AbstractTensor::Int8(x), Ty::Tensor(TensorDType::Float32)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float32(res))
+            }
+            (AbstractTensor::Int8(x), Ty::Tensor(TensorDType::Float64)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float64(res))
+            }
+            (AbstractTensor::Int16(x), Ty::Tensor(TensorDType::Float32)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float32(res))
+            }
+            (AbstractTensor::Int16(x), Ty::Tensor(TensorDType::Float64)) => {
+                let res = plc.cast(sess, x);
+                Ok(AbstractTensor::Float64(res))
+            }
+            // fixedpoint casts
+            // fixedpoint decoding
+            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Float32)) => {
                 let (_, fractional_precision) = arg0_precision.unwrap();
                 let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
                 Ok(AbstractTensor::Float32(inner))
@@ -1311,11 +2400,143 @@ impl CastOp {
                     plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
                 Ok(AbstractTensor::Fixed128(inner))
             }
+            // to and from raw integers
+            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Uint64)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Uint64(inner))
+            }
+            (AbstractTensor::Fixed128(x), Ty::Tensor(TensorDType::Uint64)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Uint64(inner))
+            }
+            (
+                AbstractTensor::Uint64(x),
+                Ty::Tensor(TensorDType::Fixed64 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed64(inner))
+            }
+            (
+                AbstractTensor::Uint64(x),
+                Ty::Tensor(TensorDType::Fixed128 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed128(inner))
+            }
+            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Uint8)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Uint8(inner))
+            }
+            (AbstractTensor::Fixed128(x), Ty::Tensor(TensorDType::Uint8)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Uint8(inner))
+            }
+            (
+                AbstractTensor::Uint8(x),
+                Ty::Tensor(TensorDType::Fixed64 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed64(inner))
+            }
+            (
+                AbstractTensor::Uint8(x),
+                Ty::Tensor(TensorDType::Fixed128 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed128(inner))
+            }
+            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Int8)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Int8(inner))
+            }
+            (AbstractTensor::Fixed128(x), Ty::Tensor(TensorDType::Int8)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Int8(inner))
+            }
+            (
+                AbstractTensor::Int8(x),
+                Ty::Tensor(TensorDType::Fixed64 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed64(inner))
+            }
+            (
+                AbstractTensor::Int8(x),
+                Ty::Tensor(TensorDType::Fixed128 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed128(inner))
+            }
+            (AbstractTensor::Fixed64(x), Ty::Tensor(TensorDType::Int16)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Int16(inner))
+            }
+            (AbstractTensor::Fixed128(x), Ty::Tensor(TensorDType::Int16)) => {
+                let (_, fractional_precision) = arg0_precision.unwrap();
+                let inner = plc.fixedpoint_decode(sess, fractional_precision, x);
+                Ok(AbstractTensor::Int16(inner))
+            }
+            (
+                AbstractTensor::Int16(x),
+                Ty::Tensor(TensorDType::Fixed64 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed64(inner))
+            }
+            (
+                AbstractTensor::Int16(x),
+                Ty::Tensor(TensorDType::Fixed128 {
+                    fractional_precision,
+                    integral_precision,
+                }),
+            ) => {
+                let inner =
+                    plc.fixedpoint_encode(sess, fractional_precision, integral_precision, x);
+                Ok(AbstractTensor::Fixed128(inner))
+            }
             (AbstractTensor::Float32(_), ret)
             | (AbstractTensor::Float64(_), ret)
             | (AbstractTensor::Fixed64(_), ret)
             | (AbstractTensor::Fixed128(_), ret)
             | (AbstractTensor::Uint64(_), ret)
+            | (AbstractTensor::Uint8(_), ret)
+            | (AbstractTensor::Int8(_), ret)
+            | (AbstractTensor::Int16(_), ret)
             | (AbstractTensor::Bool(_), ret) => Err(Error::UnimplementedOperator(format!(
                 "Cast operator does not support casting of {} to {:?}",
                 x.ty_desc(),
@@ -1324,12 +2545,43 @@ impl CastOp {
         }
     }
 
-    pub(crate) fn mir_kernel<S: Session, Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>(
+    pub(crate) fn mir_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
         sess: &S,
         plc: &Mirrored3Placement,
         sig: Signature,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         Mirrored3Placement: PlacementFixedpointDecode<S, Fixed64T, Float32T>,
         Mirrored3Placement: PlacementFixedpointDecode<S, Fixed128T, Float64T>,
@@ -1386,6 +2638,9 @@ impl CastOp {
             | (AbstractTensor::Fixed64(_), ret)
             | (AbstractTensor::Fixed128(_), ret)
             | (AbstractTensor::Uint64(_), ret)
+            | (AbstractTensor::Uint8(_), ret)
+            | (AbstractTensor::Int8(_), ret)
+            | (AbstractTensor::Int16(_), ret)
             | (AbstractTensor::Bool(_), ret) => Err(Error::UnimplementedOperator(format!(
                 "Cast operator does not support casting of {:?} to {:?}",
                 x.ty_desc(),
@@ -1404,12 +2659,35 @@ impl AtLeast2DOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         to_column_vector: bool,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementAtLeast2D<S, Float32T, Float32T>,
         HostPlacement: PlacementAtLeast2D<S, Float64T, Float64T>,
@@ -1424,9 +2702,12 @@ impl AtLeast2DOp {
                 let z = plc.at_least_2d(sess, to_column_vector, &x);
                 Ok(Float64(z))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated at_least_2d for {:?}", &x.ty_desc(),),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated at_least_2d for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -1440,13 +2721,36 @@ impl MeanOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
         axis: Option<u32>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementMean<S, Fixed64T, Fixed64T>,
         HostPlacement: PlacementMean<S, Fixed128T, Fixed128T>,
@@ -1486,12 +2790,14 @@ impl MeanOp {
                 let z = plc.mean(sess, axis, &x);
                 Ok(AbstractTensor::Float64(z))
             }
-            AbstractTensor::Bool(_) | AbstractTensor::Uint64(_) => {
-                Err(Error::UnimplementedOperator(format!(
-                    "Mean op (Host) is unsupported for {:?}.",
-                    x.ty_desc()
-                )))
-            }
+            AbstractTensor::Bool(_)
+            | AbstractTensor::Uint64(_)
+            | AbstractTensor::Uint8(_)
+            | AbstractTensor::Int8(_)
+            | AbstractTensor::Int16(_) => Err(Error::UnimplementedOperator(format!(
+                "Mean op (Host) is unsupported for {:?}.",
+                x.ty_desc()
+            ))),
         }
     }
 
@@ -1503,13 +2809,36 @@ impl MeanOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         sig: Signature,
         axis: Option<u32>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementMean<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementMean<S, Fixed128T, Fixed128T>,
@@ -1542,7 +2871,10 @@ impl MeanOp {
             AbstractTensor::Float32(_)
             | AbstractTensor::Float64(_)
             | AbstractTensor::Bool(_)
-            | AbstractTensor::Uint64(_) => Err(Error::UnimplementedOperator(format!(
+            | AbstractTensor::Uint64(_)
+            | AbstractTensor::Uint8(_)
+            | AbstractTensor::Int8(_)
+            | AbstractTensor::Int16(_) => Err(Error::UnimplementedOperator(format!(
                 "Replicated mean is unsupported for {:?}.",
                 x.ty_desc()
             ))),
@@ -1559,12 +2891,35 @@ impl SumOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: Option<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSum<S, Fixed64T, Fixed64T>,
         HostPlacement: PlacementSum<S, Fixed128T, Fixed128T>,
@@ -1589,10 +2944,12 @@ impl SumOp {
                 let z = plc.sum(sess, axis, &x);
                 Ok(Float64(z))
             }
-            Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Sum op (Host) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Sum op (Host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 
@@ -1604,12 +2961,35 @@ impl SumOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: Option<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSum<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementSum<S, Fixed128T, Fixed128T>,
@@ -1624,9 +3004,12 @@ impl SumOp {
                 let z = plc.sum(sess, axis, &x);
                 Ok(Fixed128(z))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Replicated sum is unsupported for {:?}.", x.ty_desc()),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Replicated sum is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 }
@@ -1684,12 +3067,35 @@ impl ExpandDimsOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: Vec<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementExpandDims<S, Float32T, Float32T>,
         HostPlacement: PlacementExpandDims<S, Float64T, Float64T>,
@@ -1719,10 +3125,12 @@ impl ExpandDimsOp {
                 let z = plc.expand_dims(sess, axis, &x);
                 Ok(AbstractTensor::Bool(z))
             }
-            Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Expand dims op (host) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Expand dims op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 }
@@ -1736,12 +3144,35 @@ impl ExpandDimsOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: Vec<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementExpandDims<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementExpandDims<S, Fixed128T, Fixed128T>,
@@ -1761,10 +3192,12 @@ impl ExpandDimsOp {
                 let result = plc.expand_dims(sess, axis, &x);
                 Ok(AbstractTensor::Bool(result))
             }
-            Float32(_) | Float64(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Missing replicated expand_dims for {:?}",
-                &x.ty_desc(),
-            ))),
+            Float32(_) | Float64(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated expand_dims for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -1778,13 +3211,36 @@ impl IndexAxisOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: usize,
         index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementIndexAxis<S, Float32T, Float32T>,
         HostPlacement: PlacementIndexAxis<S, Float64T, Float64T>,
@@ -1814,10 +3270,9 @@ impl IndexAxisOp {
                 let z = plc.index_axis(sess, axis, index, &x);
                 Ok(AbstractTensor::Bool(z))
             }
-            Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Index axis op (host) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => Err(Error::UnimplementedOperator(
+                format!("Index axis op (host) is unsupported for {:?}.", x.ty_desc()),
+            )),
         }
     }
 }
@@ -1831,13 +3286,36 @@ impl IndexAxisOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: usize,
         index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementIndexAxis<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementIndexAxis<S, Fixed128T, Fixed128T>,
@@ -1857,10 +3335,12 @@ impl IndexAxisOp {
                 let result = plc.index_axis(sess, axis, index, &x);
                 Ok(AbstractTensor::Bool(result))
             }
-            Float32(_) | Float64(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Missing replicated index_axis for {:?}",
-                &x.ty_desc(),
-            ))),
+            Float32(_) | Float64(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated index_axis for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -1874,12 +3354,36 @@ impl ConcatOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: u32,
-        xs: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        xs: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementConcatenate<S, Float32T, Float32T>,
         HostPlacement: PlacementConcatenate<S, Float64T, Float64T>,
@@ -1918,9 +3422,9 @@ impl ConcatOp {
                 let result = plc.concatenate(sess, axis, &xs);
                 Ok(Float64(result))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                "ConcatOp missing an implementation.".to_string(),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => Err(
+                Error::UnimplementedOperator("ConcatOp missing an implementation.".to_string()),
+            ),
         }
     }
 
@@ -1932,13 +3436,37 @@ impl ConcatOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: u32,
-        x: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
-    where
+        x: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
         ReplicatedPlacement: PlacementConcatenate<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementConcatenate<S, Fixed128T, Fixed128T>,
         ReplicatedPlacement: PlacementConcatenate<S, BoolT, BoolT>,
@@ -2007,7 +3535,7 @@ impl ConcatOp {
                 }
                 Bool(plc.concatenate(sess, axis, &xv))
             }
-            Float32(_) | Float64(_) | Uint64(_) => {
+            Float32(_) | Float64(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
                 return Err(Error::UnimplementedOperator(format!(
                     "Missing replicated concat op for {:?}",
                     &x[0].ty_desc(),
@@ -2027,11 +3555,34 @@ impl TransposeOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementTranspose<S, BoolT, BoolT>,
         HostPlacement: PlacementTranspose<S, Fixed64T, Fixed64T>,
@@ -2066,6 +3617,10 @@ impl TransposeOp {
                 let z = plc.transpose(sess, &x);
                 Ok(Uint64(z))
             }
+            Uint8(_) | Int8(_) | Int16(_) => Err(Error::UnimplementedOperator(format!(
+                "Transpose op (host) is unsupported for {:?}.",
+                x.ty_desc()
+            ))),
         }
     }
 
@@ -2077,11 +3632,34 @@ impl TransposeOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementTranspose<S, BoolT, BoolT>,
         ReplicatedPlacement: PlacementTranspose<S, Fixed64T, Fixed64T>,
@@ -2106,20 +3684,53 @@ impl TransposeOp {
                 let z = plc.transpose(sess, &x);
                 Ok(AbstractTensor::Uint64(z))
             }
-            Float32(_) | Float64(_) => Err(Error::UnimplementedOperator(format!(
-                "Transpose op (rep) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Float32(_) | Float64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Transpose op (rep) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 }
 
 impl InverseOp {
-    pub(crate) fn kernel<S: Session, Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>(
+    pub(crate) fn kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementInverse<S, Float32T, Float32T>,
         HostPlacement: PlacementInverse<S, Float64T, Float64T>,
@@ -2134,9 +3745,12 @@ impl InverseOp {
                 let z = plc.inverse(sess, &x);
                 Ok(Float64(z))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Inverse op (host) is unsupported for {:?}.", x.ty_desc()),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Inverse op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 }
@@ -2168,11 +3782,24 @@ impl SaveOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         key: m!(HostString),
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
     ) -> Result<m!(HostUnit)>
     where
         HostString: KnownType<S>,
@@ -2181,6 +3808,9 @@ impl SaveOp {
         HostPlacement: PlacementSave<S, m!(HostString), Float64T, m!(HostUnit)>,
         HostPlacement: PlacementSave<S, m!(HostString), BoolT, m!(HostUnit)>,
         HostPlacement: PlacementSave<S, m!(HostString), Uint64T, m!(HostUnit)>,
+        HostPlacement: PlacementSave<S, m!(HostString), Uint8T, m!(HostUnit)>,
+        HostPlacement: PlacementSave<S, m!(HostString), Int8T, m!(HostUnit)>,
+        HostPlacement: PlacementSave<S, m!(HostString), Int16T, m!(HostUnit)>,
     {
         use AbstractTensor::*;
         match x {
@@ -2188,6 +3818,9 @@ impl SaveOp {
             Float32(x) => Ok(plc.save(sess, &key, &x)),
             Float64(x) => Ok(plc.save(sess, &key, &x)),
             Uint64(x) => Ok(plc.save(sess, &key, &x)),
+            Uint8(x) => Ok(plc.save(sess, &key, &x)),
+            Int8(x) => Ok(plc.save(sess, &key, &x)),
+            Int16(x) => Ok(plc.save(sess, &key, &x)),
             Fixed64(_) | Fixed128(_) => Err(Error::UnimplementedOperator(format!(
                 "Save op (host) is unsupported for {:?}.",
                 x.ty_desc()
@@ -2210,7 +3843,17 @@ impl ShapeOp {
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
     ) -> Result<AbstractShape<HostShapeT, RepShapeT>>
     where
         HostPlacement: PlacementShape<S, Float32T, HostShapeT>,
@@ -2225,10 +3868,12 @@ impl ShapeOp {
             Float64(x) => Ok(Host(plc.shape(sess, &x))),
             Fixed64(x) => Ok(Host(plc.shape(sess, &x))),
             Fixed128(x) => Ok(Host(plc.shape(sess, &x))),
-            Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Shape op (host) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Shape op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 
@@ -2245,7 +3890,17 @@ impl ShapeOp {
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
     ) -> Result<AbstractShape<HostShapeT, RepShapeT>>
     where
         ReplicatedPlacement: PlacementShape<S, Fixed64T, RepShapeT>,
@@ -2256,9 +3911,11 @@ impl ShapeOp {
         match x {
             Fixed64(x) => Ok(Replicated(plc.shape(sess, &x))),
             Fixed128(x) => Ok(Replicated(plc.shape(sess, &x))),
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                "Shape op (Rep) op not supported on ReplicatedPlacement.".to_string(),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(
+                    "Shape op (Rep) op not supported on ReplicatedPlacement.".to_string(),
+                ))
+            }
         }
     }
 }
@@ -2277,9 +3934,29 @@ impl ReshapeOp {
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
         shape: AbstractShape<HostS, RepS>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementReshape<S, Float32T, HostS, Float32T>,
         HostPlacement: PlacementReshape<S, Float64T, HostS, Float64T>,
@@ -2314,9 +3991,29 @@ impl ReshapeOp {
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
         shape: AbstractShape<HostShapeT, RepShapeT>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementShare<S, HostShapeT, RepShapeT>,
         ReplicatedPlacement: PlacementReshape<S, Fixed64T, RepShapeT, Fixed64T>,
@@ -2331,9 +4028,11 @@ impl ReshapeOp {
         match x {
             Fixed64(x) => Ok(Fixed64(plc.reshape(sess, &x, &sh))),
             Fixed128(x) => Ok(Fixed128(plc.reshape(sess, &x, &sh))),
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                "Reshape op (Rep) op not supported on ReplicatedPlacement.".to_string(),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(
+                    "Reshape op (Rep) op not supported on ReplicatedPlacement.".to_string(),
+                ))
+            }
         }
     }
 }
@@ -2367,12 +4066,35 @@ impl SliceOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         slice: SliceInfo,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSlice<S, Float32T, Float32T>,
         HostPlacement: PlacementSlice<S, Float64T, Float64T>,
@@ -2407,6 +4129,10 @@ impl SliceOp {
                 let result = plc.slice(sess, slice, &x);
                 Ok(Uint64(result))
             }
+            Uint8(_) | Int8(_) | Int16(_) => Err(Error::UnimplementedOperator(format!(
+                "Missing host slice for {:?}",
+                &x.ty_desc(),
+            ))),
         }
     }
 
@@ -2438,12 +4164,35 @@ impl SliceOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         info: SliceInfo,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSlice<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementSlice<S, Fixed128T, Fixed128T>,
@@ -2468,10 +4217,9 @@ impl SliceOp {
                 let result = plc.slice(sess, info, &x);
                 Ok(Uint64(result))
             }
-            Float32(_) | Float64(_) => Err(Error::UnimplementedOperator(format!(
-                "Missing rep slice for {:?}",
-                &x.ty_desc(),
-            ))),
+            Float32(_) | Float64(_) | Uint8(_) | Int8(_) | Int16(_) => Err(
+                Error::UnimplementedOperator(format!("Missing rep slice for {:?}", &x.ty_desc(),)),
+            ),
         }
     }
 }
@@ -2485,17 +4233,33 @@ impl ConstantOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
         value: Constant,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementConstant<S, Float32T>,
         HostPlacement: PlacementConstant<S, Float64T>,
         HostPlacement: PlacementConstant<S, Uint64T>,
         HostPlacement: PlacementConstant<S, BoolT>,
+        HostPlacement: PlacementConstant<S, Uint8T>,
+        HostPlacement: PlacementConstant<S, Int8T>,
+        HostPlacement: PlacementConstant<S, Int16T>,
     {
         match sig.ret() {
             Ty::Tensor(TensorDType::Float32) => {
@@ -2514,6 +4278,18 @@ impl ConstantOp {
                 let z = plc.constant(sess, value);
                 Ok(AbstractTensor::Bool(z))
             }
+            Ty::Tensor(TensorDType::Uint8) => {
+                let z = plc.constant(sess, value);
+                Ok(AbstractTensor::Uint8(z))
+            }
+            Ty::Tensor(TensorDType::Int8) => {
+                let z = plc.constant(sess, value);
+                Ok(AbstractTensor::Int8(z))
+            }
+            Ty::Tensor(TensorDType::Int16) => {
+                let z = plc.constant(sess, value);
+                Ok(AbstractTensor::Int16(z))
+            }
             ret => Err(Error::UnimplementedOperator(format!(
                 "ConstantOp can not produce tensors of type {:?} yet",
                 ret
@@ -2529,12 +4305,25 @@ impl ConstantOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &Mirrored3Placement,
         sig: Signature,
         value: Constant,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         Mirrored3Placement: PlacementConstant<S, Float32T>,
         Mirrored3Placement: PlacementConstant<S, Float64T>,
@@ -2586,15 +4375,31 @@ impl InputOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         sig: Signature,
         arg_name: String,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementInput<S, Float32T>,
         HostPlacement: PlacementInput<S, Float64T>,
+        HostPlacement: PlacementInput<S, Uint8T>,
+        HostPlacement: PlacementInput<S, Int8T>,
+        HostPlacement: PlacementInput<S, Int16T>,
     {
         match sig.ret() {
             Ty::Tensor(TensorDType::Float32) => {
@@ -2605,16 +4410,331 @@ impl InputOp {
                 let z = plc.input(sess, arg_name);
                 Ok(AbstractTensor::Float64(z))
             }
-            ret => Err(Error::UnimplementedOperator(format!(
-                "InputOp can not produce tensors of type {:?} yet",
-                ret
-            ))),
+            Ty::Tensor(TensorDType::Uint8) => {
+                let z = plc.input(sess, arg_name);
+                Ok(AbstractTensor::Uint8(z))
+            }
+            Ty::Tensor(TensorDType::Int8) => {
+                let z = plc.input(sess, arg_name);
+                Ok(AbstractTensor::Int8(z))
+            }
+            Ty::Tensor(TensorDType::Int16) => {
+                let z = plc.input(sess, arg_name);
+                Ok(AbstractTensor::Int16(z))
+            }
+            ret => Err(Error::UnimplementedOperator(format!(
+                "InputOp can not produce tensors of type {:?} yet",
+                ret
+            ))),
+        }
+    }
+}
+
+impl OutputOp {
+    pub(crate) fn logical_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        tag: String,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        HostPlacement: PlacementOutput<S, Float32T, Float32T>,
+        HostPlacement: PlacementOutput<S, Float64T, Float64T>,
+        HostPlacement: PlacementOutput<S, BoolT, BoolT>,
+        HostPlacement: PlacementOutput<S, Uint8T, Uint8T>,
+        HostPlacement: PlacementOutput<S, Int8T, Int8T>,
+        HostPlacement: PlacementOutput<S, Int16T, Int16T>,
+    {
+        use AbstractTensor::*;
+        match x {
+            Bool(x) => Ok(Bool(plc.output(sess, tag, &x))),
+            Float32(x) => Ok(Float32(plc.output(sess, tag, &x))),
+            Float64(x) => Ok(Float64(plc.output(sess, tag, &x))),
+            Uint8(x) => Ok(Uint8(plc.output(sess, tag, &x))),
+            Int8(x) => Ok(Int8(plc.output(sess, tag, &x))),
+            Int16(x) => Ok(Int16(plc.output(sess, tag, &x))),
+            Fixed64(_) | Fixed128(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
+                "Output op (host) is unsupported for {:?}.",
+                x.ty_desc()
+            ))),
+        }
+    }
+}
+
+impl ExpOp {
+    pub(crate) fn logical_rep_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        ReplicatedPlacement: PlacementExp<S, Fixed64T, Fixed64T>,
+        ReplicatedPlacement: PlacementExp<S, Fixed128T, Fixed128T>,
+    {
+        use AbstractTensor::*;
+        match x {
+            Fixed64(x) => {
+                let result = plc.exp(sess, &x);
+                Ok(Fixed64(result))
+            }
+            Fixed128(x) => {
+                let result = plc.exp(sess, &x);
+                Ok(Fixed128(result))
+            }
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated exp for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
+        }
+    }
+
+    pub(crate) fn logical_host_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        HostPlacement: PlacementExp<S, Float32T, Float32T>,
+        HostPlacement: PlacementExp<S, Float64T, Float64T>,
+    {
+        use AbstractTensor::*;
+        match x {
+            Float32(x) => {
+                let result = plc.exp(sess, &x);
+                Ok(Float32(result))
+            }
+            Float64(x) => {
+                let result = plc.exp(sess, &x);
+                Ok(Float64(result))
+            }
+            Fixed64(_) | Fixed128(_) => Err(Error::UnimplementedOperator(
+                "Missing host exp for fixed point tensors. Try casting to Float instead"
+                    .to_string(),
+            )),
+            Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => Err(
+                Error::UnimplementedOperator(format!("Missing host exp for {:?}", &x.ty_desc(),)),
+            ),
+        }
+    }
+}
+
+impl SqrtOp {
+    pub(crate) fn logical_host_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &HostPlacement,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        HostPlacement: PlacementSqrt<S, Float32T, Float32T>,
+        HostPlacement: PlacementSqrt<S, Float64T, Float64T>,
+    {
+        use AbstractTensor::*;
+        match x {
+            Float32(x) => {
+                let z = plc.sqrt(sess, &x);
+                Ok(Float32(z))
+            }
+            Float64(x) => {
+                let z = plc.sqrt(sess, &x);
+                Ok(Float64(z))
+            }
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Sqrt op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
+        }
+    }
+
+    pub(crate) fn logical_rep_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
+        sess: &S,
+        plc: &ReplicatedPlacement,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
+    where
+        ReplicatedPlacement: PlacementSqrt<S, Fixed64T, Fixed64T>,
+        ReplicatedPlacement: PlacementSqrt<S, Fixed128T, Fixed128T>,
+    {
+        use AbstractTensor::*;
+        match x {
+            Fixed64(x) => {
+                let result = plc.sqrt(sess, &x);
+                Ok(Fixed64(result))
+            }
+            Fixed128(x) => {
+                let result = plc.sqrt(sess, &x);
+                Ok(Fixed128(result))
+            }
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated sqrt for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
 
-impl OutputOp {
-    pub(crate) fn logical_kernel<
+impl LaplaceNoiseOp {
+    pub(crate) fn logical_host_kernel<
         S: Session,
         Fixed64T,
         Fixed128T,
@@ -2622,31 +4742,59 @@ impl OutputOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        tag: String,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        epsilon: f64,
+        sensitivity: f64,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
-        HostPlacement: PlacementOutput<S, Float32T, Float32T>,
-        HostPlacement: PlacementOutput<S, Float64T, Float64T>,
-        HostPlacement: PlacementOutput<S, BoolT, BoolT>,
+        HostPlacement: PlacementLaplaceNoise<S, Float32T, Float32T>,
+        HostPlacement: PlacementLaplaceNoise<S, Float64T, Float64T>,
     {
         use AbstractTensor::*;
         match x {
-            Bool(x) => Ok(Bool(plc.output(sess, tag, &x))),
-            Float32(x) => Ok(Float32(plc.output(sess, tag, &x))),
-            Float64(x) => Ok(Float64(plc.output(sess, tag, &x))),
-            Fixed64(_) | Fixed128(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Output op (host) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Float32(x) => {
+                let z = plc.laplace_noise(sess, epsilon, sensitivity, &x);
+                Ok(Float32(z))
+            }
+            Float64(x) => {
+                let z = plc.laplace_noise(sess, epsilon, sensitivity, &x);
+                Ok(Float64(z))
+            }
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "LaplaceNoise op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
-}
 
-impl ExpOp {
     pub(crate) fn logical_rep_kernel<
         S: Session,
         Fixed64T,
@@ -2655,31 +4803,61 @@ impl ExpOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        epsilon: f64,
+        sensitivity: f64,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
-        ReplicatedPlacement: PlacementExp<S, Fixed64T, Fixed64T>,
-        ReplicatedPlacement: PlacementExp<S, Fixed128T, Fixed128T>,
+        ReplicatedPlacement: PlacementLaplaceNoise<S, Fixed64T, Fixed64T>,
+        ReplicatedPlacement: PlacementLaplaceNoise<S, Fixed128T, Fixed128T>,
     {
         use AbstractTensor::*;
         match x {
             Fixed64(x) => {
-                let result = plc.exp(sess, &x);
+                let result = plc.laplace_noise(sess, epsilon, sensitivity, &x);
                 Ok(Fixed64(result))
             }
             Fixed128(x) => {
-                let result = plc.exp(sess, &x);
+                let result = plc.laplace_noise(sess, epsilon, sensitivity, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated exp for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated laplace_noise for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
+}
 
+impl GaussianNoiseOp {
     pub(crate) fn logical_host_kernel<
         S: Session,
         Fixed64T,
@@ -2688,68 +4866,57 @@ impl ExpOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
-    where
-        HostPlacement: PlacementExp<S, Float32T, Float32T>,
-        HostPlacement: PlacementExp<S, Float64T, Float64T>,
-    {
-        use AbstractTensor::*;
-        match x {
-            Float32(x) => {
-                let result = plc.exp(sess, &x);
-                Ok(Float32(result))
-            }
-            Float64(x) => {
-                let result = plc.exp(sess, &x);
-                Ok(Float64(result))
-            }
-            Fixed64(_) | Fixed128(_) => Err(Error::UnimplementedOperator(
-                "Missing host exp for fixed point tensors. Try casting to Float instead"
-                    .to_string(),
-            )),
-            Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(format!(
-                "Missing host exp for {:?}",
-                &x.ty_desc(),
-            ))),
-        }
-    }
-}
-
-impl SqrtOp {
-    pub(crate) fn logical_host_kernel<
-        S: Session,
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
         Fixed64T,
         Fixed128T,
         Float32T,
         Float64T,
         BoolT,
         Uint64T,
-    >(
-        sess: &S,
-        plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
-        HostPlacement: PlacementSqrt<S, Float32T, Float32T>,
-        HostPlacement: PlacementSqrt<S, Float64T, Float64T>,
+        HostPlacement: PlacementGaussianNoise<S, Float32T, Float32T>,
+        HostPlacement: PlacementGaussianNoise<S, Float64T, Float64T>,
     {
         use AbstractTensor::*;
         match x {
             Float32(x) => {
-                let z = plc.sqrt(sess, &x);
+                let z = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
                 Ok(Float32(z))
             }
             Float64(x) => {
-                let z = plc.sqrt(sess, &x);
+                let z = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
                 Ok(Float64(z))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Sqrt op (host) is unsupported for {:?}.", x.ty_desc()),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "GaussianNoise op (host) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 
@@ -2761,28 +4928,57 @@ impl SqrtOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
-        ReplicatedPlacement: PlacementSqrt<S, Fixed64T, Fixed64T>,
-        ReplicatedPlacement: PlacementSqrt<S, Fixed128T, Fixed128T>,
+        ReplicatedPlacement: PlacementGaussianNoise<S, Fixed64T, Fixed64T>,
+        ReplicatedPlacement: PlacementGaussianNoise<S, Fixed128T, Fixed128T>,
     {
         use AbstractTensor::*;
         match x {
             Fixed64(x) => {
-                let result = plc.sqrt(sess, &x);
+                let result = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
                 Ok(Fixed64(result))
             }
             Fixed128(x) => {
-                let result = plc.sqrt(sess, &x);
+                let result = plc.gaussian_noise(sess, epsilon, delta, sensitivity, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated sqrt for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated gaussian_noise for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -2796,11 +4992,34 @@ impl SigmoidOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSigmoid<S, Float32T, Float32T>,
         HostPlacement: PlacementSigmoid<S, Float64T, Float64T>,
@@ -2815,9 +5034,12 @@ impl SigmoidOp {
                 let result = plc.sigmoid(sess, &x);
                 Ok(Float64(result))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated sigmoid for {:?}", &x.ty_desc(),),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated sigmoid for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 
@@ -2829,11 +5051,34 @@ impl SigmoidOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSigmoid<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementSigmoid<S, Fixed128T, Fixed128T>,
@@ -2848,9 +5093,12 @@ impl SigmoidOp {
                 let result = plc.sigmoid(sess, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated sigmoid for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated sigmoid for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -2864,11 +5112,34 @@ impl LogOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementLog<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementLog<S, Fixed128T, Fixed128T>,
@@ -2883,7 +5154,7 @@ impl LogOp {
                 let result = plc.log(sess, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => {
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
                 Err(Error::UnimplementedOperator(format!(
                     "Missing replicated natural logarithm for {:?}",
                     &x.ty_desc(),
@@ -2900,11 +5171,34 @@ impl LogOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementLog<S, Float32T, Float32T>,
         HostPlacement: PlacementLog<S, Float64T, Float64T>,
@@ -2919,7 +5213,7 @@ impl LogOp {
                 let result = plc.log(sess, &x);
                 Ok(Float64(result))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => {
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
                 Err(Error::UnimplementedOperator(format!(
                     "Missing replicated natural logarithm for {:?}",
                     &x.ty_desc(),
@@ -2938,11 +5232,34 @@ impl Log2Op {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementLog2<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementLog2<S, Fixed128T, Fixed128T>,
@@ -2957,9 +5274,12 @@ impl Log2Op {
                 let result = plc.log2(sess, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated logarithm base 2 for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated logarithm base 2 for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 
@@ -2971,11 +5291,34 @@ impl Log2Op {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementLog2<S, Float32T, Float32T>,
         HostPlacement: PlacementLog2<S, Float64T, Float64T>,
@@ -2990,9 +5333,12 @@ impl Log2Op {
                 let result = plc.log2(sess, &x);
                 Ok(Float32(result))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated logarithm base 2 for {:?}", &x.ty_desc(),),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated logarithm base 2 for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -3006,12 +5352,45 @@ impl OrOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-        y: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+        y: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementOr<S, BoolT, BoolT, BoolT>,
     {
@@ -3026,6 +5405,9 @@ impl OrOp {
             | (Float32(_), _)
             | (Float64(_), _)
             | (Uint64(_), _)
+            | (Uint8(_), _)
+            | (Int8(_), _)
+            | (Int16(_), _)
             | (Bool(_), _) => Err(Error::UnimplementedOperator(format!(
                 "Missing host less op for {:?} and {:?}",
                 x.ty_desc(),
@@ -3044,11 +5426,35 @@ impl MaximumOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
-        x: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementMaximum<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementMaximum<S, Fixed128T, Fixed128T>,
@@ -3100,7 +5506,7 @@ impl MaximumOp {
                 }
                 Fixed128(plc.maximum(sess, &xv))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => {
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
                 return Err(Error::UnimplementedOperator(format!(
                     "Missing replicated maximum op for {:?}",
                     &x[0].ty_desc(),
@@ -3118,11 +5524,35 @@ impl MaximumOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
-        x: &[AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>],
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensorSlice<
+            '_,
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementMaximum<S, Fixed64T, Fixed64T>,
         HostPlacement: PlacementMaximum<S, Fixed128T, Fixed128T>,
@@ -3206,21 +5636,52 @@ impl MaximumOp {
                 let result = plc.maximum(sess, &xs);
                 Ok(Float64(result))
             }
-            Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                "MaximumOp missing an implementation.".to_string(),
-            )),
+            Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => Err(
+                Error::UnimplementedOperator("MaximumOp missing an implementation.".to_string()),
+            ),
         }
     }
 }
 
 impl SoftmaxOp {
-    pub fn logical_rep_kernel<S: Session, Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>(
+    pub fn logical_rep_kernel<
+        S: Session,
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: usize,
         upmost_index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSoftmax<S, Fixed64T, Fixed64T>,
         ReplicatedPlacement: PlacementSoftmax<S, Fixed128T, Fixed128T>,
@@ -3235,9 +5696,12 @@ impl SoftmaxOp {
                 let result = plc.softmax(sess, axis, upmost_index, &x);
                 Ok(Fixed128(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated softmax for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated softmax for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 
@@ -3249,13 +5713,36 @@ impl SoftmaxOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: usize,
         upmost_index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSoftmax<S, Float32T, Float32T>,
         HostPlacement: PlacementSoftmax<S, Float64T, Float64T>,
@@ -3270,9 +5757,12 @@ impl SoftmaxOp {
                 let result = plc.softmax(sess, axis, upmost_index, &x);
                 Ok(Float64(result))
             }
-            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated softmax for {:?}", &x.ty_desc(),),
-            )),
+            Fixed64(_) | Fixed128(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated softmax for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -3286,13 +5776,36 @@ impl ArgmaxOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: usize,
         upmost_index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementArgmax<S, Fixed64T, Uint64T>,
         ReplicatedPlacement: PlacementArgmax<S, Fixed128T, Uint64T>,
@@ -3307,9 +5820,12 @@ impl ArgmaxOp {
                 let result = plc.argmax(sess, axis, upmost_index, &x);
                 Ok(Uint64(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated argmax for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated argmax for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 
@@ -3321,13 +5837,36 @@ impl ArgmaxOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: usize,
         upmost_index: usize,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementArgmax<S, Fixed64T, Uint64T>,
         HostPlacement: PlacementArgmax<S, Fixed128T, Uint64T>,
@@ -3342,9 +5881,12 @@ impl ArgmaxOp {
                 let result = plc.argmax(sess, axis, upmost_index, &x);
                 Ok(Uint64(result))
             }
-            Float32(_) | Float64(_) | Bool(_) | Uint64(_) => Err(Error::UnimplementedOperator(
-                format!("Missing replicated argmax for {:?}", &x.ty_desc(),),
-            )),
+            Float32(_) | Float64(_) | Bool(_) | Uint64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Missing replicated argmax for {:?}",
+                    &x.ty_desc(),
+                )))
+            }
         }
     }
 }
@@ -3358,12 +5900,35 @@ impl SqueezeOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &HostPlacement,
         axis: Option<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         HostPlacement: PlacementSqueeze<S, BoolT, BoolT>,
         HostPlacement: PlacementSqueeze<S, Fixed64T, Fixed64T>,
@@ -3398,6 +5963,10 @@ impl SqueezeOp {
                 let z = plc.squeeze(sess, axis, &x);
                 Ok(AbstractTensor::Uint64(z))
             }
+            Uint8(_) | Int8(_) | Int16(_) => Err(Error::UnimplementedOperator(format!(
+                "Squeeze op (host) is unsupported for {:?}.",
+                x.ty_desc()
+            ))),
         }
     }
 
@@ -3409,12 +5978,35 @@ impl SqueezeOp {
         Float64T,
         BoolT,
         Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
     >(
         sess: &S,
         plc: &ReplicatedPlacement,
         axis: Option<usize>,
-        x: AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>,
-    ) -> Result<AbstractTensor<Fixed64T, Fixed128T, Float32T, Float64T, BoolT, Uint64T>>
+        x: AbstractTensor<
+            Fixed64T,
+            Fixed128T,
+            Float32T,
+            Float64T,
+            BoolT,
+            Uint64T,
+            Uint8T,
+            Int8T,
+            Int16T,
+        >,
+    ) -> AbstractTensorResult<
+        Fixed64T,
+        Fixed128T,
+        Float32T,
+        Float64T,
+        BoolT,
+        Uint64T,
+        Uint8T,
+        Int8T,
+        Int16T,
+    >
     where
         ReplicatedPlacement: PlacementSqueeze<S, BoolT, BoolT>,
         ReplicatedPlacement: PlacementSqueeze<S, Fixed64T, Fixed64T>,
@@ -3439,10 +6031,12 @@ impl SqueezeOp {
                 let z = plc.squeeze(sess, axis, &x);
                 Ok(AbstractTensor::Uint64(z))
             }
-            Float32(_) | Float64(_) => Err(Error::UnimplementedOperator(format!(
-                "Squeeze op (rep) is unsupported for {:?}.",
-                x.ty_desc()
-            ))),
+            Float32(_) | Float64(_) | Uint8(_) | Int8(_) | Int16(_) => {
+                Err(Error::UnimplementedOperator(format!(
+                    "Squeeze op (rep) is unsupported for {:?}.",
+                    x.ty_desc()
+                )))
+            }
         }
     }
 }
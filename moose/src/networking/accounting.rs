@@ -0,0 +1,101 @@
+//! Accounting of network traffic per session and peer.
+
+use crate::execution::Identity;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Traffic exchanged with a single peer.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PeerStats {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+}
+
+/// Traffic exchanged during a session, broken down by peer.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct NetworkStats {
+    pub peers: HashMap<Identity, PeerStats>,
+}
+
+impl NetworkStats {
+    pub fn bytes_sent(&self) -> u64 {
+        self.peers.values().map(|stats| stats.bytes_sent).sum()
+    }
+
+    pub fn bytes_received(&self) -> u64 {
+        self.peers.values().map(|stats| stats.bytes_received).sum()
+    }
+
+    pub fn messages_sent(&self) -> u64 {
+        self.peers.values().map(|stats| stats.messages_sent).sum()
+    }
+
+    pub fn messages_received(&self) -> u64 {
+        self.peers
+            .values()
+            .map(|stats| stats.messages_received)
+            .sum()
+    }
+}
+
+/// Concurrent counters from which `NetworkStats` snapshots can be taken.
+#[derive(Default, Debug)]
+pub struct NetworkAccounting {
+    peers: DashMap<Identity, PeerStats>,
+}
+
+impl NetworkAccounting {
+    pub fn record_sent(&self, receiver: &Identity, bytes: usize) {
+        let mut stats = self.peers.entry(receiver.clone()).or_default();
+        stats.bytes_sent += bytes as u64;
+        stats.messages_sent += 1;
+    }
+
+    pub fn record_received(&self, sender: &Identity, bytes: usize) {
+        let mut stats = self.peers.entry(sender.clone()).or_default();
+        stats.bytes_received += bytes as u64;
+        stats.messages_received += 1;
+    }
+
+    pub fn snapshot(&self) -> NetworkStats {
+        NetworkStats {
+            peers: self
+                .peers
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accounting() {
+        let accounting = NetworkAccounting::default();
+        accounting.record_sent(&"bob".into(), 100);
+        accounting.record_sent(&"bob".into(), 20);
+        accounting.record_sent(&"carole".into(), 5);
+        accounting.record_received(&"carole".into(), 7);
+
+        let stats = accounting.snapshot();
+        assert_eq!(
+            stats.peers[&Identity::from("bob")],
+            PeerStats {
+                bytes_sent: 120,
+                bytes_received: 0,
+                messages_sent: 2,
+                messages_received: 0,
+            }
+        );
+        assert_eq!(stats.bytes_sent(), 125);
+        assert_eq!(stats.bytes_received(), 7);
+        assert_eq!(stats.messages_sent(), 3);
+        assert_eq!(stats.messages_received(), 1);
+    }
+}
@@ -1,4 +1,4 @@
-//! Constants for the exponential backoff policy for gRPC
+//! Constants for the exponential backoff policy and channels for gRPC
 use lazy_static::lazy_static;
 use std::time::Duration;
 
@@ -11,4 +11,16 @@ lazy_static! {
 
     /// The default maximum elapsed time before giving up on retrying
     pub(crate) static ref MAX_ELAPSED_TIME: Option<Duration> = Some(Duration::from_secs(5 * 60));
+
+    /// The default maximum size of messages sent and received; effectively unlimited
+    pub(crate) static ref MAX_MESSAGE_SIZE: usize = usize::MAX;
+
+    /// The default timeout for establishing a connection
+    pub(crate) static ref CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// The default interval between keepalive pings
+    pub(crate) static ref KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// The default time to wait for a keepalive ping to be acknowledged
+    pub(crate) static ref KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(20);
 }
@@ -0,0 +1,114 @@
+//! Discovery of the endpoints at which parties can be reached.
+//!
+//! By default identities are themselves the endpoints of the parties. With
+//! discovery, identities may instead be logical names that are resolved to
+//! endpoints once per session, so that parties can move between sessions
+//! without their addresses being baked into every session request.
+
+use crate::error::{Error, Result};
+use crate::execution::Identity;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+/// Resolves identities to the endpoints at which they can currently be reached.
+#[async_trait]
+pub trait Discovery: Send + Sync {
+    async fn resolve(&self, identity: &Identity) -> Result<String>;
+}
+
+/// Fixed endpoints for some identities, using all other identities as endpoints.
+#[derive(Default)]
+pub struct StaticDiscovery {
+    endpoints: HashMap<Identity, String>,
+}
+
+impl StaticDiscovery {
+    pub fn new(endpoints: HashMap<Identity, String>) -> Self {
+        StaticDiscovery { endpoints }
+    }
+}
+
+#[async_trait]
+impl Discovery for StaticDiscovery {
+    async fn resolve(&self, identity: &Identity) -> Result<String> {
+        Ok(self
+            .endpoints
+            .get(identity)
+            .cloned()
+            .unwrap_or_else(|| identity.0.clone()))
+    }
+}
+
+/// Discovery through DNS SRV records.
+///
+/// Identity `x` is resolved to the target and port of the SRV record of
+/// `x<suffix>` with the lowest priority and, among those, the highest weight.
+#[cfg(feature = "dns")]
+pub struct DnsSrvDiscovery {
+    resolver: trust_dns_resolver::TokioAsyncResolver,
+    suffix: String,
+}
+
+#[cfg(feature = "dns")]
+impl DnsSrvDiscovery {
+    /// Look up records of `x<suffix>` using the system's DNS configuration.
+    pub fn from_system_conf(suffix: String) -> Result<Self> {
+        let resolver = trust_dns_resolver::TokioAsyncResolver::tokio_from_system_conf()
+            .map_err(|e| Error::Networking(format!("failed to create DNS resolver: {}", e)))?;
+        Ok(DnsSrvDiscovery { resolver, suffix })
+    }
+}
+
+#[cfg(feature = "dns")]
+#[async_trait]
+impl Discovery for DnsSrvDiscovery {
+    async fn resolve(&self, identity: &Identity) -> Result<String> {
+        let name = format!("{}{}", identity.0, self.suffix);
+        let lookup = self
+            .resolver
+            .srv_lookup(name.as_str())
+            .await
+            .map_err(|e| Error::Networking(format!("failed to look up '{}': {}", name, e)))?;
+        let record = lookup
+            .iter()
+            .min_by_key(|srv| (srv.priority(), std::cmp::Reverse(srv.weight())))
+            .ok_or_else(|| Error::Networking(format!("no SRV records found for '{}'", name)))?;
+        let target = record.target().to_utf8();
+        Ok(format!(
+            "{}:{}",
+            target.trim_end_matches('.'),
+            record.port()
+        ))
+    }
+}
+
+/// Endpoints of `identities`, resolved using `discovery`.
+pub async fn resolve_all<'a>(
+    discovery: &dyn Discovery,
+    identities: impl IntoIterator<Item = &'a Identity>,
+) -> Result<HashMap<Identity, String>> {
+    let mut endpoints = HashMap::new();
+    for identity in identities {
+        let endpoint = discovery.resolve(identity).await?;
+        tracing::debug!("Resolved '{}' to '{}'", identity, endpoint);
+        endpoints.insert(identity.clone(), endpoint);
+    }
+    Ok(endpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::hashmap;
+
+    #[tokio::test]
+    async fn test_static_discovery() {
+        let alice = Identity::from("alice");
+        let bob = Identity::from("localhost:50001");
+        let discovery = StaticDiscovery::new(hashmap!(alice.clone() => "10.0.0.1:50000".into()));
+
+        let endpoints = resolve_all(&discovery, vec![&alice, &bob]).await.unwrap();
+        assert_eq!(endpoints[&alice], "10.0.0.1:50000");
+        assert_eq!(endpoints[&bob], "localhost:50001");
+    }
+}
@@ -7,8 +7,11 @@ mod gen {
 
 use self::gen::networking_client::NetworkingClient;
 use self::gen::networking_server::{Networking, NetworkingServer};
-use self::gen::{SendValueRequest, SendValueResponse};
+use self::gen::{HeartbeatRequest, HeartbeatResponse, SendValueRequest, SendValueResponse};
+use crate::networking::accounting::{NetworkAccounting, NetworkStats};
 use crate::networking::constants;
+use crate::networking::discovery::Discovery;
+use crate::networking::wire;
 use crate::networking::AsyncNetworking;
 use crate::prelude::*;
 use crate::{Error, Result};
@@ -16,16 +19,70 @@ use async_cell::sync::AsyncCell;
 use async_trait::async_trait;
 use backoff::future::retry;
 use backoff::ExponentialBackoff;
+use bytes::Bytes;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tonic::transport::{Channel, ClientTlsConfig, Uri};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tonic::transport::{Channel, ClientTlsConfig};
+
+/// Configuration of the channels used for sending values to other parties.
+#[derive(Clone, Debug)]
+pub struct ChannelConfig {
+    /// Reuse a single channel per receiver instead of connecting on every send.
+    pub reuse_channels: bool,
+    /// Timeout for establishing a connection.
+    pub connect_timeout: Option<Duration>,
+    /// Deadline for each individual send; failed sends are retried.
+    pub request_timeout: Option<Duration>,
+    /// Interval between HTTP/2 keepalive pings; disabled if `None`.
+    pub keepalive_interval: Option<Duration>,
+    /// Time to wait for a keepalive ping to be acknowledged before closing the connection.
+    pub keepalive_timeout: Option<Duration>,
+    /// Also send keepalive pings while there are no outstanding requests.
+    pub keepalive_while_idle: bool,
+    /// TCP keepalive interval; disabled if `None`.
+    pub tcp_keepalive: Option<Duration>,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        ChannelConfig {
+            reuse_channels: true,
+            connect_timeout: Some(*constants::CONNECT_TIMEOUT),
+            request_timeout: None,
+            keepalive_interval: Some(*constants::KEEPALIVE_INTERVAL),
+            keepalive_timeout: Some(*constants::KEEPALIVE_TIMEOUT),
+            keepalive_while_idle: true,
+            tcp_keepalive: Some(*constants::KEEPALIVE_INTERVAL),
+        }
+    }
+}
+
+/// Configuration of the heartbeats used to detect unreachable peers.
+///
+/// While waiting for a value from a peer, heartbeats are sent to it every
+/// `interval`, failing the receive if none of them was answered for `timeout`.
+#[derive(Clone, Copy, Debug)]
+pub struct HeartbeatConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
 
 #[derive(Default, Clone)]
 pub struct GrpcNetworkingManager {
     stores: Arc<SessionStores>,
     channels: Arc<Channels>,
-    tls_client_config: Option<ClientTlsConfig>,
+    tls_client_config: Arc<RwLock<Option<ClientTlsConfig>>>,
+    channel_config: ChannelConfig,
+    auth_token: Option<String>,
+    max_message_size: Option<usize>,
+    discovery: Option<Arc<dyn Discovery>>,
+    heartbeats: Option<HeartbeatConfig>,
 }
 
 impl GrpcNetworkingManager {
@@ -33,13 +90,34 @@ impl GrpcNetworkingManager {
         NetworkingServer::new(NetworkingImpl {
             stores: Arc::clone(&self.stores),
         })
+        .max_decoding_message_size(self.max_message_size())
+    }
+
+    fn max_message_size(&self) -> usize {
+        self.max_message_size
+            .unwrap_or(*constants::MAX_MESSAGE_SIZE)
+    }
+
+    /// Limit the size of values sent and received, in bytes.
+    ///
+    /// By default values of any size are allowed.
+    pub fn with_max_message_size(self, max_message_size: usize) -> Self {
+        GrpcNetworkingManager {
+            max_message_size: Some(max_message_size),
+            ..self
+        }
     }
 
     pub fn without_tls() -> Self {
         GrpcNetworkingManager {
             stores: Default::default(),
             channels: Default::default(),
-            tls_client_config: None,
+            tls_client_config: Default::default(),
+            channel_config: Default::default(),
+            auth_token: None,
+            max_message_size: None,
+            discovery: None,
+            heartbeats: None,
         }
     }
 
@@ -47,52 +125,276 @@ impl GrpcNetworkingManager {
         GrpcNetworkingManager {
             stores: Default::default(),
             channels: Default::default(),
-            tls_client_config: Some(client),
+            tls_client_config: Arc::new(RwLock::new(Some(client))),
+            channel_config: Default::default(),
+            auth_token: None,
+            max_message_size: None,
+            discovery: None,
+            heartbeats: None,
+        }
+    }
+
+    /// Replace the client TLS configuration, e.g. after certificates have been rotated.
+    ///
+    /// Existing channels are dropped so that subsequent sends, including those of
+    /// running sessions, connect using the new configuration.
+    pub fn reload_tls_config(&self, client: ClientTlsConfig) {
+        *self.tls_client_config.write() = Some(client);
+        self.channels.clear();
+    }
+
+    /// Use `auth_token` as bearer token when sending values to other parties.
+    pub fn with_auth_token(self, auth_token: String) -> Self {
+        GrpcNetworkingManager {
+            auth_token: Some(auth_token),
+            ..self
+        }
+    }
+
+    pub fn with_channel_config(self, channel_config: ChannelConfig) -> Self {
+        GrpcNetworkingManager {
+            channel_config,
+            ..self
+        }
+    }
+
+    /// Resolve the identities of receivers to endpoints using `discovery`.
+    ///
+    /// Endpoints are resolved once per session, when first sending to a receiver.
+    pub fn with_discovery(self, discovery: Arc<dyn Discovery>) -> Self {
+        GrpcNetworkingManager {
+            discovery: Some(discovery),
+            ..self
         }
     }
 
+    /// Fail sessions promptly if a peer they are waiting for stops answering heartbeats.
+    ///
+    /// Without heartbeats, receiving from a peer that died waits until the
+    /// operation times out, if ever.
+    pub fn with_heartbeats(self, heartbeats: HeartbeatConfig) -> Self {
+        GrpcNetworkingManager {
+            heartbeats: Some(heartbeats),
+            ..self
+        }
+    }
+
+    /// Check that `peer` answers a heartbeat within `timeout`.
+    pub async fn check_peer(&self, peer: &Identity, timeout: Duration) -> Result<()> {
+        let endpoint = match self.discovery {
+            Some(ref discovery) => Identity::from(discovery.resolve(peer).await?),
+            None => peer.clone(),
+        };
+        let channel = new_channel(
+            peer,
+            &endpoint,
+            &self.channel_config,
+            &self.tls_client_config,
+        )?;
+        let mut request = tonic::Request::new(HeartbeatRequest {});
+        request.set_timeout(timeout);
+        crate::grpc::attach_token(&mut request, &self.auth_token).map_err(Error::Networking)?;
+        NetworkingClient::new(channel)
+            .heartbeat(request)
+            .await
+            .map_err(|e| {
+                Error::Networking(format!(
+                    "peer '{}' did not answer heartbeat: {}",
+                    peer,
+                    e.message()
+                ))
+            })?;
+        Ok(())
+    }
+
     pub fn new_session(&self, session_id: SessionId) -> Arc<impl AsyncNetworking> {
-        Arc::new(GrpcNetworking {
+        Arc::new(self.session(session_id))
+    }
+
+    fn session(&self, session_id: SessionId) -> GrpcNetworking {
+        GrpcNetworking {
             session_id,
             stores: Arc::clone(&self.stores),
             channels: Arc::clone(&self.channels),
-            tls_config: self.tls_client_config.clone(),
-        })
+            tls_config: Arc::clone(&self.tls_client_config),
+            channel_config: self.channel_config.clone(),
+            auth_token: self.auth_token.clone(),
+            max_message_size: self.max_message_size(),
+            accounting: NetworkAccounting::default(),
+            discovery: self.discovery.clone(),
+            endpoints: Default::default(),
+            heartbeats: self.heartbeats,
+            monitors: Default::default(),
+        }
     }
 }
 
 pub struct GrpcNetworking {
-    tls_config: Option<ClientTlsConfig>,
+    tls_config: Arc<RwLock<Option<ClientTlsConfig>>>,
+    channel_config: ChannelConfig,
+    auth_token: Option<String>,
+    max_message_size: usize,
     session_id: SessionId,
     stores: Arc<SessionStores>,
     channels: Arc<Channels>,
+    accounting: NetworkAccounting,
+    discovery: Option<Arc<dyn Discovery>>,
+    endpoints: DashMap<Identity, Identity>,
+    heartbeats: Option<HeartbeatConfig>,
+    monitors: DashMap<Identity, PeerMonitor>,
+}
+
+/// Task sending heartbeats to a peer, reporting it once it is deemed unreachable.
+struct PeerMonitor {
+    unreachable: watch::Receiver<Option<String>>,
+    task: JoinHandle<()>,
+}
+
+async fn monitor_peer(
+    peer: Identity,
+    channel: Channel,
+    auth_token: Option<String>,
+    config: HeartbeatConfig,
+    unreachable: watch::Sender<Option<String>>,
+) {
+    let mut last_seen = Instant::now();
+    loop {
+        tokio::time::sleep(config.interval).await;
+        let mut request = tonic::Request::new(HeartbeatRequest {});
+        request.set_timeout(config.interval);
+        let answered = crate::grpc::attach_token(&mut request, &auth_token).is_ok()
+            && NetworkingClient::new(channel.clone())
+                .heartbeat(request)
+                .await
+                .is_ok();
+        if answered {
+            last_seen = Instant::now();
+        } else if last_seen.elapsed() >= config.timeout {
+            let error = format!(
+                "peer '{}' unreachable since {:.1?} ago",
+                peer,
+                last_seen.elapsed()
+            );
+            tracing::warn!("{}", error);
+            let _ = unreachable.send(Some(error));
+            return;
+        }
+    }
+}
+
+/// Wait until the monitored peer is reported unreachable, returning the reason.
+async fn unreachable(receiver: &mut watch::Receiver<Option<String>>) -> String {
+    loop {
+        if let Some(error) = receiver.borrow_and_update().clone() {
+            return error;
+        }
+        if receiver.changed().await.is_err() {
+            // the monitor was stopped, so the peer is never reported
+            return futures::future::pending().await;
+        }
+    }
 }
 
 impl GrpcNetworking {
-    fn channel(&self, receiver: &Identity) -> Result<Channel> {
+    /// Status of `peer`, monitored using heartbeats for the rest of the session.
+    async fn monitor(
+        &self,
+        peer: &Identity,
+        config: HeartbeatConfig,
+    ) -> Result<watch::Receiver<Option<String>>> {
+        if let Some(monitor) = self.monitors.get(peer) {
+            return Ok(monitor.unreachable.clone());
+        }
+        let endpoint = self.endpoint(peer).await?;
+        let channel = self.channel(peer, &endpoint)?;
+        match self.monitors.entry(peer.clone()) {
+            Entry::Occupied(entry) => Ok(entry.get().unreachable.clone()),
+            Entry::Vacant(entry) => {
+                let (sender, receiver) = watch::channel(None);
+                let task = tokio::spawn(monitor_peer(
+                    peer.clone(),
+                    channel,
+                    self.auth_token.clone(),
+                    config,
+                    sender,
+                ));
+                entry.insert(PeerMonitor {
+                    unreachable: receiver.clone(),
+                    task,
+                });
+                Ok(receiver)
+            }
+        }
+    }
+
+    /// Endpoint of `receiver`, resolved at most once per session.
+    async fn endpoint(&self, receiver: &Identity) -> Result<Identity> {
+        let discovery = match &self.discovery {
+            Some(discovery) => discovery,
+            None => return Ok(receiver.clone()),
+        };
+        if let Some(endpoint) = self.endpoints.get(receiver) {
+            return Ok(endpoint.clone());
+        }
+        let endpoint = Identity::from(discovery.resolve(receiver).await?);
+        tracing::debug!("Resolved '{}' to '{}'", receiver, endpoint);
+        self.endpoints.insert(receiver.clone(), endpoint.clone());
+        Ok(endpoint)
+    }
+
+    fn channel(&self, receiver: &Identity, endpoint: &Identity) -> Result<Channel> {
+        if !self.channel_config.reuse_channels {
+            return self.new_channel(receiver, endpoint);
+        }
         let channel = self
             .channels
-            .entry(receiver.clone())
-            .or_try_insert_with(|| {
-                tracing::debug!("Creating channel to '{}'", receiver);
-                let endpoint: Uri = format!("http://{}", receiver).parse().map_err(|_e| {
-                    Error::Networking(format!(
-                        "failed to parse identity as endpoint: {:?}",
-                        receiver
-                    ))
-                })?;
-
-                let mut channel = Channel::builder(endpoint);
-                if let Some(ref tls_config) = self.tls_config {
-                    channel = channel.tls_config(tls_config.clone()).map_err(|e| {
-                        Error::Networking(format!("failed to TLS config {:?}", e.to_string()))
-                    })?;
-                };
-                Ok(channel.connect_lazy())
-            })?
+            .entry((receiver.clone(), endpoint.clone()))
+            .or_try_insert_with(|| self.new_channel(receiver, endpoint))?
             .clone(); // cloning channels is cheap per tonic documentation
         Ok(channel)
     }
+
+    fn new_channel(&self, receiver: &Identity, endpoint: &Identity) -> Result<Channel> {
+        new_channel(receiver, endpoint, &self.channel_config, &self.tls_config)
+    }
+}
+
+/// Channel to `receiver`, connecting to the `endpoint` it was resolved to.
+fn new_channel(
+    receiver: &Identity,
+    endpoint: &Identity,
+    config: &ChannelConfig,
+    tls_config: &RwLock<Option<ClientTlsConfig>>,
+) -> Result<Channel> {
+    tracing::debug!("Creating channel to '{}' at '{}'", receiver, endpoint);
+    let target = &endpoint.0;
+    let endpoint = crate::grpc::endpoint(target).map_err(Error::Networking)?;
+
+    let mut channel = endpoint
+        .keep_alive_while_idle(config.keepalive_while_idle)
+        .tcp_keepalive(config.tcp_keepalive);
+    if let Some(timeout) = config.connect_timeout {
+        channel = channel.connect_timeout(timeout);
+    }
+    if let Some(timeout) = config.request_timeout {
+        channel = channel.timeout(timeout);
+    }
+    if let Some(interval) = config.keepalive_interval {
+        channel = channel.http2_keep_alive_interval(interval);
+    }
+    if let Some(timeout) = config.keepalive_timeout {
+        channel = channel.keep_alive_timeout(timeout);
+    }
+    if let Some(ref tls_config) = *tls_config.read() {
+        let tls_config = match crate::grpc::tls_domain_name(&receiver.0) {
+            Some(domain_name) => tls_config.clone().domain_name(domain_name),
+            None => tls_config.clone(),
+        };
+        channel = channel
+            .tls_config(tls_config)
+            .map_err(|e| Error::Networking(format!("failed to TLS config {:?}", e.to_string())))?;
+    };
+    Ok(crate::grpc::connect_lazy(channel, target))
 }
 
 #[async_trait]
@@ -104,6 +406,16 @@ impl AsyncNetworking for GrpcNetworking {
         rendezvous_key: &RendezvousKey,
         _session_id: &SessionId,
     ) -> Result<()> {
+        let tag = bincode::serialize(&Tag {
+            session_id: self.session_id.clone(),
+            rendezvous_key: rendezvous_key.clone(),
+        })
+        .map_err(|e| Error::Networking(e.to_string()))?;
+        let mut bytes = tag;
+        bytes.extend(wire::serialize_value(val)?);
+        let bytes = Bytes::from(bytes);
+        let num_bytes = bytes.len();
+
         retry(
             ExponentialBackoff {
                 max_elapsed_time: *constants::MAX_ELAPSED_TIME,
@@ -112,24 +424,22 @@ impl AsyncNetworking for GrpcNetworking {
                 ..Default::default()
             },
             || async {
-                let tagged_value = TaggedValue {
-                    session_id: self.session_id.clone(),
-                    rendezvous_key: rendezvous_key.clone(),
-                    value: val.clone(),
-                };
-                let bytes = bincode::serialize(&tagged_value)
-                    .map_err(|e| Error::Networking(e.to_string()))?;
-                let request = SendValueRequest {
-                    tagged_value: bytes,
-                };
-                let channel = self.channel(receiver)?;
-                let mut client = NetworkingClient::new(channel);
+                let mut request = tonic::Request::new(SendValueRequest {
+                    tagged_value: bytes.clone(),
+                });
+                crate::grpc::attach_token(&mut request, &self.auth_token)
+                    .map_err(Error::Networking)?;
+                let endpoint = self.endpoint(receiver).await?;
+                let channel = self.channel(receiver, &endpoint)?;
+                let mut client =
+                    NetworkingClient::new(channel).max_encoding_message_size(self.max_message_size);
                 #[cfg(debug_assertions)]
-                tracing::debug!("Sending '{}' to {}", rendezvous_key, receiver);
+                tracing::debug!(%receiver, %rendezvous_key, bytes = num_bytes, "Sending value");
                 let _response = client
                     .send_value(request)
                     .await
                     .map_err(|e| Error::Networking(e.to_string()))?;
+                self.accounting.record_sent(receiver, num_bytes);
                 Ok(())
             },
         )
@@ -148,7 +458,17 @@ impl AsyncNetworking for GrpcNetworking {
             rendezvous_key.clone(),
         );
 
-        let (actual_sender, value) = cell.take().await;
+        let (actual_sender, value, num_bytes) = match self.heartbeats {
+            None => cell.take().await,
+            Some(config) => {
+                let mut status = self.monitor(sender, config).await?;
+                tokio::select! {
+                    taken = cell.take() => taken,
+                    error = unreachable(&mut status) => return Err(Error::Networking(error)),
+                }
+            }
+        };
+        self.accounting.record_received(sender, num_bytes);
         match actual_sender {
             Some(actual_sender) => {
                 if *sender != actual_sender {
@@ -158,30 +478,39 @@ impl AsyncNetworking for GrpcNetworking {
                     )))
                 } else {
                     #[cfg(debug_assertions)]
-                    tracing::debug!("Received '{}' from {}", rendezvous_key, sender);
+                    tracing::debug!(%sender, %rendezvous_key, bytes = num_bytes, "Received value");
                     Ok(value)
                 }
             }
             None => {
                 #[cfg(debug_assertions)]
-                tracing::debug!("Received '{}' from {}", rendezvous_key, sender);
+                tracing::debug!(%sender, %rendezvous_key, bytes = num_bytes, "Received value");
                 Ok(value)
             }
         }
     }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        Some(self.accounting.snapshot())
+    }
 }
 
 impl Drop for GrpcNetworking {
     fn drop(&mut self) {
         let _ = self.stores.remove(&self.session_id);
+        for monitor in self.monitors.iter() {
+            monitor.task.abort();
+        }
     }
 }
 
-type AuthValue = (Option<Identity>, Value);
+/// Value together with the authenticated sender and its size on the wire.
+type AuthValue = (Option<Identity>, Value, usize);
 
 type SessionStore = DashMap<RendezvousKey, Arc<AsyncCell<AuthValue>>>;
 type SessionStores = DashMap<SessionId, Arc<SessionStore>>;
-type Channels = DashMap<Identity, Channel>;
+/// Channels by receiver and the endpoint it was resolved to.
+type Channels = DashMap<(Identity, Identity), Channel>;
 
 #[derive(Default)]
 struct NetworkingImpl {
@@ -219,25 +548,174 @@ impl Networking for NetworkingImpl {
             .map(Identity::from);
 
         let request = request.into_inner();
-        let tagged_value =
-            bincode::deserialize::<TaggedValue>(&request.tagged_value).map_err(|_e| {
-                tonic::Status::new(tonic::Code::Aborted, "failed to parse value".to_string())
-            })?;
+        let num_bytes = request.tagged_value.len();
+        let mut reader = &request.tagged_value[..];
+        let tag = bincode::deserialize_from::<_, Tag>(&mut reader).map_err(|_e| {
+            tonic::Status::new(tonic::Code::Aborted, "failed to parse value".to_string())
+        })?;
+        let value = wire::deserialize_value(reader).map_err(|_e| {
+            tonic::Status::new(tonic::Code::Aborted, "failed to parse value".to_string())
+        })?;
 
-        let cell = cell(
-            &self.stores,
-            tagged_value.session_id,
-            tagged_value.rendezvous_key,
-        );
-        cell.set((sender, tagged_value.value));
+        let cell = cell(&self.stores, tag.session_id, tag.rendezvous_key);
+        cell.set((sender, value, num_bytes));
 
         Ok(tonic::Response::new(SendValueResponse::default()))
     }
+
+    async fn heartbeat(
+        &self,
+        _request: tonic::Request<HeartbeatRequest>,
+    ) -> std::result::Result<tonic::Response<HeartbeatResponse>, tonic::Status> {
+        Ok(tonic::Response::new(HeartbeatResponse::default()))
+    }
 }
 
+/// Header preceding the value in `SendValueRequest::tagged_value`.
 #[derive(Serialize, Deserialize)]
-struct TaggedValue {
+struct Tag {
     session_id: SessionId,
     rendezvous_key: RendezvousKey,
-    value: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{FromRaw, HostPlacement};
+    use crate::types::HostFloat64Tensor;
+    use ndarray::{array, Array1};
+    use std::convert::TryInto;
+
+    /// Serve `manager` on a local port, returning its endpoint.
+    async fn serve(manager: &GrpcNetworkingManager) -> Identity {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = Identity::from(listener.local_addr().unwrap().to_string());
+        let server = tonic::transport::Server::builder()
+            .add_service(manager.new_server())
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener));
+        tokio::spawn(server);
+        endpoint
+    }
+
+    fn tagged_value(session_id: &SessionId, rendezvous_key: &str, value: &Value) -> Bytes {
+        let mut bytes = bincode::serialize(&Tag {
+            session_id: session_id.clone(),
+            rendezvous_key: rendezvous_key.try_into().unwrap(),
+        })
+        .unwrap();
+        bytes.extend(wire::serialize_value(value).unwrap());
+        Bytes::from(bytes)
+    }
+
+    #[tokio::test]
+    async fn test_channel_reuse() {
+        let receiver = Identity::from("localhost:50000");
+
+        let networking = GrpcNetworkingManager::without_tls().session("12345".try_into().unwrap());
+        networking.channel(&receiver).unwrap();
+        networking.channel(&receiver).unwrap();
+        assert_eq!(networking.channels.len(), 1);
+
+        let networking = GrpcNetworkingManager::without_tls()
+            .with_channel_config(ChannelConfig {
+                reuse_channels: false,
+                ..Default::default()
+            })
+            .session("12345".try_into().unwrap());
+        networking.channel(&receiver).unwrap();
+        assert!(networking.channels.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_tls_config() {
+        let manager = GrpcNetworkingManager::without_tls();
+        let networking = manager.session("12345".try_into().unwrap());
+        networking
+            .channel(&Identity::from("localhost:50000"))
+            .unwrap();
+        assert_eq!(manager.channels.len(), 1);
+
+        // running sessions reconnect using the new configuration
+        manager.reload_tls_config(ClientTlsConfig::new().domain_name("alice"));
+        assert!(manager.channels.is_empty());
+        assert!(networking.tls_config.read().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_max_message_size() {
+        let manager = GrpcNetworkingManager::without_tls().with_max_message_size(1024);
+        let endpoint = serve(&manager).await;
+        let channel = new_channel(
+            &endpoint,
+            &endpoint,
+            &ChannelConfig::default(),
+            &RwLock::new(None),
+        )
+        .unwrap();
+        let mut client = NetworkingClient::new(channel);
+
+        let plc = HostPlacement::from("alice");
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let small: HostFloat64Tensor = plc.from_raw(array![1.0, 2.0]);
+        let small = Value::from(small);
+        let large: HostFloat64Tensor = plc.from_raw(Array1::<f64>::zeros(1000));
+        let large = Value::from(large);
+
+        let status = client
+            .send_value(SendValueRequest {
+                tagged_value: tagged_value(&session_id, "large", &large),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::OutOfRange);
+
+        client
+            .send_value(SendValueRequest {
+                tagged_value: tagged_value(&session_id, "small", &small),
+            })
+            .await
+            .unwrap();
+        let (_, received, _) = cell(&manager.stores, session_id, "small".try_into().unwrap())
+            .take()
+            .await;
+        assert_eq!(received, small);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats() {
+        let manager = GrpcNetworkingManager::without_tls();
+        let alive = serve(&manager).await;
+        manager
+            .check_peer(&alive, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        // nothing listens on the port of a dropped listener
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead = Identity::from(listener.local_addr().unwrap().to_string());
+        drop(listener);
+        assert!(manager
+            .check_peer(&dead, Duration::from_millis(500))
+            .await
+            .is_err());
+
+        // waiting for a value from the dead peer fails once it missed enough heartbeats
+        let session_id: SessionId = "12345".try_into().unwrap();
+        let networking = manager
+            .with_heartbeats(HeartbeatConfig {
+                interval: Duration::from_millis(10),
+                timeout: Duration::from_millis(50),
+            })
+            .session(session_id.clone());
+        let received = tokio::time::timeout(
+            Duration::from_secs(5),
+            networking.receive(&dead, &"rdv".try_into().unwrap(), &session_id),
+        )
+        .await
+        .unwrap();
+        match received {
+            Err(Error::Networking(e)) => assert!(e.contains("unreachable")),
+            res => panic!("expected peer to be unreachable but got {:?}", res),
+        }
+    }
 }
@@ -0,0 +1,264 @@
+//! Networking implementation connecting several in-process parties through channels.
+
+use super::accounting::{NetworkAccounting, NetworkStats};
+use super::wire;
+use super::*;
+use async_cell::sync::AsyncCell;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+type Mailboxes = DashMap<Identity, mpsc::UnboundedSender<Vec<u8>>>;
+
+type Inbox = DashMap<(SessionId, RendezvousKey), Arc<AsyncCell<(Identity, Value, usize)>>>;
+
+/// Collection of in-process parties able to exchange values.
+///
+/// Each party obtains its own endpoint via [`InProcessNetwork::endpoint`]. As opposed
+/// to `LocalAsyncNetworking`, values are serialized by the sender and deserialized
+/// by the receiver, meaning the full send/receive path is exercised without any
+/// sockets. This is intended for tests and benchmarks.
+#[derive(Default, Clone)]
+pub struct InProcessNetwork {
+    mailboxes: Arc<Mailboxes>,
+}
+
+impl InProcessNetwork {
+    /// Register a new party and return its networking endpoint.
+    ///
+    /// Registering the same identity twice replaces the previous endpoint.
+    pub fn endpoint(&self, identity: Identity) -> Arc<InProcessNetworking> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.mailboxes.insert(identity.clone(), sender);
+        Arc::new(InProcessNetworking {
+            own_identity: identity,
+            mailboxes: Arc::clone(&self.mailboxes),
+            inbox: Arc::default(),
+            incoming: Mutex::new(Some(receiver)),
+            accounting: Arc::default(),
+        })
+    }
+}
+
+/// Networking endpoint of a single in-process party.
+pub struct InProcessNetworking {
+    own_identity: Identity,
+    mailboxes: Arc<Mailboxes>,
+    inbox: Arc<Inbox>,
+    incoming: Mutex<Option<mpsc::UnboundedReceiver<Vec<u8>>>>,
+    accounting: Arc<DashMap<SessionId, NetworkAccounting>>,
+}
+
+/// Header preceding the value in each message.
+#[derive(Serialize, Deserialize)]
+struct Tag {
+    sender: Identity,
+    session_id: SessionId,
+    rendezvous_key: RendezvousKey,
+}
+
+fn decode(bytes: &[u8]) -> Result<(Tag, Value)> {
+    let mut reader = bytes;
+    let tag: Tag = bincode::deserialize_from(&mut reader)
+        .map_err(|e| Error::Networking(format!("failed to deserialize tag: {}", e)))?;
+    let value = wire::deserialize_value(reader)?;
+    Ok((tag, value))
+}
+
+fn cell(
+    inbox: &Inbox,
+    key: (SessionId, RendezvousKey),
+) -> Arc<AsyncCell<(Identity, Value, usize)>> {
+    inbox
+        .entry(key)
+        .or_insert_with(AsyncCell::shared)
+        .value()
+        .clone()
+}
+
+async fn deliver(mut incoming: mpsc::UnboundedReceiver<Vec<u8>>, inbox: Arc<Inbox>) {
+    while let Some(bytes) = incoming.recv().await {
+        match decode(&bytes) {
+            Ok((tag, value)) => {
+                let key = (tag.session_id, tag.rendezvous_key);
+                cell(&inbox, key).set((tag.sender, value, bytes.len()));
+            }
+            Err(e) => {
+                tracing::error!("failed to deserialize incoming value: {}", e);
+            }
+        }
+    }
+}
+
+impl InProcessNetworking {
+    /// Start moving incoming messages into the inbox, unless already started.
+    ///
+    /// This is done lazily since a Tokio runtime is needed to spawn the task.
+    fn ensure_delivery(&self) {
+        if let Some(incoming) = self.incoming.lock().take() {
+            tokio::spawn(deliver(incoming, Arc::clone(&self.inbox)));
+        }
+    }
+
+    /// Traffic statistics of this party for the given session.
+    pub fn session_stats(&self, session_id: &SessionId) -> NetworkStats {
+        self.accounting
+            .get(session_id)
+            .map(|accounting| accounting.snapshot())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for InProcessNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        tracing::debug!(
+            "In-process sending; rdv:'{}' sid:{} to:{}",
+            rendezvous_key,
+            session_id,
+            receiver
+        );
+        let tag = Tag {
+            sender: self.own_identity.clone(),
+            session_id: session_id.clone(),
+            rendezvous_key: rendezvous_key.clone(),
+        };
+        let mut bytes = bincode::serialize(&tag)
+            .map_err(|e| Error::Networking(format!("failed to serialize tag: {}", e)))?;
+        bytes.extend(wire::serialize_value(value)?);
+        let mailbox = self
+            .mailboxes
+            .get(receiver)
+            .ok_or_else(|| Error::Networking(format!("unknown receiver '{}'", receiver)))?;
+        let num_bytes = bytes.len();
+        mailbox.send(bytes).map_err(|_e| {
+            Error::Networking(format!("receiver '{}' is no longer listening", receiver))
+        })?;
+        self.accounting
+            .entry(session_id.clone())
+            .or_default()
+            .record_sent(receiver, num_bytes);
+        Ok(())
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        tracing::debug!(
+            "In-process receiving; rdv:'{}' sid:{} from:{}",
+            rendezvous_key,
+            session_id,
+            sender
+        );
+        self.ensure_delivery();
+
+        let key = (session_id.clone(), rendezvous_key.clone());
+        let (actual_sender, value, num_bytes) = cell(&self.inbox, key.clone()).take().await;
+        self.inbox.remove(&key);
+        self.accounting
+            .entry(session_id.clone())
+            .or_default()
+            .record_received(&actual_sender, num_bytes);
+
+        if *sender != actual_sender {
+            return Err(Error::Networking(format!(
+                "wrong sender; expected {:?} but got {:?}",
+                sender, actual_sender
+            )));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{FromRaw, HostPlacement};
+    use crate::types::HostFloat64Tensor;
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_exchange() {
+        let network = InProcessNetwork::default();
+        let alice = network.endpoint("alice".into());
+        let bob = network.endpoint("bob".into());
+
+        let plc = HostPlacement::from("alice");
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let x = Value::from(x);
+
+        let rdv: RendezvousKey = "rdv".try_into().unwrap();
+        let sid0: SessionId = "12345".try_into().unwrap();
+        let sid1: SessionId = "67890".try_into().unwrap();
+
+        let bob_task = {
+            let bob = Arc::clone(&bob);
+            let rdv = rdv.clone();
+            let sid0 = sid0.clone();
+            let sid1 = sid1.clone();
+            tokio::spawn(async move {
+                let y1 = bob.receive(&"alice".into(), &rdv, &sid1).await?;
+                let y0 = bob.receive(&"alice".into(), &rdv, &sid0).await?;
+                Ok::<_, Error>((y0, y1))
+            })
+        };
+
+        alice.send(&x, &"bob".into(), &rdv, &sid0).await.unwrap();
+        alice.send(&x, &"bob".into(), &rdv, &sid1).await.unwrap();
+
+        let (y0, y1) = bob_task.await.unwrap().unwrap();
+        assert_eq!(y0, x);
+        assert_eq!(y1, x);
+
+        let alice_stats = alice.session_stats(&sid0);
+        let bob_stats = bob.session_stats(&sid0);
+        assert_eq!(alice_stats.messages_sent(), 1);
+        assert_eq!(bob_stats.messages_received(), 1);
+        assert!(alice_stats.bytes_sent() > 0);
+        assert_eq!(alice_stats.bytes_sent(), bob_stats.bytes_received());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_sender() {
+        let network = InProcessNetwork::default();
+        let alice = network.endpoint("alice".into());
+        let bob = network.endpoint("bob".into());
+
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let rdv: RendezvousKey = "rdv".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+
+        alice.send(&unit, &"bob".into(), &rdv, &sid).await.unwrap();
+        let res = bob.receive(&"carole".into(), &rdv, &sid).await;
+        assert!(matches!(res, Err(Error::Networking(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_receiver() {
+        let network = InProcessNetwork::default();
+        let alice = network.endpoint("alice".into());
+
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let res = alice
+            .send(
+                &unit,
+                &"bob".into(),
+                &"rdv".try_into().unwrap(),
+                &"12345".try_into().unwrap(),
+            )
+            .await;
+        assert!(matches!(res, Err(Error::Networking(_))));
+    }
+}
@@ -3,12 +3,36 @@
 use crate::computation::*;
 use crate::error::{Error, Result};
 use crate::execution::Identity;
+use accounting::NetworkStats;
 use async_trait::async_trait;
 
+pub mod accounting;
+#[cfg(feature = "async_execute")]
 mod constants;
+#[cfg(feature = "async_execute")]
+pub mod discovery;
+#[cfg(feature = "async_execute")]
 pub mod grpc;
+#[cfg(feature = "async_execute")]
+pub mod inprocess;
 pub mod local;
+#[cfg(feature = "async_execute")]
+pub mod prioritized;
+#[cfg(feature = "async_execute")]
+pub mod relay;
+#[cfg(feature = "async_execute")]
+pub mod simulated;
+#[cfg(feature = "async_execute")]
+pub mod streaming;
+#[cfg(feature = "async_execute")]
 pub mod tcpstream;
+#[cfg(feature = "async_execute")]
+pub mod throttled;
+#[cfg(feature = "async_execute")]
+pub mod transcript;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+pub mod wire;
 
 /// Requirements for synchronous networking.
 ///
@@ -51,4 +75,9 @@ pub trait AsyncNetworking {
         rendezvous_key: &RendezvousKey,
         session_id: &SessionId,
     ) -> Result<Value>;
+
+    /// Traffic statistics collected so far, if supported by the implementation.
+    fn network_stats(&self) -> Option<NetworkStats> {
+        None
+    }
 }
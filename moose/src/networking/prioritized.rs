@@ -0,0 +1,246 @@
+//! Networking wrapper sending control values ahead of bulk tensor payloads.
+//!
+//! Protocols frequently need small values such as seeds and shapes to make
+//! progress, while large tensors may take a long time to transfer. Sending
+//! everything as soon as it is ready lets a round of the protocol stall behind
+//! unrelated bulk transfers to the same party; instead, bulk values to the same
+//! receiver are sent one at a time and only when no control values are waiting.
+
+use super::accounting::NetworkStats;
+use super::*;
+use crate::execution::AsyncNetworkingImpl;
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Notify, Semaphore};
+
+/// Scheduling class of an outgoing value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Priority {
+    /// Small values needed for protocol progress; never queued.
+    Control,
+    /// Large values sent one at a time per receiver.
+    Bulk,
+}
+
+impl Priority {
+    /// Classify `value`, treating values of at least `bulk_threshold` bytes as bulk.
+    ///
+    /// Seeds, keys, shapes, strings, and units are always control values.
+    pub fn of(value: &Value, bulk_threshold: u64) -> Result<Priority> {
+        match value.ty() {
+            Ty::HostSeed | Ty::HostPrfKey | Ty::HostShape | Ty::HostString | Ty::HostUnit => {
+                Ok(Priority::Control)
+            }
+            _ => {
+                let num_bytes = bincode::serialized_size(value).map_err(|e| {
+                    Error::Networking(format!("failed to estimate value size: {}", e))
+                })?;
+                if num_bytes >= bulk_threshold {
+                    Ok(Priority::Bulk)
+                } else {
+                    Ok(Priority::Control)
+                }
+            }
+        }
+    }
+}
+
+/// Outgoing traffic to a single receiver.
+struct PeerQueue {
+    bulk: Semaphore,
+    pending_control: AtomicUsize,
+    control_done: Notify,
+}
+
+impl Default for PeerQueue {
+    fn default() -> Self {
+        PeerQueue {
+            bulk: Semaphore::new(1),
+            pending_control: AtomicUsize::new(0),
+            control_done: Notify::new(),
+        }
+    }
+}
+
+impl PeerQueue {
+    /// Wait until no control values are being sent to this receiver.
+    async fn wait_for_control(&self) {
+        loop {
+            let notified = self.control_done.notified();
+            if self.pending_control.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Networking wrapper scheduling outgoing values by their [`Priority`].
+pub struct PrioritizedNetworking {
+    inner: AsyncNetworkingImpl,
+    bulk_threshold: u64,
+    queues: DashMap<Identity, Arc<PeerQueue>>,
+}
+
+impl PrioritizedNetworking {
+    pub fn new(inner: AsyncNetworkingImpl, bulk_threshold: u64) -> Self {
+        PrioritizedNetworking {
+            inner,
+            bulk_threshold,
+            queues: DashMap::default(),
+        }
+    }
+
+    fn queue(&self, receiver: &Identity) -> Arc<PeerQueue> {
+        self.queues
+            .entry(receiver.clone())
+            .or_insert_with(Arc::default)
+            .value()
+            .clone()
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for PrioritizedNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        let queue = self.queue(receiver);
+        match Priority::of(value, self.bulk_threshold)? {
+            Priority::Control => {
+                queue.pending_control.fetch_add(1, Ordering::SeqCst);
+                let res = self
+                    .inner
+                    .send(value, receiver, rendezvous_key, session_id)
+                    .await;
+                if queue.pending_control.fetch_sub(1, Ordering::SeqCst) == 1 {
+                    queue.control_done.notify_waiters();
+                }
+                res
+            }
+            Priority::Bulk => {
+                let _permit = queue.bulk.acquire().await.map_err(|e| {
+                    Error::Networking(format!("failed to schedule bulk value: {}", e))
+                })?;
+                queue.wait_for_control().await;
+                #[cfg(debug_assertions)]
+                tracing::debug!("Sending bulk value '{}' to {}", rendezvous_key, receiver);
+                self.inner
+                    .send(value, receiver, rendezvous_key, session_id)
+                    .await
+            }
+        }
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        self.inner.receive(sender, rendezvous_key, session_id).await
+    }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner.network_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{HostPlacement, HostTensor};
+    use ndarray::ArrayD;
+    use parking_lot::Mutex;
+    use std::convert::TryInto;
+    use std::time::Duration;
+
+    /// Records the order in which sends start and takes 100ms per bulk value.
+    #[derive(Default)]
+    struct RecordingNetworking {
+        started: Mutex<Vec<RendezvousKey>>,
+    }
+
+    #[async_trait]
+    impl AsyncNetworking for RecordingNetworking {
+        async fn send(
+            &self,
+            value: &Value,
+            _receiver: &Identity,
+            rendezvous_key: &RendezvousKey,
+            _session_id: &SessionId,
+        ) -> Result<()> {
+            self.started.lock().push(rendezvous_key.clone());
+            if Priority::of(value, 1_000)? == Priority::Bulk {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Ok(())
+        }
+
+        async fn receive(
+            &self,
+            _sender: &Identity,
+            _rendezvous_key: &RendezvousKey,
+            _session_id: &SessionId,
+        ) -> Result<Value> {
+            Err(Error::Networking(
+                "recording networking does not receive values".to_string(),
+            ))
+        }
+    }
+
+    fn tensor(len: usize) -> Value {
+        let plc = HostPlacement::from("alice");
+        let x = HostTensor::<f64>(ArrayD::zeros(vec![len]).into_shared(), plc);
+        Value::HostFloat64Tensor(Box::new(x))
+    }
+
+    #[test]
+    fn test_priority() {
+        let plc = HostPlacement::from("alice");
+        let unit = Value::HostUnit(Box::new(HostUnit(plc)));
+        assert_eq!(Priority::of(&unit, 0).unwrap(), Priority::Control);
+        assert_eq!(Priority::of(&tensor(10), 1_000).unwrap(), Priority::Control);
+        assert_eq!(Priority::of(&tensor(1_000), 1_000).unwrap(), Priority::Bulk);
+    }
+
+    #[tokio::test]
+    async fn test_control_overtakes_bulk() {
+        let recording = Arc::new(RecordingNetworking::default());
+        let networking = Arc::new(PrioritizedNetworking::new(
+            Arc::clone(&recording) as AsyncNetworkingImpl,
+            1_000,
+        ));
+        let sid: SessionId = "12345".try_into().unwrap();
+        let bob = Identity::from("bob");
+
+        let send = |key: &str, value: Value| {
+            let networking = Arc::clone(&networking);
+            let rdv: RendezvousKey = key.try_into().unwrap();
+            let sid = sid.clone();
+            let bob = bob.clone();
+            tokio::spawn(async move { networking.send(&value, &bob, &rdv, &sid).await })
+        };
+
+        let first = send("bulk0", tensor(1_000));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let second = send("bulk1", tensor(1_000));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let third = send("control", tensor(10));
+
+        for task in [first, second, third] {
+            task.await.unwrap().unwrap();
+        }
+
+        let started: Vec<RendezvousKey> = ["bulk0", "control", "bulk1"]
+            .iter()
+            .map(|key| (*key).try_into().unwrap())
+            .collect();
+        assert_eq!(*recording.started.lock(), started);
+    }
+}
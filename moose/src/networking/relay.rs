@@ -0,0 +1,425 @@
+//! Relay service and networking for parties that cannot reach each other directly.
+//!
+//! Parties behind NAT or restrictive firewalls can usually still open outgoing
+//! connections. The relay accepts values addressed to any party and holds them
+//! until the receiver fetches them, so all parties only ever connect to the
+//! relay. Connections to it should use TLS, in which case the relay
+//! authenticates both senders and receivers.
+//!
+//! Payloads are not encrypted end to end, so the relay sees every value sent
+//! through it. Since a relay collecting the shares of several parties would learn
+//! the secrets they share, only plaintext values such as floating-point and
+//! integer tensors are relayed; sessions sending ring or bit tensors, seeds, or
+//! keys, as replicated and additive protocols do, fail instead.
+//!
+//! Messages are only deleted by the relay once their receiver acknowledges them
+//! in its next fetch, so that messages are not lost with a failed response.
+
+mod gen {
+    #![allow(clippy::derive_partial_eq_without_eq)]
+    tonic::include_proto!("moose_relay");
+}
+
+use self::gen::relay_client::RelayClient;
+use self::gen::relay_server::{Relay, RelayServer};
+use self::gen::{FetchRequest, FetchResponse, ForwardRequest, ForwardResponse, RelayedMessage};
+use super::accounting::{NetworkAccounting, NetworkStats};
+use super::wire;
+use super::*;
+use async_cell::sync::AsyncCell;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tonic::transport::{Channel, ClientTlsConfig};
+
+/// How long a fetch waits for new messages before returning empty-handed.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// How long to wait before fetching again after a failure.
+const FETCH_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+struct MailboxState {
+    /// Messages not yet acknowledged by the receiver, ordered by sequence number.
+    messages: VecDeque<RelayedMessage>,
+    last_sequence_number: u64,
+}
+
+impl Default for MailboxState {
+    fn default() -> Self {
+        // starting at a random point keeps receivers from acknowledging messages
+        // with sequence numbers handed out by an earlier instance of the relay
+        MailboxState {
+            messages: VecDeque::new(),
+            last_sequence_number: rand::random::<u64>() >> 1,
+        }
+    }
+}
+
+impl MailboxState {
+    /// Delete the messages up to `acknowledged` and return the remaining ones.
+    fn acknowledge(&mut self, acknowledged: u64) -> Vec<RelayedMessage> {
+        if acknowledged <= self.last_sequence_number {
+            while let Some(message) = self.messages.front() {
+                if message.sequence_number > acknowledged {
+                    break;
+                }
+                self.messages.pop_front();
+            }
+        }
+        self.messages.iter().cloned().collect()
+    }
+}
+
+#[derive(Default)]
+struct Mailbox {
+    state: Mutex<MailboxState>,
+    notify: Notify,
+}
+
+/// Relay service holding messages until their receivers fetch them.
+#[derive(Default, Clone)]
+pub struct RelayService {
+    mailboxes: Arc<DashMap<String, Arc<Mailbox>>>,
+}
+
+impl RelayService {
+    pub fn new_server(&self) -> RelayServer<impl Relay> {
+        RelayServer::new(self.clone()).max_decoding_message_size(usize::MAX)
+    }
+
+    fn mailbox(&self, identity: &str) -> Arc<Mailbox> {
+        self.mailboxes
+            .entry(identity.to_string())
+            .or_insert_with(Arc::default)
+            .value()
+            .clone()
+    }
+}
+
+/// Determine the identity of the caller, preferring the authenticated identity.
+fn caller<T>(request: &tonic::Request<T>, claimed: &str) -> Result<String, tonic::Status> {
+    let authenticated = crate::grpc::extract_sender(request)
+        .map_err(|e| tonic::Status::new(tonic::Code::Unauthenticated, e))?;
+    match authenticated {
+        Some(identity) => Ok(identity),
+        None if !claimed.is_empty() => Ok(claimed.to_string()),
+        None => Err(tonic::Status::new(
+            tonic::Code::InvalidArgument,
+            "caller identity missing".to_string(),
+        )),
+    }
+}
+
+#[async_trait]
+impl Relay for RelayService {
+    async fn forward(
+        &self,
+        request: tonic::Request<ForwardRequest>,
+    ) -> std::result::Result<tonic::Response<ForwardResponse>, tonic::Status> {
+        let sender = caller(&request, &request.get_ref().sender)?;
+        let request = request.into_inner();
+        let mailbox = self.mailbox(&request.receiver);
+        {
+            let mut state = mailbox.state.lock();
+            state.last_sequence_number += 1;
+            let sequence_number = state.last_sequence_number;
+            state.messages.push_back(RelayedMessage {
+                sender,
+                payload: request.payload,
+                sequence_number,
+            });
+        }
+        mailbox.notify.notify_one();
+        Ok(tonic::Response::new(ForwardResponse::default()))
+    }
+
+    async fn fetch(
+        &self,
+        request: tonic::Request<FetchRequest>,
+    ) -> std::result::Result<tonic::Response<FetchResponse>, tonic::Status> {
+        let receiver = caller(&request, &request.get_ref().receiver)?;
+        let acknowledged = request.get_ref().acknowledged;
+        let mailbox = self.mailbox(&receiver);
+
+        let pending = || mailbox.state.lock().acknowledge(acknowledged);
+        let mut messages = pending();
+        if messages.is_empty() {
+            let _ = tokio::time::timeout(FETCH_TIMEOUT, mailbox.notify.notified()).await;
+            messages = pending();
+        }
+        Ok(tonic::Response::new(FetchResponse { messages }))
+    }
+}
+
+/// Header preceding the value in each payload.
+#[derive(Serialize, Deserialize)]
+struct Tag {
+    session_id: SessionId,
+    rendezvous_key: RendezvousKey,
+}
+
+type Store = DashMap<(SessionId, RendezvousKey), Arc<AsyncCell<(Identity, Value, usize)>>>;
+
+fn cell(
+    store: &Store,
+    key: (SessionId, RendezvousKey),
+) -> Arc<AsyncCell<(Identity, Value, usize)>> {
+    store
+        .entry(key)
+        .or_insert_with(AsyncCell::shared)
+        .value()
+        .clone()
+}
+
+/// Whether values of type `ty` are plaintext rather than possibly secret shares.
+fn is_plaintext(ty: Ty) -> bool {
+    matches!(
+        ty,
+        Ty::HostUnit
+            | Ty::HostShape
+            | Ty::HostString
+            | Ty::HostFloat32Tensor
+            | Ty::HostFloat64Tensor
+            | Ty::HostInt8Tensor
+            | Ty::HostInt16Tensor
+            | Ty::HostInt32Tensor
+            | Ty::HostInt64Tensor
+            | Ty::HostUint8Tensor
+            | Ty::HostUint16Tensor
+            | Ty::HostUint32Tensor
+            | Ty::HostUint64Tensor
+    )
+}
+
+/// Networking sending and receiving all values through a relay.
+///
+/// A single instance is shared by all sessions of a party.
+pub struct RelayNetworking {
+    own_identity: Identity,
+    client: RelayClient<Channel>,
+    store: Arc<Store>,
+    accounting: DashMap<SessionId, NetworkAccounting>,
+}
+
+impl RelayNetworking {
+    /// Connect to the relay at `relay_endpoint` and start fetching incoming values.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn new(
+        own_identity: Identity,
+        relay_endpoint: &str,
+        tls_config: Option<ClientTlsConfig>,
+    ) -> Result<Arc<Self>> {
+        let mut endpoint = crate::grpc::endpoint(relay_endpoint).map_err(Error::Networking)?;
+        if let Some(tls_config) = tls_config {
+            endpoint = endpoint.tls_config(tls_config).map_err(|e| {
+                Error::Networking(format!("failed to TLS config {:?}", e.to_string()))
+            })?;
+        }
+        let channel = crate::grpc::connect_lazy(endpoint, relay_endpoint);
+        let client = RelayClient::new(channel)
+            .max_decoding_message_size(usize::MAX)
+            .max_encoding_message_size(usize::MAX);
+
+        let networking = Arc::new(RelayNetworking {
+            own_identity,
+            client,
+            store: Arc::default(),
+            accounting: DashMap::default(),
+        });
+        tokio::spawn(fetch_loop(
+            networking.own_identity.clone(),
+            networking.client.clone(),
+            Arc::clone(&networking.store),
+        ));
+        Ok(networking)
+    }
+
+    /// Traffic statistics of this party for the given session.
+    pub fn session_stats(&self, session_id: &SessionId) -> NetworkStats {
+        self.accounting
+            .get(session_id)
+            .map(|accounting| accounting.snapshot())
+            .unwrap_or_default()
+    }
+}
+
+async fn fetch_loop(own_identity: Identity, mut client: RelayClient<Channel>, store: Arc<Store>) {
+    let mut acknowledged = 0;
+    loop {
+        let request = FetchRequest {
+            receiver: own_identity.0.clone(),
+            acknowledged,
+        };
+        let messages = match client.fetch(request).await {
+            Ok(response) => response.into_inner().messages,
+            Err(e) => {
+                tracing::error!("Failed to fetch from relay: {}", e);
+                tokio::time::sleep(FETCH_RETRY_INTERVAL).await;
+                continue;
+            }
+        };
+        if let Some(last) = messages.last() {
+            acknowledged = last.sequence_number;
+        }
+        for message in messages {
+            let num_bytes = message.payload.len();
+            let mut reader = &message.payload[..];
+            let tag = match bincode::deserialize_from::<_, Tag>(&mut reader) {
+                Ok(tag) => tag,
+                Err(e) => {
+                    tracing::error!("Failed to parse relayed value: {}", e);
+                    continue;
+                }
+            };
+            match wire::deserialize_value(reader) {
+                Ok(value) => {
+                    let sender = Identity::from(message.sender);
+                    cell(&store, (tag.session_id, tag.rendezvous_key))
+                        .set((sender, value, num_bytes));
+                }
+                Err(e) => tracing::error!("Failed to parse relayed value: {}", e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for RelayNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        if !is_plaintext(value.ty()) {
+            return Err(Error::Networking(format!(
+                "refusing to send {} through the relay, since it may be a secret share",
+                value.ty()
+            )));
+        }
+        let mut payload = bincode::serialize(&Tag {
+            session_id: session_id.clone(),
+            rendezvous_key: rendezvous_key.clone(),
+        })
+        .map_err(|e| Error::Networking(e.to_string()))?;
+        payload.extend(wire::serialize_value(value)?);
+        let num_bytes = payload.len();
+
+        let request = ForwardRequest {
+            receiver: receiver.0.clone(),
+            sender: self.own_identity.0.clone(),
+            payload,
+        };
+        self.client
+            .clone()
+            .forward(request)
+            .await
+            .map_err(|e| Error::Networking(format!("failed to forward to relay: {}", e)))?;
+        self.accounting
+            .entry(session_id.clone())
+            .or_default()
+            .record_sent(receiver, num_bytes);
+        Ok(())
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        let key = (session_id.clone(), rendezvous_key.clone());
+        let (actual_sender, value, num_bytes) = cell(&self.store, key.clone()).take().await;
+        self.store.remove(&key);
+        if *sender != actual_sender {
+            return Err(Error::Networking(format!(
+                "wrong sender; expected {:?} but got {:?}",
+                sender, actual_sender
+            )));
+        }
+        self.accounting
+            .entry(session_id.clone())
+            .or_default()
+            .record_received(sender, num_bytes);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{FromRaw, HostPlacement};
+    use crate::types::{HostFloat64Tensor, HostRing64Tensor};
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_relay() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tonic::transport::Server::builder()
+            .add_service(RelayService::default().new_server())
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener));
+        tokio::spawn(server);
+
+        let endpoint = addr.to_string();
+        let alice = RelayNetworking::new("alice".into(), &endpoint, None).unwrap();
+        let bob = RelayNetworking::new("bob".into(), &endpoint, None).unwrap();
+
+        let plc = HostPlacement::from("alice");
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let x = Value::from(x);
+        let rdv: RendezvousKey = "rdv".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+
+        alice.send(&x, &"bob".into(), &rdv, &sid).await.unwrap();
+        let y = bob.receive(&"alice".into(), &rdv, &sid).await.unwrap();
+        assert_eq!(y, x);
+        assert_eq!(
+            alice.session_stats(&sid).bytes_sent(),
+            bob.session_stats(&sid).bytes_received()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_refuses_shares() {
+        // the relay is never reached
+        let alice = RelayNetworking::new("alice".into(), "127.0.0.1:1", None).unwrap();
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![1u64, 2, 3]);
+        let rdv: RendezvousKey = "rdv".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+        let result = alice.send(&x.into(), &"bob".into(), &rdv, &sid).await;
+        assert!(matches!(result, Err(Error::Networking(_))));
+    }
+
+    #[test]
+    fn test_acknowledge() {
+        let mut state = MailboxState::default();
+        let first = state.last_sequence_number + 1;
+        for payload in [vec![1], vec![2], vec![3]] {
+            state.last_sequence_number += 1;
+            state.messages.push_back(RelayedMessage {
+                sender: "alice".to_string(),
+                payload,
+                sequence_number: state.last_sequence_number,
+            });
+        }
+
+        // messages are kept until acknowledged, such as when a response was lost
+        assert_eq!(state.acknowledge(0).len(), 3);
+        assert_eq!(state.acknowledge(0).len(), 3);
+        let pending = state.acknowledge(first);
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].payload, vec![2]);
+        // acknowledgements of an earlier relay are ignored
+        assert_eq!(state.acknowledge(u64::MAX).len(), 2);
+        assert!(state.acknowledge(first + 2).is_empty());
+    }
+}
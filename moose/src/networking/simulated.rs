@@ -0,0 +1,198 @@
+//! Networking wrapper simulating latency and bandwidth of wide-area links.
+
+use super::accounting::NetworkStats;
+use super::*;
+use crate::execution::AsyncNetworkingImpl;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Characteristics of a single (directed) link between two parties.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkProfile {
+    /// One-way delay added to every message.
+    pub latency: Duration,
+    /// Upper bound on the uniformly random delay added on top of `latency`.
+    pub jitter: Duration,
+    /// Bandwidth cap in bytes per second; unlimited if `None`.
+    pub bandwidth: Option<u64>,
+}
+
+impl LinkProfile {
+    fn transfer_time(&self, num_bytes: u64) -> Duration {
+        match self.bandwidth {
+            Some(bandwidth) if bandwidth > 0 => {
+                Duration::from_secs_f64(num_bytes as f64 / bandwidth as f64)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn propagation_delay(&self) -> Duration {
+        if self.jitter.is_zero() {
+            self.latency
+        } else {
+            self.latency + rand::thread_rng().gen_range(Duration::ZERO..=self.jitter)
+        }
+    }
+}
+
+/// Link characteristics of a simulated network.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkProfile {
+    pub default_link: LinkProfile,
+    pub links: HashMap<(Identity, Identity), LinkProfile>,
+}
+
+impl NetworkProfile {
+    pub fn new(default_link: LinkProfile) -> Self {
+        NetworkProfile {
+            default_link,
+            links: HashMap::new(),
+        }
+    }
+
+    /// Override the characteristics of the link from `sender` to `receiver`.
+    pub fn with_link(mut self, sender: Identity, receiver: Identity, link: LinkProfile) -> Self {
+        self.links.insert((sender, receiver), link);
+        self
+    }
+
+    pub fn link(&self, sender: &Identity, receiver: &Identity) -> &LinkProfile {
+        self.links
+            .get(&(sender.clone(), receiver.clone()))
+            .unwrap_or(&self.default_link)
+    }
+}
+
+/// Networking wrapper delaying outgoing values according to a `NetworkProfile`.
+///
+/// The size of each value is estimated from its serialized form. Values sent over
+/// the same link are transmitted one after the other, so the bandwidth cap is
+/// shared by concurrent sends. Since only sends are delayed, the wrapper must be
+/// applied to every party for the simulation to be accurate.
+pub struct SimulatedNetworking {
+    own_identity: Identity,
+    inner: AsyncNetworkingImpl,
+    profile: Arc<NetworkProfile>,
+    link_busy_until: Mutex<HashMap<Identity, Instant>>,
+}
+
+impl SimulatedNetworking {
+    pub fn new(
+        own_identity: Identity,
+        inner: AsyncNetworkingImpl,
+        profile: Arc<NetworkProfile>,
+    ) -> Self {
+        SimulatedNetworking {
+            own_identity,
+            inner,
+            profile,
+            link_busy_until: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserve the link to `receiver` for transmitting `num_bytes` and return the
+    /// instant at which the value arrives at the receiver.
+    fn schedule(&self, receiver: &Identity, num_bytes: u64) -> Instant {
+        let link = self.profile.link(&self.own_identity, receiver);
+        let now = Instant::now();
+        let mut link_busy_until = self.link_busy_until.lock();
+        let busy_until = link_busy_until.entry(receiver.clone()).or_insert(now);
+        let start = std::cmp::max(*busy_until, now);
+        *busy_until = start + link.transfer_time(num_bytes);
+        *busy_until + link.propagation_delay()
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for SimulatedNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        let num_bytes = bincode::serialized_size(value)
+            .map_err(|e| Error::Networking(format!("failed to estimate value size: {}", e)))?;
+        let arrival = self.schedule(receiver, num_bytes);
+        tokio::time::sleep_until(arrival).await;
+        self.inner
+            .send(value, receiver, rendezvous_key, session_id)
+            .await
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        self.inner.receive(sender, rendezvous_key, session_id).await
+    }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner.network_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::HostPlacement;
+    use crate::networking::inprocess::InProcessNetwork;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_transfer_time() {
+        let link = LinkProfile {
+            bandwidth: Some(1_000),
+            ..Default::default()
+        };
+        assert_eq!(link.transfer_time(500), Duration::from_millis(500));
+        assert_eq!(LinkProfile::default().transfer_time(500), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_link_lookup() {
+        let slow = LinkProfile {
+            latency: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let profile =
+            NetworkProfile::default().with_link("alice".into(), "bob".into(), slow.clone());
+        assert_eq!(profile.link(&"alice".into(), &"bob".into()), &slow);
+        assert_eq!(
+            profile.link(&"bob".into(), &"alice".into()),
+            &LinkProfile::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latency() {
+        let network = InProcessNetwork::default();
+        let profile = Arc::new(NetworkProfile::new(LinkProfile {
+            latency: Duration::from_millis(50),
+            ..Default::default()
+        }));
+        let alice = SimulatedNetworking::new(
+            "alice".into(),
+            network.endpoint("alice".into()),
+            Arc::clone(&profile),
+        );
+        let bob = network.endpoint("bob".into());
+
+        let unit = Value::HostUnit(Box::new(HostUnit(HostPlacement::from("alice"))));
+        let rdv: RendezvousKey = "rdv".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+
+        let start = std::time::Instant::now();
+        alice.send(&unit, &"bob".into(), &rdv, &sid).await.unwrap();
+        let res = bob.receive(&"alice".into(), &rdv, &sid).await.unwrap();
+        assert_eq!(res, unit);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,324 @@
+//! Networking wrapper sending large tensors as a stream of row blocks.
+//!
+//! Large host tensors are split along their first axis and each block is sent as
+//! a separate message, keeping individual messages small and letting blocks be
+//! transferred concurrently. Values are only split once they have been computed,
+//! and receivers reassemble them before passing them on, so dependent kernels
+//! still wait for the complete value.
+
+use super::accounting::NetworkStats;
+use super::*;
+use crate::execution::AsyncNetworkingImpl;
+use crate::host::{HostPlacement, HostShape, RawShape};
+use futures::stream::{Stream, StreamExt};
+use futures::FutureExt;
+use ndarray::{Axis, Slice};
+use std::sync::Arc;
+
+/// Maximum number of blocks a value is split into, larger values use larger blocks.
+const MAX_ROW_BLOCKS: usize = 1 << 16;
+
+/// Maximum number of blocks of a single value being received at a time.
+const MAX_BLOCKS_IN_FLIGHT: usize = 16;
+
+/// Derive the rendezvous key used for part `index` of the value sent under `key`.
+///
+/// The header announcing the number of blocks uses index 0, and blocks use
+/// indices starting from 1. Derived keys never coincide with keys produced by the
+/// compiler since they are pseudorandom.
+fn derive_key(key: &RendezvousKey, index: u64) -> RendezvousKey {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"row-block");
+    hasher.update(&key.0);
+    hasher.update(&index.to_le_bytes());
+    let mut raw = [0; TAG_BYTES];
+    raw.copy_from_slice(&hasher.finalize().as_bytes()[..TAG_BYTES]);
+    RendezvousKey(raw)
+}
+
+macro_rules! row_blocks {
+    ($($variant:ident($wrapper:ident),)*) => {
+        /// Split a host tensor into blocks of at most `block_rows` rows.
+        ///
+        /// Blocks are made larger if needed to produce at most `MAX_ROW_BLOCKS` blocks.
+        /// Returns `None` for values that are not row-partitionable host tensors.
+        fn split_rows(value: &Value, block_rows: usize) -> Option<Vec<Value>> {
+            match value {
+                $(
+                    Value::$variant(x) if x.0.ndim() > 0 => {
+                        let rows = x.0.len_of(Axis(0));
+                        let block_rows =
+                            std::cmp::max(block_rows, (rows + MAX_ROW_BLOCKS - 1) / MAX_ROW_BLOCKS);
+                        let blocks = (0..rows)
+                            .step_by(block_rows)
+                            .map(|start| {
+                                let end = std::cmp::min(start + block_rows, rows);
+                                let block = x.0.slice_axis(Axis(0), Slice::from(start..end));
+                                Value::$variant(Box::new(crate::host::$wrapper(
+                                    block.to_shared(),
+                                    x.1.clone(),
+                                )))
+                            })
+                            .collect();
+                        Some(blocks)
+                    }
+                )*
+                _ => None,
+            }
+        }
+
+        /// Concatenate row blocks produced by `split_rows`.
+        fn concat_rows(blocks: Vec<Value>) -> Result<Value> {
+            let first = blocks
+                .first()
+                .ok_or_else(|| Error::Networking("no row blocks received".to_string()))?;
+            match first {
+                $(
+                    Value::$variant(x) => {
+                        let plc = x.1.clone();
+                        let arrays = blocks
+                            .iter()
+                            .map(|block| match block {
+                                Value::$variant(x) => Ok(x.0.view()),
+                                _ => Err(Error::Networking(
+                                    "row blocks of different types received".to_string(),
+                                )),
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        let array = ndarray::concatenate(Axis(0), &arrays).map_err(|e| {
+                            Error::Networking(format!("failed to concatenate row blocks: {}", e))
+                        })?;
+                        Ok(Value::$variant(Box::new(crate::host::$wrapper(
+                            array.into_shared(),
+                            plc,
+                        ))))
+                    }
+                )*
+                _ => Err(Error::Networking(format!(
+                    "cannot concatenate row blocks of type {:?}",
+                    first.ty()
+                ))),
+            }
+        }
+    };
+}
+
+row_blocks![
+    HostRing64Tensor(HostRingTensor),
+    HostRing128Tensor(HostRingTensor),
+    HostFloat32Tensor(HostTensor),
+    HostFloat64Tensor(HostTensor),
+    HostInt32Tensor(HostTensor),
+    HostInt64Tensor(HostTensor),
+    HostUint32Tensor(HostTensor),
+    HostUint64Tensor(HostTensor),
+];
+
+/// Networking wrapper streaming host tensors with more than `block_rows` rows as row blocks.
+///
+/// Every value is preceded by a header giving the number of blocks, so the
+/// wrapper must be used by both the sending and the receiving party.
+pub struct StreamingNetworking {
+    inner: AsyncNetworkingImpl,
+    block_rows: usize,
+}
+
+impl StreamingNetworking {
+    pub fn new(inner: AsyncNetworkingImpl, block_rows: usize) -> Self {
+        assert!(block_rows > 0, "block_rows must be positive");
+        StreamingNetworking { inner, block_rows }
+    }
+
+    /// Receive the row blocks of a value in order.
+    ///
+    /// Values that were not split are yielded as a single block.
+    fn receive_row_blocks<'a>(
+        &'a self,
+        sender: &'a Identity,
+        rendezvous_key: &'a RendezvousKey,
+        session_id: &'a SessionId,
+    ) -> impl Stream<Item = Result<Value>> + 'a {
+        async move {
+            let header = self
+                .inner
+                .receive(sender, &derive_key(rendezvous_key, 0), session_id)
+                .await?;
+            let num_blocks = match header {
+                Value::HostShape(shape) if shape.0 .0.len() == 1 => shape.0 .0[0],
+                _ => return Err(Error::Networking("expected row block header".to_string())),
+            };
+            if num_blocks > MAX_ROW_BLOCKS {
+                return Err(Error::Networking(format!(
+                    "peer announced {} row blocks but at most {} are allowed",
+                    num_blocks, MAX_ROW_BLOCKS
+                )));
+            }
+            let blocks = futures::stream::iter(1..=num_blocks as u64)
+                .map(move |index| {
+                    let key = derive_key(rendezvous_key, index);
+                    async move { self.inner.receive(sender, &key, session_id).await }
+                })
+                .buffered(MAX_BLOCKS_IN_FLIGHT);
+            Ok(blocks)
+        }
+        .into_stream()
+        .map(|blocks| match blocks {
+            Ok(blocks) => blocks.left_stream(),
+            Err(e) => futures::stream::once(async move { Err(e) }).right_stream(),
+        })
+        .flatten()
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for StreamingNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        let blocks = split_rows(value, self.block_rows).unwrap_or_else(|| vec![value.clone()]);
+
+        let header = Value::HostShape(Box::new(HostShape(
+            RawShape(vec![blocks.len()]),
+            HostPlacement::from(receiver.0.as_str()),
+        )));
+        self.inner
+            .send(
+                &header,
+                receiver,
+                &derive_key(rendezvous_key, 0),
+                session_id,
+            )
+            .await?;
+
+        // blocks are sent concurrently so large values are not sent as a single message
+        let sends = blocks.iter().enumerate().map(|(i, block)| {
+            let key = derive_key(rendezvous_key, i as u64 + 1);
+            async move { self.inner.send(block, receiver, &key, session_id).await }
+        });
+        futures::future::try_join_all(sends).await?;
+        Ok(())
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        let mut blocks: Vec<Value> = self
+            .receive_row_blocks(sender, rendezvous_key, session_id)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+        if blocks.len() == 1 {
+            Ok(blocks.remove(0))
+        } else {
+            concat_rows(blocks)
+        }
+    }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner.network_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::FromRaw;
+    use crate::networking::local::LocalAsyncNetworking;
+    use crate::types::HostRing64Tensor;
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_split_and_concat() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![[1, 2], [3, 4], [5, 6]]);
+        let x = Value::from(x);
+
+        let blocks = split_rows(&x, 2).unwrap();
+        assert_eq!(blocks.len(), 2);
+        let expected: HostRing64Tensor = plc.from_raw(array![[5, 6]]);
+        assert_eq!(blocks[1], Value::from(expected));
+        assert_eq!(concat_rows(blocks).unwrap(), x);
+    }
+
+    #[tokio::test]
+    async fn test_streaming() {
+        let networking = StreamingNetworking::new(Arc::new(LocalAsyncNetworking::default()), 2);
+
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![[1, 2], [3, 4], [5, 6], [7, 8], [9, 10]]);
+        let x = Value::from(x);
+        let unit = Value::HostUnit(Box::new(HostUnit(plc)));
+
+        let rdv_x: RendezvousKey = "x".try_into().unwrap();
+        let rdv_unit: RendezvousKey = "unit".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+
+        networking
+            .send(&x, &"bob".into(), &rdv_x, &sid)
+            .await
+            .unwrap();
+        networking
+            .send(&unit, &"bob".into(), &rdv_unit, &sid)
+            .await
+            .unwrap();
+
+        let blocks: Vec<_> = networking
+            .receive_row_blocks(&"alice".into(), &rdv_x, &sid)
+            .collect()
+            .await;
+        assert_eq!(blocks.len(), 3);
+
+        networking
+            .send(&x, &"bob".into(), &rdv_x, &"67890".try_into().unwrap())
+            .await
+            .unwrap();
+        let y = networking
+            .receive(&"alice".into(), &rdv_x, &"67890".try_into().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(y, x);
+
+        let y = networking
+            .receive(&"alice".into(), &rdv_unit, &sid)
+            .await
+            .unwrap();
+        assert_eq!(y, unit);
+    }
+
+    #[tokio::test]
+    async fn test_reject_too_many_blocks() {
+        let inner = Arc::new(LocalAsyncNetworking::default());
+        let networking = StreamingNetworking::new(inner.clone(), 2);
+
+        let rdv: RendezvousKey = "x".try_into().unwrap();
+        let sid: SessionId = "12345".try_into().unwrap();
+        let header = Value::HostShape(Box::new(HostShape(
+            RawShape(vec![MAX_ROW_BLOCKS + 1]),
+            HostPlacement::from("bob"),
+        )));
+        inner
+            .send(&header, &"bob".into(), &derive_key(&rdv, 0), &sid)
+            .await
+            .unwrap();
+        let res = networking.receive(&"alice".into(), &rdv, &sid).await;
+        assert!(matches!(res, Err(Error::Networking(_))));
+    }
+
+    #[test]
+    fn test_split_caps_blocks() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor =
+            plc.from_raw(ndarray::Array2::<u64>::zeros((MAX_ROW_BLOCKS + 1, 1)));
+        let blocks = split_rows(&Value::from(x), 1).unwrap();
+        assert!(blocks.len() <= MAX_ROW_BLOCKS);
+    }
+}
@@ -0,0 +1,138 @@
+//! Networking wrapper limiting the bandwidth used for outgoing values.
+
+use super::accounting::NetworkStats;
+use super::*;
+use crate::execution::AsyncNetworkingImpl;
+use parking_lot::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket refilled with `rate` bytes per second, holding at most `burst` bytes.
+///
+/// Values larger than the bucket are still let through, but leave the bucket in
+/// debt so that subsequent values are delayed accordingly.
+pub struct TokenBucket {
+    rate: u64,
+    burst: u64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64, burst: u64) -> Self {
+        TokenBucket {
+            rate,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take `num_bytes` tokens from the bucket, returning how long the caller must
+    /// wait before actually sending.
+    fn reserve(&self, num_bytes: u64, now: Instant) -> Duration {
+        if self.rate == 0 {
+            return Duration::ZERO;
+        }
+        let mut state = self.state.lock();
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        state.tokens = f64::min(
+            self.burst as f64,
+            state.tokens + elapsed.as_secs_f64() * self.rate as f64,
+        );
+        state.last_refill = now;
+        state.tokens -= num_bytes as f64;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate as f64)
+        }
+    }
+}
+
+/// Networking wrapper delaying outgoing values to stay within a `TokenBucket`.
+///
+/// Intended to be applied to the networking of a single session, such that each
+/// session is throttled independently.
+pub struct ThrottledNetworking {
+    inner: AsyncNetworkingImpl,
+    bucket: TokenBucket,
+}
+
+impl ThrottledNetworking {
+    pub fn new(inner: AsyncNetworkingImpl, bucket: TokenBucket) -> Self {
+        ThrottledNetworking { inner, bucket }
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for ThrottledNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        let num_bytes = bincode::serialized_size(value)
+            .map_err(|e| Error::Networking(format!("failed to estimate value size: {}", e)))?;
+        let wait = self.bucket.reserve(num_bytes, Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!(
+                "Throttling '{}' to {} for {:?}",
+                rendezvous_key,
+                receiver,
+                wait
+            );
+            tokio::time::sleep(wait).await;
+        }
+        self.inner
+            .send(value, receiver, rendezvous_key, session_id)
+            .await
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        self.inner.receive(sender, rendezvous_key, session_id).await
+    }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner.network_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let bucket = TokenBucket::new(1_000, 500);
+        bucket.state.lock().last_refill = start;
+
+        // within burst
+        assert_eq!(bucket.reserve(400, start), Duration::ZERO);
+        // exceeds remaining tokens by 400 bytes
+        assert_eq!(bucket.reserve(500, start), Duration::from_millis(400));
+        // refilled with 1000 bytes but capped at burst
+        let later = start + Duration::from_secs(2);
+        assert_eq!(bucket.reserve(500, later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_unlimited_bucket() {
+        let bucket = TokenBucket::new(0, 0);
+        assert_eq!(bucket.reserve(1_000_000, Instant::now()), Duration::ZERO);
+    }
+}
@@ -0,0 +1,401 @@
+//! Commitments to the messages exchanged in sessions.
+//!
+//! Wrapping the networking of a session in `TranscriptNetworking` records a
+//! digest of every value sent to and received from each peer. Once the session
+//! has finished these are condensed into a `TranscriptCommitment`, optionally
+//! signed by the party, holding one digest per peer and direction. Messages are
+//! committed to in the order of their rendezvous keys rather than the order in
+//! which they happened to be sent, such that the commitment of the sender of
+//! messages matches that of their receiver whenever both saw the same values.
+//! Comparing the commitments of all parties with `check_transcripts` thus
+//! reveals which pairs of parties disagree about what was exchanged between
+//! them, which is the basis for resolving disputes about an execution.
+//!
+//! Parties do not exchange their commitments among themselves; they are
+//! collected by whoever retrieves the results of the session, who should
+//! first check them with `verify_transcripts` against the public keys they
+//! expect the parties to sign with.
+
+use super::accounting::NetworkStats;
+use super::*;
+use crate::execution::AsyncNetworkingImpl;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::path::Path;
+
+/// Digests of the messages exchanged with each peer, by rendezvous key.
+type Transcript = BTreeMap<String, BTreeMap<String, blake3::Hash>>;
+
+fn transcript_error(e: impl std::fmt::Display) -> Error {
+    Error::Networking(format!("transcript: {}", e))
+}
+
+fn value_digest(value: &Value) -> Result<blake3::Hash> {
+    let bytes = bincode::serialize(value).map_err(transcript_error)?;
+    Ok(blake3::hash(&bytes))
+}
+
+/// Hex-encoded digest of each peer's messages.
+fn condense(transcript: &Transcript) -> BTreeMap<String, String> {
+    transcript
+        .iter()
+        .map(|(peer, messages)| {
+            let mut hasher = blake3::Hasher::new();
+            for (rendezvous_key, digest) in messages {
+                hasher.update(rendezvous_key.as_bytes());
+                hasher.update(digest.as_bytes());
+            }
+            (peer.clone(), hasher.finalize().to_hex().to_string())
+        })
+        .collect()
+}
+
+/// Ed25519 key with which a party signs its transcript commitments.
+pub struct TranscriptSigner {
+    key: SigningKey,
+}
+
+impl TranscriptSigner {
+    pub fn from_bytes(secret_key: &[u8; 32]) -> Self {
+        TranscriptSigner {
+            key: SigningKey::from_bytes(secret_key),
+        }
+    }
+
+    /// Read the 32 bytes of a secret key from the file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            Error::InvalidArgument(format!(
+                "failed to read transcript key {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let secret_key = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+            Error::InvalidArgument(format!(
+                "transcript key {} must hold exactly 32 bytes, found {}",
+                path.display(),
+                bytes.len()
+            ))
+        })?;
+        Ok(TranscriptSigner::from_bytes(&secret_key))
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+}
+
+/// Commitment of a party to the messages it exchanged during a session.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptCommitment {
+    pub session_id: String,
+    pub identity: String,
+    /// Hex-encoded BLAKE3 digest of the messages sent to each peer.
+    pub sent: BTreeMap<String, String>,
+    /// Hex-encoded BLAKE3 digest of the messages received from each peer.
+    pub received: BTreeMap<String, String>,
+    /// Ed25519 public key of the party, if it signed the commitment.
+    pub public_key: Option<Vec<u8>>,
+    /// Ed25519 signature over all fields but the public key and the signature.
+    pub signature: Option<Vec<u8>>,
+}
+
+impl TranscriptCommitment {
+    fn signed_content(&self) -> Result<Vec<u8>> {
+        bincode::serialize(&(&self.session_id, &self.identity, &self.sent, &self.received))
+            .map_err(transcript_error)
+    }
+
+    pub fn sign(self, signer: &TranscriptSigner) -> Result<Self> {
+        let signature = signer.key.sign(&self.signed_content()?);
+        Ok(TranscriptCommitment {
+            public_key: Some(signer.public_key().to_vec()),
+            signature: Some(signature.to_bytes().to_vec()),
+            ..self
+        })
+    }
+
+    /// Check that the commitment was signed by the holder of `public_key`.
+    ///
+    /// The public key included in the commitment is deliberately ignored, since
+    /// it is only as trustworthy as the party presenting it.
+    pub fn verify(&self, public_key: &[u8; 32]) -> Result<()> {
+        let signature = self
+            .signature
+            .as_deref()
+            .and_then(|signature| <[u8; 64]>::try_from(signature).ok())
+            .ok_or_else(|| transcript_error("commitment is not signed"))?;
+        let public_key = VerifyingKey::from_bytes(public_key).map_err(transcript_error)?;
+        public_key
+            .verify(&self.signed_content()?, &Signature::from_bytes(&signature))
+            .map_err(|_| {
+                transcript_error(format!(
+                    "invalid signature on the commitment of {}",
+                    self.identity
+                ))
+            })
+    }
+}
+
+/// Ed25519 public keys of the parties, by identity, to verify commitments against.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptKeys(HashMap<String, [u8; 32]>);
+
+impl TranscriptKeys {
+    pub fn new(keys: HashMap<String, [u8; 32]>) -> Self {
+        TranscriptKeys(keys)
+    }
+
+    /// Read the key of each party from `<identity>.pub` in `dir`, holding its 32 bytes.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let read_error = |e: std::io::Error| {
+            Error::InvalidArgument(format!(
+                "failed to read transcript keys from {}: {}",
+                dir.display(),
+                e
+            ))
+        };
+        let mut keys = HashMap::new();
+        for entry in std::fs::read_dir(dir).map_err(read_error)? {
+            let path = entry.map_err(read_error)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pub") {
+                continue;
+            }
+            let identity = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(identity) => identity.to_string(),
+                None => continue,
+            };
+            let bytes = std::fs::read(&path).map_err(read_error)?;
+            let key = <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| {
+                Error::InvalidArgument(format!(
+                    "transcript key {} must hold exactly 32 bytes, found {}",
+                    path.display(),
+                    bytes.len()
+                ))
+            })?;
+            keys.insert(identity, key);
+        }
+        Ok(TranscriptKeys(keys))
+    }
+
+    pub fn get(&self, identity: &str) -> Option<&[u8; 32]> {
+        self.0.get(identity)
+    }
+}
+
+/// Check that every commitment was signed with the key configured for its party.
+///
+/// Fails on the first commitment of a party without a configured key, or with
+/// a missing or invalid signature.
+pub fn verify_transcripts(
+    commitments: &[TranscriptCommitment],
+    keys: &TranscriptKeys,
+) -> Result<()> {
+    for commitment in commitments {
+        let public_key = keys.get(&commitment.identity).ok_or_else(|| {
+            transcript_error(format!("no key configured for {}", commitment.identity))
+        })?;
+        commitment.verify(public_key)?;
+    }
+    Ok(())
+}
+
+/// Disagreements between the `commitments` of the parties of a session.
+///
+/// Messages between a party and a peer whose commitment is missing are not checked.
+pub fn check_transcripts(commitments: &[TranscriptCommitment]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let mut session_ids: Vec<&str> = commitments.iter().map(|c| c.session_id.as_str()).collect();
+    session_ids.sort_unstable();
+    session_ids.dedup();
+    if session_ids.len() > 1 {
+        mismatches.push(format!(
+            "commitments are for different sessions: {}",
+            session_ids.join(", ")
+        ));
+    }
+
+    for sender in commitments {
+        for receiver in commitments {
+            let sent = sender.sent.get(&receiver.identity);
+            let received = receiver.received.get(&sender.identity);
+            if sent != received {
+                mismatches.push(format!(
+                    "{} and {} disagree about the messages sent from {} to {}",
+                    sender.identity, receiver.identity, sender.identity, receiver.identity
+                ));
+            }
+        }
+    }
+    mismatches
+}
+
+/// Networking wrapper recording the messages of a single session.
+pub struct TranscriptNetworking {
+    inner: AsyncNetworkingImpl,
+    own_identity: Identity,
+    session_id: SessionId,
+    sent: Mutex<Transcript>,
+    received: Mutex<Transcript>,
+}
+
+impl TranscriptNetworking {
+    pub fn new(inner: AsyncNetworkingImpl, own_identity: Identity, session_id: SessionId) -> Self {
+        TranscriptNetworking {
+            inner,
+            own_identity,
+            session_id,
+            sent: Mutex::new(Transcript::new()),
+            received: Mutex::new(Transcript::new()),
+        }
+    }
+
+    /// Commitment to the messages exchanged so far, signed by `signer` if given.
+    pub fn commitment(&self, signer: Option<&TranscriptSigner>) -> Result<TranscriptCommitment> {
+        let commitment = TranscriptCommitment {
+            session_id: self.session_id.to_string(),
+            identity: self.own_identity.to_string(),
+            sent: condense(&self.sent.lock()),
+            received: condense(&self.received.lock()),
+            public_key: None,
+            signature: None,
+        };
+        match signer {
+            Some(signer) => commitment.sign(signer),
+            None => Ok(commitment),
+        }
+    }
+}
+
+fn record(transcript: &Mutex<Transcript>, peer: &Identity, key: &RendezvousKey, value: &Value) {
+    match value_digest(value) {
+        Ok(digest) => {
+            transcript
+                .lock()
+                .entry(peer.to_string())
+                .or_default()
+                .insert(key.to_string(), digest);
+        }
+        // leaves the transcript incomplete, which shows when comparing commitments
+        Err(e) => tracing::error!("Failed to record '{}' in transcript: {}", key, e),
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for TranscriptNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        self.inner
+            .send(value, receiver, rendezvous_key, session_id)
+            .await?;
+        record(&self.sent, receiver, rendezvous_key, value);
+        Ok(())
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        let value = self
+            .inner
+            .receive(sender, rendezvous_key, session_id)
+            .await?;
+        record(&self.received, sender, rendezvous_key, &value);
+        Ok(value)
+    }
+
+    fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner.network_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{HostPlacement, HostTensor};
+    use crate::networking::local::LocalAsyncNetworking;
+    use crate::types::HostFloat64Tensor;
+    use ndarray::array;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_transcripts() -> Result<()> {
+        let net: AsyncNetworkingImpl = Arc::new(LocalAsyncNetworking::default());
+        let session_id = SessionId::try_from("my-session")?;
+        let alice = TranscriptNetworking::new(net.clone(), "alice".into(), session_id.clone());
+        let bob = TranscriptNetworking::new(net, "bob".into(), session_id.clone());
+
+        let x: HostFloat64Tensor = HostTensor(
+            array![1.0, 2.0].into_dyn().into_shared(),
+            HostPlacement::from("alice"),
+        );
+        let x = Value::from(x);
+        let key_0 = RendezvousKey::try_from("0")?;
+        let key_1 = RendezvousKey::try_from("1")?;
+        alice.send(&x, &"bob".into(), &key_0, &session_id).await?;
+        alice.send(&x, &"bob".into(), &key_1, &session_id).await?;
+        // received in another order than sent
+        bob.receive(&"alice".into(), &key_1, &session_id).await?;
+        bob.receive(&"alice".into(), &key_0, &session_id).await?;
+
+        let signer = TranscriptSigner::from_bytes(&[7; 32]);
+        let alice_commitment = alice.commitment(Some(&signer))?;
+        let bob_commitment = bob.commitment(None)?;
+        alice_commitment.verify(&signer.public_key())?;
+        assert!(bob_commitment.verify(&signer.public_key()).is_err());
+        assert!(check_transcripts(&[alice_commitment.clone(), bob_commitment.clone()]).is_empty());
+
+        let mut tampered = alice_commitment.clone();
+        tampered.sent.insert("bob".to_string(), "00".to_string());
+        assert!(tampered.verify(&signer.public_key()).is_err());
+        let mismatches = check_transcripts(&[tampered, bob_commitment]);
+        assert_eq!(
+            mismatches,
+            vec!["alice and bob disagree about the messages sent from alice to bob"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_transcripts() -> Result<()> {
+        let commitment = |identity: &str| TranscriptCommitment {
+            session_id: "my-session".to_string(),
+            identity: identity.to_string(),
+            sent: BTreeMap::new(),
+            received: BTreeMap::new(),
+            public_key: None,
+            signature: None,
+        };
+        let alice_signer = TranscriptSigner::from_bytes(&[7; 32]);
+        let bob_signer = TranscriptSigner::from_bytes(&[8; 32]);
+        let alice = commitment("alice").sign(&alice_signer)?;
+        let bob = commitment("bob").sign(&bob_signer)?;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("alice.pub"), alice_signer.public_key()).unwrap();
+        std::fs::write(dir.path().join("bob.pub"), bob_signer.public_key()).unwrap();
+        let keys = TranscriptKeys::from_dir(dir.path())?;
+        verify_transcripts(&[alice.clone(), bob.clone()], &keys)?;
+
+        // signed with a key other than the one configured for the party
+        let forged = commitment("bob").sign(&alice_signer)?;
+        assert!(verify_transcripts(&[alice.clone(), forged], &keys).is_err());
+        // unsigned
+        assert!(verify_transcripts(&[alice.clone(), commitment("bob")], &keys).is_err());
+        // party without a configured key
+        let carole = commitment("carole").sign(&bob_signer)?;
+        assert!(verify_transcripts(&[alice, carole], &keys).is_err());
+        Ok(())
+    }
+}
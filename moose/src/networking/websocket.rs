@@ -0,0 +1,265 @@
+//! WebSocket-based networking implementation.
+//!
+//! Connections are bidirectional: a party that cannot accept incoming
+//! connections (e.g. a data owner behind a restrictive firewall) may dial
+//! out to a worker, after which the worker sends values back over the same
+//! connection. Parties that are not yet connected are dialed at `ws://<identity>`.
+
+use crate::computation::{RendezvousKey, SessionId, Value};
+use crate::execution::Identity;
+use crate::networking::AsyncNetworking;
+use crate::{Error, Result};
+use async_cell::sync::AsyncCell;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+type Store = DashMap<(SessionId, RendezvousKey), Arc<AsyncCell<(Identity, Value)>>>;
+
+type Peers = DashMap<Identity, mpsc::UnboundedSender<Message>>;
+
+#[derive(Serialize, Deserialize)]
+enum Frame {
+    /// First frame sent by the dialing party to announce its identity.
+    Hello(Identity),
+    Value {
+        sender: Identity,
+        session_id: SessionId,
+        rendezvous_key: RendezvousKey,
+        value: Value,
+    },
+}
+
+/// Networking over WebSocket connections, suited for browser and edge parties.
+#[derive(Clone)]
+pub struct WebSocketNetworking {
+    own_identity: Identity,
+    store: Arc<Store>,
+    peers: Arc<Peers>,
+}
+
+fn cell(store: &Store, key: (SessionId, RendezvousKey)) -> Arc<AsyncCell<(Identity, Value)>> {
+    store
+        .entry(key)
+        .or_insert_with(AsyncCell::shared)
+        .value()
+        .clone()
+}
+
+impl WebSocketNetworking {
+    pub fn new(own_identity: Identity) -> WebSocketNetworking {
+        WebSocketNetworking {
+            own_identity,
+            store: Default::default(),
+            peers: Default::default(),
+        }
+    }
+
+    /// Accept incoming connections on `addr` in a background task.
+    ///
+    /// Returns the address actually bound, which is useful when binding to port 0.
+    pub async fn listen(&self, addr: &str) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| Error::Networking(format!("could not bind to address {}: {}", addr, e)))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| Error::Networking(format!("could not get local address: {}", e)))?;
+
+        let networking = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer_addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::error!("failed to accept connection: {}", e);
+                        continue;
+                    }
+                };
+                tracing::debug!("accepted connection from {}", peer_addr);
+                match tokio_tungstenite::accept_async(stream).await {
+                    Ok(ws) => networking.spawn_connection(ws, None),
+                    Err(e) => tracing::error!("websocket handshake failed: {}", e),
+                }
+            }
+        });
+        Ok(local_addr)
+    }
+
+    /// Dial `endpoint` (e.g. `ws://host:port`) and use the connection for `peer`.
+    pub async fn connect(&self, peer: &Identity, endpoint: &str) -> Result<()> {
+        tracing::debug!("connecting to '{}' at {}", peer, endpoint);
+        let (ws, _response) = tokio_tungstenite::connect_async(endpoint)
+            .await
+            .map_err(|e| Error::Networking(format!("could not connect to {}: {}", endpoint, e)))?;
+        let sender = self.spawn_connection(ws, Some(peer.clone()));
+        let hello = bincode::serialize(&Frame::Hello(self.own_identity.clone()))
+            .map_err(|e| Error::Networking(format!("failed to serialize hello: {}", e)))?;
+        sender
+            .send(Message::Binary(hello))
+            .map_err(|_e| Error::Networking(format!("connection to '{}' closed", peer)))
+    }
+
+    fn spawn_connection<S>(
+        &self,
+        ws: WebSocketStream<S>,
+        peer: Option<Identity>,
+    ) -> mpsc::UnboundedSender<Message>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (mut sink, mut stream) = ws.split();
+        let (sender, mut outgoing) = mpsc::unbounded_channel::<Message>();
+        if let Some(peer) = peer {
+            self.peers.insert(peer, sender.clone());
+        }
+
+        tokio::spawn(async move {
+            while let Some(msg) = outgoing.recv().await {
+                if let Err(e) = sink.send(msg).await {
+                    tracing::error!("failed to write to websocket: {}", e);
+                    break;
+                }
+            }
+        });
+
+        let store = Arc::clone(&self.store);
+        let peers = Arc::clone(&self.peers);
+        let reply_sender = sender.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = stream.next().await {
+                let bytes = match msg {
+                    Ok(Message::Binary(bytes)) => bytes,
+                    Ok(Message::Close(_)) => break,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::error!("failed to read from websocket: {}", e);
+                        break;
+                    }
+                };
+                match bincode::deserialize::<Frame>(&bytes) {
+                    Ok(Frame::Hello(identity)) => {
+                        tracing::debug!("'{}' connected", identity);
+                        peers.insert(identity, reply_sender.clone());
+                    }
+                    Ok(Frame::Value {
+                        sender,
+                        session_id,
+                        rendezvous_key,
+                        value,
+                    }) => {
+                        cell(&store, (session_id, rendezvous_key)).set((sender, value));
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to deserialize incoming frame: {}", e);
+                    }
+                }
+            }
+        });
+
+        sender
+    }
+
+    async fn peer(&self, receiver: &Identity) -> Result<mpsc::UnboundedSender<Message>> {
+        if let Some(sender) = self.peers.get(receiver) {
+            return Ok(sender.clone());
+        }
+        self.connect(receiver, &format!("ws://{}", receiver))
+            .await?;
+        self.peers
+            .get(receiver)
+            .map(|sender| sender.clone())
+            .ok_or_else(|| Error::Networking(format!("no connection to '{}'", receiver)))
+    }
+}
+
+#[async_trait]
+impl AsyncNetworking for WebSocketNetworking {
+    async fn send(
+        &self,
+        value: &Value,
+        receiver: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        tracing::debug!("sending '{}' to {}", rendezvous_key, receiver);
+        let frame = Frame::Value {
+            sender: self.own_identity.clone(),
+            session_id: session_id.clone(),
+            rendezvous_key: rendezvous_key.clone(),
+            value: value.clone(),
+        };
+        let bytes = bincode::serialize(&frame)
+            .map_err(|e| Error::Networking(format!("failed to serialize value: {}", e)))?;
+        let sender = self.peer(receiver).await?;
+        sender
+            .send(Message::Binary(bytes))
+            .map_err(|_e| Error::Networking(format!("connection to '{}' closed", receiver)))
+    }
+
+    async fn receive(
+        &self,
+        sender: &Identity,
+        rendezvous_key: &RendezvousKey,
+        session_id: &SessionId,
+    ) -> Result<Value> {
+        let key = (session_id.clone(), rendezvous_key.clone());
+        let (actual_sender, value) = cell(&self.store, key.clone()).take().await;
+        self.store.remove(&key);
+        if *sender != actual_sender {
+            return Err(Error::Networking(format!(
+                "wrong sender; expected {:?} but got {:?}",
+                sender, actual_sender
+            )));
+        }
+        tracing::debug!("received '{}' from {}", rendezvous_key, sender);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{FromRaw, HostPlacement};
+    use crate::types::HostFloat64Tensor;
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    #[tokio::test]
+    async fn test_dial_out_and_reply() {
+        let worker = WebSocketNetworking::new("worker".into());
+        let addr = worker.listen("127.0.0.1:0").await.unwrap();
+
+        let edge = WebSocketNetworking::new("edge".into());
+        edge.connect(&"worker".into(), &format!("ws://{}", addr))
+            .await
+            .unwrap();
+
+        let plc = HostPlacement::from("edge");
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let x = Value::from(x);
+        let sid: SessionId = "12345".try_into().unwrap();
+        let input: RendezvousKey = "input".try_into().unwrap();
+        let output: RendezvousKey = "output".try_into().unwrap();
+
+        edge.send(&x, &"worker".into(), &input, &sid).await.unwrap();
+        let y = worker.receive(&"edge".into(), &input, &sid).await.unwrap();
+        assert_eq!(y, x);
+
+        // the worker replies over the connection opened by the edge party
+        worker
+            .send(&y, &"edge".into(), &output, &sid)
+            .await
+            .unwrap();
+        let z = edge.receive(&"worker".into(), &output, &sid).await.unwrap();
+        assert_eq!(z, x);
+    }
+}
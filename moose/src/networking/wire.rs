@@ -0,0 +1,310 @@
+//! Wire format for values sent between parties.
+//!
+//! Host tensors with fixed-size numeric elements, which make up the bulk of the
+//! traffic during protocol execution, are encoded as a small header followed by
+//! their elements as a flat little-endian buffer. On little-endian targets the
+//! elements of tensors in standard layout are copied to and from this buffer in
+//! bulk rather than one by one; other tensors are written element by element.
+//! All other values fall back to bincode.
+
+use crate::computation::{Role, Value};
+use crate::host::{HostPlacement, HostRingTensor, HostTensor};
+use crate::{Error, Result};
+use bincode::Options;
+use ndarray::{ArrayD, IxDyn};
+use std::convert::{TryFrom, TryInto};
+use std::num::Wrapping;
+
+/// Fixed-size element that can be written to and read from a little-endian buffer.
+///
+/// # Safety
+///
+/// Implementors must be integers or floats, or transparent wrappers of them, so
+/// that they have no padding, are valid for any bit pattern, and have `SIZE` bytes.
+unsafe trait WireElement: Sized {
+    const SIZE: usize;
+    fn write(&self, buf: &mut Vec<u8>);
+    fn read(bytes: &[u8]) -> Self;
+}
+
+macro_rules! wire_element {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl WireElement for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+wire_element!(u8, u16, u32, u64, u128, i8, i16, i32, i64, f32, f64);
+
+unsafe impl<T: WireElement> WireElement for Wrapping<T> {
+    const SIZE: usize = T::SIZE;
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        self.0.write(buf)
+    }
+
+    fn read(bytes: &[u8]) -> Self {
+        Wrapping(T::read(bytes))
+    }
+}
+
+/// Elements as they are laid out in memory, which is their wire encoding on little-endian targets.
+#[cfg(target_endian = "little")]
+fn as_bytes<T: WireElement>(elements: &[T]) -> &[u8] {
+    // SAFETY: elements have no padding as required by `WireElement`
+    unsafe {
+        std::slice::from_raw_parts(
+            elements.as_ptr() as *const u8,
+            std::mem::size_of_val(elements),
+        )
+    }
+}
+
+/// Elements encoded in `bytes`, whose length must be a multiple of the element size.
+fn read_elements<T: WireElement>(bytes: &[u8]) -> Vec<T> {
+    debug_assert_eq!(bytes.len() % T::SIZE, 0);
+    #[cfg(target_endian = "little")]
+    {
+        let len = bytes.len() / T::SIZE;
+        let mut elements = Vec::<T>::with_capacity(len);
+        // SAFETY: the vector has room for `len` elements, which are valid for any
+        // bit pattern as required by `WireElement`
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                elements.as_mut_ptr() as *mut u8,
+                len * T::SIZE,
+            );
+            elements.set_len(len);
+        }
+        elements
+    }
+    #[cfg(not(target_endian = "little"))]
+    {
+        bytes.chunks_exact(T::SIZE).map(T::read).collect()
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::Networking(
+                "unexpected end of serialized value".to_string(),
+            ));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        self.take(8).map(u64::read)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        usize::try_from(self.u64()?)
+            .map_err(|_e| Error::Networking("length in serialized value too large".to_string()))
+    }
+
+    /// Make sure that all bytes have been read.
+    fn finish(&self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(Error::Networking(format!(
+                "{} trailing bytes after serialized value",
+                self.bytes.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn write_array<T: WireElement>(
+    buf: &mut Vec<u8>,
+    tag: u8,
+    array: &ndarray::ArcArray<T, IxDyn>,
+    plc: &HostPlacement,
+) {
+    let owner = plc.owner.0.as_bytes();
+    buf.reserve(1 + 8 + owner.len() + 8 + 8 * array.ndim() + T::SIZE * array.len());
+    buf.push(tag);
+    (owner.len() as u64).write(buf);
+    buf.extend_from_slice(owner);
+    (array.ndim() as u64).write(buf);
+    for dim in array.shape() {
+        (*dim as u64).write(buf);
+    }
+    match array.as_slice() {
+        #[cfg(target_endian = "little")]
+        Some(elements) => buf.extend_from_slice(as_bytes(elements)),
+        _ => {
+            for element in array.iter() {
+                element.write(buf);
+            }
+        }
+    }
+}
+
+fn read_array<T: WireElement>(reader: &mut Reader) -> Result<(ArrayD<T>, HostPlacement)> {
+    let owner_len = reader.len()?;
+    let owner = std::str::from_utf8(reader.take(owner_len)?)
+        .map_err(|e| Error::Networking(format!("invalid placement in serialized value: {}", e)))?;
+    let plc = HostPlacement {
+        owner: Role::from(owner),
+    };
+
+    let ndim = reader.len()?;
+    let shape = (0..ndim)
+        .map(|_| reader.len())
+        .collect::<Result<Vec<_>>>()?;
+    let num_elements = shape
+        .iter()
+        .try_fold(1usize, |acc, dim| acc.checked_mul(*dim));
+    let num_bytes = num_elements
+        .and_then(|n| n.checked_mul(T::SIZE))
+        .ok_or_else(|| Error::Networking("shape in serialized value too large".to_string()))?;
+    let elements = read_elements(reader.take(num_bytes)?);
+    let array = ArrayD::from_shape_vec(IxDyn(&shape), elements)
+        .map_err(|e| Error::Networking(format!("invalid shape in serialized value: {}", e)))?;
+    Ok((array, plc))
+}
+
+macro_rules! wire_values {
+    ($($tag:literal => $variant:ident($wrapper:ident),)*) => {
+        /// Serialize a value for sending to another party.
+        pub fn serialize_value(value: &Value) -> Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            match value {
+                $(
+                    Value::$variant(x) => write_array(&mut buf, $tag, &x.0, &x.1),
+                )*
+                _ => {
+                    buf.push(BINCODE_TAG);
+                    bincode::serialize_into(&mut buf, value).map_err(|e| {
+                        Error::Networking(format!("failed to serialize value: {}", e))
+                    })?;
+                }
+            }
+            Ok(buf)
+        }
+
+        /// Deserialize a value produced by [`serialize_value`].
+        pub fn deserialize_value(bytes: &[u8]) -> Result<Value> {
+            let mut reader = Reader { bytes };
+            let tag = reader.take(1)?[0];
+            match tag {
+                // same encoding as `bincode::serialize_into` but without trailing bytes
+                BINCODE_TAG => bincode::DefaultOptions::new()
+                    .with_fixint_encoding()
+                    .reject_trailing_bytes()
+                    .deserialize(reader.bytes)
+                    .map_err(|e| {
+                        Error::Networking(format!("failed to deserialize value: {}", e))
+                    }),
+                $(
+                    $tag => {
+                        let (array, plc) = read_array(&mut reader)?;
+                        reader.finish()?;
+                        Ok(Value::$variant(Box::new($wrapper(array.into_shared(), plc))))
+                    }
+                )*
+                _ => Err(Error::Networking(format!(
+                    "unknown tag {} in serialized value",
+                    tag
+                ))),
+            }
+        }
+    };
+}
+
+const BINCODE_TAG: u8 = 0;
+
+wire_values![
+    1 => HostRing64Tensor(HostRingTensor),
+    2 => HostRing128Tensor(HostRingTensor),
+    3 => HostFloat32Tensor(HostTensor),
+    4 => HostFloat64Tensor(HostTensor),
+    5 => HostInt8Tensor(HostTensor),
+    6 => HostInt16Tensor(HostTensor),
+    7 => HostInt32Tensor(HostTensor),
+    8 => HostInt64Tensor(HostTensor),
+    9 => HostUint8Tensor(HostTensor),
+    10 => HostUint16Tensor(HostTensor),
+    11 => HostUint32Tensor(HostTensor),
+    12 => HostUint64Tensor(HostTensor),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::computation::HostUnit;
+    use crate::host::FromRaw;
+    use crate::types::{HostFloat64Tensor, HostRing128Tensor, HostRing64Tensor};
+    use ndarray::array;
+
+    fn roundtrip(value: Value) {
+        let bytes = serialize_value(&value).unwrap();
+        assert_eq!(deserialize_value(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn test_roundtrip_tensors() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![[1, 2, 3], [4, 5, u64::MAX]]);
+        roundtrip(Value::from(x));
+        let x: HostRing128Tensor = plc.from_raw(array![1, 2, u128::MAX]);
+        roundtrip(Value::from(x));
+        let x: HostFloat64Tensor = plc.from_raw(array![[[1.5], [-2.0]]]);
+        roundtrip(Value::from(x));
+    }
+
+    #[test]
+    fn test_roundtrip_non_standard_layout() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![[1, 2, 3], [4, 5, 6]].reversed_axes());
+        roundtrip(Value::from(x));
+    }
+
+    #[test]
+    fn test_roundtrip_fallback() {
+        roundtrip(Value::HostUnit(Box::new(HostUnit(HostPlacement::from(
+            "bob",
+        )))));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![1, 2, 3]);
+        let bytes = serialize_value(&Value::from(x)).unwrap();
+        assert!(deserialize_value(&bytes[..bytes.len() - 1]).is_err());
+        assert!(deserialize_value(&[]).is_err());
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let plc = HostPlacement::from("alice");
+        let x: HostRing64Tensor = plc.from_raw(array![1, 2, 3]);
+        let mut bytes = serialize_value(&Value::from(x)).unwrap();
+        bytes.push(0);
+        assert!(deserialize_value(&bytes).is_err());
+
+        let mut bytes = serialize_value(&Value::HostUnit(Box::new(HostUnit(plc)))).unwrap();
+        bytes.push(0);
+        assert!(deserialize_value(&bytes).is_err());
+    }
+}
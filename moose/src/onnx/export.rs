@@ -0,0 +1,441 @@
+//! Export of logical computations as ONNX models.
+//!
+//! Only plaintext computations can be exported: every operation must be placed on
+//! a host and compute on `Float32` or `Float64` tensors, or on the shapes thereof.
+//! This covers the reference pipelines that secure computations are usually checked
+//! against, which can then be evaluated by any ONNX runtime and compared with the
+//! results of evaluating them in moose.
+
+use super::proto::{
+    type_proto, AttributeProto, GraphProto, ModelProto, NodeProto, OperatorSetIdProto, TensorProto,
+    TypeProto, ValueInfoProto,
+};
+use super::{DOUBLE, FLOAT};
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::logical::TensorDType;
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+
+const INT64: i32 = 7;
+
+/// Version of the default ONNX operator set that exported models use.
+const OPSET_VERSION: i64 = 13;
+/// Version of the ONNX format introducing operator set 13.
+const IR_VERSION: i64 = 7;
+
+// kinds of attributes, as in the `AttributeType` enum of ONNX
+const ATTRIBUTE_INT: i32 = 2;
+const ATTRIBUTE_INTS: i32 = 7;
+
+/// Translate a plaintext logical computation into a serialized ONNX model.
+///
+/// Each argument of the computation becomes an input of the model named by its
+/// argument name, and each output an output named by its tag. Constants become
+/// initializers of the graph.
+pub fn export_onnx(comp: &Computation) -> Result<Vec<u8>> {
+    let mut exporter = Exporter::default();
+    for op in ordered(comp)? {
+        exporter.export_operation(op)?;
+    }
+    let graph = GraphProto {
+        node: exporter.nodes,
+        name: "moose".to_string(),
+        initializer: exporter.initializers,
+        input: exporter.inputs,
+        output: exporter.outputs,
+    };
+    let model = ModelProto {
+        ir_version: IR_VERSION,
+        producer_name: "moose".to_string(),
+        producer_version: env!("CARGO_PKG_VERSION").to_string(),
+        graph: Some(graph),
+        opset_import: vec![OperatorSetIdProto {
+            domain: String::new(),
+            version: OPSET_VERSION,
+        }],
+    };
+    Ok(model.encode_to_vec())
+}
+
+/// Operations of `comp` ordered such that each comes after its inputs, as required by ONNX.
+fn ordered(comp: &Computation) -> Result<Vec<&Operation>> {
+    let mut defined = HashSet::with_capacity(comp.operations.len());
+    let mut ordered = Vec::with_capacity(comp.operations.len());
+    let mut pending: Vec<&Operation> = comp.operations.iter().collect();
+    while !pending.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) = pending
+            .into_iter()
+            .partition(|op| op.inputs.iter().all(|input| defined.contains(input)));
+        if ready.is_empty() {
+            return Err(Error::MalformedComputation(format!(
+                "operation '{}' depends on an undefined or cyclic input",
+                blocked[0].name
+            )));
+        }
+        for op in ready {
+            defined.insert(&op.name);
+            ordered.push(op);
+        }
+        pending = blocked;
+    }
+    Ok(ordered)
+}
+
+#[derive(Default)]
+struct Exporter {
+    nodes: Vec<NodeProto>,
+    initializers: Vec<TensorProto>,
+    inputs: Vec<ValueInfoProto>,
+    outputs: Vec<ValueInfoProto>,
+    /// Name of the ONNX value holding the result of each operation, where not the operation name.
+    aliases: HashMap<String, String>,
+}
+
+impl Exporter {
+    fn value_name(&self, op_name: &str) -> String {
+        self.aliases
+            .get(op_name)
+            .cloned()
+            .unwrap_or_else(|| op_name.to_string())
+    }
+
+    fn node(&mut self, op: &Operation, op_type: &str, attribute: Vec<AttributeProto>) {
+        self.node_with_inputs(op, op_type, attribute, vec![]);
+    }
+
+    /// Add a node computing `op`, with `extra_inputs` following the inputs of `op`.
+    fn node_with_inputs(
+        &mut self,
+        op: &Operation,
+        op_type: &str,
+        attribute: Vec<AttributeProto>,
+        extra_inputs: Vec<String>,
+    ) {
+        let mut input: Vec<String> = op
+            .inputs
+            .iter()
+            .map(|input| self.value_name(input))
+            .collect();
+        input.extend(extra_inputs);
+        self.nodes.push(NodeProto {
+            input,
+            output: vec![op.name.clone()],
+            name: op.name.clone(),
+            op_type: op_type.to_string(),
+            attribute,
+            domain: String::new(),
+        });
+    }
+
+    /// Add an initializer holding the axes of `op`, for operators taking them as an input.
+    fn axes(&mut self, op: &Operation, axes: &[i64]) -> String {
+        let name = format!("{}_axes", op.name);
+        self.initializers.push(TensorProto {
+            dims: vec![axes.len() as i64],
+            data_type: INT64,
+            int64_data: axes.to_vec(),
+            name: name.clone(),
+            ..Default::default()
+        });
+        name
+    }
+
+    fn export_operation(&mut self, op: &Operation) -> Result<()> {
+        if !matches!(op.placement, Placement::Host(_)) {
+            return Err(not_plaintext(op));
+        }
+
+        match &op.kind {
+            Operator::Input(input) => {
+                let elem_type = elem_type(op, input.sig.ret())?;
+                self.inputs.push(value_info(&input.arg_name, elem_type));
+                self.aliases.insert(op.name.clone(), input.arg_name.clone());
+            }
+            Operator::Output(output) => {
+                let elem_type = elem_type(op, output.sig.ret())?;
+                let input = op.inputs.first().ok_or_else(|| missing_input(op))?;
+                self.nodes.push(NodeProto {
+                    input: vec![self.value_name(input)],
+                    output: vec![output.tag.clone()],
+                    name: op.name.clone(),
+                    op_type: "Identity".to_string(),
+                    ..Default::default()
+                });
+                self.outputs.push(value_info(&output.tag, elem_type));
+            }
+            Operator::Constant(constant) => {
+                let mut tensor = tensor(op, &constant.value)?;
+                tensor.name = op.name.clone();
+                self.initializers.push(tensor);
+            }
+            Operator::Cast(cast) => {
+                elem_type(op, cast.sig.arg(0)?)?;
+                let to = elem_type(op, cast.sig.ret())?;
+                self.node(op, "Cast", vec![int_attribute("to", to as i64)]);
+            }
+            Operator::Add(x) => self.float_node(op, x.sig, "Add")?,
+            Operator::Sub(x) => self.float_node(op, x.sig, "Sub")?,
+            Operator::Mul(x) => self.float_node(op, x.sig, "Mul")?,
+            Operator::Div(x) => self.float_node(op, x.sig, "Div")?,
+            Operator::Dot(x) => self.float_node(op, x.sig, "MatMul")?,
+            Operator::Maximum(x) => self.float_node(op, x.sig, "Max")?,
+            Operator::Relu(x) => self.float_node(op, x.sig, "Relu")?,
+            Operator::Sigmoid(x) => self.float_node(op, x.sig, "Sigmoid")?,
+            Operator::Exp(x) => self.float_node(op, x.sig, "Exp")?,
+            Operator::Neg(x) => self.float_node(op, x.sig, "Neg")?,
+            Operator::Abs(x) => self.float_node(op, x.sig, "Abs")?,
+            Operator::Sqrt(x) => self.float_node(op, x.sig, "Sqrt")?,
+            Operator::Log(x) => self.float_node(op, x.sig, "Log")?,
+            Operator::Identity(x) => self.float_node(op, x.sig, "Identity")?,
+            Operator::Transpose(x) => self.float_node(op, x.sig, "Transpose")?,
+            Operator::Reshape(x) => self.float_node(op, x.sig, "Reshape")?,
+            Operator::Softmax(x) => {
+                elem_type(op, x.sig.ret())?;
+                self.node(op, "Softmax", vec![int_attribute("axis", x.axis as i64)]);
+            }
+            Operator::Concat(x) => {
+                elem_type(op, x.sig.ret())?;
+                self.node(op, "Concat", vec![int_attribute("axis", x.axis as i64)]);
+            }
+            Operator::Sum(x) => {
+                elem_type(op, x.sig.ret())?;
+                // without axes, ONNX reduces over all axes as well
+                let axes = match x.axis {
+                    Some(axis) => vec![self.axes(op, &[axis as i64])],
+                    None => vec![],
+                };
+                self.node_with_inputs(op, "ReduceSum", vec![int_attribute("keepdims", 0)], axes);
+            }
+            Operator::Mean(x) => {
+                elem_type(op, x.sig.ret())?;
+                let mut attribute = vec![int_attribute("keepdims", 0)];
+                if let Some(axis) = x.axis {
+                    attribute.push(ints_attribute("axes", vec![axis as i64]));
+                }
+                self.node(op, "ReduceMean", attribute);
+            }
+            Operator::ExpandDims(x) => {
+                elem_type(op, x.sig.ret())?;
+                let axes: Vec<i64> = x.axis.iter().map(|axis| *axis as i64).collect();
+                let axes = self.axes(op, &axes);
+                self.node_with_inputs(op, "Unsqueeze", vec![], vec![axes]);
+            }
+            Operator::Shape(x) => {
+                elem_type(op, x.sig.arg(0)?)?;
+                self.node(op, "Shape", vec![]);
+            }
+            kind => {
+                return Err(Error::UnimplementedOperator(format!(
+                    "cannot export operation '{}' ({}) to ONNX",
+                    op.name,
+                    kind.short_name()
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a node for an operator that ONNX defines with the same inputs and semantics.
+    fn float_node(&mut self, op: &Operation, sig: Signature, op_type: &str) -> Result<()> {
+        elem_type(op, sig.ret())?;
+        self.node(op, op_type, vec![]);
+        Ok(())
+    }
+}
+
+/// ONNX element type of values of type `ty`, which must be plaintext floats or shapes.
+fn elem_type(op: &Operation, ty: Ty) -> Result<i32> {
+    match ty {
+        Ty::Tensor(TensorDType::Float32) | Ty::HostFloat32Tensor => Ok(FLOAT),
+        Ty::Tensor(TensorDType::Float64) | Ty::HostFloat64Tensor => Ok(DOUBLE),
+        Ty::Shape(_) | Ty::HostShape => Ok(INT64),
+        _ => Err(not_plaintext(op)),
+    }
+}
+
+fn tensor(op: &Operation, value: &Constant) -> Result<TensorProto> {
+    let dims = |shape: &[usize]| shape.iter().map(|dim| *dim as i64).collect();
+    match value {
+        Constant::HostFloat32Tensor(x) => Ok(TensorProto {
+            dims: dims(x.0.shape()),
+            data_type: FLOAT,
+            raw_data: x.0.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ..Default::default()
+        }),
+        Constant::HostFloat64Tensor(x) => Ok(TensorProto {
+            dims: dims(x.0.shape()),
+            data_type: DOUBLE,
+            raw_data: x.0.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ..Default::default()
+        }),
+        Constant::RawShape(shape) => Ok(TensorProto {
+            dims: vec![shape.0.len() as i64],
+            data_type: INT64,
+            int64_data: shape.0.iter().map(|dim| *dim as i64).collect(),
+            ..Default::default()
+        }),
+        _ => Err(not_plaintext(op)),
+    }
+}
+
+fn value_info(name: &str, elem_type: i32) -> ValueInfoProto {
+    ValueInfoProto {
+        name: name.to_string(),
+        r#type: Some(TypeProto {
+            tensor_type: Some(type_proto::Tensor {
+                elem_type,
+                shape: None,
+            }),
+        }),
+    }
+}
+
+fn int_attribute(name: &str, i: i64) -> AttributeProto {
+    AttributeProto {
+        name: name.to_string(),
+        i,
+        r#type: ATTRIBUTE_INT,
+        ..Default::default()
+    }
+}
+
+fn ints_attribute(name: &str, ints: Vec<i64>) -> AttributeProto {
+    AttributeProto {
+        name: name.to_string(),
+        ints,
+        r#type: ATTRIBUTE_INTS,
+        ..Default::default()
+    }
+}
+
+fn not_plaintext(op: &Operation) -> Error {
+    Error::InvalidArgument(format!(
+        "cannot export operation '{}' to ONNX since it does not compute on plaintext floats",
+        op.name
+    ))
+}
+
+fn missing_input(op: &Operation) -> Error {
+    Error::MalformedComputation(format!("operation '{}' is missing an input", op.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{import_onnx, PlacementStrategy};
+    use super::*;
+    use crate::host::{HostPlacement, HostTensor};
+    use ndarray::array;
+
+    fn computation() -> Computation {
+        let alice = Placement::from(HostPlacement::from("alice"));
+        let float = Ty::Tensor(TensorDType::Float64);
+        let weights = HostTensor::<f64>(
+            array![[1.0, -1.0], [2.0, 0.5]].into_dyn().into_shared(),
+            HostPlacement::from("alice"),
+        );
+        let operation = |name: &str, kind: Operator, inputs: &[&str]| Operation {
+            name: name.to_string(),
+            kind,
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            placement: alice.clone(),
+        };
+        Computation {
+            operations: vec![
+                operation(
+                    "output_0",
+                    OutputOp {
+                        sig: Signature::unary(float, float),
+                        tag: "y".to_string(),
+                    }
+                    .into(),
+                    &["relu_0"],
+                ),
+                operation(
+                    "x",
+                    InputOp {
+                        sig: Signature::nullary(float),
+                        arg_name: "features".to_string(),
+                    }
+                    .into(),
+                    &[],
+                ),
+                operation(
+                    "w",
+                    ConstantOp {
+                        sig: Signature::nullary(float),
+                        value: Constant::HostFloat64Tensor(weights),
+                    }
+                    .into(),
+                    &[],
+                ),
+                operation(
+                    "dot_0",
+                    DotOp {
+                        sig: Signature::binary(float, float, float),
+                    }
+                    .into(),
+                    &["x", "w"],
+                ),
+                operation(
+                    "relu_0",
+                    ReluOp {
+                        sig: Signature::unary(float, float),
+                    }
+                    .into(),
+                    &["dot_0"],
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_export() {
+        let model = ModelProto::decode(export_onnx(&computation()).unwrap().as_slice()).unwrap();
+        let graph = model.graph.unwrap();
+        let nodes: Vec<(&str, &[String])> = graph
+            .node
+            .iter()
+            .map(|node| (node.op_type.as_str(), node.input.as_slice()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("MatMul", &["features".to_string(), "w".to_string()][..]),
+                ("Relu", &["dot_0".to_string()][..]),
+                ("Identity", &["relu_0".to_string()][..]),
+            ]
+        );
+        assert_eq!(graph.input[0].name, "features");
+        assert_eq!(graph.output[0].name, "y");
+        assert_eq!(graph.initializer[0].data_type, DOUBLE);
+        assert_eq!(model.opset_import[0].version, OPSET_VERSION);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let model = export_onnx(&computation()).unwrap();
+        let alice = HostPlacement::from("alice");
+        let comp = import_onnx(&model, &PlacementStrategy::Host(alice)).unwrap();
+        let kinds: Vec<&str> = comp
+            .operations
+            .iter()
+            .map(|op| op.kind.short_name())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["Input", "Constant", "Dot", "Relu", "Identity", "Output"]
+        );
+    }
+
+    #[test]
+    fn test_export_secret() {
+        let mut comp = computation();
+        comp.operations[4].placement =
+            Placement::from(crate::replicated::ReplicatedPlacement::from([
+                "alice", "bob", "carole",
+            ]));
+        let err = export_onnx(&comp).unwrap_err();
+        assert!(err.to_string().contains("'relu_0'"));
+    }
+}
@@ -0,0 +1,799 @@
+//! Import of ONNX models as logical computations, and export of plaintext
+//! computations as ONNX models.
+//!
+//! The nodes of an ONNX graph are translated one by one into operations of the
+//! logical dialect, with the initializers of the graph becoming constants and its
+//! inputs and outputs becoming the arguments and outputs of the computation. Where
+//! each of these is placed is chosen by a [`PlacementStrategy`], allowing the same
+//! model to be evaluated in plaintext or on secret shares.
+//!
+//! Only a subset of ONNX operators is supported, covering the layers of common
+//! regression models and feed-forward networks such as those exported from
+//! scikit-learn and PyTorch; importing a model using any other operator fails.
+
+pub(crate) mod proto {
+    #![allow(clippy::derive_partial_eq_without_eq)]
+    include!(concat!(env!("OUT_DIR"), "/onnx.rs"));
+}
+
+mod export;
+
+pub use export::export_onnx;
+
+use self::proto::{AttributeProto, GraphProto, ModelProto, NodeProto, TensorProto};
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::host::HostPlacement;
+use crate::importer::ComputationBuilder;
+use crate::logical::{TensorDType, TensorShape};
+use crate::replicated::ReplicatedPlacement;
+use ndarray::{ArrayD, IxDyn};
+use prost::Message;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+const FLOAT: i32 = 1;
+const INT64: i32 = 7;
+const DOUBLE: i32 = 11;
+
+/// Where the values of an imported model are placed.
+#[derive(Clone, Debug)]
+pub enum PlacementStrategy {
+    /// Evaluate the model in plaintext on a single host, which provides the inputs
+    /// and weights and receives the outputs.
+    Host(HostPlacement),
+    /// Evaluate the model on secret shares, encoding values as fixed-point numbers.
+    Replicated {
+        /// Host providing the inputs of the model as arguments.
+        input_owner: HostPlacement,
+        /// Host holding the weights of the model.
+        model_owner: HostPlacement,
+        /// Host receiving the outputs of the model.
+        output_receiver: HostPlacement,
+        replicated: ReplicatedPlacement,
+        /// Fixed-point encoding of values while secret shared.
+        dtype: TensorDType,
+    },
+}
+
+impl PlacementStrategy {
+    /// Evaluate the model on secret shares held by `replicated`, using the default
+    /// fixed-point encoding of `Fixed128(24, 40)`.
+    pub fn replicated(
+        input_owner: HostPlacement,
+        model_owner: HostPlacement,
+        output_receiver: HostPlacement,
+        replicated: ReplicatedPlacement,
+    ) -> Self {
+        PlacementStrategy::Replicated {
+            input_owner,
+            model_owner,
+            output_receiver,
+            replicated,
+            dtype: TensorDType::Fixed128 {
+                integral_precision: 24,
+                fractional_precision: 40,
+            },
+        }
+    }
+
+    pub(crate) fn compute_placement(&self) -> Placement {
+        match self {
+            PlacementStrategy::Host(host) => host.clone().into(),
+            PlacementStrategy::Replicated { replicated, .. } => replicated.clone().into(),
+        }
+    }
+
+    pub(crate) fn compute_ty(&self) -> Ty {
+        match self {
+            PlacementStrategy::Host(_) => Ty::Tensor(TensorDType::Float64),
+            PlacementStrategy::Replicated { dtype, .. } => Ty::Tensor(*dtype),
+        }
+    }
+}
+
+/// Translate a serialized ONNX model into a logical computation placed according to `strategy`.
+///
+/// Each input of the model becomes an argument of the same name, expected to be a
+/// `Float64` tensor, and each output of the model an output tagged with its name.
+pub fn import_onnx(model: &[u8], strategy: &PlacementStrategy) -> Result<Computation> {
+    let model = ModelProto::decode(model)
+        .map_err(|e| Error::InvalidArgument(format!("failed to decode ONNX model: {}", e)))?;
+    let graph = model
+        .graph
+        .ok_or_else(|| Error::InvalidArgument("ONNX model has no graph".to_string()))?;
+    Importer::new(strategy, &graph).import(&graph)
+}
+
+/// Dimensions of an imported value, each of which may be unknown.
+pub(crate) type Shape = Option<Vec<Option<usize>>>;
+
+/// Value of the graph, as computed by an operation of the computation.
+#[derive(Clone)]
+struct Imported {
+    op_name: String,
+    shape: Shape,
+}
+
+struct Importer<'a> {
+    strategy: &'a PlacementStrategy,
+    /// Initializers of the graph, only imported once used.
+    initializers: HashMap<String, &'a TensorProto>,
+    values: HashMap<String, Imported>,
+    builder: ComputationBuilder<'a>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(strategy: &'a PlacementStrategy, graph: &'a GraphProto) -> Self {
+        Importer {
+            strategy,
+            initializers: graph
+                .initializer
+                .iter()
+                .map(|tensor| (tensor.name.clone(), tensor))
+                .collect(),
+            values: HashMap::new(),
+            builder: ComputationBuilder::new(strategy),
+        }
+    }
+
+    fn import(mut self, graph: &'a GraphProto) -> Result<Computation> {
+        for input in graph.input.iter() {
+            // older exporters also list initializers as inputs
+            if self.initializers.contains_key(&input.name) {
+                continue;
+            }
+            let shape = input
+                .r#type
+                .as_ref()
+                .and_then(|ty| ty.tensor_type.as_ref())
+                .and_then(|tensor| tensor.shape.as_ref())
+                .map(|shape| {
+                    shape
+                        .dim
+                        .iter()
+                        .map(|dim| match dim.value {
+                            Some(proto::tensor_shape_proto::dimension::Value::DimValue(n))
+                                if n >= 0 =>
+                            {
+                                Some(n as usize)
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                });
+            self.import_input(&input.name, shape);
+        }
+
+        for node in graph.node.iter() {
+            self.import_node(node)?;
+        }
+
+        for output in graph.output.iter() {
+            let value = self.value(&output.name)?;
+            self.builder.output(&output.name, value.op_name);
+        }
+        Ok(self.builder.finish())
+    }
+
+    fn import_input(&mut self, name: &str, shape: Shape) {
+        let op_name = self.builder.input(name);
+        self.values
+            .insert(name.to_string(), Imported { op_name, shape });
+    }
+
+    fn import_initializer(&mut self, tensor: &TensorProto) -> Result<Imported> {
+        let dims: Vec<usize> = tensor.dims.iter().map(|dim| *dim as usize).collect();
+        let values = float_values(tensor)?;
+        let array = ArrayD::from_shape_vec(IxDyn(&dims), values).map_err(|e| {
+            Error::InvalidArgument(format!(
+                "initializer '{}' has inconsistent data: {}",
+                tensor.name, e
+            ))
+        })?;
+        let op_name = self.builder.constant(array);
+        Ok(Imported {
+            op_name,
+            shape: Some(dims.into_iter().map(Some).collect()),
+        })
+    }
+
+    /// Value of the graph named `name`, importing it first if it is an initializer.
+    fn value(&mut self, name: &str) -> Result<Imported> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(value.clone());
+        }
+        let initializer = match self.initializers.get(name) {
+            Some(initializer) => *initializer,
+            None => {
+                return Err(Error::InvalidArgument(format!(
+                    "ONNX value '{}' is used before it is defined",
+                    name
+                )))
+            }
+        };
+        let value = self.import_initializer(initializer)?;
+        self.values.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn operand(&mut self, node: &NodeProto, index: usize) -> Result<Imported> {
+        match node.input.get(index) {
+            Some(name) if !name.is_empty() => self.value(name),
+            _ => Err(Error::InvalidArgument(format!(
+                "ONNX node '{}' ({}) is missing input {}",
+                node.name, node.op_type, index
+            ))),
+        }
+    }
+
+    fn reshape(&mut self, x: &Imported, shape: Vec<usize>) -> Imported {
+        let shape_op = self.builder.shape_constant(shape.clone());
+        let ty = self.strategy.compute_ty();
+        let op_name = self.builder.compute(
+            "reshape",
+            ReshapeOp {
+                sig: Signature::binary(ty, Ty::Shape(TensorShape::Host), ty),
+            }
+            .into(),
+            vec![x.op_name.clone(), shape_op],
+        );
+        Imported {
+            op_name,
+            shape: Some(shape.into_iter().map(Some).collect()),
+        }
+    }
+
+    /// Add an operation computing on the compute placement.
+    fn compute(&mut self, prefix: &str, kind: Operator, inputs: &[&Imported]) -> String {
+        let inputs = inputs.iter().map(|input| input.op_name.clone()).collect();
+        self.builder.compute(prefix, kind, inputs)
+    }
+
+    fn import_node(&mut self, node: &NodeProto) -> Result<()> {
+        let ty = self.strategy.compute_ty();
+        let unary = Signature::unary(ty, ty);
+        let binary = Signature::binary(ty, ty, ty);
+
+        let result = match node.op_type.as_str() {
+            "MatMul" => {
+                let (x, y) = (self.operand(node, 0)?, self.operand(node, 1)?);
+                let op_name = self.compute("dot", DotOp { sig: binary }.into(), &[&x, &y]);
+                Imported {
+                    op_name,
+                    shape: dot_shape(&x.shape, &y.shape),
+                }
+            }
+            "Gemm" => self.import_gemm(node)?,
+            "Add" | "Sub" | "Mul" | "Div" => {
+                let (x, y) = (self.operand(node, 0)?, self.operand(node, 1)?);
+                let (prefix, kind): (_, Operator) = match node.op_type.as_str() {
+                    "Add" => ("add", AddOp { sig: binary }.into()),
+                    "Sub" => ("sub", SubOp { sig: binary }.into()),
+                    "Mul" => ("mul", MulOp { sig: binary }.into()),
+                    _ => ("div", DivOp { sig: binary }.into()),
+                };
+                let op_name = self.compute(prefix, kind, &[&x, &y]);
+                Imported {
+                    op_name,
+                    shape: broadcast_shape(&x.shape, &y.shape),
+                }
+            }
+            "Relu" | "Sigmoid" | "Exp" | "Neg" | "Abs" | "Sqrt" | "Log" | "Identity" => {
+                let x = self.operand(node, 0)?;
+                let (prefix, kind): (_, Operator) = match node.op_type.as_str() {
+                    "Relu" => ("relu", ReluOp { sig: unary }.into()),
+                    "Sigmoid" => ("sigmoid", SigmoidOp { sig: unary }.into()),
+                    "Exp" => ("exp", ExpOp { sig: unary }.into()),
+                    "Neg" => ("neg", NegOp { sig: unary }.into()),
+                    "Abs" => ("abs", AbsOp { sig: unary }.into()),
+                    "Sqrt" => ("sqrt", SqrtOp { sig: unary }.into()),
+                    "Log" => ("log", LogOp { sig: unary }.into()),
+                    _ => ("identity", IdentityOp { sig: unary }.into()),
+                };
+                let op_name = self.compute(prefix, kind, &[&x]);
+                Imported {
+                    op_name,
+                    shape: x.shape,
+                }
+            }
+            "Softmax" => {
+                let x = self.operand(node, 0)?;
+                let rank = x.shape.as_ref().map(|shape| shape.len());
+                let axis = attribute(node, "axis").map(|attr| attr.i).unwrap_or(-1);
+                let axis = match (axis, rank) {
+                    (axis, _) if axis >= 0 => axis as usize,
+                    (axis, Some(rank)) if axis + rank as i64 >= 0 => (axis + rank as i64) as usize,
+                    _ => return Err(unknown_shape(node, "the axis to normalize over")),
+                };
+                let upmost_index = x
+                    .shape
+                    .as_ref()
+                    .and_then(|shape| shape.get(axis).copied().flatten())
+                    .ok_or_else(|| unknown_shape(node, "the size of the axis to normalize over"))?;
+                let op_name = self.compute(
+                    "softmax",
+                    SoftmaxOp {
+                        sig: unary,
+                        axis,
+                        upmost_index,
+                    }
+                    .into(),
+                    &[&x],
+                );
+                Imported {
+                    op_name,
+                    shape: x.shape,
+                }
+            }
+            "Transpose" => {
+                let x = self.operand(node, 0)?;
+                if let Some(attr) = attribute(node, "perm") {
+                    let reversed = (0..attr.ints.len() as i64)
+                        .rev()
+                        .eq(attr.ints.iter().copied());
+                    if !reversed {
+                        return Err(Error::UnimplementedOperator(format!(
+                            "ONNX node '{}' (Transpose) only supports reversing all axes",
+                            node.name
+                        )));
+                    }
+                }
+                let op_name = self.compute("transpose", TransposeOp { sig: unary }.into(), &[&x]);
+                Imported {
+                    op_name,
+                    shape: x.shape.map(|shape| shape.into_iter().rev().collect()),
+                }
+            }
+            "Reshape" => {
+                let x = self.operand(node, 0)?;
+                let target = node
+                    .input
+                    .get(1)
+                    .and_then(|name| self.initializers.get(name))
+                    .ok_or_else(|| {
+                        Error::UnimplementedOperator(format!(
+                            "ONNX node '{}' (Reshape) only supports shapes given as initializers",
+                            node.name
+                        ))
+                    })
+                    .and_then(|tensor| int_values(tensor))?;
+                if attribute(node, "allowzero").map_or(false, |attr| attr.i != 0) {
+                    return Err(Error::UnimplementedOperator(format!(
+                        "ONNX node '{}' (Reshape) does not support allowzero = 1",
+                        node.name
+                    )));
+                }
+                let shape = resolve_shape(node, &x.shape, &target)?;
+                self.reshape(&x, shape)
+            }
+            "Flatten" => {
+                let x = self.operand(node, 0)?;
+                let dims = x
+                    .shape
+                    .as_ref()
+                    .and_then(|shape| shape.iter().copied().collect::<Option<Vec<usize>>>())
+                    .ok_or_else(|| unknown_shape(node, "the shape of the input"))?;
+                let axis = attribute(node, "axis").map(|attr| attr.i).unwrap_or(1);
+                let rank = dims.len() as i64;
+                let axis = match axis {
+                    axis if (0..=rank).contains(&axis) => axis as usize,
+                    axis if (-rank..0).contains(&axis) => (axis + rank) as usize,
+                    axis => {
+                        return Err(Error::InvalidArgument(format!(
+                            "ONNX node '{}' (Flatten) has axis {} for input of rank {}",
+                            node.name, axis, rank
+                        )))
+                    }
+                };
+                let shape = vec![dims[..axis].iter().product(), dims[axis..].iter().product()];
+                self.reshape(&x, shape)
+            }
+            "Constant" => {
+                let tensor = attribute(node, "value")
+                    .and_then(|attr| attr.t.as_ref())
+                    .ok_or_else(|| {
+                        Error::UnimplementedOperator(format!(
+                            "ONNX node '{}' (Constant) only supports tensor values",
+                            node.name
+                        ))
+                    })?;
+                self.import_initializer(tensor)?
+            }
+            op_type => {
+                return Err(Error::UnimplementedOperator(format!(
+                    "ONNX operator '{}' of node '{}' is not supported",
+                    op_type, node.name
+                )))
+            }
+        };
+
+        let output = node.output.first().ok_or_else(|| {
+            Error::InvalidArgument(format!("ONNX node '{}' has no outputs", node.name))
+        })?;
+        self.values.insert(output.clone(), result);
+        Ok(())
+    }
+
+    /// `alpha * A' B' + beta * C` where `A'` and `B'` are optionally transposed.
+    fn import_gemm(&mut self, node: &NodeProto) -> Result<Imported> {
+        let ty = self.strategy.compute_ty();
+        for scale in ["alpha", "beta"].iter() {
+            if let Some(attr) = attribute(node, scale) {
+                if attr.f != 1.0 {
+                    return Err(Error::UnimplementedOperator(format!(
+                        "ONNX node '{}' (Gemm) only supports {} = 1",
+                        node.name, scale
+                    )));
+                }
+            }
+        }
+        let mut operands = Vec::with_capacity(2);
+        for (index, transposed) in [(0, "transA"), (1, "transB")].iter() {
+            let x = self.operand(node, *index)?;
+            let x = match attribute(node, transposed) {
+                Some(attr) if attr.i != 0 => {
+                    let op_name = self.compute(
+                        "transpose",
+                        TransposeOp {
+                            sig: Signature::unary(ty, ty),
+                        }
+                        .into(),
+                        &[&x],
+                    );
+                    Imported {
+                        op_name,
+                        shape: x.shape.map(|shape| shape.into_iter().rev().collect()),
+                    }
+                }
+                _ => x,
+            };
+            operands.push(x);
+        }
+        let binary = Signature::binary(ty, ty, ty);
+        let op_name = self.compute(
+            "dot",
+            DotOp { sig: binary }.into(),
+            &[&operands[0], &operands[1]],
+        );
+        let product = Imported {
+            op_name,
+            shape: dot_shape(&operands[0].shape, &operands[1].shape),
+        };
+        match node.input.get(2) {
+            Some(bias) if !bias.is_empty() => {
+                let bias = self.value(bias)?;
+                let op_name = self.compute("add", AddOp { sig: binary }.into(), &[&product, &bias]);
+                Ok(Imported {
+                    op_name,
+                    shape: broadcast_shape(&product.shape, &bias.shape),
+                })
+            }
+            _ => Ok(product),
+        }
+    }
+}
+
+fn attribute<'n>(node: &'n NodeProto, name: &str) -> Option<&'n AttributeProto> {
+    node.attribute.iter().find(|attr| attr.name == name)
+}
+
+fn unknown_shape(node: &NodeProto, what: &str) -> Error {
+    Error::InvalidArgument(format!(
+        "cannot determine {} of ONNX node '{}' ({}) from the shapes in the graph",
+        what, node.name, node.op_type
+    ))
+}
+
+pub(crate) fn dot_shape(x: &Shape, y: &Shape) -> Shape {
+    match (x.as_deref(), y.as_deref()) {
+        (Some([rows, _]), Some([_, cols])) => Some(vec![*rows, *cols]),
+        (Some([rows, _]), Some([_])) => Some(vec![*rows]),
+        (Some([_]), Some([_, cols])) => Some(vec![*cols]),
+        _ => None,
+    }
+}
+
+pub(crate) fn broadcast_shape(x: &Shape, y: &Shape) -> Shape {
+    let (x, y) = (x.as_ref()?, y.as_ref()?);
+    let (long, short) = if x.len() >= y.len() { (x, y) } else { (y, x) };
+    let offset = long.len() - short.len();
+    Some(
+        long.iter()
+            .enumerate()
+            .map(
+                |(i, dim)| match (dim, i.checked_sub(offset).map(|j| short[j])) {
+                    (Some(1), Some(other)) => other,
+                    (dim, _) => *dim,
+                },
+            )
+            .collect(),
+    )
+}
+
+/// Target shape of a `Reshape` node, where `0` keeps the dimension of the input
+/// and at most one dimension of `-1` is inferred from the size of the input.
+fn resolve_shape(node: &NodeProto, shape: &Shape, target: &[i64]) -> Result<Vec<usize>> {
+    let invalid = || {
+        Error::InvalidArgument(format!(
+            "ONNX node '{}' (Reshape) cannot reshape input of shape {:?} into {:?}",
+            node.name, shape, target
+        ))
+    };
+    let mut dims = Vec::with_capacity(target.len());
+    let mut inferred = None;
+    for (i, dim) in target.iter().enumerate() {
+        let dim = match *dim {
+            0 => shape
+                .as_ref()
+                .and_then(|shape| shape.get(i).copied().flatten())
+                .ok_or_else(|| unknown_shape(node, "the dimensions to keep"))?,
+            -1 if inferred.is_none() => {
+                inferred = Some(i);
+                1
+            }
+            dim if dim > 0 => dim as usize,
+            _ => return Err(invalid()),
+        };
+        dims.push(dim);
+    }
+    if let Some(index) = inferred {
+        let size: usize = shape
+            .as_ref()
+            .and_then(|shape| shape.iter().copied().product::<Option<usize>>())
+            .ok_or_else(|| unknown_shape(node, "the size of the input"))?;
+        let known: usize = dims.iter().product();
+        if known == 0 || size % known != 0 {
+            return Err(invalid());
+        }
+        dims[index] = size / known;
+    }
+    Ok(dims)
+}
+
+/// Entries of an integer tensor, such as the target shape of a `Reshape` node.
+fn int_values(tensor: &TensorProto) -> Result<Vec<i64>> {
+    match tensor.data_type {
+        INT64 if !tensor.raw_data.is_empty() => Ok(tensor
+            .raw_data
+            .chunks_exact(8)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect()),
+        INT64 => Ok(tensor.int64_data.clone()),
+        data_type => Err(Error::InvalidArgument(format!(
+            "expected ONNX tensor '{}' of 64-bit integers, got data type {}",
+            tensor.name, data_type
+        ))),
+    }
+}
+
+/// Entries of a floating-point tensor, converted to `f64`.
+fn float_values(tensor: &TensorProto) -> Result<Vec<f64>> {
+    match tensor.data_type {
+        FLOAT if !tensor.raw_data.is_empty() => Ok(tensor
+            .raw_data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64)
+            .collect()),
+        FLOAT => Ok(tensor.float_data.iter().map(|x| *x as f64).collect()),
+        DOUBLE if !tensor.raw_data.is_empty() => Ok(tensor
+            .raw_data
+            .chunks_exact(8)
+            .map(|bytes| {
+                let mut le = [0u8; 8];
+                le.copy_from_slice(bytes);
+                f64::from_le_bytes(le)
+            })
+            .collect()),
+        DOUBLE => Ok(tensor.double_data.clone()),
+        data_type => Err(Error::UnimplementedOperator(format!(
+            "ONNX tensor '{}' has unsupported data type {}",
+            tensor.name, data_type
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proto::*;
+    use super::*;
+    use crate::host::RawShape;
+    use ndarray::array;
+
+    fn tensor(name: &str, dims: &[i64], values: &[f32]) -> TensorProto {
+        TensorProto {
+            dims: dims.to_vec(),
+            data_type: FLOAT,
+            float_data: values.to_vec(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn node(op_type: &str, inputs: &[&str], output: &str) -> NodeProto {
+        NodeProto {
+            input: inputs.iter().map(|input| input.to_string()).collect(),
+            output: vec![output.to_string()],
+            name: output.to_string(),
+            op_type: op_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+        let dim = dims
+            .iter()
+            .map(|dim| tensor_shape_proto::Dimension {
+                value: Some(tensor_shape_proto::dimension::Value::DimValue(*dim)),
+            })
+            .collect();
+        ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                tensor_type: Some(type_proto::Tensor {
+                    elem_type: FLOAT,
+                    shape: Some(TensorShapeProto { dim }),
+                }),
+            }),
+        }
+    }
+
+    /// Two-layer network computing `softmax(relu(x W + b) V)`.
+    fn model() -> Vec<u8> {
+        let graph = GraphProto {
+            node: vec![
+                node("Gemm", &["x", "w", "b"], "h"),
+                node("Relu", &["h"], "a"),
+                node("MatMul", &["a", "v"], "z"),
+                node("Softmax", &["z"], "y"),
+            ],
+            name: "net".to_string(),
+            initializer: vec![
+                tensor("w", &[2, 2], &[1.0, -1.0, 2.0, 0.5]),
+                tensor("b", &[2], &[0.5, -1.0]),
+                tensor("v", &[2, 2], &[1.0, 0.0, 0.0, 1.0]),
+            ],
+            input: vec![value_info("x", &[1, 2])],
+            output: vec![value_info("y", &[1, 2])],
+        };
+        ModelProto {
+            ir_version: 8,
+            producer_name: "pytorch".to_string(),
+            graph: Some(graph),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn test_import_host() {
+        let alice = HostPlacement::from("alice");
+        let comp = import_onnx(&model(), &PlacementStrategy::Host(alice.clone())).unwrap();
+        let kinds: Vec<&str> = comp
+            .operations
+            .iter()
+            .map(|op| op.kind.short_name())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "Input", "Constant", "Dot", "Constant", "Add", "Relu", "Constant", "Dot",
+                "Softmax", "Output"
+            ]
+        );
+        assert!(comp
+            .operations
+            .iter()
+            .all(|op| op.placement == Placement::from(alice.clone())));
+
+        let weights = comp.operations[1].kind.clone();
+        let expected = array![[1.0, -1.0], [2.0, 0.5]].into_dyn().into_shared();
+        assert!(matches!(
+            weights,
+            Operator::Constant(ConstantOp { value: Constant::HostFloat64Tensor(ref w), .. }) if w.0 == expected
+        ));
+        assert!(matches!(
+            comp.operations[8].kind,
+            Operator::Softmax(SoftmaxOp {
+                axis: 1,
+                upmost_index: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_import_replicated() {
+        let strategy = PlacementStrategy::replicated(
+            HostPlacement::from("alice"),
+            HostPlacement::from("bob"),
+            HostPlacement::from("alice"),
+            ReplicatedPlacement::from(["alice", "bob", "carole"]),
+        );
+        let comp = import_onnx(&model(), &strategy).unwrap();
+        let placement_of = |prefix: &str| -> Vec<Placement> {
+            comp.operations
+                .iter()
+                .filter(|op| op.name.starts_with(prefix))
+                .map(|op| op.placement.clone())
+                .collect()
+        };
+        // inputs are encoded by their owner, weights by the model owner
+        assert_eq!(
+            placement_of("cast"),
+            vec![
+                Placement::from(HostPlacement::from("alice")),
+                Placement::from(HostPlacement::from("bob")),
+                Placement::from(HostPlacement::from("bob")),
+                Placement::from(HostPlacement::from("bob")),
+                Placement::from(HostPlacement::from("alice")),
+            ]
+        );
+        assert!(placement_of("softmax")
+            .iter()
+            .all(|plc| matches!(plc, Placement::Replicated(_))));
+    }
+
+    #[test]
+    fn test_import_reshape() {
+        let graph = GraphProto {
+            node: vec![
+                node("Flatten", &["x"], "f"),
+                node("Reshape", &["f", "shape"], "y"),
+            ],
+            initializer: vec![TensorProto {
+                dims: vec![2],
+                data_type: INT64,
+                int64_data: vec![-1, 3],
+                name: "shape".to_string(),
+                ..Default::default()
+            }],
+            input: vec![value_info("x", &[2, 3, 2])],
+            output: vec![value_info("y", &[4, 3])],
+            ..Default::default()
+        };
+        let model = ModelProto {
+            graph: Some(graph),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let comp = import_onnx(
+            &model,
+            &PlacementStrategy::Host(HostPlacement::from("alice")),
+        )
+        .unwrap();
+        let shapes: Vec<&RawShape> = comp
+            .operations
+            .iter()
+            .filter_map(|op| match &op.kind {
+                Operator::Constant(ConstantOp {
+                    value: Constant::RawShape(shape),
+                    ..
+                }) => Some(shape),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(shapes, vec![&RawShape(vec![2, 6]), &RawShape(vec![4, 3])]);
+    }
+
+    #[test]
+    fn test_unsupported_operator() {
+        let graph = GraphProto {
+            node: vec![node("Conv", &["x", "x"], "y")],
+            input: vec![value_info("x", &[1, 2])],
+            output: vec![value_info("y", &[1, 2])],
+            ..Default::default()
+        };
+        let model = ModelProto {
+            graph: Some(graph),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let err = import_onnx(
+            &model,
+            &PlacementStrategy::Host(HostPlacement::from("alice")),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'Conv'"));
+    }
+}
@@ -1,7 +1,28 @@
 //! Common library (helper functions) for the reindeer.
 
+use crate::kms::KeyProvider;
+use std::sync::Arc;
+use tokio::sync::Notify;
 use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
 
+/// Log to stdout, keeping the events enabled by `filter`, or by `RUST_LOG` if not given
+///
+/// Filters are given as directives such as `info,moose::networking=debug`. Events
+/// of sessions are prefixed with their spans, which include the session id and,
+/// for events of operations, the name, operator, and placement of the operation.
+pub fn setup_logging(filter: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    use tracing_subscriber::EnvFilter;
+
+    let filter = match filter {
+        Some(filter) => EnvFilter::try_new(filter)?,
+        None => EnvFilter::from_default_env(),
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .try_init()?;
+    Ok(())
+}
+
 /// Setup Jaeger tracing via OpenTelemetry
 #[cfg(feature = "telemetry")]
 pub fn setup_tracing(identity: &str, service_name: &str) -> Result<(), Box<dyn std::error::Error>> {
@@ -61,14 +82,101 @@ pub fn load_server_tlc_config(
     Ok(server_tls)
 }
 
+/// Like `load_client_tls_config` but taking the private key named `<my_cert_name>.key` from `keys`
+pub async fn load_client_tls_config_with_keys(
+    my_cert_name: &str,
+    certs_dir: &str,
+    keys: &(dyn KeyProvider + Send + Sync),
+) -> Result<ClientTlsConfig, Box<dyn std::error::Error>> {
+    let my_key_raw = keys.get_key(&format!("{}.key", my_cert_name)).await?;
+    let (client_identity, ca_cert) = load_identity_with_key(my_cert_name, certs_dir, my_key_raw)?;
+    let client_tls = ClientTlsConfig::new()
+        .identity(client_identity)
+        .ca_certificate(ca_cert);
+    Ok(client_tls)
+}
+
+/// Like `load_server_tlc_config` but taking the private key named `<my_cert_name>.key` from `keys`
+pub async fn load_server_tls_config_with_keys(
+    my_cert_name: &str,
+    certs_dir: &str,
+    keys: &(dyn KeyProvider + Send + Sync),
+) -> Result<ServerTlsConfig, Box<dyn std::error::Error>> {
+    let my_key_raw = keys.get_key(&format!("{}.key", my_cert_name)).await?;
+    let (identity, ca_cert) = load_identity_with_key(my_cert_name, certs_dir, my_key_raw)?;
+    let server_tls = ServerTlsConfig::new()
+        .identity(identity)
+        .client_ca_root(ca_cert);
+    Ok(server_tls)
+}
+
+/// Spawn tasks signalling when certificates should be reloaded
+///
+/// The returned `Notify` is triggered on SIGHUP and, if `watch` is set, whenever
+/// files in `certs_dir` change.
+pub fn spawn_certificate_reload_trigger(
+    certs_dir: &str,
+    watch: bool,
+) -> Result<Arc<Notify>, Box<dyn std::error::Error>> {
+    let trigger = Arc::new(Notify::new());
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut hangups = signal(SignalKind::hangup())?;
+        let trigger = Arc::clone(&trigger);
+        tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                tracing::info!("Received SIGHUP");
+                trigger.notify_one();
+            }
+        });
+    }
+
+    if watch {
+        use notify::{DebouncedEvent, Watcher};
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::watcher(tx, std::time::Duration::from_secs(2))?;
+        watcher.watch(certs_dir, notify::RecursiveMode::NonRecursive)?;
+        let trigger = Arc::clone(&trigger);
+        tokio::task::spawn_blocking(move || {
+            // keep the watcher alive for as long as events are processed
+            let _watcher = watcher;
+            for event in rx {
+                match event {
+                    DebouncedEvent::Create(_)
+                    | DebouncedEvent::Write(_)
+                    | DebouncedEvent::Rename(_, _) => {
+                        tracing::info!("Certificates changed");
+                        trigger.notify_one();
+                    }
+                    _ => {
+                        // ignore
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(trigger)
+}
+
 const CA_NAME: &str = "ca";
 
 fn load_identity_and_ca(
     my_cert_name: &str,
     certs_dir: &str,
 ) -> Result<(Identity, Certificate), Box<dyn std::error::Error>> {
-    let my_cert_raw = std::fs::read(format!("{}/{}.crt", certs_dir, my_cert_name))?;
     let my_key_raw = std::fs::read(format!("{}/{}.key", certs_dir, my_cert_name))?;
+    load_identity_with_key(my_cert_name, certs_dir, my_key_raw)
+}
+
+fn load_identity_with_key(
+    my_cert_name: &str,
+    certs_dir: &str,
+    my_key_raw: Vec<u8>,
+) -> Result<(Identity, Certificate), Box<dyn std::error::Error>> {
+    let my_cert_raw = std::fs::read(format!("{}/{}.crt", certs_dir, my_cert_name))?;
     let identity = Identity::from_pem(my_cert_raw, my_key_raw);
 
     let ca_cert_raw = std::fs::read(format!("{}/{}.crt", certs_dir, CA_NAME))?;
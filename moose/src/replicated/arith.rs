@@ -492,6 +492,74 @@ impl DotOp {
     }
 }
 
+impl Conv2DOp {
+    pub(crate) fn rep_rep_kernel<S: Session, ShapeT, RingT>(
+        sess: &S,
+        rep: &ReplicatedPlacement,
+        stride: usize,
+        padding: usize,
+        x: RepTensor<RingT>,
+        filter: RepTensor<RingT>,
+    ) -> Result<RepTensor<RingT>>
+    where
+        RingT: Clone,
+        HostPlacement: PlacementAdd<S, RingT, RingT, RingT>,
+        HostPlacement: PlacementConv2D<S, RingT, RingT, RingT>,
+        HostPlacement: PlacementShape<S, RingT, ShapeT>,
+        ReplicatedPlacement: ZeroShareGen<S, ShapeT, RingT>,
+        ReplicatedPlacement: PlacementPlace<S, RepTensor<RingT>>,
+    {
+        let (player0, player1, player2) = rep.host_placements();
+
+        let RepTensor {
+            shares: [[x00, x10], [x11, x21], [x22, x02]],
+        } = &x;
+
+        let RepTensor {
+            shares: [[y00, y10], [y11, y21], [y22, y02]],
+        } = &filter;
+
+        // convolutions are bilinear, so shares are combined as in dot products
+        let v0 = with_context!(player0, sess, {
+            conv2d(stride, padding, x00, y00)
+                + conv2d(stride, padding, x00, y10)
+                + conv2d(stride, padding, x10, y00)
+        });
+        let v1 = with_context!(player1, sess, {
+            conv2d(stride, padding, x11, y11)
+                + conv2d(stride, padding, x11, y21)
+                + conv2d(stride, padding, x21, y11)
+        });
+        let v2 = with_context!(player2, sess, {
+            conv2d(stride, padding, x22, y22)
+                + conv2d(stride, padding, x22, y02)
+                + conv2d(stride, padding, x02, y22)
+        });
+
+        let s0 = player0.shape(sess, &v0);
+        let s1 = player1.shape(sess, &v1);
+        let s2 = player2.shape(sess, &v2);
+        let zero_shape = RepShape {
+            shapes: [s0, s1, s2],
+        };
+
+        let RepZeroShare {
+            alphas: [a0, a1, a2],
+        } = rep.gen_zero_share(sess, &zero_shape)?;
+
+        let z0 = with_context!(player0, sess, { v0 + a0 });
+        let z1 = with_context!(player1, sess, { v1 + a1 });
+        let z2 = with_context!(player2, sess, { v2 + a2 });
+
+        Ok(rep.place(
+            sess,
+            RepTensor {
+                shares: [[z0.clone(), z1.clone()], [z1, z2.clone()], [z2, z0]],
+            },
+        ))
+    }
+}
+
 impl AndOp {
     pub(crate) fn rep_kernel<S: Session, RepT>(
         sess: &S,
@@ -29,6 +29,7 @@ mod fixedpoint;
 mod input;
 mod log;
 mod misc;
+mod noise;
 mod ops;
 mod setup;
 mod softmax;
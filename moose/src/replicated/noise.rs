@@ -0,0 +1,124 @@
+//! Differential privacy noise generated jointly by the players.
+//!
+//! Every player samples noise locally, encodes it with the precision of the
+//! secret tensor, and shares it; the sum of all shared noise is then added to
+//! the tensor. Since a corrupted player knows its own contribution, the noise
+//! of the two remaining players must by itself give the privacy guarantee.
+
+use super::*;
+use crate::fixedpoint::FixedpointTensor;
+
+fn add_shared_noise<S: Session, RepFixedT, HostFloatT, HostFixedT, F>(
+    sess: &S,
+    rep: &ReplicatedPlacement,
+    x: RepFixedT,
+    sample: F,
+) -> RepFixedT
+where
+    F: Fn(&HostPlacement, &m!(HostFloatT)) -> m!(HostFloatT),
+    RepFixedT: FixedpointTensor,
+    ReplicatedShape: KnownType<S>,
+    HostShape: KnownType<S>,
+    HostFloatT: KnownType<S>,
+    HostFixedT: KnownType<S>,
+    ReplicatedPlacement: PlacementShape<S, RepFixedT, m!(ReplicatedShape)>,
+    HostPlacement: PlacementReveal<S, m!(ReplicatedShape), m!(HostShape)>,
+    HostPlacement: PlacementZeros<S, m!(HostShape), m!(HostFloatT)>,
+    HostPlacement: PlacementFixedpointEncode<S, m!(HostFloatT), m!(HostFixedT)>,
+    ReplicatedPlacement: PlacementShare<S, m!(HostFixedT), RepFixedT>,
+    ReplicatedPlacement: PlacementAdd<S, RepFixedT, RepFixedT, RepFixedT>,
+{
+    let rep_shape = rep.shape(sess, &x);
+    let (player0, player1, player2) = rep.host_placements();
+
+    let shared_noise = |player: &HostPlacement| {
+        let shape = player.reveal(sess, &rep_shape);
+        let zeros = player.zeros(sess, &shape);
+        let noise = sample(player, &zeros);
+        let noise = player.fixedpoint_encode(
+            sess,
+            x.fractional_precision(),
+            x.integral_precision(),
+            &noise,
+        );
+        rep.share(sess, &noise)
+    };
+
+    let noise0 = shared_noise(&player0);
+    let noise1 = shared_noise(&player1);
+    let noise2 = shared_noise(&player2);
+
+    let z = rep.add(sess, &x, &noise0);
+    let z = rep.add(sess, &z, &noise1);
+    rep.add(sess, &z, &noise2)
+}
+
+impl LaplaceNoiseOp {
+    pub(crate) fn rep_kernel<S: Session, RepFixedT, HostFloatT, HostFixedT>(
+        sess: &S,
+        rep: &ReplicatedPlacement,
+        epsilon: f64,
+        sensitivity: f64,
+        x: RepFixedT,
+    ) -> Result<RepFixedT>
+    where
+        RepFixedT: FixedpointTensor,
+        ReplicatedShape: KnownType<S>,
+        HostShape: KnownType<S>,
+        HostFloatT: KnownType<S>,
+        HostFixedT: KnownType<S>,
+        ReplicatedPlacement: PlacementShape<S, RepFixedT, m!(ReplicatedShape)>,
+        HostPlacement: PlacementReveal<S, m!(ReplicatedShape), m!(HostShape)>,
+        HostPlacement: PlacementZeros<S, m!(HostShape), m!(HostFloatT)>,
+        HostPlacement: PlacementLaplaceNoise<S, m!(HostFloatT), m!(HostFloatT)>,
+        HostPlacement: PlacementFixedpointEncode<S, m!(HostFloatT), m!(HostFixedT)>,
+        ReplicatedPlacement: PlacementShare<S, m!(HostFixedT), RepFixedT>,
+        ReplicatedPlacement: PlacementAdd<S, RepFixedT, RepFixedT, RepFixedT>,
+    {
+        // Laplace noise is not closed under addition, so every player samples
+        // noise of the full scale; that of any one honest player suffices
+        Self::scale(epsilon, sensitivity)?;
+        Ok(add_shared_noise::<_, _, HostFloatT, HostFixedT, _>(
+            sess,
+            rep,
+            x,
+            |player, zeros| player.laplace_noise(sess, epsilon, sensitivity, zeros),
+        ))
+    }
+}
+
+impl GaussianNoiseOp {
+    pub(crate) fn rep_kernel<S: Session, RepFixedT, HostFloatT, HostFixedT>(
+        sess: &S,
+        rep: &ReplicatedPlacement,
+        epsilon: f64,
+        delta: f64,
+        sensitivity: f64,
+        x: RepFixedT,
+    ) -> Result<RepFixedT>
+    where
+        RepFixedT: FixedpointTensor,
+        ReplicatedShape: KnownType<S>,
+        HostShape: KnownType<S>,
+        HostFloatT: KnownType<S>,
+        HostFixedT: KnownType<S>,
+        ReplicatedPlacement: PlacementShape<S, RepFixedT, m!(ReplicatedShape)>,
+        HostPlacement: PlacementReveal<S, m!(ReplicatedShape), m!(HostShape)>,
+        HostPlacement: PlacementZeros<S, m!(HostShape), m!(HostFloatT)>,
+        HostPlacement: PlacementGaussianNoise<S, m!(HostFloatT), m!(HostFloatT)>,
+        HostPlacement: PlacementFixedpointEncode<S, m!(HostFloatT), m!(HostFixedT)>,
+        ReplicatedPlacement: PlacementShare<S, m!(HostFixedT), RepFixedT>,
+        ReplicatedPlacement: PlacementAdd<S, RepFixedT, RepFixedT, RepFixedT>,
+    {
+        // every player samples half of the variance, such that the noise of
+        // any two honest players sums to the full variance
+        Self::scale(epsilon, delta, sensitivity)?;
+        let player_sensitivity = sensitivity / std::f64::consts::SQRT_2;
+        Ok(add_shared_noise::<_, _, HostFloatT, HostFixedT, _>(
+            sess,
+            rep,
+            x,
+            |player, zeros| player.gaussian_noise(sess, epsilon, delta, player_sensitivity, zeros),
+        ))
+    }
+}
@@ -0,0 +1,315 @@
+//! Access control on the keys that sessions may load and save.
+//!
+//! Workers may be configured with an access control list stating, per role,
+//! which storage keys sessions may load and save when the worker plays that
+//! role, such that a malicious driver cannot instruct a worker to read
+//! arbitrary local files into a computation. The list is given in TOML:
+//!
+//! ```toml
+//! [[roles]]
+//! name = "alice"
+//! load = ["data/*.csv", "model.npy"]
+//! save = ["results/*"]
+//! ```
+//!
+//! In patterns, `*` matches any sequence of characters other than `/`, while
+//! `**` also matches across `/`. Keys with `..` components are never allowed,
+//! and roles that are not listed may neither load nor save anything.
+
+use crate::computation::{Computation, Constant, Operator, Placement, Role, SessionId, Ty, Value};
+use crate::error::{Error, Result};
+use crate::execution::AsyncStorageImpl;
+use crate::storage::{AsyncStorage, ValueStat, ValueStream};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Load,
+    Save,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Load => write!(f, "load"),
+            Access::Save => write!(f, "save"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RoleAccess {
+    pub name: String,
+    /// Patterns of the keys the role may load.
+    #[serde(default)]
+    pub load: Vec<String>,
+    /// Patterns of the keys the role may save.
+    #[serde(default)]
+    pub save: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+pub struct StorageAcl {
+    #[serde(default)]
+    pub roles: Vec<RoleAccess>,
+}
+
+impl FromStr for StorageAcl {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| {
+            Error::MalformedEnvironment(format!("failed to parse storage access list: {}", e))
+        })
+    }
+}
+
+impl StorageAcl {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            Error::MalformedEnvironment(format!(
+                "failed to read storage access list from {:?}: {}",
+                path, e
+            ))
+        })?;
+        Self::from_str(&raw)
+    }
+
+    /// Whether any of `roles` may access `key`.
+    pub fn allows(&self, roles: &[Role], key: &str, access: Access) -> bool {
+        if key
+            .split(&['/', '\\'][..])
+            .any(|component| component == "..")
+        {
+            return false;
+        }
+        self.roles
+            .iter()
+            .filter(|role| roles.iter().any(|r| r.0 == role.name))
+            .flat_map(|role| match access {
+                Access::Load => role.load.iter(),
+                Access::Save => role.save.iter(),
+            })
+            .any(|pattern| matches(pattern.as_bytes(), key.as_bytes()))
+    }
+
+    pub fn check(&self, roles: &[Role], key: &str, access: Access) -> Result<()> {
+        if self.allows(roles, key, access) {
+            return Ok(());
+        }
+        let roles: Vec<&str> = roles.iter().map(|role| role.0.as_str()).collect();
+        Err(Error::Storage(format!(
+            "roles [{}] may not {} '{}'",
+            roles.join(", "),
+            access,
+            key
+        )))
+    }
+
+    /// Check the constant keys of the load and save operations of `computation` played by `roles`.
+    ///
+    /// Operations with keys computed at run-time are checked when executed.
+    pub fn check_computation(&self, computation: &Computation, roles: &[Role]) -> Result<()> {
+        let constants: HashMap<&str, &str> = computation
+            .operations
+            .iter()
+            .filter_map(|op| match &op.kind {
+                Operator::Constant(constant) => match &constant.value {
+                    Constant::String(key) => Some((op.name.as_str(), key.as_str())),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let mut problems = Vec::new();
+        for op in computation.operations.iter() {
+            let access = match op.kind {
+                Operator::Load(_) => Access::Load,
+                Operator::Save(_) => Access::Save,
+                _ => continue,
+            };
+            let owner = match &op.placement {
+                Placement::Host(plc) if roles.contains(&plc.owner) => plc.owner.clone(),
+                _ => continue,
+            };
+            let key = op
+                .inputs
+                .first()
+                .and_then(|key| constants.get(key.as_str()));
+            if let Some(key) = key {
+                if !self.allows(&[owner.clone()], key, access) {
+                    problems.push(format!(
+                        "'{}' may not {} '{}' in operation '{}'",
+                        owner, access, key, op.name
+                    ));
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Storage(format!(
+                "computation violates the storage access list: {}",
+                problems.join("; ")
+            )))
+        }
+    }
+}
+
+/// Whether `key` matches `pattern`, in which `*` does not match `/` but `**` does.
+fn matches(pattern: &[u8], key: &[u8]) -> bool {
+    match pattern {
+        [] => key.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=key.len()).any(|i| matches(rest, &key[i..])),
+        [b'*', rest @ ..] => (0..=key.len())
+            .take_while(|i| *i == 0 || key[i - 1] != b'/')
+            .any(|i| matches(rest, &key[i..])),
+        [c, rest @ ..] => key.first() == Some(c) && matches(rest, &key[1..]),
+    }
+}
+
+/// Storage wrapper rejecting accesses not allowed for the roles played in a session.
+pub struct AclStorage {
+    inner: AsyncStorageImpl,
+    acl: Arc<StorageAcl>,
+    roles: Vec<Role>,
+}
+
+impl AclStorage {
+    pub fn new(inner: AsyncStorageImpl, acl: Arc<StorageAcl>, roles: Vec<Role>) -> Self {
+        AclStorage { inner, acl, roles }
+    }
+}
+
+#[async_trait]
+impl AsyncStorage for AclStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        self.acl.check(&self.roles, key, Access::Save)?;
+        self.inner.save(key, session_id, val).await
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        self.acl.check(&self.roles, key, Access::Load)?;
+        self.inner.load(key, session_id, type_hint, query).await
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        self.acl.check(&self.roles, key, Access::Load)?;
+        self.inner
+            .load_chunks(key, session_id, type_hint, query, chunk_rows)
+            .await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // only keys that may be loaded are revealed
+        let keys = self.inner.list(prefix).await?;
+        Ok(keys
+            .into_iter()
+            .filter(|key| self.acl.check(&self.roles, key, Access::Load).is_ok())
+            .collect())
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        self.acl.check(&self.roles, key, Access::Load)?;
+        self.inner.stat(key).await
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        self.inner.formats()
+    }
+
+    async fn check(&self) -> Result<()> {
+        self.inner.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::{HostPlacement, HostString};
+    use crate::storage::local::LocalAsyncStorage;
+    use std::convert::{TryFrom, TryInto};
+
+    const ACL: &str = r#"
+    [[roles]]
+    name = "alice"
+    load = ["data/*.csv", "model.npy"]
+    save = ["results/**"]
+    "#;
+
+    #[test]
+    fn test_allows() {
+        let acl = StorageAcl::from_str(ACL).unwrap();
+        let alice = [Role::from("alice")];
+        assert!(acl.allows(&alice, "data/x.csv", Access::Load));
+        assert!(acl.allows(&alice, "model.npy", Access::Load));
+        assert!(!acl.allows(&alice, "data/x/y.csv", Access::Load));
+        assert!(!acl.allows(&alice, "data/../../etc/passwd.csv", Access::Load));
+        assert!(!acl.allows(&alice, "/etc/passwd", Access::Load));
+        assert!(!acl.allows(&alice, "model.npy", Access::Save));
+        assert!(acl.allows(&alice, "results/a/b.npy", Access::Save));
+        assert!(!acl.allows(&[Role::from("bob")], "model.npy", Access::Load));
+    }
+
+    #[test]
+    fn test_check_computation() {
+        let acl = StorageAcl::from_str(ACL).unwrap();
+        let computation: Computation = r#"
+        key = Constant{value = "/etc/passwd"}: () -> HostString @Host(alice)
+        query = Constant{value = ""}: () -> HostString @Host(alice)
+        x = Load: (HostString, HostString) -> HostFloat64Tensor (key, query) @Host(alice)
+        "#
+        .try_into()
+        .unwrap();
+        let err = acl
+            .check_computation(&computation, &[Role::from("alice")])
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'alice' may not load '/etc/passwd'"));
+        // operations of other workers are left to them
+        assert!(acl
+            .check_computation(&computation, &[Role::from("bob")])
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_acl_storage() {
+        let acl = Arc::new(StorageAcl::from_str(ACL).unwrap());
+        let inner: AsyncStorageImpl = Arc::new(LocalAsyncStorage::default());
+        let storage = AclStorage::new(inner, acl, vec![Role::from("alice")]);
+        let session_id = SessionId::try_from("session").unwrap();
+        let value = Value::from(HostString("x".to_string(), HostPlacement::from("alice")));
+
+        storage
+            .save("results/x", &session_id, &value)
+            .await
+            .unwrap();
+        assert!(storage
+            .save("model.npy", &session_id, &value)
+            .await
+            .is_err());
+        assert!(storage
+            .load("secrets.txt", &session_id, None, "")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Storage encrypting values at rest.
+//!
+//! Values saved under keys with extension `.enc`, such as `results/x.enc`, are
+//! serialized in the wire format used between parties and written to the file
+//! at the key encrypted with AES-256-GCM, such that intermediate results can be
+//! kept on shared infrastructure. Loading such a key decrypts the file, failing
+//! if it has been tampered with or was encrypted under another key. All other
+//! keys are passed on to a fallback storage, such that computations can still
+//! load plaintext inputs.
+//!
+//! Files consist of a magic header, a random 96-bit nonce, and the ciphertext
+//! followed by its tag. The storage key is authenticated along with the value,
+//! such that encrypted files cannot be swapped for one another.
+
+use crate::error::Error;
+use crate::execution::AsyncStorageImpl;
+use crate::networking::wire::{deserialize_value, serialize_value};
+use crate::prelude::*;
+use crate::storage::{stream_rows, AsyncStorage, ValueStat, ValueStream};
+use crate::Result;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use rand::RngCore;
+use std::path::Path;
+
+/// Extension of the keys of values encrypted at rest.
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+/// Start of every encrypted file, including the version of the layout.
+const MAGIC: &[u8; 8] = b"MOOSE\x00E1";
+
+const NONCE_LEN: usize = 12;
+
+pub struct AsyncEncryptedStorage {
+    cipher: Aes256Gcm,
+    fallback: AsyncStorageImpl,
+}
+
+impl AsyncEncryptedStorage {
+    /// Encrypt values saved under keys with extension `.enc` under the 32-byte
+    /// `key`, and serve all other keys from `fallback`.
+    pub fn new(key: &[u8], fallback: AsyncStorageImpl) -> Result<Self> {
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| {
+            Error::InvalidArgument(format!(
+                "storage encryption key must consist of 32 bytes, got {}",
+                key.len()
+            ))
+        })?;
+        Ok(AsyncEncryptedStorage { cipher, fallback })
+    }
+
+    fn encrypt(&self, key: &str, val: &Value) -> Result<Vec<u8>> {
+        let plaintext = serialize_value(val)
+            .map_err(|e| Error::Storage(format!("failed to serialize '{}': {}", key, e)))?;
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: &plaintext,
+            aad: key.as_bytes(),
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| Error::Storage(format!("failed to encrypt '{}'", key)))?;
+
+        let mut contents = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        contents.extend_from_slice(MAGIC);
+        contents.extend_from_slice(&nonce);
+        contents.extend_from_slice(&ciphertext);
+        Ok(contents)
+    }
+
+    fn decrypt(&self, key: &str, contents: &[u8]) -> Result<Value> {
+        let rest = contents
+            .strip_prefix(&MAGIC[..])
+            .filter(|rest| rest.len() >= NONCE_LEN)
+            .ok_or_else(|| Error::Storage(format!("not an encrypted moose value: {}", key)))?;
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: key.as_bytes(),
+        };
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| {
+                Error::Storage(format!(
+                    "failed to decrypt '{}', it was modified or encrypted under another key",
+                    key
+                ))
+            })?;
+        deserialize_value(&plaintext)
+            .map_err(|e| Error::Storage(format!("failed to deserialize '{}': {}", key, e)))
+    }
+}
+
+fn is_encrypted(key: &str) -> bool {
+    Path::new(key).extension().and_then(|ext| ext.to_str()) == Some(ENCRYPTED_EXTENSION)
+}
+
+#[async_trait]
+impl AsyncStorage for AsyncEncryptedStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        if !is_encrypted(key) {
+            return self.fallback.save(key, session_id, val).await;
+        }
+        tracing::debug!(key, ty = %val.ty(), "Saving encrypted value");
+        let contents = self.encrypt(key, val)?;
+        tokio::fs::write(key, contents).await.map_err(|e| {
+            Error::Storage(format!(
+                "failed to write moose value to file: '{}': {}",
+                key, e
+            ))
+        })
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        if !is_encrypted(key) {
+            return self.fallback.load(key, session_id, type_hint, query).await;
+        }
+        tracing::debug!(key, query, "Loading encrypted value");
+        if !query.is_empty() {
+            return Err(Error::Storage(
+                "query is not allowed for encrypted values".into(),
+            ));
+        }
+        let contents = tokio::fs::read(key).await.map_err(|e| {
+            Error::Storage(format!("failed to read encrypted file: {}: {}", key, e))
+        })?;
+        let value = self.decrypt(key, &contents)?;
+        match type_hint {
+            Some(ty) if ty != value.ty() => Err(Error::Storage(format!(
+                "value of '{}' is a {} but a {} was expected",
+                key,
+                value.ty(),
+                ty
+            ))),
+            _ => Ok(value),
+        }
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        if !is_encrypted(key) {
+            return self
+                .fallback
+                .load_chunks(key, session_id, type_hint, query, chunk_rows)
+                .await;
+        }
+        // encrypted values are decrypted as a whole
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // encrypted values are held in files alongside those of the fallback
+        self.fallback.list(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        if !is_encrypted(key) {
+            return self.fallback.stat(key).await;
+        }
+        // the type and shape of encrypted values are only known once decrypted
+        let metadata = tokio::fs::metadata(key)
+            .await
+            .map_err(|e| Error::Storage(format!("failed to stat file: {}: {}", key, e)))?;
+        Ok(ValueStat {
+            bytes: metadata.len(),
+            modified: metadata.modified().ok(),
+            ..ValueStat::default()
+        })
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        self.fallback.formats().map(|mut formats| {
+            formats.push(ENCRYPTED_EXTENSION.to_string());
+            formats
+        })
+    }
+
+    async fn check(&self) -> Result<()> {
+        self.fallback.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::filesystem::AsyncFilesystemStorage;
+    use ndarray::array;
+    use std::convert::TryFrom;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_encrypted_storage() {
+        let fallback = Arc::new(AsyncFilesystemStorage::default());
+        let storage = AsyncEncryptedStorage::new(&[7; 32], fallback.clone()).unwrap();
+        let session_id = SessionId::try_from("session").unwrap();
+
+        let plc = HostPlacement::from("host");
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let expected = Value::from(x);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("x.enc");
+        let key = path.to_str().unwrap();
+        storage.save(key, &session_id, &expected).await.unwrap();
+
+        // the file does not hold the value in plaintext
+        let contents = std::fs::read(&path).unwrap();
+        assert!(contents.starts_with(MAGIC));
+        assert!(!contents
+            .windows(8)
+            .any(|window| window == 3.0f64.to_le_bytes()));
+
+        let data = storage.load(key, &session_id, None, "").await.unwrap();
+        assert_eq!(data, expected);
+        assert!(storage
+            .load(key, &session_id, Some(Ty::HostInt64Tensor), "")
+            .await
+            .is_err());
+
+        // tampering with the file or using another key is detected
+        let other = AsyncEncryptedStorage::new(&[8; 32], fallback).unwrap();
+        assert!(other.load(key, &session_id, None, "").await.is_err());
+        let mut tampered = contents;
+        *tampered.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, tampered).unwrap();
+        assert!(storage.load(key, &session_id, None, "").await.is_err());
+
+        assert!(
+            AsyncEncryptedStorage::new(&[7; 16], Arc::new(AsyncFilesystemStorage::default()))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Transparent zstd compression of stored files.
+//!
+//! Files are compressed as a whole, in whatever format they are written, and are
+//! recognized as compressed on read by the zstd magic number at their start, such
+//! that compressed and uncompressed files can be mixed. Files are staged
+//! uncompressed in a temporary directory while being written or read. Datasets
+//! of HDF5 files are never compressed since they are written into existing files.
+
+use crate::error::Error;
+use crate::Result;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Whether the file at `path` starts with a zstd frame; false if it cannot be read,
+/// leaving it to the reader of its format to report the error.
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map(|_| magic == ZSTD_MAGIC)
+        .unwrap_or(false)
+}
+
+/// Path in a new temporary directory with the same file name as `key`, such that
+/// it is read and written in the same format; the directory is removed once dropped.
+pub(crate) fn staging_path(key: &str) -> Result<(TempDir, PathBuf)> {
+    let file_name = Path::new(key)
+        .file_name()
+        .ok_or_else(|| Error::Storage(format!("failed to get file name from key: {}", key)))?;
+    let dir = tempfile::tempdir()
+        .map_err(|e| Error::Storage(format!("failed to create staging directory: {}", e)))?;
+    let path = dir.path().join(file_name);
+    Ok((dir, path))
+}
+
+/// Compress the file at `src` into `dst` at `level`.
+pub(crate) fn compress_file(src: &Path, dst: &Path, level: i32) -> Result<()> {
+    let failed = |e: std::io::Error| {
+        Error::Storage(format!("failed to compress file: {}: {}", dst.display(), e))
+    };
+    let src = File::open(src).map_err(failed)?;
+    let dst = File::create(dst).map_err(failed)?;
+    zstd::stream::copy_encode(src, dst, level).map_err(failed)
+}
+
+/// Decompress the file at `src` into `dst`.
+pub(crate) fn decompress_file(src: &Path, dst: &Path) -> Result<()> {
+    let failed = |e: std::io::Error| {
+        Error::Storage(format!(
+            "failed to decompress file: {}: {}",
+            src.display(),
+            e
+        ))
+    };
+    let source = File::open(src).map_err(failed)?;
+    let dst = File::create(dst).map_err(failed)?;
+    zstd::stream::copy_decode(source, dst).map_err(failed)
+}
@@ -1,29 +1,146 @@
 use crate::prelude::*;
 use crate::{Error, Result};
-use csv::WriterBuilder;
+use csv::{ReaderBuilder, WriterBuilder};
 use ndarray::prelude::*;
 use ndarray::ArcArray;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
+use std::str::FromStr;
 
-#[allow(dead_code)]
+/// Options for reading CSV files.
+#[derive(Clone, Debug)]
+pub(crate) struct CsvOptions {
+    /// Columns to read, in the order of the file; all columns if empty.
+    pub columns: Vec<String>,
+    /// Whether the first record names the columns; otherwise they are named
+    /// `col_0`, `col_1`, and so on, as when writing CSV files.
+    pub has_headers: bool,
+    pub delimiter: u8,
+    /// Type of the resulting tensors.
+    pub dtype: Ty,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            columns: Vec::new(),
+            has_headers: true,
+            delimiter: b',',
+            dtype: Ty::HostFloat64Tensor,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn with_columns(self, columns: Vec<String>) -> Self {
+        CsvOptions { columns, ..self }
+    }
+
+    pub fn with_headers(self, has_headers: bool) -> Self {
+        CsvOptions {
+            has_headers,
+            ..self
+        }
+    }
+
+    pub fn with_delimiter(self, delimiter: u8) -> Self {
+        CsvOptions { delimiter, ..self }
+    }
+
+    pub fn with_dtype(self, dtype: Ty) -> Self {
+        CsvOptions { dtype, ..self }
+    }
+}
+
+/// Read a CSV file as tensors of the given dtype, by invoking `$read!(element, tensor)`.
+macro_rules! dispatch_dtype {
+    ($dtype:expr, $read:ident) => {
+        match $dtype {
+            Ty::HostFloat64Tensor => $read!(f64, HostFloat64Tensor),
+            Ty::HostFloat32Tensor => $read!(f32, HostFloat32Tensor),
+            Ty::HostInt8Tensor => $read!(i8, HostInt8Tensor),
+            Ty::HostInt16Tensor => $read!(i16, HostInt16Tensor),
+            Ty::HostInt32Tensor => $read!(i32, HostInt32Tensor),
+            Ty::HostInt64Tensor => $read!(i64, HostInt64Tensor),
+            Ty::HostUint8Tensor => $read!(u8, HostUint8Tensor),
+            Ty::HostUint16Tensor => $read!(u16, HostUint16Tensor),
+            Ty::HostUint32Tensor => $read!(u32, HostUint32Tensor),
+            Ty::HostUint64Tensor => $read!(u64, HostUint64Tensor),
+            dtype => Err(Error::Storage(format!(
+                "invalid dtype for csv storage read: {}",
+                dtype
+            ))),
+        }
+    };
+}
+
+/// Read the selected columns of a CSV file as a matrix with a column per selected column.
 pub(crate) async fn read_csv(
     filename: &str,
-    columns: &[String],
+    options: &CsvOptions,
     placement: &HostPlacement,
 ) -> Result<Value> {
-    let include_columns: HashSet<&String> = columns.iter().collect();
+    macro_rules! matrix {
+        ($t:ty, $tensor:ty) => {{
+            let (_, matrix) = read_matrix::<$t>(filename, options)?;
+            let tensor: $tensor = placement.from_raw(matrix);
+            Ok(Value::from(tensor))
+        }};
+    }
+    dispatch_dtype!(options.dtype, matrix)
+}
+
+/// Read the selected columns of a CSV file as vectors, keyed by their column names.
+pub(crate) async fn read_csv_columns(
+    filename: &str,
+    options: &CsvOptions,
+    placement: &HostPlacement,
+) -> Result<HashMap<String, Value>> {
+    macro_rules! columns {
+        ($t:ty, $tensor:ty) => {{
+            let (names, matrix) = read_matrix::<$t>(filename, options)?;
+            Ok(names
+                .into_iter()
+                .zip(matrix.columns())
+                .map(|(name, column)| {
+                    let tensor: $tensor = placement.from_raw(column.to_owned());
+                    (name, Value::from(tensor))
+                })
+                .collect())
+        }};
+    }
+    dispatch_dtype!(options.dtype, columns)
+}
 
-    let mut reader = csv::Reader::from_path(filename)
+/// Names of the selected columns and their values, parsed as `T`.
+fn read_matrix<T>(filename: &str, options: &CsvOptions) -> Result<(Vec<String>, Array2<T>)>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let mut reader = ReaderBuilder::new()
+        .has_headers(options.has_headers)
+        .delimiter(options.delimiter)
+        .from_path(filename)
         .map_err(|e| Error::Storage(format!("could not open file: {}: {}", filename, e)))?;
 
-    let headers: Vec<String> = reader
-        .headers()
-        .map_err(|e| Error::Storage(format!("could not get headers from: {}: {}", filename, e)))?
-        .into_iter()
-        .map(|header| header.to_string())
-        .collect();
+    let headers: Vec<String> = if options.has_headers {
+        reader
+            .headers()
+            .map_err(|e| {
+                Error::Storage(format!("could not get headers from: {}: {}", filename, e))
+            })?
+            .into_iter()
+            .map(|header| header.to_string())
+            .collect()
+    } else {
+        let ncols = reader
+            .headers()
+            .map_err(|e| Error::Storage(format!("could not get record from: {}: {}", filename, e)))?
+            .len();
+        (0..ncols).map(|i| format!("col_{}", i)).collect()
+    };
     if headers.is_empty() {
         return Err(Error::Storage(format!(
             "no columns found for file: {}",
@@ -31,35 +148,54 @@ pub(crate) async fn read_csv(
         )));
     }
 
-    let mut matrix: Vec<f64> = Vec::new();
+    let selected: Vec<usize> = if options.columns.is_empty() {
+        (0..headers.len()).collect()
+    } else {
+        let include_columns: HashSet<&String> = options.columns.iter().collect();
+        if let Some(missing) = options
+            .columns
+            .iter()
+            .find(|column| !headers.contains(*column))
+        {
+            return Err(Error::Storage(format!(
+                "no column named '{}' in file: {}",
+                missing, filename
+            )));
+        }
+        (0..headers.len())
+            .filter(|i| include_columns.contains(&headers[*i]))
+            .collect()
+    };
+
+    let mut matrix: Vec<T> = Vec::new();
     let mut nrows = 0;
-    let mut ncols = 0;
     for record in reader.records() {
         nrows += 1;
         let record = record.map_err(|e| {
             Error::Storage(format!("could not get record from: {}: {}", filename, e))
         })?;
-        for (header, value) in headers.iter().zip(record.iter()) {
-            if include_columns.contains(header) || include_columns.is_empty() {
-                if nrows == 1 {
-                    // i.e., only count number of cols for the first row
-                    ncols += 1;
-                }
-                let value = value.parse::<f64>().map_err(|e| {
-                    Error::Storage(format!("could not parse '{}' to f64: {}", value, e))
-                })?;
-                matrix.push(value);
-            }
+        for i in selected.iter() {
+            let value = record.get(*i).unwrap_or_default().trim();
+            let value = value.parse::<T>().map_err(|e| {
+                Error::Storage(format!(
+                    "could not parse '{}' in column '{}' to {}: {}",
+                    value,
+                    headers[*i],
+                    std::any::type_name::<T>(),
+                    e
+                ))
+            })?;
+            matrix.push(value);
         }
     }
-    let ndarr: Array2<f64> = Array2::from_shape_vec((nrows, ncols), matrix).map_err(|e| {
+    let ndarr = Array2::from_shape_vec((nrows, selected.len()), matrix).map_err(|e| {
         Error::Storage(format!(
             "could not convert data from: {} to matrix: {}",
             filename, e
         ))
     })?;
-    let tensor: HostFloat64Tensor = placement.from_raw(ndarr);
-    Ok(Value::from(tensor))
+    let names = selected.into_iter().map(|i| headers[i].clone()).collect();
+    Ok((names, ndarr))
 }
 
 #[allow(dead_code)]
@@ -214,7 +350,9 @@ mod tests {
             .to_string();
 
         let plc = HostPlacement::from("host");
-        let data = read_csv(&filename, &[], &plc).await.unwrap();
+        let data = read_csv(&filename, &CsvOptions::default(), &plc)
+            .await
+            .unwrap();
         assert_eq!(data, expected);
     }
 
@@ -234,7 +372,39 @@ mod tests {
 
         write_csv(&filename, &expected).await.unwrap();
 
-        let data = read_csv(&filename, &[], &plc).await.unwrap();
+        let data = read_csv(&filename, &CsvOptions::default(), &plc)
+            .await
+            .unwrap();
         assert_eq!(data, expected);
     }
+
+    #[tokio::test]
+    async fn test_read_csv_options() {
+        let plc = HostPlacement::from("host");
+        let file_data = concat!("1;20;0.5\n", "2; 30;1.5\n", "3;40;2.5\n");
+        let mut file = NamedTempFile::new().expect("trying to create tempfile");
+        file.write_all(file_data.as_bytes()).unwrap();
+        let filename = file.path().to_str().unwrap().to_string();
+
+        let options = CsvOptions::default()
+            .with_headers(false)
+            .with_delimiter(b';')
+            .with_columns(vec!["col_0".to_string(), "col_1".to_string()])
+            .with_dtype(Ty::HostInt64Tensor);
+        let data = read_csv(&filename, &options, &plc).await.unwrap();
+        let expected: HostInt64Tensor = plc.from_raw(array![[1, 20], [2, 30], [3, 40]]);
+        assert_eq!(data, Value::from(expected));
+
+        let columns = read_csv_columns(&filename, &options, &plc).await.unwrap();
+        let expected: HostInt64Tensor = plc.from_raw(array![20, 30, 40]);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns["col_1"], Value::from(expected));
+
+        // the fractional column does not coerce to integers
+        let options = options.with_columns(Vec::new());
+        assert!(read_csv(&filename, &options, &plc).await.is_err());
+        // nor can missing columns be selected
+        let options = options.with_columns(vec!["col_3".to_string()]);
+        assert!(read_csv(&filename, &options, &plc).await.is_err());
+    }
 }
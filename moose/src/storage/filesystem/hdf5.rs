@@ -0,0 +1,248 @@
+use crate::prelude::*;
+use crate::{Error, Result};
+use hdf5::types::{FloatSize, IntSize, TypeDescriptor};
+use hdf5::{Dataset, File, Group, H5Type};
+use ndarray::ArrayD;
+
+fn hdf5_error(filename: &str, e: hdf5::Error) -> Error {
+    Error::Storage(format!("failed to access HDF5 file: {}: {}", filename, e))
+}
+
+/// Tensor type matching the type of the elements of `dataset`.
+fn dataset_dtype(filename: &str, dataset: &Dataset) -> Result<Ty> {
+    let descriptor = dataset
+        .dtype()
+        .and_then(|dtype| dtype.to_descriptor())
+        .map_err(|e| hdf5_error(filename, e))?;
+    let dtype = match descriptor {
+        TypeDescriptor::Float(FloatSize::U4) => Ty::HostFloat32Tensor,
+        TypeDescriptor::Float(FloatSize::U8) => Ty::HostFloat64Tensor,
+        TypeDescriptor::Integer(IntSize::U1) => Ty::HostInt8Tensor,
+        TypeDescriptor::Integer(IntSize::U2) => Ty::HostInt16Tensor,
+        TypeDescriptor::Integer(IntSize::U4) => Ty::HostInt32Tensor,
+        TypeDescriptor::Integer(IntSize::U8) => Ty::HostInt64Tensor,
+        TypeDescriptor::Unsigned(IntSize::U1) => Ty::HostUint8Tensor,
+        TypeDescriptor::Unsigned(IntSize::U2) => Ty::HostUint16Tensor,
+        TypeDescriptor::Unsigned(IntSize::U4) => Ty::HostUint32Tensor,
+        TypeDescriptor::Unsigned(IntSize::U8) => Ty::HostUint64Tensor,
+        TypeDescriptor::Boolean => Ty::HostBitTensor,
+        other => {
+            return Err(Error::Storage(format!(
+                "unsupported element type {} of dataset '{}' in HDF5 file: {}",
+                other,
+                dataset.name(),
+                filename
+            )))
+        }
+    };
+    Ok(dtype)
+}
+
+/// Dataset at `path` in the file, or its only dataset at the root if `None`.
+fn find_dataset(filename: &str, file: &File, path: Option<&str>) -> Result<Dataset> {
+    match path {
+        Some(path) => file.dataset(path).map_err(|e| hdf5_error(filename, e)),
+        None => {
+            let mut datasets = file.datasets().map_err(|e| hdf5_error(filename, e))?;
+            match datasets.len() {
+                1 => Ok(datasets.remove(0)),
+                0 => Err(Error::Storage(format!(
+                    "no dataset at the root of HDF5 file: {}",
+                    filename
+                ))),
+                _ => Err(Error::Storage(format!(
+                    "HDF5 file {} holds several datasets, select one with {}#/<path>",
+                    filename, filename
+                ))),
+            }
+        }
+    }
+}
+
+/// Read the dataset at `path`, or the only dataset at the root of the file if `None`.
+pub(crate) async fn read_hdf5(
+    filename: &str,
+    path: Option<&str>,
+    placement: &HostPlacement,
+    dtype: Option<Ty>,
+) -> Result<Value> {
+    let file = File::open(filename).map_err(|e| hdf5_error(filename, e))?;
+    let dataset = find_dataset(filename, &file, path)?;
+    let stored_dtype = dataset_dtype(filename, &dataset)?;
+    if let Some(dtype) = dtype {
+        if dtype != stored_dtype {
+            return Err(Error::Storage(format!(
+                "expected {} but dataset '{}' of HDF5 file {} holds {}",
+                dtype,
+                dataset.name(),
+                filename,
+                stored_dtype
+            )));
+        }
+    }
+
+    macro_rules! tensor {
+        ($t:ty, $tensor:ty) => {{
+            let arr: ArrayD<$t> = dataset.read_dyn().map_err(|e| hdf5_error(filename, e))?;
+            let tensor: $tensor = placement.from_raw(arr);
+            Ok(Value::from(tensor))
+        }};
+    }
+
+    match stored_dtype {
+        Ty::HostFloat32Tensor => tensor!(f32, HostFloat32Tensor),
+        Ty::HostFloat64Tensor => tensor!(f64, HostFloat64Tensor),
+        Ty::HostInt8Tensor => tensor!(i8, HostInt8Tensor),
+        Ty::HostInt16Tensor => tensor!(i16, HostInt16Tensor),
+        Ty::HostInt32Tensor => tensor!(i32, HostInt32Tensor),
+        Ty::HostInt64Tensor => tensor!(i64, HostInt64Tensor),
+        Ty::HostUint8Tensor => tensor!(u8, HostUint8Tensor),
+        Ty::HostUint16Tensor => tensor!(u16, HostUint16Tensor),
+        Ty::HostUint32Tensor => tensor!(u32, HostUint32Tensor),
+        Ty::HostUint64Tensor => tensor!(u64, HostUint64Tensor),
+        Ty::HostBitTensor => {
+            let arr: ArrayD<bool> = dataset.read_dyn().map_err(|e| hdf5_error(filename, e))?;
+            let tensor: HostBitTensor = placement.from_raw(arr.mapv(u8::from));
+            Ok(Value::from(tensor))
+        }
+        _ => unreachable!("unsupported dtypes are rejected by dataset_dtype"),
+    }
+}
+
+/// Type and shape of the dataset at `path`, or of the only dataset at the root of the file if `None`.
+pub(crate) fn stat_hdf5(filename: &str, path: Option<&str>) -> Result<(Ty, Vec<usize>)> {
+    let file = File::open(filename).map_err(|e| hdf5_error(filename, e))?;
+    let dataset = find_dataset(filename, &file, path)?;
+    Ok((dataset_dtype(filename, &dataset)?, dataset.shape()))
+}
+
+/// Group holding the dataset at `path`, created along with its parents if missing,
+/// and the name of the dataset in it.
+fn parent_group<'p>(filename: &str, file: &File, path: &'p str) -> Result<(Group, &'p str)> {
+    let path = path.trim_start_matches('/');
+    let (parents, name) = match path.rsplit_once('/') {
+        Some((parents, name)) => (parents, name),
+        None => ("", path),
+    };
+    let mut group = file.group("/").map_err(|e| hdf5_error(filename, e))?;
+    for component in parents.split('/').filter(|c| !c.is_empty()) {
+        group = if group.link_exists(component) {
+            group.group(component)
+        } else {
+            group.create_group(component)
+        }
+        .map_err(|e| hdf5_error(filename, e))?;
+    }
+    Ok((group, name))
+}
+
+fn write_dataset<T: H5Type>(
+    filename: &str,
+    group: &Group,
+    name: &str,
+    arr: &ArrayD<T>,
+) -> Result<()> {
+    group
+        .new_dataset::<T>()
+        .shape(arr.shape())
+        .create(name)
+        .and_then(|dataset| dataset.write(arr))
+        .map_err(|e| hdf5_error(filename, e))
+}
+
+/// Write `data` as the dataset at `path`, replacing any dataset already there
+/// but keeping all other datasets of the file.
+pub(crate) async fn write_hdf5(filename: &str, path: &str, data: &Value) -> Result<()> {
+    let file = File::append(filename).map_err(|e| hdf5_error(filename, e))?;
+    let (group, name) = parent_group(filename, &file, path)?;
+    if name.is_empty() {
+        return Err(Error::Storage(format!(
+            "invalid dataset path '{}' for HDF5 file: {}",
+            path, filename
+        )));
+    }
+    if group.link_exists(name) {
+        group.unlink(name).map_err(|e| hdf5_error(filename, e))?;
+    }
+
+    match data {
+        Value::HostFloat32Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostFloat64Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostInt8Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostInt16Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostInt32Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostInt64Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostUint8Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostUint16Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostUint32Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostUint64Tensor(t) => write_dataset(filename, &group, name, &t.0.to_owned()),
+        Value::HostBitTensor(t) => {
+            let bits =
+                t.0.into_array::<u8>()
+                    .map_err(|e| Error::Storage(format!("failed to read bit tensor: {}", e)))?;
+            write_dataset(filename, &group, name, &bits.mapv(|bit| bit != 0))
+        }
+        _ => Err(Error::Storage(format!(
+            "cannot write unsupported tensor to HDF5 file: {}",
+            filename
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[tokio::test]
+    async fn test_hdf5_datasets() {
+        let plc = HostPlacement::from("host");
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("experiment.h5");
+        let filename = path.to_str().unwrap();
+
+        let features: HostFloat32Tensor = plc.from_raw(array![[0.5f32, 1.5], [2.5, 3.5]]);
+        let labels: HostInt64Tensor = plc.from_raw(array![1, 0]);
+        let mask: HostBitTensor = plc.from_raw(array![1u8, 0]);
+        write_hdf5(filename, "/inputs/features", &Value::from(features.clone()))
+            .await
+            .unwrap();
+        write_hdf5(filename, "/inputs/labels", &Value::from(labels.clone()))
+            .await
+            .unwrap();
+        write_hdf5(filename, "mask", &Value::from(mask.clone()))
+            .await
+            .unwrap();
+
+        let data = read_hdf5(filename, Some("/inputs/features"), &plc, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(features));
+        let data = read_hdf5(filename, Some("/inputs/labels"), &plc, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(labels));
+        // the only dataset at the root is read if no path is given
+        let data = read_hdf5(filename, None, &plc, None).await.unwrap();
+        assert_eq!(data, Value::from(mask));
+
+        // datasets are replaced when written again
+        let labels: HostInt64Tensor = plc.from_raw(array![0, 1, 1]);
+        write_hdf5(filename, "/inputs/labels", &Value::from(labels.clone()))
+            .await
+            .unwrap();
+        let data = read_hdf5(filename, Some("/inputs/labels"), &plc, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(labels));
+
+        assert!(read_hdf5(
+            filename,
+            Some("/inputs/labels"),
+            &plc,
+            Some(Ty::HostFloat64Tensor)
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{Error, Result};
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use std::fs::File;
+
+/// Table held by an Arrow IPC file, also known as Feather (version 2) file.
+pub(crate) fn read_ipc(filename: &str) -> Result<RecordBatch> {
+    let failed = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!("failed to read Arrow file: {}: {}", filename, e))
+    };
+    let file = File::open(filename).map_err(|e| failed(&e))?;
+    let reader = FileReader::try_new(file, None).map_err(|e| failed(&e))?;
+    let schema = reader.schema();
+    let batches = reader
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| failed(&e))?;
+    arrow::compute::concat_batches(&schema, &batches).map_err(|e| failed(&e))
+}
+
+/// Write `batch` to an Arrow IPC file, which can also be read as Feather file.
+pub(crate) fn write_ipc(filename: &str, batch: &RecordBatch) -> Result<()> {
+    let failed = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!("failed to write Arrow file: {}: {}", filename, e))
+    };
+    let file = File::create(filename).map_err(|e| failed(&e))?;
+    let mut writer = FileWriter::try_new(file, &batch.schema()).map_err(|e| failed(&e))?;
+    writer.write(batch).map_err(|e| failed(&e))?;
+    writer.finish().map_err(|e| failed(&e))?;
+    Ok(())
+}
@@ -1,23 +1,106 @@
 //! Filesystem-based storage implementation.
 
+#[cfg(feature = "compression")]
+pub(crate) mod compression;
 pub(crate) mod csv;
+#[cfg(feature = "hdf5")]
+pub(crate) mod hdf5;
+#[cfg(feature = "arrow")]
+pub(crate) mod ipc;
 pub(crate) mod numpy;
+pub mod safetensors;
 
-use self::csv::{read_csv, write_csv};
-use self::numpy::{read_numpy, write_numpy};
+use self::csv::{read_csv, read_csv_columns, write_csv, CsvOptions};
+#[cfg(feature = "hdf5")]
+use self::hdf5::{read_hdf5, stat_hdf5, write_hdf5};
+#[cfg(feature = "arrow")]
+use self::ipc::{read_ipc, write_ipc};
+use self::numpy::{
+    read_npz, read_numpy, read_numpy_chunks, read_numpy_header, write_npz, write_numpy,
+};
+use self::safetensors::{read_safetensors, read_safetensors_header, write_safetensors};
 use crate::error::Error;
 use crate::prelude::*;
-use crate::storage::AsyncStorage;
+use crate::storage::{stream_rows, AsyncStorage, ValueStat, ValueStream};
 use crate::Result;
+#[cfg(feature = "arrow")]
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
 use std::path::Path;
 
 #[derive(Default)]
-pub struct AsyncFilesystemStorage {}
+pub struct AsyncFilesystemStorage {
+    /// Level at which saved files are compressed with zstd, if at all.
+    #[cfg(feature = "compression")]
+    compression_level: Option<i32>,
+}
+
+#[cfg(feature = "compression")]
+impl AsyncFilesystemStorage {
+    /// Compress saved files with zstd at `level`, typically from 1 to 22, or not at all if `None`.
+    ///
+    /// Compressed files are detected and decompressed on load regardless of this setting.
+    pub fn with_compression(self, compression_level: Option<i32>) -> Self {
+        AsyncFilesystemStorage { compression_level }
+    }
+}
+
+#[cfg(feature = "compression")]
+fn staged_key(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| Error::Storage(format!("invalid staging path: {}", path.display())))
+}
 
 #[async_trait]
 impl AsyncStorage for AsyncFilesystemStorage {
+    fn formats(&self) -> Option<Vec<String>> {
+        #[allow(unused_mut)]
+        let mut formats = vec![
+            "csv".to_string(),
+            "npy".to_string(),
+            "npz".to_string(),
+            "safetensors".to_string(),
+        ];
+        #[cfg(feature = "parquet")]
+        formats.push("parquet".to_string());
+        #[cfg(feature = "arrow")]
+        formats.extend(vec![
+            "arrow".to_string(),
+            "feather".to_string(),
+            "ipc".to_string(),
+        ]);
+        #[cfg(feature = "hdf5")]
+        formats.extend(vec!["h5".to_string(), "hdf5".to_string()]);
+        Some(formats)
+    }
+
+    async fn check(&self) -> Result<()> {
+        // keys are paths relative to the working directory
+        tokio::fs::read_dir(".")
+            .await
+            .map_err(|e| Error::Storage(format!("working directory cannot be read: {}", e)))?;
+        Ok(())
+    }
+
     async fn save(&self, key: &str, _session_id: &SessionId, val: &Value) -> Result<()> {
+        tracing::debug!(key, ty = %val.ty(), "Saving value");
+        #[cfg(feature = "hdf5")]
+        if let Some((filename, dataset)) = split_hdf5_key(key) {
+            // without a dataset path the value is stored at the root, named after the file
+            let dataset = match dataset {
+                Some(dataset) => dataset,
+                None => file_stem(Path::new(filename), key)?,
+            };
+            return write_hdf5(filename, dataset, val).await;
+        }
+        #[cfg(feature = "compression")]
+        if let Some(level) = self.compression_level {
+            let (_dir, staged) = compression::staging_path(key)?;
+            AsyncFilesystemStorage::default()
+                .save(staged_key(&staged)?, _session_id, val)
+                .await?;
+            return compression::compress_file(&staged, Path::new(key), level);
+        }
         let path = Path::new(key);
         let extension = path
             .extension()
@@ -25,8 +108,29 @@ impl AsyncStorage for AsyncFilesystemStorage {
         match extension.to_str() {
             Some("csv") => write_csv(key, val).await,
             Some("npy") => write_numpy(key, val).await,
+            Some("npz") => {
+                // the value is stored as the only array in the archive, named after it
+                let array_name = file_stem(path, key)?;
+                let values = std::iter::once((array_name.to_string(), val.clone())).collect();
+                write_npz(key, &values).await
+            }
+            Some("safetensors") => {
+                // the value is stored as the only tensor in the file, named after it
+                let tensor_name = file_stem(path, key)?;
+                write_safetensors(key, tensor_name, val).await
+            }
+            #[cfg(feature = "parquet")]
+            Some("parquet") => {
+                let batch = value_to_table(path, key, val)?;
+                crate::storage::parquet::write_record_batch(key, &batch, None)
+            }
+            #[cfg(feature = "arrow")]
+            Some("arrow") | Some("feather") | Some("ipc") => {
+                let batch = value_to_table(path, key, val)?;
+                write_ipc(key, &batch)
+            }
             _ => Err(Error::Storage(format!(
-                "key must provide an extension of either '.csv' or '.npy', got: {}",
+                "key must provide an extension of either '.csv', '.npy', '.npz', or '.safetensors', got: {}",
                 key
             ))),
         }
@@ -39,26 +143,296 @@ impl AsyncStorage for AsyncFilesystemStorage {
         type_hint: Option<Ty>,
         query: &str,
     ) -> Result<Value> {
+        tracing::debug!(key, query, "Loading value");
+        let plc = HostPlacement::from("host");
+        #[cfg(feature = "hdf5")]
+        if let Some((filename, dataset)) = split_hdf5_key(key) {
+            return read_hdf5(filename, dataset, &plc, type_hint).await;
+        }
+        #[cfg(feature = "compression")]
+        if compression::is_compressed(Path::new(key)) {
+            let (_dir, staged) = compression::staging_path(key)?;
+            compression::decompress_file(Path::new(key), &staged)?;
+            return self
+                .load(staged_key(&staged)?, _session_id, type_hint, query)
+                .await;
+        }
         let path = Path::new(key);
         let extension = path
             .extension()
             .ok_or_else(|| Error::Storage(format!("failed to get extension from key: {}", key)))?;
-        let plc = HostPlacement::from("host");
         match extension.to_str() {
             Some("csv") => {
-                let query = parse_columns(query)?;
-                read_csv(key, &query, &plc).await
+                let options = parse_csv_options(query, type_hint)?;
+                match parse_tensor_name(query)? {
+                    Some(name) => {
+                        let mut columns = read_csv_columns(key, &options, &plc).await?;
+                        columns.remove(&name).ok_or_else(|| {
+                            Error::Storage(format!("no column named '{}' in file: {}", name, key))
+                        })
+                    }
+                    None => read_csv(key, &options, &plc).await,
+                }
             }
             Some("npy") => read_numpy(key, &plc, type_hint).await,
+            Some("npz") => {
+                let array_name = parse_tensor_name(query)?;
+                let mut values = read_npz(key, &plc, type_hint).await?;
+                match array_name {
+                    Some(name) => values.remove(&name).ok_or_else(|| {
+                        Error::Storage(format!("no array named '{}' in archive: {}", name, key))
+                    }),
+                    None if values.len() == 1 => Ok(values.into_values().next().unwrap()),
+                    None => Err(Error::Storage(format!(
+                        "archive {} holds several arrays, select one with {{\"tensor_name\": ...}}",
+                        key
+                    ))),
+                }
+            }
+            Some("safetensors") => {
+                let tensor_name = parse_tensor_name(query)?;
+                read_safetensors(key, tensor_name.as_deref(), &plc, type_hint).await
+            }
+            #[cfg(feature = "parquet")]
+            Some("parquet") => load_parquet(key, query, &plc),
+            #[cfg(feature = "arrow")]
+            Some("arrow") | Some("feather") | Some("ipc") => {
+                let batch = read_ipc(key)?;
+                table_to_value(key, &batch, query, &plc)
+            }
             _ => Err(Error::Storage(format!(
-                "key must provide an extension of either '.csv' or '.npy', got: {}",
+                "key must provide an extension of either '.csv', '.npy', '.npz', or '.safetensors', got: {}",
                 key
             ))),
         }
     }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        let path = Path::new(key);
+        let is_numpy = path.extension().and_then(|ext| ext.to_str()) == Some("npy");
+        #[cfg(feature = "compression")]
+        let is_numpy = is_numpy && !compression::is_compressed(path);
+        if is_numpy {
+            // numpy files are read row by row as chunks are consumed
+            tracing::debug!(key, chunk_rows, "Loading value in chunks");
+            let plc = HostPlacement::from("host");
+            return read_numpy_chunks(key, &plc, type_hint, chunk_rows).await;
+        }
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        list_files(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        #[allow(unused_mut)]
+        let mut filename = key;
+        #[cfg(feature = "hdf5")]
+        let mut dataset = None;
+        #[cfg(feature = "hdf5")]
+        if let Some((file, path)) = split_hdf5_key(key) {
+            filename = file;
+            dataset = Some(path);
+        }
+        let metadata = tokio::fs::metadata(filename)
+            .await
+            .map_err(|e| Error::Storage(format!("failed to stat file: {}: {}", filename, e)))?;
+        let mut stat = ValueStat {
+            bytes: metadata.len(),
+            modified: metadata.modified().ok(),
+            ..ValueStat::default()
+        };
+
+        // the type and shape are only given where they can be read from a header
+        #[cfg(feature = "hdf5")]
+        if let Some(path) = dataset {
+            let (ty, shape) = stat_hdf5(filename, path)?;
+            stat.ty = Some(ty);
+            stat.shape = Some(shape);
+            return Ok(stat);
+        }
+        #[cfg(feature = "compression")]
+        if compression::is_compressed(Path::new(key)) {
+            return Ok(stat);
+        }
+        let header = match Path::new(key).extension().and_then(|ext| ext.to_str()) {
+            Some("npy") => Some(read_numpy_header(key)?),
+            Some("safetensors") => read_safetensors_header(key)?,
+            _ => None,
+        };
+        if let Some((ty, shape)) = header {
+            stat.ty = Some(ty);
+            stat.shape = Some(shape);
+        }
+        Ok(stat)
+    }
+}
+
+/// Paths of all files, in any directory, starting with `prefix`.
+///
+/// Only the directories that may hold such files are visited, starting from the
+/// directory that `prefix` ends in, such that `data/` lists everything under `data`
+/// while `data/x` lists all files in `data` whose name starts with `x`.
+async fn list_files(prefix: &str) -> Result<Vec<String>> {
+    let base = match prefix.rfind('/') {
+        Some(i) => &prefix[..=i],
+        None => "",
+    };
+    let mut keys = Vec::new();
+    let mut dirs = vec![base.to_string()];
+    while let Some(dir) = dirs.pop() {
+        let path = if dir.is_empty() { "." } else { dir.as_str() };
+        let mut entries = match tokio::fs::read_dir(path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(Error::Storage(format!(
+                    "failed to list directory: {}: {}",
+                    path, e
+                )))
+            }
+        };
+        let list_error = |e: std::io::Error| {
+            Error::Storage(format!("failed to list directory: {}: {}", path, e))
+        };
+        while let Some(entry) = entries.next_entry().await.map_err(list_error)? {
+            let name = match entry.file_name().into_string() {
+                Ok(name) => name,
+                // such files cannot be given as keys
+                Err(_) => continue,
+            };
+            let key = format!("{}{}", dir, name);
+            if entry.file_type().await.map_err(list_error)?.is_dir() {
+                let dir = format!("{}/", key);
+                if dir.starts_with(prefix) || prefix.starts_with(&dir) {
+                    dirs.push(dir);
+                }
+            } else if key.starts_with(prefix) {
+                keys.push(key);
+            }
+        }
+    }
+    keys.sort();
+    Ok(keys)
+}
+
+/// Table holding a vector as a column named after the file, or a matrix as columns `col_<i>`.
+#[cfg(feature = "arrow")]
+fn value_to_table(path: &Path, key: &str, val: &Value) -> Result<RecordBatch> {
+    use crate::host::arrow::{matrix_to_record_batch, tensors_to_record_batch};
+
+    let is_matrix = match val {
+        Value::HostFloat64Tensor(t) => t.0.ndim() == 2,
+        Value::HostFloat32Tensor(t) => t.0.ndim() == 2,
+        _ => false,
+    };
+    let batch = if is_matrix {
+        matrix_to_record_batch(val)
+    } else {
+        let column_name = file_stem(path, key)?;
+        tensors_to_record_batch(&[(column_name.to_string(), val.clone())])
+    };
+    batch.map_err(|e| Error::Storage(format!("cannot save value to {}: {}", key, e)))
+}
+
+/// The column of `batch` selected by `tensor_name` as a vector, or the columns
+/// selected by `select_columns` as a `Float64` matrix.
+#[cfg(feature = "arrow")]
+fn table_to_value(
+    key: &str,
+    batch: &RecordBatch,
+    query: &str,
+    plc: &HostPlacement,
+) -> Result<Value> {
+    use crate::host::arrow::{array_to_value, record_batches_to_matrix};
+
+    match parse_tensor_name(query)? {
+        Some(name) => {
+            let column = batch.column_by_name(&name).ok_or_else(|| {
+                Error::Storage(format!("no column named '{}' in table: {}", name, key))
+            })?;
+            array_to_value(&name, column.as_ref(), plc)
+                .map_err(|e| Error::Storage(format!("cannot load column from {}: {}", key, e)))
+        }
+        None => {
+            let columns = parse_columns(query)?;
+            record_batches_to_matrix(&batch.schema(), std::slice::from_ref(batch), &columns, plc)
+                .map_err(|e| Error::Storage(format!("cannot load table from {}: {}", key, e)))
+        }
+    }
+}
+
+/// Load a table as by `table_to_value`, optionally restricted to the row groups
+/// listed by `row_groups`.
+#[cfg(feature = "parquet")]
+fn load_parquet(key: &str, query: &str, plc: &HostPlacement) -> Result<Value> {
+    use crate::storage::parquet::{read_record_batch, ParquetOptions};
+
+    let mut options = ParquetOptions::default();
+    if !query.is_empty() {
+        let jsn: serde_json::Value = serde_json::from_str(query)
+            .map_err(|e| Error::Storage(format!("failed to parse query as json: {}", e)))?;
+        match jsn.get("row_groups") {
+            Some(serde_json::Value::Array(v)) => {
+                let row_groups = v
+                    .iter()
+                    .map(|i| match i.as_u64() {
+                        Some(i) => Ok(i as usize),
+                        None => Err(Error::Storage(
+                            "row_groups must contain an array of row group indices".to_string(),
+                        )),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                options = options.with_row_groups(row_groups);
+            }
+            None => (),
+            _ => {
+                return Err(Error::Storage(
+                    "row_groups must contain an array of row group indices".to_string(),
+                ))
+            }
+        }
+    }
+
+    // only read the columns that are needed
+    let columns = match parse_tensor_name(query)? {
+        Some(name) => vec![name],
+        None => parse_columns(query)?,
+    };
+    let batch = read_record_batch(key, &options.with_columns(columns))?;
+    table_to_value(key, &batch, query, plc)
+}
+
+/// File and dataset path of keys of HDF5 files, given as `<file>.h5#<dataset path>`
+/// or as `<file>.h5` alone.
+#[cfg(feature = "hdf5")]
+fn split_hdf5_key(key: &str) -> Option<(&str, Option<&str>)> {
+    let (filename, dataset) = match key.split_once('#') {
+        Some((filename, dataset)) => (filename, Some(dataset)),
+        None => (key, None),
+    };
+    match Path::new(filename).extension()?.to_str()? {
+        "h5" | "hdf5" => Some((filename, dataset)),
+        _ => None,
+    }
 }
 
-fn parse_columns(query: &str) -> Result<Vec<String>> {
+fn file_stem<'k>(path: &'k Path, key: &str) -> Result<&'k str> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| Error::Storage(format!("failed to get file name from key: {}", key)))
+}
+
+pub(crate) fn parse_columns(query: &str) -> Result<Vec<String>> {
     match query {
         "" => Ok(Vec::new()),
         query_str => {
@@ -83,10 +457,83 @@ fn parse_columns(query: &str) -> Result<Vec<String>> {
     }
 }
 
+/// Options for reading CSV files, given as
+/// `{"select_columns": [...], "has_headers": ..., "delimiter": ..., "dtype": ...}`.
+///
+/// The dtype defaults to the type hint of the load, if any, and otherwise to `float64`.
+fn parse_csv_options(query: &str, type_hint: Option<Ty>) -> Result<CsvOptions> {
+    let mut options = CsvOptions::default().with_columns(parse_columns(query)?);
+    if let Some(dtype) = type_hint {
+        options = options.with_dtype(dtype);
+    }
+    if query.is_empty() {
+        return Ok(options);
+    }
+    let jsn: serde_json::Value = serde_json::from_str(query)
+        .map_err(|e| Error::Storage(format!("failed to parse query as json: {}", e)))?;
+    match jsn.get("has_headers") {
+        Some(serde_json::Value::Bool(has_headers)) => {
+            options = options.with_headers(*has_headers);
+        }
+        None => (),
+        _ => return Err(Error::Storage("has_headers must be a boolean".to_string())),
+    }
+    match jsn.get("delimiter") {
+        Some(serde_json::Value::String(s)) if s.len() == 1 => {
+            options = options.with_delimiter(s.as_bytes()[0]);
+        }
+        None => (),
+        _ => {
+            return Err(Error::Storage(
+                "delimiter must be a string of a single ASCII character".to_string(),
+            ))
+        }
+    }
+    match jsn.get("dtype") {
+        Some(serde_json::Value::String(s)) => {
+            let dtype = match s.as_str() {
+                "float32" => Ty::HostFloat32Tensor,
+                "float64" => Ty::HostFloat64Tensor,
+                "int8" => Ty::HostInt8Tensor,
+                "int16" => Ty::HostInt16Tensor,
+                "int32" => Ty::HostInt32Tensor,
+                "int64" => Ty::HostInt64Tensor,
+                "uint8" => Ty::HostUint8Tensor,
+                "uint16" => Ty::HostUint16Tensor,
+                "uint32" => Ty::HostUint32Tensor,
+                "uint64" => Ty::HostUint64Tensor,
+                _ => return Err(Error::Storage(format!("unsupported csv dtype: {}", s))),
+            };
+            options = options.with_dtype(dtype);
+        }
+        None => (),
+        _ => return Err(Error::Storage("dtype must be a string".to_string())),
+    }
+    Ok(options)
+}
+
+/// Name of the tensor to load from a file or archive holding several, given as `{"tensor_name": ...}`.
+fn parse_tensor_name(query: &str) -> Result<Option<String>> {
+    match query {
+        "" => Ok(None),
+        query_str => {
+            let jsn: serde_json::Value = serde_json::from_str(query_str)
+                .map_err(|e| Error::Storage(format!("failed to parse query as json: {}", e)))?;
+            match jsn.get("tensor_name") {
+                Some(serde_json::Value::String(s)) => Ok(Some(s.to_string())),
+                None => Ok(None),
+                _ => Err(Error::Storage(
+                    "tensor_name must be a string naming a tensor in the file".to_string(),
+                )),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::HostFloat64Tensor;
+    use crate::types::{HostFloat64Tensor, HostInt64Tensor};
     use ndarray::array;
     use std::convert::TryFrom;
     use std::fs::File;
@@ -126,6 +573,103 @@ mod tests {
         assert_eq!(data, expected);
     }
 
+    #[tokio::test]
+    async fn test_load_chunks_async_local_file_storage() {
+        use futures::TryStreamExt;
+
+        let storage = AsyncFilesystemStorage::default();
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![
+            [1.0, 2.0],
+            [3.0, 4.0],
+            [5.0, 6.0],
+            [7.0, 8.0],
+            [9.0, 10.0]
+        ]);
+
+        let temp_dir = tempdir().unwrap();
+        for file_name in ["data.npy", "data.csv"].iter() {
+            let path = temp_dir.path().join(file_name);
+            let key = path.to_str().unwrap();
+            storage
+                .save(key, &session_id, &Value::from(tensor.clone()))
+                .await
+                .unwrap();
+
+            let chunks: Vec<Value> = storage
+                .load_chunks(key, &session_id, None, "", 2)
+                .await
+                .unwrap()
+                .try_collect()
+                .await
+                .unwrap();
+            let expected: Vec<HostFloat64Tensor> = vec![
+                plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]),
+                plc.from_raw(array![[5.0, 6.0], [7.0, 8.0]]),
+                plc.from_raw(array![[9.0, 10.0]]),
+            ];
+            let expected: Vec<Value> = expected.into_iter().map(Value::from).collect();
+            assert_eq!(chunks, expected);
+        }
+
+        let path = temp_dir.path().join("data.npy");
+        assert!(storage
+            .load_chunks(path.to_str().unwrap(), &session_id, None, "", 0)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_and_stat_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        let plc = HostPlacement::from("host");
+        let tensor: HostInt64Tensor = plc.from_raw(array![[1, 2, 3], [4, 5, 6]]);
+
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+        std::fs::create_dir(temp_dir.path().join("results")).unwrap();
+        let keys: Vec<String> = ["x.npy", "x.safetensors", "y.csv", "results/x.npy"]
+            .iter()
+            .map(|name| format!("{}/{}", root, name))
+            .collect();
+        for key in keys.iter() {
+            storage
+                .save(key, &session_id, &Value::from(tensor.clone()))
+                .await
+                .unwrap();
+        }
+
+        let listed = storage.list(&format!("{}/", root)).await.unwrap();
+        let mut expected = keys.clone();
+        expected.sort();
+        assert_eq!(listed, expected);
+        let listed = storage.list(&format!("{}/x", root)).await.unwrap();
+        assert_eq!(listed, vec![keys[0].clone(), keys[1].clone()]);
+        let listed = storage.list(&format!("{}/res", root)).await.unwrap();
+        assert_eq!(listed, vec![keys[3].clone()]);
+        let listed = storage.list(&format!("{}/missing/", root)).await.unwrap();
+        assert!(listed.is_empty());
+
+        for key in keys[..2].iter() {
+            let stat = storage.stat(key).await.unwrap();
+            assert_eq!(stat.ty, Some(Ty::HostInt64Tensor));
+            assert_eq!(stat.shape, Some(vec![2, 3]));
+            assert_eq!(stat.bytes, std::fs::metadata(key).unwrap().len());
+            assert!(stat.modified.is_some());
+        }
+        // the type of values in csv files is only known once loaded
+        let stat = storage.stat(&keys[2]).await.unwrap();
+        assert_eq!(stat.ty, None);
+        assert_eq!(stat.shape, None);
+
+        assert!(storage
+            .stat(&format!("{}/missing.npy", root))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_csv_async_local_file_storage() {
         let storage = AsyncFilesystemStorage::default();
@@ -156,4 +700,281 @@ mod tests {
             .unwrap();
         assert_eq!(data, expected);
     }
+
+    #[tokio::test]
+    async fn test_safetensors_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("weights.safetensors");
+        let filename = path
+            .to_str()
+            .expect("trying to get path from temp file")
+            .to_string();
+
+        let session_id_str = "01FGSQ37YDJSVJXSA6SSY7G4Y2";
+        let session_id = SessionId::try_from(session_id_str).unwrap();
+        storage
+            .save(&filename, &session_id, &expected)
+            .await
+            .unwrap();
+
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"tensor_name": "weights"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_compressed_async_local_file_storage() {
+        let plc = HostPlacement::from("host");
+        let tensor: HostInt64Tensor =
+            plc.from_raw(ndarray::Array::from_elem((64, 64), 7).into_dyn());
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let compressed = temp_dir.path().join("compressed.npy");
+        let compressed = compressed.to_str().unwrap();
+        let plain = temp_dir.path().join("plain.npy");
+        let plain = plain.to_str().unwrap();
+
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        let storage = AsyncFilesystemStorage::default().with_compression(Some(3));
+        storage
+            .save(compressed, &session_id, &expected)
+            .await
+            .unwrap();
+        AsyncFilesystemStorage::default()
+            .save(plain, &session_id, &expected)
+            .await
+            .unwrap();
+        assert!(compression::is_compressed(Path::new(compressed)));
+        assert!(!compression::is_compressed(Path::new(plain)));
+        let compressed_len = std::fs::metadata(compressed).unwrap().len();
+        let plain_len = std::fs::metadata(plain).unwrap().len();
+        assert!(compressed_len * 10 < plain_len);
+
+        // compressed files are detected regardless of the settings of the storage
+        for key in [compressed, plain].iter() {
+            let data = AsyncFilesystemStorage::default()
+                .load(key, &session_id, None, "")
+                .await
+                .unwrap();
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn test_parquet_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("features.parquet");
+        let filename = path.to_str().unwrap().to_string();
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        storage
+            .save(&filename, &session_id, &expected)
+            .await
+            .unwrap();
+
+        let data = storage
+            .load(&filename, &session_id, None, "")
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+        let data = storage
+            .load(&filename, &session_id, None, r#"{"tensor_name": "col_1"}"#)
+            .await
+            .unwrap();
+        let column: HostFloat64Tensor = plc.from_raw(array![4.0, 7.0]);
+        assert_eq!(data, Value::from(column));
+
+        // vectors are saved as a single column named after the file
+        let labels: HostInt64Tensor = plc.from_raw(array![0, 1, 1]);
+        let path = temp_dir.path().join("labels.parquet");
+        let filename = path.to_str().unwrap().to_string();
+        storage
+            .save(&filename, &session_id, &Value::from(labels.clone()))
+            .await
+            .unwrap();
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"tensor_name": "labels", "row_groups": [0]}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(labels));
+    }
+
+    #[cfg(feature = "arrow")]
+    #[tokio::test]
+    async fn test_feather_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("features.feather");
+        let filename = path.to_str().unwrap().to_string();
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        storage
+            .save(&filename, &session_id, &expected)
+            .await
+            .unwrap();
+
+        let data = storage
+            .load(&filename, &session_id, None, "")
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"select_columns": ["col_2"]}"#,
+            )
+            .await
+            .unwrap();
+        let column: HostFloat64Tensor = plc.from_raw(array![[5.0], [12.0]]);
+        assert_eq!(data, Value::from(column));
+
+        let labels: HostInt64Tensor = plc.from_raw(array![0, 1, 1]);
+        let path = temp_dir.path().join("labels.arrow");
+        let filename = path.to_str().unwrap().to_string();
+        storage
+            .save(&filename, &session_id, &Value::from(labels.clone()))
+            .await
+            .unwrap();
+        let data = storage
+            .load(&filename, &session_id, None, r#"{"tensor_name": "labels"}"#)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(labels));
+    }
+
+    #[cfg(feature = "hdf5")]
+    #[tokio::test]
+    async fn test_hdf5_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("results.h5");
+        let filename = path.to_str().unwrap().to_string();
+        let key = format!("{}#/model/predictions", filename);
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+        storage.save(&key, &session_id, &expected).await.unwrap();
+
+        let data = storage.load(&key, &session_id, None, "").await.unwrap();
+        assert_eq!(data, expected);
+        // the file holds no dataset at its root
+        assert!(storage
+            .load(&filename, &session_id, None, "")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_npz_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("features.npz");
+        let filename = path
+            .to_str()
+            .expect("trying to get path from temp file")
+            .to_string();
+
+        let session_id_str = "01FGSQ37YDJSVJXSA6SSY7G4Y2";
+        let session_id = SessionId::try_from(session_id_str).unwrap();
+        storage
+            .save(&filename, &session_id, &expected)
+            .await
+            .unwrap();
+
+        let data = storage
+            .load(&filename, &session_id, None, "")
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"tensor_name": "features"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+        assert!(storage
+            .load(&filename, &session_id, None, r#"{"tensor_name": "labels"}"#)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv_options_async_local_file_storage() {
+        let storage = AsyncFilesystemStorage::default();
+        let plc = HostPlacement::from("host");
+
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("people.csv");
+        std::fs::write(&path, "age\theight\tid\n31\t1.8\t1\n47\t1.6\t2\n").unwrap();
+        let filename = path.to_str().unwrap().to_string();
+        let session_id = SessionId::try_from("01FGSQ37YDJSVJXSA6SSY7G4Y2").unwrap();
+
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"select_columns": ["age", "id"], "delimiter": "\t", "dtype": "int64"}"#,
+            )
+            .await
+            .unwrap();
+        let expected: HostInt64Tensor = plc.from_raw(array![[31, 1], [47, 2]]);
+        assert_eq!(data, Value::from(expected));
+
+        let data = storage
+            .load(
+                &filename,
+                &session_id,
+                None,
+                r#"{"tensor_name": "height", "delimiter": "\t"}"#,
+            )
+            .await
+            .unwrap();
+        let expected: HostFloat64Tensor = plc.from_raw(array![1.8, 1.6]);
+        assert_eq!(data, Value::from(expected));
+    }
 }
@@ -1,9 +1,13 @@
 use crate::prelude::*;
+use crate::storage::{stream_rows, ValueStream};
 use crate::{Error, Result};
-use ndarray::ArrayD;
-use ndarray_npy::{read_npy, write_npy};
+use futures::StreamExt;
+use ndarray::{ArrayD, IxDyn, ShapeBuilder};
+use ndarray_npy::{write_npy, NpzReader, NpzWriter, ReadNpzError};
+use std::collections::HashMap;
+use std::convert::TryInto;
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufReader, Read, Seek};
 
 #[allow(dead_code)]
 pub(crate) async fn read_numpy(
@@ -11,287 +15,579 @@ pub(crate) async fn read_numpy(
     placement: &HostPlacement,
     dtype: Option<Ty>,
 ) -> Result<Value> {
-    let dtype = match dtype {
-        Some(dtype) => Ok(dtype),
-        None => extract_dtype(filename).map_err(|e| {
-            Error::Storage(format!(
-                "parsing failure from numpy data file: {}: {}",
-                filename, e
-            ))
-        }),
-    }?;
+    let read_error = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!(
+            "failed to read numpy data file: {}: {}",
+            filename, e
+        ))
+    };
+    let file = File::open(filename).map_err(|e| read_error(&e))?;
+    let contents = file_contents(&file).map_err(|e| read_error(&e))?;
+    let mut reader: &[u8] = &contents;
+    let header = read_header(&mut reader).map_err(|e| {
+        Error::Storage(format!(
+            "parsing failure from numpy data file: {}: {}",
+            filename, e
+        ))
+    })?;
+    if let Some(dtype) = dtype {
+        if dtype != header.dtype {
+            return Err(read_error(&format!(
+                "expected {} but file holds {}",
+                dtype, header.dtype
+            )));
+        }
+    }
+    read_value(reader, &header, placement).map_err(|e| read_error(&e))
+}
+
+/// Type and shape of the array in the numpy data file at `filename`, read from its header only.
+pub(crate) fn read_numpy_header(filename: &str) -> Result<(Ty, Vec<usize>)> {
+    let file = File::open(filename).map_err(|e| {
+        Error::Storage(format!(
+            "failed to read numpy data file: {}: {}",
+            filename, e
+        ))
+    })?;
+    let header = read_header(&mut BufReader::new(file)).map_err(|e| {
+        Error::Storage(format!(
+            "parsing failure from numpy data file: {}: {}",
+            filename, e
+        ))
+    })?;
+    Ok((header.dtype, header.shape))
+}
+
+/// Chunks of at most `chunk_rows` rows of the array in the numpy data file at
+/// `filename`, each read from the file only once the previous one has been consumed.
+///
+/// Arrays in Fortran order are not stored row by row and are instead read as a
+/// whole before being split up.
+pub(crate) async fn read_numpy_chunks(
+    filename: &str,
+    placement: &HostPlacement,
+    dtype: Option<Ty>,
+    chunk_rows: usize,
+) -> Result<ValueStream> {
+    let read_error = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!(
+            "failed to read numpy data file: {}: {}",
+            filename, e
+        ))
+    };
+    let file = File::open(filename).map_err(|e| read_error(&e))?;
+    let mut reader = BufReader::new(file);
+    let header = read_header(&mut reader).map_err(|e| {
+        Error::Storage(format!(
+            "parsing failure from numpy data file: {}: {}",
+            filename, e
+        ))
+    })?;
+    if let Some(dtype) = dtype {
+        if dtype != header.dtype {
+            return Err(read_error(&format!(
+                "expected {} but file holds {}",
+                dtype, header.dtype
+            )));
+        }
+    }
+    if header.fortran_order || header.shape.is_empty() || chunk_rows == 0 {
+        let value = read_numpy(filename, placement, dtype).await?;
+        return stream_rows(&value, chunk_rows);
+    }
+
+    let rows = header.shape[0];
+    let row_bytes = header.shape[1..].iter().product::<usize>() * element_size(&header.dtype)?;
+    let filename = filename.to_string();
+    let placement = placement.clone();
+    let chunks = futures::stream::try_unfold((reader, 0), move |(mut reader, start)| {
+        let filename = filename.clone();
+        let placement = placement.clone();
+        let mut shape = header.shape.clone();
+        let num_rows = std::cmp::min(chunk_rows, rows.saturating_sub(start));
+        shape[0] = num_rows;
+        let header = NpyHeader {
+            shape,
+            ..header.clone()
+        };
+        async move {
+            if num_rows == 0 {
+                return Ok(None);
+            }
+            let read_chunk = move || -> Result<(Value, BufReader<File>)> {
+                let mut bytes = vec![0u8; num_rows * row_bytes];
+                reader
+                    .read_exact(&mut bytes)
+                    .map_err(|e| Error::Storage(format!("numpy data is truncated: {}", e)))?;
+                let value = read_value(&bytes, &header, &placement)?;
+                Ok((value, reader))
+            };
+            let (value, reader) = tokio::task::spawn_blocking(read_chunk)
+                .await
+                .map_err(|e| Error::Storage(e.to_string()))?
+                .map_err(|e| {
+                    Error::Storage(format!(
+                        "failed to read numpy data file: {}: {}",
+                        filename, e
+                    ))
+                })?;
+            Ok(Some((value, (reader, start + num_rows))))
+        }
+    });
+    Ok(chunks.boxed())
+}
+
+/// Tensor held by the data section following `header`.
+fn read_value(bytes: &[u8], header: &NpyHeader, placement: &HostPlacement) -> Result<Value> {
+    macro_rules! tensor {
+        ($t:ty, $tensor:ty) => {{
+            let arr: ArrayD<$t> = read_data(bytes, header)?;
+            let tensor: $tensor = placement.from_raw(arr);
+            Ok(Value::from(tensor))
+        }};
+    }
+
+    match header.dtype {
+        Ty::HostFloat64Tensor => tensor!(f64, HostFloat64Tensor),
+        Ty::HostFloat32Tensor => tensor!(f32, HostFloat32Tensor),
+        // numpy stores booleans as one byte each, holding either 0 or 1
+        Ty::HostBitTensor => tensor!(u8, HostBitTensor),
+        Ty::HostInt8Tensor => tensor!(i8, HostInt8Tensor),
+        Ty::HostInt16Tensor => tensor!(i16, HostInt16Tensor),
+        Ty::HostInt32Tensor => tensor!(i32, HostInt32Tensor),
+        Ty::HostInt64Tensor => tensor!(i64, HostInt64Tensor),
+        Ty::HostUint64Tensor => tensor!(u64, HostUint64Tensor),
+        Ty::HostUint8Tensor => tensor!(u8, HostUint8Tensor),
+        Ty::HostUint16Tensor => tensor!(u16, HostUint16Tensor),
+        Ty::HostUint32Tensor => tensor!(u32, HostUint32Tensor),
+        dtype => Err(Error::Storage(format!(
+            "invalid dtype for numpy storage read: {}",
+            dtype
+        ))),
+    }
+}
+
+/// Number of bytes taken by each element of tensors of type `dtype` in numpy data files.
+fn element_size(dtype: &Ty) -> Result<usize> {
     match dtype {
+        Ty::HostFloat64Tensor | Ty::HostInt64Tensor | Ty::HostUint64Tensor => Ok(8),
+        Ty::HostFloat32Tensor | Ty::HostInt32Tensor | Ty::HostUint32Tensor => Ok(4),
+        Ty::HostInt16Tensor | Ty::HostUint16Tensor => Ok(2),
+        Ty::HostBitTensor | Ty::HostInt8Tensor | Ty::HostUint8Tensor => Ok(1),
+        dtype => Err(Error::Storage(format!(
+            "invalid dtype for numpy storage read: {}",
+            dtype
+        ))),
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) async fn write_numpy(filename: &str, data: &Value) -> Result<()> {
+    let result = match data {
+        Value::HostFloat64Tensor(t) => write_npy(filename, &t.0),
+        Value::HostFloat32Tensor(t) => write_npy(filename, &t.0),
+        Value::HostUint8Tensor(t) => write_npy(filename, &t.0),
+        Value::HostUint16Tensor(t) => write_npy(filename, &t.0),
+        Value::HostUint32Tensor(t) => write_npy(filename, &t.0),
+        Value::HostUint64Tensor(t) => write_npy(filename, &t.0),
+        Value::HostInt8Tensor(t) => write_npy(filename, &t.0),
+        Value::HostInt16Tensor(t) => write_npy(filename, &t.0),
+        Value::HostInt32Tensor(t) => write_npy(filename, &t.0),
+        Value::HostInt64Tensor(t) => write_npy(filename, &t.0),
+        Value::HostBitTensor(t) => {
+            let bits =
+                t.0.into_array::<u8>()
+                    .map_err(|e| Error::Storage(format!("failed to read bit tensor: {}", e)))?;
+            write_npy(filename, &bits.mapv(|bit| bit != 0))
+        }
+        _ => {
+            return Err(Error::Storage(format!(
+                "cannot write unsupported tensor to numpy file: {}",
+                filename
+            )))
+        }
+    };
+    result.map_err(|e| {
+        Error::Storage(format!(
+            "failed to write moose value to file: '{}': {}",
+            filename, e
+        ))
+    })?;
+    Ok(())
+}
+
+/// Element types tried, in order, when reading arrays from archives without a type hint.
+const NPZ_DTYPES: [Ty; 6] = [
+    Ty::HostFloat64Tensor,
+    Ty::HostFloat32Tensor,
+    Ty::HostInt64Tensor,
+    Ty::HostInt32Tensor,
+    Ty::HostUint64Tensor,
+    Ty::HostUint32Tensor,
+];
+
+fn read_npz_array<R: Read + Seek>(
+    npz: &mut NpzReader<R>,
+    name: &str,
+    placement: &HostPlacement,
+    dtype: &Ty,
+) -> std::result::Result<Value, ReadNpzError> {
+    let value = match dtype {
         Ty::HostFloat64Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
+            let arr: ArrayD<f64> = npz.by_name(name)?;
             let tensor: HostFloat64Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
+            Value::from(tensor)
         }
         Ty::HostFloat32Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
+            let arr: ArrayD<f32> = npz.by_name(name)?;
             let tensor: HostFloat32Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
+            Value::from(tensor)
         }
         Ty::HostInt32Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
+            let arr: ArrayD<i32> = npz.by_name(name)?;
             let tensor: HostInt32Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
+            Value::from(tensor)
         }
         Ty::HostInt64Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
+            let arr: ArrayD<i64> = npz.by_name(name)?;
             let tensor: HostInt64Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
-        }
-        Ty::HostUint64Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
-            let tensor: HostUint64Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
+            Value::from(tensor)
         }
         Ty::HostUint32Tensor => {
-            let arr: ArrayD<_> = read_npy(filename).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to read numpy data file: {}: {}",
-                    filename, e
-                ))
-            })?;
+            let arr: ArrayD<u32> = npz.by_name(name)?;
             let tensor: HostUint32Tensor = placement.from_raw(arr);
-            let value = Value::from(tensor);
-            Ok(value)
+            Value::from(tensor)
         }
-        _ => Err(Error::Storage(format!(
-            "invalid dtype for numpy storage read: {}",
-            dtype
-        ))),
-    }
+        Ty::HostUint64Tensor => {
+            let arr: ArrayD<u64> = npz.by_name(name)?;
+            let tensor: HostUint64Tensor = placement.from_raw(arr);
+            Value::from(tensor)
+        }
+        _ => unreachable!("unsupported dtypes are rejected by read_npz"),
+    };
+    Ok(value)
 }
 
-#[allow(dead_code)]
-pub(crate) async fn write_numpy(filename: &str, data: &Value) -> Result<()> {
-    match data {
-        Value::HostFloat64Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        Value::HostFloat32Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        Value::HostUint32Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        Value::HostUint64Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        Value::HostInt32Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        Value::HostInt64Tensor(t) => {
-            write_npy(filename, &t.0).map_err(|e| {
-                Error::Storage(format!(
-                    "failed to write moose value to file: '{}': {}",
-                    filename, e
-                ))
-            })?;
-        }
-        _ => {
+/// Read all arrays of a `.npz` archive, keyed by their names without the `.npy` extension.
+///
+/// If `dtype` is given then all arrays are read as that type, otherwise the type
+/// of each array is derived from its header.
+pub(crate) async fn read_npz(
+    filename: &str,
+    placement: &HostPlacement,
+    dtype: Option<Ty>,
+) -> Result<HashMap<String, Value>> {
+    let read_error = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!("failed to read numpy archive: {}: {}", filename, e))
+    };
+    let candidates = match dtype {
+        Some(dtype) if NPZ_DTYPES.contains(&dtype) => vec![dtype],
+        Some(dtype) => {
             return Err(Error::Storage(format!(
-                "cannot write unsupported tensor to numpy file: {}",
-                filename
+                "invalid dtype for numpy storage read: {}",
+                dtype
             )))
         }
+        None => NPZ_DTYPES.to_vec(),
+    };
+    let file = File::open(filename).map_err(|e| read_error(&e))?;
+    let mut npz = NpzReader::new(file).map_err(|e| read_error(&e))?;
+    let names = npz.names().map_err(|e| read_error(&e))?;
+
+    let mut values = HashMap::with_capacity(names.len());
+    for name in names {
+        let mut last_error = None;
+        let mut value = None;
+        for dtype in candidates.iter() {
+            match read_npz_array(&mut npz, &name, placement, dtype) {
+                Ok(v) => {
+                    value = Some(v);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let value = match (value, last_error) {
+            (Some(value), _) => value,
+            (None, Some(e)) => return Err(read_error(&format!("array '{}': {}", name, e))),
+            (None, None) => unreachable!(),
+        };
+        let key = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+        values.insert(key, value);
     }
-    Ok(())
+    Ok(values)
 }
 
-fn match_char(got: u8, expected: char) -> Result<()> {
-    if got != expected as u8 {
-        Err(Error::Storage(format!(
-            "expecting: {} got: {}",
-            expected, got
-        )))
-    } else {
-        Ok(())
+/// Write `values` as arrays of a `.npz` archive named after their keys.
+pub(crate) async fn write_npz(filename: &str, values: &HashMap<String, Value>) -> Result<()> {
+    let write_error = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!(
+            "failed to write moose values to numpy archive: '{}': {}",
+            filename, e
+        ))
+    };
+    let file = File::create(filename).map_err(|e| write_error(&e))?;
+    let mut npz = NpzWriter::new(file);
+
+    // sort by name for reproducible archives
+    let mut names: Vec<&String> = values.keys().collect();
+    names.sort();
+    for name in names {
+        let result = match &values[name] {
+            Value::HostFloat64Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            Value::HostFloat32Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            Value::HostUint32Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            Value::HostUint64Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            Value::HostInt32Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            Value::HostInt64Tensor(t) => npz.add_array(name.as_str(), &t.0),
+            _ => {
+                return Err(Error::Storage(format!(
+                    "cannot write unsupported tensor '{}' to numpy archive: {}",
+                    name, filename
+                )))
+            }
+        };
+        result.map_err(|e| write_error(&e))?;
     }
+    npz.finish().map_err(|e| write_error(&e))?;
+    Ok(())
 }
 
-fn consume_spaces(file: &mut File) -> Result<u8> {
-    loop {
-        let c = getc(file)?;
-        if c != b' ' {
-            return Ok(c);
-        }
-    }
+/// Header of a numpy data file, see
+///     https://numpy.org/devdocs/reference/generated/numpy.lib.format.html
+#[derive(Clone, Debug, PartialEq)]
+struct NpyHeader {
+    dtype: Ty,
+    big_endian: bool,
+    fortran_order: bool,
+    shape: Vec<usize>,
 }
 
-fn getc(file: &mut File) -> Result<u8> {
-    let mut buf: [u8; 1] = [0; 1];
-    file.read(&mut buf)
-        .map_err(|e| Error::Storage(format!("failed to read byte from file: {}", e)))?;
-    let byte = buf[0];
-    Ok(byte)
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+fn header_error(msg: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("malformed numpy header: {}", msg))
 }
 
-// Lexical analysis of the numpy data file to find the dtype
-// description of numpy binary file format here:
-//     https://numpy.org/devdocs/reference/generated/numpy.lib.format.html
-fn extract_descr(file: &mut File) -> Result<Vec<char>> {
-    // First 10 bytes are magic numbers
-    for _ in 0..10 {
-        getc(file)?;
+fn read_header<R: Read>(reader: &mut R) -> Result<NpyHeader> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble).map_err(header_error)?;
+    if &preamble[..6] != NPY_MAGIC {
+        return Err(header_error("missing magic string"));
     }
-    let c = getc(file)?;
-
-    // Found start of dictionary
-    match_char(c, '{')?;
-    let c = consume_spaces(file)?;
-    match_char(c, '\'')?;
-
-    // Find the key "descr". This is the entry for the dtype of the numpy object
-    loop {
-        let mut word: String = String::new();
-        loop {
-            let c = getc(file)?;
-            if c == b'\'' || c == b'"' {
-                break;
-            }
-            word.push(c as char);
-        }
-        if word == "descr" {
-            break;
+    // version 1 uses two bytes for the header length, later versions four
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).map_err(header_error)?;
+            u16::from_le_bytes(len) as usize
         }
-    }
-    match_char(c, '\'')?;
-
-    let c = consume_spaces(file)?;
-
-    // ':' denotes the beginning of the value section for this dict entry
-    match_char(c, ':')?;
-    let c = consume_spaces(file)?;
-    match_char(c, '\'')?;
-
-    // Now we are at the value corresponding to the "descr" key in the
-    // dictionary. Let's now read what the value actually is.
-    let mut descr = Vec::new();
-    loop {
-        let c = getc(file)?;
-        if c == b'\'' {
-            break;
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len).map_err(header_error)?;
+            u32::from_le_bytes(len) as usize
         }
-        descr.push(c as char);
-    }
+        version => return Err(header_error(format!("unsupported version {}", version))),
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header).map_err(header_error)?;
+    let header = String::from_utf8(header).map_err(header_error)?;
+    parse_header(&header)
+}
 
-    if descr.is_empty() {
-        Err(Error::Storage(
-            "could not find \"descr\" in numpy data dictionary".to_string(),
-        ))
-    } else {
-        Ok(descr)
-    }
+/// Parse the Python dictionary literal describing the array.
+fn parse_header(header: &str) -> Result<NpyHeader> {
+    let descr = dict_value(header, "descr")?;
+    let descr = descr.trim_matches(|c| c == '\'' || c == '"');
+    let (dtype, big_endian) = descr_to_dtype(descr)?;
+
+    let fortran_order = match dict_value(header, "fortran_order")? {
+        "True" => true,
+        "False" => false,
+        other => return Err(header_error(format!("invalid fortran_order {}", other))),
+    };
+
+    let shape = dict_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|shape| shape.strip_suffix(')'))
+        .ok_or_else(|| header_error(format!("invalid shape {}", shape)))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| {
+            dim.parse::<usize>()
+                .map_err(|e| header_error(format!("invalid dimension {}: {}", dim, e)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(NpyHeader {
+        dtype,
+        big_endian,
+        fortran_order,
+        shape,
+    })
 }
 
-fn descr_to_dtype(descr: &[char]) -> Result<Ty> {
-    if descr.is_empty() {
-        return Err(Error::Storage(
-            "descr is empty in numpy data dictionary".to_string(),
-        ));
+/// Raw value of `key` in a flat Python dictionary literal.
+fn dict_value<'h>(header: &'h str, key: &str) -> Result<&'h str> {
+    let start = ["'", "\""]
+        .iter()
+        .find_map(|quote| header.find(&format!("{}{}{}", quote, key, quote)))
+        .ok_or_else(|| header_error(format!("could not find \"{}\"", key)))?;
+    let rest = &header[start + key.len() + 2..];
+    let rest = rest
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or_else(|| header_error(format!("missing value of \"{}\"", key)))?
+        .trim_start();
+    // the value ends at the first comma or brace outside of parentheses and quotes
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') | (None, '}') if depth == 0 => return Ok(rest[..i].trim_end()),
+            _ => (),
+        }
     }
+    Err(header_error(format!("unterminated value of \"{}\"", key)))
+}
 
-    // we can ignore byte order marks to get the dtype
-    let skip_byte_order = descr[0] == '<' || descr[0] == '>';
-    let dtype_start = usize::from(skip_byte_order);
+/// Type and byte order given by a numpy dtype descriptor such as `<f8`.
+fn descr_to_dtype(descr: &str) -> Result<(Ty, bool)> {
+    let mut chars = descr.chars();
+    // '|' marks types for which byte order is not applicable, and '=' native order
+    let big_endian = match descr.chars().next() {
+        Some('>') => {
+            chars.next();
+            true
+        }
+        Some('<') | Some('|') => {
+            chars.next();
+            false
+        }
+        Some('=') => {
+            chars.next();
+            cfg!(target_endian = "big")
+        }
+        _ => false,
+    };
 
-    let letter_code = descr
-        .get(dtype_start)
+    let letter_code = chars
+        .next()
         .ok_or_else(|| Error::Storage("missing letter code from numpy file descr".to_string()))?;
-    let number_code = descr.get(dtype_start + 1);
+    let number_code = chars.as_str();
 
     // letter_code:
     //     specifies overall type, e.g., float is f, int is i, uint is u.
     // number_code:
     //     specifies the number of bytes, e.g., 4 means 32 bits, 8 means 64 bits
-    match (letter_code, number_code) {
-        ('f', Some('4')) => Ok(Ty::HostFloat32Tensor),
-        ('f', Some('8')) => Ok(Ty::HostFloat64Tensor),
-        ('d', None) => Ok(Ty::HostFloat64Tensor),
-        ('i', Some('4')) => Ok(Ty::HostInt32Tensor),
-        ('i', Some('8')) => Ok(Ty::HostInt64Tensor),
-        ('u', Some('4')) => Ok(Ty::HostUint32Tensor),
-        ('u', Some('8')) => Ok(Ty::HostUint64Tensor),
+    let dtype = match (letter_code, number_code) {
+        ('b', "1") => Ty::HostBitTensor,
+        ('?', "") => Ty::HostBitTensor,
+        ('f', "4") => Ty::HostFloat32Tensor,
+        ('f', "8") => Ty::HostFloat64Tensor,
+        ('d', "") => Ty::HostFloat64Tensor,
+        ('i', "1") => Ty::HostInt8Tensor,
+        ('i', "2") => Ty::HostInt16Tensor,
+        ('i', "4") => Ty::HostInt32Tensor,
+        ('i', "8") => Ty::HostInt64Tensor,
+        ('u', "1") => Ty::HostUint8Tensor,
+        ('u', "2") => Ty::HostUint16Tensor,
+        ('u', "4") => Ty::HostUint32Tensor,
+        ('u', "8") => Ty::HostUint64Tensor,
         _ => {
-            let number_code_display = match number_code {
-                Some(c) => c.to_string(),
-                None => String::new(),
-            };
-            Err(Error::Storage(format!(
+            return Err(Error::Storage(format!(
                 "unknown numpy descr: {}{}",
-                letter_code, number_code_display
+                letter_code, number_code
             )))
         }
-    }
+    };
+    Ok((dtype, big_endian))
+}
+
+/// Elements that can be decoded from the data section of numpy files.
+trait NpyElement: Sized + Clone {
+    const SIZE: usize;
+    fn from_bytes(bytes: &[u8], big_endian: bool) -> Self;
 }
 
-fn extract_dtype(npy_filename: &str) -> Result<Ty> {
-    let mut file = std::fs::File::open(npy_filename).map_err(|e| {
+macro_rules! npy_element {
+    ($($t:ty),*) => {
+        $(
+        impl NpyElement for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            fn from_bytes(bytes: &[u8], big_endian: bool) -> Self {
+                let bytes = bytes.try_into().unwrap();
+                if big_endian {
+                    <$t>::from_be_bytes(bytes)
+                } else {
+                    <$t>::from_le_bytes(bytes)
+                }
+            }
+        }
+        )*
+    };
+}
+
+npy_element!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// Read the data section following `header`, as an array in standard layout.
+fn read_data<T: NpyElement>(bytes: &[u8], header: &NpyHeader) -> Result<ArrayD<T>> {
+    let len: usize = header.shape.iter().product();
+    let bytes = bytes.get(..len * T::SIZE).ok_or_else(|| {
         Error::Storage(format!(
-            "failed to open numpy data file for reading: {}: {}",
-            npy_filename, e
+            "numpy data is truncated: expected {} bytes but found {}",
+            len * T::SIZE,
+            bytes.len()
         ))
     })?;
-    let descr = extract_descr(&mut file)?;
-    let numpy_dtype = descr_to_dtype(&descr)?;
-    Ok(numpy_dtype)
+    let data: Vec<T> = bytes
+        .chunks_exact(T::SIZE)
+        .map(|chunk| T::from_bytes(chunk, header.big_endian))
+        .collect();
+    let shape = IxDyn(&header.shape);
+    if header.fortran_order {
+        let arr = ArrayD::from_shape_vec(shape.f(), data).map_err(shape_error)?;
+        Ok(arr.as_standard_layout().into_owned())
+    } else {
+        ArrayD::from_shape_vec(shape, data).map_err(shape_error)
+    }
+}
+
+fn shape_error(e: ndarray::ShapeError) -> Error {
+    Error::Storage(format!("numpy data does not match its shape: {}", e))
+}
+
+/// Contents of a numpy data file, mapped into memory rather than read up front.
+///
+/// Pages of the file are only loaded as the data is decoded and may be evicted
+/// again right after, so that reading a file needs memory for the resulting
+/// tensor but not for a copy of the file itself. The file must not be modified
+/// while being read.
+#[cfg(feature = "mmap")]
+fn file_contents(file: &File) -> std::io::Result<memmap2::Mmap> {
+    // safety: numpy files are only read by storage and must not be changed
+    // concurrently by other processes, as documented above
+    let contents = unsafe { memmap2::Mmap::map(file)? };
+    #[cfg(unix)]
+    contents.advise(memmap2::Advice::Sequential)?;
+    Ok(contents)
+}
+
+/// Contents of a numpy data file, read into memory.
+#[cfg(not(feature = "mmap"))]
+fn file_contents(file: &File) -> std::io::Result<Vec<u8>> {
+    let mut file = file;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    Ok(contents)
 }
 
 #[cfg(test)]
@@ -330,6 +626,131 @@ mod tests {
         assert_eq!(data, expected);
     }
 
+    fn npy_bytes(header: &str, data: &[u8]) -> Vec<u8> {
+        // pad the header so that the data is 64-byte aligned
+        let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+        let header = format!("{}{}\n", header, " ".repeat((64 - unpadded % 64) % 64));
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    async fn read_npy_bytes(bytes: &[u8], dtype: Option<Ty>) -> Result<Value> {
+        let mut file = NamedTempFile::new().expect("trying to create tempfile");
+        file.write_all(bytes).unwrap();
+        let filename = file.path().to_str().unwrap().to_string();
+        read_numpy(&filename, &HostPlacement::from("host"), dtype).await
+    }
+
+    #[tokio::test]
+    async fn test_read_numpy_big_endian() {
+        let plc = HostPlacement::from("host");
+        let values = [1.5f64, -2.0, 3.25, 4.0];
+        let data: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+        let bytes = npy_bytes(
+            "{'descr': '>f8', 'fortran_order': False, 'shape': (2, 2), }",
+            &data,
+        );
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[1.5, -2.0], [3.25, 4.0]]);
+        let data = read_npy_bytes(&bytes, None).await.unwrap();
+        assert_eq!(data, Value::from(tensor));
+
+        let values = [7i32, -8];
+        let data: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+        let bytes = npy_bytes(
+            "{'descr': '>i4', 'fortran_order': False, 'shape': (2,), }",
+            &data,
+        );
+        let tensor: HostInt32Tensor = plc.from_raw(array![7, -8]);
+        let data = read_npy_bytes(&bytes, Some(Ty::HostInt32Tensor))
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(tensor));
+    }
+
+    #[tokio::test]
+    async fn test_read_numpy_fortran_order() {
+        let plc = HostPlacement::from("host");
+        // column-major layout of [[1, 2, 3], [4, 5, 6]]
+        let values = [1.0f32, 4.0, 2.0, 5.0, 3.0, 6.0];
+        let data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let bytes = npy_bytes(
+            "{'descr': '<f4', 'fortran_order': True, 'shape': (2, 3), }",
+            &data,
+        );
+        let tensor: HostFloat32Tensor = plc.from_raw(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let data = read_npy_bytes(&bytes, None).await.unwrap();
+        assert_eq!(data, Value::from(tensor));
+
+        // a mismatching type hint is rejected rather than misreading the data
+        assert!(read_npy_bytes(&bytes, Some(Ty::HostFloat64Tensor))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_small_dtypes_roundtrip() {
+        let plc = HostPlacement::from("host");
+        let bits: HostBitTensor = plc.from_raw(array![[1u8, 0, 1], [0, 0, 1]]);
+        let int8: HostInt8Tensor = plc.from_raw(array![-128i8, 0, 127]);
+        let int16: HostInt16Tensor = plc.from_raw(array![[-300i16], [300]]);
+        let uint8: HostUint8Tensor = plc.from_raw(array![0u8, 17, 255]);
+        let uint16: HostUint16Tensor = plc.from_raw(array![65535u16, 2]);
+        let values = vec![
+            Value::from(bits),
+            Value::from(int8),
+            Value::from(int16),
+            Value::from(uint8),
+            Value::from(uint16),
+        ];
+
+        for expected in values {
+            let file = NamedTempFile::new().expect("trying to create tempfile");
+            let filename = file.path().to_str().unwrap().to_string();
+            write_numpy(&filename, &expected).await.unwrap();
+            let data = read_numpy(&filename, &plc, None).await.unwrap();
+            assert_eq!(data, expected);
+        }
+
+        // numpy writes booleans as '|b1' with one byte per element
+        let bytes = npy_bytes(
+            "{'descr': '|b1', 'fortran_order': False, 'shape': (3,), }",
+            &[1, 0, 1],
+        );
+        let expected: HostBitTensor = plc.from_raw(array![1u8, 0, 1]);
+        let data = read_npy_bytes(&bytes, Some(Ty::HostBitTensor))
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(expected));
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let header =
+            parse_header("{'descr': '>u8', 'fortran_order': True, 'shape': (3, 1, 2), }").unwrap();
+        assert_eq!(
+            header,
+            NpyHeader {
+                dtype: Ty::HostUint64Tensor,
+                big_endian: true,
+                fortran_order: true,
+                shape: vec![3, 1, 2],
+            }
+        );
+
+        let header =
+            parse_header("{'descr': '<f8', 'fortran_order': False, 'shape': (), }").unwrap();
+        assert_eq!(header.shape, Vec::<usize>::new());
+        assert!(!header.big_endian);
+
+        assert!(
+            parse_header("{'descr': '<c16', 'fortran_order': False, 'shape': (2,), }").is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_write_numpy() {
         let plc = HostPlacement::from("host");
@@ -351,4 +772,77 @@ mod tests {
         let data = read_numpy(&filename, &plc, None).await.unwrap();
         assert_eq!(data, expected);
     }
+
+    #[tokio::test]
+    async fn test_npz_roundtrip() {
+        let plc = HostPlacement::from("host");
+        let features: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0], [6.0, 7.0]]);
+        let labels: HostInt64Tensor = plc.from_raw(array![0, 1]);
+        let values: HashMap<String, Value> = vec![
+            ("features".to_string(), Value::from(features)),
+            ("labels".to_string(), Value::from(labels)),
+        ]
+        .into_iter()
+        .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dataset.npz");
+        let filename = path.to_str().unwrap();
+        write_npz(filename, &values).await.unwrap();
+
+        let data = read_npz(filename, &plc, None).await.unwrap();
+        assert_eq!(data, values);
+        // a type hint applies to all arrays
+        assert!(read_npz(filename, &plc, Some(Ty::HostFloat64Tensor))
+            .await
+            .is_err());
+    }
+
+    /// Array spanning many pages, as read through a memory map with the `mmap` feature.
+    fn large_tensor(plc: &HostPlacement) -> HostFloat64Tensor {
+        let values = ArrayD::from_shape_fn(IxDyn(&[1024, 1024]), |idx| {
+            (idx[0] * 1024 + idx[1]) as f64 / 7.0
+        });
+        plc.from_raw(values)
+    }
+
+    #[tokio::test]
+    async fn test_read_large_numpy() {
+        let plc = HostPlacement::from("host");
+        let expected = Value::from(large_tensor(&plc));
+        let file = NamedTempFile::new().expect("trying to create tempfile");
+        let filename = file.path().to_str().unwrap().to_string();
+        write_numpy(&filename, &expected).await.unwrap();
+        assert!(file.as_file().metadata().unwrap().len() > 8 * 1024 * 1024);
+
+        let data = read_numpy(&filename, &plc, None).await.unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[tokio::test]
+    async fn test_read_truncated_numpy() {
+        let plc = HostPlacement::from("host");
+        let file = NamedTempFile::new().expect("trying to create tempfile");
+        let filename = file.path().to_str().unwrap().to_string();
+        write_numpy(&filename, &Value::from(large_tensor(&plc)))
+            .await
+            .unwrap();
+        let len = file.as_file().metadata().unwrap().len();
+
+        // cut off within the data, within the header, and entirely
+        file.as_file().set_len(len - 8).unwrap();
+        let err = read_numpy(&filename, &plc, None).await.unwrap_err();
+        assert!(
+            err.to_string().contains("numpy data is truncated"),
+            "{}",
+            err
+        );
+
+        file.as_file().set_len(20).unwrap();
+        let err = read_numpy(&filename, &plc, None).await.unwrap_err();
+        assert!(err.to_string().contains("parsing failure"), "{}", err);
+
+        file.as_file().set_len(0).unwrap();
+        assert!(read_numpy(&filename, &plc, None).await.is_err());
+    }
 }
@@ -0,0 +1,525 @@
+//! Tensors read from and written to safetensors files.
+//!
+//! Safetensors is the format in which model weights are commonly exported from
+//! PyTorch and the Hugging Face hub. All tensors of a file can be read at once
+//! into named host tensors, for instance to serve as storage for local execution:
+//!
+//! ```no_run
+//! # use moose::prelude::*;
+//! # use moose::storage::filesystem::safetensors::read_named_tensors;
+//! # use moose::storage::local::LocalAsyncStorage;
+//! let plc = HostPlacement::from("alice");
+//! let weights = read_named_tensors("model.safetensors", &plc).unwrap();
+//! let storage = LocalAsyncStorage::from_hashmap(weights);
+//! ```
+//!
+//! Half-precision tensors (`F16` and `BF16`) are read as `Float32` tensors since
+//! there are no half-precision host tensors; all other supported dtypes are read
+//! as tensors of the same type.
+
+use crate::prelude::*;
+use crate::{Error, Result};
+use ndarray::{ArrayD, IxDyn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryInto;
+use std::io::Read;
+use std::path::Path;
+
+// Layout of safetensors files, as described here:
+//     https://github.com/huggingface/safetensors#format
+// An 8 byte little-endian header size is followed by a JSON header mapping each
+// tensor name to its dtype, shape, and byte range in the data buffer that follows.
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: (usize, usize),
+}
+
+/// Key of the header entry holding free-form metadata rather than a tensor.
+const METADATA_KEY: &str = "__metadata__";
+
+fn decode<T: LeBytes>(bytes: &[u8], shape: &[usize]) -> Result<ArrayD<T>> {
+    let values: Vec<T> = bytes
+        .chunks_exact(std::mem::size_of::<T>())
+        .map(T::from_le_slice)
+        .collect();
+    ArrayD::from_shape_vec(IxDyn(shape), values).map_err(|e| {
+        Error::Storage(format!(
+            "safetensors data does not match its shape {:?}: {}",
+            shape, e
+        ))
+    })
+}
+
+/// Value of a half-precision float, as found in `F16` tensors.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let fraction = (bits & 0x3ff) as f32;
+    match exponent {
+        // subnormal numbers and zero
+        0 => sign * fraction * 2f32.powi(-24),
+        0x1f if fraction == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + fraction / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// Value of a brain float, as found in `BF16` tensors.
+fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+/// Type of the tensors read from entries with the given dtype.
+fn dtype_to_ty(dtype: &str) -> Option<Ty> {
+    let ty = match dtype {
+        "F16" | "BF16" | "F32" => Ty::HostFloat32Tensor,
+        "F64" => Ty::HostFloat64Tensor,
+        "I8" => Ty::HostInt8Tensor,
+        "I16" => Ty::HostInt16Tensor,
+        "I32" => Ty::HostInt32Tensor,
+        "I64" => Ty::HostInt64Tensor,
+        "U8" => Ty::HostUint8Tensor,
+        "U16" => Ty::HostUint16Tensor,
+        "U32" => Ty::HostUint32Tensor,
+        "U64" => Ty::HostUint64Tensor,
+        "BOOL" => Ty::HostBitTensor,
+        _ => return None,
+    };
+    Some(ty)
+}
+
+/// Tensor `name` described by `info`, with its data taken from the data buffer of the file.
+fn decode_tensor(
+    filename: &str,
+    name: &str,
+    info: &TensorInfo,
+    data: &[u8],
+    placement: &HostPlacement,
+) -> Result<Value> {
+    let (begin, end) = info.data_offsets;
+    let bytes = data.get(begin..end).ok_or_else(|| {
+        Error::Storage(format!(
+            "tensor '{}' lies outside of the data in safetensors file: {}",
+            name, filename
+        ))
+    })?;
+    let shape = info.shape.as_slice();
+
+    macro_rules! tensor {
+        ($t:ty, $tensor:ty) => {{
+            let tensor: $tensor = placement.from_raw(decode::<$t>(bytes, shape)?);
+            Value::from(tensor)
+        }};
+    }
+
+    let value = match info.dtype.as_str() {
+        "F16" => {
+            let tensor: HostFloat32Tensor =
+                placement.from_raw(decode::<u16>(bytes, shape)?.mapv(f16_to_f32));
+            Value::from(tensor)
+        }
+        "BF16" => {
+            let tensor: HostFloat32Tensor =
+                placement.from_raw(decode::<u16>(bytes, shape)?.mapv(bf16_to_f32));
+            Value::from(tensor)
+        }
+        "F32" => tensor!(f32, HostFloat32Tensor),
+        "F64" => tensor!(f64, HostFloat64Tensor),
+        "I8" => tensor!(i8, HostInt8Tensor),
+        "I16" => tensor!(i16, HostInt16Tensor),
+        "I32" => tensor!(i32, HostInt32Tensor),
+        "I64" => tensor!(i64, HostInt64Tensor),
+        "U8" => tensor!(u8, HostUint8Tensor),
+        "U16" => tensor!(u16, HostUint16Tensor),
+        "U32" => tensor!(u32, HostUint32Tensor),
+        "U64" => tensor!(u64, HostUint64Tensor),
+        "BOOL" => {
+            let tensor: HostBitTensor =
+                placement.from_raw(decode::<u8>(bytes, shape)?.mapv(|b| (b != 0) as u8));
+            Value::from(tensor)
+        }
+        other => {
+            return Err(Error::Storage(format!(
+                "unsupported dtype {} of tensor '{}' in safetensors file: {}",
+                other, name, filename
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Header and data buffer of the safetensors file held by `bytes`.
+fn parse_file<'b>(
+    filename: &str,
+    bytes: &'b [u8],
+) -> Result<(BTreeMap<String, TensorInfo>, &'b [u8])> {
+    parse_header(bytes).map_err(|e| {
+        Error::Storage(format!(
+            "failed to parse safetensors file: {}: {}",
+            filename, e
+        ))
+    })
+}
+
+#[allow(dead_code)]
+pub(crate) async fn read_safetensors(
+    filename: &str,
+    tensor_name: Option<&str>,
+    placement: &HostPlacement,
+    dtype: Option<Ty>,
+) -> Result<Value> {
+    let bytes = tokio::fs::read(filename).await.map_err(|e| {
+        Error::Storage(format!(
+            "failed to read safetensors file: {}: {}",
+            filename, e
+        ))
+    })?;
+    let (mut header, data) = parse_file(filename, &bytes)?;
+
+    let (name, info) = match tensor_name {
+        Some(name) => header.remove_entry(name).ok_or_else(|| {
+            Error::Storage(format!(
+                "no tensor named '{}' in safetensors file: {}",
+                name, filename
+            ))
+        })?,
+        None if header.len() == 1 => header.into_iter().next().unwrap(),
+        None => {
+            return Err(Error::Storage(format!(
+                "safetensors file holds {} tensors, a tensor name must be given in the query: {}",
+                header.len(),
+                filename
+            )))
+        }
+    };
+
+    let value = decode_tensor(filename, &name, &info, data, placement)?;
+    match dtype {
+        Some(dtype) if dtype != value.ty() => Err(Error::Storage(format!(
+            "tensor '{}' in safetensors file {} is a {} but a {} was expected",
+            name,
+            filename,
+            value.ty(),
+            dtype
+        ))),
+        _ => Ok(value),
+    }
+}
+
+/// Type and shape of the only tensor of the safetensors file at `filename`, read
+/// from its header only; `None` if the file holds several tensors or one of an
+/// unsupported dtype.
+pub(crate) fn read_safetensors_header(filename: &str) -> Result<Option<(Ty, Vec<usize>)>> {
+    let read_error = |e: std::io::Error| {
+        Error::Storage(format!(
+            "failed to read safetensors file: {}: {}",
+            filename, e
+        ))
+    };
+    let mut file = std::fs::File::open(filename).map_err(read_error)?;
+    let mut size = [0u8; 8];
+    file.read_exact(&mut size).map_err(read_error)?;
+    let mut bytes = size.to_vec();
+    file.take(u64::from_le_bytes(size))
+        .read_to_end(&mut bytes)
+        .map_err(read_error)?;
+    let (header, _) = parse_file(filename, &bytes)?;
+    if header.len() != 1 {
+        return Ok(None);
+    }
+    let info = header.into_values().next().unwrap();
+    Ok(dtype_to_ty(&info.dtype).map(|ty| (ty, info.shape)))
+}
+
+/// All tensors of the safetensors file at `path`, keyed by name.
+pub fn read_named_tensors<P: AsRef<Path>>(
+    path: P,
+    placement: &HostPlacement,
+) -> Result<HashMap<String, Value>> {
+    let path = path.as_ref();
+    let filename = path.display().to_string();
+    let bytes = std::fs::read(path).map_err(|e| {
+        Error::Storage(format!(
+            "failed to read safetensors file: {}: {}",
+            filename, e
+        ))
+    })?;
+    let (header, data) = parse_file(&filename, &bytes)?;
+    header
+        .iter()
+        .map(|(name, info)| {
+            let value = decode_tensor(&filename, name, info, data, placement)?;
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Write `data` as the only tensor of a safetensors file, named `tensor_name`.
+#[allow(dead_code)]
+pub(crate) async fn write_safetensors(
+    filename: &str,
+    tensor_name: &str,
+    data: &Value,
+) -> Result<()> {
+    let contents = serialize(filename, &[(tensor_name, data)])?;
+    tokio::fs::write(filename, contents).await.map_err(|e| {
+        Error::Storage(format!(
+            "failed to write moose value to file: '{}': {}",
+            filename, e
+        ))
+    })
+}
+
+/// Write the named `tensors` to a safetensors file at `path`, in the given order.
+pub fn write_named_tensors<P: AsRef<Path>>(path: P, tensors: &[(String, Value)]) -> Result<()> {
+    let path = path.as_ref();
+    let filename = path.display().to_string();
+    let tensors: Vec<(&str, &Value)> = tensors
+        .iter()
+        .map(|(name, value)| (name.as_str(), value))
+        .collect();
+    let contents = serialize(&filename, &tensors)?;
+    std::fs::write(path, contents).map_err(|e| {
+        Error::Storage(format!(
+            "failed to write moose value to file: '{}': {}",
+            filename, e
+        ))
+    })
+}
+
+/// Dtype, shape, and little-endian data of `data`.
+fn encode_tensor(filename: &str, data: &Value) -> Result<(&'static str, Vec<usize>, Vec<u8>)> {
+    let encoded = match data {
+        Value::HostFloat32Tensor(t) => ("F32", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostFloat64Tensor(t) => ("F64", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostInt8Tensor(t) => ("I8", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostInt16Tensor(t) => ("I16", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostInt32Tensor(t) => ("I32", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostInt64Tensor(t) => ("I64", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostUint8Tensor(t) => ("U8", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostUint16Tensor(t) => ("U16", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostUint32Tensor(t) => ("U32", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostUint64Tensor(t) => ("U64", t.0.shape().to_vec(), encode(t.0.iter())),
+        Value::HostBitTensor(t) => {
+            let bits =
+                t.0.into_array::<u8>()
+                    .map_err(|e| Error::Storage(format!("failed to read bit tensor: {}", e)))?;
+            ("BOOL", bits.shape().to_vec(), encode(bits.iter()))
+        }
+        _ => {
+            return Err(Error::Storage(format!(
+                "cannot write unsupported tensor to safetensors file: {}",
+                filename
+            )))
+        }
+    };
+    Ok(encoded)
+}
+
+/// Contents of a safetensors file holding the named `tensors`.
+fn serialize(filename: &str, tensors: &[(&str, &Value)]) -> Result<Vec<u8>> {
+    let mut header = BTreeMap::new();
+    let mut data = Vec::new();
+    for (name, value) in tensors {
+        let (dtype, shape, bytes) = encode_tensor(filename, value)?;
+        let begin = data.len();
+        data.extend_from_slice(&bytes);
+        let info = TensorInfo {
+            dtype: dtype.to_string(),
+            shape,
+            data_offsets: (begin, data.len()),
+        };
+        if header.insert(name.to_string(), info).is_some() {
+            return Err(Error::Storage(format!(
+                "tensor '{}' given more than once for safetensors file: {}",
+                name, filename
+            )));
+        }
+    }
+    let mut header = serde_json::to_vec(&header)
+        .map_err(|e| Error::Storage(format!("failed to serialize safetensors header: {}", e)))?;
+    // pad the header such that the data is aligned
+    while header.len() % 8 != 0 {
+        header.push(b' ');
+    }
+
+    let mut contents = Vec::with_capacity(8 + header.len() + data.len());
+    contents.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    contents.extend_from_slice(&header);
+    contents.extend_from_slice(&data);
+    Ok(contents)
+}
+
+trait LeBytes: Sized {
+    fn le_bytes(&self) -> Vec<u8>;
+
+    /// Value held by `bytes`, which must be exactly as many as the size of `Self`.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! le_bytes {
+    ($($ty:ty),+) => {
+        $(
+        impl LeBytes for $ty {
+            fn le_bytes(&self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+
+            fn from_le_slice(bytes: &[u8]) -> Self {
+                <$ty>::from_le_bytes(bytes.try_into().unwrap())
+            }
+        }
+        )+
+    };
+}
+
+le_bytes!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64);
+
+fn encode<'a, T: LeBytes + 'a, I: Iterator<Item = &'a T>>(values: I) -> Vec<u8> {
+    values.flat_map(|x| x.le_bytes()).collect()
+}
+
+/// Split the contents of a safetensors file into the tensors it holds and the data buffer.
+fn parse_header(
+    bytes: &[u8],
+) -> std::result::Result<(BTreeMap<String, TensorInfo>, &[u8]), String> {
+    let size = bytes
+        .get(..8)
+        .ok_or_else(|| "file is too short to hold a header".to_string())?;
+    let size = u64::from_le_bytes(size.try_into().unwrap()) as usize;
+    let header = bytes
+        .get(8..8usize.saturating_add(size))
+        .ok_or_else(|| format!("header of {} bytes exceeds the file", size))?;
+    let mut entries: BTreeMap<String, serde_json::Value> =
+        serde_json::from_slice(header).map_err(|e| format!("invalid header: {}", e))?;
+    entries.remove(METADATA_KEY);
+    let tensors = entries
+        .into_iter()
+        .map(|(name, entry)| {
+            let info = serde_json::from_value(entry)
+                .map_err(|e| format!("invalid header entry for tensor '{}': {}", name, e))?;
+            Ok((name, info))
+        })
+        .collect::<std::result::Result<_, String>>()?;
+    Ok((tensors, &bytes[8 + size..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test]
+    async fn test_read_safetensors() {
+        // as written by the Python safetensors package
+        let header = br#"{"__metadata__":{"format":"pt"},"bias":{"dtype":"F32","shape":[2],"data_offsets":[0,8]},"weight":{"dtype":"F64","shape":[2,2],"data_offsets":[8,40]}}"#;
+        let mut contents = (header.len() as u64).to_le_bytes().to_vec();
+        contents.extend_from_slice(header);
+        for x in [0.5f32, -1.0].iter() {
+            contents.extend_from_slice(&x.to_le_bytes());
+        }
+        for x in [1.0f64, 2.0, 3.0, 4.0].iter() {
+            contents.extend_from_slice(&x.to_le_bytes());
+        }
+        let file = NamedTempFile::new().expect("trying to create tempfile");
+        let filename = file
+            .path()
+            .to_str()
+            .expect("trying to get path from temp file")
+            .to_string();
+        std::fs::write(&filename, contents).unwrap();
+
+        let plc = HostPlacement::from("host");
+        let weight: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let data = read_safetensors(&filename, Some("weight"), &plc, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(weight));
+
+        let bias: HostFloat32Tensor = plc.from_raw(array![0.5f32, -1.0]);
+        let data = read_safetensors(&filename, Some("bias"), &plc, None)
+            .await
+            .unwrap();
+        assert_eq!(data, Value::from(bias));
+
+        // the tensor to read is ambiguous without a name
+        assert!(read_safetensors(&filename, None, &plc, None).await.is_err());
+        assert!(
+            read_safetensors(&filename, Some("bias"), &plc, Some(Ty::HostFloat64Tensor))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_safetensors() {
+        let plc = HostPlacement::from("host");
+        let tensor: HostInt64Tensor = plc.from_raw(array![[1, -2, 3], [4, 5, -6]]);
+        let expected = Value::from(tensor);
+
+        let file = NamedTempFile::new().expect("trying to create tempfile");
+        let filename = file
+            .path()
+            .to_str()
+            .expect("trying to get path from temp file")
+            .to_string();
+
+        write_safetensors(&filename, "x", &expected).await.unwrap();
+
+        let data = read_safetensors(&filename, None, &plc, None).await.unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_named_tensors_roundtrip() {
+        let plc = HostPlacement::from("host");
+        let weight: HostFloat32Tensor = plc.from_raw(array![[0.5f32, -1.5], [2.0, 0.25]]);
+        let quantized: HostInt8Tensor = plc.from_raw(array![-128i8, 0, 127]);
+        let mask: HostBitTensor = plc.from_raw(array![1u8, 0, 1]);
+        let tensors = vec![
+            ("layer.weight".to_string(), Value::from(weight)),
+            ("layer.quantized".to_string(), Value::from(quantized)),
+            ("layer.mask".to_string(), Value::from(mask)),
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.safetensors");
+        write_named_tensors(&path, &tensors).unwrap();
+
+        let data = read_named_tensors(&path, &plc).unwrap();
+        assert_eq!(data.len(), 3);
+        for (name, value) in tensors.iter() {
+            assert_eq!(&data[name], value);
+        }
+
+        let duplicated = vec![tensors[0].clone(), tensors[0].clone()];
+        assert!(write_named_tensors(&path, &duplicated).is_err());
+    }
+
+    #[test]
+    fn test_read_half_precision() {
+        // 1.0, -2.0, 0.5, and 2^-24 as F16 and 1.0, -2.0, 0.5, and 3.0 as BF16
+        let half: [u16; 4] = [0x3c00, 0xc000, 0x3800, 0x0001];
+        let brain: [u16; 4] = [0x3f80, 0xc000, 0x3f00, 0x4040];
+        let header = br#"{"h":{"dtype":"F16","shape":[4],"data_offsets":[0,8]},"b":{"dtype":"BF16","shape":[2,2],"data_offsets":[8,16]}}"#;
+        let mut contents = (header.len() as u64).to_le_bytes().to_vec();
+        contents.extend_from_slice(header);
+        for x in half.iter().chain(brain.iter()) {
+            contents.extend_from_slice(&x.to_le_bytes());
+        }
+        let file = NamedTempFile::new().expect("trying to create tempfile");
+        std::fs::write(file.path(), contents).unwrap();
+
+        let plc = HostPlacement::from("host");
+        let data = read_named_tensors(file.path(), &plc).unwrap();
+        let h: HostFloat32Tensor = plc.from_raw(array![1.0f32, -2.0, 0.5, 2f32.powi(-24)]);
+        let b: HostFloat32Tensor = plc.from_raw(array![[1.0f32, -2.0], [0.5, 3.0]]);
+        assert_eq!(data["h"], Value::from(h));
+        assert_eq!(data["b"], Value::from(b));
+    }
+}
@@ -0,0 +1,204 @@
+//! Storage backed by an Arrow Flight service.
+//!
+//! Keys starting with `flight:` are served by the Flight service: loading a value
+//! fetches the table returned for the rest of the key as ticket, and saving a value
+//! uploads it as a table under the rest of the key as descriptor path. All other
+//! keys are passed on to a fallback storage, such that computations can mix values
+//! held by data services with local files.
+//!
+//! Tables are converted to and from two-dimensional `Float64` tensors with a row
+//! per record, in the same way as CSV files; a query of the form
+//! `{"select_columns": [...]}` selects the columns to load.
+
+use crate::error::Error;
+use crate::execution::AsyncStorageImpl;
+use crate::host::arrow::{matrix_to_record_batch, record_batches_to_matrix};
+use crate::prelude::*;
+use crate::storage::filesystem::parse_columns;
+use crate::storage::{stream_rows, AsyncStorage, ValueStat, ValueStream};
+use crate::Result;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::{FlightClient, FlightDescriptor, Ticket};
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use tonic::transport::{Channel, Endpoint};
+
+/// Prefix of the keys of values held by the Flight service.
+pub const FLIGHT_PREFIX: &str = "flight:";
+
+pub struct AsyncFlightStorage {
+    channel: Channel,
+    fallback: AsyncStorageImpl,
+}
+
+impl AsyncFlightStorage {
+    /// Serve keys starting with `flight:` from the Flight service at `endpoint`
+    /// and all others from `fallback`.
+    ///
+    /// Connections are only established once values are loaded or saved.
+    pub fn new(endpoint: &str, fallback: AsyncStorageImpl) -> Result<Self> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| Error::Storage(format!("invalid Flight endpoint '{}': {}", endpoint, e)))?
+            .connect_lazy();
+        Ok(AsyncFlightStorage { channel, fallback })
+    }
+
+    fn client(&self) -> FlightClient {
+        FlightClient::new(self.channel.clone())
+    }
+}
+
+fn flight_error(key: &str, e: FlightError) -> Error {
+    Error::Storage(format!("Flight request for '{}' failed: {}", key, e))
+}
+
+#[async_trait]
+impl AsyncStorage for AsyncFlightStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        let path = match key.strip_prefix(FLIGHT_PREFIX) {
+            Some(path) => path,
+            None => return self.fallback.save(key, session_id, val).await,
+        };
+        tracing::debug!(key, ty = %val.ty(), "Uploading value");
+        let batch = to_record_batch(key, val)?;
+        let data: Vec<_> = FlightDataEncoderBuilder::new()
+            .with_flight_descriptor(Some(FlightDescriptor::new_path(vec![path.to_string()])))
+            .build(futures::stream::iter(vec![Ok(batch)]))
+            .try_collect()
+            .await
+            .map_err(|e| flight_error(key, e))?;
+        let mut results = self
+            .client()
+            .do_put(futures::stream::iter(data))
+            .await
+            .map_err(|e| flight_error(key, e))?;
+        // the upload is only complete once the service has acknowledged it
+        while let Some(result) = results.next().await {
+            result.map_err(|e| flight_error(key, e))?;
+        }
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        let ticket = match key.strip_prefix(FLIGHT_PREFIX) {
+            Some(ticket) => ticket,
+            None => return self.fallback.load(key, session_id, type_hint, query).await,
+        };
+        tracing::debug!(key, query, "Fetching value");
+        let columns = parse_columns(query)?;
+        let batches: Vec<RecordBatch> = self
+            .client()
+            .do_get(Ticket::new(ticket.to_string()))
+            .await
+            .map_err(|e| flight_error(key, e))?
+            .try_collect()
+            .await
+            .map_err(|e| flight_error(key, e))?;
+        let plc = HostPlacement::from("host");
+        from_record_batches(key, &batches, &columns, &plc)
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        if !key.starts_with(FLIGHT_PREFIX) {
+            return self
+                .fallback
+                .load_chunks(key, session_id, type_hint, query, chunk_rows)
+                .await;
+        }
+        // values held by the Flight service are fetched as a whole
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // values held by the Flight service cannot be listed
+        self.fallback.list(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        if key.starts_with(FLIGHT_PREFIX) {
+            return Err(Error::Storage(format!(
+                "values held by the Flight service cannot be described: {}",
+                key
+            )));
+        }
+        self.fallback.stat(key).await
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        // keys of values held by the Flight service need not have an extension
+        None
+    }
+
+    async fn check(&self) -> Result<()> {
+        self.fallback.check().await
+    }
+}
+
+/// Table with a column `col_<i>` for each column of a one- or two-dimensional tensor.
+fn to_record_batch(key: &str, val: &Value) -> Result<RecordBatch> {
+    matrix_to_record_batch(val)
+        .map_err(|e| Error::Storage(format!("cannot upload value as table for '{}': {}", key, e)))
+}
+
+/// Two-dimensional `Float64` tensor with a row per record of `batches`.
+fn from_record_batches(
+    key: &str,
+    batches: &[RecordBatch],
+    columns: &[String],
+    plc: &HostPlacement,
+) -> Result<Value> {
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => {
+            return Err(Error::Storage(format!(
+                "Flight service returned no records for '{}'",
+                key
+            )))
+        }
+    };
+    record_batches_to_matrix(&schema, batches, columns, plc)
+        .map_err(|e| Error::Storage(format!("cannot load table for '{}': {}", key, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let plc = HostPlacement::from("host");
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[2.3, 4.0, 5.0], [6.0, 7.0, 12.0]]);
+        let expected = Value::from(tensor);
+
+        let batch = to_record_batch("flight:data", &expected).unwrap();
+        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(batch.num_rows(), 2);
+
+        // records may arrive split over several batches
+        let batches = vec![batch.slice(0, 1), batch.slice(1, 1)];
+        let data = from_record_batches("flight:data", &batches, &[], &plc).unwrap();
+        assert_eq!(data, expected);
+
+        let columns = vec!["col_2".to_string(), "col_0".to_string()];
+        let selected = from_record_batches("flight:data", &batches, &columns, &plc).unwrap();
+        let tensor: HostFloat64Tensor = plc.from_raw(array![[5.0, 2.3], [12.0, 6.0]]);
+        assert_eq!(selected, Value::from(tensor));
+    }
+}
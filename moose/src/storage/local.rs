@@ -50,6 +50,25 @@ impl SyncStorage for LocalSyncStorage {
         check_types(&item, &type_hint)?;
         Ok(item)
     }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let store = self.store.read().map_err(|e| {
+            tracing::error!("failed to get read lock: {:?}", e);
+            Error::Unexpected(None)
+        })?;
+        Ok(matching_keys(store.keys(), prefix))
+    }
+
+    fn stat(&self, key: &str) -> Result<ValueStat> {
+        let store = self.store.read().map_err(|e| {
+            tracing::error!("failed to get read lock: {:?}", e);
+            Error::Unexpected(None)
+        })?;
+        let item = store
+            .get(key)
+            .ok_or_else(|| Error::Storage("key not found in store".into()))?;
+        ValueStat::of_value(item)
+    }
 }
 
 #[derive(Default)]
@@ -68,7 +87,7 @@ impl LocalAsyncStorage {
 #[async_trait]
 impl AsyncStorage for LocalAsyncStorage {
     async fn save(&self, key: &str, _session_id: &SessionId, val: &Value) -> Result<()> {
-        tracing::debug!("Async storage saving; key:'{}'", key);
+        tracing::debug!(key, "Saving value");
         let mut store = self.store.write().await;
         store.insert(key.to_string(), val.clone());
         Ok(())
@@ -81,7 +100,7 @@ impl AsyncStorage for LocalAsyncStorage {
         type_hint: Option<Ty>,
         query: &str,
     ) -> Result<Value> {
-        tracing::debug!("Async storage loading; key:'{}'", key);
+        tracing::debug!(key, query, "Loading value");
         match query {
             "" => Ok(()),
             _ => Err(Error::Storage(
@@ -96,6 +115,29 @@ impl AsyncStorage for LocalAsyncStorage {
         check_types(&item, &type_hint)?;
         Ok(item)
     }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let store = self.store.read().await;
+        Ok(matching_keys(store.keys(), prefix))
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        let store = self.store.read().await;
+        let item = store
+            .get(key)
+            .ok_or_else(|| Error::Storage("key not found in store".into()))?;
+        ValueStat::of_value(item)
+    }
+}
+
+/// Keys starting with `prefix`, in order.
+fn matching_keys<'k, I: Iterator<Item = &'k String>>(keys: I, prefix: &str) -> Vec<String> {
+    let mut keys: Vec<String> = keys
+        .filter(|key| key.starts_with(prefix))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys
 }
 
 fn check_types(item: &Value, type_hint: &Option<Ty>) -> Result<()> {
@@ -114,3 +156,40 @@ fn check_types(item: &Value, type_hint: &Option<Ty>) -> Result<()> {
         None => Ok(()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use ndarray::array;
+    use std::convert::TryFrom;
+
+    #[tokio::test]
+    async fn test_list_and_stat_local_storage() {
+        let plc = HostPlacement::from("host");
+        let x: HostFloat64Tensor = plc.from_raw(array![[1.0, 2.0], [3.0, 4.0]]);
+        let x = Value::from(x);
+        let session_id = SessionId::try_from("session").unwrap();
+
+        let storage = LocalAsyncStorage::default();
+        for key in ["weights/b", "weights/a", "bias"].iter() {
+            storage.save(key, &session_id, &x).await.unwrap();
+        }
+        assert_eq!(
+            storage.list("weights/").await.unwrap(),
+            vec!["weights/a".to_string(), "weights/b".to_string()]
+        );
+        assert_eq!(storage.list("").await.unwrap().len(), 3);
+
+        let stat = storage.stat("bias").await.unwrap();
+        assert_eq!(stat.ty, Some(Ty::HostFloat64Tensor));
+        assert_eq!(stat.shape, Some(vec![2, 2]));
+        assert_eq!(stat.bytes, bincode::serialized_size(&x).unwrap());
+        assert!(storage.stat("missing").await.is_err());
+
+        let storage = LocalSyncStorage::default();
+        storage.save("bias", &session_id, &x).unwrap();
+        assert_eq!(storage.list("b").unwrap(), vec!["bias".to_string()]);
+        assert_eq!(storage.stat("bias").unwrap().shape, Some(vec![2, 2]));
+    }
+}
@@ -2,10 +2,30 @@
 
 use crate::computation::*;
 use crate::error::{Error, Result};
+use crate::host::{FromRaw, HostRingTensor, HostTensor};
+use crate::types::HostBitTensor;
 use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use ndarray::Axis;
+use std::time::SystemTime;
 
+#[cfg(feature = "async_execute")]
+pub mod acl;
+#[cfg(feature = "encrypted-storage")]
+pub mod encrypted;
+#[cfg(feature = "async_execute")]
 pub mod filesystem;
+#[cfg(feature = "flight")]
+pub mod flight;
 pub mod local;
+#[cfg(feature = "object-store")]
+pub mod object_store;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "redis")]
+pub mod redis;
 
 pub trait SyncStorage {
     fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()>;
@@ -17,6 +37,48 @@ pub trait SyncStorage {
         type_hint: Option<Ty>,
         query: &str,
     ) -> Result<Value>;
+
+    /// Keys of the stored values starting with `prefix`, in order.
+    fn list(&self, _prefix: &str) -> Result<Vec<String>> {
+        Err(Error::Storage(
+            "listing keys is not supported by this storage".to_string(),
+        ))
+    }
+
+    /// Description of the value stored under `key`.
+    fn stat(&self, key: &str) -> Result<ValueStat> {
+        Err(Error::Storage(format!(
+            "describing values is not supported by this storage: {}",
+            key
+        )))
+    }
+}
+
+/// Description of a stored value, as given by `stat`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValueStat {
+    /// Type of the value, if known without loading it.
+    pub ty: Option<Ty>,
+    /// Shape of the value, if it is a tensor whose shape is known without loading it.
+    pub shape: Option<Vec<usize>>,
+    /// Number of bytes taken by the value as stored.
+    pub bytes: u64,
+    /// Last time the value was saved, if known.
+    pub modified: Option<SystemTime>,
+}
+
+impl ValueStat {
+    /// Description of a value held in memory.
+    pub fn of_value(value: &Value) -> Result<Self> {
+        let bytes = bincode::serialized_size(value)
+            .map_err(|e| Error::Storage(format!("failed to compute size of value: {}", e)))?;
+        Ok(ValueStat {
+            ty: Some(value.ty()),
+            shape: host_shape(value),
+            bytes,
+            modified: None,
+        })
+    }
 }
 
 #[async_trait]
@@ -30,4 +92,138 @@ pub trait AsyncStorage {
         type_hint: Option<Ty>,
         query: &str,
     ) -> Result<Value>;
+
+    /// Load the value under `key` as a stream of chunks of at most `chunk_rows`
+    /// rows each, that is of slices along its first axis, such that computing on
+    /// the first rows of a large value can start while the rest is still loading.
+    ///
+    /// By default the value is loaded as a whole and then split up.
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    /// Keys of the stored values starting with `prefix`, in order.
+    async fn list(&self, _prefix: &str) -> Result<Vec<String>> {
+        Err(Error::Storage(
+            "listing keys is not supported by this storage".to_string(),
+        ))
+    }
+
+    /// Description of the value stored under `key`, without loading it where possible.
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        Err(Error::Storage(format!(
+            "describing values is not supported by this storage: {}",
+            key
+        )))
+    }
+
+    /// Extensions of the keys that values can be saved and loaded under, or `None` if any.
+    fn formats(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Check that the backing store can be reached, as reported by worker health checks.
+    async fn check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Chunks of a value loaded by `AsyncStorage::load_chunks`, in order.
+pub type ValueStream = BoxStream<'static, Result<Value>>;
+
+/// Stream of the chunks of `value` given by `split_rows`.
+pub fn stream_rows(value: &Value, chunk_rows: usize) -> Result<ValueStream> {
+    let chunks = split_rows(value, chunk_rows)?;
+    Ok(futures::stream::iter(chunks.into_iter().map(Ok)).boxed())
+}
+
+/// Slices of at most `chunk_rows` rows of a host tensor along its first axis, in order.
+///
+/// Scalars are returned as a single chunk.
+pub fn split_rows(value: &Value, chunk_rows: usize) -> Result<Vec<Value>> {
+    if chunk_rows == 0 {
+        return Err(Error::Storage(
+            "chunks must hold at least one row".to_string(),
+        ));
+    }
+
+    macro_rules! split {
+        ($t:expr, $wrapper:ident) => {
+            if $t.0.ndim() == 0 {
+                vec![value.clone()]
+            } else {
+                $t.0.axis_chunks_iter(Axis(0), chunk_rows)
+                    .map(|chunk| Value::from($wrapper(chunk.to_shared(), $t.1.clone())))
+                    .collect()
+            }
+        };
+    }
+
+    let chunks = match value {
+        Value::HostFloat32Tensor(t) => split!(t, HostTensor),
+        Value::HostFloat64Tensor(t) => split!(t, HostTensor),
+        Value::HostInt8Tensor(t) => split!(t, HostTensor),
+        Value::HostInt16Tensor(t) => split!(t, HostTensor),
+        Value::HostInt32Tensor(t) => split!(t, HostTensor),
+        Value::HostInt64Tensor(t) => split!(t, HostTensor),
+        Value::HostUint8Tensor(t) => split!(t, HostTensor),
+        Value::HostUint16Tensor(t) => split!(t, HostTensor),
+        Value::HostUint32Tensor(t) => split!(t, HostTensor),
+        Value::HostUint64Tensor(t) => split!(t, HostTensor),
+        Value::HostRing64Tensor(t) => split!(t, HostRingTensor),
+        Value::HostRing128Tensor(t) => split!(t, HostRingTensor),
+        Value::HostBitTensor(t) => {
+            let bits =
+                t.0.into_array::<u8>()
+                    .map_err(|e| Error::Storage(format!("failed to read bit tensor: {}", e)))?;
+            if bits.ndim() == 0 {
+                vec![value.clone()]
+            } else {
+                bits.axis_chunks_iter(Axis(0), chunk_rows)
+                    .map(|chunk| {
+                        let chunk: HostBitTensor = t.1.from_raw(chunk.to_owned());
+                        Value::from(chunk)
+                    })
+                    .collect()
+            }
+        }
+        _ => {
+            return Err(Error::Storage(format!(
+                "cannot split a {} into chunks of rows",
+                value.ty()
+            )))
+        }
+    };
+    Ok(chunks)
+}
+
+/// Shape of a host tensor, or `None` for other values.
+fn host_shape(value: &Value) -> Option<Vec<usize>> {
+    let shape = match value {
+        Value::HostFloat32Tensor(t) => t.0.shape(),
+        Value::HostFloat64Tensor(t) => t.0.shape(),
+        Value::HostInt8Tensor(t) => t.0.shape(),
+        Value::HostInt16Tensor(t) => t.0.shape(),
+        Value::HostInt32Tensor(t) => t.0.shape(),
+        Value::HostInt64Tensor(t) => t.0.shape(),
+        Value::HostUint8Tensor(t) => t.0.shape(),
+        Value::HostUint16Tensor(t) => t.0.shape(),
+        Value::HostUint32Tensor(t) => t.0.shape(),
+        Value::HostUint64Tensor(t) => t.0.shape(),
+        Value::HostRing64Tensor(t) => t.0.shape(),
+        Value::HostRing128Tensor(t) => t.0.shape(),
+        Value::HostFixed64Tensor(t) => t.tensor.0.shape(),
+        Value::HostFixed128Tensor(t) => t.tensor.0.shape(),
+        Value::HostBitTensor(t) => t.0.shape(),
+        _ => return None,
+    };
+    Some(shape.to_vec())
 }
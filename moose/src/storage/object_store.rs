@@ -0,0 +1,319 @@
+//! Storage backed by cloud object stores.
+//!
+//! Keys given as `s3://<bucket>/<path>`, `gs://<bucket>/<path>`, or
+//! `az://<container>/<path>` URIs are served by Amazon S3, Google Cloud Storage,
+//! or Azure Blob Storage respectively. All other keys are passed on to a fallback
+//! storage, such that computations can mix objects with local files.
+//!
+//! Objects are held in the same formats as files of the filesystem storage,
+//! depending on the extension of their path: loading a value streams the object
+//! into a staging file which is then read as usual, and saving a value writes a
+//! staging file which is then uploaded in parts. Queries are interpreted in the
+//! same way as for files.
+//!
+//! Credentials are taken from the environment variables usual for each service,
+//! such as `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`,
+//! `GOOGLE_SERVICE_ACCOUNT`, or `AZURE_STORAGE_ACCOUNT_NAME` and
+//! `AZURE_STORAGE_ACCOUNT_KEY`.
+
+use crate::error::Error;
+use crate::execution::AsyncStorageImpl;
+use crate::prelude::*;
+use crate::storage::filesystem::AsyncFilesystemStorage;
+use crate::storage::{stream_rows, AsyncStorage, ValueStat, ValueStream};
+use crate::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use futures::StreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// URI schemes of the keys of values held by object stores.
+pub const OBJECT_STORE_SCHEMES: [&str; 3] = ["s3", "gs", "az"];
+
+/// Settings of the clients of object stores, besides credentials.
+#[derive(Clone, Debug, Default)]
+pub struct ObjectStoreConfig {
+    /// Region of S3 buckets.
+    pub region: Option<String>,
+    /// Endpoint to use for S3 instead of AWS, such as that of a MinIO deployment.
+    pub endpoint: Option<String>,
+    /// Whether to allow connecting to `endpoint` over plain HTTP.
+    pub allow_http: bool,
+    /// Directory in which to stage objects, or the system's temporary directory if `None`.
+    pub staging_dir: Option<PathBuf>,
+}
+
+impl ObjectStoreConfig {
+    pub fn with_region(self, region: Option<String>) -> Self {
+        ObjectStoreConfig { region, ..self }
+    }
+
+    pub fn with_endpoint(self, endpoint: Option<String>) -> Self {
+        ObjectStoreConfig { endpoint, ..self }
+    }
+
+    pub fn with_allow_http(self, allow_http: bool) -> Self {
+        ObjectStoreConfig { allow_http, ..self }
+    }
+
+    pub fn with_staging_dir(self, staging_dir: Option<PathBuf>) -> Self {
+        ObjectStoreConfig {
+            staging_dir,
+            ..self
+        }
+    }
+}
+
+/// Location of an object given by a key.
+#[derive(Debug, PartialEq)]
+struct ObjectUri<'k> {
+    scheme: &'k str,
+    bucket: &'k str,
+    path: &'k str,
+}
+
+impl<'k> ObjectUri<'k> {
+    /// Location given by `key`, or `None` if the key is not an object store URI.
+    fn parse(key: &'k str) -> Option<Result<Self>> {
+        let (scheme, rest) = key.split_once("://")?;
+        if !OBJECT_STORE_SCHEMES.contains(&scheme) {
+            return None;
+        }
+        let uri = match rest.split_once('/') {
+            Some((bucket, path)) if !bucket.is_empty() && !path.is_empty() => Ok(ObjectUri {
+                scheme,
+                bucket,
+                path,
+            }),
+            _ => Err(Error::Storage(format!(
+                "expected an object store URI of the form {}://<bucket>/<path>, got: {}",
+                scheme, key
+            ))),
+        };
+        Some(uri)
+    }
+
+    /// File name of the object, holding the extension that determines its format.
+    fn file_name(&self) -> &'k str {
+        self.path.rsplit('/').next().unwrap_or(self.path)
+    }
+}
+
+pub struct AsyncObjectStorage {
+    config: ObjectStoreConfig,
+    /// Clients of the buckets accessed so far, keyed by scheme and bucket.
+    stores: DashMap<String, Arc<dyn ObjectStore>>,
+    fallback: AsyncStorageImpl,
+    staging: AsyncFilesystemStorage,
+}
+
+impl AsyncObjectStorage {
+    /// Serve keys given as object store URIs using `config` and all others from `fallback`.
+    pub fn new(config: ObjectStoreConfig, fallback: AsyncStorageImpl) -> Self {
+        AsyncObjectStorage {
+            config,
+            stores: DashMap::new(),
+            fallback,
+            staging: AsyncFilesystemStorage::default(),
+        }
+    }
+
+    fn store(&self, uri: &ObjectUri<'_>) -> Result<Arc<dyn ObjectStore>> {
+        let id = format!("{}://{}", uri.scheme, uri.bucket);
+        if let Some(store) = self.stores.get(&id) {
+            return Ok(store.value().clone());
+        }
+        let store: Arc<dyn ObjectStore> = match uri.scheme {
+            "s3" => {
+                let mut builder = AmazonS3Builder::from_env()
+                    .with_bucket_name(uri.bucket)
+                    .with_allow_http(self.config.allow_http);
+                if let Some(region) = &self.config.region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(endpoint) = &self.config.endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                builder.build().map(|store| Arc::new(store) as _)
+            }
+            "gs" => GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(uri.bucket)
+                .build()
+                .map(|store| Arc::new(store) as _),
+            "az" => MicrosoftAzureBuilder::from_env()
+                .with_container_name(uri.bucket)
+                .build()
+                .map(|store| Arc::new(store) as _),
+            scheme => unreachable!("unexpected object store scheme {}", scheme),
+        }
+        .map_err(|e| Error::Storage(format!("failed to configure client for {}: {}", id, e)))?;
+        self.stores.insert(id, Arc::clone(&store));
+        Ok(store)
+    }
+
+    /// Directory in which to stage a single object, removed once dropped.
+    fn staging_dir(&self) -> Result<tempfile::TempDir> {
+        let dir = match &self.config.staging_dir {
+            Some(dir) => tempfile::tempdir_in(dir),
+            None => tempfile::tempdir(),
+        };
+        dir.map_err(|e| Error::Storage(format!("failed to create staging directory: {}", e)))
+    }
+}
+
+fn object_error(key: &str, e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("object store request for '{}' failed: {}", key, e))
+}
+
+fn staged_key(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| Error::Storage(format!("invalid staging path: {}", path.display())))
+}
+
+#[async_trait]
+impl AsyncStorage for AsyncObjectStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        let uri = match ObjectUri::parse(key) {
+            Some(uri) => uri?,
+            None => return self.fallback.save(key, session_id, val).await,
+        };
+        tracing::debug!(key, ty = %val.ty(), "Uploading value");
+        let store = self.store(&uri)?;
+        let dir = self.staging_dir()?;
+        let staged = dir.path().join(uri.file_name());
+        self.staging
+            .save(staged_key(&staged)?, session_id, val)
+            .await?;
+
+        let location = ObjectPath::from(uri.path);
+        let (upload_id, mut writer) = store
+            .put_multipart(&location)
+            .await
+            .map_err(|e| object_error(key, e))?;
+        let upload = async {
+            let mut file = tokio::fs::File::open(&staged).await?;
+            tokio::io::copy(&mut file, &mut writer).await?;
+            // the upload is only complete once all parts have been written
+            writer.shutdown().await
+        };
+        if let Err(e) = upload.await {
+            // do not leave incomplete uploads behind
+            let _ = store.abort_multipart(&location, &upload_id).await;
+            return Err(object_error(key, e));
+        }
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        let uri = match ObjectUri::parse(key) {
+            Some(uri) => uri?,
+            None => return self.fallback.load(key, session_id, type_hint, query).await,
+        };
+        tracing::debug!(key, query, "Downloading value");
+        let store = self.store(&uri)?;
+        let dir = self.staging_dir()?;
+        let staged = dir.path().join(uri.file_name());
+
+        let mut chunks = store
+            .get(&ObjectPath::from(uri.path))
+            .await
+            .map_err(|e| object_error(key, e))?
+            .into_stream();
+        let mut file = tokio::fs::File::create(&staged)
+            .await
+            .map_err(|e| object_error(key, e))?;
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.map_err(|e| object_error(key, e))?;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| object_error(key, e))?;
+        }
+        file.flush().await.map_err(|e| object_error(key, e))?;
+
+        self.staging
+            .load(staged_key(&staged)?, session_id, type_hint, query)
+            .await
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        if ObjectUri::parse(key).is_none() {
+            return self
+                .fallback
+                .load_chunks(key, session_id, type_hint, query, chunk_rows)
+                .await;
+        }
+        // values held by object stores are fetched as a whole
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // values held by object stores cannot be listed
+        self.fallback.list(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        if ObjectUri::parse(key).is_some() {
+            return Err(Error::Storage(format!(
+                "values held by object stores cannot be described: {}",
+                key
+            )));
+        }
+        self.fallback.stat(key).await
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        // objects are held in the same formats as files
+        self.fallback.formats()
+    }
+
+    async fn check(&self) -> Result<()> {
+        self.fallback.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_uri() {
+        let uri = ObjectUri::parse("s3://datasets/2023/features.npy")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            uri,
+            ObjectUri {
+                scheme: "s3",
+                bucket: "datasets",
+                path: "2023/features.npy",
+            }
+        );
+        assert_eq!(uri.file_name(), "features.npy");
+
+        assert!(ObjectUri::parse("data/features.npy").is_none());
+        assert!(ObjectUri::parse("flight:features").is_none());
+        assert!(ObjectUri::parse("gs://datasets").unwrap().is_err());
+        assert!(ObjectUri::parse("az:///features.npy").unwrap().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Tensors read from and written to Parquet files.
+//!
+//! Each numeric column of a Parquet file is read into a one-dimensional tensor
+//! of the same dtype, keyed by column name. Files are read in row groups, such
+//! that subsets of the row groups of large files can be read on their own, for
+//! instance to split a dataset between workers:
+//!
+//! ```no_run
+//! # use moose::prelude::*;
+//! # use moose::storage::parquet::{num_row_groups, read_parquet, ParquetOptions};
+//! let plc = HostPlacement::from("alice");
+//! let row_groups = num_row_groups("features.parquet").unwrap();
+//! let options = ParquetOptions::default()
+//!     .with_columns(vec!["age".to_string()])
+//!     .with_row_groups((0..row_groups / 2).collect());
+//! let tensors = read_parquet("features.parquet", &options, &plc).unwrap();
+//! ```
+
+use crate::error::{Error, Result};
+use crate::host::arrow::{record_batch_to_tensors, tensors_to_record_batch};
+use crate::prelude::*;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+/// Options for reading Parquet files.
+#[derive(Clone, Debug, Default)]
+pub struct ParquetOptions {
+    /// Columns to read; all columns if empty.
+    pub columns: Vec<String>,
+    /// Indices of the row groups to read, in order; all row groups if `None`.
+    pub row_groups: Option<Vec<usize>>,
+}
+
+impl ParquetOptions {
+    pub fn with_columns(self, columns: Vec<String>) -> Self {
+        ParquetOptions { columns, ..self }
+    }
+
+    pub fn with_row_groups(self, row_groups: Vec<usize>) -> Self {
+        ParquetOptions {
+            row_groups: Some(row_groups),
+            ..self
+        }
+    }
+}
+
+fn parquet_error(path: &Path, e: impl std::fmt::Display) -> Error {
+    Error::Storage(format!("failed to read {}: {}", path.display(), e))
+}
+
+fn reader_builder(path: &Path) -> Result<ParquetRecordBatchReaderBuilder<File>> {
+    let file = File::open(path).map_err(|e| parquet_error(path, e))?;
+    ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| parquet_error(path, e))
+}
+
+/// Number of row groups of the Parquet file at `path`.
+pub fn num_row_groups<P: AsRef<Path>>(path: P) -> Result<usize> {
+    let builder = reader_builder(path.as_ref())?;
+    Ok(builder.metadata().num_row_groups())
+}
+
+/// Selected rows and columns of the Parquet file at `path`, as a single record batch.
+pub fn read_record_batch<P: AsRef<Path>>(path: P, options: &ParquetOptions) -> Result<RecordBatch> {
+    let path = path.as_ref();
+    let mut builder = reader_builder(path)?;
+    let schema = builder.schema().clone();
+
+    if !options.columns.is_empty() {
+        let indices = options
+            .columns
+            .iter()
+            .map(|column| {
+                schema.index_of(column).map_err(|_| {
+                    Error::Storage(format!("no column '{}' in {}", column, path.display()))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        builder = builder.with_projection(mask);
+    }
+
+    if let Some(row_groups) = &options.row_groups {
+        let available = builder.metadata().num_row_groups();
+        if let Some(missing) = row_groups.iter().find(|i| **i >= available) {
+            return Err(Error::Storage(format!(
+                "no row group {} in {}, which has {}",
+                missing,
+                path.display(),
+                available
+            )));
+        }
+        builder = builder.with_row_groups(row_groups.clone());
+    }
+
+    let reader = builder.build().map_err(|e| parquet_error(path, e))?;
+    let schema = reader.schema();
+    let batches = reader
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| parquet_error(path, e))?;
+    arrow::compute::concat_batches(&schema, &batches).map_err(|e| parquet_error(path, e))
+}
+
+/// Tensor for each selected column of the Parquet file at `path`, keyed by column name.
+pub fn read_parquet<P: AsRef<Path>>(
+    path: P,
+    options: &ParquetOptions,
+    plc: &HostPlacement,
+) -> Result<HashMap<String, Value>> {
+    let batch = read_record_batch(path, options)?;
+    record_batch_to_tensors(&batch, plc)
+}
+
+/// Write a column for each of the named one-dimensional `tensors` to a Parquet
+/// file at `path`, in row groups of at most `row_group_size` rows if given.
+pub fn write_parquet<P: AsRef<Path>>(
+    path: P,
+    tensors: &[(String, Value)],
+    row_group_size: Option<usize>,
+) -> Result<()> {
+    let batch = tensors_to_record_batch(tensors)?;
+    write_record_batch(path, &batch, row_group_size)
+}
+
+/// Write `batch` to a Parquet file at `path`, in row groups of at most
+/// `row_group_size` rows if given.
+pub fn write_record_batch<P: AsRef<Path>>(
+    path: P,
+    batch: &RecordBatch,
+    row_group_size: Option<usize>,
+) -> Result<()> {
+    let path = path.as_ref();
+    let failed = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!("failed to write {}: {}", path.display(), e))
+    };
+    let mut properties = WriterProperties::builder();
+    if let Some(row_group_size) = row_group_size {
+        properties = properties.set_max_row_group_size(row_group_size);
+    }
+    let file = File::create(path).map_err(|e| failed(&e))?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties.build()))
+        .map_err(|e| failed(&e))?;
+    writer.write(batch).map_err(|e| failed(&e))?;
+    writer.close().map_err(|e| failed(&e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parquet_row_groups() {
+        let plc = HostPlacement::from("alice");
+        let age: HostInt64Tensor = plc.from_raw(array![31, 47, 25, 62, 38]);
+        let income: HostFloat64Tensor = plc.from_raw(array![2.5, 4.0, 1.5, 3.0, 2.0]);
+        let tensors = vec![
+            ("age".to_string(), Value::from(age)),
+            ("income".to_string(), Value::from(income)),
+        ];
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("people.parquet");
+        write_parquet(&path, &tensors, Some(2)).unwrap();
+        assert_eq!(num_row_groups(&path).unwrap(), 3);
+
+        let all = read_parquet(&path, &ParquetOptions::default(), &plc).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["age"], tensors[0].1);
+        assert_eq!(all["income"], tensors[1].1);
+
+        let options = ParquetOptions::default()
+            .with_columns(vec!["income".to_string()])
+            .with_row_groups(vec![0, 2]);
+        let some = read_parquet(&path, &options, &plc).unwrap();
+        let income: HostFloat64Tensor = plc.from_raw(array![2.5, 4.0, 2.0]);
+        assert_eq!(some.len(), 1);
+        assert_eq!(some["income"], Value::from(income));
+
+        let options = ParquetOptions::default().with_row_groups(vec![3]);
+        assert!(read_parquet(&path, &options, &plc).is_err());
+    }
+}
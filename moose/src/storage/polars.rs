@@ -0,0 +1,82 @@
+//! Named tensors read from files supported by Polars.
+//!
+//! The tables held by CSV, Parquet, and Arrow IPC files are read into a
+//! one-dimensional tensor per column, keyed by column name, such that they can
+//! serve as storage for local execution:
+//!
+//! ```no_run
+//! # use moose::prelude::*;
+//! # use moose::storage::local::LocalAsyncStorage;
+//! # use moose::storage::polars::read_named_tensors;
+//! let plc = HostPlacement::from("alice");
+//! let tensors = read_named_tensors("features.parquet", &plc).unwrap();
+//! let storage = LocalAsyncStorage::from_hashmap(tensors);
+//! ```
+
+use crate::error::{Error, Result};
+use crate::host::polars::series_to_value;
+use crate::prelude::*;
+use polars::prelude::{CsvReader, DataFrame, IpcReader, ParquetReader, SerReader};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+/// Table held by a CSV, Parquet, or Arrow IPC file, depending on its extension.
+pub fn read_dataframe<P: AsRef<Path>>(path: P) -> Result<DataFrame> {
+    let path = path.as_ref();
+    let failed = |e: &dyn std::fmt::Display| {
+        Error::Storage(format!(
+            "failed to read {} with Polars: {}",
+            path.display(),
+            e
+        ))
+    };
+    let file = File::open(path).map_err(|e| failed(&e))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => CsvReader::new(file).has_header(true).finish(),
+        Some("parquet") => ParquetReader::new(file).finish(),
+        Some("arrow") | Some("ipc") | Some("feather") => IpcReader::new(file).finish(),
+        _ => {
+            return Err(Error::Storage(format!(
+                "key must provide an extension of either '.csv', '.parquet', '.arrow', '.ipc', or '.feather', got: {}",
+                path.display()
+            )))
+        }
+    }
+    .map_err(|e| failed(&e))
+}
+
+/// Tensor for each column of the table held by the file at `path`, keyed by column name.
+pub fn read_named_tensors<P: AsRef<Path>>(
+    path: P,
+    plc: &HostPlacement,
+) -> Result<HashMap<String, Value>> {
+    let df = read_dataframe(path)?;
+    df.get_columns()
+        .iter()
+        .map(|series| Ok((series.name().to_string(), series_to_value(series, plc)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::io::Write;
+    use tempfile::Builder;
+
+    #[test]
+    fn test_read_named_tensors() {
+        let mut file = Builder::new().suffix(".csv").tempfile().unwrap();
+        writeln!(file, "age,income\n31,2.5\n47,4.0").unwrap();
+
+        let plc = HostPlacement::from("alice");
+        let tensors = read_named_tensors(file.path(), &plc).unwrap();
+        assert_eq!(tensors.len(), 2);
+
+        let age: HostInt64Tensor = plc.from_raw(array![31, 47]);
+        assert_eq!(tensors["age"], Value::from(age));
+        let income: HostFloat64Tensor = plc.from_raw(array![2.5, 4.0]);
+        assert_eq!(tensors["income"], Value::from(income));
+    }
+}
@@ -0,0 +1,237 @@
+//! Storage backed by a Redis server.
+//!
+//! Keys starting with `redis:` are served by Redis, such that workers of a
+//! cluster can share values, including intermediate results, without a shared
+//! filesystem. All other keys are passed on to a fallback storage.
+//!
+//! Values are held in the wire format used between parties, under the rest of
+//! the key prefixed by a namespace and, if values are scoped to sessions, by the
+//! id of the session saving or loading them. Values may be given a time to live
+//! after which Redis evicts them.
+
+use crate::error::Error;
+use crate::execution::AsyncStorageImpl;
+use crate::networking::wire::{deserialize_value, serialize_value};
+use crate::prelude::*;
+use crate::storage::{stream_rows, AsyncStorage, ValueStat, ValueStream};
+use crate::Result;
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// Prefix of the keys of values held by Redis.
+pub const REDIS_PREFIX: &str = "redis:";
+
+/// Layout and lifetime of the values held by Redis.
+#[derive(Clone, Debug)]
+pub struct RedisConfig {
+    /// Prefix of the Redis keys of all values, such that several deployments can share a server.
+    pub namespace: String,
+    /// Whether values are only visible to the session saving them.
+    pub session_scoped: bool,
+    /// Time after which values are evicted, or never if `None`.
+    pub ttl: Option<Duration>,
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        RedisConfig {
+            namespace: "moose".to_string(),
+            session_scoped: false,
+            ttl: None,
+        }
+    }
+}
+
+impl RedisConfig {
+    pub fn with_namespace(self, namespace: String) -> Self {
+        RedisConfig { namespace, ..self }
+    }
+
+    pub fn with_session_scoped(self, session_scoped: bool) -> Self {
+        RedisConfig {
+            session_scoped,
+            ..self
+        }
+    }
+
+    pub fn with_ttl(self, ttl: Option<Duration>) -> Self {
+        RedisConfig { ttl, ..self }
+    }
+
+    /// Redis key holding the value saved under `key`, without its `redis:` prefix.
+    fn redis_key(&self, key: &str, session_id: &SessionId) -> String {
+        if self.session_scoped {
+            format!("{}:{}:{}", self.namespace, session_id, key)
+        } else {
+            format!("{}:{}", self.namespace, key)
+        }
+    }
+}
+
+pub struct AsyncRedisStorage {
+    client: redis::Client,
+    connection: OnceCell<ConnectionManager>,
+    config: RedisConfig,
+    fallback: AsyncStorageImpl,
+}
+
+impl AsyncRedisStorage {
+    /// Serve keys starting with `redis:` from the Redis server at `url`, such as
+    /// `redis://127.0.0.1:6379`, and all others from `fallback`.
+    ///
+    /// The connection is only established once values are loaded or saved, and
+    /// is re-established if lost.
+    pub fn new(url: &str, config: RedisConfig, fallback: AsyncStorageImpl) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| Error::Storage(format!("invalid Redis URL '{}': {}", url, e)))?;
+        Ok(AsyncRedisStorage {
+            client,
+            connection: OnceCell::new(),
+            config,
+            fallback,
+        })
+    }
+
+    async fn connection(&self) -> Result<ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .cloned()
+            .map_err(|e| Error::Storage(format!("failed to connect to Redis: {}", e)))
+    }
+}
+
+fn redis_error(key: &str, e: redis::RedisError) -> Error {
+    Error::Storage(format!("Redis request for '{}' failed: {}", key, e))
+}
+
+#[async_trait]
+impl AsyncStorage for AsyncRedisStorage {
+    async fn save(&self, key: &str, session_id: &SessionId, val: &Value) -> Result<()> {
+        let name = match key.strip_prefix(REDIS_PREFIX) {
+            Some(name) => name,
+            None => return self.fallback.save(key, session_id, val).await,
+        };
+        tracing::debug!(key, ty = %val.ty(), "Storing value");
+        let bytes = serialize_value(val)
+            .map_err(|e| Error::Storage(format!("failed to serialize '{}': {}", key, e)))?;
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(self.config.redis_key(name, session_id)).arg(bytes);
+        if let Some(ttl) = self.config.ttl {
+            cmd.arg("PX").arg(ttl.as_millis() as u64);
+        }
+        let mut connection = self.connection().await?;
+        cmd.query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(|e| redis_error(key, e))
+    }
+
+    async fn load(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+    ) -> Result<Value> {
+        let name = match key.strip_prefix(REDIS_PREFIX) {
+            Some(name) => name,
+            None => return self.fallback.load(key, session_id, type_hint, query).await,
+        };
+        tracing::debug!(key, query, "Fetching value");
+        if !query.is_empty() {
+            return Err(Error::Storage(
+                "query is not allowed for Redis storage".into(),
+            ));
+        }
+        let mut connection = self.connection().await?;
+        let bytes: Option<Vec<u8>> = redis::cmd("GET")
+            .arg(self.config.redis_key(name, session_id))
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| redis_error(key, e))?;
+        let bytes =
+            bytes.ok_or_else(|| Error::Storage(format!("key not found in Redis: {}", key)))?;
+        let value = deserialize_value(&bytes)
+            .map_err(|e| Error::Storage(format!("failed to deserialize '{}': {}", key, e)))?;
+        match type_hint {
+            Some(ty) if ty != value.ty() => Err(Error::Storage(format!(
+                "value of '{}' is a {} but a {} was expected",
+                key,
+                value.ty(),
+                ty
+            ))),
+            _ => Ok(value),
+        }
+    }
+
+    async fn load_chunks(
+        &self,
+        key: &str,
+        session_id: &SessionId,
+        type_hint: Option<Ty>,
+        query: &str,
+        chunk_rows: usize,
+    ) -> Result<ValueStream> {
+        if !key.starts_with(REDIS_PREFIX) {
+            return self
+                .fallback
+                .load_chunks(key, session_id, type_hint, query, chunk_rows)
+                .await;
+        }
+        // values held by Redis are fetched as a whole
+        let value = self.load(key, session_id, type_hint, query).await?;
+        stream_rows(&value, chunk_rows)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // values held by Redis cannot be listed
+        self.fallback.list(prefix).await
+    }
+
+    async fn stat(&self, key: &str) -> Result<ValueStat> {
+        if key.starts_with(REDIS_PREFIX) {
+            return Err(Error::Storage(format!(
+                "values held by Redis cannot be described: {}",
+                key
+            )));
+        }
+        self.fallback.stat(key).await
+    }
+
+    fn formats(&self) -> Option<Vec<String>> {
+        // keys of values held by Redis need not have an extension
+        None
+    }
+
+    async fn check(&self) -> Result<()> {
+        let mut connection = self.connection().await?;
+        redis::cmd("PING")
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(|e| Error::Storage(format!("Redis cannot be reached: {}", e)))?;
+        self.fallback.check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_redis_key() {
+        let session_id = SessionId::try_from("session-1").unwrap();
+        let config = RedisConfig::default();
+        assert_eq!(config.redis_key("x", &session_id), "moose:x");
+
+        let config = config
+            .with_namespace("cluster".to_string())
+            .with_session_scoped(true);
+        assert_eq!(
+            config.redis_key("results/x", &session_id),
+            "cluster:session-1:results/x"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Import of the weights of Keras models saved in HDF5 format.
+//!
+//! Both files written by `model.save_weights("weights.h5")` and by
+//! `model.save("model.h5")` are supported. Each weight is named after its Keras
+//! name with slashes replaced by underscores and the `:0` suffix dropped, such
+//! that the kernel `dense/kernel:0` of a dense layer becomes `dense_kernel`.
+//!
+//! Weights can either be bound into a logical computation, replacing the
+//! arguments of the same names by constants, or be used as storage entries for
+//! computations loading them by name.
+
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::host::{HostPlacement, HostTensor};
+use crate::logical::TensorDType;
+use crate::types::HostFloat64Tensor;
+use ndarray::{ArrayD, IxDyn};
+use std::collections::HashMap;
+use std::path::Path;
+
+fn hdf5_error(path: &Path, e: hdf5::Error) -> Error {
+    Error::Storage(format!(
+        "failed to read Keras weights from {}: {}",
+        path.display(),
+        e
+    ))
+}
+
+/// Name of the parameter holding the Keras weight `weight_name`.
+pub fn parameter_name(weight_name: &str) -> String {
+    let name = match weight_name.rsplit_once(':') {
+        Some((name, index)) if index.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => weight_name,
+    };
+    name.replace('/', "_")
+}
+
+/// Weights held by the Keras HDF5 file at `path`, keyed by parameter name.
+pub fn read_weights<P: AsRef<Path>>(path: P) -> Result<HashMap<String, ArrayD<f64>>> {
+    let path = path.as_ref();
+    let file = hdf5::File::open(path).map_err(|e| hdf5_error(path, e))?;
+    // full models keep their weights in a group of their own
+    let root = if file.link_exists("model_weights") {
+        file.group("model_weights")
+            .map_err(|e| hdf5_error(path, e))?
+    } else {
+        file.group("/").map_err(|e| hdf5_error(path, e))?
+    };
+
+    let mut weights = HashMap::new();
+    for layer in root.groups().map_err(|e| hdf5_error(path, e))? {
+        let prefix = format!("{}/", layer.name());
+        let mut groups = vec![layer];
+        while let Some(group) = groups.pop() {
+            groups.extend(group.groups().map_err(|e| hdf5_error(path, e))?);
+            for dataset in group.datasets().map_err(|e| hdf5_error(path, e))? {
+                let full_name = dataset.name();
+                let weight_name = full_name.strip_prefix(&prefix).unwrap_or(&full_name);
+                let values: Vec<f64> = dataset.read_raw().map_err(|e| hdf5_error(path, e))?;
+                let array =
+                    ArrayD::from_shape_vec(IxDyn(&dataset.shape()), values).map_err(|e| {
+                        Error::Storage(format!("Keras weight '{}' is malformed: {}", full_name, e))
+                    })?;
+                let name = parameter_name(weight_name);
+                if weights.insert(name.clone(), array).is_some() {
+                    return Err(Error::Storage(format!(
+                        "Keras weights in {} hold parameter '{}' more than once",
+                        path.display(),
+                        name
+                    )));
+                }
+            }
+        }
+    }
+    Ok(weights)
+}
+
+/// Storage entries holding `weights` on `plc`, keyed by parameter name.
+pub fn weights_to_storage(
+    weights: &HashMap<String, ArrayD<f64>>,
+    plc: &HostPlacement,
+) -> HashMap<String, Value> {
+    weights
+        .iter()
+        .map(|(name, array)| {
+            let tensor: HostFloat64Tensor = HostTensor(array.clone().into_shared(), plc.clone());
+            (name.clone(), Value::from(tensor))
+        })
+        .collect()
+}
+
+/// Replace the arguments of `comp` named after parameters in `weights` by
+/// constants holding the weights.
+///
+/// Arguments must be `Float64` tensors on host placements to be replaced; all
+/// other arguments, as well as weights without a matching argument, are left
+/// as they are.
+pub fn bind_weights(
+    comp: &Computation,
+    weights: &HashMap<String, ArrayD<f64>>,
+) -> Result<Computation> {
+    let operations = comp
+        .operations
+        .iter()
+        .map(|op| {
+            let input = match &op.kind {
+                Operator::Input(input) => input,
+                _ => return Ok(op.clone()),
+            };
+            let array = match weights.get(&input.arg_name) {
+                Some(array) => array,
+                None => return Ok(op.clone()),
+            };
+            let plc = match &op.placement {
+                Placement::Host(plc) => plc,
+                _ => {
+                    return Err(Error::InvalidArgument(format!(
+                        "cannot bind weight '{}' to an argument not on a host placement",
+                        input.arg_name
+                    )))
+                }
+            };
+            match input.sig.ret() {
+                Ty::Tensor(TensorDType::Float64) | Ty::HostFloat64Tensor => (),
+                ty => {
+                    return Err(Error::InvalidArgument(format!(
+                        "cannot bind weight '{}' to an argument of type {:?}",
+                        input.arg_name, ty
+                    )))
+                }
+            }
+            let value = HostTensor(array.clone().into_shared(), plc.clone());
+            Ok(Operation {
+                kind: ConstantOp {
+                    sig: input.sig,
+                    value: Constant::HostFloat64Tensor(value),
+                }
+                .into(),
+                ..op.clone()
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Computation { operations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_parameter_name() {
+        assert_eq!(parameter_name("dense/kernel:0"), "dense_kernel");
+        assert_eq!(parameter_name("dense_1/bias"), "dense_1_bias");
+    }
+
+    #[test]
+    fn test_bind_weights() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("weights.h5");
+        {
+            let file = hdf5::File::create(&path).unwrap();
+            let layer = file.create_group("dense").unwrap();
+            let weights = layer.create_group("dense").unwrap();
+            weights
+                .new_dataset::<f32>()
+                .shape([2, 1])
+                .create("kernel:0")
+                .unwrap()
+                .write_raw(&[0.5f32, -1.0])
+                .unwrap();
+            weights
+                .new_dataset::<f32>()
+                .shape([1])
+                .create("bias:0")
+                .unwrap()
+                .write_raw(&[2.0f32])
+                .unwrap();
+        }
+
+        let weights = read_weights(&path).unwrap();
+        assert_eq!(weights.len(), 2);
+        assert_eq!(weights["dense_kernel"], array![[0.5], [-1.0]].into_dyn());
+
+        let comp: Computation = r#"
+        x = Input{arg_name = "x"}: () -> Tensor<Float64> @Host(alice)
+        w = Input{arg_name = "dense_kernel"}: () -> Tensor<Float64> @Host(alice)
+        y = Dot: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)
+        output = Output{tag = "output_0"}: (Tensor<Float64>) -> Tensor<Float64> (y) @Host(alice)
+        "#
+        .try_into()
+        .unwrap();
+        let bound = bind_weights(&comp, &weights).unwrap();
+        assert!(matches!(bound.operations[0].kind, Operator::Input(_)));
+        match &bound.operations[1].kind {
+            Operator::Constant(op) => assert_eq!(
+                op.value,
+                Constant::HostFloat64Tensor(HostTensor(
+                    weights["dense_kernel"].clone().into_shared(),
+                    HostPlacement::from("alice")
+                ))
+            ),
+            _ => panic!("expected the weight to be bound as a constant"),
+        }
+        assert_eq!(bound.operations[2..], comp.operations[2..]);
+    }
+}
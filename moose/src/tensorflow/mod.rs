@@ -0,0 +1,874 @@
+//! Import of frozen TensorFlow graphs and SavedModels as logical computations.
+//!
+//! Starting from the requested outputs, the nodes of the graph they depend on are
+//! translated into operations of the logical dialect, with placeholders becoming
+//! arguments and constants becoming constants; all other nodes are ignored. Values
+//! are placed according to a [`PlacementStrategy`], as for ONNX models.
+//!
+//! Variables are not supported, so SavedModels must have their variables frozen
+//! into constants, as done by `convert_variables_to_constants_v2`. The supported
+//! operations cover dense and convolutional layers along with their activations.
+//! Convolutions are lowered to matrix products of the patches of their input, which
+//! requires the shapes of the inputs to be fully known from their placeholders.
+
+pub(crate) mod proto {
+    #![allow(clippy::derive_partial_eq_without_eq)]
+    include!(concat!(env!("OUT_DIR"), "/tensorflow.rs"));
+}
+
+#[cfg(feature = "keras")]
+pub mod keras;
+
+use self::proto::{attr_value, GraphDef, NodeDef, SavedModel, TensorProto};
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::host::{SliceInfo, SliceInfoElem};
+use crate::importer::ComputationBuilder;
+use crate::logical::TensorShape;
+use crate::onnx::{broadcast_shape, dot_shape, PlacementStrategy, Shape};
+use ndarray::{ArrayD, IxDyn};
+use prost::Message;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+// values of the `DataType` enum of TensorFlow
+const DT_FLOAT: i32 = 1;
+const DT_DOUBLE: i32 = 2;
+const DT_INT32: i32 = 3;
+const DT_INT64: i32 = 9;
+
+/// Translate a serialized, frozen TensorFlow `GraphDef` into a logical computation
+/// computing the tensors named by `outputs`, placed according to `strategy`.
+///
+/// Each placeholder the outputs depend on becomes an argument of the same name,
+/// expected to be a `Float64` tensor, and each output an output tagged with the name
+/// of the node computing it.
+pub fn import_frozen_graph(
+    graph: &[u8],
+    outputs: &[&str],
+    strategy: &PlacementStrategy,
+) -> Result<Computation> {
+    let graph = GraphDef::decode(graph)
+        .map_err(|e| Error::InvalidArgument(format!("failed to decode TensorFlow graph: {}", e)))?;
+    let outputs: Vec<(String, String)> = outputs
+        .iter()
+        .map(|tensor| (node_name(tensor).to_string(), tensor.to_string()))
+        .collect();
+    Importer::new(strategy, &graph).import(&outputs)
+}
+
+/// Translate a serialized SavedModel with frozen variables into a logical computation
+/// computing the outputs of its `signature`, placed according to `strategy`.
+///
+/// Outputs are tagged with their keys in the signature, typically `serving_default`.
+pub fn import_saved_model(
+    saved_model: &[u8],
+    signature: &str,
+    strategy: &PlacementStrategy,
+) -> Result<Computation> {
+    let model = SavedModel::decode(saved_model)
+        .map_err(|e| Error::InvalidArgument(format!("failed to decode SavedModel: {}", e)))?;
+    let (graph, signature_def) = model
+        .meta_graphs
+        .iter()
+        .find_map(|meta_graph| {
+            let signature_def = meta_graph.signature_def.get(signature)?;
+            Some((meta_graph.graph_def.as_ref()?, signature_def))
+        })
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!("SavedModel has no signature '{}'", signature))
+        })?;
+    let mut outputs: Vec<(String, String)> = signature_def
+        .outputs
+        .iter()
+        .map(|(key, info)| (key.clone(), info.name.clone()))
+        .collect();
+    outputs.sort();
+    Importer::new(strategy, graph).import(&outputs)
+}
+
+/// Value of the graph, as computed by an operation of the computation.
+#[derive(Clone)]
+struct Imported {
+    op_name: String,
+    shape: Shape,
+}
+
+struct Importer<'a> {
+    strategy: &'a PlacementStrategy,
+    nodes: HashMap<&'a str, &'a NodeDef>,
+    /// Values computed by the nodes imported so far.
+    values: HashMap<String, Imported>,
+    builder: ComputationBuilder<'a>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(strategy: &'a PlacementStrategy, graph: &'a GraphDef) -> Self {
+        Importer {
+            strategy,
+            nodes: graph
+                .node
+                .iter()
+                .map(|node| (node.name.as_str(), node))
+                .collect(),
+            values: HashMap::new(),
+            builder: ComputationBuilder::new(strategy),
+        }
+    }
+
+    /// Import the nodes computing `outputs`, given as pairs of tag and tensor name.
+    fn import(mut self, outputs: &[(String, String)]) -> Result<Computation> {
+        for (tag, tensor) in outputs.iter() {
+            let value = self.value(tensor)?;
+            self.builder.output(tag, value.op_name);
+        }
+        Ok(self.builder.finish())
+    }
+
+    /// Node computing `tensor`, which must be its first output.
+    fn node(&self, tensor: &str) -> Result<&'a NodeDef> {
+        if let Some((_, index)) = tensor.rsplit_once(':') {
+            if index != "0" {
+                return Err(Error::UnimplementedOperator(format!(
+                    "only the first output of TensorFlow nodes is supported, got '{}'",
+                    tensor
+                )));
+            }
+        }
+        self.nodes.get(node_name(tensor)).copied().ok_or_else(|| {
+            Error::InvalidArgument(format!("TensorFlow graph has no node '{}'", tensor))
+        })
+    }
+
+    /// Node computing `tensor`, looking through `Identity` nodes.
+    fn source(&self, tensor: &str) -> Result<&'a NodeDef> {
+        let mut node = self.node(tensor)?;
+        while node.op == "Identity" {
+            node = self.node(operand_name(node, 0)?)?;
+        }
+        Ok(node)
+    }
+
+    /// Value of `tensor`, importing the nodes computing it first if needed.
+    fn value(&mut self, tensor: &str) -> Result<Imported> {
+        let node = self.node(tensor)?;
+        if let Some(value) = self.values.get(&node.name) {
+            return Ok(value.clone());
+        }
+        let value = self.import_node(node)?;
+        self.values.insert(node.name.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn operand(&mut self, node: &NodeDef, index: usize) -> Result<Imported> {
+        self.value(operand_name(node, index)?)
+    }
+
+    fn import_input(&mut self, node: &NodeDef) -> Result<Imported> {
+        match attr(node, "dtype") {
+            Some(attr_value::Value::Type(DT_FLOAT)) | Some(attr_value::Value::Type(DT_DOUBLE)) => {}
+            _ => {
+                return Err(Error::UnimplementedOperator(format!(
+                    "TensorFlow placeholder '{}' must hold floats",
+                    node.name
+                )))
+            }
+        }
+        let shape = match attr(node, "shape") {
+            Some(attr_value::Value::Shape(shape)) if !shape.unknown_rank => Some(
+                shape
+                    .dim
+                    .iter()
+                    .map(|dim| {
+                        if dim.size >= 0 {
+                            Some(dim.size as usize)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+        let op_name = self.builder.input(&node.name);
+        Ok(Imported { op_name, shape })
+    }
+
+    /// Add the weights `values` of shape `dims` as a constant held by the model owner.
+    fn import_weights(
+        &mut self,
+        name: &str,
+        dims: Vec<usize>,
+        values: Vec<f64>,
+    ) -> Result<Imported> {
+        let array = ArrayD::from_shape_vec(IxDyn(&dims), values).map_err(|e| {
+            Error::InvalidArgument(format!(
+                "TensorFlow constant '{}' has inconsistent data: {}",
+                name, e
+            ))
+        })?;
+        let op_name = self.builder.constant(array);
+        Ok(Imported {
+            op_name,
+            shape: Some(dims.into_iter().map(Some).collect()),
+        })
+    }
+
+    fn reshape(&mut self, x: String, shape: Vec<usize>) -> Imported {
+        let ty = self.strategy.compute_ty();
+        let shape_op = self.builder.shape_constant(shape.clone());
+        let op_name = self.builder.compute(
+            "reshape",
+            ReshapeOp {
+                sig: Signature::binary(ty, Ty::Shape(TensorShape::Host), ty),
+            }
+            .into(),
+            vec![x, shape_op],
+        );
+        Imported {
+            op_name,
+            shape: Some(shape.into_iter().map(Some).collect()),
+        }
+    }
+
+    fn import_node(&mut self, node: &'a NodeDef) -> Result<Imported> {
+        let ty = self.strategy.compute_ty();
+        let unary = Signature::unary(ty, ty);
+        let binary = Signature::binary(ty, ty, ty);
+
+        let imported = match node.op.as_str() {
+            "Placeholder" => self.import_input(node)?,
+            "Const" => {
+                let tensor = const_tensor(node).ok_or_else(|| {
+                    Error::InvalidArgument(format!(
+                        "TensorFlow constant '{}' has no value",
+                        node.name
+                    ))
+                })?;
+                self.import_weights(&node.name, tensor_dims(tensor), float_values(tensor)?)?
+            }
+            "Identity" | "StopGradient" => self.operand(node, 0)?,
+            "MatMul" => {
+                let mut operands = Vec::with_capacity(2);
+                for (index, transposed) in [(0, "transpose_a"), (1, "transpose_b")].iter() {
+                    let x = self.operand(node, *index)?;
+                    let x = if attr_bool(node, transposed) {
+                        let op_name = self.builder.compute(
+                            "transpose",
+                            TransposeOp { sig: unary }.into(),
+                            vec![x.op_name],
+                        );
+                        Imported {
+                            op_name,
+                            shape: x.shape.map(|shape| shape.into_iter().rev().collect()),
+                        }
+                    } else {
+                        x
+                    };
+                    operands.push(x);
+                }
+                let op_name = self.builder.compute(
+                    "dot",
+                    DotOp { sig: binary }.into(),
+                    vec![operands[0].op_name.clone(), operands[1].op_name.clone()],
+                );
+                Imported {
+                    op_name,
+                    shape: dot_shape(&operands[0].shape, &operands[1].shape),
+                }
+            }
+            "BiasAdd" | "Add" | "AddV2" | "Sub" | "Mul" | "RealDiv" => {
+                if node.op == "BiasAdd"
+                    && attr_str(node, "data_format").unwrap_or(b"NHWC") != b"NHWC"
+                {
+                    return Err(Error::UnimplementedOperator(format!(
+                        "TensorFlow node '{}' (BiasAdd) only supports the NHWC data format",
+                        node.name
+                    )));
+                }
+                let (x, y) = (self.operand(node, 0)?, self.operand(node, 1)?);
+                let (prefix, kind): (_, Operator) = match node.op.as_str() {
+                    "Sub" => ("sub", SubOp { sig: binary }.into()),
+                    "Mul" => ("mul", MulOp { sig: binary }.into()),
+                    "RealDiv" => ("div", DivOp { sig: binary }.into()),
+                    _ => ("add", AddOp { sig: binary }.into()),
+                };
+                let op_name =
+                    self.builder
+                        .compute(prefix, kind, vec![x.op_name.clone(), y.op_name.clone()]);
+                Imported {
+                    op_name,
+                    shape: broadcast_shape(&x.shape, &y.shape),
+                }
+            }
+            "Relu" | "Sigmoid" | "Exp" | "Neg" | "Abs" | "Sqrt" | "Log" => {
+                let x = self.operand(node, 0)?;
+                let (prefix, kind): (_, Operator) = match node.op.as_str() {
+                    "Relu" => ("relu", ReluOp { sig: unary }.into()),
+                    "Sigmoid" => ("sigmoid", SigmoidOp { sig: unary }.into()),
+                    "Exp" => ("exp", ExpOp { sig: unary }.into()),
+                    "Neg" => ("neg", NegOp { sig: unary }.into()),
+                    "Abs" => ("abs", AbsOp { sig: unary }.into()),
+                    "Sqrt" => ("sqrt", SqrtOp { sig: unary }.into()),
+                    _ => ("log", LogOp { sig: unary }.into()),
+                };
+                let op_name = self.builder.compute(prefix, kind, vec![x.op_name]);
+                Imported {
+                    op_name,
+                    shape: x.shape,
+                }
+            }
+            "Softmax" => {
+                // TensorFlow always normalizes over the last axis
+                let x = self.operand(node, 0)?;
+                let (axis, upmost_index) = match x.shape.as_deref() {
+                    Some(shape @ [.., Some(size)]) => (shape.len() - 1, *size),
+                    _ => return Err(unknown_shape(node, "the size of the last axis")),
+                };
+                let op_name = self.builder.compute(
+                    "softmax",
+                    SoftmaxOp {
+                        sig: unary,
+                        axis,
+                        upmost_index,
+                    }
+                    .into(),
+                    vec![x.op_name],
+                );
+                Imported {
+                    op_name,
+                    shape: x.shape,
+                }
+            }
+            "Reshape" => {
+                let x = self.operand(node, 0)?;
+                let target = const_tensor(self.source(operand_name(node, 1)?)?)
+                    .ok_or_else(|| {
+                        Error::UnimplementedOperator(format!(
+                            "TensorFlow node '{}' (Reshape) only supports constant shapes",
+                            node.name
+                        ))
+                    })
+                    .and_then(int_values)?;
+                let shape = resolve_shape(node, &x.shape, &target)?;
+                self.reshape(x.op_name, shape)
+            }
+            "Conv2D" => self.import_conv2d(node)?,
+            op => {
+                return Err(Error::UnimplementedOperator(format!(
+                    "TensorFlow operation '{}' of node '{}' is not supported",
+                    op, node.name
+                )))
+            }
+        };
+        Ok(imported)
+    }
+
+    /// Convolution with `VALID` padding, lowered to a product of each patch of the
+    /// input with the filter, flattened into a matrix.
+    fn import_conv2d(&mut self, node: &'a NodeDef) -> Result<Imported> {
+        let unsupported = |what: &str| {
+            Error::UnimplementedOperator(format!(
+                "TensorFlow node '{}' (Conv2D) only supports {}",
+                node.name, what
+            ))
+        };
+        if attr_str(node, "data_format").unwrap_or(b"NHWC") != b"NHWC" {
+            return Err(unsupported("the NHWC data format"));
+        }
+        if attr_str(node, "padding") != Some(&b"VALID"[..]) {
+            return Err(unsupported("VALID padding"));
+        }
+        if attr_ints(node, "dilations").map_or(false, |dilations| dilations.iter().any(|d| *d != 1))
+        {
+            return Err(unsupported("dilations of 1"));
+        }
+        let (stride_h, stride_w) = match attr_ints(node, "strides") {
+            None => (1, 1),
+            Some([1, h, w, 1]) if *h > 0 && *w > 0 => (*h as usize, *w as usize),
+            Some(_) => return Err(unsupported("positive strides along the spatial axes")),
+        };
+
+        let x = self.operand(node, 0)?;
+        let (batch, height, width, channels) = match x.shape.as_deref() {
+            Some([Some(n), Some(h), Some(w), Some(c)]) => (*n, *h, *w, *c),
+            _ => return Err(unknown_shape(node, "the shape of the input")),
+        };
+        let filter = const_tensor(self.source(operand_name(node, 1)?)?)
+            .ok_or_else(|| unsupported("constant filters"))?;
+        let (filter_h, filter_w, filters) = match tensor_dims(filter).as_slice() {
+            [h, w, c, o] if *c == channels && *h <= height && *w <= width => (*h, *w, *o),
+            dims => {
+                return Err(Error::InvalidArgument(format!(
+                    "TensorFlow node '{}' (Conv2D) has filter of shape {:?} for input {:?}",
+                    node.name,
+                    dims,
+                    [batch, height, width, channels]
+                )))
+            }
+        };
+        // row-major order of the filter matches that of the flattened patches
+        let patch_size = filter_h * filter_w * channels;
+        let kernel =
+            self.import_weights(&node.name, vec![patch_size, filters], float_values(filter)?)?;
+
+        let ty = self.strategy.compute_ty();
+        let out_h = (height - filter_h) / stride_h + 1;
+        let out_w = (width - filter_w) / stride_w + 1;
+        let full = || SliceInfoElem {
+            start: 0,
+            end: None,
+            step: None,
+        };
+        let range = |start: usize, size: usize| SliceInfoElem {
+            start: start as isize,
+            end: Some((start + size) as isize),
+            step: None,
+        };
+        let mut columns = Vec::with_capacity(out_h * out_w);
+        for i in 0..out_h {
+            for j in 0..out_w {
+                let slice = SliceInfo(vec![
+                    full(),
+                    range(i * stride_h, filter_h),
+                    range(j * stride_w, filter_w),
+                    full(),
+                ]);
+                let patch = self.builder.compute(
+                    "slice",
+                    SliceOp {
+                        sig: Signature::unary(ty, ty),
+                        slice,
+                    }
+                    .into(),
+                    vec![x.op_name.clone()],
+                );
+                let patch = self.reshape(patch, vec![batch, patch_size]);
+                columns.push(
+                    self.builder.compute(
+                        "dot",
+                        DotOp {
+                            sig: Signature::binary(ty, ty, ty),
+                        }
+                        .into(),
+                        vec![patch.op_name, kernel.op_name.clone()],
+                    ),
+                );
+            }
+        }
+        // concatenating the results for all positions yields the output in NHWC order
+        let concatenated = match columns.len() {
+            1 => columns.pop().unwrap(),
+            _ => self.builder.compute(
+                "concat",
+                ConcatOp {
+                    sig: Signature::variadic(ty, ty),
+                    axis: 1,
+                }
+                .into(),
+                columns,
+            ),
+        };
+        Ok(self.reshape(concatenated, vec![batch, out_h, out_w, filters]))
+    }
+}
+
+/// Name of the node computing `tensor`.
+fn node_name(tensor: &str) -> &str {
+    let tensor = tensor.trim_start_matches('^');
+    match tensor.rsplit_once(':') {
+        Some((name, _)) => name,
+        None => tensor,
+    }
+}
+
+/// Name of the tensor given as input `index` of `node`, skipping control dependencies.
+fn operand_name(node: &NodeDef, index: usize) -> Result<&str> {
+    node.input
+        .iter()
+        .filter(|input| !input.starts_with('^'))
+        .nth(index)
+        .map(|input| input.as_str())
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "TensorFlow node '{}' ({}) is missing input {}",
+                node.name, node.op, index
+            ))
+        })
+}
+
+fn attr<'n>(node: &'n NodeDef, name: &str) -> Option<&'n attr_value::Value> {
+    node.attr.get(name).and_then(|attr| attr.value.as_ref())
+}
+
+fn attr_bool(node: &NodeDef, name: &str) -> bool {
+    matches!(attr(node, name), Some(attr_value::Value::B(true)))
+}
+
+fn attr_str<'n>(node: &'n NodeDef, name: &str) -> Option<&'n [u8]> {
+    match attr(node, name) {
+        Some(attr_value::Value::S(s)) => Some(s.as_slice()),
+        _ => None,
+    }
+}
+
+fn attr_ints<'n>(node: &'n NodeDef, name: &str) -> Option<&'n [i64]> {
+    match attr(node, name) {
+        Some(attr_value::Value::List(list)) => Some(list.i.as_slice()),
+        _ => None,
+    }
+}
+
+fn const_tensor(node: &NodeDef) -> Option<&TensorProto> {
+    match attr(node, "value") {
+        Some(attr_value::Value::Tensor(tensor)) if node.op == "Const" => Some(tensor),
+        _ => None,
+    }
+}
+
+fn unknown_shape(node: &NodeDef, what: &str) -> Error {
+    Error::InvalidArgument(format!(
+        "cannot determine {} of TensorFlow node '{}' ({}) from the shapes of the placeholders",
+        what, node.name, node.op
+    ))
+}
+
+/// Shape to reshape a value of shape `shape` into, inferring any `-1` in `target`.
+fn resolve_shape(node: &NodeDef, shape: &Shape, target: &[i64]) -> Result<Vec<usize>> {
+    let inferred: Vec<usize> = target
+        .iter()
+        .enumerate()
+        .filter(|(_, dim)| **dim < 0)
+        .map(|(i, _)| i)
+        .collect();
+    let mut dims: Vec<usize> = target.iter().map(|dim| (*dim).max(0) as usize).collect();
+    match inferred.as_slice() {
+        [] => Ok(dims),
+        [index] => {
+            let size: usize = shape
+                .as_ref()
+                .and_then(|shape| shape.iter().copied().product::<Option<usize>>())
+                .ok_or_else(|| unknown_shape(node, "the size of the input"))?;
+            let known: usize = dims
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i != index)
+                .map(|(_, dim)| dim)
+                .product();
+            if known == 0 || size % known != 0 {
+                return Err(Error::InvalidArgument(format!(
+                    "TensorFlow node '{}' (Reshape) cannot reshape {} entries into {:?}",
+                    node.name, size, target
+                )));
+            }
+            dims[*index] = size / known;
+            Ok(dims)
+        }
+        _ => Err(Error::InvalidArgument(format!(
+            "TensorFlow node '{}' (Reshape) may only infer one dimension, got {:?}",
+            node.name, target
+        ))),
+    }
+}
+
+fn tensor_dims(tensor: &TensorProto) -> Vec<usize> {
+    tensor
+        .tensor_shape
+        .as_ref()
+        .map(|shape| {
+            shape
+                .dim
+                .iter()
+                .map(|dim| dim.size.max(0) as usize)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Entries of a tensor, where a single given entry is repeated to fill the shape.
+fn fill<T: Clone>(tensor: &TensorProto, values: Vec<T>) -> Vec<T> {
+    let size: usize = tensor_dims(tensor).iter().product();
+    match values.as_slice() {
+        [value] if size > 1 => vec![value.clone(); size],
+        _ => values,
+    }
+}
+
+/// Entries of a floating-point tensor, converted to `f64`.
+fn float_values(tensor: &TensorProto) -> Result<Vec<f64>> {
+    let content = &tensor.tensor_content;
+    let values = match tensor.dtype {
+        DT_FLOAT if !content.is_empty() => content
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()) as f64)
+            .collect(),
+        DT_FLOAT => tensor.float_val.iter().map(|x| *x as f64).collect(),
+        DT_DOUBLE if !content.is_empty() => content
+            .chunks_exact(8)
+            .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect(),
+        DT_DOUBLE => tensor.double_val.clone(),
+        dtype => {
+            return Err(Error::UnimplementedOperator(format!(
+                "TensorFlow tensors of data type {} are not supported as weights",
+                dtype
+            )))
+        }
+    };
+    Ok(fill(tensor, values))
+}
+
+/// Entries of an integer tensor, such as a shape.
+fn int_values(tensor: &TensorProto) -> Result<Vec<i64>> {
+    let content = &tensor.tensor_content;
+    let values = match tensor.dtype {
+        DT_INT32 if !content.is_empty() => content
+            .chunks_exact(4)
+            .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()) as i64)
+            .collect(),
+        DT_INT32 => tensor.int_val.iter().map(|x| *x as i64).collect(),
+        DT_INT64 if !content.is_empty() => content
+            .chunks_exact(8)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect(),
+        DT_INT64 => tensor.int64_val.clone(),
+        dtype => {
+            return Err(Error::InvalidArgument(format!(
+                "expected TensorFlow tensor of integers, got data type {}",
+                dtype
+            )))
+        }
+    };
+    Ok(fill(tensor, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proto::*;
+    use super::*;
+    use crate::host::HostPlacement;
+    use crate::replicated::ReplicatedPlacement;
+
+    fn shape(dims: &[i64]) -> TensorShapeProto {
+        TensorShapeProto {
+            dim: dims
+                .iter()
+                .map(|size| tensor_shape_proto::Dim {
+                    size: *size,
+                    name: String::new(),
+                })
+                .collect(),
+            unknown_rank: false,
+        }
+    }
+
+    fn value(value: attr_value::Value) -> AttrValue {
+        AttrValue { value: Some(value) }
+    }
+
+    fn node(
+        name: &str,
+        op: &str,
+        inputs: &[&str],
+        attrs: Vec<(&str, attr_value::Value)>,
+    ) -> NodeDef {
+        NodeDef {
+            name: name.to_string(),
+            op: op.to_string(),
+            input: inputs.iter().map(|input| input.to_string()).collect(),
+            device: String::new(),
+            attr: attrs
+                .into_iter()
+                .map(|(key, attr)| (key.to_string(), value(attr)))
+                .collect(),
+        }
+    }
+
+    fn floats(name: &str, dims: &[i64], values: &[f32]) -> NodeDef {
+        let tensor = TensorProto {
+            dtype: DT_FLOAT,
+            tensor_shape: Some(shape(dims)),
+            float_val: values.to_vec(),
+            ..Default::default()
+        };
+        node(
+            name,
+            "Const",
+            &[],
+            vec![("value", attr_value::Value::Tensor(tensor))],
+        )
+    }
+
+    fn ints(name: &str, values: &[i32]) -> NodeDef {
+        let tensor = TensorProto {
+            dtype: DT_INT32,
+            tensor_shape: Some(shape(&[values.len() as i64])),
+            int_val: values.to_vec(),
+            ..Default::default()
+        };
+        node(
+            name,
+            "Const",
+            &[],
+            vec![("value", attr_value::Value::Tensor(tensor))],
+        )
+    }
+
+    /// Convolutional network computing `softmax(relu(flatten(conv(x, k)) W + b))`.
+    fn graph() -> GraphDef {
+        GraphDef {
+            node: vec![
+                node(
+                    "x",
+                    "Placeholder",
+                    &[],
+                    vec![
+                        ("dtype", attr_value::Value::Type(DT_FLOAT)),
+                        ("shape", attr_value::Value::Shape(shape(&[1, 3, 3, 1]))),
+                    ],
+                ),
+                // the filter of ones sums each 2x2 patch
+                floats("k", &[2, 2, 1, 1], &[1.0]),
+                node("k/read", "Identity", &["k"], vec![]),
+                node(
+                    "conv",
+                    "Conv2D",
+                    &["x", "k/read"],
+                    vec![
+                        ("padding", attr_value::Value::S(b"VALID".to_vec())),
+                        (
+                            "strides",
+                            attr_value::Value::List(attr_value::ListValue {
+                                i: vec![1, 1, 1, 1],
+                                ..Default::default()
+                            }),
+                        ),
+                    ],
+                ),
+                ints("flat_shape", &[-1, 4]),
+                node("flat", "Reshape", &["conv", "flat_shape"], vec![]),
+                floats("w", &[4, 2], &[1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0]),
+                node("dense", "MatMul", &["flat", "w"], vec![]),
+                floats("b", &[2], &[0.5, -0.5]),
+                node("dense/bias", "BiasAdd", &["dense", "b", "^k"], vec![]),
+                node("relu", "Relu", &["dense/bias"], vec![]),
+                node("y", "Softmax", &["relu"], vec![]),
+                // not needed for computing the output
+                node(
+                    "loss",
+                    "SparseSoftmaxCrossEntropyWithLogits",
+                    &["relu"],
+                    vec![],
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_import_frozen_graph() {
+        let alice = HostPlacement::from("alice");
+        let graph = graph().encode_to_vec();
+        let comp = import_frozen_graph(&graph, &["y:0"], &PlacementStrategy::Host(alice)).unwrap();
+        let count = |kind: &str| {
+            comp.operations
+                .iter()
+                .filter(|op| op.kind.short_name() == kind)
+                .count()
+        };
+        // one slice and product for each of the 2x2 output positions
+        assert_eq!(count("Slice"), 4);
+        assert_eq!(count("Dot"), 5);
+        assert_eq!(count("Concat"), 1);
+        assert_eq!(count("Input"), 1);
+        assert_eq!(count("Output"), 1);
+
+        let kernel = comp
+            .operations
+            .iter()
+            .find_map(|op| match &op.kind {
+                Operator::Constant(ConstantOp {
+                    value: Constant::HostFloat64Tensor(x),
+                    ..
+                }) if x.0.shape() == [4, 1] => Some(x.0.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(kernel.iter().all(|x| *x == 1.0));
+
+        let reshapes: Vec<Vec<usize>> = comp
+            .operations
+            .iter()
+            .filter_map(|op| match &op.kind {
+                Operator::Constant(ConstantOp {
+                    value: Constant::RawShape(shape),
+                    ..
+                }) => Some(shape.0.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reshapes.last().unwrap(), &vec![1, 4]);
+        assert!(comp.operations.iter().any(|op| matches!(
+            op.kind,
+            Operator::Softmax(SoftmaxOp {
+                axis: 1,
+                upmost_index: 2,
+                ..
+            })
+        )));
+    }
+
+    #[test]
+    fn test_import_saved_model() {
+        let mut signature_def = SignatureDef::default();
+        signature_def.outputs.insert(
+            "probabilities".to_string(),
+            proto::TensorInfo {
+                name: "y:0".to_string(),
+                ..Default::default()
+            },
+        );
+        let model = SavedModel {
+            saved_model_schema_version: 1,
+            meta_graphs: vec![MetaGraphDef {
+                graph_def: Some(graph()),
+                signature_def: vec![("serving_default".to_string(), signature_def)]
+                    .into_iter()
+                    .collect(),
+            }],
+        }
+        .encode_to_vec();
+        let strategy = PlacementStrategy::replicated(
+            HostPlacement::from("alice"),
+            HostPlacement::from("bob"),
+            HostPlacement::from("alice"),
+            ReplicatedPlacement::from(["alice", "bob", "carole"]),
+        );
+        let comp = import_saved_model(&model, "serving_default", &strategy).unwrap();
+        assert!(comp.operations.iter().any(|op| matches!(
+            &op.kind,
+            Operator::Output(OutputOp { tag, .. }) if tag == "probabilities"
+        )));
+        assert!(comp
+            .operations
+            .iter()
+            .filter(|op| op.name.starts_with("slice"))
+            .all(|op| matches!(op.placement, Placement::Replicated(_))));
+
+        let err = import_saved_model(&model, "predict", &strategy).unwrap_err();
+        assert!(err.to_string().contains("'predict'"));
+    }
+
+    #[test]
+    fn test_unsupported_operation() {
+        let graph = graph().encode_to_vec();
+        let err = import_frozen_graph(
+            &graph,
+            &["loss"],
+            &PlacementStrategy::Host(HostPlacement::from("alice")),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("'SparseSoftmaxCrossEntropyWithLogits'"));
+    }
+}
@@ -0,0 +1,329 @@
+//! Utilities for property-testing kernels.
+//!
+//! Provides [proptest](https://docs.rs/proptest) strategies for generating
+//! host, fixed-point, and replicated tensors with constrained shapes and
+//! element ranges, as well as assertions comparing tensors up to a tolerance
+//! suitable for fixed-point arithmetic. Requires the `testing` feature.
+//!
+//! ```ignore
+//! use moose::testing::*;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn test_neg(x in host_float64_tensor(HostPlacement::from("alice"), shape(2, 5), -100.0..100.0)) {
+//!         let y = ...;
+//!         assert_close(&y.0, &x.0.mapv(|v| -v), 0.0);
+//!     }
+//! }
+//! ```
+
+use crate::computation::Value;
+use crate::host::{Convert, FromRaw, HostPlacement, HostTensor};
+use crate::types::{
+    HostBitTensor, HostFixed128Tensor, HostFixed64Tensor, HostFloat32Tensor, HostFloat64Tensor,
+    HostInt64Tensor, HostRing128Tensor, HostRing64Tensor,
+};
+use ndarray::{ArrayD, IxDyn};
+use proptest::prelude::*;
+use std::fmt::Debug;
+use std::ops::Range;
+
+/// Shapes of rank `1..=max_rank` with dimensions of length `1..=max_dim`.
+pub fn shape(max_rank: usize, max_dim: usize) -> impl Strategy<Value = Vec<usize>> {
+    proptest::collection::vec(1..=max_dim.max(1), 1..=max_rank.max(1))
+}
+
+/// Arrays of the shapes given by `shape`, with elements given by `elements`.
+pub fn array<T, S, E>(shape: S, elements: E) -> impl Strategy<Value = ArrayD<T>>
+where
+    T: Debug + Clone,
+    S: Strategy<Value = Vec<usize>>,
+    E: Strategy<Value = T> + Clone,
+{
+    shape.prop_flat_map(move |shape| {
+        let len = shape.iter().product();
+        proptest::collection::vec(elements.clone(), len)
+            .prop_map(move |values| ArrayD::from_shape_vec(IxDyn(&shape), values).unwrap())
+    })
+}
+
+/// Host tensors on `plc` with elements given by `elements`.
+pub fn host_tensor<T, S, E>(
+    plc: HostPlacement,
+    shape: S,
+    elements: E,
+) -> impl Strategy<Value = HostTensor<T>>
+where
+    T: Debug + Clone,
+    S: Strategy<Value = Vec<usize>>,
+    E: Strategy<Value = T> + Clone,
+{
+    array(shape, elements).prop_map(move |x| plc.from_raw(x))
+}
+
+pub fn host_float64_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+    range: Range<f64>,
+) -> impl Strategy<Value = HostFloat64Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    host_tensor(plc, shape, range)
+}
+
+pub fn host_float32_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+    range: Range<f32>,
+) -> impl Strategy<Value = HostFloat32Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    host_tensor(plc, shape, range)
+}
+
+pub fn host_int64_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+    range: Range<i64>,
+) -> impl Strategy<Value = HostInt64Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    host_tensor(plc, shape, range)
+}
+
+/// Ring tensors with uniformly random elements.
+pub fn host_ring64_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+) -> impl Strategy<Value = HostRing64Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    array(shape, any::<u64>()).prop_map(move |x| plc.from_raw(x))
+}
+
+/// Ring tensors with uniformly random elements.
+pub fn host_ring128_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+) -> impl Strategy<Value = HostRing128Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    array(shape, any::<u128>()).prop_map(move |x| plc.from_raw(x))
+}
+
+pub fn host_bit_tensor<S>(plc: HostPlacement, shape: S) -> impl Strategy<Value = HostBitTensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    array(shape, 0u8..2).prop_map(move |x| plc.from_raw(x))
+}
+
+/// Fixed-point tensors encoding values from `range`.
+///
+/// The range should fit the integral precision, since values outside of it wrap around.
+pub fn host_fixed64_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+    range: Range<f64>,
+    integral_precision: u32,
+    fractional_precision: u32,
+) -> impl Strategy<Value = HostFixed64Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    host_float64_tensor(plc, shape, range).prop_map(move |x| HostFixed64Tensor {
+        tensor: HostRing64Tensor::encode(&x, 1u64 << fractional_precision),
+        fractional_precision,
+        integral_precision,
+    })
+}
+
+/// Fixed-point tensors encoding values from `range`.
+///
+/// The range should fit the integral precision, since values outside of it wrap around.
+pub fn host_fixed128_tensor<S>(
+    plc: HostPlacement,
+    shape: S,
+    range: Range<f64>,
+    integral_precision: u32,
+    fractional_precision: u32,
+) -> impl Strategy<Value = HostFixed128Tensor>
+where
+    S: Strategy<Value = Vec<usize>>,
+{
+    host_float64_tensor(plc, shape, range).prop_map(move |x| HostFixed128Tensor {
+        tensor: HostRing128Tensor::encode(&x, 1u128 << fractional_precision),
+        fractional_precision,
+        integral_precision,
+    })
+}
+
+/// Host values of any of the common tensor types, on `plc`.
+pub fn host_value(plc: HostPlacement) -> impl Strategy<Value = Value> {
+    let shapes = || shape(3, 4);
+    prop_oneof![
+        host_float32_tensor(plc.clone(), shapes(), -1e3..1e3).prop_map(Value::from),
+        host_float64_tensor(plc.clone(), shapes(), -1e6..1e6).prop_map(Value::from),
+        host_int64_tensor(plc.clone(), shapes(), i64::MIN..i64::MAX).prop_map(Value::from),
+        host_ring64_tensor(plc.clone(), shapes()).prop_map(Value::from),
+        host_ring128_tensor(plc.clone(), shapes()).prop_map(Value::from),
+        host_bit_tensor(plc, shapes()).prop_map(Value::from),
+    ]
+}
+
+#[cfg(feature = "sync_execute")]
+mod replicated {
+    use super::*;
+    use crate::execution::SyncSession;
+    use crate::kernels::PlacementShare;
+    use crate::replicated::ReplicatedPlacement;
+    use crate::types::{ReplicatedFixed128Tensor, ReplicatedRing128Tensor, ReplicatedRing64Tensor};
+
+    /// Secret shares of uniformly random ring tensors.
+    pub fn replicated_ring64_tensor<S>(
+        rep: ReplicatedPlacement,
+        shape: S,
+    ) -> impl Strategy<Value = ReplicatedRing64Tensor>
+    where
+        S: Strategy<Value = Vec<usize>>,
+    {
+        let owner = HostPlacement {
+            owner: rep.owners[0].clone(),
+        };
+        host_ring64_tensor(owner, shape).prop_map(move |x| rep.share(&SyncSession::default(), &x))
+    }
+
+    /// Secret shares of uniformly random ring tensors.
+    pub fn replicated_ring128_tensor<S>(
+        rep: ReplicatedPlacement,
+        shape: S,
+    ) -> impl Strategy<Value = ReplicatedRing128Tensor>
+    where
+        S: Strategy<Value = Vec<usize>>,
+    {
+        let owner = HostPlacement {
+            owner: rep.owners[0].clone(),
+        };
+        host_ring128_tensor(owner, shape).prop_map(move |x| rep.share(&SyncSession::default(), &x))
+    }
+
+    /// Secret shares of fixed-point tensors encoding values from `range`.
+    pub fn replicated_fixed128_tensor<S>(
+        rep: ReplicatedPlacement,
+        shape: S,
+        range: Range<f64>,
+        integral_precision: u32,
+        fractional_precision: u32,
+    ) -> impl Strategy<Value = ReplicatedFixed128Tensor>
+    where
+        S: Strategy<Value = Vec<usize>>,
+    {
+        let owner = HostPlacement {
+            owner: rep.owners[0].clone(),
+        };
+        host_fixed128_tensor(
+            owner,
+            shape,
+            range,
+            integral_precision,
+            fractional_precision,
+        )
+        .prop_map(move |x| rep.share(&SyncSession::default(), &x))
+    }
+}
+
+#[cfg(feature = "sync_execute")]
+pub use replicated::*;
+
+/// Absolute error introduced by encoding a value with `fractional_precision` bits.
+pub fn fixedpoint_tolerance(fractional_precision: u32) -> f64 {
+    1.0 / (1u128 << fractional_precision) as f64
+}
+
+/// Values encoded by a fixed-point tensor.
+pub fn decode_fixed64(x: &HostFixed64Tensor) -> ArrayD<f64> {
+    HostRing64Tensor::decode(&x.tensor, 1u64 << x.fractional_precision)
+        .0
+        .to_owned()
+}
+
+/// Values encoded by a fixed-point tensor.
+pub fn decode_fixed128(x: &HostFixed128Tensor) -> ArrayD<f64> {
+    HostRing128Tensor::decode(&x.tensor, 1u128 << x.fractional_precision)
+        .0
+        .to_owned()
+}
+
+/// Assert that `actual` and `expected` have the same shape and differ by at
+/// most `tolerance` in every element.
+#[track_caller]
+pub fn assert_close(actual: &ArrayD<f64>, expected: &ArrayD<f64>, tolerance: f64) {
+    assert_eq!(
+        actual.shape(),
+        expected.shape(),
+        "shapes differ: {:?} vs {:?}",
+        actual,
+        expected
+    );
+    for ((index, a), e) in actual.indexed_iter().zip(expected.iter()) {
+        assert!(
+            (a - e).abs() <= tolerance,
+            "elements at {:?} differ by more than {}: {} vs {}",
+            index.slice(),
+            tolerance,
+            a,
+            e
+        );
+    }
+}
+
+/// Assert that the values encoded by `actual` are within `ulps` units of the
+/// last place of `expected`, given the fractional precision of `actual`.
+#[track_caller]
+pub fn assert_fixed_close(actual: &HostFixed128Tensor, expected: &ArrayD<f64>, ulps: u32) {
+    let tolerance = ulps as f64 * fixedpoint_tolerance(actual.fractional_precision);
+    assert_close(&decode_fixed128(actual), expected, tolerance);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::test_runner::TestRunner;
+
+    #[test]
+    fn test_host_fixed128_tensor() {
+        let plc = HostPlacement::from("alice");
+        let strategy = host_fixed128_tensor(plc.clone(), shape(3, 4), -1e3..1e3, 24, 40);
+        TestRunner::default()
+            .run(&strategy, |x| {
+                let decoded = decode_fixed128(&x);
+                prop_assert!(decoded.ndim() >= 1 && decoded.ndim() <= 3);
+                prop_assert!(decoded.iter().all(|v| (-1e3..=1e3).contains(v)));
+                prop_assert_eq!(x.tensor.1, plc.clone());
+                Ok(())
+            })
+            .unwrap();
+
+        let x: HostFloat64Tensor = plc.from_raw(ndarray::array![1.5, -2.25]);
+        let fixed = HostFixed128Tensor {
+            tensor: HostRing128Tensor::encode(&x, 1u128 << 20),
+            fractional_precision: 20,
+            integral_precision: 10,
+        };
+        assert_fixed_close(&fixed, &x.0.to_owned(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "differ by more than")]
+    fn test_assert_close() {
+        let x = ndarray::array![1.0, 2.0].into_dyn();
+        let y = ndarray::array![1.0, 2.1].into_dyn();
+        assert_close(&x, &y, 0.05);
+    }
+}
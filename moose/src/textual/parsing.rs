@@ -48,6 +48,16 @@ impl TryFrom<&str> for Value {
     }
 }
 
+impl TryFrom<&str> for Operator {
+    type Error = anyhow::Error;
+
+    fn try_from(source: &str) -> anyhow::Result<Operator> {
+        all_consuming(ws(parse_operator::<VerboseError<&str>>))(source)
+            .map(|(_, op)| op)
+            .map_err(|e| friendly_error("Failed to parse operator", source, e))
+    }
+}
+
 impl FromStr for Value {
     type Err = anyhow::Error;
     fn from_str(source: &str) -> Result<Self, Self::Err> {
@@ -352,6 +362,50 @@ impl<'a, E: 'a + ParseError<&'a str> + ContextError<&'a str>> FromTextual<'a, E>
     }
 }
 
+impl<'a, E: 'a + ParseError<&'a str> + ContextError<&'a str>> FromTextual<'a, E>
+    for LaplaceNoiseOp
+{
+    fn from_textual(input: &'a str) -> IResult<&'a str, Operator, E> {
+        let (input, (epsilon, sensitivity)) = attributes!((
+            attributes_member("epsilon", double),
+            attributes_member("sensitivity", double),
+        ))(input)?;
+        let (input, sig) = operator_signature(1)(input)?;
+        Ok((
+            input,
+            LaplaceNoiseOp {
+                sig,
+                epsilon,
+                sensitivity,
+            }
+            .into(),
+        ))
+    }
+}
+
+impl<'a, E: 'a + ParseError<&'a str> + ContextError<&'a str>> FromTextual<'a, E>
+    for GaussianNoiseOp
+{
+    fn from_textual(input: &'a str) -> IResult<&'a str, Operator, E> {
+        let (input, (epsilon, delta, sensitivity)) = attributes!((
+            attributes_member("epsilon", double),
+            attributes_member("delta", double),
+            attributes_member("sensitivity", double),
+        ))(input)?;
+        let (input, sig) = operator_signature(1)(input)?;
+        Ok((
+            input,
+            GaussianNoiseOp {
+                sig,
+                epsilon,
+                delta,
+                sensitivity,
+            }
+            .into(),
+        ))
+    }
+}
+
 /// Parses list of arguments.
 ///
 /// Accepts input in the form of
@@ -524,6 +578,10 @@ fn parse_tensor_dtype<'a, E: 'a + ParseError<&'a str> + ContextError<&'a str>>(
         value(TensorDType::Float32, tag(TensorDType::Float32.short_name())),
         value(TensorDType::Float64, tag(TensorDType::Float64.short_name())),
         value(TensorDType::Bool, tag(TensorDType::Bool.short_name())),
+        value(TensorDType::Uint64, tag(TensorDType::Uint64.short_name())),
+        value(TensorDType::Uint8, tag(TensorDType::Uint8.short_name())),
+        value(TensorDType::Int8, tag(TensorDType::Int8.short_name())),
+        value(TensorDType::Int16, tag(TensorDType::Int16.short_name())),
         preceded(
             tag(TensorDType::Fixed64 {
                 integral_precision: 0,
@@ -1218,6 +1276,7 @@ impl ToTextual for Operator {
             Squeeze(op) => op.to_textual(),
             Transpose(op) => op.to_textual(),
             Dot(op) => op.to_textual(),
+            Conv2D(op) => op.to_textual(),
             Inverse(op) => op.to_textual(),
             Add(op) => op.to_textual(),
             Sub(op) => op.to_textual(),
@@ -1275,6 +1334,11 @@ impl ToTextual for Operator {
             Mirror(op) => op.to_textual(),
             Maximum(op) => op.to_textual(),
             Argmax(op) => op.to_textual(),
+            GaussianNoise(op) => op.to_textual(),
+            LaplaceNoise(op) => op.to_textual(),
+            Commit(op) => op.to_textual(),
+            OpenCommitment(op) => op.to_textual(),
+            VerifyCommitment(op) => op.to_textual(),
         }
     }
 }
@@ -1367,6 +1431,29 @@ impl ToTextual for SampleSeededOp {
     }
 }
 
+impl ToTextual for LaplaceNoiseOp {
+    fn to_textual(&self) -> String {
+        format!(
+            "LaplaceNoise{{epsilon = {:?}, sensitivity = {:?}}}: {}",
+            self.epsilon,
+            self.sensitivity,
+            self.sig.to_textual()
+        )
+    }
+}
+
+impl ToTextual for GaussianNoiseOp {
+    fn to_textual(&self) -> String {
+        format!(
+            "GaussianNoise{{epsilon = {:?}, delta = {:?}, sensitivity = {:?}}}: {}",
+            self.epsilon,
+            self.delta,
+            self.sensitivity,
+            self.sig.to_textual()
+        )
+    }
+}
+
 impl ToTextual for Ty {
     fn to_textual(&self) -> String {
         match self {
@@ -1473,6 +1560,9 @@ impl ToTextual for Value {
             | Value::Float32Tensor(_)
             | Value::Float64Tensor(_)
             | Value::Uint64Tensor(_)
+            | Value::Uint8Tensor(_)
+            | Value::Int8Tensor(_)
+            | Value::Int16Tensor(_)
             | Value::ReplicatedShape(_)
             | Value::ReplicatedBitTensor(_)
             | Value::ReplicatedBitArray64(_)
@@ -1821,6 +1911,8 @@ mod tests {
         }
         let (_, parsed_type) = parse_type::<(&str, ErrorKind)>("Tensor<Float64>")?;
         assert_eq!(parsed_type, Ty::Tensor(TensorDType::Float64));
+        let (_, parsed_type) = parse_type::<(&str, ErrorKind)>("Tensor<Int16>")?;
+        assert_eq!(parsed_type, Ty::Tensor(TensorDType::Int16));
         Ok(())
     }
 
@@ -2095,6 +2187,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_conv2d() -> Result<(), anyhow::Error> {
+        let (_, op) = parse_assignment::<(&str, ErrorKind)>(
+            "y = Conv2D{stride = 2, padding = 1}: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)",
+        )?;
+        assert_eq!(
+            op.kind,
+            Operator::Conv2D(Conv2DOp {
+                sig: Signature::binary(
+                    Ty::Tensor(TensorDType::Float64),
+                    Ty::Tensor(TensorDType::Float64),
+                    Ty::Tensor(TensorDType::Float64)
+                ),
+                stride: 2,
+                padding: 1,
+            })
+        );
+        let (_, parsed) = parse_assignment::<(&str, ErrorKind)>(&op.to_textual())?;
+        assert_eq!(parsed, op);
+        Ok(())
+    }
+
+    #[test]
+    fn test_noise() -> Result<(), anyhow::Error> {
+        let (_, op) = parse_assignment::<(&str, ErrorKind)>(
+            "z = LaplaceNoise{epsilon = 0.5, sensitivity = 2.0}: (Tensor<Float64>) -> Tensor<Float64> (x) @Host(alice)",
+        )?;
+        assert_eq!(
+            op.kind,
+            Operator::LaplaceNoise(LaplaceNoiseOp {
+                sig: Signature::unary(
+                    Ty::Tensor(TensorDType::Float64),
+                    Ty::Tensor(TensorDType::Float64)
+                ),
+                epsilon: 0.5,
+                sensitivity: 2.0,
+            })
+        );
+        let (_, parsed) = parse_assignment::<(&str, ErrorKind)>(&op.to_textual())?;
+        assert_eq!(parsed, op);
+
+        let (_, op) = parse_assignment::<(&str, ErrorKind)>(
+            "z = GaussianNoise{sensitivity = 1.0, epsilon = 0.5, delta = 1e-5}: (ReplicatedFixed128Tensor) -> ReplicatedFixed128Tensor (x) @Replicated(alice, bob, carole)",
+        )?;
+        let (_, parsed) = parse_assignment::<(&str, ErrorKind)>(&op.to_textual())?;
+        assert_eq!(parsed, op);
+        match op.kind {
+            Operator::GaussianNoise(op) => assert_eq!(op.delta, 1e-5),
+            _ => panic!("expected a GaussianNoise operator"),
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_various() -> Result<(), anyhow::Error> {
         // The following tests are verifying that each valid line is parsed successfuly.
@@ -9,7 +9,7 @@ use crate::host::{
     AbstractHostAesKey, HostBitArray, HostFixedAesTensor, HostFixedTensor, HostRingTensor,
     HostTensor,
 };
-use crate::integer::AbstractUint64Tensor;
+use crate::integer::{AbstractSmallIntTensor, AbstractUint64Tensor};
 use crate::logical::{AbstractShape, AbstractTensor};
 use crate::mirrored::{Mir3Tensor, MirFixedTensor};
 use crate::replicated::{
@@ -29,6 +29,9 @@ pub type Tensor = AbstractTensor<
     Float64Tensor,
     BooleanTensor,
     Uint64Tensor,
+    Uint8Tensor,
+    Int8Tensor,
+    Int16Tensor,
 >;
 
 pub type Shape = AbstractShape<HostShape, ReplicatedShape>;
@@ -58,6 +61,10 @@ moose_type!(ReplicatedBitTensor = RepTensor<HostBitTensor>);
 moose_type!(ReplicatedUint64Tensor = RepUintTensor<ReplicatedRing64Tensor>);
 moose_type!(HostUint64Tensor = [atomic] HostTensor<u64>);
 moose_type!(Uint64Tensor = AbstractUint64Tensor<HostUint64Tensor, ReplicatedUint64Tensor>);
+
+moose_type!(Uint8Tensor = AbstractSmallIntTensor<HostUint8Tensor>);
+moose_type!(Int8Tensor = AbstractSmallIntTensor<HostInt8Tensor>);
+moose_type!(Int16Tensor = AbstractSmallIntTensor<HostInt16Tensor>);
 // Encrypted types
 
 moose_type!(AesTensor = AbstractAesTensor<Fixed128AesTensor>);
@@ -0,0 +1,582 @@
+//! Import of XGBoost models as logical computations.
+//!
+//! Models are read from the JSON format written by `Booster.save_model`, with each
+//! tree of the ensemble translated into comparisons of a feature against the split
+//! condition of each inner node, combined bottom-up by oblivious selection between
+//! the predictions of both children. Every example hence evaluates all nodes of all
+//! trees, such that nothing about the path it takes is revealed when evaluated on
+//! secret shares. Values are placed according to a [`PlacementStrategy`], as for
+//! ONNX models: the split conditions and leaf values are constants held by the model
+//! owner, while the structure of the trees is part of the computation.
+//!
+//! Only tree boosters with numerical splits are supported, for objectives whose
+//! predictions are either the sum of the trees or one of their sigmoid, exponential,
+//! or softmax across classes. Missing features are not supported.
+
+use crate::computation::*;
+use crate::error::{Error, Result};
+use crate::importer::ComputationBuilder;
+use crate::logical::TensorDType;
+use crate::onnx::PlacementStrategy;
+use ndarray::{ArrayD, IxDyn};
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// Name of the argument holding the features, a `Float64` matrix with a row per example.
+pub const FEATURES_ARG: &str = "x";
+
+/// Tag of the output holding the predictions, a vector with an entry per example or,
+/// for multi-class models, a matrix with the probability of each class per example.
+pub const PREDICTIONS_TAG: &str = "y";
+
+// Layout of models saved as JSON, as described here:
+//     https://xgboost.readthedocs.io/en/stable/tutorials/saving_model.html
+// Only the fields needed for inference are read; numbers of the learner parameters
+// are written as strings.
+
+#[derive(Deserialize, Debug)]
+struct Model {
+    learner: Learner,
+}
+
+#[derive(Deserialize, Debug)]
+struct Learner {
+    learner_model_param: LearnerModelParam,
+    gradient_booster: GradientBooster,
+    objective: Objective,
+}
+
+#[derive(Deserialize, Debug)]
+struct LearnerModelParam {
+    base_score: String,
+    num_class: String,
+    num_feature: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GradientBooster {
+    name: String,
+    model: Option<TreeModel>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TreeModel {
+    trees: Vec<Tree>,
+    /// Class each tree contributes to.
+    tree_info: Vec<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Tree {
+    left_children: Vec<i64>,
+    right_children: Vec<i64>,
+    /// Threshold of inner nodes, and prediction of leaves already scaled by the
+    /// learning rate; unlike `base_weights`, which are not.
+    split_conditions: Vec<f64>,
+    split_indices: Vec<usize>,
+    /// Whether splits are numerical (0) or categorical (1).
+    #[serde(default)]
+    split_type: Vec<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Objective {
+    name: String,
+}
+
+/// Mapping of the sum of the trees to predictions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Transform {
+    Identity,
+    Sigmoid,
+    Exp,
+    Softmax,
+}
+
+/// Transform of the predictions of `objective`, along with the margin corresponding
+/// to a base score, which is given in the space of predictions.
+fn objective_transform(objective: &str, base_score: f64) -> Result<(Transform, f64)> {
+    let transform = match objective {
+        "reg:squarederror"
+        | "reg:linear"
+        | "reg:squaredlogerror"
+        | "reg:pseudohubererror"
+        | "reg:absoluteerror" => (Transform::Identity, base_score),
+        "binary:logitraw" => (Transform::Identity, logit(base_score)),
+        "reg:logistic" | "binary:logistic" => (Transform::Sigmoid, logit(base_score)),
+        "count:poisson" | "reg:gamma" | "reg:tweedie" => (Transform::Exp, base_score.ln()),
+        "multi:softprob" => (Transform::Softmax, base_score),
+        "multi:softmax" => {
+            return Err(Error::UnimplementedOperator(
+                "XGBoost objective multi:softmax is not supported, use multi:softprob".to_string(),
+            ));
+        }
+        other => {
+            return Err(Error::UnimplementedOperator(format!(
+                "XGBoost objective '{}' is not supported",
+                other
+            )))
+        }
+    };
+    Ok(transform)
+}
+
+fn logit(p: f64) -> f64 {
+    (p / (1.0 - p)).ln()
+}
+
+fn parse_param<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    // recent versions write the base score as a single-element list
+    value
+        .trim_matches(|c| c == '[' || c == ']')
+        .parse()
+        .map_err(|_| {
+            Error::InvalidArgument(format!(
+                "invalid XGBoost model parameter {}: '{}'",
+                name, value
+            ))
+        })
+}
+
+/// Translate an XGBoost model saved as JSON into a logical computation placed according to `strategy`.
+///
+/// The features are given as the argument named [`FEATURES_ARG`] and the predictions
+/// returned as the output tagged [`PREDICTIONS_TAG`].
+pub fn import_xgboost(model: &[u8], strategy: &PlacementStrategy) -> Result<Computation> {
+    let model: Model = serde_json::from_slice(model)
+        .map_err(|e| Error::InvalidArgument(format!("failed to decode XGBoost model: {}", e)))?;
+    let learner = model.learner;
+    let trees = match (
+        learner.gradient_booster.name.as_str(),
+        learner.gradient_booster.model,
+    ) {
+        ("gbtree", Some(model)) => model,
+        (name, _) => {
+            return Err(Error::UnimplementedOperator(format!(
+                "XGBoost booster '{}' is not supported",
+                name
+            )))
+        }
+    };
+    let params = &learner.learner_model_param;
+    let base_score: f64 = parse_param("base_score", &params.base_score)?;
+    let num_feature: usize = parse_param("num_feature", &params.num_feature)?;
+    let num_class = std::cmp::max(parse_param::<usize>("num_class", &params.num_class)?, 1);
+    let (transform, base_margin) = objective_transform(&learner.objective.name, base_score)?;
+    if num_class > 1 && transform != Transform::Softmax {
+        return Err(Error::InvalidArgument(format!(
+            "XGBoost objective {} does not predict {} classes",
+            learner.objective.name, num_class
+        )));
+    }
+    if trees.trees.len() != trees.tree_info.len() {
+        return Err(Error::InvalidArgument(format!(
+            "XGBoost model has {} trees but classes for {}",
+            trees.trees.len(),
+            trees.tree_info.len()
+        )));
+    }
+
+    let mut importer = Importer::new(strategy, num_feature);
+    let mut scores = Vec::with_capacity(num_class);
+    for class in 0..num_class {
+        let mut predictions = Vec::new();
+        for (index, tree) in trees.trees.iter().enumerate() {
+            if trees.tree_info[index] == class {
+                predictions.push(importer.import_tree(index, tree)?);
+            }
+        }
+        scores.push(importer.sum(predictions, base_margin));
+    }
+    let predictions = importer.transform(transform, scores);
+    importer.builder.output(PREDICTIONS_TAG, predictions);
+    Ok(importer.builder.finish())
+}
+
+/// Step of the traversal of a tree: importing the subtree below a node, or selecting
+/// between the values of both children of a split once these have been imported.
+enum Visit {
+    Node(usize),
+    Split(usize),
+}
+
+struct Importer<'a> {
+    strategy: &'a PlacementStrategy,
+    /// Operation extracting each feature from the features, only added once used.
+    features: Vec<Option<String>>,
+    input: Option<String>,
+    builder: ComputationBuilder<'a>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(strategy: &'a PlacementStrategy, num_feature: usize) -> Self {
+        Importer {
+            strategy,
+            features: vec![None; num_feature],
+            input: None,
+            builder: ComputationBuilder::new(strategy),
+        }
+    }
+
+    /// Add `value` as a scalar constant held by the model owner.
+    fn constant(&mut self, value: f64) -> String {
+        self.builder.constant(ArrayD::from_elem(IxDyn(&[]), value))
+    }
+
+    /// Column `index` of the features, adding the argument holding them if needed.
+    fn feature(&mut self, index: usize) -> Result<String> {
+        match self.features.get(index) {
+            Some(Some(feature)) => return Ok(feature.clone()),
+            Some(None) => {}
+            None => {
+                return Err(Error::InvalidArgument(format!(
+                    "XGBoost model splits on feature {} but has {} features",
+                    index,
+                    self.features.len()
+                )))
+            }
+        }
+        let input = match &self.input {
+            Some(input) => input.clone(),
+            None => {
+                let input = self.builder.input(FEATURES_ARG);
+                self.input = Some(input.clone());
+                input
+            }
+        };
+        let ty = self.strategy.compute_ty();
+        let feature = self.builder.compute(
+            "feature",
+            IndexAxisOp {
+                sig: Signature::unary(ty, ty),
+                axis: 1,
+                index,
+            }
+            .into(),
+            vec![input],
+        );
+        self.features[index] = Some(feature.clone());
+        Ok(feature)
+    }
+
+    /// Prediction of tree `index` for every example.
+    ///
+    /// Nodes are visited in post-order without recursion since trees may be deep.
+    fn import_tree(&mut self, index: usize, tree: &Tree) -> Result<String> {
+        let num_nodes = tree.left_children.len();
+        let invalid =
+            |what: &str| Error::InvalidArgument(format!("XGBoost tree {} has {}", index, what));
+        if tree.right_children.len() != num_nodes
+            || tree.split_conditions.len() != num_nodes
+            || tree.split_indices.len() != num_nodes
+            || num_nodes == 0
+        {
+            return Err(invalid("inconsistent node arrays"));
+        }
+        if tree.split_type.iter().any(|split_type| *split_type != 0) {
+            return Err(Error::UnimplementedOperator(format!(
+                "XGBoost tree {} has categorical splits, which are not supported",
+                index
+            )));
+        }
+        let child = |child: i64| -> Result<usize> {
+            match usize::try_from(child) {
+                Ok(child) if child < num_nodes => Ok(child),
+                _ => Err(invalid(&format!("invalid child {}", child))),
+            }
+        };
+
+        let ty = self.strategy.compute_ty();
+        let bool_ty = Ty::Tensor(TensorDType::Bool);
+        let mut visits = vec![Visit::Node(0)];
+        let mut values: Vec<String> = Vec::new();
+        // every node is visited at most once in a tree
+        let mut visited = vec![false; num_nodes];
+        while let Some(visit) = visits.pop() {
+            match visit {
+                Visit::Node(node) => {
+                    if std::mem::replace(&mut visited[node], true) {
+                        return Err(invalid(&format!("node {} with several parents", node)));
+                    }
+                    if tree.left_children[node] == -1 {
+                        values.push(self.constant(tree.split_conditions[node]));
+                    } else {
+                        let (left, right) = (
+                            child(tree.left_children[node])?,
+                            child(tree.right_children[node])?,
+                        );
+                        visits.push(Visit::Split(node));
+                        visits.push(Visit::Node(right));
+                        visits.push(Visit::Node(left));
+                    }
+                }
+                Visit::Split(node) => {
+                    let right = values.pop().unwrap();
+                    let left = values.pop().unwrap();
+                    let feature = self.feature(tree.split_indices[node])?;
+                    let threshold = self.constant(tree.split_conditions[node]);
+                    // examples go left if their feature is less than the threshold
+                    let selector = self.builder.compute(
+                        "less",
+                        LessOp {
+                            sig: Signature::binary(ty, ty, bool_ty),
+                        }
+                        .into(),
+                        vec![feature, threshold],
+                    );
+                    let selected = self.builder.compute(
+                        "mux",
+                        MuxOp {
+                            sig: Signature::ternary(bool_ty, ty, ty, ty),
+                        }
+                        .into(),
+                        vec![selector, left, right],
+                    );
+                    values.push(selected);
+                }
+            }
+        }
+        let prediction = values.pop().unwrap();
+        if tree.left_children[0] != -1 {
+            return Ok(prediction);
+        }
+        // trees consisting of a single leaf are still held by the model owner, while
+        // variadic operations expect all of their inputs on the compute placement
+        let prediction = self.builder.compute(
+            "identity",
+            IdentityOp {
+                sig: Signature::unary(ty, ty),
+            }
+            .into(),
+            vec![prediction],
+        );
+        Ok(prediction)
+    }
+
+    /// Sum of the predictions of some trees and the base margin.
+    fn sum(&mut self, mut predictions: Vec<String>, base_margin: f64) -> String {
+        let ty = self.strategy.compute_ty();
+        let base_margin = self.constant(base_margin);
+        let sum = match predictions.len() {
+            0 => return base_margin,
+            1 => predictions.remove(0),
+            _ => self.builder.compute(
+                "add_n",
+                AddNOp {
+                    sig: Signature::variadic(ty, ty),
+                }
+                .into(),
+                predictions,
+            ),
+        };
+        self.builder.compute(
+            "add",
+            AddOp {
+                sig: Signature::binary(ty, ty, ty),
+            }
+            .into(),
+            vec![sum, base_margin],
+        )
+    }
+
+    /// Predictions given the scores of each class.
+    fn transform(&mut self, transform: Transform, mut scores: Vec<String>) -> String {
+        let ty = self.strategy.compute_ty();
+        let unary = Signature::unary(ty, ty);
+        match transform {
+            Transform::Identity => scores.remove(0),
+            Transform::Sigmoid => {
+                self.builder
+                    .compute("sigmoid", SigmoidOp { sig: unary }.into(), scores)
+            }
+            Transform::Exp => self
+                .builder
+                .compute("exp", ExpOp { sig: unary }.into(), scores),
+            Transform::Softmax => {
+                let num_class = scores.len();
+                let columns = scores
+                    .into_iter()
+                    .map(|score| {
+                        self.builder.compute(
+                            "expand_dims",
+                            ExpandDimsOp {
+                                sig: unary,
+                                axis: vec![1],
+                            }
+                            .into(),
+                            vec![score],
+                        )
+                    })
+                    .collect();
+                let scores = self.builder.compute(
+                    "concat",
+                    ConcatOp {
+                        sig: Signature::variadic(ty, ty),
+                        axis: 1,
+                    }
+                    .into(),
+                    columns,
+                );
+                self.builder.compute(
+                    "softmax",
+                    SoftmaxOp {
+                        sig: unary,
+                        axis: 1,
+                        upmost_index: num_class,
+                    }
+                    .into(),
+                    vec![scores],
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::HostPlacement;
+    use crate::replicated::ReplicatedPlacement;
+
+    /// Two trees of a binary classifier on three features.
+    fn model(objective: &str) -> Vec<u8> {
+        serde_json::json!({
+            "learner": {
+                "learner_model_param": {
+                    "base_score": "5E-1",
+                    "num_class": "0",
+                    "num_feature": "3"
+                },
+                "gradient_booster": {
+                    "name": "gbtree",
+                    "model": {
+                        "trees": [
+                            {
+                                "left_children": [1, -1, 3, -1, -1],
+                                "right_children": [2, -1, 4, -1, -1],
+                                "split_conditions": [0.5, -0.4, 2.0, 0.1, 0.3],
+                                "split_indices": [0, 0, 2, 0, 0],
+                                "split_type": [0, 0, 0, 0, 0]
+                            },
+                            {
+                                "left_children": [-1],
+                                "right_children": [-1],
+                                "split_conditions": [0.2],
+                                "split_indices": [0]
+                            }
+                        ],
+                        "tree_info": [0, 0]
+                    }
+                },
+                "objective": { "name": objective }
+            },
+            "version": [1, 7, 3]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_import_host() {
+        let alice = HostPlacement::from("alice");
+        let comp = import_xgboost(
+            &model("binary:logistic"),
+            &PlacementStrategy::Host(alice.clone()),
+        )
+        .unwrap();
+        let count = |kind: &str| {
+            comp.operations
+                .iter()
+                .filter(|op| op.kind.short_name() == kind)
+                .count()
+        };
+        assert_eq!(count("Input"), 1);
+        // only the features split on are extracted
+        assert_eq!(count("IndexAxis"), 2);
+        assert_eq!(count("Less"), 2);
+        assert_eq!(count("Mux"), 2);
+        assert_eq!(count("AddN"), 1);
+        assert_eq!(count("Sigmoid"), 1);
+        assert_eq!(count("Output"), 1);
+        assert!(comp
+            .operations
+            .iter()
+            .all(|op| op.placement == Placement::from(alice.clone())));
+    }
+
+    #[cfg(feature = "sync_execute")]
+    #[test]
+    fn test_evaluate_host() {
+        use crate::execution::{SyncSession, TestSyncExecutor};
+        use crate::prelude::*;
+        use crate::storage::local::LocalSyncStorage;
+        use maplit::hashmap;
+        use ndarray::array;
+        use std::convert::TryInto;
+        use std::rc::Rc;
+
+        let alice = HostPlacement::from("alice");
+        let comp = import_xgboost(
+            &model("reg:squarederror"),
+            &PlacementStrategy::Host(alice.clone()),
+        )
+        .unwrap();
+        let x: HostFloat64Tensor =
+            alice.from_raw(array![[0.0, 9.0, 9.0], [1.0, 9.0, 1.0], [1.0, 9.0, 3.0]]);
+        let session = SyncSession::from_storage(
+            SessionId::try_from("xgboost").unwrap(),
+            hashmap!(FEATURES_ARG.to_string() => Value::from(x)),
+            hashmap!(),
+            Rc::new(LocalSyncStorage::default()),
+        );
+        let outputs = TestSyncExecutor::default()
+            .run_computation(&comp, &session)
+            .unwrap();
+        let predictions: HostFloat64Tensor = outputs[PREDICTIONS_TAG].clone().try_into().unwrap();
+        // leaf values of both trees plus the base score
+        let expected = array![-0.4 + 0.2 + 0.5, 0.1 + 0.2 + 0.5, 0.3 + 0.2 + 0.5];
+        assert!(predictions
+            .0
+            .iter()
+            .zip(expected.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_import_replicated() {
+        let strategy = PlacementStrategy::replicated(
+            HostPlacement::from("alice"),
+            HostPlacement::from("bob"),
+            HostPlacement::from("carole"),
+            ReplicatedPlacement::from(["alice", "bob", "carole"]),
+        );
+        let comp = import_xgboost(&model("binary:logistic"), &strategy).unwrap();
+        // comparisons and selections are evaluated on secret shares
+        assert!(comp
+            .operations
+            .iter()
+            .filter(|op| matches!(op.kind, Operator::Less(_) | Operator::Mux(_)))
+            .all(|op| matches!(op.placement, Placement::Replicated(_))));
+        // split conditions and leaf values are held by the model owner
+        assert!(comp
+            .operations
+            .iter()
+            .filter(|op| matches!(op.kind, Operator::Constant(_)))
+            .all(|op| op.placement == Placement::from(HostPlacement::from("bob"))));
+        let output = comp.operations.last().unwrap();
+        assert_eq!(
+            output.placement,
+            Placement::from(HostPlacement::from("carole"))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_objective() {
+        let err = import_xgboost(
+            &model("rank:pairwise"),
+            &PlacementStrategy::Host(HostPlacement::from("alice")),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'rank:pairwise'"));
+    }
+}
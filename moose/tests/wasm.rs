@@ -0,0 +1,53 @@
+//! Runs computations on the plaintext path built for WebAssembly.
+//!
+//! Run using `make wasm-test`, which requires `wasm-pack` and Node.js.
+#![cfg(target_arch = "wasm32")]
+
+use moose::compilation::{compile, Pass};
+use moose::computation::{Computation, SessionId, Value};
+use moose::execution::{SyncSession, TestSyncExecutor};
+use moose::host::{HostPlacement, HostTensor};
+use moose::storage::local::LocalSyncStorage;
+use ndarray::array;
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::rc::Rc;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn tensor(x: ndarray::ArrayD<f64>) -> Value {
+    let plc = HostPlacement::from("alice");
+    Value::HostFloat64Tensor(Box::new(HostTensor::<f64>(x.into_shared(), plc)))
+}
+
+#[wasm_bindgen_test]
+fn test_run_computation() {
+    let source = r#"
+    x = Input{arg_name = "x"}: () -> Tensor<Float64> () @Host(alice)
+    w = Constant{value = HostFloat64Tensor([1.0, 2.0])}: () -> Tensor<Float64> () @Host(alice)
+    y = Mul: (Tensor<Float64>, Tensor<Float64>) -> Tensor<Float64> (x, w) @Host(alice)
+    z = Sum{axis = 0}: (Tensor<Float64>) -> Tensor<Float64> (y) @Host(alice)
+    key = PrfKeyGen: () -> HostPrfKey () @Host(alice)
+    out_z = Output{tag = "z"}: (Tensor<Float64>) -> Tensor<Float64> (z) @Host(alice)
+    out_key = Output{tag = "key"}: (HostPrfKey) -> HostPrfKey (key) @Host(alice)
+    "#;
+    let computation: Computation = source.try_into().unwrap();
+    let computation = compile::<Pass>(computation, None).unwrap();
+
+    let arguments: HashMap<String, Value> =
+        vec![("x".to_string(), tensor(array![3.0, 4.0].into_dyn()))]
+            .into_iter()
+            .collect();
+    let session = SyncSession::from_storage(
+        SessionId::try_from("wasm").unwrap(),
+        arguments,
+        HashMap::new(),
+        Rc::new(LocalSyncStorage::default()),
+    );
+    let outputs = TestSyncExecutor::default()
+        .run_computation(&computation, &session)
+        .unwrap();
+
+    assert_eq!(outputs["z"], tensor(ndarray::arr0(11.0).into_dyn()));
+    // keys are drawn from the randomness of the JavaScript host
+    assert!(matches!(outputs["key"], Value::HostPrfKey(_)));
+}
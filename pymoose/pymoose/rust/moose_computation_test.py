@@ -0,0 +1,60 @@
+import json
+
+import numpy as np
+from absl.testing import absltest
+from absl.testing import parameterized
+
+import pymoose as pm
+
+# regression tree splitting on the first and last of three features
+_XGBOOST_MODEL = json.dumps(
+    {
+        "learner": {
+            "learner_model_param": {
+                "base_score": "5E-1",
+                "num_class": "0",
+                "num_feature": "3",
+            },
+            "gradient_booster": {
+                "name": "gbtree",
+                "model": {
+                    "trees": [
+                        {
+                            "left_children": [1, -1, 3, -1, -1],
+                            "right_children": [2, -1, 4, -1, -1],
+                            "split_conditions": [0.5, -0.4, 2.0, 0.1, 0.3],
+                            "split_indices": [0, 0, 2, 0, 0],
+                        }
+                    ],
+                    "tree_info": [0],
+                },
+            },
+            "objective": {"name": "reg:squarederror"},
+        },
+        "version": [1, 7, 3],
+    }
+)
+
+
+class ImportedComputation(parameterized.TestCase):
+    @parameterized.parameters(
+        (None, None),
+        (["alice", "bob", "carole"], "bob"),
+    )
+    def test_xgboost(self, replicated, model_owner):
+        logical = pm.MooseComputation.from_xgboost(
+            _XGBOOST_MODEL, "alice", replicated, model_owner
+        )
+        compiled = logical.compile()
+        runtime = pm.LocalMooseRuntime(["alice", "bob", "carole"])
+        x = np.array([[0.0, 9.0, 9.0], [1.0, 9.0, 1.0], [1.0, 9.0, 3.0]])
+        outputs = runtime.evaluate_compiled(compiled, arguments={"x": x})
+        np.testing.assert_almost_equal(outputs["y"], [0.1, 0.6, 0.8], decimal=5)
+
+    def test_invalid_replicated_placement(self):
+        with self.assertRaises(TypeError):
+            pm.MooseComputation.from_xgboost(_XGBOOST_MODEL, "alice", ["alice", "bob"])
+
+
+if __name__ == "__main__":
+    absltest.main()
@@ -4,9 +4,11 @@ use moose::compilation::toposort;
 use moose::execution::grpc::GrpcMooseRuntime;
 use moose::execution::AsyncTestRuntime;
 use moose::host::HostTensor;
+use moose::onnx::{import_onnx, PlacementStrategy};
 use moose::prelude::*;
 use moose::textual::{parallel_parse_computation, ToTextual};
 use moose::tokio;
+use moose::xgboost::import_xgboost;
 use ndarray::LinalgScalar;
 use numpy::{Element, PyArrayDescr, PyArrayDyn, ToPyArray};
 use pyo3::exceptions::PyRuntimeError;
@@ -352,6 +354,45 @@ impl MooseComputation {
 
 const DEFAULT_PARSE_CHUNKS: usize = 12;
 
+/// Placement of an imported model: on the host `input_owner` alone, or on secret shares
+/// held by the three `replicated` players if given, with the weights held by
+/// `model_owner` and the outputs revealed to `output_receiver`, both defaulting to
+/// `input_owner`.
+fn placement_strategy(
+    input_owner: String,
+    replicated: Option<Vec<String>>,
+    model_owner: Option<String>,
+    output_receiver: Option<String>,
+) -> PyResult<PlacementStrategy> {
+    let input_owner = HostPlacement::from(input_owner.as_str());
+    let replicated = match replicated {
+        None => return Ok(PlacementStrategy::Host(input_owner)),
+        Some(players) => {
+            let players: [String; 3] = players.try_into().map_err(|players: Vec<String>| {
+                PyTypeError::new_err(format!(
+                    "replicated placement needs 3 players, got {}",
+                    players.len()
+                ))
+            })?;
+            ReplicatedPlacement::from(players)
+        }
+    };
+    let model_owner = model_owner
+        .as_deref()
+        .map(HostPlacement::from)
+        .unwrap_or_else(|| input_owner.clone());
+    let output_receiver = output_receiver
+        .as_deref()
+        .map(HostPlacement::from)
+        .unwrap_or_else(|| input_owner.clone());
+    Ok(PlacementStrategy::replicated(
+        input_owner,
+        model_owner,
+        output_receiver,
+        replicated,
+    ))
+}
+
 #[pymethods]
 impl MooseComputation {
     #[classmethod]
@@ -400,6 +441,48 @@ impl MooseComputation {
         let comp_text = self.computation.to_textual();
         Ok(comp_text.into_py(py))
     }
+
+    /// Logical computation evaluating a serialized ONNX model.
+    #[classmethod]
+    pub fn from_onnx(
+        _cls: &PyType,
+        py: Python,
+        model: &PyBytes,
+        input_owner: String,
+        replicated: Option<Vec<String>>,
+        model_owner: Option<String>,
+        output_receiver: Option<String>,
+    ) -> PyResult<Py<Self>> {
+        let strategy = placement_strategy(input_owner, replicated, model_owner, output_receiver)?;
+        let computation = import_onnx(model.as_bytes(), &strategy)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Py::new(py, MooseComputation { computation })
+    }
+
+    /// Logical computation evaluating an XGBoost model saved as JSON.
+    #[classmethod]
+    pub fn from_xgboost(
+        _cls: &PyType,
+        py: Python,
+        model: &PyString,
+        input_owner: String,
+        replicated: Option<Vec<String>>,
+        model_owner: Option<String>,
+        output_receiver: Option<String>,
+    ) -> PyResult<Py<Self>> {
+        let model: &str = model.extract()?;
+        let strategy = placement_strategy(input_owner, replicated, model_owner, output_receiver)?;
+        let computation = import_xgboost(model.as_bytes(), &strategy)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Py::new(py, MooseComputation { computation })
+    }
+
+    /// Computation resulting from applying compiler `passes`, or the default ones if `None`.
+    pub fn compile(&self, passes: Option<Vec<String>>) -> PyResult<MooseComputation> {
+        let computation = compile(self.computation.clone(), passes)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(MooseComputation { computation })
+    }
 }
 
 #[pymodule]
@@ -435,3 +518,93 @@ fn pymoose_bindings(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_wrapped(wrap_pymodule!(moose_runtime))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regression tree splitting on the first and last of three features.
+    const XGBOOST_MODEL: &str = r#"{
+        "learner": {
+            "learner_model_param": {"base_score": "5E-1", "num_class": "0", "num_feature": "3"},
+            "gradient_booster": {
+                "name": "gbtree",
+                "model": {
+                    "trees": [{
+                        "left_children": [1, -1, 3, -1, -1],
+                        "right_children": [2, -1, 4, -1, -1],
+                        "split_conditions": [0.5, -0.4, 2.0, 0.1, 0.3],
+                        "split_indices": [0, 0, 2, 0, 0]
+                    }],
+                    "tree_info": [0]
+                }
+            },
+            "objective": {"name": "reg:squarederror"}
+        },
+        "version": [1, 7, 3]
+    }"#;
+
+    fn players() -> Option<Vec<String>> {
+        Some(vec![
+            "alice".to_string(),
+            "bob".to_string(),
+            "carole".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_placement_strategy() {
+        let alice = HostPlacement::from("alice");
+        let bob = HostPlacement::from("bob");
+
+        let strategy = placement_strategy("alice".to_string(), None, None, None).unwrap();
+        assert!(matches!(strategy, PlacementStrategy::Host(host) if host == alice));
+
+        let strategy = placement_strategy(
+            "alice".to_string(),
+            players(),
+            Some("bob".to_string()),
+            None,
+        )
+        .unwrap();
+        match strategy {
+            PlacementStrategy::Replicated {
+                input_owner,
+                model_owner,
+                output_receiver,
+                replicated,
+                ..
+            } => {
+                assert_eq!(input_owner, alice);
+                assert_eq!(model_owner, bob);
+                assert_eq!(output_receiver, alice);
+                assert_eq!(
+                    replicated,
+                    ReplicatedPlacement::from(["alice", "bob", "carole"])
+                );
+            }
+            _ => panic!("expected a replicated strategy"),
+        }
+
+        let two_players = Some(vec!["alice".to_string(), "bob".to_string()]);
+        assert!(placement_strategy("alice".to_string(), two_players, None, None).is_err());
+    }
+
+    #[test]
+    fn test_import_and_compile() {
+        let strategy = placement_strategy(
+            "alice".to_string(),
+            players(),
+            Some("bob".to_string()),
+            None,
+        )
+        .unwrap();
+        let logical = import_xgboost(XGBOOST_MODEL.as_bytes(), &strategy).unwrap();
+        let compiled = compile::<moose::compilation::Pass>(logical.clone(), None).unwrap();
+        assert!(compiled.operations.len() > logical.operations.len());
+        assert!(compiled
+            .operations
+            .iter()
+            .all(|op| matches!(op.placement, Placement::Host(_))));
+    }
+}